@@ -24,9 +24,16 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/content")
             .wrap(AuthMiddleware::optional())
+            // Trending routes are registered before "/{id}" so they are not
+            // captured as content IDs
+            .route("/trending", web::get().to(get_trending))
+            .route("/trending/rising", web::get().to(get_trending_rising))
+            .route(
+                "/trending/genres/{genre}",
+                web::get().to(get_trending_by_genre),
+            )
             .route("/{id}", web::get().to(get_content))
-            .route("/{id}/availability", web::get().to(get_availability))
-            .route("/trending", web::get().to(get_trending)),
+            .route("/{id}/availability", web::get().to(get_availability)),
     )
     .service(
         web::scope("/movies")
@@ -164,8 +171,54 @@ async fn get_trending(
     proxy: web::Data<Arc<ServiceProxy>>,
     rate_limiter: web::Data<Arc<RateLimiter>>,
 ) -> impl Responder {
+    forward_trending(
+        &req,
+        "/api/v1/content/trending".to_string(),
+        &proxy,
+        &rate_limiter,
+    )
+    .await
+}
+
+async fn get_trending_rising(
+    req: HttpRequest,
+    proxy: web::Data<Arc<ServiceProxy>>,
+    rate_limiter: web::Data<Arc<RateLimiter>>,
+) -> impl Responder {
+    forward_trending(
+        &req,
+        "/api/v1/content/trending/rising".to_string(),
+        &proxy,
+        &rate_limiter,
+    )
+    .await
+}
+
+async fn get_trending_by_genre(
+    req: HttpRequest,
+    path: web::Path<String>,
+    proxy: web::Data<Arc<ServiceProxy>>,
+    rate_limiter: web::Data<Arc<RateLimiter>>,
+) -> impl Responder {
+    forward_trending(
+        &req,
+        format!("/api/v1/content/trending/genres/{}", path.into_inner()),
+        &proxy,
+        &rate_limiter,
+    )
+    .await
+}
+
+/// Forward a trending request to the discovery service, keeping its query
+/// parameters (region, platform, genre, limit)
+async fn forward_trending(
+    req: &HttpRequest,
+    path: String,
+    proxy: &ServiceProxy,
+    rate_limiter: &RateLimiter,
+) -> HttpResponse {
     // Check rate limit
-    let user_ctx = get_user_context(&req);
+    let user_ctx = get_user_context(req);
     let user_id = user_ctx
         .as_ref()
         .map(|u| u.user_id.as_str())
@@ -179,14 +232,11 @@ async fn get_trending(
         Ok(rate_info) => {
             let proxy_req = ProxyRequest {
                 service: "discovery".to_string(),
-                path: "/api/v1/content/trending".to_string(),
+                path,
                 method: req.method().clone(),
                 headers: convert_headers(req.headers()),
                 body: None,
-                query: req
-                    .query_string()
-                    .is_empty()
-                    .then(|| req.query_string().to_string()),
+                query: (!req.query_string().is_empty()).then(|| req.query_string().to_string()),
             };
 
             match proxy.forward(proxy_req).await {
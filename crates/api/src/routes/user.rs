@@ -27,7 +27,16 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
             .route("/watchlist", web::get().to(get_watchlist))
             .route("/watchlist", web::post().to(add_to_watchlist))
             .route("/watchlist/{id}", web::delete().to(remove_from_watchlist))
-            .route("/history", web::get().to(get_history)),
+            .route("/history", web::get().to(get_history))
+            .route("/suppressions", web::get().to(get_suppressions))
+            .route("/suppressions", web::post().to(add_suppression))
+            .route("/suppressions/{id}", web::delete().to(remove_suppression))
+            .route(
+                "/recommendations/{id}/explanation",
+                web::get().to(get_recommendation_explanation),
+            )
+            .route("/mood", web::get().to(get_mood))
+            .route("/mood", web::post().to(set_mood)),
     );
 }
 
@@ -311,3 +320,287 @@ async fn get_history(
         Err(err) => HttpResponse::from_error(err),
     }
 }
+
+async fn get_suppressions(
+    req: HttpRequest,
+    proxy: web::Data<Arc<ServiceProxy>>,
+    rate_limiter: web::Data<Arc<RateLimiter>>,
+) -> impl Responder {
+    let user_ctx = match require_user_context(&req) {
+        Ok(ctx) => ctx,
+        Err(err) => return HttpResponse::from_error(err),
+    };
+
+    match rate_limiter
+        .check_rate_limit(&user_ctx.user_id, &user_ctx.tier)
+        .await
+    {
+        Ok(rate_info) => {
+            let proxy_req = ProxyRequest {
+                service: "sona".to_string(),
+                path: format!("/api/v1/users/{}/suppressions", user_ctx.user_id),
+                method: req.method().clone(),
+                headers: convert_headers(req.headers()),
+                body: None,
+                query: (!req.query_string().is_empty()).then(|| req.query_string().to_string()),
+            };
+
+            match proxy.forward(proxy_req).await {
+                Ok(response) => {
+                    let mut http_response = HttpResponse::build(response.status);
+                    http_response.insert_header(("X-RateLimit-Limit", rate_info.limit.to_string()));
+                    http_response
+                        .insert_header(("X-RateLimit-Remaining", rate_info.remaining.to_string()));
+                    http_response.insert_header(("X-RateLimit-Reset", rate_info.reset.to_string()));
+
+                    for (key, value) in response.headers.iter() {
+                        http_response.insert_header((key.clone(), value.clone()));
+                    }
+
+                    http_response.body(response.body)
+                }
+                Err(err) => HttpResponse::from_error(err),
+            }
+        }
+        Err(err) => HttpResponse::from_error(err),
+    }
+}
+
+async fn add_suppression(
+    req: HttpRequest,
+    body: web::Bytes,
+    proxy: web::Data<Arc<ServiceProxy>>,
+    rate_limiter: web::Data<Arc<RateLimiter>>,
+) -> impl Responder {
+    let user_ctx = match require_user_context(&req) {
+        Ok(ctx) => ctx,
+        Err(err) => return HttpResponse::from_error(err),
+    };
+
+    match rate_limiter
+        .check_rate_limit(&user_ctx.user_id, &user_ctx.tier)
+        .await
+    {
+        Ok(rate_info) => {
+            let proxy_req = ProxyRequest {
+                service: "sona".to_string(),
+                path: format!("/api/v1/users/{}/suppressions", user_ctx.user_id),
+                method: req.method().clone(),
+                headers: convert_headers(req.headers()),
+                body: Some(body),
+                query: None,
+            };
+
+            match proxy.forward(proxy_req).await {
+                Ok(response) => {
+                    let mut http_response = HttpResponse::build(response.status);
+                    http_response.insert_header(("X-RateLimit-Limit", rate_info.limit.to_string()));
+                    http_response
+                        .insert_header(("X-RateLimit-Remaining", rate_info.remaining.to_string()));
+                    http_response.insert_header(("X-RateLimit-Reset", rate_info.reset.to_string()));
+
+                    for (key, value) in response.headers.iter() {
+                        http_response.insert_header((key.clone(), value.clone()));
+                    }
+
+                    http_response.body(response.body)
+                }
+                Err(err) => HttpResponse::from_error(err),
+            }
+        }
+        Err(err) => HttpResponse::from_error(err),
+    }
+}
+
+async fn remove_suppression(
+    req: HttpRequest,
+    path: web::Path<String>,
+    proxy: web::Data<Arc<ServiceProxy>>,
+    rate_limiter: web::Data<Arc<RateLimiter>>,
+) -> impl Responder {
+    let user_ctx = match require_user_context(&req) {
+        Ok(ctx) => ctx,
+        Err(err) => return HttpResponse::from_error(err),
+    };
+
+    let feedback_id = path.into_inner();
+
+    match rate_limiter
+        .check_rate_limit(&user_ctx.user_id, &user_ctx.tier)
+        .await
+    {
+        Ok(rate_info) => {
+            let proxy_req = ProxyRequest {
+                service: "sona".to_string(),
+                path: format!(
+                    "/api/v1/users/{}/suppressions/{}",
+                    user_ctx.user_id, feedback_id
+                ),
+                method: req.method().clone(),
+                headers: convert_headers(req.headers()),
+                body: None,
+                query: None,
+            };
+
+            match proxy.forward(proxy_req).await {
+                Ok(response) => {
+                    let mut http_response = HttpResponse::build(response.status);
+                    http_response.insert_header(("X-RateLimit-Limit", rate_info.limit.to_string()));
+                    http_response
+                        .insert_header(("X-RateLimit-Remaining", rate_info.remaining.to_string()));
+                    http_response.insert_header(("X-RateLimit-Reset", rate_info.reset.to_string()));
+
+                    for (key, value) in response.headers.iter() {
+                        http_response.insert_header((key.clone(), value.clone()));
+                    }
+
+                    http_response.body(response.body)
+                }
+                Err(err) => HttpResponse::from_error(err),
+            }
+        }
+        Err(err) => HttpResponse::from_error(err),
+    }
+}
+
+async fn get_recommendation_explanation(
+    req: HttpRequest,
+    path: web::Path<String>,
+    proxy: web::Data<Arc<ServiceProxy>>,
+    rate_limiter: web::Data<Arc<RateLimiter>>,
+) -> impl Responder {
+    let user_ctx = match require_user_context(&req) {
+        Ok(ctx) => ctx,
+        Err(err) => return HttpResponse::from_error(err),
+    };
+
+    let content_id = path.into_inner();
+
+    match rate_limiter
+        .check_rate_limit(&user_ctx.user_id, &user_ctx.tier)
+        .await
+    {
+        Ok(rate_info) => {
+            let proxy_req = ProxyRequest {
+                service: "sona".to_string(),
+                path: format!(
+                    "/api/v1/users/{}/recommendations/{}/explanation",
+                    user_ctx.user_id, content_id
+                ),
+                method: req.method().clone(),
+                headers: convert_headers(req.headers()),
+                body: None,
+                query: (!req.query_string().is_empty()).then(|| req.query_string().to_string()),
+            };
+
+            match proxy.forward(proxy_req).await {
+                Ok(response) => {
+                    let mut http_response = HttpResponse::build(response.status);
+                    http_response.insert_header(("X-RateLimit-Limit", rate_info.limit.to_string()));
+                    http_response
+                        .insert_header(("X-RateLimit-Remaining", rate_info.remaining.to_string()));
+                    http_response.insert_header(("X-RateLimit-Reset", rate_info.reset.to_string()));
+
+                    for (key, value) in response.headers.iter() {
+                        http_response.insert_header((key.clone(), value.clone()));
+                    }
+
+                    http_response.body(response.body)
+                }
+                Err(err) => HttpResponse::from_error(err),
+            }
+        }
+        Err(err) => HttpResponse::from_error(err),
+    }
+}
+
+async fn get_mood(
+    req: HttpRequest,
+    proxy: web::Data<Arc<ServiceProxy>>,
+    rate_limiter: web::Data<Arc<RateLimiter>>,
+) -> impl Responder {
+    let user_ctx = match require_user_context(&req) {
+        Ok(ctx) => ctx,
+        Err(err) => return HttpResponse::from_error(err),
+    };
+
+    match rate_limiter
+        .check_rate_limit(&user_ctx.user_id, &user_ctx.tier)
+        .await
+    {
+        Ok(rate_info) => {
+            let proxy_req = ProxyRequest {
+                service: "sona".to_string(),
+                path: format!("/api/v1/users/{}/mood", user_ctx.user_id),
+                method: req.method().clone(),
+                headers: convert_headers(req.headers()),
+                body: None,
+                query: None,
+            };
+
+            match proxy.forward(proxy_req).await {
+                Ok(response) => {
+                    let mut http_response = HttpResponse::build(response.status);
+                    http_response.insert_header(("X-RateLimit-Limit", rate_info.limit.to_string()));
+                    http_response
+                        .insert_header(("X-RateLimit-Remaining", rate_info.remaining.to_string()));
+                    http_response.insert_header(("X-RateLimit-Reset", rate_info.reset.to_string()));
+
+                    for (key, value) in response.headers.iter() {
+                        http_response.insert_header((key.clone(), value.clone()));
+                    }
+
+                    http_response.body(response.body)
+                }
+                Err(err) => HttpResponse::from_error(err),
+            }
+        }
+        Err(err) => HttpResponse::from_error(err),
+    }
+}
+
+async fn set_mood(
+    req: HttpRequest,
+    body: web::Bytes,
+    proxy: web::Data<Arc<ServiceProxy>>,
+    rate_limiter: web::Data<Arc<RateLimiter>>,
+) -> impl Responder {
+    let user_ctx = match require_user_context(&req) {
+        Ok(ctx) => ctx,
+        Err(err) => return HttpResponse::from_error(err),
+    };
+
+    match rate_limiter
+        .check_rate_limit(&user_ctx.user_id, &user_ctx.tier)
+        .await
+    {
+        Ok(rate_info) => {
+            let proxy_req = ProxyRequest {
+                service: "sona".to_string(),
+                path: format!("/api/v1/users/{}/mood", user_ctx.user_id),
+                method: req.method().clone(),
+                headers: convert_headers(req.headers()),
+                body: Some(body),
+                query: None,
+            };
+
+            match proxy.forward(proxy_req).await {
+                Ok(response) => {
+                    let mut http_response = HttpResponse::build(response.status);
+                    http_response.insert_header(("X-RateLimit-Limit", rate_info.limit.to_string()));
+                    http_response
+                        .insert_header(("X-RateLimit-Remaining", rate_info.remaining.to_string()));
+                    http_response.insert_header(("X-RateLimit-Reset", rate_info.reset.to_string()));
+
+                    for (key, value) in response.headers.iter() {
+                        http_response.insert_header((key.clone(), value.clone()));
+                    }
+
+                    http_response.body(response.body)
+                }
+                Err(err) => HttpResponse::from_error(err),
+            }
+        }
+        Err(err) => HttpResponse::from_error(err),
+    }
+}
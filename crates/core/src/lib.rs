@@ -23,6 +23,7 @@
 //! - `shutdown`: Graceful shutdown coordinator
 //! - `audit`: Audit logging system for tracking user actions and system events
 //! - `events`: User activity event streaming to Kafka
+//! - `trending`: Time-decayed trending and popularity counters
 
 pub mod audit;
 pub mod config;
@@ -39,6 +40,7 @@ pub mod resilience;
 pub mod retry;
 pub mod shutdown;
 pub mod telemetry;
+pub mod trending;
 pub mod types;
 pub mod validation;
 
@@ -81,6 +83,10 @@ pub use telemetry::{
     inject_trace_context, redis_op_span, shutdown_tracing, TelemetryError, TraceContext,
     TracingConfig, TracingMiddleware,
 };
+pub use trending::{
+    InMemoryTrendingStore, RedisTrendingStore, TrendingConfig, TrendingError, TrendingHit,
+    TrendingItem, TrendingResult, TrendingScope, TrendingService, TrendingSignal, TrendingStore,
+};
 pub use types::*;
 
 /// Result type alias for Media Gateway operations
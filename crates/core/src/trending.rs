@@ -0,0 +1,1011 @@
+//! Trending and popularity tracking
+//!
+//! Maintains exponentially decayed view, start and completion counters per
+//! content item, globally and per region and platform. Counters use forward
+//! decay: an increment at time `t` is stored scaled by
+//! `2^((t - epoch) / half_life)`, so a sorted set stays correctly ordered
+//! without rewriting every member as time passes. Reads scale stored values
+//! back down to the present, and a set is rebased onto a newer epoch before
+//! its stored values grow too large.
+//!
+//! Two horizons are kept per scope: a short half-life for "trending now" and
+//! a long baseline. The ratio of their per-hour rates is the item's velocity,
+//! used to rank rising content and flag spikes.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use redis::aio::ConnectionManager;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::events::{ActivityEventType, UserActivityEvent};
+
+/// Trending errors
+#[derive(Debug, thiserror::Error)]
+pub enum TrendingError {
+    #[error("Redis error: {0}")]
+    Redis(#[from] redis::RedisError),
+}
+
+pub type TrendingResult<T> = Result<T, TrendingError>;
+
+/// Engagement signal counted towards trending
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrendingSignal {
+    View,
+    Start,
+    Completion,
+}
+
+impl TrendingSignal {
+    pub const ALL: [TrendingSignal; 3] = [
+        TrendingSignal::View,
+        TrendingSignal::Start,
+        TrendingSignal::Completion,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TrendingSignal::View => "view",
+            TrendingSignal::Start => "start",
+            TrendingSignal::Completion => "completion",
+        }
+    }
+
+    /// Signal implied by an activity event type, if it counts towards trending
+    ///
+    /// Abandons are not counted separately: the playback start was already
+    /// counted when the session began.
+    pub fn from_event_type(event_type: &ActivityEventType) -> Option<Self> {
+        match event_type {
+            ActivityEventType::ContentView | ActivityEventType::SearchResultClick => {
+                Some(TrendingSignal::View)
+            }
+            ActivityEventType::PlaybackStart => Some(TrendingSignal::Start),
+            ActivityEventType::PlaybackComplete => Some(TrendingSignal::Completion),
+            _ => None,
+        }
+    }
+}
+
+/// Audience a trending list is computed for
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum TrendingScope {
+    Global,
+    /// ISO 3166-1 alpha-2 region code (uppercase)
+    Region(String),
+    /// Platform identifier (lowercase, e.g. "netflix")
+    Platform(String),
+}
+
+impl TrendingScope {
+    /// Region scope with a normalized region code
+    pub fn region(code: &str) -> Self {
+        TrendingScope::Region(code.trim().to_ascii_uppercase())
+    }
+
+    /// Platform scope with a normalized platform identifier
+    pub fn platform(id: &str) -> Self {
+        TrendingScope::Platform(id.trim().to_ascii_lowercase())
+    }
+
+    /// Scope selected by optional request parameters
+    ///
+    /// Region takes precedence over platform; blank values are ignored.
+    pub fn from_params(region: Option<&str>, platform: Option<&str>) -> Self {
+        match (
+            region.filter(|r| !r.trim().is_empty()),
+            platform.filter(|p| !p.trim().is_empty()),
+        ) {
+            (Some(region), _) => Self::region(region),
+            (None, Some(platform)) => Self::platform(platform),
+            (None, None) => TrendingScope::Global,
+        }
+    }
+
+    fn key_segment(&self) -> String {
+        match self {
+            TrendingScope::Global => "global".to_string(),
+            TrendingScope::Region(code) => format!("region:{}", code),
+            TrendingScope::Platform(id) => format!("platform:{}", id),
+        }
+    }
+}
+
+/// Trending counter settings
+#[derive(Debug, Clone)]
+pub struct TrendingConfig {
+    /// Prefix of every counter key (default: "trending")
+    pub key_prefix: String,
+    /// Half-life of the "trending now" counters (default: 6 hours)
+    pub short_half_life: Duration,
+    /// Half-life of the baseline counters velocity is measured against
+    /// (default: 7 days)
+    pub long_half_life: Duration,
+    /// Contribution of a view to the combined score (default: 1.0)
+    pub view_weight: f64,
+    /// Contribution of a playback start to the combined score (default: 2.0)
+    pub start_weight: f64,
+    /// Contribution of a completion to the combined score (default: 4.0)
+    pub completion_weight: f64,
+    /// Rebase a counter set once its epoch is this old (default: 1 day)
+    pub rebase_after: Duration,
+    /// Members kept per counter set, lowest values are evicted (default: 10,000)
+    pub max_members: usize,
+    /// Velocity at or above which an item is spiking (default: 3.0)
+    pub spike_ratio: f64,
+    /// Minimum short-horizon score per hour for rising and spiking items
+    /// (default: 2.0)
+    pub min_rising_rate: f64,
+    /// Baseline score per hour added before computing velocity, damping
+    /// items with little history (default: 1.0)
+    pub baseline_prior: f64,
+    /// Trending candidates inspected per requested rising item (default: 5)
+    pub rising_pool_factor: usize,
+}
+
+impl Default for TrendingConfig {
+    fn default() -> Self {
+        Self {
+            key_prefix: "trending".to_string(),
+            short_half_life: Duration::from_secs(6 * 3600),
+            long_half_life: Duration::from_secs(7 * 24 * 3600),
+            view_weight: 1.0,
+            start_weight: 2.0,
+            completion_weight: 4.0,
+            rebase_after: Duration::from_secs(24 * 3600),
+            max_members: 10_000,
+            spike_ratio: 3.0,
+            min_rising_rate: 2.0,
+            baseline_prior: 1.0,
+            rising_pool_factor: 5,
+        }
+    }
+}
+
+impl TrendingConfig {
+    /// Weight of a signal in the combined score
+    pub fn weight(&self, signal: TrendingSignal) -> f64 {
+        match signal {
+            TrendingSignal::View => self.view_weight,
+            TrendingSignal::Start => self.start_weight,
+            TrendingSignal::Completion => self.completion_weight,
+        }
+    }
+}
+
+/// Single engagement to count
+#[derive(Debug, Clone)]
+pub struct TrendingHit {
+    pub content_id: Uuid,
+    pub signal: TrendingSignal,
+    pub region: Option<String>,
+    pub platform: Option<String>,
+    pub at: DateTime<Utc>,
+}
+
+impl TrendingHit {
+    /// Hit implied by a user activity event, if it counts towards trending
+    ///
+    /// The platform is read from the event's `platform` metadata field.
+    pub fn from_activity_event(event: &UserActivityEvent) -> Option<Self> {
+        let signal = TrendingSignal::from_event_type(&event.event_type)?;
+        let content_id = Uuid::parse_str(event.content_id.as_deref()?).ok()?;
+
+        Some(Self {
+            content_id,
+            signal,
+            region: event.region.clone(),
+            platform: event
+                .metadata
+                .get("platform")
+                .and_then(|p| p.as_str())
+                .map(str::to_string),
+            at: event.timestamp,
+        })
+    }
+
+    /// Every scope the hit counts towards
+    pub fn scopes(&self) -> Vec<TrendingScope> {
+        let mut scopes = vec![TrendingScope::Global];
+        if let Some(region) = self.region.as_deref().filter(|r| !r.trim().is_empty()) {
+            scopes.push(TrendingScope::region(region));
+        }
+        if let Some(platform) = self.platform.as_deref().filter(|p| !p.trim().is_empty()) {
+            scopes.push(TrendingScope::platform(platform));
+        }
+        scopes
+    }
+}
+
+/// Trending item with its current counters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrendingItem {
+    pub content_id: Uuid,
+    /// Decayed weighted engagement on the short horizon
+    pub score: f64,
+    /// Decayed views on the short horizon
+    pub views: f64,
+    /// Decayed playback starts on the short horizon
+    pub starts: f64,
+    /// Decayed completions on the short horizon
+    pub completions: f64,
+    /// Short-horizon rate over the (damped) baseline rate
+    pub velocity: f64,
+    pub spiking: bool,
+}
+
+/// Increment of one member of a decayed counter set
+#[derive(Debug, Clone)]
+pub struct CounterIncrement {
+    pub key: String,
+    pub half_life: Duration,
+    pub member: Uuid,
+    pub amount: f64,
+}
+
+/// Storage for forward-decayed counter sets
+#[async_trait]
+pub trait TrendingStore: Send + Sync {
+    /// Apply increments observed at `at`
+    async fn increment(
+        &self,
+        increments: &[CounterIncrement],
+        at: DateTime<Utc>,
+    ) -> TrendingResult<()>;
+
+    /// Highest members of a set with their values decayed to `now`
+    async fn top(
+        &self,
+        key: &str,
+        half_life: Duration,
+        limit: usize,
+        now: DateTime<Utc>,
+    ) -> TrendingResult<Vec<(Uuid, f64)>>;
+
+    /// Values of specific members decayed to `now` (0 when absent)
+    async fn values(
+        &self,
+        key: &str,
+        half_life: Duration,
+        members: &[Uuid],
+        now: DateTime<Utc>,
+    ) -> TrendingResult<Vec<f64>>;
+}
+
+/// Seconds since the Unix epoch, with sub-second precision
+fn unix_seconds(at: DateTime<Utc>) -> f64 {
+    at.timestamp_millis() as f64 / 1000.0
+}
+
+/// Forward-decay scale `2^(elapsed / half_life)` of a point `elapsed`
+/// seconds after a set's epoch
+fn growth(elapsed_secs: f64, half_life: Duration) -> f64 {
+    (elapsed_secs / half_life.as_secs_f64()).exp2()
+}
+
+/// Score per hour implied by a decayed counter value
+///
+/// A steady rate of `r` per hour converges to a counter value of
+/// `r * half_life / ln 2`.
+pub fn rate_per_hour(value: f64, half_life: Duration) -> f64 {
+    value * std::f64::consts::LN_2 * 3600.0 / half_life.as_secs_f64()
+}
+
+/// Velocity of an item from its short and long horizon counter values
+pub fn velocity(short_value: f64, long_value: f64, config: &TrendingConfig) -> f64 {
+    rate_per_hour(short_value, config.short_half_life)
+        / (rate_per_hour(long_value, config.long_half_life) + config.baseline_prior)
+}
+
+/// Whether an item's short-horizon activity is a velocity spike
+pub fn is_spiking(short_value: f64, long_value: f64, config: &TrendingConfig) -> bool {
+    rate_per_hour(short_value, config.short_half_life) >= config.min_rising_rate
+        && velocity(short_value, long_value, config) >= config.spike_ratio
+}
+
+/// Rebases stale sets and adds increments, all in one round trip
+///
+/// KEYS are (set, epoch) pairs. ARGV holds the event time, rebase age and
+/// member cap, then (half-life, amount, member, ttl) per pair.
+const INCREMENT_SCRIPT: &str = r#"
+local at = tonumber(ARGV[1])
+local rebase_after = tonumber(ARGV[2])
+local max_members = tonumber(ARGV[3])
+for i = 1, #KEYS / 2 do
+  local set = KEYS[2 * i - 1]
+  local epoch_key = KEYS[2 * i]
+  local half_life = tonumber(ARGV[4 * i])
+  local amount = tonumber(ARGV[4 * i + 1])
+  local member = ARGV[4 * i + 2]
+  local ttl = tonumber(ARGV[4 * i + 3])
+  local stored = redis.call('GET', epoch_key)
+  local epoch = stored and tonumber(stored)
+  if not epoch then
+    epoch = at
+    redis.call('SET', epoch_key, tostring(at))
+  elseif at - epoch > rebase_after then
+    redis.call('ZUNIONSTORE', set, 1, set, 'WEIGHTS', tostring(2 ^ ((epoch - at) / half_life)))
+    epoch = at
+    redis.call('SET', epoch_key, tostring(at))
+  end
+  redis.call('ZINCRBY', set, tostring(amount * 2 ^ ((at - epoch) / half_life)), member)
+  if redis.call('ZCARD', set) > max_members then
+    redis.call('ZREMRANGEBYRANK', set, 0, -max_members - 1)
+  end
+  redis.call('EXPIRE', set, ttl)
+  redis.call('EXPIRE', epoch_key, ttl)
+end
+return #KEYS / 2
+"#;
+
+/// Counter sets are dropped after this many half-lives without writes
+const IDLE_HALF_LIVES: f64 = 10.0;
+
+/// Redis-backed counter sets (sorted set plus an epoch key per set)
+#[derive(Clone)]
+pub struct RedisTrendingStore {
+    manager: ConnectionManager,
+    script: Arc<redis::Script>,
+    rebase_after: Duration,
+    max_members: usize,
+}
+
+impl RedisTrendingStore {
+    pub fn new(manager: ConnectionManager, config: &TrendingConfig) -> Self {
+        Self {
+            manager,
+            script: Arc::new(redis::Script::new(INCREMENT_SCRIPT)),
+            rebase_after: config.rebase_after,
+            max_members: config.max_members,
+        }
+    }
+
+    /// Connect to Redis
+    pub async fn connect(redis_url: &str, config: &TrendingConfig) -> TrendingResult<Self> {
+        let client = redis::Client::open(redis_url)?;
+        let manager = ConnectionManager::new(client).await?;
+        Ok(Self::new(manager, config))
+    }
+
+    fn epoch_key(key: &str) -> String {
+        format!("{}:epoch", key)
+    }
+
+    /// Scale from stored values to values at `now`
+    async fn decay_scale(
+        &self,
+        conn: &mut ConnectionManager,
+        key: &str,
+        half_life: Duration,
+        now: DateTime<Utc>,
+    ) -> TrendingResult<Option<f64>> {
+        let epoch: Option<f64> = redis::cmd("GET")
+            .arg(Self::epoch_key(key))
+            .query_async(conn)
+            .await?;
+        Ok(epoch.map(|epoch| growth(epoch - unix_seconds(now), half_life)))
+    }
+}
+
+#[async_trait]
+impl TrendingStore for RedisTrendingStore {
+    async fn increment(
+        &self,
+        increments: &[CounterIncrement],
+        at: DateTime<Utc>,
+    ) -> TrendingResult<()> {
+        if increments.is_empty() {
+            return Ok(());
+        }
+
+        let mut invocation = self.script.prepare_invoke();
+        invocation
+            .arg(unix_seconds(at))
+            .arg(self.rebase_after.as_secs_f64())
+            .arg(self.max_members);
+        for increment in increments {
+            let ttl = (increment.half_life.as_secs_f64() * IDLE_HALF_LIVES).ceil() as u64;
+            invocation
+                .key(&increment.key)
+                .key(Self::epoch_key(&increment.key))
+                .arg(increment.half_life.as_secs_f64())
+                .arg(increment.amount)
+                .arg(increment.member.to_string())
+                .arg(ttl);
+        }
+
+        let mut conn = self.manager.clone();
+        let _: i64 = invocation.invoke_async(&mut conn).await?;
+        Ok(())
+    }
+
+    async fn top(
+        &self,
+        key: &str,
+        half_life: Duration,
+        limit: usize,
+        now: DateTime<Utc>,
+    ) -> TrendingResult<Vec<(Uuid, f64)>> {
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut conn = self.manager.clone();
+        let Some(scale) = self.decay_scale(&mut conn, key, half_life, now).await? else {
+            return Ok(Vec::new());
+        };
+
+        let members: Vec<(String, f64)> = redis::cmd("ZREVRANGE")
+            .arg(key)
+            .arg(0)
+            .arg(limit as isize - 1)
+            .arg("WITHSCORES")
+            .query_async(&mut conn)
+            .await?;
+
+        Ok(members
+            .into_iter()
+            .filter_map(|(member, stored)| {
+                Uuid::parse_str(&member).ok().map(|id| (id, stored * scale))
+            })
+            .collect())
+    }
+
+    async fn values(
+        &self,
+        key: &str,
+        half_life: Duration,
+        members: &[Uuid],
+        now: DateTime<Utc>,
+    ) -> TrendingResult<Vec<f64>> {
+        if members.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut conn = self.manager.clone();
+        let Some(scale) = self.decay_scale(&mut conn, key, half_life, now).await? else {
+            return Ok(vec![0.0; members.len()]);
+        };
+
+        let stored: Vec<Option<f64>> = redis::cmd("ZMSCORE")
+            .arg(key)
+            .arg(members.iter().map(Uuid::to_string).collect::<Vec<_>>())
+            .query_async(&mut conn)
+            .await?;
+
+        Ok(stored
+            .into_iter()
+            .map(|value| value.unwrap_or(0.0) * scale)
+            .collect())
+    }
+}
+
+#[derive(Debug, Default)]
+struct DecayedSet {
+    epoch: f64,
+    values: HashMap<Uuid, f64>,
+}
+
+/// In-process counter sets for single-instance deployments and tests
+pub struct InMemoryTrendingStore {
+    sets: Mutex<HashMap<String, DecayedSet>>,
+    rebase_after: Duration,
+    max_members: usize,
+}
+
+impl InMemoryTrendingStore {
+    pub fn new(config: &TrendingConfig) -> Self {
+        Self {
+            sets: Mutex::new(HashMap::new()),
+            rebase_after: config.rebase_after,
+            max_members: config.max_members,
+        }
+    }
+}
+
+#[async_trait]
+impl TrendingStore for InMemoryTrendingStore {
+    async fn increment(
+        &self,
+        increments: &[CounterIncrement],
+        at: DateTime<Utc>,
+    ) -> TrendingResult<()> {
+        let at = unix_seconds(at);
+        let mut sets = self.sets.lock().unwrap_or_else(|e| e.into_inner());
+
+        for increment in increments {
+            let set = sets
+                .entry(increment.key.clone())
+                .or_insert_with(|| DecayedSet {
+                    epoch: at,
+                    values: HashMap::new(),
+                });
+
+            if at - set.epoch > self.rebase_after.as_secs_f64() {
+                let scale = growth(set.epoch - at, increment.half_life);
+                set.values.values_mut().for_each(|v| *v *= scale);
+                set.epoch = at;
+            }
+
+            *set.values.entry(increment.member).or_insert(0.0) +=
+                increment.amount * growth(at - set.epoch, increment.half_life);
+
+            if set.values.len() > self.max_members {
+                let mut values: Vec<f64> = set.values.values().copied().collect();
+                values.sort_by(|a, b| b.total_cmp(a));
+                let cutoff = values[self.max_members - 1];
+                set.values.retain(|_, v| *v >= cutoff);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn top(
+        &self,
+        key: &str,
+        half_life: Duration,
+        limit: usize,
+        now: DateTime<Utc>,
+    ) -> TrendingResult<Vec<(Uuid, f64)>> {
+        let sets = self.sets.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(set) = sets.get(key) else {
+            return Ok(Vec::new());
+        };
+
+        let scale = growth(set.epoch - unix_seconds(now), half_life);
+        let mut top: Vec<(Uuid, f64)> = set
+            .values
+            .iter()
+            .map(|(id, value)| (*id, value * scale))
+            .collect();
+        top.sort_by(|a, b| b.1.total_cmp(&a.1));
+        top.truncate(limit);
+        Ok(top)
+    }
+
+    async fn values(
+        &self,
+        key: &str,
+        half_life: Duration,
+        members: &[Uuid],
+        now: DateTime<Utc>,
+    ) -> TrendingResult<Vec<f64>> {
+        let sets = self.sets.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(set) = sets.get(key) else {
+            return Ok(vec![0.0; members.len()]);
+        };
+
+        let scale = growth(set.epoch - unix_seconds(now), half_life);
+        Ok(members
+            .iter()
+            .map(|id| set.values.get(id).copied().unwrap_or(0.0) * scale)
+            .collect())
+    }
+}
+
+/// Counter horizon
+#[derive(Debug, Clone, Copy)]
+enum Horizon {
+    Short,
+    Long,
+}
+
+/// Trending and popularity service
+///
+/// Records engagement into decayed counters and serves trending-now and
+/// rising lists per scope.
+pub struct TrendingService {
+    store: Arc<dyn TrendingStore>,
+    config: TrendingConfig,
+}
+
+impl TrendingService {
+    pub fn new(store: Arc<dyn TrendingStore>, config: TrendingConfig) -> Self {
+        Self { store, config }
+    }
+
+    /// Service backed by Redis sorted sets
+    pub async fn from_redis_url(redis_url: &str, config: TrendingConfig) -> TrendingResult<Self> {
+        let store = RedisTrendingStore::connect(redis_url, &config).await?;
+        Ok(Self::new(Arc::new(store), config))
+    }
+
+    /// Service backed by in-process counters
+    pub fn in_memory(config: TrendingConfig) -> Self {
+        let store = InMemoryTrendingStore::new(&config);
+        Self::new(Arc::new(store), config)
+    }
+
+    pub fn config(&self) -> &TrendingConfig {
+        &self.config
+    }
+
+    fn half_life(&self, horizon: Horizon) -> Duration {
+        match horizon {
+            Horizon::Short => self.config.short_half_life,
+            Horizon::Long => self.config.long_half_life,
+        }
+    }
+
+    /// Key of a counter set, e.g. `trending:region:US:score:short`
+    fn key(&self, scope: &TrendingScope, counter: &str, horizon: Horizon) -> String {
+        let horizon = match horizon {
+            Horizon::Short => "short",
+            Horizon::Long => "long",
+        };
+        format!(
+            "{}:{}:{}:{}",
+            self.config.key_prefix,
+            scope.key_segment(),
+            counter,
+            horizon
+        )
+    }
+
+    fn increment(
+        &self,
+        scope: &TrendingScope,
+        counter: &str,
+        horizon: Horizon,
+        member: Uuid,
+        amount: f64,
+    ) -> CounterIncrement {
+        CounterIncrement {
+            key: self.key(scope, counter, horizon),
+            half_life: self.half_life(horizon),
+            member,
+            amount,
+        }
+    }
+
+    /// Count an engagement in every scope it belongs to
+    ///
+    /// Per scope this updates the signal's own short-horizon counter and the
+    /// weighted score on both horizons.
+    pub async fn record(&self, hit: &TrendingHit) -> TrendingResult<()> {
+        let weight = self.config.weight(hit.signal);
+        let increments: Vec<CounterIncrement> = hit
+            .scopes()
+            .iter()
+            .flat_map(|scope| {
+                [
+                    self.increment(
+                        scope,
+                        hit.signal.as_str(),
+                        Horizon::Short,
+                        hit.content_id,
+                        1.0,
+                    ),
+                    self.increment(scope, "score", Horizon::Short, hit.content_id, weight),
+                    self.increment(scope, "score", Horizon::Long, hit.content_id, weight),
+                ]
+            })
+            .collect();
+
+        self.store.increment(&increments, hit.at).await
+    }
+
+    /// Count a user activity event
+    ///
+    /// # Returns
+    /// `true` if the event counted towards trending
+    pub async fn record_event(&self, event: &UserActivityEvent) -> TrendingResult<bool> {
+        match TrendingHit::from_activity_event(event) {
+            Some(hit) => {
+                self.record(&hit).await?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Count activity events from a stream until it closes
+    ///
+    /// Failures are logged and skipped so a Redis outage does not stall the
+    /// producer side of the channel.
+    pub async fn consume(&self, mut events: mpsc::Receiver<UserActivityEvent>) {
+        let mut recorded = 0u64;
+        while let Some(event) = events.recv().await {
+            match self.record_event(&event).await {
+                Ok(true) => recorded += 1,
+                Ok(false) => {}
+                Err(e) => {
+                    warn!(error = %e, event_id = %event.event_id, "Failed to record trending event")
+                }
+            }
+        }
+        info!(recorded = recorded, "Trending event stream closed");
+    }
+
+    /// Highest scoring content on the short horizon, without details
+    ///
+    /// Callers that filter candidates themselves (e.g. by genre) should ask
+    /// for more than they need.
+    pub async fn top_ids(
+        &self,
+        scope: &TrendingScope,
+        limit: usize,
+        now: DateTime<Utc>,
+    ) -> TrendingResult<Vec<(Uuid, f64)>> {
+        let key = self.key(scope, "score", Horizon::Short);
+        self.store
+            .top(&key, self.half_life(Horizon::Short), limit, now)
+            .await
+    }
+
+    /// Counters and velocity for specific content, in the given order
+    pub async fn items(
+        &self,
+        scope: &TrendingScope,
+        content_ids: &[Uuid],
+        now: DateTime<Utc>,
+    ) -> TrendingResult<Vec<TrendingItem>> {
+        let short = self.half_life(Horizon::Short);
+        let long = self.half_life(Horizon::Long);
+
+        let scores = self
+            .store
+            .values(
+                &self.key(scope, "score", Horizon::Short),
+                short,
+                content_ids,
+                now,
+            )
+            .await?;
+        let baseline = self
+            .store
+            .values(
+                &self.key(scope, "score", Horizon::Long),
+                long,
+                content_ids,
+                now,
+            )
+            .await?;
+        let mut signals = Vec::with_capacity(TrendingSignal::ALL.len());
+        for signal in TrendingSignal::ALL {
+            let key = self.key(scope, signal.as_str(), Horizon::Short);
+            signals.push(self.store.values(&key, short, content_ids, now).await?);
+        }
+
+        Ok(content_ids
+            .iter()
+            .enumerate()
+            .map(|(i, content_id)| TrendingItem {
+                content_id: *content_id,
+                score: scores[i],
+                views: signals[0][i],
+                starts: signals[1][i],
+                completions: signals[2][i],
+                velocity: velocity(scores[i], baseline[i], &self.config),
+                spiking: is_spiking(scores[i], baseline[i], &self.config),
+            })
+            .collect())
+    }
+
+    /// Content with the most decayed engagement right now
+    pub async fn trending_now(
+        &self,
+        scope: &TrendingScope,
+        limit: usize,
+        now: DateTime<Utc>,
+    ) -> TrendingResult<Vec<TrendingItem>> {
+        let top = self.top_ids(scope, limit, now).await?;
+        let ids: Vec<Uuid> = top.iter().map(|(id, _)| *id).collect();
+        self.items(scope, &ids, now).await
+    }
+
+    /// Content whose engagement is accelerating fastest
+    ///
+    /// Candidates are drawn from the top of the trending-now list and ranked
+    /// by velocity; items below the minimum rising rate are dropped.
+    pub async fn rising(
+        &self,
+        scope: &TrendingScope,
+        limit: usize,
+        now: DateTime<Utc>,
+    ) -> TrendingResult<Vec<TrendingItem>> {
+        let pool = limit.saturating_mul(self.config.rising_pool_factor.max(1));
+        let candidates = self.trending_now(scope, pool, now).await?;
+
+        let mut rising: Vec<TrendingItem> = candidates
+            .into_iter()
+            .filter(|item| {
+                rate_per_hour(item.score, self.config.short_half_life)
+                    >= self.config.min_rising_rate
+            })
+            .collect();
+        rising.sort_by(|a, b| b.velocity.total_cmp(&a.velocity));
+        rising.truncate(limit);
+
+        debug!(
+            scope = %scope.key_segment(),
+            rising = rising.len(),
+            spiking = rising.iter().filter(|i| i.spiking).count(),
+            "Computed rising content"
+        );
+
+        Ok(rising)
+    }
+
+    /// Rising content whose velocity is a spike
+    pub async fn spikes(
+        &self,
+        scope: &TrendingScope,
+        limit: usize,
+        now: DateTime<Utc>,
+    ) -> TrendingResult<Vec<TrendingItem>> {
+        let mut spikes = self.rising(scope, limit, now).await?;
+        spikes.retain(|item| item.spiking);
+        Ok(spikes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration as ChronoDuration;
+
+    fn hit(content_id: Uuid, signal: TrendingSignal, at: DateTime<Utc>) -> TrendingHit {
+        TrendingHit {
+            content_id,
+            signal,
+            region: Some("us".to_string()),
+            platform: None,
+            at,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_counters_decay_by_half_life() {
+        let service = TrendingService::in_memory(TrendingConfig::default());
+        let content = Uuid::new_v4();
+        let start = Utc::now();
+
+        for _ in 0..4 {
+            service
+                .record(&hit(content, TrendingSignal::View, start))
+                .await
+                .unwrap();
+        }
+
+        let now = service
+            .trending_now(&TrendingScope::Global, 10, start)
+            .await
+            .unwrap();
+        assert!((now[0].views - 4.0).abs() < 1e-6);
+
+        let later = service
+            .trending_now(&TrendingScope::Global, 10, start + ChronoDuration::hours(6))
+            .await
+            .unwrap();
+        assert!((later[0].views - 2.0).abs() < 1e-6);
+        assert!((later[0].score - 2.0).abs() < 1e-6);
+
+        let regional = service
+            .trending_now(&TrendingScope::region("US"), 10, start)
+            .await
+            .unwrap();
+        assert_eq!(regional.len(), 1);
+        assert!(service
+            .trending_now(&TrendingScope::region("GB"), 10, start)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_rebase_preserves_values() {
+        let config = TrendingConfig {
+            rebase_after: std::time::Duration::from_secs(3600),
+            ..Default::default()
+        };
+        let service = TrendingService::in_memory(config);
+        let old = Uuid::new_v4();
+        let new = Uuid::new_v4();
+        let start = Utc::now();
+
+        service
+            .record(&hit(old, TrendingSignal::Completion, start))
+            .await
+            .unwrap();
+        // Far enough past the epoch to trigger a rebase
+        let later = start + ChronoDuration::hours(12);
+        service
+            .record(&hit(new, TrendingSignal::Completion, later))
+            .await
+            .unwrap();
+
+        let items = service
+            .items(&TrendingScope::Global, &[old, new], later)
+            .await
+            .unwrap();
+        assert!((items[0].completions - 0.25).abs() < 1e-6);
+        assert!((items[1].completions - 1.0).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    async fn test_rising_detects_spike() {
+        let service = TrendingService::in_memory(TrendingConfig::default());
+        let steady = Uuid::new_v4();
+        let spike = Uuid::new_v4();
+        let start = Utc::now();
+
+        // Steady: a start every hour for two weeks
+        for hour in 0..336 {
+            let at = start + ChronoDuration::hours(hour);
+            service
+                .record(&hit(steady, TrendingSignal::Start, at))
+                .await
+                .unwrap();
+        }
+        let now = start + ChronoDuration::hours(336);
+
+        // Spike: a burst of starts in the last hour
+        for minute in 0..30 {
+            let at = now - ChronoDuration::minutes(60 - minute * 2);
+            service
+                .record(&hit(spike, TrendingSignal::Start, at))
+                .await
+                .unwrap();
+        }
+
+        let rising = service
+            .rising(&TrendingScope::Global, 10, now)
+            .await
+            .unwrap();
+        assert_eq!(rising[0].content_id, spike);
+        assert!(rising[0].spiking);
+
+        let steady_item = &service
+            .items(&TrendingScope::Global, &[steady], now)
+            .await
+            .unwrap()[0];
+        assert!(!steady_item.spiking);
+        assert!(steady_item.velocity < 1.5);
+
+        let spikes = service
+            .spikes(&TrendingScope::Global, 10, now)
+            .await
+            .unwrap();
+        assert_eq!(spikes.len(), 1);
+    }
+
+    #[test]
+    fn test_hit_from_activity_event() {
+        let content = Uuid::new_v4();
+        let event = UserActivityEvent::new(
+            Uuid::new_v4(),
+            ActivityEventType::PlaybackComplete,
+            serde_json::json!({ "platform": "Netflix" }),
+        )
+        .with_content_id(content.to_string())
+        .with_region("gb");
+
+        let hit = TrendingHit::from_activity_event(&event).unwrap();
+        assert_eq!(hit.signal, TrendingSignal::Completion);
+        assert_eq!(
+            hit.scopes(),
+            vec![
+                TrendingScope::Global,
+                TrendingScope::Region("GB".to_string()),
+                TrendingScope::Platform("netflix".to_string()),
+            ]
+        );
+
+        let search = UserActivityEvent::new(
+            Uuid::new_v4(),
+            ActivityEventType::SearchQuery,
+            serde_json::json!({}),
+        );
+        assert!(TrendingHit::from_activity_event(&search).is_none());
+    }
+}
@@ -3,5 +3,6 @@ pub mod search_analytics;
 
 pub use query_log::{QueryLog, SearchClick, SearchEvent};
 pub use search_analytics::{
-    AnalyticsDashboard, LatencyStats, PeriodType, PopularQuery, SearchAnalytics, ZeroResultQuery,
+    AnalyticsDashboard, LatencyStats, PeriodType, PopularQuery, RecoveryStrategyStats,
+    SearchAnalytics, ZeroResultQuery,
 };
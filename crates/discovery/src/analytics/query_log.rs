@@ -109,6 +109,42 @@ impl QueryLog {
         Ok(result.0)
     }
 
+    /// Log a zero-result recovery attempt
+    ///
+    /// Records which recovery strategy was tried for a query and whether it
+    /// produced usable results, so recoveries can be attributed per strategy.
+    pub async fn log_recovery_attempt(
+        &self,
+        original_query: &str,
+        attempted_query: &str,
+        user_id: Option<&str>,
+        strategy: &str,
+        result_count: i32,
+        succeeded: bool,
+    ) -> Result<Uuid, sqlx::Error> {
+        let query_hash = Self::hash_query(original_query);
+        let user_id_hash = user_id.map(Self::anonymize_user_id);
+
+        let result: (Uuid,) = sqlx::query_as(
+            r#"
+            INSERT INTO search_recovery_events (query_hash, query_text, attempted_query, user_id_hash, strategy, result_count, succeeded)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id
+            "#
+        )
+        .bind(query_hash)
+        .bind(original_query)
+        .bind(attempted_query)
+        .bind(user_id_hash)
+        .bind(strategy)
+        .bind(result_count)
+        .bind(succeeded)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(result.0)
+    }
+
     /// Get recent search events
     pub async fn get_recent_events(&self, limit: i64) -> Result<Vec<SearchEvent>, sqlx::Error> {
         sqlx::query_as::<_, SearchEvent>(
@@ -147,6 +183,7 @@ impl QueryLog {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use sqlx::Executor;
 
     #[test]
     fn test_hash_query() {
@@ -300,20 +337,25 @@ mod tests {
             .expect("Failed to connect to test database");
 
         // Run migrations
-        sqlx::query(include_str!(
-            "../../migrations/20251206_search_analytics.sql"
-        ))
-        .execute(&pool)
-        .await
-        .expect("Failed to run migrations");
+        // Multi-statement scripts run over the simple query protocol
+        for migration in [
+            include_str!("../../migrations/20251206_search_analytics.sql"),
+            include_str!("../../../../migrations/021_search_recovery_events.up.sql"),
+        ] {
+            pool.execute(migration)
+                .await
+                .expect("Failed to run migrations");
+        }
 
         pool
     }
 
     async fn cleanup_test_db(pool: &PgPool) {
-        sqlx::query("TRUNCATE search_events, search_clicks, popular_searches CASCADE")
-            .execute(pool)
-            .await
-            .expect("Failed to cleanup test database");
+        sqlx::query(
+            "TRUNCATE search_events, search_clicks, popular_searches, search_recovery_events CASCADE",
+        )
+        .execute(pool)
+        .await
+        .expect("Failed to cleanup test database");
     }
 }
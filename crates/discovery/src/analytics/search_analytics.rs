@@ -49,6 +49,16 @@ pub struct ZeroResultQuery {
     pub count: i64,
 }
 
+/// Zero-result recovery effectiveness for a single strategy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecoveryStrategyStats {
+    pub strategy: String,
+    pub attempts: i64,
+    pub successes: i64,
+    pub success_rate: f64,
+    pub avg_results: f64,
+}
+
 /// Latency statistics with percentiles
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LatencyStats {
@@ -70,6 +80,7 @@ pub struct AnalyticsDashboard {
     pub avg_ctr: f64,
     pub top_queries: Vec<PopularQuery>,
     pub zero_result_queries: Vec<ZeroResultQuery>,
+    pub recovery_stats: Vec<RecoveryStrategyStats>,
 }
 
 /// Popular search record in database
@@ -218,6 +229,48 @@ impl SearchAnalytics {
             .collect())
     }
 
+    /// Get zero-result recovery effectiveness per strategy
+    pub async fn get_recovery_stats(
+        &self,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<RecoveryStrategyStats>, sqlx::Error> {
+        let results = sqlx::query(
+            r#"
+            SELECT
+                strategy,
+                COUNT(*) as attempts,
+                COUNT(*) FILTER (WHERE succeeded) as successes,
+                AVG(result_count)::FLOAT as avg_results
+            FROM search_recovery_events
+            WHERE created_at >= $1
+            GROUP BY strategy
+            ORDER BY attempts DESC
+            "#,
+        )
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(results
+            .into_iter()
+            .map(|r| {
+                let attempts = r.get::<Option<i64>, _>("attempts").unwrap_or(0);
+                let successes = r.get::<Option<i64>, _>("successes").unwrap_or(0);
+                RecoveryStrategyStats {
+                    strategy: r.get("strategy"),
+                    attempts,
+                    successes,
+                    success_rate: if attempts > 0 {
+                        successes as f64 / attempts as f64
+                    } else {
+                        0.0
+                    },
+                    avg_results: r.get::<Option<f64>, _>("avg_results").unwrap_or(0.0),
+                }
+            })
+            .collect())
+    }
+
     /// Calculate overall click-through rate
     pub async fn calculate_ctr(&self, since: DateTime<Utc>) -> Result<f64, sqlx::Error> {
         let result = sqlx::query(
@@ -338,6 +391,9 @@ impl SearchAnalytics {
         // Get zero-result queries
         let zero_result_queries = self.get_zero_result_queries(since, top_limit).await?;
 
+        // Get recovery effectiveness
+        let recovery_stats = self.get_recovery_stats(since).await?;
+
         Ok(AnalyticsDashboard {
             period: period.to_string(),
             total_searches,
@@ -348,6 +404,7 @@ impl SearchAnalytics {
             avg_ctr,
             top_queries,
             zero_result_queries,
+            recovery_stats,
         })
     }
 }
@@ -355,6 +412,7 @@ impl SearchAnalytics {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use sqlx::Executor;
     use std::collections::HashMap;
 
     #[test]
@@ -480,6 +538,47 @@ mod tests {
         cleanup_test_db(&pool).await;
     }
 
+    #[tokio::test]
+    #[ignore] // Integration test - requires database
+    async fn test_get_recovery_stats() {
+        let pool = setup_test_db().await;
+        let analytics = SearchAnalytics::new(pool.clone());
+
+        let attempts = [
+            ("relax_year", 0, false),
+            ("relax_genre", 12, true),
+            ("relax_year", 0, false),
+        ];
+        for (strategy, result_count, succeeded) in attempts {
+            analytics
+                .query_log()
+                .log_recovery_attempt(
+                    "horor movies 1850",
+                    "horror movies 1850",
+                    Some("user123"),
+                    strategy,
+                    result_count,
+                    succeeded,
+                )
+                .await
+                .expect("Failed to log recovery attempt");
+        }
+
+        let stats = analytics
+            .get_recovery_stats(Utc::now() - Duration::hours(1))
+            .await
+            .expect("Failed to get recovery stats");
+
+        assert_eq!(stats[0].strategy, "relax_year");
+        assert_eq!(stats[0].attempts, 2);
+        assert_eq!(stats[0].successes, 0);
+
+        let genre = stats.iter().find(|s| s.strategy == "relax_genre").unwrap();
+        assert_eq!(genre.success_rate, 1.0);
+
+        cleanup_test_db(&pool).await;
+    }
+
     #[tokio::test]
     #[ignore] // Integration test - requires database
     async fn test_calculate_ctr() {
@@ -622,18 +721,21 @@ mod tests {
             .await
             .expect("Failed to connect to test database");
 
-        sqlx::query(include_str!(
-            "../../migrations/20251206_search_analytics.sql"
-        ))
-        .execute(&pool)
-        .await
-        .expect("Failed to run migrations");
+        // Multi-statement scripts run over the simple query protocol
+        for migration in [
+            include_str!("../../migrations/20251206_search_analytics.sql"),
+            include_str!("../../../../migrations/021_search_recovery_events.up.sql"),
+        ] {
+            pool.execute(migration)
+                .await
+                .expect("Failed to run migrations");
+        }
 
         pool
     }
 
     async fn cleanup_test_db(pool: &PgPool) {
-        sqlx::query("TRUNCATE search_events, search_clicks, popular_searches CASCADE")
+        sqlx::query("TRUNCATE search_events, search_clicks, popular_searches, search_recovery_events CASCADE")
             .execute(pool)
             .await
             .expect("Failed to cleanup test database");
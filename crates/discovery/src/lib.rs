@@ -18,10 +18,16 @@ pub use embedding::{EmbeddingClient, EmbeddingModel, EmbeddingProvider, Embeddin
 pub use intent::{IntentParser, ParsedIntent};
 pub use search::{
     HybridSearchService, RankingConfig, RankingConfigStore, SearchRequest, SearchResponse,
+    TrendingContent, TrendingList,
 };
 
+use media_gateway_core::{TrendingConfig, TrendingService};
 use std::sync::Arc;
 
+/// Activity events queued for the trending counters before new ones are
+/// dropped
+const TRENDING_EVENT_BUFFER: usize = 10_000;
+
 /// Initialize discovery service components
 pub async fn init_service(
     config: Arc<DiscoveryConfig>,
@@ -82,15 +88,37 @@ pub async fn init_service(
         config.keyword.index_path.clone(),
     ));
 
+    // Initialize trending counters (optional, trending lists fall back to popularity)
+    let trending =
+        match TrendingService::from_redis_url(&config.cache.redis_url, TrendingConfig::default())
+            .await
+        {
+            Ok(trending) => Some(Arc::new(trending)),
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to initialize trending counters");
+                None
+            }
+        };
+
     // Initialize hybrid search service
-    let search_service = Arc::new(HybridSearchService::new(
+    let mut search_service = HybridSearchService::new(
         config.clone(),
         intent_parser,
         vector_search,
         keyword_search,
         db_pool,
         cache,
-    ));
+    );
+    if let Some(trending) = trending {
+        // Count discovery's own activity events towards trending
+        let (events_tx, events_rx) = tokio::sync::mpsc::channel(TRENDING_EVENT_BUFFER);
+        let consumer = trending.clone();
+        tokio::spawn(async move { consumer.consume(events_rx).await });
+        search_service = search_service
+            .with_trending(trending)
+            .with_trending_events(events_tx);
+    }
+    let search_service = Arc::new(search_service);
 
     Ok(search_service)
 }
@@ -45,6 +45,9 @@ async fn main() -> anyhow::Result<()> {
         .connect(&config.database.url)
         .await?;
 
+    // Quality and remediation handlers take the pool directly
+    let pool_data = web::Data::new(db_pool.clone());
+
     // Initialize catalog service
     let mut catalog_service = catalog::CatalogService::new(
         db_pool,
@@ -68,6 +71,9 @@ async fn main() -> anyhow::Result<()> {
         "default-jwt-secret-change-in-production".to_string()
     });
 
+    // Search service is also shared directly with search and trending handlers
+    let search_data = web::Data::new(search_service.clone());
+
     // Create application state
     let app_state = web::Data::new(server::AppState {
         config: config.clone(),
@@ -85,7 +91,9 @@ async fn main() -> anyhow::Result<()> {
     HttpServer::new(move || {
         App::new()
             .app_data(app_state.clone())
+            .app_data(search_data.clone())
             .app_data(catalog_state.clone())
+            .app_data(pool_data.clone())
             .route("/health", web::get().to(health_check))
             .route("/ready", web::get().to(readiness_check))
             .configure(server::configure_routes)
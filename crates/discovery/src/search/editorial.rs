@@ -0,0 +1,1089 @@
+//! Editorial pin, boost, bury and hide rules
+//!
+//! Lets the editorial team curate results for specific queries ("oscars" →
+//! a pinned list), promote titles for a time window, and bury or hide titles
+//! per region for legal reasons. Rules are stored in Postgres, cached
+//! in-process for the search path, and applied after fusion and
+//! personalization but before facets and pagination.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{Postgres, QueryBuilder};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tracing::{debug, info, instrument, warn};
+use uuid::Uuid;
+
+use media_gateway_core::audit::{AuditAction, AuditEvent, AuditLogger, PostgresAuditLogger};
+
+use super::{HybridSearchService, SearchFilters, SearchRequest, SearchResult};
+use crate::intent::ParsedIntent;
+
+/// How long the search path reuses the cached rule set before reloading
+const RULE_CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// What a rule does to its content
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EditorialAction {
+    /// Place content at the top in the listed order, fetching it if retrieval missed it
+    Pin,
+    /// Multiply relevance by `factor` and re-sort
+    Boost { factor: f32 },
+    /// Move content below all other results
+    Bury,
+    /// Remove content from results entirely
+    Hide,
+}
+
+impl EditorialAction {
+    /// Whether the action keeps content out of sight (hide or bury)
+    pub fn suppresses(&self) -> bool {
+        matches!(self, EditorialAction::Bury | EditorialAction::Hide)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EditorialAction::Pin => "pin",
+            EditorialAction::Boost { .. } => "boost",
+            EditorialAction::Bury => "bury",
+            EditorialAction::Hide => "hide",
+        }
+    }
+}
+
+/// How a rule's queries are compared with the normalized search query
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMatchType {
+    /// Every query matches (subject to intent terms)
+    #[default]
+    Any,
+    /// Normalized query equals one of the rule queries
+    Exact,
+    /// Normalized query contains one of the rule queries as whole words
+    Phrase,
+}
+
+/// When a rule fires
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RuleCondition {
+    #[serde(default)]
+    pub match_type: QueryMatchType,
+    /// Queries to match, normalized on save
+    #[serde(default)]
+    pub queries: Vec<String>,
+    /// Intent genres, themes, moods or references; when set, at least one
+    /// must appear in the parsed intent
+    #[serde(default)]
+    pub intent_terms: Vec<String>,
+}
+
+impl RuleCondition {
+    /// Whether a normalized query and its intent satisfy this condition
+    pub fn matches(&self, normalized_query: &str, intent: Option<&ParsedIntent>) -> bool {
+        if !self.matches_query(normalized_query) {
+            return false;
+        }
+        if self.intent_terms.is_empty() {
+            return true;
+        }
+
+        let Some(intent) = intent else {
+            return false;
+        };
+        let intent_terms: HashSet<String> = intent
+            .filters
+            .genre
+            .iter()
+            .chain(&intent.themes)
+            .chain(&intent.mood)
+            .chain(&intent.references)
+            .map(|t| normalize_query(t))
+            .collect();
+        self.intent_terms.iter().any(|t| intent_terms.contains(t))
+    }
+
+    /// Whether the query part of the condition is satisfied, ignoring intent terms
+    pub fn matches_query(&self, normalized_query: &str) -> bool {
+        match self.match_type {
+            QueryMatchType::Any => true,
+            QueryMatchType::Exact => self.queries.iter().any(|q| q == normalized_query),
+            QueryMatchType::Phrase => self
+                .queries
+                .iter()
+                .any(|q| contains_phrase(normalized_query, q)),
+        }
+    }
+
+    fn is_unconditional(&self) -> bool {
+        self.match_type == QueryMatchType::Any && self.intent_terms.is_empty()
+    }
+}
+
+/// An editorial rule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EditorialRule {
+    pub id: Uuid,
+    pub name: String,
+    pub action: EditorialAction,
+    /// Affected content, in pin order for pin rules
+    pub content_ids: Vec<Uuid>,
+    pub condition: RuleCondition,
+    /// ISO 3166-1 alpha-2 regions the rule applies in; empty means everywhere
+    pub regions: Vec<String>,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub enabled: bool,
+    /// Higher priority rules pin first
+    pub priority: i32,
+    /// Editorial or legal justification
+    pub reason: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl EditorialRule {
+    /// Whether the rule is live at `now` for `region`
+    ///
+    /// Region-scoped pins and boosts only apply when the request region is
+    /// known. Region-scoped hides and buries (e.g. rights takedowns) fail
+    /// closed and also apply when the region is unknown.
+    pub fn is_active(&self, now: DateTime<Utc>, region: Option<&str>) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        if self.starts_at.is_some_and(|start| now < start) {
+            return false;
+        }
+        if self.ends_at.is_some_and(|end| now >= end) {
+            return false;
+        }
+        if self.regions.is_empty() {
+            return true;
+        }
+        match region {
+            Some(r) => self
+                .regions
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(r)),
+            None => self.action.suppresses(),
+        }
+    }
+}
+
+/// Admin request to create or replace a rule
+#[derive(Debug, Clone, Deserialize)]
+pub struct EditorialRuleInput {
+    pub name: String,
+    pub action: EditorialAction,
+    pub content_ids: Vec<Uuid>,
+    #[serde(default)]
+    pub condition: RuleCondition,
+    #[serde(default)]
+    pub regions: Vec<String>,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub priority: i32,
+    pub reason: Option<String>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl EditorialRuleInput {
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            return Err(anyhow::anyhow!("Rule name is required"));
+        }
+        if self.content_ids.is_empty() {
+            return Err(anyhow::anyhow!("At least one content ID is required"));
+        }
+        if let (Some(start), Some(end)) = (self.starts_at, self.ends_at) {
+            if end <= start {
+                return Err(anyhow::anyhow!("ends_at must be after starts_at"));
+            }
+        }
+        if self.condition.match_type != QueryMatchType::Any
+            && self.condition.queries.iter().all(|q| q.trim().is_empty())
+        {
+            return Err(anyhow::anyhow!(
+                "Exact and phrase rules need at least one query"
+            ));
+        }
+        match &self.action {
+            EditorialAction::Boost { factor } if !(*factor > 0.0 && factor.is_finite()) => {
+                Err(anyhow::anyhow!("Boost factor must be a positive number"))
+            }
+            EditorialAction::Pin if self.condition.is_unconditional() => Err(anyhow::anyhow!(
+                "Pin rules must match specific queries or intent terms"
+            )),
+            EditorialAction::Hide if self.reason.as_deref().unwrap_or("").trim().is_empty() => {
+                Err(anyhow::anyhow!("Hide rules require a reason"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn into_rule(
+        self,
+        id: Uuid,
+        created_by: Option<Uuid>,
+        created_at: DateTime<Utc>,
+    ) -> EditorialRule {
+        let mut condition = self.condition;
+        condition.queries = normalize_all(&condition.queries);
+        condition.intent_terms = normalize_all(&condition.intent_terms);
+
+        EditorialRule {
+            id,
+            name: self.name.trim().to_string(),
+            action: self.action,
+            content_ids: self.content_ids,
+            condition,
+            regions: self
+                .regions
+                .iter()
+                .map(|r| r.trim().to_uppercase())
+                .filter(|r| !r.is_empty())
+                .collect(),
+            starts_at: self.starts_at,
+            ends_at: self.ends_at,
+            enabled: self.enabled,
+            priority: self.priority,
+            reason: self.reason,
+            created_by,
+            created_at,
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+/// Effects of all rules matching one request
+#[derive(Debug, Clone, Default)]
+pub struct EditorialPlan {
+    pins: Vec<Uuid>,
+    boosts: HashMap<Uuid, f32>,
+    buried: HashSet<Uuid>,
+    hidden: HashSet<Uuid>,
+    /// IDs of the rules that matched
+    pub applied_rules: Vec<Uuid>,
+}
+
+impl EditorialPlan {
+    /// Combine matching rules; `rules` must already be active for the request
+    pub fn from_rules<'a>(rules: impl IntoIterator<Item = &'a EditorialRule>) -> Self {
+        let mut rules: Vec<&EditorialRule> = rules.into_iter().collect();
+        rules.sort_by(|a, b| {
+            b.priority
+                .cmp(&a.priority)
+                .then(a.created_at.cmp(&b.created_at))
+        });
+
+        let mut plan = Self::default();
+        for rule in rules {
+            plan.applied_rules.push(rule.id);
+            match &rule.action {
+                EditorialAction::Pin => {
+                    for id in &rule.content_ids {
+                        if !plan.pins.contains(id) {
+                            plan.pins.push(*id);
+                        }
+                    }
+                }
+                EditorialAction::Boost { factor } => {
+                    for id in &rule.content_ids {
+                        *plan.boosts.entry(*id).or_insert(1.0) *= factor;
+                    }
+                }
+                EditorialAction::Bury => plan.buried.extend(rule.content_ids.iter().copied()),
+                EditorialAction::Hide => plan.hidden.extend(rule.content_ids.iter().copied()),
+            }
+        }
+        plan
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.applied_rules.is_empty()
+    }
+
+    /// Pinned content not present in `results` (and not hidden)
+    pub fn missing_pins(&self, results: &[SearchResult]) -> Vec<Uuid> {
+        let present: HashSet<Uuid> = results.iter().map(|r| r.content.id).collect();
+        self.pins
+            .iter()
+            .filter(|id| !present.contains(id) && !self.hidden.contains(id))
+            .copied()
+            .collect()
+    }
+
+    /// Remove hidden content only; used for early streaming stages
+    pub fn filter_hidden(&self, results: Vec<SearchResult>) -> Vec<SearchResult> {
+        if self.hidden.is_empty() {
+            return results;
+        }
+        results
+            .into_iter()
+            .filter(|r| !self.hidden.contains(&r.content.id))
+            .collect()
+    }
+
+    /// Apply hide, boost, bury and pin in that order
+    ///
+    /// `pinned` supplies results for pinned content that retrieval missed.
+    /// Hidden content is removed even if pinned.
+    pub fn apply(
+        &self,
+        results: Vec<SearchResult>,
+        mut pinned: HashMap<Uuid, SearchResult>,
+    ) -> Vec<SearchResult> {
+        if self.is_empty() {
+            return results;
+        }
+
+        let mut results = self.filter_hidden(results);
+
+        if !self.boosts.is_empty() {
+            for result in &mut results {
+                if let Some(factor) = self.boosts.get(&result.content.id) {
+                    result.relevance_score *= factor;
+                    result.match_reasons.push("editorial_boost".to_string());
+                }
+            }
+            results.sort_by(|a, b| {
+                b.relevance_score
+                    .partial_cmp(&a.relevance_score)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+        }
+
+        if !self.buried.is_empty() {
+            let (buried, kept): (Vec<_>, Vec<_>) = results
+                .into_iter()
+                .partition(|r| self.buried.contains(&r.content.id));
+            results = kept;
+            results.extend(buried.into_iter().map(|mut r| {
+                r.match_reasons.push("editorial_bury".to_string());
+                r
+            }));
+        }
+
+        if !self.pins.is_empty() {
+            let mut by_id: HashMap<Uuid, SearchResult> = HashMap::new();
+            results.retain(|r| {
+                if self.pins.contains(&r.content.id) {
+                    by_id.insert(r.content.id, r.clone());
+                    false
+                } else {
+                    true
+                }
+            });
+
+            let mut head: Vec<SearchResult> = self
+                .pins
+                .iter()
+                .filter(|id| !self.hidden.contains(id))
+                .filter_map(|id| by_id.remove(id).or_else(|| pinned.remove(id)))
+                .map(|mut r| {
+                    r.match_reasons.push("editorial_pin".to_string());
+                    r
+                })
+                .collect();
+            head.extend(results);
+            results = head;
+        }
+
+        results
+    }
+}
+
+/// Postgres-backed rule store with an in-process cache for the search path
+pub struct EditorialRuleStore {
+    db_pool: sqlx::PgPool,
+    audit_logger: OnceLock<Arc<PostgresAuditLogger>>,
+    cache: RwLock<Option<(Instant, Arc<Vec<EditorialRule>>)>>,
+}
+
+#[derive(sqlx::FromRow)]
+struct EditorialRuleRow {
+    id: Uuid,
+    name: String,
+    action: String,
+    boost_factor: Option<f32>,
+    content_ids: Vec<Uuid>,
+    match_type: String,
+    queries: Vec<String>,
+    intent_terms: Vec<String>,
+    regions: Vec<String>,
+    starts_at: Option<DateTime<Utc>>,
+    ends_at: Option<DateTime<Utc>>,
+    enabled: bool,
+    priority: i32,
+    reason: Option<String>,
+    created_by: Option<Uuid>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<EditorialRuleRow> for EditorialRule {
+    type Error = anyhow::Error;
+
+    fn try_from(row: EditorialRuleRow) -> Result<Self> {
+        let action = match row.action.as_str() {
+            "pin" => EditorialAction::Pin,
+            "boost" => EditorialAction::Boost {
+                factor: row.boost_factor.unwrap_or(1.0),
+            },
+            "bury" => EditorialAction::Bury,
+            "hide" => EditorialAction::Hide,
+            other => return Err(anyhow::anyhow!("Unknown editorial action: {}", other)),
+        };
+        let match_type = match row.match_type.as_str() {
+            "any" => QueryMatchType::Any,
+            "exact" => QueryMatchType::Exact,
+            "phrase" => QueryMatchType::Phrase,
+            other => return Err(anyhow::anyhow!("Unknown query match type: {}", other)),
+        };
+
+        Ok(Self {
+            id: row.id,
+            name: row.name,
+            action,
+            content_ids: row.content_ids,
+            condition: RuleCondition {
+                match_type,
+                queries: row.queries,
+                intent_terms: row.intent_terms,
+            },
+            regions: row.regions,
+            starts_at: row.starts_at,
+            ends_at: row.ends_at,
+            enabled: row.enabled,
+            priority: row.priority,
+            reason: row.reason,
+            created_by: row.created_by,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        })
+    }
+}
+
+impl EditorialRuleStore {
+    pub fn new(db_pool: sqlx::PgPool) -> Self {
+        Self {
+            db_pool,
+            audit_logger: OnceLock::new(),
+            cache: RwLock::new(None),
+        }
+    }
+
+    /// All rules, including disabled and expired ones
+    #[instrument(skip(self))]
+    pub async fn list(&self) -> Result<Vec<EditorialRule>> {
+        let rows = sqlx::query_as::<_, EditorialRuleRow>(
+            "SELECT * FROM editorial_rules ORDER BY priority DESC, created_at ASC",
+        )
+        .fetch_all(&self.db_pool)
+        .await
+        .context("Failed to list editorial rules")?;
+
+        rows.into_iter().map(EditorialRule::try_from).collect()
+    }
+
+    #[instrument(skip(self))]
+    pub async fn get(&self, id: Uuid) -> Result<Option<EditorialRule>> {
+        let row =
+            sqlx::query_as::<_, EditorialRuleRow>("SELECT * FROM editorial_rules WHERE id = $1")
+                .bind(id)
+                .fetch_optional(&self.db_pool)
+                .await
+                .context("Failed to get editorial rule")?;
+
+        row.map(EditorialRule::try_from).transpose()
+    }
+
+    /// Create a rule and audit the change
+    #[instrument(skip(self, input))]
+    pub async fn create(&self, input: EditorialRuleInput, admin_id: Uuid) -> Result<EditorialRule> {
+        input.validate()?;
+        let rule = input.into_rule(Uuid::new_v4(), Some(admin_id), Utc::now());
+        self.upsert(&rule).await?;
+
+        info!(rule_id = %rule.id, action = rule.action.as_str(), admin_id = %admin_id, "Created editorial rule");
+        self.audit(AuditAction::Create, &rule, admin_id).await;
+        Ok(rule)
+    }
+
+    /// Replace a rule and audit the change; `None` if it does not exist
+    #[instrument(skip(self, input))]
+    pub async fn update(
+        &self,
+        id: Uuid,
+        input: EditorialRuleInput,
+        admin_id: Uuid,
+    ) -> Result<Option<EditorialRule>> {
+        input.validate()?;
+        let Some(existing) = self.get(id).await? else {
+            return Ok(None);
+        };
+
+        let rule = input.into_rule(id, existing.created_by, existing.created_at);
+        self.upsert(&rule).await?;
+
+        info!(rule_id = %rule.id, admin_id = %admin_id, "Updated editorial rule");
+        self.audit(AuditAction::Update, &rule, admin_id).await;
+        Ok(Some(rule))
+    }
+
+    /// Delete a rule and audit the change
+    #[instrument(skip(self))]
+    pub async fn delete(&self, id: Uuid, admin_id: Uuid) -> Result<bool> {
+        let Some(existing) = self.get(id).await? else {
+            return Ok(false);
+        };
+
+        sqlx::query("DELETE FROM editorial_rules WHERE id = $1")
+            .bind(id)
+            .execute(&self.db_pool)
+            .await
+            .context("Failed to delete editorial rule")?;
+        self.invalidate().await;
+
+        info!(rule_id = %id, admin_id = %admin_id, "Deleted editorial rule");
+        self.audit(AuditAction::Delete, &existing, admin_id).await;
+        Ok(true)
+    }
+
+    /// Enabled rules for the search path, reloaded at most every [`RULE_CACHE_TTL`]
+    ///
+    /// On a database error the previous rule set is kept so a transient
+    /// failure never drops legal hides.
+    pub async fn cached_rules(&self) -> Arc<Vec<EditorialRule>> {
+        if let Some((loaded_at, rules)) = self.cache.read().await.as_ref() {
+            if loaded_at.elapsed() < RULE_CACHE_TTL {
+                return rules.clone();
+            }
+        }
+
+        let mut cache = self.cache.write().await;
+        if let Some((loaded_at, rules)) = cache.as_ref() {
+            if loaded_at.elapsed() < RULE_CACHE_TTL {
+                return rules.clone();
+            }
+        }
+
+        let loaded = sqlx::query_as::<_, EditorialRuleRow>(
+            "SELECT * FROM editorial_rules WHERE enabled = true AND (ends_at IS NULL OR ends_at > NOW())",
+        )
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(anyhow::Error::from)
+        .and_then(|rows| rows.into_iter().map(EditorialRule::try_from).collect());
+
+        match loaded {
+            Ok(rules) => {
+                debug!(count = rules.len(), "Loaded editorial rules");
+                let rules = Arc::new(rules);
+                *cache = Some((Instant::now(), rules.clone()));
+                rules
+            }
+            Err(e) => {
+                warn!(error = %e, "Failed to load editorial rules, keeping previous set");
+                let previous = cache
+                    .as_ref()
+                    .map(|(_, rules)| rules.clone())
+                    .unwrap_or_default();
+                *cache = Some((Instant::now(), previous.clone()));
+                previous
+            }
+        }
+    }
+
+    /// Rules that apply to a request right now
+    pub async fn matching_rules(
+        &self,
+        query: &str,
+        intent: Option<&ParsedIntent>,
+        region: Option<&str>,
+    ) -> Vec<EditorialRule> {
+        let normalized = normalize_query(query);
+        let now = Utc::now();
+        self.cached_rules()
+            .await
+            .iter()
+            .filter(|rule| {
+                rule.is_active(now, region) && rule.condition.matches(&normalized, intent)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Rules that may apply before the query intent is known
+    ///
+    /// Hide rules are matched on the query alone so that intent-scoped hides
+    /// are never leaked by early streaming stages; other rules still need
+    /// their intent terms to match.
+    pub async fn provisional_rules(&self, query: &str, region: Option<&str>) -> Vec<EditorialRule> {
+        let normalized = normalize_query(query);
+        let now = Utc::now();
+        self.cached_rules()
+            .await
+            .iter()
+            .filter(|rule| rule.is_active(now, region))
+            .filter(|rule| match rule.action {
+                EditorialAction::Hide => rule.condition.matches_query(&normalized),
+                _ => rule.condition.matches(&normalized, None),
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Version of the live rule set, mixed into search cache keys
+    ///
+    /// Changes whenever a rule is created, edited or deleted, or a
+    /// scheduling window opens or closes.
+    pub async fn fingerprint(&self) -> String {
+        let now = Utc::now();
+        let rules = self.cached_rules().await;
+        let mut live: Vec<(Uuid, i64)> = rules
+            .iter()
+            .filter(|rule| !rule.starts_at.is_some_and(|start| now < start))
+            .filter(|rule| !rule.ends_at.is_some_and(|end| now >= end))
+            .map(|rule| (rule.id, rule.updated_at.timestamp_micros()))
+            .collect();
+        if live.is_empty() {
+            return String::new();
+        }
+        live.sort();
+
+        let mut hasher = Sha256::new();
+        for (id, updated_at) in live {
+            hasher.update(id.as_bytes());
+            hasher.update(updated_at.to_le_bytes());
+        }
+        hex::encode(&hasher.finalize()[..8])
+    }
+
+    async fn upsert(&self, rule: &EditorialRule) -> Result<()> {
+        let boost_factor = match rule.action {
+            EditorialAction::Boost { factor } => Some(factor),
+            _ => None,
+        };
+        let match_type = match rule.condition.match_type {
+            QueryMatchType::Any => "any",
+            QueryMatchType::Exact => "exact",
+            QueryMatchType::Phrase => "phrase",
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO editorial_rules (
+                id, name, action, boost_factor, content_ids, match_type, queries,
+                intent_terms, regions, starts_at, ends_at, enabled, priority, reason,
+                created_by, created_at, updated_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)
+            ON CONFLICT (id) DO UPDATE SET
+                name = EXCLUDED.name,
+                action = EXCLUDED.action,
+                boost_factor = EXCLUDED.boost_factor,
+                content_ids = EXCLUDED.content_ids,
+                match_type = EXCLUDED.match_type,
+                queries = EXCLUDED.queries,
+                intent_terms = EXCLUDED.intent_terms,
+                regions = EXCLUDED.regions,
+                starts_at = EXCLUDED.starts_at,
+                ends_at = EXCLUDED.ends_at,
+                enabled = EXCLUDED.enabled,
+                priority = EXCLUDED.priority,
+                reason = EXCLUDED.reason,
+                updated_at = EXCLUDED.updated_at
+            "#,
+        )
+        .bind(rule.id)
+        .bind(&rule.name)
+        .bind(rule.action.as_str())
+        .bind(boost_factor)
+        .bind(&rule.content_ids)
+        .bind(match_type)
+        .bind(&rule.condition.queries)
+        .bind(&rule.condition.intent_terms)
+        .bind(&rule.regions)
+        .bind(rule.starts_at)
+        .bind(rule.ends_at)
+        .bind(rule.enabled)
+        .bind(rule.priority)
+        .bind(&rule.reason)
+        .bind(rule.created_by)
+        .bind(rule.created_at)
+        .bind(rule.updated_at)
+        .execute(&self.db_pool)
+        .await
+        .context("Failed to save editorial rule")?;
+
+        self.invalidate().await;
+        Ok(())
+    }
+
+    async fn invalidate(&self) {
+        *self.cache.write().await = None;
+    }
+
+    async fn audit(&self, action: AuditAction, rule: &EditorialRule, admin_id: Uuid) {
+        let logger = self
+            .audit_logger
+            .get_or_init(|| Arc::new(PostgresAuditLogger::new(self.db_pool.clone())));
+
+        let event = AuditEvent::new(action, "EditorialRule".to_string())
+            .with_user_id(admin_id)
+            .with_resource_id(rule.id.to_string())
+            .with_details(serde_json::to_value(rule).unwrap_or_default());
+
+        if let Err(e) = logger.log(event).await {
+            warn!(error = %e, "Failed to log audit event for editorial rule change");
+        }
+    }
+}
+
+impl HybridSearchService {
+    /// Build the editorial plan for a request
+    pub(super) async fn editorial_plan(
+        &self,
+        request: &SearchRequest,
+        intent: Option<&ParsedIntent>,
+    ) -> EditorialPlan {
+        let rules = self
+            .editorial_rules
+            .matching_rules(&request.query, intent, request.region.as_deref())
+            .await;
+        if !rules.is_empty() {
+            debug!(rule_count = rules.len(), "Applying editorial rules");
+        }
+        EditorialPlan::from_rules(&rules)
+    }
+
+    /// Build an editorial plan from the query alone, before intent parsing completes
+    pub(super) async fn provisional_editorial_plan(
+        &self,
+        request: &SearchRequest,
+    ) -> EditorialPlan {
+        let rules = self
+            .editorial_rules
+            .provisional_rules(&request.query, request.region.as_deref())
+            .await;
+        EditorialPlan::from_rules(&rules)
+    }
+
+    /// Apply editorial rules to a fused (and personalized) ranking
+    ///
+    /// Pinned content that retrieval missed is fetched, honoring the
+    /// request filters so pins never bypass parental controls.
+    pub(super) async fn apply_editorial(
+        &self,
+        request: &SearchRequest,
+        plan: &EditorialPlan,
+        results: Vec<SearchResult>,
+    ) -> Vec<SearchResult> {
+        if plan.is_empty() {
+            return results;
+        }
+
+        let missing = plan.missing_pins(&results);
+        let pinned = if missing.is_empty() {
+            HashMap::new()
+        } else {
+            match self.fetch_pinned(&missing, request.filters.as_ref()).await {
+                Ok(pinned) => pinned,
+                Err(e) => {
+                    warn!(error = %e, "Failed to fetch pinned content");
+                    HashMap::new()
+                }
+            }
+        };
+
+        plan.apply(results, pinned)
+    }
+
+    async fn fetch_pinned(
+        &self,
+        ids: &[Uuid],
+        filters: Option<&SearchFilters>,
+    ) -> Result<HashMap<Uuid, SearchResult>> {
+        let mut query = QueryBuilder::<Postgres>::new(
+            "SELECT id, title, overview, release_year, genres, \
+             ARRAY[]::text[] AS platforms, popularity_score FROM content WHERE id = ANY(",
+        );
+        query.push_bind(ids.to_vec()).push(")");
+        if let Some(filters) = filters {
+            filters.push_sql_conditions(&mut query);
+        }
+
+        let rows = query
+            .build_query_as::<super::ContentSummary>()
+            .fetch_all(&self.db_pool)
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|content| {
+                (
+                    content.id,
+                    SearchResult {
+                        relevance_score: content.popularity_score,
+                        content,
+                        match_reasons: Vec::new(),
+                        vector_similarity: None,
+                        graph_score: None,
+                        keyword_score: None,
+                    },
+                )
+            })
+            .collect())
+    }
+}
+
+/// Lowercase, strip punctuation and collapse whitespace
+pub fn normalize_query(query: &str) -> String {
+    query
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn normalize_all(values: &[String]) -> Vec<String> {
+    values
+        .iter()
+        .map(|v| normalize_query(v))
+        .filter(|v| !v.is_empty())
+        .collect()
+}
+
+/// Whole-word phrase containment on normalized strings
+fn contains_phrase(haystack: &str, phrase: &str) -> bool {
+    if phrase.is_empty() {
+        return false;
+    }
+    format!(" {} ", haystack).contains(&format!(" {} ", phrase))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intent::IntentFilters;
+    use crate::search::ContentSummary;
+
+    fn result(id: Uuid, score: f32) -> SearchResult {
+        SearchResult {
+            content: ContentSummary {
+                id,
+                title: format!("Title {}", id),
+                overview: String::new(),
+                release_year: 2020,
+                genres: vec![],
+                platforms: vec![],
+                popularity_score: 0.5,
+            },
+            relevance_score: score,
+            match_reasons: vec![],
+            vector_similarity: None,
+            graph_score: None,
+            keyword_score: None,
+        }
+    }
+
+    fn rule(action: EditorialAction, content_ids: Vec<Uuid>) -> EditorialRule {
+        EditorialRule {
+            id: Uuid::new_v4(),
+            name: "test".to_string(),
+            action,
+            content_ids,
+            condition: RuleCondition::default(),
+            regions: vec![],
+            starts_at: None,
+            ends_at: None,
+            enabled: true,
+            priority: 0,
+            reason: None,
+            created_by: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn ids(results: &[SearchResult]) -> Vec<Uuid> {
+        results.iter().map(|r| r.content.id).collect()
+    }
+
+    #[test]
+    fn test_normalize_query() {
+        assert_eq!(normalize_query("  The OSCARS, 2024! "), "the oscars 2024");
+    }
+
+    #[test]
+    fn test_condition_matching() {
+        let exact = RuleCondition {
+            match_type: QueryMatchType::Exact,
+            queries: vec!["oscars".to_string()],
+            intent_terms: vec![],
+        };
+        assert!(exact.matches("oscars", None));
+        assert!(!exact.matches("oscars 2024", None));
+
+        let phrase = RuleCondition {
+            match_type: QueryMatchType::Phrase,
+            queries: vec!["best picture".to_string()],
+            intent_terms: vec![],
+        };
+        assert!(phrase.matches("oscar best picture winners", None));
+        assert!(!phrase.matches("best pictures", None));
+    }
+
+    #[test]
+    fn test_condition_intent_terms() {
+        let condition = RuleCondition {
+            match_type: QueryMatchType::Any,
+            queries: vec![],
+            intent_terms: vec!["horror".to_string()],
+        };
+        let intent = ParsedIntent {
+            mood: vec![],
+            themes: vec![],
+            references: vec![],
+            filters: IntentFilters {
+                genre: vec!["Horror".to_string()],
+                ..Default::default()
+            },
+            fallback_query: String::new(),
+            confidence: 0.9,
+        };
+
+        assert!(condition.matches("scary movies", Some(&intent)));
+        assert!(!condition.matches("scary movies", None));
+    }
+
+    #[test]
+    fn test_rule_schedule_and_region() {
+        let now = Utc::now();
+        let mut r = rule(EditorialAction::Pin, vec![Uuid::new_v4()]);
+        r.regions = vec!["DE".to_string()];
+        assert!(r.is_active(now, Some("de")));
+        assert!(!r.is_active(now, Some("US")));
+        assert!(!r.is_active(now, None));
+
+        r.regions.clear();
+        r.starts_at = Some(now + chrono::Duration::hours(1));
+        assert!(!r.is_active(now, None));
+        r.starts_at = Some(now - chrono::Duration::hours(2));
+        r.ends_at = Some(now - chrono::Duration::hours(1));
+        assert!(!r.is_active(now, None));
+    }
+
+    #[test]
+    fn test_regional_hide_applies_without_region() {
+        let now = Utc::now();
+        let mut r = rule(EditorialAction::Hide, vec![Uuid::new_v4()]);
+        r.regions = vec!["DE".to_string()];
+        assert!(r.is_active(now, None));
+        assert!(r.is_active(now, Some("DE")));
+        assert!(!r.is_active(now, Some("US")));
+
+        r.action = EditorialAction::Boost { factor: 2.0 };
+        assert!(!r.is_active(now, None));
+    }
+
+    #[test]
+    fn test_plan_apply_order() {
+        let (a, b, c, d, e) = (
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+        );
+        let results = vec![
+            result(a, 0.9),
+            result(b, 0.8),
+            result(c, 0.7),
+            result(d, 0.6),
+        ];
+
+        let rules = vec![
+            rule(EditorialAction::Hide, vec![b]),
+            rule(EditorialAction::Bury, vec![a]),
+            rule(EditorialAction::Boost { factor: 2.0 }, vec![d]),
+            rule(EditorialAction::Pin, vec![e, c]),
+        ];
+        let plan = EditorialPlan::from_rules(&rules);
+
+        assert_eq!(plan.missing_pins(&results), vec![e]);
+        let pinned = HashMap::from([(e, result(e, 0.1))]);
+        let applied = plan.apply(results, pinned);
+
+        // Pins first in order, then boosted d, buried a last, hidden b gone
+        assert_eq!(ids(&applied), vec![e, c, d, a]);
+        assert!(applied[0]
+            .match_reasons
+            .contains(&"editorial_pin".to_string()));
+        assert!(applied[2]
+            .match_reasons
+            .contains(&"editorial_boost".to_string()));
+    }
+
+    #[test]
+    fn test_hide_wins_over_pin() {
+        let a = Uuid::new_v4();
+        let rules = vec![
+            rule(EditorialAction::Pin, vec![a]),
+            rule(EditorialAction::Hide, vec![a]),
+        ];
+        let plan = EditorialPlan::from_rules(&rules);
+
+        assert!(plan.missing_pins(&[]).is_empty());
+        assert!(plan.apply(vec![result(a, 1.0)], HashMap::new()).is_empty());
+    }
+
+    #[test]
+    fn test_input_validation() {
+        let input = EditorialRuleInput {
+            name: "Oscars".to_string(),
+            action: EditorialAction::Pin,
+            content_ids: vec![Uuid::new_v4()],
+            condition: RuleCondition::default(),
+            regions: vec![],
+            starts_at: None,
+            ends_at: None,
+            enabled: true,
+            priority: 0,
+            reason: None,
+        };
+        assert!(input.validate().is_err(), "unconditional pin rejected");
+
+        let mut hide = input.clone();
+        hide.action = EditorialAction::Hide;
+        assert!(hide.validate().is_err(), "hide requires a reason");
+        hide.reason = Some("Legal takedown".to_string());
+        assert!(hide.validate().is_ok());
+
+        let mut pin = input;
+        pin.condition = RuleCondition {
+            match_type: QueryMatchType::Exact,
+            queries: vec!["The Oscars!".to_string()],
+            intent_terms: vec![],
+        };
+        assert!(pin.validate().is_ok());
+        let saved = pin.into_rule(Uuid::new_v4(), None, Utc::now());
+        assert_eq!(saved.condition.queries, vec!["the oscars"]);
+    }
+}
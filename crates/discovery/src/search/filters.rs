@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use sqlx::{Postgres, QueryBuilder};
 
 /// Content rating for parental controls
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
@@ -56,6 +57,11 @@ pub struct SearchFilters {
 
     /// Blocked genres for parental controls
     pub blocked_genres: Vec<String>,
+
+    /// Maximum runtime in minutes
+    ///
+    /// Applied by vector search; the keyword index does not store runtimes.
+    pub max_runtime_minutes: Option<i32>,
 }
 
 impl SearchFilters {
@@ -67,6 +73,7 @@ impl SearchFilters {
             && self.rating_range.is_none()
             && self.content_rating_limit.is_none()
             && self.blocked_genres.is_empty()
+            && self.max_runtime_minutes.is_none()
     }
 
     /// Build SQL WHERE clause for filters
@@ -132,6 +139,11 @@ impl SearchFilters {
             ));
         }
 
+        // Runtime cap
+        if let Some(max_runtime) = self.max_runtime_minutes {
+            conditions.push(format!("runtime_minutes <= {}", max_runtime));
+        }
+
         let clause = if conditions.is_empty() {
             "1=1".to_string()
         } else {
@@ -141,6 +153,88 @@ impl SearchFilters {
         (clause, params)
     }
 
+    /// Append the filters to a `content` query as bound `AND` predicates
+    ///
+    /// Values are bound as parameters rather than spliced into the SQL. The
+    /// `content` table has no platform or rating columns, so platforms are
+    /// matched through `platform_ids` and the content rating limit through
+    /// the US `content_ratings` row; unrated content fails the limit.
+    pub fn push_sql_conditions(&self, builder: &mut QueryBuilder<'_, Postgres>) {
+        // Genre filter
+        if !self.genres.is_empty() {
+            builder
+                .push(" AND genres && ")
+                .push_bind(self.genres.clone())
+                .push("::text[]");
+        }
+
+        // Platform filter
+        if !self.platforms.is_empty() {
+            builder
+                .push(
+                    " AND EXISTS (SELECT 1 FROM platform_ids p \
+                     WHERE p.content_id = content.id AND p.platform = ANY(",
+                )
+                .push_bind(self.platforms.clone())
+                .push("::text[]))");
+        }
+
+        // Year range filter
+        if let Some((min_year, max_year)) = self.year_range {
+            builder
+                .push(" AND release_year BETWEEN ")
+                .push_bind(min_year)
+                .push(" AND ")
+                .push_bind(max_year);
+        }
+
+        // Rating range filter
+        if let Some((min_rating, max_rating)) = self.rating_range {
+            builder
+                .push(" AND average_rating BETWEEN ")
+                .push_bind(min_rating as f64)
+                .push(" AND ")
+                .push_bind(max_rating as f64);
+        }
+
+        // Content rating filter (parental controls)
+        if let Some(rating_limit) = self.content_rating_limit {
+            let allowed: Vec<String> = [
+                ContentRating::G,
+                ContentRating::PG,
+                ContentRating::PG13,
+                ContentRating::R,
+                ContentRating::NC17,
+            ]
+            .into_iter()
+            .filter(|rating| *rating <= rating_limit)
+            .map(|rating| rating.as_str().to_string())
+            .collect();
+            builder
+                .push(
+                    " AND EXISTS (SELECT 1 FROM content_ratings r \
+                     WHERE r.content_id = content.id AND r.region = 'US' AND r.rating = ANY(",
+                )
+                .push_bind(allowed)
+                .push("::text[]))");
+        }
+
+        // Blocked genres filter (parental controls)
+        if !self.blocked_genres.is_empty() {
+            builder
+                .push(" AND NOT (genres && ")
+                .push_bind(self.blocked_genres.clone())
+                .push("::text[])");
+        }
+
+        // Runtime cap
+        if let Some(max_runtime) = self.max_runtime_minutes {
+            builder
+                .push(" AND runtime_minutes <= ")
+                .push_bind(max_runtime);
+        }
+    }
+
     /// Estimate filter selectivity (0.0 = very selective, 1.0 = not selective)
     pub fn estimate_selectivity(&self) -> f32 {
         let mut selectivity = 1.0;
@@ -184,6 +278,11 @@ impl SearchFilters {
             selectivity *= 0.7; // Reduces content by ~30%
         }
 
+        // Runtime cap
+        if self.max_runtime_minutes.is_some() {
+            selectivity *= 0.5;
+        }
+
         selectivity
     }
 
@@ -231,4 +330,24 @@ mod tests {
         assert!(selectivity < 0.1); // Should be highly selective
         assert!(filters.should_pre_filter());
     }
+
+    #[test]
+    fn test_sql_conditions_are_bound() {
+        let filters = SearchFilters {
+            genres: vec!["action'); DROP TABLE content; --".to_string()],
+            platforms: vec!["netflix".to_string()],
+            content_rating_limit: Some(ContentRating::PG),
+            ..Default::default()
+        };
+
+        let mut builder = QueryBuilder::<Postgres>::new("SELECT id FROM content WHERE 1=1");
+        filters.push_sql_conditions(&mut builder);
+        let sql = builder.sql();
+
+        assert!(!sql.contains("DROP TABLE"));
+        assert!(!sql.contains("netflix"));
+        assert!(sql.contains("genres && $1::text[]"));
+        assert!(sql.contains("FROM platform_ids p"));
+        assert!(sql.contains("r.rating = ANY($3::text[])"));
+    }
 }
@@ -2,25 +2,37 @@ use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 use std::sync::Arc;
+use tokio::sync::mpsc;
 use tracing::{debug, info, instrument};
 use uuid::Uuid;
 
 pub mod autocomplete;
+pub mod editorial;
 pub mod facets;
 pub mod filters;
 pub mod keyword;
 pub mod personalization;
 pub mod query_processor;
 pub mod ranking;
+pub mod recovery;
+pub mod session;
+pub mod stream;
+pub mod trending;
 pub mod vector;
 
 pub use autocomplete::AutocompleteService;
+pub use editorial::{EditorialAction, EditorialRule, EditorialRuleInput, EditorialRuleStore};
 pub use facets::{FacetCount, FacetService};
 pub use filters::SearchFilters;
 pub use keyword::KeywordSearch;
 pub use personalization::PersonalizationService;
 pub use query_processor::QueryProcessor;
 pub use ranking::{RankingConfig, RankingConfigStore, UpdateRankingConfigRequest};
+pub use recovery::{RecoveryAttempt, RecoveryReport, RecoveryStrategy};
+pub use session::{
+    SearchSession, SearchSessionStore, SessionRefinement, SessionSearchResponse, SessionTurn,
+};
+pub use trending::{TrendingContent, TrendingList};
 pub use vector::VectorSearch;
 
 use crate::analytics::SearchAnalytics;
@@ -28,7 +40,8 @@ use crate::cache::RedisCache;
 use crate::config::DiscoveryConfig;
 use crate::intent::{IntentParser, ParsedIntent};
 use media_gateway_core::{
-    ActivityEventType, KafkaActivityProducer, UserActivityEvent, UserActivityProducer,
+    ActivityEventType, KafkaActivityProducer, TrendingService, UserActivityEvent,
+    UserActivityProducer,
 };
 
 /// Hybrid search service orchestrator
@@ -41,8 +54,13 @@ pub struct HybridSearchService {
     cache: Arc<RedisCache>,
     facet_service: Arc<FacetService>,
     personalization_service: Arc<PersonalizationService>,
+    session_store: Arc<SearchSessionStore>,
+    editorial_rules: Arc<EditorialRuleStore>,
+    query_processor: Arc<QueryProcessor>,
     analytics: Option<Arc<SearchAnalytics>>,
     activity_producer: Option<Arc<KafkaActivityProducer>>,
+    trending: Option<Arc<TrendingService>>,
+    trending_events: Option<mpsc::Sender<UserActivityEvent>>,
 }
 
 /// Search request
@@ -56,6 +74,9 @@ pub struct SearchRequest {
     /// A/B test experiment variant (e.g., "control", "low_boost", "high_boost")
     #[serde(skip_serializing_if = "Option::is_none")]
     pub experiment_variant: Option<String>,
+    /// ISO 3166-1 alpha-2 region of the requester, used by region-scoped editorial rules
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
 }
 
 /// Search response
@@ -69,6 +90,9 @@ pub struct SearchResponse {
     pub search_time_ms: u64,
     /// Facet counts by dimension (genres, platforms, years, ratings)
     pub facets: HashMap<String, Vec<FacetCount>>,
+    /// Zero-result recovery details, present when the recovery cascade ran
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recovery: Option<RecoveryReport>,
 }
 
 /// Individual search result
@@ -122,6 +146,9 @@ impl HybridSearchService {
             }
         };
 
+        let session_store = Arc::new(SearchSessionStore::new(cache.clone()));
+        let editorial_rules = Arc::new(EditorialRuleStore::new(db_pool.clone()));
+
         Self {
             config,
             intent_parser,
@@ -131,8 +158,13 @@ impl HybridSearchService {
             cache,
             facet_service: Arc::new(FacetService::new()),
             personalization_service,
+            session_store,
+            editorial_rules,
+            query_processor: Arc::new(QueryProcessor::new()),
             analytics,
             activity_producer,
+            trending: None,
+            trending_events: None,
         }
     }
 
@@ -163,6 +195,9 @@ impl HybridSearchService {
             }
         };
 
+        let session_store = Arc::new(SearchSessionStore::new(cache.clone()));
+        let editorial_rules = Arc::new(EditorialRuleStore::new(db_pool.clone()));
+
         Self {
             config,
             intent_parser,
@@ -172,8 +207,13 @@ impl HybridSearchService {
             cache,
             facet_service: Arc::new(FacetService::new()),
             personalization_service,
+            session_store,
+            editorial_rules,
+            query_processor: Arc::new(QueryProcessor::new()),
             analytics,
             activity_producer,
+            trending: None,
+            trending_events: None,
         }
     }
 
@@ -182,13 +222,160 @@ impl HybridSearchService {
         self.analytics.clone()
     }
 
+    /// Use decayed engagement counters for trending lists and recovery
+    pub fn with_trending(mut self, trending: Arc<TrendingService>) -> Self {
+        self.trending = Some(trending);
+        self
+    }
+
+    /// Forward this service's activity events to a trending event consumer
+    pub fn with_trending_events(mut self, events: mpsc::Sender<UserActivityEvent>) -> Self {
+        self.trending_events = Some(events);
+        self
+    }
+
+    /// Get trending service
+    pub fn trending_service(&self) -> Option<Arc<TrendingService>> {
+        self.trending.clone()
+    }
+
+    /// Get conversational search session store
+    pub fn session_store(&self) -> Arc<SearchSessionStore> {
+        self.session_store.clone()
+    }
+
+    /// Get editorial rule store
+    pub fn editorial_rules(&self) -> Arc<EditorialRuleStore> {
+        self.editorial_rules.clone()
+    }
+
+    /// Execute a search as a turn of a conversational session
+    ///
+    /// Follow-up utterances are interpreted against the session's previous
+    /// turns (filter additions/removals, "not that one", "more like the second
+    /// one") and the resulting turn is persisted back to Redis.
+    ///
+    /// # Arguments
+    /// * `session_id` - Existing session to continue, or `None` to start one
+    /// * `request` - Search request carrying the raw utterance as its query
+    ///
+    /// # Errors
+    /// Returns error if the session does not exist (or has expired), belongs
+    /// to another user, or the underlying search fails
+    #[instrument(skip(self, request), fields(query = %request.query))]
+    pub async fn search_in_session(
+        &self,
+        session_id: Option<Uuid>,
+        request: SearchRequest,
+    ) -> anyhow::Result<SessionSearchResponse> {
+        let mut session = match session_id {
+            Some(id) => {
+                let session = self
+                    .session_store
+                    .load(id)
+                    .await?
+                    .ok_or_else(|| anyhow::anyhow!("Search session not found: {}", id))?;
+                if session.user_id.is_some() && session.user_id != request.user_id {
+                    anyhow::bail!("Search session not found: {}", id);
+                }
+                session
+            }
+            None => SearchSession::new(request.user_id),
+        };
+
+        let utterance_intent = self.intent_parser.parse(&request.query).await?;
+        let plan = session.plan(&request.query, &utterance_intent, request.filters.as_ref());
+
+        let excluded: Vec<Uuid> = if plan.is_follow_up {
+            let mut excluded = session.excluded_ids.clone();
+            for refinement in &plan.refinements {
+                if let SessionRefinement::ExcludeResult { id, .. }
+                | SessionRefinement::MoreLike { id, .. } = refinement
+                {
+                    excluded.push(*id);
+                }
+            }
+            excluded
+        } else {
+            Vec::new()
+        };
+
+        // Fetch everything up to the end of the requested page, plus room for
+        // excluded items, and page after dropping them; paging the
+        // underlying search would shift offsets by the excluded count
+        let page = request.page.max(1);
+        let window_start = (page - 1).saturating_mul(request.page_size) as usize;
+        let search_request = SearchRequest {
+            query: plan.effective_query.clone(),
+            filters: if plan.filters.is_empty() {
+                None
+            } else {
+                Some(plan.filters.clone())
+            },
+            page: 1,
+            page_size: page
+                .saturating_mul(request.page_size)
+                .saturating_add(excluded.len() as u32),
+            user_id: request.user_id.or(session.user_id),
+            experiment_variant: request.experiment_variant,
+            region: request.region,
+        };
+
+        let mut response = self.search(search_request).await?;
+        let before = response.results.len();
+        response
+            .results
+            .retain(|r| !excluded.contains(&r.content.id));
+        response.total_count = response
+            .total_count
+            .saturating_sub(before - response.results.len());
+        response.results = response
+            .results
+            .into_iter()
+            .skip(window_start)
+            .take(request.page_size as usize)
+            .collect();
+        response.page = page;
+        response.page_size = request.page_size;
+
+        let turn_intent = if plan.is_follow_up {
+            response.query_parsed.clone()
+        } else {
+            utterance_intent
+        };
+        session.record_turn(&request.query, &plan, turn_intent, &response.results);
+
+        if let Err(e) = self.session_store.save(&session).await {
+            tracing::warn!(error = %e, session_id = %session.session_id, "Failed to persist search session");
+        }
+
+        info!(
+            session_id = %session.session_id,
+            turn = session.turns.len(),
+            is_follow_up = plan.is_follow_up,
+            refinements = plan.refinements.len(),
+            "Completed session search turn"
+        );
+
+        Ok(SessionSearchResponse {
+            session_id: session.session_id,
+            turn: session.turns.len(),
+            is_follow_up: plan.is_follow_up,
+            effective_query: plan.effective_query,
+            refinements: plan.refinements,
+            active_filters: session.active_filters.clone(),
+            response,
+        })
+    }
+
     /// Execute hybrid search with caching
     #[instrument(skip(self), fields(query = %request.query, page = %request.page))]
     pub async fn search(&self, request: SearchRequest) -> anyhow::Result<SearchResponse> {
         let start_time = std::time::Instant::now();
 
-        // Generate cache key from request
-        let cache_key = self.generate_cache_key(&request);
+        // Generate cache key from request and the live editorial rule set
+        let editorial_version = self.editorial_rules.fingerprint().await;
+        let cache_key = self.generate_cache_key(&request, &editorial_version);
 
         // Check cache first
         if let Ok(Some(cached_response)) = self.cache.get::<SearchResponse>(&cache_key).await {
@@ -204,10 +391,50 @@ impl HybridSearchService {
         debug!(cache_key = %cache_key, "Cache miss - executing full search");
 
         // Execute full search pipeline
-        let response = self.execute_search(&request).await?;
+        let mut response = self.execute_search(&request).await?;
+
+        // Run the recovery cascade for empty or low-confidence results
+        if recovery::needs_recovery(&response) {
+            response = self.recover(&request, response).await;
+        }
+
+        let latency_ms = start_time.elapsed().as_millis() as i32;
+        self.record_search(&request, &response, latency_ms);
+
+        // Cache results with 30-minute TTL
+        if let Err(e) = self.cache.set(&cache_key, &response, 1800).await {
+            // Log cache write error but don't fail the request
+            debug!(error = %e, cache_key = %cache_key, "Failed to cache search results");
+        } else {
+            debug!(cache_key = %cache_key, ttl = 1800, "Cached search results");
+        }
+
+        Ok(response)
+    }
+
+    /// Publish a user activity event and count it towards trending
+    /// (non-blocking)
+    pub fn record_activity(&self, event: UserActivityEvent) {
+        if let Some(events) = &self.trending_events {
+            if let Err(e) = events.try_send(event.clone()) {
+                debug!(error = %e, "Trending event queue unavailable, dropping event");
+            }
+        }
+
+        if let Some(producer) = &self.activity_producer {
+            let producer = producer.clone();
+            tokio::spawn(async move {
+                if let Err(e) = producer.publish_activity(event).await {
+                    tracing::warn!(error = %e, "Failed to publish user activity event");
+                }
+            });
+        }
+    }
 
+    /// Publish activity and analytics events for a completed search
+    fn record_search(&self, request: &SearchRequest, response: &SearchResponse, latency_ms: i32) {
         // Publish user activity event (non-blocking)
-        if let (Some(producer), Some(user_id)) = (&self.activity_producer, request.user_id) {
+        if let Some(user_id) = request.user_id {
             let clicked_items: Vec<String> = response
                 .results
                 .iter()
@@ -223,17 +450,10 @@ impl HybridSearchService {
             });
 
             let event = UserActivityEvent::new(user_id, ActivityEventType::SearchQuery, metadata);
-
-            let producer_clone = producer.clone();
-            tokio::spawn(async move {
-                if let Err(e) = producer_clone.publish_activity(event).await {
-                    tracing::warn!(error = %e, "Failed to publish search activity event");
-                }
-            });
+            self.record_activity(event);
         }
 
         // Log search event for analytics (non-blocking)
-        let latency_ms = start_time.elapsed().as_millis() as i32;
         if let Some(analytics) = &self.analytics {
             let user_id = request.user_id.as_ref().map(|id| id.to_string());
             let filters = request
@@ -253,35 +473,32 @@ impl HybridSearchService {
                     if let Some((min, max)) = f.rating_range {
                         map.insert("rating_range".to_string(), serde_json::json!([min, max]));
                     }
+                    if let Some(max_runtime) = f.max_runtime_minutes {
+                        map.insert(
+                            "max_runtime_minutes".to_string(),
+                            serde_json::json!(max_runtime),
+                        );
+                    }
                     map
                 })
                 .unwrap_or_default();
 
             let analytics_clone = analytics.clone();
             let query_clone = request.query.clone();
+            let total_count = response.total_count as i32;
             tokio::spawn(async move {
                 let _ = analytics_clone
                     .query_log()
                     .log_search(
                         &query_clone,
                         user_id.as_deref(),
-                        response.total_count as i32,
+                        total_count,
                         latency_ms,
                         filters,
                     )
                     .await;
             });
         }
-
-        // Cache results with 30-minute TTL
-        if let Err(e) = self.cache.set(&cache_key, &response, 1800).await {
-            // Log cache write error but don't fail the request
-            debug!(error = %e, cache_key = %cache_key, "Failed to cache search results");
-        } else {
-            debug!(cache_key = %cache_key, ttl = 1800, "Cached search results");
-        }
-
-        Ok(response)
     }
 
     /// Execute the full search pipeline (without caching)
@@ -362,10 +579,14 @@ impl HybridSearchService {
             merged_results
         };
 
-        // Phase 5: Compute facets from all results (before pagination)
+        // Phase 5: Apply editorial pin, boost, bury and hide rules
+        let plan = self.editorial_plan(request, Some(&intent)).await;
+        let ranked_results = self.apply_editorial(request, &plan, ranked_results).await;
+
+        // Phase 6: Compute facets from all results (before pagination)
         let facets = self.facet_service.compute_facets(&ranked_results);
 
-        // Phase 6: Paginate
+        // Phase 7: Paginate
         let total_count = ranked_results.len();
         let start = ((request.page - 1) * request.page_size) as usize;
         let end = std::cmp::min(start + request.page_size as usize, total_count);
@@ -388,6 +609,7 @@ impl HybridSearchService {
             query_parsed: intent,
             search_time_ms,
             facets,
+            recovery: None,
         })
     }
 
@@ -500,14 +722,16 @@ impl HybridSearchService {
     /// - Filters (genres, platforms, year range, rating range)
     /// - Pagination (page, page_size)
     /// - User ID for personalized results
+    /// - Editorial rule set version, so rule changes take effect immediately
     ///
     /// # Arguments
     /// * `request` - Search request to generate key for
+    /// * `editorial_version` - Fingerprint of the active editorial rules
     ///
     /// # Returns
     /// Cache key string in format: "search:{sha256_hash}"
     #[instrument(skip(self, request), fields(query = %request.query))]
-    fn generate_cache_key(&self, request: &SearchRequest) -> String {
+    fn generate_cache_key(&self, request: &SearchRequest, editorial_version: &str) -> String {
         // Serialize request to JSON for consistent hashing
         let json =
             serde_json::to_string(request).expect("SearchRequest serialization should never fail");
@@ -515,6 +739,7 @@ impl HybridSearchService {
         // Generate SHA256 hash
         let mut hasher = Sha256::new();
         hasher.update(json.as_bytes());
+        hasher.update(editorial_version.as_bytes());
         let hash = hasher.finalize();
         let hash_hex = hex::encode(hash);
 
@@ -625,11 +850,16 @@ mod tests {
             vector_search: Arc::new(vector::VectorSearch::new(String::new(), String::new(), 768)),
             keyword_search: Arc::new(keyword::KeywordSearch::new(String::new())),
             db_pool: db_pool.clone(),
-            cache,
             facet_service: Arc::new(FacetService::new()),
             personalization_service,
+            session_store: Arc::new(SearchSessionStore::new(cache.clone())),
+            editorial_rules: Arc::new(EditorialRuleStore::new(db_pool.clone())),
+            query_processor: Arc::new(QueryProcessor::new()),
+            cache,
             analytics: Some(Arc::new(SearchAnalytics::new(db_pool))),
             activity_producer: None,
+            trending: None,
+            trending_events: None,
         };
 
         let merged = service.reciprocal_rank_fusion(vector_results, keyword_results, 60.0);
@@ -653,6 +883,7 @@ mod tests {
             page_size: 20,
             user_id: Some(Uuid::nil()), // Use nil UUID for deterministic testing
             experiment_variant: None,
+            region: None,
         };
 
         let request2 = request1.clone();
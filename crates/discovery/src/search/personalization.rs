@@ -1,10 +1,13 @@
 //! User Personalization Service for Search Results
 //!
 //! Integrates with SONA Personalization Engine to apply user preference scoring
-//! to search results with caching and A/B testing support.
+//! to search results with caching and A/B testing support. Titles and genres
+//! the user marked "not interested" (or that SONA inferred from dismissals and
+//! abandons) are removed or down-weighted after the preference boost.
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Arc;
 use tracing::{debug, info, instrument, warn};
 use uuid::Uuid;
@@ -42,6 +45,28 @@ struct UserPreferenceCache {
     cached_at: i64,
 }
 
+/// Negative feedback suppressions from SONA
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct UserSuppressions {
+    /// Titles to remove
+    #[serde(default)]
+    blocked_content: Vec<Uuid>,
+    /// Score multiplier per title
+    #[serde(default)]
+    content_penalties: HashMap<Uuid, f32>,
+    /// Score multiplier per lowercased genre
+    #[serde(default)]
+    genre_penalties: HashMap<String, f32>,
+}
+
+impl UserSuppressions {
+    fn is_empty(&self) -> bool {
+        self.blocked_content.is_empty()
+            && self.content_penalties.is_empty()
+            && self.genre_penalties.is_empty()
+    }
+}
+
 /// Personalization service
 pub struct PersonalizationService {
     config: PersonalizationConfig,
@@ -90,37 +115,57 @@ impl PersonalizationService {
         // Get boost weight (may vary by A/B test variant)
         let boost_weight = self.get_boost_weight_for_variant(experiment_variant);
 
-        // Fetch personalization scores for all content
+        // Fetch personalization scores for all content, and the user's
+        // suppressions alongside
         let content_ids: Vec<Uuid> = results.iter().map(|r| r.content.id).collect();
-        let personalization_scores = match self.fetch_scores_batch(user_id, content_ids).await {
-            Ok(scores) => scores,
+        let (personalization_scores, suppressions) = futures::join!(
+            self.fetch_scores_batch(user_id, content_ids),
+            self.fetch_suppressions(user_id)
+        );
+        let suppressions = suppressions.unwrap_or_else(|e| {
+            warn!(
+                error = %e,
+                user_id = %user_id,
+                "Failed to fetch suppressions, not applying negative feedback"
+            );
+            UserSuppressions::default()
+        });
+
+        match personalization_scores {
+            Ok(personalization_scores) => {
+                // Apply personalization boost to relevance scores
+                for result in &mut results {
+                    if let Some(&score) = personalization_scores.get(&result.content.id) {
+                        let original_score = result.relevance_score;
+                        result.relevance_score =
+                            original_score * (1.0 - boost_weight) + score * boost_weight;
+
+                        debug!(
+                            content_id = %result.content.id,
+                            original_score = %original_score,
+                            personalization_score = %score,
+                            final_score = %result.relevance_score,
+                            "Applied personalization boost"
+                        );
+                    }
+                }
+            }
             Err(e) => {
                 warn!(
                     error = %e,
                     user_id = %user_id,
                     "Failed to fetch personalization scores, using original ranking"
                 );
-                return Ok(results);
-            }
-        };
-
-        // Apply personalization boost to relevance scores
-        for result in &mut results {
-            if let Some(&score) = personalization_scores.get(&result.content.id) {
-                let original_score = result.relevance_score;
-                result.relevance_score =
-                    original_score * (1.0 - boost_weight) + score * boost_weight;
-
-                debug!(
-                    content_id = %result.content.id,
-                    original_score = %original_score,
-                    personalization_score = %score,
-                    final_score = %result.relevance_score,
-                    "Applied personalization boost"
-                );
+                if suppressions.is_empty() {
+                    return Ok(results);
+                }
             }
         }
 
+        // Negative feedback applies after the boost so a high preference
+        // score cannot undo it
+        apply_suppressions(&mut results, &suppressions);
+
         // Rerank by new relevance scores
         results.sort_by(|a, b| {
             b.relevance_score
@@ -243,6 +288,44 @@ impl PersonalizationService {
         Ok(score_response.score)
     }
 
+    /// Fetch the user's negative feedback suppressions
+    async fn fetch_suppressions(&self, user_id: Uuid) -> Result<UserSuppressions> {
+        let cache_key = format!("personalization:{}:suppressions", user_id);
+        if let Ok(Some(cached)) = self.cache.get::<UserSuppressions>(&cache_key).await {
+            return Ok(cached);
+        }
+
+        let url = format!(
+            "{}/api/v1/users/{}/suppressions/summary",
+            self.config.sona_url, user_id
+        );
+        let response = self
+            .http_client
+            .get(&url)
+            .send()
+            .await
+            .context("Failed to send request to SONA")?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("SONA returned error status: {}", response.status());
+        }
+
+        let suppressions: UserSuppressions = response
+            .json()
+            .await
+            .context("Failed to parse SONA response")?;
+
+        if let Err(e) = self
+            .cache
+            .set(&cache_key, &suppressions, self.config.cache_ttl_sec)
+            .await
+        {
+            debug!(error = %e, "Failed to cache suppressions");
+        }
+
+        Ok(suppressions)
+    }
+
     /// Get boost weight based on A/B test variant
     pub(crate) fn get_boost_weight_for_variant(&self, variant: Option<&str>) -> f32 {
         match variant {
@@ -262,11 +345,39 @@ impl PersonalizationService {
             .delete(&cache_key)
             .await
             .context("Failed to invalidate cache")?;
+        self.cache
+            .delete(&format!("personalization:{}:suppressions", user_id))
+            .await
+            .context("Failed to invalidate cache")?;
         info!(user_id = %user_id, cache_key = %cache_key, "Invalidated personalization cache");
         Ok(())
     }
 }
 
+/// Remove blocked titles and scale the rest by their title and strongest
+/// genre penalty
+fn apply_suppressions(results: &mut Vec<SearchResult>, suppressions: &UserSuppressions) {
+    if suppressions.is_empty() {
+        return;
+    }
+
+    results.retain(|r| !suppressions.blocked_content.contains(&r.content.id));
+    for result in results.iter_mut() {
+        let content_penalty = suppressions
+            .content_penalties
+            .get(&result.content.id)
+            .copied()
+            .unwrap_or(1.0);
+        let genre_penalty = result
+            .content
+            .genres
+            .iter()
+            .filter_map(|g| suppressions.genre_penalties.get(&g.to_lowercase()))
+            .fold(1.0f32, |a, &b| a.min(b));
+        result.relevance_score *= content_penalty * genre_penalty;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -350,6 +461,27 @@ mod tests {
         assert_eq!(results[0].content.title, "Movie B");
     }
 
+    #[test]
+    fn test_apply_suppressions() {
+        let blocked = Uuid::new_v4();
+        let mut results = vec![
+            create_mock_result(blocked, "Movie A", 0.9),
+            create_mock_result(Uuid::new_v4(), "Movie B", 0.8),
+        ];
+        results[1].content.genres = vec!["Horror".to_string(), "Comedy".to_string()];
+
+        let suppressions = UserSuppressions {
+            blocked_content: vec![blocked],
+            genre_penalties: HashMap::from([("horror".to_string(), 0.4)]),
+            ..Default::default()
+        };
+        apply_suppressions(&mut results, &suppressions);
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].content.title, "Movie B");
+        assert!((results[0].relevance_score - 0.32).abs() < 1e-6);
+    }
+
     #[tokio::test]
     async fn test_personalization_disabled() {
         let mut config = PersonalizationConfig::default();
@@ -0,0 +1,547 @@
+//! Zero-result query recovery
+//!
+//! When a search returns nothing (or only a handful of low-confidence
+//! results) the recovery cascade retries it with progressively looser
+//! constraints: spelling correction, filter relaxation (year, then platform,
+//! then genre), a pure-semantic retry and finally trending content. Every
+//! attempt is logged so analytics can attribute which step recovered a query.
+
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+use tracing::{debug, info, instrument, warn};
+
+use super::{HybridSearchService, SearchFilters, SearchRequest, SearchResponse, SearchResult};
+use crate::intent::ParsedIntent;
+
+/// Searches with fewer results than this are candidates for recovery
+pub const LOW_RESULT_THRESHOLD: usize = 3;
+
+/// Intent confidence below which a short result list triggers recovery
+pub const LOW_CONFIDENCE_THRESHOLD: f32 = 0.3;
+
+/// Number of trending items considered for the final fallback
+const TRENDING_FALLBACK_LIMIT: usize = 50;
+
+/// Recovery strategy, in cascade order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecoveryStrategy {
+    SpellingCorrection,
+    RelaxYear,
+    RelaxPlatform,
+    RelaxGenre,
+    SemanticOnly,
+    Trending,
+}
+
+impl RecoveryStrategy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RecoveryStrategy::SpellingCorrection => "spelling_correction",
+            RecoveryStrategy::RelaxYear => "relax_year",
+            RecoveryStrategy::RelaxPlatform => "relax_platform",
+            RecoveryStrategy::RelaxGenre => "relax_genre",
+            RecoveryStrategy::SemanticOnly => "semantic_only",
+            RecoveryStrategy::Trending => "trending",
+        }
+    }
+}
+
+/// Single recovery attempt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecoveryAttempt {
+    pub strategy: RecoveryStrategy,
+    /// Query used for the attempt
+    pub query: String,
+    pub result_count: usize,
+    pub succeeded: bool,
+}
+
+/// Recovery summary returned alongside search results
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecoveryReport {
+    pub original_query: String,
+    pub original_result_count: usize,
+    /// Spell-corrected query, if a correction was applied
+    pub corrected_query: Option<String>,
+    /// Filters dropped to obtain the returned results (e.g. "year_range")
+    pub relaxed_filters: Vec<String>,
+    pub attempts: Vec<RecoveryAttempt>,
+    /// Strategy whose results are returned, if any improved on the original
+    pub recovered_by: Option<RecoveryStrategy>,
+}
+
+/// Whether a response should go through the recovery cascade
+pub fn needs_recovery(response: &SearchResponse) -> bool {
+    response.total_count == 0
+        || (response.total_count < LOW_RESULT_THRESHOLD
+            && response.query_parsed.confidence < LOW_CONFIDENCE_THRESHOLD)
+}
+
+/// Build the progressive filter relaxation steps
+///
+/// Each step drops one more user filter on top of the previous step
+/// (year, then platform, then genre). Steps that would not change the
+/// filters are skipped. Parental control filters are never relaxed.
+///
+/// # Returns
+/// Strategy, relaxed filters and the names of all filters dropped so far
+pub fn relaxation_steps(
+    filters: &SearchFilters,
+) -> Vec<(RecoveryStrategy, SearchFilters, Vec<String>)> {
+    let mut steps = Vec::new();
+    let mut current = filters.clone();
+    let mut dropped = Vec::new();
+
+    if current.year_range.is_some() {
+        current.year_range = None;
+        dropped.push("year_range".to_string());
+        steps.push((
+            RecoveryStrategy::RelaxYear,
+            current.clone(),
+            dropped.clone(),
+        ));
+    }
+
+    if !current.platforms.is_empty() {
+        current.platforms.clear();
+        dropped.push("platforms".to_string());
+        steps.push((
+            RecoveryStrategy::RelaxPlatform,
+            current.clone(),
+            dropped.clone(),
+        ));
+    }
+
+    if !current.genres.is_empty() {
+        current.genres.clear();
+        dropped.push("genres".to_string());
+        steps.push((
+            RecoveryStrategy::RelaxGenre,
+            current.clone(),
+            dropped.clone(),
+        ));
+    }
+
+    steps
+}
+
+/// Filters that must survive every recovery step (parental controls)
+pub fn protected_filters(filters: Option<&SearchFilters>) -> Option<SearchFilters> {
+    let filters = filters?;
+    let protected = SearchFilters {
+        content_rating_limit: filters.content_rating_limit,
+        blocked_genres: filters.blocked_genres.clone(),
+        ..Default::default()
+    };
+
+    if protected.is_empty() {
+        None
+    } else {
+        Some(protected)
+    }
+}
+
+/// Names of the user (non-parental) filters set on a request
+fn user_filter_names(filters: Option<&SearchFilters>) -> Vec<String> {
+    let Some(filters) = filters else {
+        return Vec::new();
+    };
+
+    let mut names = Vec::new();
+    if filters.year_range.is_some() {
+        names.push("year_range".to_string());
+    }
+    if !filters.platforms.is_empty() {
+        names.push("platforms".to_string());
+    }
+    if !filters.genres.is_empty() {
+        names.push("genres".to_string());
+    }
+    if filters.rating_range.is_some() {
+        names.push("rating_range".to_string());
+    }
+    if filters.max_runtime_minutes.is_some() {
+        names.push("max_runtime_minutes".to_string());
+    }
+    names
+}
+
+fn non_empty(filters: SearchFilters) -> Option<SearchFilters> {
+    if filters.is_empty() {
+        None
+    } else {
+        Some(filters)
+    }
+}
+
+impl HybridSearchService {
+    /// Run the recovery cascade for an empty or low-confidence response
+    ///
+    /// Never fails: strategies that error are logged and skipped, and the
+    /// original response is returned if nothing improves on it.
+    #[instrument(skip(self, request, original), fields(query = %request.query))]
+    pub(super) async fn recover(
+        &self,
+        request: &SearchRequest,
+        original: SearchResponse,
+    ) -> SearchResponse {
+        let start_time = Instant::now();
+        let mut report = RecoveryReport {
+            original_query: request.query.clone(),
+            original_result_count: original.total_count,
+            corrected_query: None,
+            relaxed_filters: Vec::new(),
+            attempts: Vec::new(),
+            recovered_by: None,
+        };
+        let mut best = original;
+        let mut query = request.query.clone();
+
+        // Step 1: spelling correction
+        let processed = self.query_processor.process(&request.query);
+        if processed.was_corrected {
+            query = processed.corrected.clone();
+            report.corrected_query = Some(processed.corrected);
+
+            let attempt = SearchRequest {
+                query: query.clone(),
+                ..request.clone()
+            };
+            let result = self.execute_search(&attempt).await;
+            if self.record_attempt(
+                &mut report,
+                &mut best,
+                RecoveryStrategy::SpellingCorrection,
+                &query,
+                request,
+                result,
+            ) {
+                return self.finish_recovery(report, best, start_time);
+            }
+        }
+
+        // Step 2: progressive filter relaxation
+        if let Some(filters) = &request.filters {
+            for (strategy, relaxed, dropped) in relaxation_steps(filters) {
+                let attempt = SearchRequest {
+                    query: query.clone(),
+                    filters: non_empty(relaxed),
+                    ..request.clone()
+                };
+                let result = self.execute_search(&attempt).await;
+                let improved_before = report.recovered_by;
+                let succeeded =
+                    self.record_attempt(&mut report, &mut best, strategy, &query, request, result);
+                if report.recovered_by != improved_before {
+                    report.relaxed_filters = dropped;
+                }
+                if succeeded {
+                    return self.finish_recovery(report, best, start_time);
+                }
+            }
+        }
+
+        let protected = protected_filters(request.filters.as_ref());
+
+        // The remaining steps bypass the main pipeline, so apply editorial
+        // hides here to keep region-blocked titles out of fallback results
+        let editorial = self.editorial_plan(request, Some(&best.query_parsed)).await;
+
+        // Step 3: pure-semantic retry (no keyword constraint, no user filters)
+        let semantic = self
+            .vector_search
+            .search(&query, protected.clone())
+            .await
+            .map(|results| {
+                self.response_from_results(
+                    request,
+                    best.query_parsed.clone(),
+                    editorial.filter_hidden(results),
+                )
+            });
+        let improved_before = report.recovered_by;
+        let succeeded = self.record_attempt(
+            &mut report,
+            &mut best,
+            RecoveryStrategy::SemanticOnly,
+            &query,
+            request,
+            semantic,
+        );
+        if report.recovered_by != improved_before {
+            report.relaxed_filters = user_filter_names(request.filters.as_ref());
+        }
+        if succeeded {
+            return self.finish_recovery(report, best, start_time);
+        }
+
+        // Step 4: trending content
+        let trending = self
+            .trending_results(protected.as_ref(), TRENDING_FALLBACK_LIMIT)
+            .await
+            .map(|results| {
+                self.response_from_results(
+                    request,
+                    best.query_parsed.clone(),
+                    editorial.filter_hidden(results),
+                )
+            });
+        let improved_before = report.recovered_by;
+        self.record_attempt(
+            &mut report,
+            &mut best,
+            RecoveryStrategy::Trending,
+            &query,
+            request,
+            trending,
+        );
+        if report.recovered_by != improved_before {
+            report.relaxed_filters = user_filter_names(request.filters.as_ref());
+        }
+
+        self.finish_recovery(report, best, start_time)
+    }
+
+    /// Record an attempt, keep the best response so far and log for analytics
+    ///
+    /// # Returns
+    /// `true` if the attempt fully recovered the query
+    fn record_attempt(
+        &self,
+        report: &mut RecoveryReport,
+        best: &mut SearchResponse,
+        strategy: RecoveryStrategy,
+        query: &str,
+        request: &SearchRequest,
+        result: anyhow::Result<SearchResponse>,
+    ) -> bool {
+        let candidate = match result {
+            Ok(candidate) => candidate,
+            Err(e) => {
+                warn!(error = %e, strategy = strategy.as_str(), "Recovery strategy failed");
+                report.attempts.push(RecoveryAttempt {
+                    strategy,
+                    query: query.to_string(),
+                    result_count: 0,
+                    succeeded: false,
+                });
+                self.log_recovery_attempt(request, strategy, query, 0, false);
+                return false;
+            }
+        };
+
+        let result_count = candidate.total_count;
+        let succeeded = !needs_recovery(&candidate);
+
+        debug!(
+            strategy = strategy.as_str(),
+            result_count = result_count,
+            succeeded = succeeded,
+            "Recovery attempt completed"
+        );
+
+        report.attempts.push(RecoveryAttempt {
+            strategy,
+            query: query.to_string(),
+            result_count,
+            succeeded,
+        });
+        self.log_recovery_attempt(request, strategy, query, result_count, succeeded);
+
+        if result_count > best.total_count {
+            *best = candidate;
+            report.recovered_by = Some(strategy);
+        }
+
+        succeeded
+    }
+
+    fn finish_recovery(
+        &self,
+        report: RecoveryReport,
+        mut response: SearchResponse,
+        start_time: Instant,
+    ) -> SearchResponse {
+        info!(
+            query = %report.original_query,
+            recovered_by = ?report.recovered_by.map(|s| s.as_str()),
+            attempts = report.attempts.len(),
+            result_count = response.total_count,
+            recovery_time_ms = start_time.elapsed().as_millis() as u64,
+            "Zero-result recovery completed"
+        );
+
+        response.search_time_ms += start_time.elapsed().as_millis() as u64;
+        response.recovery = Some(report);
+        response
+    }
+
+    /// Log a recovery attempt for analytics (non-blocking)
+    fn log_recovery_attempt(
+        &self,
+        request: &SearchRequest,
+        strategy: RecoveryStrategy,
+        query: &str,
+        result_count: usize,
+        succeeded: bool,
+    ) {
+        if let Some(analytics) = &self.analytics {
+            let analytics = analytics.clone();
+            let original_query = request.query.clone();
+            let attempted_query = query.to_string();
+            let user_id = request.user_id.map(|id| id.to_string());
+            tokio::spawn(async move {
+                if let Err(e) = analytics
+                    .query_log()
+                    .log_recovery_attempt(
+                        &original_query,
+                        &attempted_query,
+                        user_id.as_deref(),
+                        strategy.as_str(),
+                        result_count as i32,
+                        succeeded,
+                    )
+                    .await
+                {
+                    debug!(error = %e, "Failed to log recovery attempt");
+                }
+            });
+        }
+    }
+
+    /// Build a paginated response from an already-ranked result list
+    fn response_from_results(
+        &self,
+        request: &SearchRequest,
+        query_parsed: ParsedIntent,
+        results: Vec<SearchResult>,
+    ) -> SearchResponse {
+        let facets = self.facet_service.compute_facets(&results);
+        let total_count = results.len();
+        let start = std::cmp::min(
+            ((request.page.max(1) - 1) * request.page_size) as usize,
+            total_count,
+        );
+        let end = std::cmp::min(start + request.page_size as usize, total_count);
+
+        SearchResponse {
+            results: results[start..end].to_vec(),
+            total_count,
+            page: request.page,
+            page_size: request.page_size,
+            query_parsed,
+            search_time_ms: 0,
+            facets,
+            recovery: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intent::IntentFilters;
+    use crate::search::filters::ContentRating;
+    use std::collections::HashMap;
+
+    fn response(total_count: usize, confidence: f32) -> SearchResponse {
+        SearchResponse {
+            results: vec![],
+            total_count,
+            page: 1,
+            page_size: 20,
+            query_parsed: ParsedIntent {
+                mood: vec![],
+                themes: vec![],
+                references: vec![],
+                filters: IntentFilters::default(),
+                fallback_query: String::new(),
+                confidence,
+            },
+            search_time_ms: 0,
+            facets: HashMap::new(),
+            recovery: None,
+        }
+    }
+
+    #[test]
+    fn test_needs_recovery() {
+        assert!(needs_recovery(&response(0, 0.9)));
+        assert!(needs_recovery(&response(2, 0.1)));
+        assert!(!needs_recovery(&response(2, 0.8)));
+        assert!(!needs_recovery(&response(10, 0.1)));
+    }
+
+    #[test]
+    fn test_relaxation_order() {
+        let filters = SearchFilters {
+            genres: vec!["horror".to_string()],
+            platforms: vec!["netflix".to_string()],
+            year_range: Some((1990, 1995)),
+            ..Default::default()
+        };
+
+        let steps = relaxation_steps(&filters);
+        let strategies: Vec<_> = steps.iter().map(|(s, _, _)| *s).collect();
+        assert_eq!(
+            strategies,
+            vec![
+                RecoveryStrategy::RelaxYear,
+                RecoveryStrategy::RelaxPlatform,
+                RecoveryStrategy::RelaxGenre
+            ]
+        );
+
+        // Relaxation is cumulative
+        let (_, after_platform, dropped) = &steps[1];
+        assert!(after_platform.year_range.is_none());
+        assert!(after_platform.platforms.is_empty());
+        assert_eq!(after_platform.genres, vec!["horror".to_string()]);
+        assert_eq!(
+            dropped,
+            &vec!["year_range".to_string(), "platforms".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_relaxation_skips_absent_filters() {
+        let filters = SearchFilters {
+            genres: vec!["comedy".to_string()],
+            ..Default::default()
+        };
+
+        let steps = relaxation_steps(&filters);
+        assert_eq!(steps.len(), 1);
+        assert_eq!(steps[0].0, RecoveryStrategy::RelaxGenre);
+    }
+
+    #[test]
+    fn test_relaxation_keeps_parental_controls() {
+        let filters = SearchFilters {
+            genres: vec!["comedy".to_string()],
+            content_rating_limit: Some(ContentRating::PG),
+            blocked_genres: vec!["horror".to_string()],
+            ..Default::default()
+        };
+
+        let (_, relaxed, _) = relaxation_steps(&filters).pop().unwrap();
+        assert!(relaxed.genres.is_empty());
+        assert_eq!(relaxed.content_rating_limit, Some(ContentRating::PG));
+        assert_eq!(relaxed.blocked_genres, vec!["horror".to_string()]);
+
+        let protected = protected_filters(Some(&filters)).unwrap();
+        assert!(protected.genres.is_empty());
+        assert_eq!(protected.blocked_genres, vec!["horror".to_string()]);
+        assert!(protected_filters(Some(&SearchFilters::default())).is_none());
+    }
+
+    #[test]
+    fn test_strategy_serialization() {
+        assert_eq!(
+            serde_json::to_string(&RecoveryStrategy::RelaxPlatform).unwrap(),
+            "\"relax_platform\""
+        );
+        assert_eq!(RecoveryStrategy::SemanticOnly.as_str(), "semantic_only");
+    }
+}
@@ -0,0 +1,798 @@
+//! Conversational multi-turn search sessions
+//!
+//! A search session carries the parsed intents and shown results of earlier
+//! turns so that follow-up utterances ("only on Netflix", "without horror",
+//! "shorter", "not that one", "more like the second one") are interpreted as
+//! refinements of the running query instead of brand new searches. Sessions
+//! are persisted in Redis with a sliding expiry.
+
+use chrono::{DateTime, Datelike, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::{debug, instrument};
+use uuid::Uuid;
+
+use super::{SearchFilters, SearchResponse, SearchResult};
+use crate::cache::{CacheError, RedisCache};
+use crate::intent::ParsedIntent;
+
+/// Default session TTL (30 minutes of inactivity)
+pub const DEFAULT_SESSION_TTL_SEC: u64 = 1800;
+
+/// Number of shown results remembered per turn for ordinal references
+const SHOWN_RESULTS_PER_TURN: usize = 10;
+
+/// Maximum number of turns kept in a session
+const MAX_SESSION_TURNS: usize = 20;
+
+/// Window used for "newer" / "more recent" refinements
+const RECENT_YEARS_WINDOW: i32 = 5;
+
+/// Runtime cap in minutes set by a first "shorter" refinement
+const SHORT_RUNTIME_MINUTES: i32 = 100;
+
+/// Amount each further "shorter" lowers an existing runtime cap by
+const RUNTIME_STEP_MINUTES: i32 = 30;
+
+/// Lowest runtime cap "shorter" refinements step down to
+const MIN_RUNTIME_MINUTES: i32 = 30;
+
+/// Words that negate the filter terms following them within a clause
+const NEGATION_MARKERS: &[&str] = &[
+    "no",
+    "not",
+    "without",
+    "except",
+    "exclude",
+    "excluding",
+    "remove",
+    "drop",
+    "skip",
+    "minus",
+];
+
+/// Words that open a follow-up rather than a fresh query
+const CONTINUATION_MARKERS: &[&str] = &[
+    "only", "just", "but", "and", "also", "without", "now", "more", "less", "not", "no", "with",
+    "on", "what", "how", "make", "instead", "newer", "older", "shorter",
+];
+
+/// Tokens that turn a following "like" into a dislike ("don't" splits into
+/// "don" and "t")
+const LIKE_NEGATIONS: &[&str] = &["not", "never", "t", "dont", "didnt", "doesnt"];
+
+/// Ordinal words mapped to zero-based positions in the shown results
+const ORDINALS: &[(&str, usize)] = &[
+    ("first", 0),
+    ("1st", 0),
+    ("second", 1),
+    ("2nd", 1),
+    ("third", 2),
+    ("3rd", 2),
+    ("fourth", 3),
+    ("4th", 3),
+    ("fifth", 4),
+    ("5th", 4),
+];
+
+/// A result shown to the user in a previous turn
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ShownResult {
+    pub id: Uuid,
+    pub title: String,
+}
+
+/// A single turn of a search session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionTurn {
+    /// Raw utterance as typed by the user
+    pub utterance: String,
+    /// Query actually sent to the search pipeline
+    pub effective_query: String,
+    /// Intent parsed from the effective query
+    pub intent: ParsedIntent,
+    /// Refinements applied on top of the previous turn
+    pub refinements: Vec<SessionRefinement>,
+    /// Results shown for this turn, in display order
+    pub shown_results: Vec<ShownResult>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Refinement applied by a follow-up utterance
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SessionRefinement {
+    AddGenre { genre: String },
+    RemoveGenre { genre: String },
+    AddPlatform { platform: String },
+    RemovePlatform { platform: String },
+    SetYearRange { min: i32, max: i32 },
+    SetMaxRuntime { minutes: i32 },
+    ExcludeResult { id: Uuid, title: String },
+    MoreLike { id: Uuid, title: String },
+}
+
+/// Conversational search session state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchSession {
+    pub session_id: Uuid,
+    pub user_id: Option<Uuid>,
+    /// Base query that follow-up refinements apply to
+    pub base_query: String,
+    /// Filters accumulated across turns
+    pub active_filters: SearchFilters,
+    /// Content excluded via "not that one" style feedback
+    pub excluded_ids: Vec<Uuid>,
+    pub turns: Vec<SessionTurn>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Outcome of interpreting an utterance against a session
+#[derive(Debug, Clone)]
+pub struct SessionPlan {
+    /// Whether the utterance refines the previous turn
+    pub is_follow_up: bool,
+    /// Query to send to the search pipeline
+    pub effective_query: String,
+    /// Filters to send to the search pipeline
+    pub filters: SearchFilters,
+    /// Refinements detected in the utterance
+    pub refinements: Vec<SessionRefinement>,
+}
+
+/// Response for a search executed within a session
+#[derive(Debug, Serialize)]
+pub struct SessionSearchResponse {
+    pub session_id: Uuid,
+    /// One-based turn number
+    pub turn: usize,
+    pub is_follow_up: bool,
+    pub effective_query: String,
+    pub refinements: Vec<SessionRefinement>,
+    pub active_filters: SearchFilters,
+    #[serde(flatten)]
+    pub response: SearchResponse,
+}
+
+impl SearchSession {
+    /// Create a new empty session
+    pub fn new(user_id: Option<Uuid>) -> Self {
+        let now = Utc::now();
+        Self {
+            session_id: Uuid::new_v4(),
+            user_id,
+            base_query: String::new(),
+            active_filters: SearchFilters::default(),
+            excluded_ids: Vec::new(),
+            turns: Vec::new(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Most recent turn, if any
+    pub fn last_turn(&self) -> Option<&SessionTurn> {
+        self.turns.last()
+    }
+
+    /// Interpret an utterance against the session state
+    ///
+    /// # Arguments
+    /// * `utterance` - Raw user utterance
+    /// * `intent` - Intent parsed from the utterance
+    /// * `request_filters` - Explicit filters supplied with the request
+    ///
+    /// # Returns
+    /// Plan describing the query and filters to execute
+    pub fn plan(
+        &self,
+        utterance: &str,
+        intent: &ParsedIntent,
+        request_filters: Option<&SearchFilters>,
+    ) -> SessionPlan {
+        let refinements = self.interpret_follow_up(utterance, intent);
+        let is_follow_up = self.last_turn().is_some()
+            && (!refinements.is_empty() && starts_like_follow_up(utterance)
+                || refinements.iter().any(|r| {
+                    matches!(
+                        r,
+                        SessionRefinement::ExcludeResult { .. }
+                            | SessionRefinement::MoreLike { .. }
+                    )
+                })
+                || (!refinements.is_empty() && utterance.split_whitespace().count() <= 4));
+
+        if !is_follow_up {
+            return SessionPlan {
+                is_follow_up: false,
+                effective_query: utterance.trim().to_string(),
+                filters: request_filters.cloned().unwrap_or_default(),
+                refinements: Vec::new(),
+            };
+        }
+
+        let mut filters = self.active_filters.clone();
+        let mut effective_query = self.base_query.clone();
+
+        for refinement in &refinements {
+            match refinement {
+                SessionRefinement::AddGenre { genre } => {
+                    filters.blocked_genres.retain(|g| g != genre);
+                    if !filters.genres.contains(genre) {
+                        filters.genres.push(genre.clone());
+                    }
+                }
+                SessionRefinement::RemoveGenre { genre } => {
+                    filters.genres.retain(|g| g != genre);
+                    if !filters.blocked_genres.contains(genre) {
+                        filters.blocked_genres.push(genre.clone());
+                    }
+                }
+                SessionRefinement::AddPlatform { platform } => {
+                    if !filters.platforms.contains(platform) {
+                        filters.platforms.push(platform.clone());
+                    }
+                }
+                SessionRefinement::RemovePlatform { platform } => {
+                    filters.platforms.retain(|p| p != platform);
+                }
+                SessionRefinement::SetYearRange { min, max } => {
+                    filters.year_range = Some((*min, *max));
+                }
+                SessionRefinement::SetMaxRuntime { minutes } => {
+                    filters.max_runtime_minutes = Some(*minutes);
+                }
+                SessionRefinement::MoreLike { title, .. } => {
+                    effective_query = title.clone();
+                }
+                SessionRefinement::ExcludeResult { .. } => {}
+            }
+        }
+
+        if let Some(explicit) = request_filters {
+            merge_filters(&mut filters, explicit);
+        }
+
+        SessionPlan {
+            is_follow_up: true,
+            effective_query,
+            filters,
+            refinements,
+        }
+    }
+
+    /// Detect refinements expressed by a follow-up utterance
+    fn interpret_follow_up(
+        &self,
+        utterance: &str,
+        intent: &ParsedIntent,
+    ) -> Vec<SessionRefinement> {
+        let Some(last_turn) = self.last_turn() else {
+            return Vec::new();
+        };
+
+        let text = utterance.to_lowercase();
+        let negated = negated_tokens(&text);
+        let mut refinements = Vec::new();
+
+        // Result references: "not that one", "more like the second one"
+        let shown = &last_turn.shown_results;
+        if let Some(index) = referenced_position(&text) {
+            if let Some(target) = shown.get(index) {
+                if is_more_like(&text) {
+                    refinements.push(SessionRefinement::MoreLike {
+                        id: target.id,
+                        title: target.title.clone(),
+                    });
+                } else if is_rejection(&text) {
+                    refinements.push(SessionRefinement::ExcludeResult {
+                        id: target.id,
+                        title: target.title.clone(),
+                    });
+                }
+            }
+        }
+
+        for genre in &intent.filters.genre {
+            if is_negated(genre, &negated) {
+                refinements.push(SessionRefinement::RemoveGenre {
+                    genre: genre.clone(),
+                });
+            } else {
+                refinements.push(SessionRefinement::AddGenre {
+                    genre: genre.clone(),
+                });
+            }
+        }
+
+        for platform in &intent.filters.platform {
+            if is_negated(platform, &negated) {
+                refinements.push(SessionRefinement::RemovePlatform {
+                    platform: platform.clone(),
+                });
+            } else {
+                refinements.push(SessionRefinement::AddPlatform {
+                    platform: platform.clone(),
+                });
+            }
+        }
+
+        if let Some((min, max)) = intent.filters.year_range {
+            refinements.push(SessionRefinement::SetYearRange { min, max });
+        } else if text.contains("newer") || text.contains("more recent") {
+            let current_year = Utc::now().year();
+            refinements.push(SessionRefinement::SetYearRange {
+                min: current_year - RECENT_YEARS_WINDOW,
+                max: current_year,
+            });
+        }
+
+        if tokenize(&text).iter().any(|t| t == "shorter") {
+            let minutes = match self.active_filters.max_runtime_minutes {
+                Some(current) => (current - RUNTIME_STEP_MINUTES).max(MIN_RUNTIME_MINUTES),
+                None => SHORT_RUNTIME_MINUTES,
+            };
+            refinements.push(SessionRefinement::SetMaxRuntime { minutes });
+        }
+
+        refinements
+    }
+
+    /// Record an executed turn and update the accumulated state
+    pub fn record_turn(
+        &mut self,
+        utterance: &str,
+        plan: &SessionPlan,
+        intent: ParsedIntent,
+        results: &[SearchResult],
+    ) {
+        if plan.is_follow_up {
+            for refinement in &plan.refinements {
+                if let SessionRefinement::ExcludeResult { id, .. }
+                | SessionRefinement::MoreLike { id, .. } = refinement
+                {
+                    if !self.excluded_ids.contains(id) {
+                        self.excluded_ids.push(*id);
+                    }
+                }
+            }
+        } else {
+            self.excluded_ids.clear();
+        }
+
+        self.base_query = plan.effective_query.clone();
+        self.active_filters = plan.filters.clone();
+
+        self.turns.push(SessionTurn {
+            utterance: utterance.to_string(),
+            effective_query: plan.effective_query.clone(),
+            intent,
+            refinements: plan.refinements.clone(),
+            shown_results: results
+                .iter()
+                .take(SHOWN_RESULTS_PER_TURN)
+                .map(|r| ShownResult {
+                    id: r.content.id,
+                    title: r.content.title.clone(),
+                })
+                .collect(),
+            timestamp: Utc::now(),
+        });
+
+        if self.turns.len() > MAX_SESSION_TURNS {
+            let overflow = self.turns.len() - MAX_SESSION_TURNS;
+            self.turns.drain(..overflow);
+        }
+
+        self.updated_at = Utc::now();
+    }
+}
+
+/// Redis-backed store for search sessions
+pub struct SearchSessionStore {
+    cache: Arc<RedisCache>,
+    ttl_sec: u64,
+}
+
+impl SearchSessionStore {
+    /// Create a new session store with the default TTL
+    pub fn new(cache: Arc<RedisCache>) -> Self {
+        Self {
+            cache,
+            ttl_sec: DEFAULT_SESSION_TTL_SEC,
+        }
+    }
+
+    /// Override the session TTL
+    pub fn with_ttl(mut self, ttl_sec: u64) -> Self {
+        self.ttl_sec = ttl_sec;
+        self
+    }
+
+    fn key(session_id: Uuid) -> String {
+        format!("search_session:{}", session_id)
+    }
+
+    /// Load a session by ID
+    #[instrument(skip(self))]
+    pub async fn load(&self, session_id: Uuid) -> Result<Option<SearchSession>, CacheError> {
+        self.cache.get(&Self::key(session_id)).await
+    }
+
+    /// Persist a session, refreshing its expiry
+    #[instrument(skip(self, session), fields(session_id = %session.session_id))]
+    pub async fn save(&self, session: &SearchSession) -> Result<(), CacheError> {
+        self.cache
+            .set(&Self::key(session.session_id), session, self.ttl_sec)
+            .await?;
+        debug!(turns = session.turns.len(), "Saved search session");
+        Ok(())
+    }
+
+    /// Delete a session
+    #[instrument(skip(self))]
+    pub async fn delete(&self, session_id: Uuid) -> Result<bool, CacheError> {
+        Ok(self.cache.delete(&Self::key(session_id)).await? > 0)
+    }
+}
+
+/// Merge explicitly supplied filters into the accumulated filters
+fn merge_filters(target: &mut SearchFilters, explicit: &SearchFilters) {
+    for genre in &explicit.genres {
+        if !target.genres.contains(genre) {
+            target.genres.push(genre.clone());
+        }
+    }
+    for platform in &explicit.platforms {
+        if !target.platforms.contains(platform) {
+            target.platforms.push(platform.clone());
+        }
+    }
+    for genre in &explicit.blocked_genres {
+        if !target.blocked_genres.contains(genre) {
+            target.blocked_genres.push(genre.clone());
+        }
+    }
+    if explicit.year_range.is_some() {
+        target.year_range = explicit.year_range;
+    }
+    if explicit.rating_range.is_some() {
+        target.rating_range = explicit.rating_range;
+    }
+    if explicit.content_rating_limit.is_some() {
+        target.content_rating_limit = explicit.content_rating_limit;
+    }
+    if explicit.max_runtime_minutes.is_some() {
+        target.max_runtime_minutes = explicit.max_runtime_minutes;
+    }
+}
+
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_'))
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_string())
+        .collect()
+}
+
+fn starts_like_follow_up(utterance: &str) -> bool {
+    tokenize(&utterance.to_lowercase())
+        .first()
+        .map(|first| CONTINUATION_MARKERS.contains(&first.as_str()))
+        .unwrap_or(false)
+}
+
+/// Collect tokens that appear after a negation marker within the same clause
+fn negated_tokens(text: &str) -> Vec<String> {
+    let mut negated = Vec::new();
+
+    for clause in text.split([',', ';', '.']) {
+        for part in clause.split(" but ") {
+            let mut negating = false;
+            for token in tokenize(part) {
+                if NEGATION_MARKERS.contains(&token.as_str()) {
+                    negating = true;
+                } else if token == "and" || token == "or" || token == "nor" {
+                    continue;
+                } else if negating {
+                    negated.push(token);
+                }
+            }
+        }
+    }
+
+    negated
+}
+
+/// Surface forms a canonical genre/platform name can appear as in text
+fn surface_forms(canonical: &str) -> Vec<String> {
+    let mut forms = vec![canonical.to_string(), canonical.replace('_', " ")];
+    match canonical {
+        "science_fiction" => forms.extend(["sci-fi", "scifi", "science"].map(String::from)),
+        "prime_video" => forms.extend(["prime", "amazon"].map(String::from)),
+        "disney_plus" => forms.push("disney".to_string()),
+        "hbo_max" => forms.extend(["hbo", "max"].map(String::from)),
+        _ => {}
+    }
+    forms
+}
+
+fn is_negated(canonical: &str, negated: &[String]) -> bool {
+    let forms = surface_forms(canonical);
+    negated
+        .iter()
+        .any(|token| forms.iter().any(|form| form == token))
+}
+
+/// Resolve which shown result an utterance refers to
+fn referenced_position(text: &str) -> Option<usize> {
+    let tokens = tokenize(text);
+
+    for token in &tokens {
+        if let Some((_, index)) = ORDINALS.iter().find(|(word, _)| word == token) {
+            return Some(*index);
+        }
+    }
+
+    // "that one" / "this one" refer to the top result
+    if text.contains("that one") || text.contains("this one") || text.contains("top one") {
+        return Some(0);
+    }
+
+    None
+}
+
+fn is_more_like(text: &str) -> bool {
+    if text.contains("more like") || text.contains("similar to") {
+        return true;
+    }
+
+    // "like the second one", but not "don't like the second one"
+    let tokens = tokenize(text);
+    tokens
+        .windows(2)
+        .enumerate()
+        .any(|(i, pair)| pair[0] == "like" && pair[1] == "the" && !is_negated_like(&tokens, i))
+}
+
+/// Whether the "like" at `index` follows a negation ("don't like", "not like")
+fn is_negated_like(tokens: &[String], index: usize) -> bool {
+    index > 0 && LIKE_NEGATIONS.contains(&tokens[index - 1].as_str())
+}
+
+fn is_rejection(text: &str) -> bool {
+    let tokens = tokenize(text);
+    NEGATION_MARKERS
+        .iter()
+        .any(|marker| tokens.iter().any(|t| t == marker))
+        || tokens.iter().any(|t| t == "dislike")
+        || tokens
+            .iter()
+            .enumerate()
+            .any(|(i, t)| t == "like" && is_negated_like(&tokens, i))
+        || text.contains("something else")
+        || text.contains("seen it")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intent::IntentFilters;
+    use crate::search::ContentSummary;
+
+    fn intent(genres: &[&str], platforms: &[&str]) -> ParsedIntent {
+        ParsedIntent {
+            mood: vec![],
+            themes: vec![],
+            references: vec![],
+            filters: IntentFilters {
+                genre: genres.iter().map(|s| s.to_string()).collect(),
+                platform: platforms.iter().map(|s| s.to_string()).collect(),
+                year_range: None,
+            },
+            fallback_query: String::new(),
+            confidence: 0.5,
+        }
+    }
+
+    fn result(title: &str) -> SearchResult {
+        SearchResult {
+            content: ContentSummary {
+                id: Uuid::new_v4(),
+                title: title.to_string(),
+                overview: String::new(),
+                release_year: 2020,
+                genres: vec![],
+                platforms: vec![],
+                popularity_score: 0.5,
+            },
+            relevance_score: 1.0,
+            match_reasons: vec![],
+            vector_similarity: None,
+            graph_score: None,
+            keyword_score: None,
+        }
+    }
+
+    fn session_with_turn(query: &str, results: &[SearchResult]) -> SearchSession {
+        let mut session = SearchSession::new(None);
+        let plan = session.plan(query, &intent(&[], &[]), None);
+        session.record_turn(query, &plan, intent(&[], &[]), results);
+        session
+    }
+
+    #[test]
+    fn test_first_turn_is_fresh_query() {
+        let session = SearchSession::new(None);
+        let plan = session.plan("space movies", &intent(&["science_fiction"], &[]), None);
+
+        assert!(!plan.is_follow_up);
+        assert_eq!(plan.effective_query, "space movies");
+        assert!(plan.refinements.is_empty());
+    }
+
+    #[test]
+    fn test_follow_up_adds_platform_and_keeps_query() {
+        let session = session_with_turn("space movies", &[result("Interstellar")]);
+        let plan = session.plan("only on netflix", &intent(&[], &["netflix"]), None);
+
+        assert!(plan.is_follow_up);
+        assert_eq!(plan.effective_query, "space movies");
+        assert_eq!(plan.filters.platforms, vec!["netflix".to_string()]);
+    }
+
+    #[test]
+    fn test_follow_up_removes_genre() {
+        let mut session = session_with_turn("scary movies", &[result("It")]);
+        session.active_filters.genres = vec!["horror".to_string(), "thriller".to_string()];
+
+        let plan = session.plan("without horror", &intent(&["horror"], &[]), None);
+
+        assert!(plan.is_follow_up);
+        assert_eq!(plan.filters.genres, vec!["thriller".to_string()]);
+        assert_eq!(plan.filters.blocked_genres, vec!["horror".to_string()]);
+    }
+
+    #[test]
+    fn test_mixed_add_and_remove() {
+        let session = session_with_turn("something fun", &[result("Up")]);
+        let plan = session.plan(
+            "comedy but no sci-fi",
+            &intent(&["comedy", "science_fiction"], &[]),
+            None,
+        );
+
+        assert!(plan.refinements.contains(&SessionRefinement::AddGenre {
+            genre: "comedy".to_string()
+        }));
+        assert!(plan.refinements.contains(&SessionRefinement::RemoveGenre {
+            genre: "science_fiction".to_string()
+        }));
+    }
+
+    #[test]
+    fn test_not_that_one_excludes_top_result() {
+        let shown = vec![result("Heat"), result("Ronin")];
+        let mut session = session_with_turn("heist movies", &shown);
+
+        let plan = session.plan("not that one", &intent(&[], &[]), None);
+        assert!(plan.is_follow_up);
+        assert_eq!(
+            plan.refinements,
+            vec![SessionRefinement::ExcludeResult {
+                id: shown[0].content.id,
+                title: "Heat".to_string()
+            }]
+        );
+
+        session.record_turn("not that one", &plan, intent(&[], &[]), &shown[1..]);
+        assert_eq!(session.excluded_ids, vec![shown[0].content.id]);
+        assert_eq!(session.base_query, "heist movies");
+    }
+
+    #[test]
+    fn test_more_like_second_one() {
+        let shown = vec![result("Heat"), result("Ronin")];
+        let session = session_with_turn("heist movies", &shown);
+
+        let plan = session.plan("more like the second one", &intent(&[], &[]), None);
+
+        assert!(plan.is_follow_up);
+        assert_eq!(plan.effective_query, "Ronin");
+        assert_eq!(
+            plan.refinements,
+            vec![SessionRefinement::MoreLike {
+                id: shown[1].content.id,
+                title: "Ronin".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_negated_like_excludes_instead_of_more_like() {
+        let shown = vec![result("Heat"), result("Ronin")];
+        let session = session_with_turn("heist movies", &shown);
+
+        for utterance in [
+            "don't like the second one",
+            "i do not like the second one",
+            "dislike the second one",
+        ] {
+            let plan = session.plan(utterance, &intent(&[], &[]), None);
+            assert_eq!(
+                plan.refinements,
+                vec![SessionRefinement::ExcludeResult {
+                    id: shown[1].content.id,
+                    title: "Ronin".to_string()
+                }],
+                "{}",
+                utterance
+            );
+        }
+
+        let plan = session.plan("i like the second one", &intent(&[], &[]), None);
+        assert!(matches!(
+            plan.refinements[..],
+            [SessionRefinement::MoreLike { .. }]
+        ));
+    }
+
+    #[test]
+    fn test_shorter_caps_runtime() {
+        let mut session = session_with_turn("something funny", &[result("Airplane!")]);
+
+        let plan = session.plan("shorter, and on netflix", &intent(&[], &["netflix"]), None);
+        assert!(plan.is_follow_up);
+        assert_eq!(plan.effective_query, "something funny");
+        assert_eq!(
+            plan.filters.max_runtime_minutes,
+            Some(SHORT_RUNTIME_MINUTES)
+        );
+        assert_eq!(plan.filters.platforms, vec!["netflix".to_string()]);
+
+        session.record_turn("shorter, and on netflix", &plan, intent(&[], &[]), &[]);
+        let plan = session.plan("even shorter", &intent(&[], &[]), None);
+        assert_eq!(
+            plan.filters.max_runtime_minutes,
+            Some(SHORT_RUNTIME_MINUTES - RUNTIME_STEP_MINUTES)
+        );
+    }
+
+    #[test]
+    fn test_new_topic_resets_state() {
+        let mut session = session_with_turn("heist movies", &[result("Heat")]);
+        session.active_filters.platforms = vec!["hulu".to_string()];
+        session.excluded_ids.push(Uuid::new_v4());
+
+        let query = "romantic comedies from the nineties set in paris";
+        let plan = session.plan(query, &intent(&["romance", "comedy"], &[]), None);
+        assert!(!plan.is_follow_up);
+        assert!(plan.filters.platforms.is_empty());
+
+        session.record_turn(query, &plan, intent(&[], &[]), &[]);
+        assert!(session.excluded_ids.is_empty());
+        assert_eq!(session.turns.len(), 2);
+    }
+
+    #[test]
+    fn test_turn_history_is_bounded() {
+        let mut session = SearchSession::new(None);
+        for i in 0..(MAX_SESSION_TURNS + 5) {
+            let query = format!("query {}", i);
+            let plan = session.plan(&query, &intent(&[], &[]), None);
+            session.record_turn(&query, &plan, intent(&[], &[]), &[]);
+        }
+        assert_eq!(session.turns.len(), MAX_SESSION_TURNS);
+        assert_eq!(session.turns[0].utterance, "query 5");
+    }
+
+    #[test]
+    fn test_session_serialization_roundtrip() {
+        let session = session_with_turn("heist movies", &[result("Heat")]);
+        let json = serde_json::to_string(&session).unwrap();
+        let restored: SearchSession = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored.session_id, session.session_id);
+        assert_eq!(restored.turns[0].shown_results[0].title, "Heat");
+    }
+}
@@ -0,0 +1,416 @@
+//! Streaming search over Server-Sent Events
+//!
+//! Runs the same pipeline as [`HybridSearchService::search`] but emits each
+//! stage as soon as it completes so clients can render keyword matches within
+//! tens of milliseconds while vector search, personalization and faceting
+//! are still in flight. Each result event carries the full ranked page for
+//! that stage; clients should replace the previous page with the latest one.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::Instant;
+use tokio::sync::mpsc;
+use tracing::{debug, info, instrument, warn};
+
+use super::facets::FacetCount;
+use super::recovery::{self, RecoveryReport};
+use super::{HybridSearchService, SearchRequest, SearchResponse, SearchResult};
+use crate::intent::ParsedIntent;
+
+/// Channel capacity for stream events; a search emits at most eight events
+pub const STREAM_CHANNEL_CAPACITY: usize = 9;
+
+/// Ranked page emitted by a result stage
+#[derive(Debug, Clone, Serialize)]
+pub struct StageResults {
+    pub results: Vec<SearchResult>,
+    pub total_count: usize,
+    pub page: u32,
+    pub page_size: u32,
+    /// Milliseconds since the search started
+    pub elapsed_ms: u64,
+}
+
+/// Typed event emitted by a streaming search
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SearchStreamEvent {
+    /// Parsed query intent
+    Intent { query_parsed: ParsedIntent },
+    /// Keyword (BM25) results, available before vector search completes
+    Keyword(StageResults),
+    /// Reciprocal Rank Fusion of keyword and vector results
+    Fused(StageResults),
+    /// Fused results re-ranked for the requesting user
+    Personalized(StageResults),
+    /// Ranking after editorial pin, boost and bury rules
+    Editorial(StageResults),
+    /// Results from the zero-result recovery cascade
+    Recovered {
+        #[serde(flatten)]
+        stage: StageResults,
+        recovery: RecoveryReport,
+    },
+    /// Facet counts computed over the final ranking
+    Facets {
+        facets: HashMap<String, Vec<FacetCount>>,
+    },
+    /// Final event; no further events follow
+    Done {
+        total_count: usize,
+        search_time_ms: u64,
+        cached: bool,
+    },
+    /// Terminal failure; no further events follow
+    Error { message: String },
+}
+
+impl SearchStreamEvent {
+    /// SSE event name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Intent { .. } => "intent",
+            Self::Keyword(_) => "keyword",
+            Self::Fused(_) => "fused",
+            Self::Personalized(_) => "personalized",
+            Self::Editorial(_) => "editorial",
+            Self::Recovered { .. } => "recovered",
+            Self::Facets { .. } => "facets",
+            Self::Done { .. } => "done",
+            Self::Error { .. } => "error",
+        }
+    }
+
+    /// Encode as an SSE frame (`event:` and `data:` lines)
+    pub fn to_sse(&self) -> String {
+        let data = serde_json::to_string(self).unwrap_or_else(|e| {
+            format!(
+                r#"{{"type":"error","message":"Failed to encode event: {}"}}"#,
+                e
+            )
+        });
+        format!("event: {}\ndata: {}\n\n", self.name(), data)
+    }
+}
+
+impl StageResults {
+    fn page(request: &SearchRequest, ranked: &[SearchResult], started: Instant) -> Self {
+        let total_count = ranked.len();
+        let start = std::cmp::min(
+            ((request.page.max(1) - 1) * request.page_size) as usize,
+            total_count,
+        );
+        let end = std::cmp::min(start + request.page_size as usize, total_count);
+
+        Self {
+            results: ranked[start..end].to_vec(),
+            total_count,
+            page: request.page,
+            page_size: request.page_size,
+            elapsed_ms: started.elapsed().as_millis() as u64,
+        }
+    }
+
+    fn from_response(response: &SearchResponse, started: Instant) -> Self {
+        Self {
+            results: response.results.clone(),
+            total_count: response.total_count,
+            page: response.page,
+            page_size: response.page_size,
+            elapsed_ms: started.elapsed().as_millis() as u64,
+        }
+    }
+}
+
+impl HybridSearchService {
+    /// Execute a search, emitting each pipeline stage on `events`
+    ///
+    /// Keyword results are sent as soon as the keyword index answers, fused
+    /// results once vector search returns, then personalized results (when a
+    /// user is given), editorial results (when rules match), facets and a
+    /// final `done` event. Editorial hides apply to every stage. Cache hits replay the
+    /// cached response as a single `fused` stage. Sending stops early if the
+    /// receiver is dropped.
+    #[instrument(skip(self, events), fields(query = %request.query, page = %request.page))]
+    pub async fn search_streaming(
+        &self,
+        request: SearchRequest,
+        events: mpsc::Sender<SearchStreamEvent>,
+    ) {
+        if let Err(e) = self.run_streaming(&request, &events).await {
+            warn!(error = %e, "Streaming search failed");
+            let _ = events
+                .send(SearchStreamEvent::Error {
+                    message: format!("Search failed: {}", e),
+                })
+                .await;
+        }
+    }
+
+    async fn run_streaming(
+        &self,
+        request: &SearchRequest,
+        events: &mpsc::Sender<SearchStreamEvent>,
+    ) -> anyhow::Result<()> {
+        let started = Instant::now();
+        let editorial_version = self.editorial_rules.fingerprint().await;
+        let cache_key = self.generate_cache_key(request, &editorial_version);
+
+        if let Ok(Some(cached)) = self.cache.get::<SearchResponse>(&cache_key).await {
+            debug!(cache_key = %cache_key, "Cache hit - replaying cached search results");
+            let stage = StageResults::from_response(&cached, started);
+            emit(
+                events,
+                SearchStreamEvent::Intent {
+                    query_parsed: cached.query_parsed,
+                },
+            )
+            .await?;
+            emit(events, SearchStreamEvent::Fused(stage)).await?;
+            emit(
+                events,
+                SearchStreamEvent::Facets {
+                    facets: cached.facets,
+                },
+            )
+            .await?;
+            return emit(
+                events,
+                SearchStreamEvent::Done {
+                    total_count: cached.total_count,
+                    search_time_ms: started.elapsed().as_millis() as u64,
+                    cached: true,
+                },
+            )
+            .await;
+        }
+
+        // Phase 1: Run intent parsing and both retrievers concurrently,
+        // emitting each as it lands. Vector results are held back until
+        // keyword results have been sent so the fused page always follows.
+        let intent_fut = self.intent_parser.parse(&request.query);
+        let keyword_fut = self
+            .keyword_search
+            .search(&request.query, request.filters.clone());
+        let vector_fut = self
+            .vector_search
+            .search(&request.query, request.filters.clone());
+        tokio::pin!(intent_fut, keyword_fut, vector_fut);
+
+        // Keyword results may land before the intent, so hide with a
+        // query-only plan until the full plan can be built
+        let provisional = self.provisional_editorial_plan(request).await;
+
+        let mut intent: Option<ParsedIntent> = None;
+        let mut keyword_results = None;
+        let mut vector_results = None;
+
+        while intent.is_none() || keyword_results.is_none() || vector_results.is_none() {
+            tokio::select! {
+                parsed = &mut intent_fut, if intent.is_none() => {
+                    let parsed = parsed?;
+                    emit(events, SearchStreamEvent::Intent { query_parsed: parsed.clone() }).await?;
+                    intent = Some(parsed);
+                }
+                result = &mut keyword_fut, if keyword_results.is_none() => {
+                    match &result {
+                        Ok(results) => {
+                            let visible = provisional.filter_hidden(results.clone());
+                            let stage = StageResults::page(request, &visible, started);
+                            debug!(elapsed_ms = stage.elapsed_ms, "Streaming keyword results");
+                            emit(events, SearchStreamEvent::Keyword(stage)).await?;
+                        }
+                        Err(e) => warn!(error = %e, "Keyword search failed during streaming search"),
+                    }
+                    keyword_results = Some(result);
+                }
+                result = &mut vector_fut, if vector_results.is_none() => {
+                    if let Err(e) = &result {
+                        warn!(error = %e, "Vector search failed during streaming search");
+                    }
+                    vector_results = Some(result);
+                }
+            }
+        }
+
+        let intent = intent.expect("streaming loop exits only after intent is parsed");
+        let plan = self.editorial_plan(request, Some(&intent)).await;
+
+        // Phase 2: Fuse, falling back to whichever strategy succeeded
+        let fused = match (vector_results, keyword_results) {
+            (Some(Ok(vector_res)), Some(Ok(keyword_res))) => {
+                self.reciprocal_rank_fusion(vector_res, keyword_res, self.config.search.rrf_k)
+            }
+            (Some(Err(_)), Some(Ok(keyword_res))) => keyword_res,
+            (Some(Ok(vector_res)), Some(Err(_))) => vector_res,
+            (Some(Err(vector_err)), Some(Err(keyword_err))) => {
+                return Err(anyhow::anyhow!(
+                    "All search strategies failed: vector={}, keyword={}",
+                    vector_err,
+                    keyword_err
+                ));
+            }
+            _ => unreachable!("streaming loop exits only after both retrievers complete"),
+        };
+        emit(
+            events,
+            SearchStreamEvent::Fused(StageResults::page(
+                request,
+                &plan.filter_hidden(fused.clone()),
+                started,
+            )),
+        )
+        .await?;
+
+        // Phase 3: Personalize
+        let ranked = match request.user_id {
+            Some(user_id) => match self
+                .personalization_service
+                .personalize_results(
+                    user_id,
+                    fused.clone(),
+                    request.experiment_variant.as_deref(),
+                )
+                .await
+            {
+                Ok(personalized) => {
+                    emit(
+                        events,
+                        SearchStreamEvent::Personalized(StageResults::page(
+                            request,
+                            &plan.filter_hidden(personalized.clone()),
+                            started,
+                        )),
+                    )
+                    .await?;
+                    personalized
+                }
+                Err(e) => {
+                    warn!(error = %e, user_id = %user_id, "Personalization failed, using fused ranking");
+                    fused
+                }
+            },
+            None => fused,
+        };
+
+        // Phase 4: Apply editorial rules
+        let ranked = self.apply_editorial(request, &plan, ranked).await;
+        if !plan.is_empty() {
+            emit(
+                events,
+                SearchStreamEvent::Editorial(StageResults::page(request, &ranked, started)),
+            )
+            .await?;
+        }
+
+        // Phase 5: Assemble the final response, recovering empty result sets
+        let stage = StageResults::page(request, &ranked, started);
+        let mut response = SearchResponse {
+            results: stage.results,
+            total_count: stage.total_count,
+            page: request.page,
+            page_size: request.page_size,
+            query_parsed: intent,
+            search_time_ms: 0,
+            facets: self.facet_service.compute_facets(&ranked),
+            recovery: None,
+        };
+
+        if recovery::needs_recovery(&response) {
+            response = self.recover(request, response).await;
+            if let Some(report) = response.recovery.clone() {
+                emit(
+                    events,
+                    SearchStreamEvent::Recovered {
+                        stage: StageResults::from_response(&response, started),
+                        recovery: report,
+                    },
+                )
+                .await?;
+            }
+        }
+
+        response.search_time_ms = started.elapsed().as_millis() as u64;
+
+        emit(
+            events,
+            SearchStreamEvent::Facets {
+                facets: response.facets.clone(),
+            },
+        )
+        .await?;
+        emit(
+            events,
+            SearchStreamEvent::Done {
+                total_count: response.total_count,
+                search_time_ms: response.search_time_ms,
+                cached: false,
+            },
+        )
+        .await?;
+
+        info!(
+            search_time_ms = %response.search_time_ms,
+            total_results = %response.total_count,
+            "Completed streaming search"
+        );
+
+        self.record_search(request, &response, response.search_time_ms as i32);
+        if let Err(e) = self.cache.set(&cache_key, &response, 1800).await {
+            debug!(error = %e, cache_key = %cache_key, "Failed to cache streamed search results");
+        }
+
+        Ok(())
+    }
+}
+
+async fn emit(
+    events: &mpsc::Sender<SearchStreamEvent>,
+    event: SearchStreamEvent,
+) -> anyhow::Result<()> {
+    events
+        .send(event)
+        .await
+        .map_err(|_| anyhow::anyhow!("Search stream closed by client"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sse_frame_format() {
+        let event = SearchStreamEvent::Done {
+            total_count: 3,
+            search_time_ms: 42,
+            cached: false,
+        };
+
+        let frame = event.to_sse();
+        assert!(frame.starts_with("event: done\ndata: {"));
+        assert!(frame.ends_with("}\n\n"));
+
+        let data: serde_json::Value =
+            serde_json::from_str(frame.lines().nth(1).unwrap().trim_start_matches("data: "))
+                .unwrap();
+        assert_eq!(data["type"], "done");
+        assert_eq!(data["total_count"], 3);
+    }
+
+    #[test]
+    fn test_stage_event_serialization() {
+        let event = SearchStreamEvent::Keyword(StageResults {
+            results: vec![],
+            total_count: 0,
+            page: 1,
+            page_size: 20,
+            elapsed_ms: 12,
+        });
+
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(event.name(), "keyword");
+        assert_eq!(json["type"], "keyword");
+        assert_eq!(json["elapsed_ms"], 12);
+        assert_eq!(json["page_size"], 20);
+    }
+}
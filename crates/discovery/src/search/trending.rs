@@ -0,0 +1,286 @@
+//! Trending content lists
+//!
+//! Hydrates the decayed engagement counters kept by
+//! [`media_gateway_core::TrendingService`] into content summaries: trending
+//! now, rising and top per genre, scoped globally or to a region or
+//! platform. Counters only know content IDs, so genre and filter
+//! restrictions are applied against the catalog on an oversized candidate
+//! pool. When no counters are available (Redis down, fresh deployment) the
+//! lists fall back to catalog popularity.
+
+use chrono::Utc;
+use media_gateway_core::{TrendingItem, TrendingScope};
+use serde::{Deserialize, Serialize};
+use sqlx::{Postgres, QueryBuilder};
+use std::collections::HashMap;
+use tracing::{debug, instrument, warn};
+use uuid::Uuid;
+
+use super::{ContentSummary, HybridSearchService, SearchFilters, SearchResult};
+
+/// Trending candidates fetched per requested item when results are
+/// restricted by genre or filters
+const FILTERED_POOL_FACTOR: usize = 10;
+
+/// Catalog columns hydrated into a [`ContentSummary`]
+const CONTENT_SUMMARY_SELECT: &str = "SELECT id, title, overview, release_year, genres, \
+     ARRAY[]::text[] AS platforms, popularity_score FROM content";
+
+/// Trending list kind
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrendingList {
+    Now,
+    Rising,
+}
+
+/// Content summary with its trending counters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrendingContent {
+    pub content: ContentSummary,
+    /// Counters, absent when the list fell back to catalog popularity
+    pub trending: Option<TrendingItem>,
+}
+
+impl TrendingContent {
+    fn into_search_result(self) -> SearchResult {
+        let relevance_score = match &self.trending {
+            Some(item) => item.score as f32,
+            None => self.content.popularity_score,
+        };
+        SearchResult {
+            content: self.content,
+            relevance_score,
+            match_reasons: vec!["trending".to_string()],
+            vector_similarity: None,
+            graph_score: None,
+            keyword_score: None,
+        }
+    }
+}
+
+impl HybridSearchService {
+    /// Trending or rising content for a scope
+    ///
+    /// # Arguments
+    /// * `list` - Trending now or rising
+    /// * `scope` - Global, region or platform audience
+    /// * `genre` - Restrict to content in this genre (top per genre)
+    /// * `filters` - Additional catalog filters (e.g. parental controls)
+    /// * `limit` - Maximum number of items
+    #[instrument(skip(self, filters))]
+    pub async fn trending(
+        &self,
+        list: TrendingList,
+        scope: &TrendingScope,
+        genre: Option<&str>,
+        filters: Option<&SearchFilters>,
+        limit: usize,
+    ) -> anyhow::Result<Vec<TrendingContent>> {
+        let restricted = genre.is_some() || filters.is_some_and(|f| !f.is_empty());
+        let pool = if restricted {
+            limit.saturating_mul(FILTERED_POOL_FACTOR)
+        } else {
+            limit
+        };
+
+        let items = match self.trending_items(list, scope, pool).await {
+            Ok(items) => items,
+            Err(e) => {
+                warn!(error = %e, "Trending counters unavailable, falling back to popularity");
+                Vec::new()
+            }
+        };
+
+        if items.is_empty() {
+            // Without engagement there is nothing rising; trending falls back
+            // to catalog popularity
+            if list == TrendingList::Rising {
+                return Ok(Vec::new());
+            }
+            return self.popular_content(genre, filters, limit).await;
+        }
+
+        let ids: Vec<Uuid> = items.iter().map(|item| item.content_id).collect();
+        let mut summaries: HashMap<Uuid, ContentSummary> = self
+            .content_summaries(&ids, genre, filters)
+            .await?
+            .into_iter()
+            .map(|content| (content.id, content))
+            .collect();
+
+        let results: Vec<TrendingContent> = items
+            .into_iter()
+            .filter_map(|item| {
+                summaries
+                    .remove(&item.content_id)
+                    .map(|content| TrendingContent {
+                        content,
+                        trending: Some(item),
+                    })
+            })
+            .take(limit)
+            .collect();
+
+        debug!(
+            candidates = ids.len(),
+            returned = results.len(),
+            "Hydrated trending content"
+        );
+
+        Ok(results)
+    }
+
+    async fn trending_items(
+        &self,
+        list: TrendingList,
+        scope: &TrendingScope,
+        limit: usize,
+    ) -> anyhow::Result<Vec<TrendingItem>> {
+        let Some(trending) = &self.trending else {
+            return Ok(Vec::new());
+        };
+
+        let now = Utc::now();
+        let items = match list {
+            TrendingList::Now => trending.trending_now(scope, limit, now).await?,
+            TrendingList::Rising => trending.rising(scope, limit, now).await?,
+        };
+        Ok(items)
+    }
+
+    /// Catalog rows for trending candidates that pass the restrictions
+    async fn content_summaries(
+        &self,
+        ids: &[Uuid],
+        genre: Option<&str>,
+        filters: Option<&SearchFilters>,
+    ) -> anyhow::Result<Vec<ContentSummary>> {
+        let mut query = QueryBuilder::<Postgres>::new(CONTENT_SUMMARY_SELECT);
+        query
+            .push(" WHERE id = ANY(")
+            .push_bind(ids.to_vec())
+            .push(")");
+        push_restrictions(&mut query, genre, filters);
+
+        let rows = query
+            .build_query_as::<ContentSummary>()
+            .fetch_all(&self.db_pool)
+            .await?;
+
+        Ok(rows)
+    }
+
+    /// Most popular catalog content, honoring genre and filters
+    async fn popular_content(
+        &self,
+        genre: Option<&str>,
+        filters: Option<&SearchFilters>,
+        limit: usize,
+    ) -> anyhow::Result<Vec<TrendingContent>> {
+        let mut query = QueryBuilder::<Postgres>::new(CONTENT_SUMMARY_SELECT);
+        query.push(" WHERE 1=1");
+        push_restrictions(&mut query, genre, filters);
+        query
+            .push(" ORDER BY popularity_score DESC LIMIT ")
+            .push_bind(limit as i64);
+
+        let rows = query
+            .build_query_as::<ContentSummary>()
+            .fetch_all(&self.db_pool)
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|content| TrendingContent {
+                content,
+                trending: None,
+            })
+            .collect())
+    }
+
+    /// Trending content as search results, for zero-result recovery
+    pub(super) async fn trending_results(
+        &self,
+        filters: Option<&SearchFilters>,
+        limit: usize,
+    ) -> anyhow::Result<Vec<SearchResult>> {
+        let trending = self
+            .trending(
+                TrendingList::Now,
+                &TrendingScope::Global,
+                None,
+                filters,
+                limit,
+            )
+            .await?;
+
+        Ok(trending
+            .into_iter()
+            .map(TrendingContent::into_search_result)
+            .collect())
+    }
+}
+
+/// Append genre and filter restrictions as bound predicates
+fn push_restrictions(
+    query: &mut QueryBuilder<'_, Postgres>,
+    genre: Option<&str>,
+    filters: Option<&SearchFilters>,
+) {
+    if let Some(genre) = genre {
+        query
+            .push(" AND ")
+            .push_bind(genre.to_string())
+            .push(" = ANY(genres)");
+    }
+    if let Some(filters) = filters {
+        filters.push_sql_conditions(query);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(popularity_score: f32) -> ContentSummary {
+        ContentSummary {
+            id: Uuid::new_v4(),
+            title: "Title".to_string(),
+            overview: String::new(),
+            release_year: 2024,
+            genres: vec!["drama".to_string()],
+            platforms: vec![],
+            popularity_score,
+        }
+    }
+
+    #[test]
+    fn test_search_result_prefers_trending_score() {
+        let content = summary(0.4);
+        let item = TrendingItem {
+            content_id: content.id,
+            score: 12.5,
+            views: 3.0,
+            starts: 2.0,
+            completions: 1.0,
+            velocity: 1.2,
+            spiking: false,
+        };
+
+        let trending = TrendingContent {
+            content: content.clone(),
+            trending: Some(item),
+        }
+        .into_search_result();
+        assert_eq!(trending.relevance_score, 12.5);
+        assert_eq!(trending.match_reasons, vec!["trending".to_string()]);
+
+        let popular = TrendingContent {
+            content,
+            trending: None,
+        }
+        .into_search_result();
+        assert_eq!(popular.relevance_score, 0.4);
+    }
+}
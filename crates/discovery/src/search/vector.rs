@@ -184,6 +184,17 @@ impl VectorSearch {
             ));
         }
 
+        // Runtime cap
+        if let Some(max_runtime) = filters.max_runtime_minutes {
+            conditions.push(Condition::range(
+                "runtime_minutes",
+                Range {
+                    lte: Some(max_runtime as f64),
+                    ..Default::default()
+                },
+            ));
+        }
+
         Filter::must(conditions)
     }
 
@@ -68,6 +68,7 @@ pub async fn get_analytics(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use sqlx::Executor;
 
     #[test]
     fn test_default_period() {
@@ -142,20 +143,25 @@ mod tests {
             .await
             .expect("Failed to connect to test database");
 
-        sqlx::query(include_str!(
-            "../../../migrations/20251206_search_analytics.sql"
-        ))
-        .execute(&pool)
-        .await
-        .expect("Failed to run migrations");
+        // Multi-statement scripts run over the simple query protocol
+        for migration in [
+            include_str!("../../../migrations/20251206_search_analytics.sql"),
+            include_str!("../../../../../migrations/021_search_recovery_events.up.sql"),
+        ] {
+            pool.execute(migration)
+                .await
+                .expect("Failed to run migrations");
+        }
 
         pool
     }
 
     async fn cleanup_test_db(pool: &PgPool) {
-        sqlx::query("TRUNCATE search_events, search_clicks, popular_searches CASCADE")
-            .execute(pool)
-            .await
-            .expect("Failed to cleanup test database");
+        sqlx::query(
+            "TRUNCATE search_events, search_clicks, popular_searches, search_recovery_events CASCADE",
+        )
+        .execute(pool)
+        .await
+        .expect("Failed to cleanup test database");
     }
 }
@@ -0,0 +1,174 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use std::sync::Arc;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use super::ranking::extract_admin_user_id;
+use super::search::ErrorResponse;
+use crate::search::{EditorialRuleInput, HybridSearchService};
+
+/// GET /api/v1/admin/search/editorial/rules - List all editorial rules
+///
+/// Includes disabled and expired rules, ordered by priority.
+pub async fn list_editorial_rules(
+    search_service: web::Data<Arc<HybridSearchService>>,
+    req: HttpRequest,
+) -> impl Responder {
+    let admin_id = match extract_admin_user_id(&req) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    info!(admin_id = %admin_id, "Admin requested editorial rules list");
+
+    match search_service.editorial_rules().list().await {
+        Ok(rules) => HttpResponse::Ok().json(rules),
+        Err(e) => {
+            error!(error = %e, "Failed to list editorial rules");
+            HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Failed to list editorial rules: {}", e),
+            })
+        }
+    }
+}
+
+/// GET /api/v1/admin/search/editorial/rules/{id} - Get an editorial rule
+pub async fn get_editorial_rule(
+    search_service: web::Data<Arc<HybridSearchService>>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> impl Responder {
+    let admin_id = match extract_admin_user_id(&req) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    let id = path.into_inner();
+    info!(admin_id = %admin_id, rule_id = %id, "Admin requested editorial rule");
+
+    match search_service.editorial_rules().get(id).await {
+        Ok(Some(rule)) => HttpResponse::Ok().json(rule),
+        Ok(None) => HttpResponse::NotFound().json(ErrorResponse {
+            error: format!("Editorial rule {} not found", id),
+        }),
+        Err(e) => {
+            error!(error = %e, "Failed to get editorial rule");
+            HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Failed to get editorial rule: {}", e),
+            })
+        }
+    }
+}
+
+/// POST /api/v1/admin/search/editorial/rules - Create an editorial rule
+///
+/// Request body:
+/// - name: Rule name (required)
+/// - action: `{"type": "pin" | "bury" | "hide"}` or `{"type": "boost", "factor": 1.5}`
+/// - content_ids: Affected content, in pin order for pin rules
+/// - condition: Optional `match_type` (any, exact, phrase), `queries` and `intent_terms`
+/// - regions: Optional ISO 3166-1 alpha-2 regions; empty means everywhere
+/// - starts_at / ends_at: Optional scheduling window
+/// - enabled: Default true
+/// - priority: Higher priority rules pin first (default: 0)
+/// - reason: Editorial or legal justification (required for hide rules)
+pub async fn create_editorial_rule(
+    search_service: web::Data<Arc<HybridSearchService>>,
+    req: HttpRequest,
+    body: web::Json<EditorialRuleInput>,
+) -> impl Responder {
+    let admin_id = match extract_admin_user_id(&req) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    let input = body.into_inner();
+    if let Err(e) = input.validate() {
+        warn!(error = %e, "Invalid editorial rule");
+        return HttpResponse::BadRequest().json(ErrorResponse {
+            error: format!("Invalid rule: {}", e),
+        });
+    }
+
+    match search_service
+        .editorial_rules()
+        .create(input, admin_id)
+        .await
+    {
+        Ok(rule) => HttpResponse::Created().json(rule),
+        Err(e) => {
+            error!(error = %e, "Failed to create editorial rule");
+            HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Failed to create editorial rule: {}", e),
+            })
+        }
+    }
+}
+
+/// PUT /api/v1/admin/search/editorial/rules/{id} - Replace an editorial rule
+///
+/// Accepts the same body as rule creation.
+pub async fn update_editorial_rule(
+    search_service: web::Data<Arc<HybridSearchService>>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: web::Json<EditorialRuleInput>,
+) -> impl Responder {
+    let admin_id = match extract_admin_user_id(&req) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    let id = path.into_inner();
+    let input = body.into_inner();
+    if let Err(e) = input.validate() {
+        warn!(error = %e, rule_id = %id, "Invalid editorial rule");
+        return HttpResponse::BadRequest().json(ErrorResponse {
+            error: format!("Invalid rule: {}", e),
+        });
+    }
+
+    match search_service
+        .editorial_rules()
+        .update(id, input, admin_id)
+        .await
+    {
+        Ok(Some(rule)) => HttpResponse::Ok().json(rule),
+        Ok(None) => HttpResponse::NotFound().json(ErrorResponse {
+            error: format!("Editorial rule {} not found", id),
+        }),
+        Err(e) => {
+            error!(error = %e, "Failed to update editorial rule");
+            HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Failed to update editorial rule: {}", e),
+            })
+        }
+    }
+}
+
+/// DELETE /api/v1/admin/search/editorial/rules/{id} - Delete an editorial rule
+pub async fn delete_editorial_rule(
+    search_service: web::Data<Arc<HybridSearchService>>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+) -> impl Responder {
+    let admin_id = match extract_admin_user_id(&req) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    let id = path.into_inner();
+
+    match search_service.editorial_rules().delete(id, admin_id).await {
+        Ok(true) => HttpResponse::NoContent().finish(),
+        Ok(false) => HttpResponse::NotFound().json(ErrorResponse {
+            error: format!("Editorial rule {} not found", id),
+        }),
+        Err(e) => {
+            error!(error = %e, "Failed to delete editorial rule");
+            HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Failed to delete editorial rule: {}", e),
+            })
+        }
+    }
+}
@@ -1,12 +1,24 @@
 pub mod analytics;
+pub mod editorial;
 pub mod quality;
 pub mod ranking;
 pub mod search;
+pub mod session;
+pub mod trending;
 
 pub use analytics::get_analytics;
-pub use quality::get_quality_report;
+pub use editorial::{
+    create_editorial_rule, delete_editorial_rule, get_editorial_rule, list_editorial_rules,
+    update_editorial_rule,
+};
+pub use quality::{
+    capture_quality_snapshot, enqueue_remediation, get_quality_report, get_quality_trend,
+    get_remediation, list_remediation, run_remediation, update_remediation_status,
+};
 pub use ranking::{
     delete_ranking_variant, get_ranking_config, get_ranking_config_history, get_ranking_variant,
     list_ranking_variants, update_ranking_config, update_ranking_variant,
 };
-pub use search::{autocomplete, execute_search};
+pub use search::{autocomplete, execute_search, stream_search};
+pub use session::{delete_search_session, get_search_session, session_search};
+pub use trending::{get_rising, get_trending, get_trending_by_genre, record_trending_events};
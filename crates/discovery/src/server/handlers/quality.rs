@@ -1,13 +1,20 @@
-use actix_web::{web, HttpResponse, Responder};
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use chrono::{Duration, Utc};
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use tracing::{error, info};
+use uuid::Uuid;
 
+use media_gateway_ingestion::aggregator::TMDbClient;
 use media_gateway_ingestion::normalizer::CanonicalContent;
+use media_gateway_ingestion::quality::export;
 use media_gateway_ingestion::{
-    ContentRepository, PostgresContentRepository,
-    QualityScorer,
+    ContentRepository, ExportFormat, PostgresContentRepository, QualityScorer, QualityTrendTracker,
+    RemediationQueue, RemediationStatus, RemediationTask, RemediationWorker,
 };
 
+use super::ranking::extract_admin_user_id;
+
 /// Query parameters for quality report endpoint
 #[derive(Debug, Deserialize)]
 pub struct QualityReportQuery {
@@ -17,6 +24,9 @@ pub struct QualityReportQuery {
     /// Maximum number of low-quality items to return (default: 100)
     #[serde(default = "default_limit")]
     pub limit: i64,
+    /// Output format: json, csv or jsonl (default: json)
+    #[serde(default)]
+    pub format: ExportFormat,
 }
 
 fn default_threshold() -> f32 {
@@ -54,6 +64,8 @@ pub struct LowQualityItemResponse {
 /// Query parameters:
 /// - threshold: Quality score threshold (0.0-1.0, default: 0.6)
 /// - limit: Maximum number of items to return (default: 100)
+/// - format: json, csv or jsonl (default: json); csv and jsonl are returned
+///   as file downloads for editorial teams
 pub async fn get_quality_report(
     pool: web::Data<sqlx::PgPool>,
     params: web::Query<QualityReportQuery>,
@@ -103,11 +115,381 @@ pub async fn get_quality_report(
         params.threshold
     );
 
-    HttpResponse::Ok().json(QualityReportResponse {
-        total_low_quality: response_items.len(),
-        threshold: params.threshold,
-        low_quality_items: response_items,
-    })
+    match params.format {
+        ExportFormat::Json => HttpResponse::Ok().json(QualityReportResponse {
+            total_low_quality: response_items.len(),
+            threshold: params.threshold,
+            low_quality_items: response_items,
+        }),
+        ExportFormat::Csv => {
+            let body = export::to_csv(
+                &[
+                    "id",
+                    "title",
+                    "quality_score",
+                    "missing_fields",
+                    "platform",
+                    "content_type",
+                ],
+                response_items.into_iter().map(|item| {
+                    vec![
+                        item.id,
+                        item.title,
+                        format!("{:.3}", item.quality_score),
+                        item.missing_fields.join(";"),
+                        item.platform,
+                        item.content_type,
+                    ]
+                }),
+            );
+            export_response(ExportFormat::Csv, "quality_report", body)
+        }
+        ExportFormat::Jsonl => match export::to_jsonl(&response_items) {
+            Ok(body) => export_response(ExportFormat::Jsonl, "quality_report", body),
+            Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to export quality report: {}", e)
+            })),
+        },
+    }
+}
+
+/// Request body for queueing low-quality content for remediation
+#[derive(Debug, Deserialize)]
+pub struct EnqueueRemediationRequest {
+    #[serde(default = "default_threshold")]
+    pub threshold: f32,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+/// POST /api/v1/quality/remediation - Queue low-quality content for enrichment
+///
+/// Items already tracked keep their current remediation state. Requires the
+/// admin role.
+pub async fn enqueue_remediation(
+    pool: web::Data<sqlx::PgPool>,
+    req: HttpRequest,
+    body: web::Json<EnqueueRemediationRequest>,
+) -> impl Responder {
+    let admin_id = match extract_admin_user_id(&req) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    info!(admin_id = %admin_id, threshold = body.threshold, "Admin queued content for remediation");
+
+    let queue = RemediationQueue::new(pool.get_ref().clone());
+
+    match queue
+        .enqueue_below_threshold(body.threshold, body.limit)
+        .await
+    {
+        Ok(queued) => HttpResponse::Ok().json(serde_json::json!({
+            "queued": queued,
+            "threshold": body.threshold,
+        })),
+        Err(e) => {
+            error!("Failed to queue content for remediation: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to queue content for remediation: {}", e)
+            }))
+        }
+    }
+}
+
+/// Query parameters for listing remediation records
+#[derive(Debug, Deserialize)]
+pub struct RemediationListQuery {
+    /// Filter by status (queued, in_progress, enriched, unresolved, dismissed)
+    pub status: Option<RemediationStatus>,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    #[serde(default)]
+    pub format: ExportFormat,
+}
+
+/// Response for remediation list endpoint
+#[derive(Debug, Serialize)]
+pub struct RemediationListResponse {
+    /// Record count per status across all tracked content
+    pub status_counts: std::collections::HashMap<String, i64>,
+    pub tasks: Vec<RemediationTask>,
+}
+
+/// GET /api/v1/quality/remediation - List remediation state
+///
+/// Query parameters:
+/// - status: Optional status filter
+/// - limit: Maximum number of records (default: 100)
+/// - format: json, csv or jsonl (default: json)
+pub async fn list_remediation(
+    pool: web::Data<sqlx::PgPool>,
+    params: web::Query<RemediationListQuery>,
+) -> impl Responder {
+    let queue = RemediationQueue::new(pool.get_ref().clone());
+
+    let tasks = match queue.list(params.status, params.limit).await {
+        Ok(tasks) => tasks,
+        Err(e) => {
+            error!("Failed to list remediation records: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to list remediation records: {}", e)
+            }));
+        }
+    };
+
+    match params.format {
+        ExportFormat::Json => {
+            let status_counts = queue
+                .status_counts()
+                .await
+                .map(|counts| counts.into_iter().collect())
+                .unwrap_or_default();
+            HttpResponse::Ok().json(RemediationListResponse {
+                status_counts,
+                tasks,
+            })
+        }
+        ExportFormat::Csv => {
+            let body = export::to_csv(
+                &[
+                    "content_id",
+                    "title",
+                    "status",
+                    "quality_score_before",
+                    "quality_score_after",
+                    "attempts",
+                    "missing_fields",
+                    "filled_fields",
+                    "sources",
+                    "last_error",
+                ],
+                tasks.into_iter().map(|task| {
+                    vec![
+                        task.content_id.to_string(),
+                        task.title,
+                        task.status.as_str().to_string(),
+                        format!("{:.3}", task.quality_score_before),
+                        task.quality_score_after
+                            .map(|s| format!("{:.3}", s))
+                            .unwrap_or_default(),
+                        task.attempts.to_string(),
+                        task.missing_fields.join(";"),
+                        task.filled_fields.join(";"),
+                        task.sources.join(";"),
+                        task.last_error.unwrap_or_default(),
+                    ]
+                }),
+            );
+            export_response(ExportFormat::Csv, "quality_remediation", body)
+        }
+        ExportFormat::Jsonl => match export::to_jsonl(&tasks) {
+            Ok(body) => export_response(ExportFormat::Jsonl, "quality_remediation", body),
+            Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to export remediation records: {}", e)
+            })),
+        },
+    }
+}
+
+/// GET /api/v1/quality/remediation/{content_id} - Remediation state for one item
+pub async fn get_remediation(
+    pool: web::Data<sqlx::PgPool>,
+    path: web::Path<Uuid>,
+) -> impl Responder {
+    let content_id = path.into_inner();
+    let queue = RemediationQueue::new(pool.get_ref().clone());
+
+    match queue.get(content_id).await {
+        Ok(Some(task)) => HttpResponse::Ok().json(task),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": format!("Content {} is not tracked for remediation", content_id)
+        })),
+        Err(e) => {
+            error!("Failed to fetch remediation record: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to fetch remediation record: {}", e)
+            }))
+        }
+    }
+}
+
+/// Request body for manual remediation status changes
+#[derive(Debug, Deserialize)]
+pub struct UpdateRemediationStatusRequest {
+    pub status: RemediationStatus,
+}
+
+/// PUT /api/v1/quality/remediation/{content_id}/status - Dismiss or requeue an item
+///
+/// Only `dismissed` and `queued` may be set manually; the other states are
+/// owned by the remediation worker. Requires the admin role.
+pub async fn update_remediation_status(
+    pool: web::Data<sqlx::PgPool>,
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: web::Json<UpdateRemediationStatusRequest>,
+) -> impl Responder {
+    let admin_id = match extract_admin_user_id(&req) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+    let content_id = path.into_inner();
+
+    if !matches!(
+        body.status,
+        RemediationStatus::Dismissed | RemediationStatus::Queued
+    ) {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Status can only be set to 'dismissed' or 'queued'"
+        }));
+    }
+
+    let queue = RemediationQueue::new(pool.get_ref().clone());
+    match queue.set_status(content_id, body.status).await {
+        Ok(true) => {
+            info!(
+                admin_id = %admin_id,
+                "Remediation status for {} set to {}",
+                content_id,
+                body.status.as_str()
+            );
+            HttpResponse::NoContent().finish()
+        }
+        Ok(false) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": format!("Content {} is not tracked for remediation", content_id)
+        })),
+        Err(e) => {
+            error!("Failed to update remediation status: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to update remediation status: {}", e)
+            }))
+        }
+    }
+}
+
+/// Request body for running the remediation worker
+#[derive(Debug, Deserialize)]
+pub struct RunRemediationRequest {
+    /// Maximum number of queued items to process (default: 50)
+    #[serde(default = "default_run_limit")]
+    pub limit: i64,
+}
+
+fn default_run_limit() -> i64 {
+    50
+}
+
+/// POST /api/v1/quality/remediation/run - Process a batch of queued items
+///
+/// Enriches from entity-resolved siblings, falling back to TMDb when
+/// `TMDB_API_KEY` is configured. Requires the admin role.
+pub async fn run_remediation(
+    pool: web::Data<sqlx::PgPool>,
+    req: HttpRequest,
+    body: web::Json<RunRemediationRequest>,
+) -> impl Responder {
+    let admin_id = match extract_admin_user_id(&req) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    info!(admin_id = %admin_id, limit = body.limit, "Admin started remediation batch");
+
+    let queue = Arc::new(RemediationQueue::new(pool.get_ref().clone()));
+    let mut worker = RemediationWorker::new(queue);
+    if let Ok(api_key) = std::env::var("TMDB_API_KEY") {
+        worker = worker.with_tmdb(Arc::new(TMDbClient::new(api_key)));
+    }
+
+    match worker.run_batch(body.limit).await {
+        Ok(report) => HttpResponse::Ok().json(report),
+        Err(e) => {
+            error!("Remediation batch failed: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Remediation batch failed: {}", e)
+            }))
+        }
+    }
+}
+
+/// Query parameters for quality trend endpoint
+#[derive(Debug, Deserialize)]
+pub struct QualityTrendQuery {
+    /// Number of days of history (default: 30)
+    #[serde(default = "default_trend_days")]
+    pub days: i64,
+}
+
+fn default_trend_days() -> i64 {
+    30
+}
+
+/// GET /api/v1/quality/trend - Quality score distribution over time
+pub async fn get_quality_trend(
+    pool: web::Data<sqlx::PgPool>,
+    params: web::Query<QualityTrendQuery>,
+) -> impl Responder {
+    let tracker = QualityTrendTracker::new(pool.get_ref().clone());
+    let since = Utc::now() - Duration::days(params.days.max(1));
+
+    match tracker.trend(since).await {
+        Ok(snapshots) => HttpResponse::Ok().json(serde_json::json!({
+            "days": params.days,
+            "snapshots": snapshots,
+        })),
+        Err(e) => {
+            error!("Failed to fetch quality trend: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to fetch quality trend: {}", e)
+            }))
+        }
+    }
+}
+
+/// POST /api/v1/quality/trend/snapshot - Capture the current score distribution
+///
+/// Intended to be called on a schedule (e.g. daily) to build the trend.
+/// Requires the admin role.
+pub async fn capture_quality_snapshot(
+    pool: web::Data<sqlx::PgPool>,
+    req: HttpRequest,
+    params: web::Query<QualityReportQuery>,
+) -> impl Responder {
+    let admin_id = match extract_admin_user_id(&req) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    info!(admin_id = %admin_id, "Admin captured quality snapshot");
+
+    let tracker = QualityTrendTracker::new(pool.get_ref().clone());
+
+    match tracker.capture(params.threshold).await {
+        Ok(snapshot) => HttpResponse::Created().json(snapshot),
+        Err(e) => {
+            error!("Failed to capture quality snapshot: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to capture quality snapshot: {}", e)
+            }))
+        }
+    }
+}
+
+/// Build a file download response for an export
+fn export_response(format: ExportFormat, name: &str, body: String) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type(format.content_type())
+        .insert_header((
+            "Content-Disposition",
+            format!(
+                "attachment; filename=\"{}_{}.{}\"",
+                name,
+                Utc::now().format("%Y%m%d"),
+                format.extension()
+            ),
+        ))
+        .body(body)
 }
 
 /// Identify missing fields for a content item
@@ -232,4 +614,28 @@ mod tests {
         assert_eq!(default_threshold(), 0.6);
         assert_eq!(default_limit(), 100);
     }
+
+    #[test]
+    fn test_report_query_format() {
+        let params = web::Query::<QualityReportQuery>::from_query("threshold=0.5&format=csv")
+            .unwrap()
+            .into_inner();
+        assert_eq!(params.format, ExportFormat::Csv);
+        assert_eq!(params.limit, 100);
+
+        let params = web::Query::<QualityReportQuery>::from_query("")
+            .unwrap()
+            .into_inner();
+        assert_eq!(params.format, ExportFormat::Json);
+    }
+
+    #[test]
+    fn test_remediation_list_query_status() {
+        let params =
+            web::Query::<RemediationListQuery>::from_query("status=unresolved&format=jsonl")
+                .unwrap()
+                .into_inner();
+        assert_eq!(params.status, Some(RemediationStatus::Unresolved));
+        assert_eq!(params.format, ExportFormat::Jsonl);
+    }
 }
@@ -9,7 +9,7 @@ use crate::search::ranking::{
 };
 
 /// Extract admin user ID from JWT token
-fn extract_admin_user_id(req: &HttpRequest) -> Result<Uuid, HttpResponse> {
+pub(crate) fn extract_admin_user_id(req: &HttpRequest) -> Result<Uuid, HttpResponse> {
     let auth_header = req
         .headers()
         .get("Authorization")
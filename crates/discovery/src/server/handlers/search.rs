@@ -1,9 +1,12 @@
 use actix_web::{web, HttpResponse, Responder};
+use futures::stream;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use tokio::sync::mpsc;
 use tracing::{error, info};
 use uuid::Uuid;
 
+use crate::search::stream::STREAM_CHANNEL_CAPACITY;
 use crate::search::{HybridSearchService, SearchFilters, SearchRequest};
 
 /// Search request body for POST /api/v1/search
@@ -18,6 +21,9 @@ pub struct SearchRequestBody {
     pub page_size: u32,
     pub user_id: Option<Uuid>,
     pub experiment_variant: Option<String>,
+    /// ISO 3166-1 alpha-2 region for region-scoped editorial rules
+    #[serde(default)]
+    pub region: Option<String>,
 }
 
 fn default_page() -> u32 {
@@ -46,6 +52,7 @@ pub struct ErrorResponse {
 /// - page_size: Results per page (default: 20)
 /// - user_id: Optional user ID for personalized results
 /// - experiment_variant: Optional A/B test variant name
+/// - region: Optional ISO 3166-1 alpha-2 region of the requester
 pub async fn execute_search(
     search_service: web::Data<Arc<HybridSearchService>>,
     body: web::Json<SearchRequestBody>,
@@ -64,6 +71,7 @@ pub async fn execute_search(
         page_size: body.page_size,
         user_id: body.user_id,
         experiment_variant: body.experiment_variant.clone(),
+        region: body.region.clone(),
     };
 
     match search_service.search(request).await {
@@ -77,6 +85,52 @@ pub async fn execute_search(
     }
 }
 
+/// POST /api/v1/search/stream - Execute hybrid search as Server-Sent Events
+///
+/// Accepts the same body as `POST /api/v1/search` and responds with a
+/// `text/event-stream` of typed events: `intent`, `keyword`, `fused`,
+/// `personalized` (when user_id is given), `editorial` (when editorial rules
+/// matched), `recovered` (when the zero-result cascade ran), `facets` and
+/// finally `done`. Failures are reported as a
+/// terminal `error` event since the response status has already been sent.
+pub async fn stream_search(
+    search_service: web::Data<Arc<HybridSearchService>>,
+    body: web::Json<SearchRequestBody>,
+) -> impl Responder {
+    info!(query = %body.query, page = %body.page, "Executing streaming search request");
+
+    let body = body.into_inner();
+    let request = SearchRequest {
+        query: body.query,
+        filters: body.filters,
+        page: body.page,
+        page_size: body.page_size,
+        user_id: body.user_id,
+        experiment_variant: body.experiment_variant,
+        region: body.region,
+    };
+
+    let (tx, rx) = mpsc::channel(STREAM_CHANNEL_CAPACITY);
+    let service = search_service.get_ref().clone();
+    tokio::spawn(async move {
+        service.search_streaming(request, tx).await;
+    });
+
+    let events = stream::unfold(rx, |mut rx| async move {
+        let event = rx.recv().await?;
+        Some((
+            Ok::<_, actix_web::Error>(web::Bytes::from(event.to_sse())),
+            rx,
+        ))
+    });
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(("Cache-Control", "no-cache"))
+        .insert_header(("X-Accel-Buffering", "no"))
+        .streaming(events)
+}
+
 /// Autocomplete query parameters
 #[derive(Debug, Deserialize)]
 pub struct AutocompleteQuery {
@@ -0,0 +1,279 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use super::search::ErrorResponse;
+use crate::search::{HybridSearchService, SearchFilters, SearchRequest, SearchSession};
+
+/// Session search request body for POST /api/v1/search/session
+#[derive(Debug, Deserialize)]
+pub struct SessionSearchRequestBody {
+    /// Existing session to continue; omit to start a new session
+    #[serde(default)]
+    pub session_id: Option<Uuid>,
+    /// Raw user utterance (initial query or follow-up refinement)
+    pub query: String,
+    #[serde(default)]
+    pub filters: Option<SearchFilters>,
+    #[serde(default = "default_page")]
+    pub page: u32,
+    #[serde(default = "default_page_size")]
+    pub page_size: u32,
+    pub experiment_variant: Option<String>,
+    /// ISO 3166-1 alpha-2 region for region-scoped editorial rules
+    #[serde(default)]
+    pub region: Option<String>,
+}
+
+fn default_page() -> u32 {
+    1
+}
+
+fn default_page_size() -> u32 {
+    20
+}
+
+/// POST /api/v1/search/session - Execute a conversational search turn
+///
+/// Starts a new session when `session_id` is omitted. Follow-up utterances
+/// such as "only on Netflix", "without horror", "not that one" or "more like
+/// the second one" refine the previous turn instead of starting over. The
+/// session belongs to the bearer token's user, and sessions owned by a user
+/// can only be continued with that user's token.
+///
+/// Request body:
+/// - session_id: Optional session to continue
+/// - query: User utterance (required)
+/// - filters: Optional explicit filters merged into the session filters
+/// - page: Page number (default: 1)
+/// - page_size: Results per page (default: 20)
+/// - experiment_variant: Optional A/B test variant name
+/// - region: Optional ISO 3166-1 alpha-2 region of the requester
+pub async fn session_search(
+    req: HttpRequest,
+    search_service: web::Data<Arc<HybridSearchService>>,
+    body: web::Json<SessionSearchRequestBody>,
+) -> impl Responder {
+    let caller = match extract_user_id(&req) {
+        Ok(caller) => caller,
+        Err(response) => return response,
+    };
+
+    info!(
+        session_id = ?body.session_id,
+        query = %body.query,
+        "Executing session search turn"
+    );
+
+    let body = body.into_inner();
+    let request = SearchRequest {
+        query: body.query,
+        filters: body.filters,
+        page: body.page,
+        page_size: body.page_size,
+        user_id: caller,
+        experiment_variant: body.experiment_variant,
+        region: body.region,
+    };
+
+    match search_service
+        .search_in_session(body.session_id, request)
+        .await
+    {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) if e.to_string().starts_with("Search session not found") => HttpResponse::NotFound()
+            .json(ErrorResponse {
+                error: e.to_string(),
+            }),
+        Err(e) => {
+            error!(error = %e, "Session search request failed");
+            HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Search failed: {}", e),
+            })
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Claims {
+    sub: String,
+    exp: usize,
+}
+
+/// Extract the caller's user ID from an optional JWT bearer token
+///
+/// Returns `None` without an Authorization header so anonymous sessions stay
+/// reachable; a header that is present must carry a valid token.
+fn extract_user_id(req: &HttpRequest) -> Result<Option<Uuid>, HttpResponse> {
+    let Some(auth_header) = req.headers().get("Authorization") else {
+        return Ok(None);
+    };
+    let auth_header = auth_header.to_str().map_err(|_| {
+        HttpResponse::Unauthorized().json(ErrorResponse {
+            error: "Invalid Authorization header".to_string(),
+        })
+    })?;
+
+    if !auth_header.starts_with("Bearer ") {
+        return Err(HttpResponse::Unauthorized().json(ErrorResponse {
+            error: "Invalid Authorization format".to_string(),
+        }));
+    }
+
+    let token = &auth_header[7..];
+
+    let secret = std::env::var("JWT_SECRET").unwrap_or_else(|_| "default-secret-key".to_string());
+
+    let token_data = jsonwebtoken::decode::<Claims>(
+        token,
+        &jsonwebtoken::DecodingKey::from_secret(secret.as_bytes()),
+        &jsonwebtoken::Validation::default(),
+    )
+    .map_err(|e| {
+        warn!(error = %e, "Failed to decode JWT token");
+        HttpResponse::Unauthorized().json(ErrorResponse {
+            error: "Invalid or expired token".to_string(),
+        })
+    })?;
+
+    Uuid::parse_str(&token_data.claims.sub)
+        .map(Some)
+        .map_err(|_| {
+            HttpResponse::InternalServerError().json(ErrorResponse {
+                error: "Invalid user ID in token".to_string(),
+            })
+        })
+}
+
+/// Whether a caller may read or end a session
+///
+/// Sessions started by a signed-in user belong to that user; anonymous
+/// sessions are reachable by anyone holding the session ID.
+fn is_session_owner(session: &SearchSession, caller: Option<Uuid>) -> bool {
+    session.user_id.is_none() || session.user_id == caller
+}
+
+/// GET /api/v1/search/session/{session_id} - Get session state
+///
+/// Returns the accumulated filters, exclusions and turn history. Sessions
+/// owned by a user require that user's bearer token.
+pub async fn get_search_session(
+    req: HttpRequest,
+    search_service: web::Data<Arc<HybridSearchService>>,
+    path: web::Path<Uuid>,
+) -> impl Responder {
+    let session_id = path.into_inner();
+    let caller = match extract_user_id(&req) {
+        Ok(caller) => caller,
+        Err(response) => return response,
+    };
+
+    match search_service.session_store().load(session_id).await {
+        Ok(Some(session)) if !is_session_owner(&session, caller) => {
+            HttpResponse::Forbidden().json(ErrorResponse {
+                error: "Search session belongs to another user".to_string(),
+            })
+        }
+        Ok(Some(session)) => HttpResponse::Ok().json(session),
+        Ok(None) => HttpResponse::NotFound().json(ErrorResponse {
+            error: format!("Search session not found: {}", session_id),
+        }),
+        Err(e) => {
+            error!(error = %e, session_id = %session_id, "Failed to load search session");
+            HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Failed to load session: {}", e),
+            })
+        }
+    }
+}
+
+/// DELETE /api/v1/search/session/{session_id} - End a session
+///
+/// Sessions owned by a user require that user's bearer token.
+pub async fn delete_search_session(
+    req: HttpRequest,
+    search_service: web::Data<Arc<HybridSearchService>>,
+    path: web::Path<Uuid>,
+) -> impl Responder {
+    let session_id = path.into_inner();
+    let caller = match extract_user_id(&req) {
+        Ok(caller) => caller,
+        Err(response) => return response,
+    };
+
+    let store = search_service.session_store();
+    match store.load(session_id).await {
+        Ok(Some(session)) if !is_session_owner(&session, caller) => {
+            return HttpResponse::Forbidden().json(ErrorResponse {
+                error: "Search session belongs to another user".to_string(),
+            });
+        }
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return HttpResponse::NotFound().json(ErrorResponse {
+                error: format!("Search session not found: {}", session_id),
+            });
+        }
+        Err(e) => {
+            error!(error = %e, session_id = %session_id, "Failed to load search session");
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Failed to load session: {}", e),
+            });
+        }
+    }
+
+    match store.delete(session_id).await {
+        Ok(true) => HttpResponse::NoContent().finish(),
+        Ok(false) => HttpResponse::NotFound().json(ErrorResponse {
+            error: format!("Search session not found: {}", session_id),
+        }),
+        Err(e) => {
+            error!(error = %e, session_id = %session_id, "Failed to delete search session");
+            HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Failed to delete session: {}", e),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_request_deserialization() {
+        let json = r#"{"query": "space movies"}"#;
+
+        let request: SessionSearchRequestBody = serde_json::from_str(json).unwrap();
+        assert!(request.session_id.is_none());
+        assert_eq!(request.page, 1);
+        assert_eq!(request.page_size, 20);
+    }
+
+    #[test]
+    fn test_session_follow_up_deserialization() {
+        let json = r#"{
+            "session_id": "00000000-0000-0000-0000-000000000000",
+            "query": "only on netflix"
+        }"#;
+
+        let request: SessionSearchRequestBody = serde_json::from_str(json).unwrap();
+        assert_eq!(request.session_id, Some(Uuid::nil()));
+        assert_eq!(request.query, "only on netflix");
+    }
+
+    #[test]
+    fn test_session_ownership() {
+        let owner = Uuid::new_v4();
+        let owned = SearchSession::new(Some(owner));
+        assert!(is_session_owner(&owned, Some(owner)));
+        assert!(!is_session_owner(&owned, Some(Uuid::new_v4())));
+        assert!(!is_session_owner(&owned, None));
+
+        let anonymous = SearchSession::new(None);
+        assert!(is_session_owner(&anonymous, None));
+        assert!(is_session_owner(&anonymous, Some(owner)));
+    }
+}
@@ -0,0 +1,249 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use media_gateway_core::{TrendingScope, UserActivityEvent};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::{error, info, warn};
+
+use super::ranking::extract_admin_user_id;
+use crate::search::{HybridSearchService, TrendingContent, TrendingList};
+
+/// Largest page a trending list returns
+const MAX_TRENDING_LIMIT: usize = 100;
+
+/// Query parameters for trending endpoints
+#[derive(Debug, Deserialize)]
+pub struct TrendingQuery {
+    /// ISO 3166-1 alpha-2 region; takes precedence over platform
+    #[serde(default)]
+    pub region: Option<String>,
+    /// Platform identifier (e.g. "netflix")
+    #[serde(default)]
+    pub platform: Option<String>,
+    /// Restrict to a genre
+    #[serde(default)]
+    pub genre: Option<String>,
+    /// Maximum number of items (default: 20, max: 100)
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+    /// Only return items whose velocity is a spike (rising list only)
+    #[serde(default)]
+    pub spiking_only: bool,
+}
+
+fn default_limit() -> usize {
+    20
+}
+
+impl TrendingQuery {
+    fn scope(&self) -> TrendingScope {
+        TrendingScope::from_params(self.region.as_deref(), self.platform.as_deref())
+    }
+
+    fn limit(&self) -> usize {
+        self.limit.clamp(1, MAX_TRENDING_LIMIT)
+    }
+}
+
+/// Trending list response
+#[derive(Debug, Serialize)]
+pub struct TrendingResponse {
+    pub list: TrendingList,
+    pub scope: TrendingScope,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub genre: Option<String>,
+    pub items: Vec<TrendingContent>,
+    pub total: usize,
+}
+
+/// Result of recording activity events
+#[derive(Debug, Serialize)]
+pub struct RecordEventsResponse {
+    pub recorded: usize,
+    pub skipped: usize,
+    pub failed: usize,
+}
+
+/// Error response
+#[derive(Debug, Serialize)]
+pub struct ErrorResponse {
+    pub error: String,
+}
+
+async fn trending_list(
+    search_service: &HybridSearchService,
+    list: TrendingList,
+    genre: Option<String>,
+    params: &TrendingQuery,
+) -> HttpResponse {
+    let scope = params.scope();
+    info!(
+        list = ?list,
+        scope = ?scope,
+        genre = ?genre,
+        limit = params.limit(),
+        "Fetching trending content"
+    );
+
+    match search_service
+        .trending(list, &scope, genre.as_deref(), None, params.limit())
+        .await
+    {
+        Ok(mut items) => {
+            if list == TrendingList::Rising && params.spiking_only {
+                items.retain(|item| item.trending.as_ref().is_some_and(|t| t.spiking));
+            }
+            HttpResponse::Ok().json(TrendingResponse {
+                list,
+                scope,
+                genre,
+                total: items.len(),
+                items,
+            })
+        }
+        Err(e) => {
+            error!(error = %e, "Failed to fetch trending content");
+            HttpResponse::InternalServerError().json(ErrorResponse {
+                error: format!("Failed to fetch trending content: {}", e),
+            })
+        }
+    }
+}
+
+/// GET /api/v1/content/trending - Content trending right now
+///
+/// Ranked by exponentially decayed views, playback starts and completions.
+/// Falls back to catalog popularity when no engagement has been recorded.
+///
+/// Query parameters:
+/// - region: ISO 3166-1 alpha-2 region (takes precedence over platform)
+/// - platform: Platform identifier
+/// - genre: Restrict to a genre
+/// - limit: Maximum number of items (default: 20, max: 100)
+pub async fn get_trending(
+    search_service: web::Data<Arc<HybridSearchService>>,
+    params: web::Query<TrendingQuery>,
+) -> impl Responder {
+    trending_list(
+        &search_service,
+        TrendingList::Now,
+        params.genre.clone(),
+        &params,
+    )
+    .await
+}
+
+/// GET /api/v1/content/trending/rising - Content with accelerating engagement
+///
+/// Ranked by velocity: the short-horizon engagement rate over the long-term
+/// baseline. Items flagged `spiking` exceed the configured spike ratio.
+///
+/// Query parameters:
+/// - region, platform, genre, limit: as for trending
+/// - spiking_only: Only return spiking items (default: false)
+pub async fn get_rising(
+    search_service: web::Data<Arc<HybridSearchService>>,
+    params: web::Query<TrendingQuery>,
+) -> impl Responder {
+    trending_list(
+        &search_service,
+        TrendingList::Rising,
+        params.genre.clone(),
+        &params,
+    )
+    .await
+}
+
+/// GET /api/v1/content/trending/genres/{genre} - Top trending content in a genre
+///
+/// Query parameters:
+/// - region, platform, limit: as for trending
+pub async fn get_trending_by_genre(
+    search_service: web::Data<Arc<HybridSearchService>>,
+    path: web::Path<String>,
+    params: web::Query<TrendingQuery>,
+) -> impl Responder {
+    trending_list(
+        &search_service,
+        TrendingList::Now,
+        Some(path.into_inner()),
+        &params,
+    )
+    .await
+}
+
+/// POST /api/v1/admin/trending/events - Record user activity events
+///
+/// For services that produce activity outside discovery. Counts content
+/// views, playback starts and completions towards trending; other event
+/// types are skipped. Requires an admin token.
+pub async fn record_trending_events(
+    req: HttpRequest,
+    search_service: web::Data<Arc<HybridSearchService>>,
+    events: web::Json<Vec<UserActivityEvent>>,
+) -> impl Responder {
+    let admin_id = match extract_admin_user_id(&req) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    let Some(trending) = search_service.trending_service() else {
+        return HttpResponse::ServiceUnavailable().json(ErrorResponse {
+            error: "Trending counters are not configured".to_string(),
+        });
+    };
+
+    let mut response = RecordEventsResponse {
+        recorded: 0,
+        skipped: 0,
+        failed: 0,
+    };
+    for event in events.iter() {
+        match trending.record_event(event).await {
+            Ok(true) => response.recorded += 1,
+            Ok(false) => response.skipped += 1,
+            Err(e) => {
+                warn!(error = %e, event_id = %event.event_id, "Failed to record trending event");
+                response.failed += 1;
+            }
+        }
+    }
+
+    info!(
+        admin_id = %admin_id,
+        recorded = response.recorded,
+        skipped = response.skipped,
+        failed = response.failed,
+        "Recorded trending events"
+    );
+    HttpResponse::Ok().json(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(region: Option<&str>, platform: Option<&str>, limit: usize) -> TrendingQuery {
+        TrendingQuery {
+            region: region.map(str::to_string),
+            platform: platform.map(str::to_string),
+            genre: None,
+            limit,
+            spiking_only: false,
+        }
+    }
+
+    #[test]
+    fn test_scope_and_limit() {
+        assert_eq!(query(None, None, 20).scope(), TrendingScope::Global);
+        assert_eq!(
+            query(Some("us"), Some("netflix"), 20).scope(),
+            TrendingScope::Region("US".to_string())
+        );
+        assert_eq!(
+            query(None, Some("Netflix"), 20).scope(),
+            TrendingScope::Platform("netflix".to_string())
+        );
+        assert_eq!(query(None, None, 0).limit(), 1);
+        assert_eq!(query(None, None, 500).limit(), MAX_TRENDING_LIMIT);
+    }
+}
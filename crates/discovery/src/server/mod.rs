@@ -44,16 +44,64 @@ pub fn configure_routes(cfg: &mut web::ServiceConfig) {
             .route("/health", web::get().to(health))
             // Search routes
             .route("/search", web::post().to(handlers::execute_search))
+            .route("/search/stream", web::post().to(handlers::stream_search))
             .route(
                 "/search/autocomplete",
                 web::get().to(handlers::autocomplete),
             )
+            // Conversational search session routes
+            .route("/search/session", web::post().to(handlers::session_search))
+            .route(
+                "/search/session/{session_id}",
+                web::get().to(handlers::get_search_session),
+            )
+            .route(
+                "/search/session/{session_id}",
+                web::delete().to(handlers::delete_search_session),
+            )
+            // Trending routes
+            .route("/content/trending", web::get().to(handlers::get_trending))
+            .route(
+                "/content/trending/rising",
+                web::get().to(handlers::get_rising),
+            )
+            .route(
+                "/content/trending/genres/{genre}",
+                web::get().to(handlers::get_trending_by_genre),
+            )
             // Analytics routes
             .route("/analytics", web::get().to(handlers::get_analytics))
             // Quality routes
             .service(
                 web::scope("/quality")
-                    .route("/report", web::get().to(handlers::get_quality_report)),
+                    .route("/report", web::get().to(handlers::get_quality_report))
+                    .route("/remediation", web::get().to(handlers::list_remediation))
+                    .route(
+                        "/remediation",
+                        web::post().to(handlers::enqueue_remediation),
+                    )
+                    .route(
+                        "/remediation/run",
+                        web::post().to(handlers::run_remediation),
+                    )
+                    .route(
+                        "/remediation/{content_id}",
+                        web::get().to(handlers::get_remediation),
+                    )
+                    .route(
+                        "/remediation/{content_id}/status",
+                        web::put().to(handlers::update_remediation_status),
+                    )
+                    .route("/trend", web::get().to(handlers::get_quality_trend))
+                    .route(
+                        "/trend/snapshot",
+                        web::post().to(handlers::capture_quality_snapshot),
+                    ),
+            )
+            // Admin trending routes
+            .route(
+                "/admin/trending/events",
+                web::post().to(handlers::record_trending_events),
             )
             // Admin ranking routes
             .service(
@@ -77,6 +125,21 @@ pub fn configure_routes(cfg: &mut web::ServiceConfig) {
                         "/history/{version}",
                         web::get().to(handlers::get_ranking_config_history),
                     ),
+            )
+            // Admin editorial rule routes
+            .service(
+                web::scope("/admin/search/editorial")
+                    .route("/rules", web::get().to(handlers::list_editorial_rules))
+                    .route("/rules", web::post().to(handlers::create_editorial_rule))
+                    .route("/rules/{id}", web::get().to(handlers::get_editorial_rule))
+                    .route(
+                        "/rules/{id}",
+                        web::put().to(handlers::update_editorial_rule),
+                    )
+                    .route(
+                        "/rules/{id}",
+                        web::delete().to(handlers::delete_editorial_rule),
+                    ),
             ),
     );
 
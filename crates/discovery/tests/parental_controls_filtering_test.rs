@@ -27,6 +27,7 @@ fn test_search_filters_with_content_rating_limit() {
         rating_range: None,
         content_rating_limit: Some(ContentRating::PG13),
         blocked_genres: vec![],
+        max_runtime_minutes: None,
     };
 
     let (clause, _) = filters.to_sql_where_clause();
@@ -42,6 +43,7 @@ fn test_search_filters_with_blocked_genres() {
         rating_range: None,
         content_rating_limit: None,
         blocked_genres: vec!["horror".to_string(), "thriller".to_string()],
+        max_runtime_minutes: None,
     };
 
     let (clause, _) = filters.to_sql_where_clause();
@@ -59,6 +61,7 @@ fn test_search_filters_with_parental_controls_affects_selectivity() {
         rating_range: None,
         content_rating_limit: None,
         blocked_genres: vec![],
+        max_runtime_minutes: None,
     };
 
     let filters_with_parental = SearchFilters {
@@ -68,6 +71,7 @@ fn test_search_filters_with_parental_controls_affects_selectivity() {
         rating_range: None,
         content_rating_limit: Some(ContentRating::G),
         blocked_genres: vec!["horror".to_string()],
+        max_runtime_minutes: None,
     };
 
     let selectivity_without = filters_without_parental.estimate_selectivity();
@@ -86,6 +90,7 @@ fn test_search_filters_g_rating_most_selective() {
         rating_range: None,
         content_rating_limit: Some(ContentRating::G),
         blocked_genres: vec![],
+        max_runtime_minutes: None,
     };
 
     let filters_nc17 = SearchFilters {
@@ -95,6 +100,7 @@ fn test_search_filters_g_rating_most_selective() {
         rating_range: None,
         content_rating_limit: Some(ContentRating::NC17),
         blocked_genres: vec![],
+        max_runtime_minutes: None,
     };
 
     assert!(filters_g.estimate_selectivity() < filters_nc17.estimate_selectivity());
@@ -109,6 +115,7 @@ fn test_search_filters_combined_parental_controls() {
         rating_range: Some((7.0, 10.0)),
         content_rating_limit: Some(ContentRating::PG),
         blocked_genres: vec!["horror".to_string(), "thriller".to_string()],
+        max_runtime_minutes: None,
     };
 
     let (clause, _) = filters.to_sql_where_clause();
@@ -163,6 +170,7 @@ fn test_filters_not_empty_with_content_rating() {
         rating_range: None,
         content_rating_limit: Some(ContentRating::PG13),
         blocked_genres: vec![],
+        max_runtime_minutes: None,
     };
 
     assert!(!filters.is_empty());
@@ -177,6 +185,7 @@ fn test_filters_not_empty_with_blocked_genres() {
         rating_range: None,
         content_rating_limit: None,
         blocked_genres: vec!["horror".to_string()],
+        max_runtime_minutes: None,
     };
 
     assert!(!filters.is_empty());
@@ -141,6 +141,34 @@ async fn test_ranking_routes_registered() {
     );
 }
 
+/// Test that admin editorial rule routes are registered
+#[actix_web::test]
+async fn test_editorial_routes_registered() {
+    let app =
+        test::init_service(App::new().configure(media_gateway_discovery::server::configure_routes))
+            .await;
+
+    let req = test::TestRequest::get()
+        .uri("/api/v1/admin/search/editorial/rules")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_ne!(
+        resp.status(),
+        404,
+        "List editorial rules endpoint should be registered"
+    );
+
+    let req = test::TestRequest::delete()
+        .uri("/api/v1/admin/search/editorial/rules/00000000-0000-0000-0000-000000000000")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_ne!(
+        resp.status(),
+        404,
+        "Delete editorial rule endpoint should be registered"
+    );
+}
+
 /// Test that catalog routes are still registered
 #[actix_web::test]
 async fn test_catalog_routes_still_registered() {
@@ -1,6 +1,6 @@
 use chrono::Utc;
 use discovery::analytics::{PeriodType, SearchAnalytics};
-use sqlx::PgPool;
+use sqlx::{Executor, PgPool};
 use std::collections::HashMap;
 
 #[tokio::test]
@@ -417,10 +417,15 @@ async fn setup_test_db() -> PgPool {
         .expect("Failed to connect to test database");
 
     // Run migrations
-    sqlx::query(include_str!("../migrations/20251206_search_analytics.sql"))
-        .execute(&pool)
-        .await
-        .expect("Failed to run migrations");
+    // Multi-statement scripts run over the simple query protocol
+    for migration in [
+        include_str!("../migrations/20251206_search_analytics.sql"),
+        include_str!("../../../migrations/021_search_recovery_events.up.sql"),
+    ] {
+        pool.execute(migration)
+            .await
+            .expect("Failed to run migrations");
+    }
 
     // Clean up any existing test data
     cleanup_test_db(&pool).await;
@@ -429,8 +434,10 @@ async fn setup_test_db() -> PgPool {
 }
 
 async fn cleanup_test_db(pool: &PgPool) {
-    sqlx::query("TRUNCATE search_events, search_clicks, popular_searches CASCADE")
-        .execute(pool)
-        .await
-        .expect("Failed to cleanup test database");
+    sqlx::query(
+        "TRUNCATE search_events, search_clicks, popular_searches, search_recovery_events CASCADE",
+    )
+    .execute(pool)
+    .await
+    .expect("Failed to cleanup test database");
 }
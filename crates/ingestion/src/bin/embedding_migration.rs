@@ -0,0 +1,166 @@
+//! Embedding migration CLI
+//!
+//! Re-embeds the catalog into a collection at a new dimension behind the
+//! content alias:
+//!
+//! ```text
+//! embedding-migration --dimension 1024 run    # start or resume, up to the switch
+//! embedding-migration status
+//! embedding-migration complete                # drop the old collection
+//! embedding-migration rollback                # point readers back and drop the new one
+//! ```
+//!
+//! `start`, `verify` and `switch` run the individual steps. Commands act on
+//! the alias's active migration unless `--migration-id` is given. While a
+//! migration backfills, ingestion pipelines with a Qdrant client mirror new
+//! content into its collection.
+
+use std::sync::Arc;
+
+use anyhow::Context;
+use clap::{Parser, Subcommand};
+use media_gateway_ingestion::reembedding::DEFAULT_ALIAS;
+use media_gateway_ingestion::{
+    EmbeddingGenerator, EmbeddingMigrationConfig, EmbeddingMigrationStore, EmbeddingMigrator,
+    MigrationStore, PostgresContentRepository, QdrantClient, VECTOR_DIM,
+};
+use uuid::Uuid;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "embedding-migration",
+    about = "Manage content embedding migrations"
+)]
+struct Cli {
+    #[arg(long, env = "DATABASE_URL")]
+    database_url: String,
+
+    #[arg(long, env = "QDRANT_URL", default_value = "http://localhost:6334")]
+    qdrant_url: String,
+
+    /// Alias content readers query
+    #[arg(long, default_value = DEFAULT_ALIAS)]
+    alias: String,
+
+    /// Target dimension of a new migration
+    #[arg(long, default_value_t = VECTOR_DIM)]
+    dimension: u64,
+
+    /// Backfill throughput ceiling
+    #[arg(long, default_value_t = 200.0)]
+    max_items_per_second: f64,
+
+    /// Migration to act on instead of the alias's active one
+    #[arg(long)]
+    migration_id: Option<Uuid>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Create the target collection and record a new migration
+    Start,
+    /// Start or resume the migration and drive it up to the switch
+    Run,
+    /// List recent migrations
+    Status,
+    /// Check coverage and recall of a backfilled migration
+    Verify,
+    /// Point readers at a verified migration's collection
+    Switch,
+    /// Delete the old collection of a switched migration
+    Complete,
+    /// Abandon a migration, restoring the old collection
+    Rollback,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    // Logs go to stderr; stdout carries the migration state as JSON
+    tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::INFO)
+        .with_writer(std::io::stderr)
+        .init();
+
+    let cli = Cli::parse();
+
+    let pool = sqlx::postgres::PgPoolOptions::new()
+        .max_connections(2)
+        .connect(&cli.database_url)
+        .await
+        .context("Failed to connect to database")?;
+    let store = Arc::new(EmbeddingMigrationStore::new(pool.clone()));
+
+    if let Command::Status = cli.command {
+        let migrations = store.list(20).await?;
+        println!("{}", serde_json::to_string_pretty(&migrations)?);
+        return Ok(());
+    }
+
+    let existing = match cli.migration_id {
+        Some(id) => Some(
+            store
+                .get(id)
+                .await?
+                .with_context(|| format!("Migration {} not found", id))?,
+        ),
+        None => store.active(&cli.alias).await?,
+    };
+
+    // Steps on an existing migration keep embedding at its dimension
+    let dimension = existing
+        .as_ref()
+        .map_or(cli.dimension, |m| m.target_dimension);
+    let config = EmbeddingMigrationConfig {
+        alias: existing
+            .as_ref()
+            .map_or_else(|| cli.alias.clone(), |m| m.alias.clone()),
+        target_dimension: dimension,
+        max_items_per_second: cli.max_items_per_second,
+        ..Default::default()
+    };
+    let migrator = EmbeddingMigrator::new(
+        Arc::new(QdrantClient::new(&cli.qdrant_url, &config.alias).await?),
+        Arc::new(PostgresContentRepository::new(pool)),
+        Arc::new(EmbeddingGenerator::with_dimension(dimension as usize)),
+        store,
+        config,
+    )?;
+
+    let existing = || {
+        existing
+            .clone()
+            .context("No active migration for the alias")
+    };
+    let migration = match cli.command {
+        Command::Status => unreachable!("handled above"),
+        Command::Start => migrator.start().await?,
+        Command::Run => migrator.run().await?,
+        Command::Verify => {
+            let mut migration = existing()?;
+            let report = migrator.verify(&mut migration).await?;
+            println!("{}", serde_json::to_string_pretty(&report)?);
+            migration
+        }
+        Command::Switch => {
+            let mut migration = existing()?;
+            migrator.switch(&mut migration).await?;
+            migration
+        }
+        Command::Complete => {
+            let mut migration = existing()?;
+            migrator.complete(&mut migration).await?;
+            migration
+        }
+        Command::Rollback => {
+            let mut migration = existing()?;
+            migrator.rollback(&mut migration).await?;
+            migration
+        }
+    };
+
+    println!("{}", serde_json::to_string_pretty(&migration)?);
+    Ok(())
+}
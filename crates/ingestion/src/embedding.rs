@@ -12,8 +12,11 @@ use ndarray::{Array, Array1};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-/// Embedding dimension (standard for sentence transformers)
-const EMBEDDING_DIM: usize = 768;
+/// Default embedding dimension (standard for sentence transformers)
+pub const EMBEDDING_DIM: usize = 768;
+
+/// Smallest supported dimension; year and rating use the first four slots
+const MIN_EMBEDDING_DIM: usize = 4;
 
 /// Embedding weights for different components
 const TEXT_WEIGHT: f32 = 0.4;
@@ -25,13 +28,22 @@ pub struct EmbeddingGenerator {
     // In production, this would use a real embedding model (e.g., sentence-transformers)
     // For now, we implement a simplified version using feature hashing
     genre_embeddings: HashMap<String, Vec<f32>>,
+    dimension: usize,
 }
 
 impl EmbeddingGenerator {
     /// Create a new embedding generator
     pub fn new() -> Self {
+        Self::with_dimension(EMBEDDING_DIM)
+    }
+
+    /// Create an embedding generator producing vectors of the given dimension
+    ///
+    /// Used when re-embedding the catalog into a collection of another size.
+    pub fn with_dimension(dimension: usize) -> Self {
         let mut generator = Self {
             genre_embeddings: HashMap::new(),
+            dimension: dimension.max(MIN_EMBEDDING_DIM),
         };
 
         // Initialize genre embeddings (simplified)
@@ -40,10 +52,15 @@ impl EmbeddingGenerator {
         generator
     }
 
+    /// Dimension of the generated embeddings
+    pub fn dimension(&self) -> usize {
+        self.dimension
+    }
+
     /// Generate content embedding
     ///
     /// Combines text, metadata, and graph embeddings with weighted sum.
-    /// Complexity: O(d) where d=768
+    /// Complexity: O(d) where d=768 by default
     pub async fn generate(&self, content: &CanonicalContent) -> Result<Vec<f32>> {
         // Generate component embeddings
         let text_embedding = self.generate_text_embedding(content);
@@ -76,7 +93,7 @@ impl EmbeddingGenerator {
 
     /// Generate metadata embedding from genres, year, and ratings
     fn generate_metadata_embedding(&self, content: &CanonicalContent) -> Vec<f32> {
-        let mut embedding = vec![0.0; EMBEDDING_DIM];
+        let mut embedding = vec![0.0; self.dimension];
 
         // Genre contribution (50% of metadata embedding)
         for genre in &content.genres {
@@ -113,15 +130,15 @@ impl EmbeddingGenerator {
     /// In production, this would use graph neural networks or graph embeddings.
     /// For now, we return a placeholder that incorporates content type and platform.
     fn generate_graph_embedding(&self, content: &CanonicalContent) -> Vec<f32> {
-        let mut embedding = vec![0.0; EMBEDDING_DIM];
+        let mut embedding = vec![0.0; self.dimension];
 
         // Platform encoding
-        let platform_hash = self.hash_string(&content.platform_id) % EMBEDDING_DIM;
+        let platform_hash = self.hash_string(&content.platform_id) % self.dimension;
         embedding[platform_hash] = 1.0;
 
         // Content type encoding
         let type_str = format!("{:?}", content.content_type);
-        let type_hash = self.hash_string(&type_str) % EMBEDDING_DIM;
+        let type_hash = self.hash_string(&type_str) % self.dimension;
         embedding[type_hash] = 1.0;
 
         embedding
@@ -129,9 +146,9 @@ impl EmbeddingGenerator {
 
     /// Combine embeddings with weighted sum
     fn combine_embeddings(&self, text: &[f32], metadata: &[f32], graph: &[f32]) -> Vec<f32> {
-        let mut combined = vec![0.0; EMBEDDING_DIM];
+        let mut combined = vec![0.0; self.dimension];
 
-        for i in 0..EMBEDDING_DIM {
+        for i in 0..self.dimension {
             combined[i] =
                 text[i] * TEXT_WEIGHT + metadata[i] * METADATA_WEIGHT + graph[i] * GRAPH_WEIGHT;
         }
@@ -156,14 +173,14 @@ impl EmbeddingGenerator {
     ///
     /// In production, use a proper sentence transformer model.
     fn text_to_embedding(&self, text: &str) -> Vec<f32> {
-        let mut embedding = vec![0.0; EMBEDDING_DIM];
+        let mut embedding = vec![0.0; self.dimension];
 
         // Tokenize and hash
         let lowercase = text.to_lowercase();
         let words: Vec<&str> = lowercase.split_whitespace().collect();
 
         for (i, word) in words.iter().enumerate() {
-            let hash = self.hash_string(word) % EMBEDDING_DIM;
+            let hash = self.hash_string(word) % self.dimension;
             // Use position weighting (earlier words more important)
             let weight = 1.0 / (1.0 + i as f32 * 0.1);
             embedding[hash] += weight;
@@ -213,12 +230,12 @@ impl EmbeddingGenerator {
         ];
 
         for genre in genres {
-            let mut embedding = vec![0.0; EMBEDDING_DIM];
+            let mut embedding = vec![0.0; self.dimension];
 
             // Use genre hash to create consistent random-like pattern
             let base_hash = self.hash_string(genre);
 
-            for i in 0..EMBEDDING_DIM {
+            for i in 0..self.dimension {
                 // Create pseudo-random values based on hash
                 let val_hash = base_hash.wrapping_add(i * 13);
                 let normalized = ((val_hash % 1000) as f32 / 1000.0) * 2.0 - 1.0;
@@ -306,6 +323,14 @@ mod tests {
         assert!((length - 1.0).abs() < 0.001);
     }
 
+    #[test]
+    fn test_custom_dimension() {
+        let generator = EmbeddingGenerator::with_dimension(512);
+        assert_eq!(generator.dimension(), 512);
+        assert_eq!(generator.text_to_embedding("The Matrix").len(), 512);
+        assert!(generator.genre_embeddings.values().all(|e| e.len() == 512));
+    }
+
     #[test]
     fn test_genre_embeddings_initialized() {
         let generator = EmbeddingGenerator::new();
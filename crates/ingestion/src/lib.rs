@@ -15,6 +15,7 @@ pub mod pipeline;
 pub mod qdrant;
 pub mod quality;
 pub mod rate_limit;
+pub mod reembedding;
 pub mod repository;
 pub mod webhooks;
 
@@ -35,10 +36,17 @@ pub use notifications::{
 pub use pipeline::{IngestionPipeline, IngestionSchedule};
 pub use qdrant::{to_content_point, ContentPayload, ContentPoint, QdrantClient, VECTOR_DIM};
 pub use quality::{
-    batch_score_content, generate_quality_report, FreshnessDecay, LowQualityItem, QualityReport,
-    QualityScorer, QualityWeights, RecalculationError, RecalculationJob, RecalculationReport,
+    batch_score_content, generate_quality_report, ExportFormat, FreshnessDecay, LowQualityItem,
+    QualityReport, QualityScorer, QualitySnapshot, QualityTrendTracker, QualityWeights,
+    RecalculationError, RecalculationJob, RecalculationReport, RemediationQueue,
+    RemediationRunReport, RemediationStatus, RemediationTask, RemediationWorker,
 };
 pub use rate_limit::RateLimitManager;
+pub use reembedding::{
+    ContentEmbedder, DualWriter, EmbeddingMigration, EmbeddingMigrationConfig,
+    EmbeddingMigrationStore, EmbeddingMigrator, MigrationStatus, MigrationStore, VectorIndex,
+    VerificationReport,
+};
 pub use repository::{
     ContentRepository, ExpiringContent, LowQualityContentItem, PostgresContentRepository,
     StaleContent,
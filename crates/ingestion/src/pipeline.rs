@@ -7,7 +7,8 @@ use crate::{
     normalizer::{CanonicalContent, PlatformNormalizer, RawContent},
     qdrant::{to_content_point, QdrantClient},
     rate_limit::RateLimitManager,
-    repository::{ContentRepository, PostgresContentRepository},
+    reembedding::{DualWriter, EmbeddingMigrationStore},
+    repository::{ContentRepository, PostgresContentRepository, StaleContent},
     IngestionError, Result,
 };
 use chrono::{DateTime, Duration as ChronoDuration, Utc};
@@ -49,6 +50,8 @@ pub struct IngestionPipeline {
     genre_mapper: Arc<GenreMapper>,
     embedding_generator: Arc<EmbeddingGenerator>,
     qdrant_client: Option<Arc<QdrantClient>>,
+    dual_writer: Option<Arc<DualWriter>>,
+    migrations: Arc<EmbeddingMigrationStore>,
     rate_limiter: Arc<RateLimitManager>,
     repository: Arc<dyn ContentRepository>,
     event_producer: Option<Arc<dyn crate::events::EventProducer>>,
@@ -74,6 +77,8 @@ impl IngestionPipeline {
             genre_mapper: Arc::new(genre_mapper),
             embedding_generator: Arc::new(embedding_generator),
             qdrant_client: None,
+            dual_writer: None,
+            migrations: Arc::new(EmbeddingMigrationStore::new(pool.clone())),
             rate_limiter: Arc::new(rate_limiter),
             repository: Arc::new(PostgresContentRepository::new(pool)),
             event_producer: None,
@@ -84,10 +89,27 @@ impl IngestionPipeline {
 
     /// Set the Qdrant client for vector indexing
     ///
+    /// Content is also mirrored into the target collection of any embedding
+    /// migration running for the client's alias.
+    ///
     /// # Arguments
     /// * `qdrant_client` - Optional Qdrant client for storing embeddings
     pub fn with_qdrant(mut self, qdrant_client: Option<QdrantClient>) -> Self {
         self.qdrant_client = qdrant_client.map(Arc::new);
+        let dual_writer = self.qdrant_client.as_ref().map(|client| {
+            Arc::new(DualWriter::at_target_dimension(
+                client.clone(),
+                self.migrations.clone(),
+                client.collection_name(),
+            ))
+        });
+        self.with_dual_writer(dual_writer)
+    }
+
+    /// Mirror indexed content into the target collection of an in-flight
+    /// embedding migration
+    pub fn with_dual_writer(mut self, dual_writer: Option<Arc<DualWriter>>) -> Self {
+        self.dual_writer = dual_writer;
         self
     }
 
@@ -142,6 +164,7 @@ impl IngestionPipeline {
         let genre_mapper = self.genre_mapper.clone();
         let embedding_generator = self.embedding_generator.clone();
         let qdrant_client = self.qdrant_client.clone();
+        let dual_writer = self.dual_writer.clone();
         let rate_limiter = self.rate_limiter.clone();
         let repository = self.repository.clone();
         let regions = self.regions.clone();
@@ -161,6 +184,7 @@ impl IngestionPipeline {
                             &genre_mapper,
                             &embedding_generator,
                             qdrant_client.as_ref().map(|c| c.as_ref()),
+                            dual_writer.as_deref(),
                             &rate_limiter,
                             repository.as_ref(),
                             region,
@@ -264,6 +288,7 @@ impl IngestionPipeline {
     fn spawn_metadata_enrichment_task(&self) -> tokio::task::JoinHandle<()> {
         let embedding_generator = self.embedding_generator.clone();
         let qdrant_client = self.qdrant_client.clone();
+        let dual_writer = self.dual_writer.clone();
         let repository = self.repository.clone();
         let event_producer = self.event_producer.clone();
         let schedule_duration = self.schedule.metadata_enrichment;
@@ -277,6 +302,7 @@ impl IngestionPipeline {
                 if let Err(e) = Self::enrich_metadata(
                     &embedding_generator,
                     qdrant_client.as_ref().map(|c| c.as_ref()),
+                    dual_writer.as_deref(),
                     repository.as_ref(),
                     event_producer.as_ref(),
                 )
@@ -291,12 +317,14 @@ impl IngestionPipeline {
     }
 
     /// Process full catalog refresh for a platform/region
+    #[allow(clippy::too_many_arguments)]
     async fn process_catalog_refresh(
         normalizer: Arc<dyn PlatformNormalizer>,
         entity_resolver: &EntityResolver,
         genre_mapper: &GenreMapper,
         embedding_generator: &EmbeddingGenerator,
         qdrant_client: Option<&QdrantClient>,
+        dual_writer: Option<&DualWriter>,
         rate_limiter: &RateLimitManager,
         repository: &dyn ContentRepository,
         region: &str,
@@ -329,6 +357,7 @@ impl IngestionPipeline {
                 genre_mapper,
                 embedding_generator,
                 qdrant_client,
+                dual_writer,
                 repository,
             )
             .await?;
@@ -338,6 +367,7 @@ impl IngestionPipeline {
     }
 
     /// Process a batch of raw content items
+    #[allow(clippy::too_many_arguments)]
     async fn process_batch(
         batch: &[RawContent],
         normalizer: &dyn PlatformNormalizer,
@@ -345,9 +375,11 @@ impl IngestionPipeline {
         genre_mapper: &GenreMapper,
         embedding_generator: &EmbeddingGenerator,
         qdrant_client: Option<&QdrantClient>,
+        dual_writer: Option<&DualWriter>,
         repository: &dyn ContentRepository,
     ) -> Result<()> {
         let mut qdrant_points = Vec::new();
+        let mut indexed = Vec::new();
 
         for raw in batch {
             // Normalize to canonical format
@@ -393,6 +425,12 @@ impl IngestionPipeline {
                     Err(e) => warn!("Failed to create Qdrant point for {}: {}", content_id, e),
                 }
             }
+            if dual_writer.is_some() {
+                indexed.push(StaleContent {
+                    content_id,
+                    content: canonical,
+                });
+            }
         }
 
         // Batch upsert to Qdrant after DB persistence
@@ -402,6 +440,13 @@ impl IngestionPipeline {
             }
         }
 
+        // Keep an in-flight embedding migration's collection current
+        if let Some(writer) = dual_writer {
+            if let Err(e) = writer.write(&indexed).await {
+                warn!("Dual write to migration collection failed: {}", e);
+            }
+        }
+
         Ok(())
     }
 
@@ -543,6 +588,7 @@ impl IngestionPipeline {
     async fn enrich_metadata(
         embedding_generator: &EmbeddingGenerator,
         qdrant_client: Option<&QdrantClient>,
+        dual_writer: Option<&DualWriter>,
         repository: &dyn ContentRepository,
         event_producer: Option<&Arc<dyn crate::events::EventProducer>>,
     ) -> Result<()> {
@@ -663,6 +709,13 @@ impl IngestionPipeline {
                 }
             }
 
+            // Re-embed the batch for an in-flight embedding migration too
+            if let Some(writer) = dual_writer {
+                if let Err(e) = writer.write(batch).await {
+                    warn!("Dual write to migration collection failed: {}", e);
+                }
+            }
+
             // Publish metadata enrichment events
             if let Some(producer) = event_producer {
                 if let Err(e) = producer.publish_batch(events).await {
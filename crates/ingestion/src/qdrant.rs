@@ -9,9 +9,12 @@
 //! - Health checking and error handling
 //! - Integration with CanonicalContent and embedding pipeline
 
+use crate::reembedding::VectorIndex;
 use crate::{normalizer::CanonicalContent, IngestionError, Result};
+use async_trait::async_trait;
 use qdrant_client::qdrant::{
-    vectors_config::Config, CreateCollection, Distance, PointStruct, UpsertPointsBuilder,
+    vector_output, vectors_config::Config, CountPointsBuilder, CreateAliasBuilder,
+    CreateCollection, Distance, GetPointsBuilder, PointId, PointStruct, UpsertPointsBuilder,
     Value as QdrantValue, VectorParams, VectorsConfig,
 };
 use qdrant_client::Qdrant as QdrantClientImpl;
@@ -45,6 +48,8 @@ pub struct ContentPayload {
     pub platform: String,
     /// Release year
     pub release_year: i32,
+    /// Runtime in minutes, when known
+    pub runtime_minutes: Option<i32>,
     /// Popularity/rating score
     pub popularity_score: f32,
 }
@@ -92,6 +97,11 @@ impl QdrantClient {
         })
     }
 
+    /// Collection (or alias) content is written to and read from
+    pub fn collection_name(&self) -> &str {
+        &self.collection_name
+    }
+
     /// Perform health check on Qdrant server
     ///
     /// # Returns
@@ -245,43 +255,7 @@ impl QdrantClient {
         vector: Vec<f32>,
         payload: ContentPayload,
     ) -> Result<PointStruct> {
-        // Convert payload to HashMap for Qdrant
-        let mut payload_map = HashMap::new();
-
-        payload_map.insert(
-            "content_id".to_string(),
-            QdrantValue::from(payload.content_id.to_string()),
-        );
-        payload_map.insert("title".to_string(), QdrantValue::from(payload.title));
-        payload_map.insert("platform".to_string(), QdrantValue::from(payload.platform));
-        payload_map.insert(
-            "release_year".to_string(),
-            QdrantValue::from(payload.release_year as i64),
-        );
-        payload_map.insert(
-            "popularity_score".to_string(),
-            QdrantValue::from(payload.popularity_score as f64),
-        );
-
-        // Convert genres to list value
-        let genre_values: Vec<QdrantValue> =
-            payload.genres.into_iter().map(QdrantValue::from).collect();
-        payload_map.insert(
-            "genres".to_string(),
-            QdrantValue {
-                kind: Some(qdrant_client::qdrant::value::Kind::ListValue(
-                    qdrant_client::qdrant::ListValue {
-                        values: genre_values,
-                    },
-                )),
-            },
-        );
-
-        use qdrant_client::Payload;
-
-        let payload: Payload = payload_map.into();
-
-        Ok(PointStruct::new(id.to_string(), vector, payload))
+        Ok(content_point_struct(id, vector, payload))
     }
 
     /// Search for similar content by vector
@@ -314,22 +288,7 @@ impl QdrantClient {
         let results: Vec<(Uuid, f32)> = search_result
             .result
             .into_iter()
-            .filter_map(|point| {
-                let point_id = point.id?;
-                let id_str = match point_id {
-                    qdrant_client::qdrant::PointId {
-                        point_id_options:
-                            Some(qdrant_client::qdrant::point_id::PointIdOptions::Uuid(uuid_str)),
-                    } => uuid_str,
-                    qdrant_client::qdrant::PointId {
-                        point_id_options:
-                            Some(qdrant_client::qdrant::point_id::PointIdOptions::Num(num)),
-                    } => num.to_string(),
-                    _ => return None,
-                };
-                let id = Uuid::parse_str(&id_str).ok()?;
-                Some((id, point.score))
-            })
+            .filter_map(|point| Some((point_uuid(point.id?)?, point.score)))
             .collect();
 
         debug!("Found {} similar vectors", results.len());
@@ -337,6 +296,213 @@ impl QdrantClient {
     }
 }
 
+/// Parse a Qdrant point id written from a content UUID
+fn point_uuid(point_id: PointId) -> Option<Uuid> {
+    let id_str = match point_id {
+        qdrant_client::qdrant::PointId {
+            point_id_options: Some(qdrant_client::qdrant::point_id::PointIdOptions::Uuid(uuid_str)),
+        } => uuid_str,
+        qdrant_client::qdrant::PointId {
+            point_id_options: Some(qdrant_client::qdrant::point_id::PointIdOptions::Num(num)),
+        } => num.to_string(),
+        _ => return None,
+    };
+    Uuid::parse_str(&id_str).ok()
+}
+
+/// Collection-level operations for embedding migrations; the collection this
+/// client was created for is not used
+#[async_trait]
+impl VectorIndex for QdrantClient {
+    async fn collection_exists(&self, collection: &str) -> Result<bool> {
+        self.client
+            .collection_exists(collection)
+            .await
+            .map_err(|e| {
+                IngestionError::DatabaseError(format!("Failed to check collection: {}", e))
+            })
+    }
+
+    async fn create_collection(&self, collection: &str, dimension: u64) -> Result<()> {
+        info!(
+            "Creating collection '{}' with vector size {}",
+            collection, dimension
+        );
+        self.client
+            .create_collection(CreateCollection {
+                collection_name: collection.to_string(),
+                vectors_config: Some(VectorsConfig {
+                    config: Some(Config::Params(VectorParams {
+                        size: dimension,
+                        distance: Distance::Cosine.into(),
+                        ..Default::default()
+                    })),
+                }),
+                ..Default::default()
+            })
+            .await
+            .map_err(|e| {
+                IngestionError::DatabaseError(format!("Failed to create collection: {}", e))
+            })?;
+        Ok(())
+    }
+
+    async fn delete_collection(&self, collection: &str) -> Result<()> {
+        info!("Deleting collection '{}'", collection);
+        self.client
+            .delete_collection(collection)
+            .await
+            .map_err(|e| {
+                IngestionError::DatabaseError(format!("Failed to delete collection: {}", e))
+            })?;
+        Ok(())
+    }
+
+    async fn resolve_alias(&self, alias: &str) -> Result<Option<String>> {
+        let aliases =
+            self.client.list_aliases().await.map_err(|e| {
+                IngestionError::DatabaseError(format!("Failed to list aliases: {}", e))
+            })?;
+        Ok(aliases
+            .aliases
+            .into_iter()
+            .find(|a| a.alias_name == alias)
+            .map(|a| a.collection_name))
+    }
+
+    async fn point_alias(&self, alias: &str, collection: &str) -> Result<()> {
+        // Creating an existing alias reassigns it in place, so readers never
+        // see the alias missing
+        self.client
+            .create_alias(CreateAliasBuilder::new(collection, alias))
+            .await
+            .map_err(|e| IngestionError::DatabaseError(format!("Failed to update alias: {}", e)))?;
+        Ok(())
+    }
+
+    async fn upsert(&self, collection: &str, points: Vec<ContentPoint>) -> Result<()> {
+        for chunk in points.chunks(MAX_BATCH_SIZE) {
+            let point_structs: Vec<PointStruct> = chunk
+                .iter()
+                .cloned()
+                .map(|p| content_point_struct(p.id, p.vector, p.payload))
+                .collect();
+            self.client
+                .upsert_points(UpsertPointsBuilder::new(collection, point_structs))
+                .await
+                .map_err(|e| {
+                    IngestionError::DatabaseError(format!("Failed to upsert batch: {}", e))
+                })?;
+        }
+        Ok(())
+    }
+
+    async fn count(&self, collection: &str) -> Result<u64> {
+        let response = self
+            .client
+            .count(CountPointsBuilder::new(collection).exact(true))
+            .await
+            .map_err(|e| IngestionError::DatabaseError(format!("Failed to count points: {}", e)))?;
+        Ok(response.result.map_or(0, |r| r.count))
+    }
+
+    async fn vectors(&self, collection: &str, ids: &[Uuid]) -> Result<HashMap<Uuid, Vec<f32>>> {
+        if ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let point_ids: Vec<PointId> = ids.iter().map(|id| id.to_string().into()).collect();
+        let response = self
+            .client
+            .get_points(
+                GetPointsBuilder::new(collection, point_ids)
+                    .with_vectors(true)
+                    .with_payload(false),
+            )
+            .await
+            .map_err(|e| IngestionError::DatabaseError(format!("Failed to get points: {}", e)))?;
+
+        Ok(response
+            .result
+            .into_iter()
+            .filter_map(|point| {
+                let id = point_uuid(point.id?)?;
+                match point.vectors?.get_vector()? {
+                    vector_output::Vector::Dense(dense) => Some((id, dense.data)),
+                    _ => None,
+                }
+            })
+            .collect())
+    }
+
+    async fn search(&self, collection: &str, vector: Vec<f32>, limit: u64) -> Result<Vec<Uuid>> {
+        let response = self
+            .client
+            .search_points(qdrant_client::qdrant::SearchPoints {
+                collection_name: collection.to_string(),
+                vector,
+                limit,
+                with_payload: Some(false.into()),
+                ..Default::default()
+            })
+            .await
+            .map_err(|e| {
+                IngestionError::DatabaseError(format!("Failed to search vectors: {}", e))
+            })?;
+        Ok(response
+            .result
+            .into_iter()
+            .filter_map(|point| point_uuid(point.id?))
+            .collect())
+    }
+}
+
+/// Build the Qdrant point for a content vector and its payload
+fn content_point_struct(id: Uuid, vector: Vec<f32>, payload: ContentPayload) -> PointStruct {
+    // Convert payload to HashMap for Qdrant
+    let mut payload_map = HashMap::new();
+
+    payload_map.insert(
+        "content_id".to_string(),
+        QdrantValue::from(payload.content_id.to_string()),
+    );
+    payload_map.insert("title".to_string(), QdrantValue::from(payload.title));
+    payload_map.insert("platform".to_string(), QdrantValue::from(payload.platform));
+    payload_map.insert(
+        "release_year".to_string(),
+        QdrantValue::from(payload.release_year as i64),
+    );
+    if let Some(runtime) = payload.runtime_minutes {
+        payload_map.insert(
+            "runtime_minutes".to_string(),
+            QdrantValue::from(runtime as i64),
+        );
+    }
+    payload_map.insert(
+        "popularity_score".to_string(),
+        QdrantValue::from(payload.popularity_score as f64),
+    );
+
+    // Convert genres to list value
+    let genre_values: Vec<QdrantValue> =
+        payload.genres.into_iter().map(QdrantValue::from).collect();
+    payload_map.insert(
+        "genres".to_string(),
+        QdrantValue {
+            kind: Some(qdrant_client::qdrant::value::Kind::ListValue(
+                qdrant_client::qdrant::ListValue {
+                    values: genre_values,
+                },
+            )),
+        },
+    );
+
+    use qdrant_client::Payload;
+
+    let payload: Payload = payload_map.into();
+
+    PointStruct::new(id.to_string(), vector, payload)
+}
+
 /// Convert CanonicalContent with embedding to ContentPoint
 ///
 /// # Arguments
@@ -356,6 +522,7 @@ pub fn to_content_point(content: &CanonicalContent, content_id: Uuid) -> Result<
         genres: content.genres.clone(),
         platform: content.platform_id.clone(),
         release_year: content.release_year.unwrap_or(0),
+        runtime_minutes: content.runtime_minutes,
         popularity_score: content.user_rating.unwrap_or(0.0),
     };
 
@@ -381,6 +548,7 @@ mod tests {
             genres: vec!["Action".to_string(), "Science Fiction".to_string()],
             platform: "netflix".to_string(),
             release_year: 1999,
+            runtime_minutes: Some(136),
             popularity_score: 8.7,
         };
 
@@ -395,6 +563,7 @@ mod tests {
         assert_eq!(deserialized.title, "The Matrix");
         assert_eq!(deserialized.genres.len(), 2);
         assert_eq!(deserialized.release_year, 1999);
+        assert_eq!(deserialized.runtime_minutes, Some(136));
     }
 
     #[test]
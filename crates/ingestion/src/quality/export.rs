@@ -0,0 +1,132 @@
+//! Quality report export for editorial teams
+//!
+//! Serializes low-quality items and remediation tasks as CSV or JSON Lines
+//! so they can be opened in spreadsheets or piped into other tooling.
+
+use serde::{Deserialize, Serialize};
+
+/// Supported export formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Json,
+    Csv,
+    Jsonl,
+}
+
+impl ExportFormat {
+    /// MIME type for HTTP responses
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "application/json",
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Jsonl => "application/x-ndjson",
+        }
+    }
+
+    /// File extension used for download filenames
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Jsonl => "jsonl",
+        }
+    }
+}
+
+/// Render rows as RFC 4180 CSV with a header line
+pub fn to_csv<I>(headers: &[&str], rows: I) -> String
+where
+    I: IntoIterator<Item = Vec<String>>,
+{
+    let mut out = String::new();
+    push_csv_line(&mut out, headers.iter().copied());
+    for row in rows {
+        push_csv_line(&mut out, row.iter().map(String::as_str));
+    }
+    out
+}
+
+/// Render rows as JSON Lines, one object per line
+pub fn to_jsonl<T: Serialize>(rows: &[T]) -> Result<String, serde_json::Error> {
+    let mut out = String::new();
+    for row in rows {
+        out.push_str(&serde_json::to_string(row)?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+fn push_csv_line<'a>(out: &mut String, fields: impl Iterator<Item = &'a str>) {
+    for (i, field) in fields.enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str(&escape_csv_field(field));
+    }
+    out.push_str("\r\n");
+}
+
+fn escape_csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::quality::LowQualityItem;
+
+    #[test]
+    fn test_csv_escaping() {
+        let csv = to_csv(
+            &["id", "title"],
+            vec![vec![
+                "1".to_string(),
+                "Crouching Tiger, \"Hidden\" Dragon".to_string(),
+            ]],
+        );
+
+        assert_eq!(
+            csv,
+            "id,title\r\n1,\"Crouching Tiger, \"\"Hidden\"\" Dragon\"\r\n"
+        );
+    }
+
+    #[test]
+    fn test_jsonl_one_object_per_line() {
+        let items = vec![
+            LowQualityItem {
+                id: "a".to_string(),
+                title: "A".to_string(),
+                quality_score: 0.1,
+                missing_fields: vec![],
+            },
+            LowQualityItem {
+                id: "b".to_string(),
+                title: "B".to_string(),
+                quality_score: 0.2,
+                missing_fields: vec!["poster".to_string()],
+            },
+        ];
+
+        let jsonl = to_jsonl(&items).unwrap();
+        let lines: Vec<&str> = jsonl.lines().collect();
+
+        assert_eq!(lines.len(), 2);
+        let second: LowQualityItem = serde_json::from_str(lines[1]).unwrap();
+        assert_eq!(second.id, "b");
+    }
+
+    #[test]
+    fn test_export_format_deserialization() {
+        let format: ExportFormat = serde_json::from_str("\"csv\"").unwrap();
+        assert_eq!(format, ExportFormat::Csv);
+        assert_eq!(format.extension(), "csv");
+        assert_eq!(ExportFormat::default(), ExportFormat::Json);
+    }
+}
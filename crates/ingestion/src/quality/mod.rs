@@ -1,13 +1,22 @@
 pub mod canonical_adapter;
+pub mod export;
 pub mod recalculation;
+pub mod remediation;
 pub mod scorer;
+pub mod trend;
 
 pub use scorer::{
     FreshnessDecay, LowQualityItem, MissingFieldsSummary, QualityReport, QualityScorer,
     QualityWeights, ScoreDistribution,
 };
 
+pub use export::ExportFormat;
 pub use recalculation::{RecalculationError, RecalculationJob, RecalculationReport};
+pub use remediation::{
+    MetadataPatch, RemediationQueue, RemediationRunReport, RemediationStatus, RemediationTask,
+    RemediationWorker,
+};
+pub use trend::{QualitySnapshot, QualityTrendTracker};
 
 use crate::normalizer::CanonicalContent;
 use chrono::{DateTime, Utc};
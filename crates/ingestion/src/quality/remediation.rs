@@ -0,0 +1,984 @@
+//! Quality remediation workflow
+//!
+//! Low-quality content found by the quality report is queued here and
+//! enriched from other sources: first from entity-resolved siblings already
+//! in the catalog (same entity, different platform record), then from the
+//! TMDb aggregator. Enrichment only fills fields that are missing, never
+//! overwriting existing metadata, and each content item's remediation state
+//! is tracked so editorial teams can follow up on unresolved items.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use super::canonical_adapter::identify_missing_fields_canonical;
+use super::QualityScorer;
+use crate::aggregator::{AggregatorContent, TMDbClient};
+use crate::normalizer::{CanonicalContent, ContentType};
+use crate::repository::{ContentRepository, PostgresContentRepository};
+
+/// Attempts before an item with no enrichment source is marked unresolved
+pub const DEFAULT_MAX_ATTEMPTS: i32 = 3;
+
+/// Seconds an in-progress claim is held before another run may reclaim it
+pub const DEFAULT_CLAIM_LEASE_SECS: u64 = 900;
+
+/// Maximum sibling records consulted per item
+const MAX_SIBLINGS: i64 = 5;
+
+/// Remediation state for a content item
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RemediationStatus {
+    /// Waiting for the remediation worker
+    Queued,
+    /// Claimed by a worker run
+    InProgress,
+    /// At least one missing field was filled
+    Enriched,
+    /// No source could fill any missing field after all attempts
+    Unresolved,
+    /// Editorial team decided not to remediate
+    Dismissed,
+}
+
+impl RemediationStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RemediationStatus::Queued => "queued",
+            RemediationStatus::InProgress => "in_progress",
+            RemediationStatus::Enriched => "enriched",
+            RemediationStatus::Unresolved => "unresolved",
+            RemediationStatus::Dismissed => "dismissed",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "queued" => Some(RemediationStatus::Queued),
+            "in_progress" => Some(RemediationStatus::InProgress),
+            "enriched" => Some(RemediationStatus::Enriched),
+            "unresolved" => Some(RemediationStatus::Unresolved),
+            "dismissed" => Some(RemediationStatus::Dismissed),
+            _ => None,
+        }
+    }
+}
+
+/// Remediation record for one content item
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemediationTask {
+    pub content_id: Uuid,
+    pub title: String,
+    pub status: RemediationStatus,
+    /// Fields still missing (updated after each enrichment)
+    pub missing_fields: Vec<String>,
+    pub quality_score_before: f32,
+    pub quality_score_after: Option<f32>,
+    pub attempts: i32,
+    /// Sources that contributed metadata (e.g. "sibling", "tmdb")
+    pub sources: Vec<String>,
+    /// Fields filled by enrichment
+    pub filled_fields: Vec<String>,
+    pub last_error: Option<String>,
+    /// When the current worker run claimed the item
+    pub claimed_at: Option<DateTime<Utc>>,
+    pub queued_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Summary of a remediation worker run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RemediationRunReport {
+    pub claimed: usize,
+    pub enriched: usize,
+    pub requeued: usize,
+    pub unresolved: usize,
+    /// Items whose outcome could not be recorded; they are retried once the
+    /// claim lease expires
+    pub failed: usize,
+}
+
+/// Metadata offered by an enrichment source
+#[derive(Debug, Clone, Default)]
+pub struct DonorMetadata {
+    pub overview: Option<String>,
+    pub release_year: Option<i32>,
+    pub runtime_minutes: Option<i32>,
+    pub genres: Vec<String>,
+    pub user_rating: Option<f32>,
+    pub imdb_id: Option<String>,
+    pub tmdb_id: Option<i32>,
+}
+
+impl From<&AggregatorContent> for DonorMetadata {
+    fn from(content: &AggregatorContent) -> Self {
+        Self {
+            overview: content.overview.clone(),
+            release_year: content.year,
+            runtime_minutes: content.runtime,
+            genres: content.genres.clone(),
+            user_rating: content.rating,
+            imdb_id: content.imdb_id.clone(),
+            tmdb_id: content.tmdb_id.filter(|id| *id > 0),
+        }
+    }
+}
+
+/// Fields to fill on a content item, accumulated across sources
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MetadataPatch {
+    pub overview: Option<String>,
+    pub release_year: Option<i32>,
+    pub runtime_minutes: Option<i32>,
+    pub genres: Vec<String>,
+    pub user_rating: Option<f32>,
+    pub imdb_id: Option<String>,
+    pub tmdb_id: Option<i32>,
+    pub sources: Vec<String>,
+}
+
+impl MetadataPatch {
+    /// Take fields from `donor` that `target` lacks and no earlier source filled
+    pub fn fill_from(&mut self, target: &CanonicalContent, donor: &DonorMetadata, source: &str) {
+        let before = self.clone();
+
+        if is_blank(&target.overview) && self.overview.is_none() {
+            self.overview = donor.overview.clone().filter(|s| !s.trim().is_empty());
+        }
+        if target.release_year.is_none() && self.release_year.is_none() {
+            self.release_year = donor.release_year;
+        }
+        if target.runtime_minutes.is_none() && self.runtime_minutes.is_none() {
+            self.runtime_minutes = donor.runtime_minutes.filter(|r| *r > 0);
+        }
+        if target.genres.is_empty() && self.genres.is_empty() {
+            self.genres = donor.genres.clone();
+        }
+        if target.user_rating.unwrap_or(0.0) <= 0.0 && self.user_rating.is_none() {
+            self.user_rating = donor.user_rating.filter(|r| *r > 0.0);
+        }
+        if external_id(target, "imdb").is_none() && self.imdb_id.is_none() {
+            self.imdb_id = donor.imdb_id.clone();
+        }
+        if external_id(target, "tmdb").is_none() && self.tmdb_id.is_none() {
+            self.tmdb_id = donor.tmdb_id;
+        }
+
+        if *self != before && !self.sources.iter().any(|s| s == source) {
+            self.sources.push(source.to_string());
+        }
+    }
+
+    /// Names of the fields this patch fills, matching quality report field names
+    pub fn filled_fields(&self) -> Vec<String> {
+        let mut fields = Vec::new();
+        if self.overview.is_some() {
+            fields.push("overview".to_string());
+        }
+        if self.release_year.is_some() {
+            fields.push("release_year".to_string());
+        }
+        if self.runtime_minutes.is_some() {
+            fields.push("runtime_minutes".to_string());
+        }
+        if !self.genres.is_empty() {
+            fields.push("genres".to_string());
+        }
+        if self.user_rating.is_some() {
+            fields.push("user_rating".to_string());
+        }
+        if self.imdb_id.is_some() || self.tmdb_id.is_some() {
+            fields.push("external_ids".to_string());
+        }
+        fields
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.filled_fields().is_empty()
+    }
+
+    /// Apply to an in-memory copy so the new quality score can be computed
+    pub fn apply_to(&self, content: &mut CanonicalContent) {
+        if let Some(overview) = &self.overview {
+            content.overview = Some(overview.clone());
+        }
+        if let Some(year) = self.release_year {
+            content.release_year = Some(year);
+        }
+        if let Some(runtime) = self.runtime_minutes {
+            content.runtime_minutes = Some(runtime);
+        }
+        if !self.genres.is_empty() {
+            content.genres = self.genres.clone();
+        }
+        if let Some(rating) = self.user_rating {
+            content.user_rating = Some(rating);
+        }
+        if let Some(imdb_id) = &self.imdb_id {
+            content
+                .external_ids
+                .insert("imdb_id".to_string(), imdb_id.clone());
+        }
+        if let Some(tmdb_id) = self.tmdb_id {
+            content
+                .external_ids
+                .insert("tmdb_id".to_string(), tmdb_id.to_string());
+        }
+    }
+}
+
+/// Persistent queue of content awaiting remediation
+pub struct RemediationQueue {
+    pool: PgPool,
+    repository: PostgresContentRepository,
+    claim_lease: Duration,
+}
+
+impl RemediationQueue {
+    pub fn new(pool: PgPool) -> Self {
+        let repository = PostgresContentRepository::new(pool.clone());
+        Self {
+            pool,
+            repository,
+            claim_lease: Duration::from_secs(DEFAULT_CLAIM_LEASE_SECS),
+        }
+    }
+
+    /// How long a claim is held before abandoned items are reclaimed
+    pub fn with_claim_lease(mut self, lease: Duration) -> Self {
+        self.claim_lease = lease;
+        self
+    }
+
+    /// Queue content scoring below `threshold`; already-tracked items are left as is
+    pub async fn enqueue_below_threshold(
+        &self,
+        threshold: f32,
+        limit: i64,
+    ) -> Result<usize, anyhow::Error> {
+        let items = self
+            .repository
+            .find_low_quality_content(threshold, limit)
+            .await?;
+
+        let mut queued = 0;
+        for item in items {
+            let missing_fields = identify_missing_fields_canonical(&item.content);
+            let result = sqlx::query(
+                r#"
+                INSERT INTO quality_remediation (
+                    content_id, title, status, missing_fields, quality_score_before, snapshot
+                ) VALUES ($1, $2, 'queued', $3, $4, $5)
+                ON CONFLICT (content_id) DO NOTHING
+                "#,
+            )
+            .bind(item.content_id)
+            .bind(&item.title)
+            .bind(&missing_fields)
+            .bind(item.quality_score)
+            .bind(serde_json::to_value(&item.content)?)
+            .execute(&self.pool)
+            .await?;
+
+            queued += result.rows_affected() as usize;
+        }
+
+        info!(
+            threshold = threshold,
+            queued = queued,
+            "Queued low-quality content for remediation"
+        );
+
+        Ok(queued)
+    }
+
+    /// Claim up to `limit` queued items, lowest quality first
+    ///
+    /// In-progress items whose claim lease has expired (the run that claimed
+    /// them crashed or timed out) are claimed again.
+    pub async fn claim(
+        &self,
+        limit: i64,
+    ) -> Result<Vec<(RemediationTask, CanonicalContent)>, anyhow::Error> {
+        let rows = sqlx::query(
+            r#"
+            UPDATE quality_remediation
+            SET status = 'in_progress', claimed_at = NOW(), updated_at = NOW()
+            WHERE content_id IN (
+                SELECT content_id FROM quality_remediation
+                WHERE status = 'queued'
+                   OR (status = 'in_progress'
+                       AND COALESCE(claimed_at, updated_at) < NOW() - make_interval(secs => $2))
+                ORDER BY quality_score_before ASC, queued_at ASC
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING *
+            "#,
+        )
+        .bind(limit)
+        .bind(self.claim_lease.as_secs_f64())
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                let snapshot: serde_json::Value = row.try_get("snapshot")?;
+                Ok((task_from_row(row)?, serde_json::from_value(snapshot)?))
+            })
+            .collect()
+    }
+
+    /// Remediation record for a content item
+    pub async fn get(&self, content_id: Uuid) -> Result<Option<RemediationTask>, anyhow::Error> {
+        let row = sqlx::query("SELECT * FROM quality_remediation WHERE content_id = $1")
+            .bind(content_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        row.as_ref().map(task_from_row).transpose()
+    }
+
+    /// List remediation records, optionally filtered by status, lowest quality first
+    pub async fn list(
+        &self,
+        status: Option<RemediationStatus>,
+        limit: i64,
+    ) -> Result<Vec<RemediationTask>, anyhow::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT * FROM quality_remediation
+            WHERE $1::text IS NULL OR status = $1
+            ORDER BY quality_score_before ASC, queued_at ASC
+            LIMIT $2
+            "#,
+        )
+        .bind(status.map(|s| s.as_str()))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(task_from_row).collect()
+    }
+
+    /// Number of records in each status
+    pub async fn status_counts(&self) -> Result<Vec<(String, i64)>, anyhow::Error> {
+        let counts = sqlx::query_as::<_, (String, i64)>(
+            "SELECT status, COUNT(*) FROM quality_remediation GROUP BY status ORDER BY status",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(counts)
+    }
+
+    /// Set status manually (dismiss, or requeue an unresolved item)
+    ///
+    /// Requeueing resets the attempt counter. Returns false when the content
+    /// is not tracked.
+    pub async fn set_status(
+        &self,
+        content_id: Uuid,
+        status: RemediationStatus,
+    ) -> Result<bool, anyhow::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE quality_remediation
+            SET status = $2,
+                attempts = CASE WHEN $2 = 'queued' THEN 0 ELSE attempts END,
+                claimed_at = NULL,
+                updated_at = NOW()
+            WHERE content_id = $1
+            "#,
+        )
+        .bind(content_id)
+        .bind(status.as_str())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn complete(
+        &self,
+        task: &RemediationTask,
+        patch: &MetadataPatch,
+        missing_fields: &[String],
+        score_after: f32,
+    ) -> Result<(), anyhow::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        // Record the outcome first so a run whose claim was taken over
+        // leaves the content untouched
+        let claimed = sqlx::query(
+            r#"
+            UPDATE quality_remediation
+            SET status = 'enriched',
+                missing_fields = $2,
+                quality_score_after = $3,
+                attempts = attempts + 1,
+                sources = $4,
+                filled_fields = $5,
+                last_error = NULL,
+                claimed_at = NULL,
+                updated_at = NOW()
+            WHERE content_id = $1 AND status = 'in_progress' AND claimed_at = $6
+            "#,
+        )
+        .bind(task.content_id)
+        .bind(missing_fields)
+        .bind(score_after)
+        .bind(&patch.sources)
+        .bind(patch.filled_fields())
+        .bind(task.claimed_at)
+        .execute(&mut *tx)
+        .await?;
+        if claimed.rows_affected() == 0 {
+            anyhow::bail!("Remediation claim for {} was lost", task.content_id);
+        }
+
+        let release_date = patch
+            .release_year
+            .and_then(|year| NaiveDate::from_ymd_opt(year, 1, 1));
+
+        sqlx::query(
+            r#"
+            UPDATE content
+            SET overview = COALESCE(NULLIF(overview, ''), $2),
+                release_date = COALESCE(release_date, $3),
+                runtime_minutes = COALESCE(runtime_minutes, $4),
+                average_rating = CASE
+                    WHEN COALESCE(average_rating, 0.0) = 0.0 THEN COALESCE($5, average_rating)
+                    ELSE average_rating
+                END,
+                quality_score = $6,
+                last_updated = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(task.content_id)
+        .bind(&patch.overview)
+        .bind(release_date)
+        .bind(patch.runtime_minutes)
+        .bind(patch.user_rating.map(|r| r as f64))
+        .bind(score_after)
+        .execute(&mut *tx)
+        .await?;
+
+        if !patch.genres.is_empty() {
+            sqlx::query(
+                r#"
+                INSERT INTO content_genres (content_id, genre)
+                SELECT $1, UNNEST($2::text[])
+                ON CONFLICT DO NOTHING
+                "#,
+            )
+            .bind(task.content_id)
+            .bind(&patch.genres)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        if patch.imdb_id.is_some() || patch.tmdb_id.is_some() {
+            sqlx::query(
+                r#"
+                INSERT INTO external_ids (content_id, imdb_id, tmdb_id)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (content_id) DO UPDATE SET
+                    imdb_id = COALESCE(external_ids.imdb_id, EXCLUDED.imdb_id),
+                    tmdb_id = COALESCE(external_ids.tmdb_id, EXCLUDED.tmdb_id)
+                "#,
+            )
+            .bind(task.content_id)
+            .bind(&patch.imdb_id)
+            .bind(patch.tmdb_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn fail_attempt(
+        &self,
+        task: &RemediationTask,
+        error: &str,
+        max_attempts: i32,
+    ) -> Result<RemediationStatus, anyhow::Error> {
+        let status = if task.attempts + 1 >= max_attempts {
+            RemediationStatus::Unresolved
+        } else {
+            RemediationStatus::Queued
+        };
+
+        let result = sqlx::query(
+            r#"
+            UPDATE quality_remediation
+            SET status = $2, attempts = attempts + 1, last_error = $3,
+                claimed_at = NULL, updated_at = NOW()
+            WHERE content_id = $1 AND status = 'in_progress' AND claimed_at = $4
+            "#,
+        )
+        .bind(task.content_id)
+        .bind(status.as_str())
+        .bind(error)
+        .bind(task.claimed_at)
+        .execute(&self.pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            anyhow::bail!("Remediation claim for {} was lost", task.content_id);
+        }
+
+        Ok(status)
+    }
+
+    /// Metadata from other catalog records resolved to the same entity
+    ///
+    /// Siblings share an entity mapping with the target, or have the same
+    /// title, content type and release year.
+    async fn find_siblings(&self, content_id: Uuid) -> Result<Vec<DonorMetadata>, anyhow::Error> {
+        let rows = sqlx::query(
+            r#"
+            WITH target AS (
+                SELECT c.id, c.title, c.content_type, c.release_date,
+                       te.eidr_id, te.imdb_id, te.tmdb_id
+                FROM content c
+                LEFT JOIN external_ids te ON te.content_id = c.id
+                WHERE c.id = $1
+            ),
+            target_entities AS (
+                SELECT em.entity_id
+                FROM entity_mappings em, target t
+                WHERE (em.id_type = 'eidr' AND em.external_id = t.eidr_id)
+                   OR (em.id_type = 'imdb' AND em.external_id = t.imdb_id)
+                   OR (em.id_type = 'tmdb' AND em.external_id = t.tmdb_id::text)
+            ),
+            sibling_ids AS (
+                SELECT ei.content_id
+                FROM entity_mappings em
+                JOIN target_entities te ON te.entity_id = em.entity_id
+                JOIN external_ids ei ON
+                    (em.id_type = 'eidr' AND ei.eidr_id = em.external_id)
+                    OR (em.id_type = 'imdb' AND ei.imdb_id = em.external_id)
+                    OR (em.id_type = 'tmdb' AND ei.tmdb_id::text = em.external_id)
+                UNION
+                SELECT c.id
+                FROM content c, target t
+                WHERE LOWER(c.title) = LOWER(t.title)
+                  AND c.content_type = t.content_type
+                  AND t.release_date IS NOT NULL
+                  AND EXTRACT(YEAR FROM c.release_date) = EXTRACT(YEAR FROM t.release_date)
+            )
+            SELECT
+                c.overview,
+                EXTRACT(YEAR FROM c.release_date)::integer as release_year,
+                c.runtime_minutes,
+                NULLIF(c.average_rating, 0.0) as average_rating,
+                ei.imdb_id,
+                ei.tmdb_id,
+                ARRAY(SELECT genre FROM content_genres g WHERE g.content_id = c.id) as genres
+            FROM content c
+            JOIN sibling_ids s ON s.content_id = c.id
+            LEFT JOIN external_ids ei ON ei.content_id = c.id
+            WHERE c.id <> $1
+            ORDER BY c.quality_score DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(content_id)
+        .bind(MAX_SIBLINGS)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| DonorMetadata {
+                overview: row.get("overview"),
+                release_year: row.get("release_year"),
+                runtime_minutes: row.get("runtime_minutes"),
+                genres: row.get("genres"),
+                user_rating: row
+                    .get::<Option<f64>, _>("average_rating")
+                    .map(|r| r as f32),
+                imdb_id: row.get("imdb_id"),
+                tmdb_id: row.get("tmdb_id"),
+            })
+            .collect())
+    }
+}
+
+/// Worker that enriches queued content from siblings and TMDb
+pub struct RemediationWorker {
+    queue: Arc<RemediationQueue>,
+    tmdb: Option<Arc<TMDbClient>>,
+    scorer: QualityScorer,
+    max_attempts: i32,
+}
+
+impl RemediationWorker {
+    pub fn new(queue: Arc<RemediationQueue>) -> Self {
+        Self {
+            queue,
+            tmdb: None,
+            scorer: QualityScorer::default(),
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+        }
+    }
+
+    /// Enable TMDb as a fallback source
+    pub fn with_tmdb(mut self, client: Arc<TMDbClient>) -> Self {
+        self.tmdb = Some(client);
+        self
+    }
+
+    pub fn with_scorer(mut self, scorer: QualityScorer) -> Self {
+        self.scorer = scorer;
+        self
+    }
+
+    pub fn with_max_attempts(mut self, max_attempts: i32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Claim and process up to `limit` queued items
+    pub async fn run_batch(&self, limit: i64) -> Result<RemediationRunReport, anyhow::Error> {
+        let claimed = self.queue.claim(limit).await?;
+        let mut report = RemediationRunReport {
+            claimed: claimed.len(),
+            ..Default::default()
+        };
+
+        for (task, content) in claimed {
+            let patch = self.build_patch(&task, &content).await;
+
+            if patch.is_empty() {
+                let outcome = self
+                    .queue
+                    .fail_attempt(
+                        &task,
+                        "No enrichment source provided the missing fields",
+                        self.max_attempts,
+                    )
+                    .await;
+                match outcome {
+                    Ok(RemediationStatus::Unresolved) => report.unresolved += 1,
+                    Ok(_) => report.requeued += 1,
+                    Err(e) => {
+                        warn!(content_id = %task.content_id, error = %e, "Failed to record remediation attempt");
+                        report.failed += 1;
+                    }
+                }
+                continue;
+            }
+
+            let mut enriched = content.clone();
+            patch.apply_to(&mut enriched);
+            let score_after = self.scorer.score_content(&enriched);
+            let missing_fields = identify_missing_fields_canonical(&enriched);
+
+            match self
+                .queue
+                .complete(&task, &patch, &missing_fields, score_after)
+                .await
+            {
+                Ok(()) => {
+                    debug!(
+                        content_id = %task.content_id,
+                        filled = ?patch.filled_fields(),
+                        score_before = task.quality_score_before,
+                        score_after = score_after,
+                        "Enriched low-quality content"
+                    );
+                    report.enriched += 1;
+                }
+                Err(e) => {
+                    warn!(content_id = %task.content_id, error = %e, "Failed to apply enrichment");
+                    match self
+                        .queue
+                        .fail_attempt(&task, &e.to_string(), self.max_attempts)
+                        .await
+                    {
+                        Ok(RemediationStatus::Unresolved) => report.unresolved += 1,
+                        Ok(_) => report.requeued += 1,
+                        Err(e) => {
+                            warn!(content_id = %task.content_id, error = %e, "Failed to record remediation attempt");
+                            report.failed += 1;
+                        }
+                    }
+                }
+            }
+        }
+
+        info!(
+            claimed = report.claimed,
+            enriched = report.enriched,
+            requeued = report.requeued,
+            unresolved = report.unresolved,
+            failed = report.failed,
+            "Completed remediation batch"
+        );
+
+        Ok(report)
+    }
+
+    /// Collect metadata from siblings first, then TMDb for whatever remains
+    async fn build_patch(
+        &self,
+        task: &RemediationTask,
+        content: &CanonicalContent,
+    ) -> MetadataPatch {
+        let mut patch = MetadataPatch::default();
+
+        match self.queue.find_siblings(task.content_id).await {
+            Ok(siblings) => {
+                for sibling in &siblings {
+                    patch.fill_from(content, sibling, "sibling");
+                }
+            }
+            Err(e) => warn!(content_id = %task.content_id, error = %e, "Sibling lookup failed"),
+        }
+
+        let mut partially_enriched = content.clone();
+        patch.apply_to(&mut partially_enriched);
+        if !has_fillable_gaps(&partially_enriched) {
+            return patch;
+        }
+
+        if let Some(tmdb) = &self.tmdb {
+            match lookup_tmdb(tmdb, &partially_enriched).await {
+                Ok(Some(found)) => patch.fill_from(content, &DonorMetadata::from(&found), "tmdb"),
+                Ok(None) => debug!(content_id = %task.content_id, "No TMDb match"),
+                Err(e) => warn!(content_id = %task.content_id, error = %e, "TMDb lookup failed"),
+            }
+        }
+
+        patch
+    }
+}
+
+/// Find TMDb details by known TMDb ID, or by exact title (and year) match
+async fn lookup_tmdb(
+    tmdb: &TMDbClient,
+    content: &CanonicalContent,
+) -> crate::Result<Option<AggregatorContent>> {
+    let is_series = matches!(
+        content.content_type,
+        ContentType::Series | ContentType::Episode
+    );
+
+    let tmdb_id = match external_id(content, "tmdb").and_then(|id| id.parse::<i32>().ok()) {
+        Some(id) => Some(id),
+        None => {
+            let candidates = if is_series {
+                tmdb.search_tv(&content.title, content.release_year).await?
+            } else {
+                tmdb.search_movie(&content.title, content.release_year)
+                    .await?
+            };
+            candidates
+                .into_iter()
+                .find(|c| {
+                    c.title.eq_ignore_ascii_case(&content.title)
+                        && (content.release_year.is_none() || c.year == content.release_year)
+                })
+                .and_then(|c| c.tmdb_id)
+        }
+    };
+
+    match tmdb_id {
+        Some(id) if is_series => tmdb.get_tv_details(id).await.map(Some),
+        Some(id) => tmdb.get_movie_details(id).await.map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Whether any field enrichment can fill is still missing
+fn has_fillable_gaps(content: &CanonicalContent) -> bool {
+    is_blank(&content.overview)
+        || content.release_year.is_none()
+        || content.runtime_minutes.is_none()
+        || content.genres.is_empty()
+        || content.user_rating.unwrap_or(0.0) <= 0.0
+        || external_id(content, "imdb").is_none()
+        || external_id(content, "tmdb").is_none()
+}
+
+/// External ID stored under either the repository key ("imdb") or the
+/// quality scorer key ("imdb_id")
+fn external_id<'a>(content: &'a CanonicalContent, kind: &str) -> Option<&'a String> {
+    content
+        .external_ids
+        .get(kind)
+        .or_else(|| content.external_ids.get(&format!("{}_id", kind)))
+}
+
+fn is_blank(value: &Option<String>) -> bool {
+    value.as_ref().map(|s| s.trim().is_empty()).unwrap_or(true)
+}
+
+fn task_from_row(row: &PgRow) -> Result<RemediationTask, anyhow::Error> {
+    let status: String = row.try_get("status")?;
+
+    Ok(RemediationTask {
+        content_id: row.try_get("content_id")?,
+        title: row.try_get("title")?,
+        status: RemediationStatus::parse(&status)
+            .ok_or_else(|| anyhow::anyhow!("Unknown remediation status: {}", status))?,
+        missing_fields: row.try_get("missing_fields")?,
+        quality_score_before: row.try_get("quality_score_before")?,
+        quality_score_after: row.try_get("quality_score_after")?,
+        attempts: row.try_get("attempts")?,
+        sources: row.try_get("sources")?,
+        filled_fields: row.try_get("filled_fields")?,
+        last_error: row.try_get("last_error")?,
+        claimed_at: row.try_get("claimed_at")?,
+        queued_at: row.try_get("queued_at")?,
+        updated_at: row.try_get("updated_at")?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::normalizer::{AvailabilityInfo, ImageSet};
+    use std::collections::HashMap;
+
+    fn sparse_content() -> CanonicalContent {
+        CanonicalContent {
+            platform_content_id: "123".to_string(),
+            platform_id: "netflix".to_string(),
+            entity_id: None,
+            title: "Test Movie".to_string(),
+            overview: None,
+            content_type: ContentType::Movie,
+            release_year: Some(2020),
+            runtime_minutes: None,
+            genres: vec![],
+            external_ids: HashMap::new(),
+            availability: AvailabilityInfo {
+                regions: vec![],
+                subscription_required: false,
+                purchase_price: None,
+                rental_price: None,
+                currency: None,
+                available_from: None,
+                available_until: None,
+            },
+            images: ImageSet::default(),
+            rating: None,
+            user_rating: None,
+            embedding: None,
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_status_round_trip() {
+        for status in [
+            RemediationStatus::Queued,
+            RemediationStatus::InProgress,
+            RemediationStatus::Enriched,
+            RemediationStatus::Unresolved,
+            RemediationStatus::Dismissed,
+        ] {
+            assert_eq!(RemediationStatus::parse(status.as_str()), Some(status));
+        }
+        assert_eq!(RemediationStatus::parse("done"), None);
+    }
+
+    #[test]
+    fn test_patch_only_fills_missing_fields() {
+        let target = sparse_content();
+        let donor = DonorMetadata {
+            overview: Some("A test movie".to_string()),
+            release_year: Some(1999),
+            runtime_minutes: Some(110),
+            genres: vec!["drama".to_string()],
+            ..Default::default()
+        };
+
+        let mut patch = MetadataPatch::default();
+        patch.fill_from(&target, &donor, "sibling");
+
+        assert_eq!(patch.overview.as_deref(), Some("A test movie"));
+        assert_eq!(
+            patch.release_year, None,
+            "existing year must not be replaced"
+        );
+        assert_eq!(patch.runtime_minutes, Some(110));
+        assert_eq!(patch.sources, vec!["sibling"]);
+        assert_eq!(
+            patch.filled_fields(),
+            vec!["overview", "runtime_minutes", "genres"]
+        );
+    }
+
+    #[test]
+    fn test_earlier_source_takes_precedence() {
+        let target = sparse_content();
+        let sibling = DonorMetadata {
+            overview: Some("From sibling".to_string()),
+            ..Default::default()
+        };
+        let tmdb = DonorMetadata {
+            overview: Some("From TMDb".to_string()),
+            imdb_id: Some("tt0000001".to_string()),
+            tmdb_id: Some(42),
+            ..Default::default()
+        };
+
+        let mut patch = MetadataPatch::default();
+        patch.fill_from(&target, &sibling, "sibling");
+        patch.fill_from(&target, &tmdb, "tmdb");
+
+        assert_eq!(patch.overview.as_deref(), Some("From sibling"));
+        assert_eq!(patch.tmdb_id, Some(42));
+        assert_eq!(patch.sources, vec!["sibling", "tmdb"]);
+    }
+
+    #[test]
+    fn test_source_without_contribution_is_not_recorded() {
+        let mut target = sparse_content();
+        target.overview = Some("Existing".to_string());
+        let donor = DonorMetadata {
+            overview: Some("Other".to_string()),
+            ..Default::default()
+        };
+
+        let mut patch = MetadataPatch::default();
+        patch.fill_from(&target, &donor, "tmdb");
+
+        assert!(patch.is_empty());
+        assert!(patch.sources.is_empty());
+    }
+
+    #[test]
+    fn test_apply_patch_raises_quality_score() {
+        let content = sparse_content();
+        let scorer = QualityScorer::default();
+        let before = scorer.score_content(&content);
+
+        let patch = MetadataPatch {
+            overview: Some("A test movie".to_string()),
+            runtime_minutes: Some(110),
+            genres: vec!["drama".to_string()],
+            imdb_id: Some("tt0000001".to_string()),
+            ..Default::default()
+        };
+        let mut enriched = content.clone();
+        patch.apply_to(&mut enriched);
+
+        assert!(scorer.score_content(&enriched) > before);
+        let missing = identify_missing_fields_canonical(&enriched);
+        assert!(!missing.contains(&"overview".to_string()));
+        assert!(!missing.contains(&"external_ids".to_string()));
+    }
+}
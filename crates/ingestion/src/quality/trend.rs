@@ -0,0 +1,159 @@
+//! Quality score distribution trending
+//!
+//! Captures point-in-time snapshots of the catalog quality score
+//! distribution so editorial teams can see whether remediation is moving
+//! the catalog toward higher quality over time.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Row};
+use tracing::info;
+use uuid::Uuid;
+
+use super::ScoreDistribution;
+
+/// Score buckets shared with [`super::generate_quality_report`]
+pub const SCORE_BUCKETS: [&str; 5] = ["0.0-0.2", "0.2-0.4", "0.4-0.6", "0.6-0.8", "0.8-1.0"];
+
+/// Point-in-time quality score distribution
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QualitySnapshot {
+    pub id: Uuid,
+    pub captured_at: DateTime<Utc>,
+    pub total_content: i64,
+    pub average_score: f32,
+    pub threshold: f32,
+    pub low_quality_count: i64,
+    pub distribution: Vec<ScoreDistribution>,
+}
+
+/// Records and reads quality score snapshots
+pub struct QualityTrendTracker {
+    pool: PgPool,
+}
+
+impl QualityTrendTracker {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Compute the current distribution and persist it as a snapshot
+    pub async fn capture(&self, threshold: f32) -> Result<QualitySnapshot, anyhow::Error> {
+        let row = sqlx::query(
+            r#"
+            SELECT
+                COUNT(*) as total_content,
+                COALESCE(AVG(quality_score), 0.0)::real as average_score,
+                COUNT(*) FILTER (WHERE quality_score < $1) as low_quality_count,
+                COUNT(*) FILTER (WHERE quality_score < 0.2) as bucket_0,
+                COUNT(*) FILTER (WHERE quality_score >= 0.2 AND quality_score < 0.4) as bucket_1,
+                COUNT(*) FILTER (WHERE quality_score >= 0.4 AND quality_score < 0.6) as bucket_2,
+                COUNT(*) FILTER (WHERE quality_score >= 0.6 AND quality_score < 0.8) as bucket_3,
+                COUNT(*) FILTER (WHERE quality_score >= 0.8) as bucket_4
+            FROM content
+            "#,
+        )
+        .bind(threshold)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let counts: Vec<i64> = (0..SCORE_BUCKETS.len())
+            .map(|i| row.get::<i64, _>(format!("bucket_{}", i).as_str()))
+            .collect();
+
+        let snapshot = QualitySnapshot {
+            id: Uuid::new_v4(),
+            captured_at: Utc::now(),
+            total_content: row.get("total_content"),
+            average_score: row.get("average_score"),
+            threshold,
+            low_quality_count: row.get("low_quality_count"),
+            distribution: distribution_from_counts(&counts),
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO quality_score_snapshots (
+                id, captured_at, total_content, average_score, threshold,
+                low_quality_count, distribution
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(snapshot.id)
+        .bind(snapshot.captured_at)
+        .bind(snapshot.total_content)
+        .bind(snapshot.average_score)
+        .bind(snapshot.threshold)
+        .bind(snapshot.low_quality_count)
+        .bind(serde_json::to_value(&snapshot.distribution)?)
+        .execute(&self.pool)
+        .await?;
+
+        info!(
+            total_content = snapshot.total_content,
+            average_score = snapshot.average_score,
+            low_quality_count = snapshot.low_quality_count,
+            "Captured quality score snapshot"
+        );
+
+        Ok(snapshot)
+    }
+
+    /// Snapshots captured since the given time, oldest first
+    pub async fn trend(&self, since: DateTime<Utc>) -> Result<Vec<QualitySnapshot>, anyhow::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, captured_at, total_content, average_score, threshold,
+                   low_quality_count, distribution
+            FROM quality_score_snapshots
+            WHERE captured_at >= $1
+            ORDER BY captured_at ASC
+            "#,
+        )
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(QualitySnapshot {
+                    id: row.get("id"),
+                    captured_at: row.get("captured_at"),
+                    total_content: row.get("total_content"),
+                    average_score: row.get("average_score"),
+                    threshold: row.get("threshold"),
+                    low_quality_count: row.get("low_quality_count"),
+                    distribution: serde_json::from_value(row.get("distribution"))?,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Pair bucket counts with their [`SCORE_BUCKETS`] labels
+fn distribution_from_counts(counts: &[i64]) -> Vec<ScoreDistribution> {
+    SCORE_BUCKETS
+        .iter()
+        .zip(counts)
+        .map(|(range, count)| ScoreDistribution {
+            range: range.to_string(),
+            count: (*count).max(0) as u64,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distribution_from_counts() {
+        let distribution = distribution_from_counts(&[4, 3, 2, 1, 0]);
+
+        assert_eq!(distribution.len(), 5);
+        assert_eq!(distribution[0].range, "0.0-0.2");
+        assert_eq!(distribution[0].count, 4);
+        assert_eq!(distribution[4].range, "0.8-1.0");
+        assert_eq!(distribution[4].count, 0);
+    }
+}
@@ -0,0 +1,1434 @@
+//! Embedding dimension migration and re-embedding jobs
+//!
+//! Content vectors are read through a Qdrant alias (`media_embeddings` by
+//! default) rather than a collection, so the catalog can be re-embedded at a
+//! new dimension without interrupting search. A migration:
+//!
+//! 1. creates a collection at the target dimension beside the live one
+//! 2. backfills it from the content table in throttled batches, while
+//!    `DualWriter` mirrors newly ingested content into it
+//! 3. verifies point coverage and nearest-neighbour recall against the live
+//!    collection
+//! 4. repoints the alias at the new collection in one alias operation
+//! 5. deletes the old collection once the migration is completed
+//!
+//! Progress is persisted in `embedding_migrations`, so an interrupted
+//! backfill resumes from its cursor.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::embedding::EmbeddingGenerator;
+use crate::normalizer::CanonicalContent;
+use crate::qdrant::{to_content_point, ContentPoint};
+use crate::repository::{ContentRepository, StaleContent};
+use crate::{IngestionError, Result};
+
+/// Alias content readers query
+pub const DEFAULT_ALIAS: &str = "media_embeddings";
+
+/// How long `DualWriter` trusts its view of the active migration
+const DUAL_WRITE_REFRESH: Duration = Duration::from_secs(30);
+
+/// Collection-level vector operations used by migrations
+#[async_trait]
+pub trait VectorIndex: Send + Sync {
+    async fn collection_exists(&self, collection: &str) -> Result<bool>;
+
+    async fn create_collection(&self, collection: &str, dimension: u64) -> Result<()>;
+
+    async fn delete_collection(&self, collection: &str) -> Result<()>;
+
+    /// Collection an alias currently points to
+    async fn resolve_alias(&self, alias: &str) -> Result<Option<String>>;
+
+    /// Point an alias at a collection, replacing its previous target
+    async fn point_alias(&self, alias: &str, collection: &str) -> Result<()>;
+
+    async fn upsert(&self, collection: &str, points: Vec<ContentPoint>) -> Result<()>;
+
+    async fn count(&self, collection: &str) -> Result<u64>;
+
+    /// Stored vectors for the given ids; missing points are omitted
+    async fn vectors(&self, collection: &str, ids: &[Uuid]) -> Result<HashMap<Uuid, Vec<f32>>>;
+
+    /// Ids of the nearest points, best first
+    async fn search(&self, collection: &str, vector: Vec<f32>, limit: u64) -> Result<Vec<Uuid>>;
+}
+
+/// Produces content embeddings at a fixed dimension
+#[async_trait]
+pub trait ContentEmbedder: Send + Sync {
+    fn dimension(&self) -> usize;
+
+    async fn embed(&self, content: &CanonicalContent) -> Result<Vec<f32>>;
+}
+
+#[async_trait]
+impl ContentEmbedder for EmbeddingGenerator {
+    fn dimension(&self) -> usize {
+        EmbeddingGenerator::dimension(self)
+    }
+
+    async fn embed(&self, content: &CanonicalContent) -> Result<Vec<f32>> {
+        self.generate(content).await
+    }
+}
+
+/// Migration lifecycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationStatus {
+    /// Copying the catalog into the new collection
+    Backfilling,
+    /// Backfill finished; coverage and recall not yet checked
+    Verifying,
+    /// Verification passed; ready to switch readers
+    Verified,
+    /// Readers use the new collection; the old one is kept for rollback
+    Switched,
+    /// Old collection deleted
+    Completed,
+    /// Verification failed or the job errored; the new collection is kept
+    /// for inspection until rolled back
+    Failed,
+    /// Abandoned; readers are back on the old collection
+    RolledBack,
+}
+
+impl MigrationStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MigrationStatus::Backfilling => "backfilling",
+            MigrationStatus::Verifying => "verifying",
+            MigrationStatus::Verified => "verified",
+            MigrationStatus::Switched => "switched",
+            MigrationStatus::Completed => "completed",
+            MigrationStatus::Failed => "failed",
+            MigrationStatus::RolledBack => "rolled_back",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "backfilling" => Some(MigrationStatus::Backfilling),
+            "verifying" => Some(MigrationStatus::Verifying),
+            "verified" => Some(MigrationStatus::Verified),
+            "switched" => Some(MigrationStatus::Switched),
+            "completed" => Some(MigrationStatus::Completed),
+            "failed" => Some(MigrationStatus::Failed),
+            "rolled_back" => Some(MigrationStatus::RolledBack),
+            _ => None,
+        }
+    }
+
+    /// Whether the migration still owns the alias
+    pub fn is_active(&self) -> bool {
+        !matches!(
+            self,
+            MigrationStatus::Completed | MigrationStatus::Failed | MigrationStatus::RolledBack
+        )
+    }
+
+    /// Whether new content must also be written to the target collection
+    pub fn is_dual_writing(&self) -> bool {
+        matches!(
+            self,
+            MigrationStatus::Backfilling | MigrationStatus::Verifying | MigrationStatus::Verified
+        )
+    }
+}
+
+/// Migration settings
+#[derive(Debug, Clone)]
+pub struct EmbeddingMigrationConfig {
+    pub alias: String,
+    pub target_dimension: u64,
+    /// Items embedded and upserted per batch (Qdrant batches cap at 100)
+    pub batch_size: usize,
+    /// Backfill throughput ceiling, to leave headroom for live ingestion
+    pub max_items_per_second: f64,
+    /// Content items whose neighbourhoods are compared during verification
+    pub recall_sample_size: usize,
+    pub recall_k: usize,
+    /// Mean top-k neighbour overlap with the live collection required to switch
+    pub min_recall: f32,
+    /// Share of live points the new collection must contain to switch
+    pub min_coverage: f32,
+}
+
+impl Default for EmbeddingMigrationConfig {
+    fn default() -> Self {
+        Self {
+            alias: DEFAULT_ALIAS.to_string(),
+            target_dimension: crate::qdrant::VECTOR_DIM,
+            batch_size: 100,
+            max_items_per_second: 200.0,
+            recall_sample_size: 200,
+            recall_k: 10,
+            min_recall: 0.6,
+            min_coverage: 0.99,
+        }
+    }
+}
+
+/// Persisted migration state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingMigration {
+    pub id: Uuid,
+    pub alias: String,
+    /// Live collection at start; None when the alias did not exist yet
+    pub source_collection: Option<String>,
+    pub target_collection: String,
+    pub target_dimension: u64,
+    pub status: MigrationStatus,
+    /// Last content id backfilled
+    pub cursor: Option<Uuid>,
+    pub processed: i64,
+    pub failed: i64,
+    /// Content ids used for recall verification
+    pub sample_ids: Vec<Uuid>,
+    pub coverage: Option<f32>,
+    pub recall: Option<f32>,
+    pub error: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub switched_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl EmbeddingMigration {
+    fn new(
+        alias: &str,
+        source_collection: Option<String>,
+        target_dimension: u64,
+        now: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            alias: alias.to_string(),
+            source_collection,
+            target_collection: target_collection_name(alias, target_dimension, now),
+            target_dimension,
+            status: MigrationStatus::Backfilling,
+            cursor: None,
+            processed: 0,
+            failed: 0,
+            sample_ids: Vec::new(),
+            coverage: None,
+            recall: None,
+            error: None,
+            started_at: now,
+            updated_at: now,
+            switched_at: None,
+            completed_at: None,
+        }
+    }
+
+    /// Whether the source is a pre-alias collection named like the alias,
+    /// which has to be dropped before the alias can take its name
+    fn source_is_legacy(&self) -> bool {
+        self.source_collection.as_deref() == Some(self.alias.as_str())
+    }
+
+    fn fail(&mut self, error: String) {
+        warn!("Embedding migration {} failed: {}", self.id, error);
+        self.status = MigrationStatus::Failed;
+        self.error = Some(error);
+    }
+}
+
+/// Name of the collection created for a migration
+pub fn target_collection_name(alias: &str, dimension: u64, started_at: DateTime<Utc>) -> String {
+    format!(
+        "{}_{}d_{}",
+        alias,
+        dimension,
+        started_at.format("%Y%m%d%H%M%S")
+    )
+}
+
+/// Share of the reference top-k neighbours also found in the candidate top-k,
+/// ignoring the query item itself
+pub fn neighbour_recall(query: Uuid, reference: &[Uuid], candidate: &[Uuid], k: usize) -> f32 {
+    let top = |ids: &[Uuid]| -> Vec<Uuid> {
+        ids.iter()
+            .copied()
+            .filter(|id| *id != query)
+            .take(k)
+            .collect()
+    };
+    let reference = top(reference);
+    if reference.is_empty() {
+        return 1.0;
+    }
+    let candidate: HashSet<Uuid> = top(candidate).into_iter().collect();
+    let overlap = reference.iter().filter(|id| candidate.contains(id)).count();
+    overlap as f32 / reference.len() as f32
+}
+
+/// Pause needed after a batch to stay under `max_items_per_second`
+pub fn throttle_delay(items: usize, max_items_per_second: f64, elapsed: Duration) -> Duration {
+    if max_items_per_second <= 0.0 {
+        return Duration::ZERO;
+    }
+    let budget = Duration::from_secs_f64(items as f64 / max_items_per_second);
+    budget.saturating_sub(elapsed)
+}
+
+/// Outcome of comparing the new collection against the live one
+#[derive(Debug, Clone, Serialize)]
+pub struct VerificationReport {
+    pub source_points: u64,
+    pub target_points: u64,
+    pub coverage: f32,
+    /// Mean neighbour recall; None when there is no live collection to compare
+    pub recall: Option<f32>,
+    pub sampled: usize,
+    pub passed: bool,
+}
+
+/// Compare coverage and neighbourhoods of the target collection with the
+/// source, recording the result on the migration
+pub async fn verify_migration(
+    index: &dyn VectorIndex,
+    migration: &mut EmbeddingMigration,
+    config: &EmbeddingMigrationConfig,
+) -> Result<VerificationReport> {
+    let target_points = index.count(&migration.target_collection).await?;
+    let Some(source) = migration.source_collection.clone() else {
+        // Nothing was live, so there is nothing to regress against
+        migration.coverage = Some(1.0);
+        migration.status = MigrationStatus::Verified;
+        return Ok(VerificationReport {
+            source_points: 0,
+            target_points,
+            coverage: 1.0,
+            recall: None,
+            sampled: 0,
+            passed: true,
+        });
+    };
+
+    let source_points = index.count(&source).await?;
+    let coverage = if source_points == 0 {
+        1.0
+    } else {
+        target_points as f32 / source_points as f32
+    };
+
+    let old_vectors = index.vectors(&source, &migration.sample_ids).await?;
+    let new_vectors = index
+        .vectors(&migration.target_collection, &migration.sample_ids)
+        .await?;
+    let limit = config.recall_k as u64 + 1;
+    let mut recall_sum = 0.0;
+    let mut sampled = 0;
+    for id in &migration.sample_ids {
+        let (Some(old), Some(new)) = (old_vectors.get(id), new_vectors.get(id)) else {
+            continue;
+        };
+        let reference = index.search(&source, old.clone(), limit).await?;
+        let candidate = index
+            .search(&migration.target_collection, new.clone(), limit)
+            .await?;
+        recall_sum += neighbour_recall(*id, &reference, &candidate, config.recall_k);
+        sampled += 1;
+    }
+    let recall = (sampled > 0).then(|| recall_sum / sampled as f32);
+
+    let passed = coverage >= config.min_coverage && recall.map_or(true, |r| r >= config.min_recall);
+    migration.coverage = Some(coverage);
+    migration.recall = recall;
+    if passed {
+        migration.status = MigrationStatus::Verified;
+    } else {
+        migration.fail(format!(
+            "Verification failed: coverage {:.3} (min {:.3}), recall {} (min {:.3})",
+            coverage,
+            config.min_coverage,
+            recall.map_or("n/a".to_string(), |r| format!("{:.3}", r)),
+            config.min_recall
+        ));
+    }
+
+    Ok(VerificationReport {
+        source_points,
+        target_points,
+        coverage,
+        recall,
+        sampled,
+        passed,
+    })
+}
+
+/// Embed content and upsert it into a collection, returning the number of
+/// items that failed to embed
+async fn embed_into(
+    index: &dyn VectorIndex,
+    embedder: &dyn ContentEmbedder,
+    collection: &str,
+    items: &[StaleContent],
+) -> Result<usize> {
+    let mut points = Vec::with_capacity(items.len());
+    let mut failed = 0;
+    for item in items {
+        let mut content = item.content.clone();
+        match embedder.embed(&content).await {
+            Ok(embedding) => {
+                content.embedding = Some(embedding);
+                points.push(to_content_point(&content, item.content_id)?);
+            }
+            Err(e) => {
+                warn!("Failed to re-embed content {}: {}", item.content_id, e);
+                failed += 1;
+            }
+        }
+    }
+    if !points.is_empty() {
+        index.upsert(collection, points).await?;
+    }
+    Ok(failed)
+}
+
+/// Persistence for migration state
+#[async_trait]
+pub trait MigrationStore: Send + Sync {
+    async fn save(&self, migration: &EmbeddingMigration) -> anyhow::Result<()>;
+
+    async fn get(&self, id: Uuid) -> anyhow::Result<Option<EmbeddingMigration>>;
+
+    /// The migration currently owning an alias, if any
+    async fn active(&self, alias: &str) -> anyhow::Result<Option<EmbeddingMigration>>;
+
+    /// Most recent migrations first
+    async fn list(&self, limit: i64) -> anyhow::Result<Vec<EmbeddingMigration>>;
+}
+
+/// Postgres persistence for migration state
+pub struct EmbeddingMigrationStore {
+    pool: PgPool,
+}
+
+impl EmbeddingMigrationStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl MigrationStore for EmbeddingMigrationStore {
+    async fn save(&self, migration: &EmbeddingMigration) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO embedding_migrations (
+                id, alias, source_collection, target_collection, target_dimension,
+                status, cursor, processed, failed, sample_ids, coverage, recall,
+                error, started_at, updated_at, switched_at, completed_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, NOW(), $15, $16)
+            ON CONFLICT (id) DO UPDATE SET
+                status = EXCLUDED.status,
+                cursor = EXCLUDED.cursor,
+                processed = EXCLUDED.processed,
+                failed = EXCLUDED.failed,
+                sample_ids = EXCLUDED.sample_ids,
+                coverage = EXCLUDED.coverage,
+                recall = EXCLUDED.recall,
+                error = EXCLUDED.error,
+                updated_at = NOW(),
+                switched_at = EXCLUDED.switched_at,
+                completed_at = EXCLUDED.completed_at
+            "#,
+        )
+        .bind(migration.id)
+        .bind(&migration.alias)
+        .bind(&migration.source_collection)
+        .bind(&migration.target_collection)
+        .bind(migration.target_dimension as i64)
+        .bind(migration.status.as_str())
+        .bind(migration.cursor)
+        .bind(migration.processed)
+        .bind(migration.failed)
+        .bind(&migration.sample_ids)
+        .bind(migration.coverage)
+        .bind(migration.recall)
+        .bind(&migration.error)
+        .bind(migration.started_at)
+        .bind(migration.switched_at)
+        .bind(migration.completed_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn get(&self, id: Uuid) -> anyhow::Result<Option<EmbeddingMigration>> {
+        let row = sqlx::query("SELECT * FROM embedding_migrations WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        row.as_ref().map(migration_from_row).transpose()
+    }
+
+    async fn active(&self, alias: &str) -> anyhow::Result<Option<EmbeddingMigration>> {
+        let row = sqlx::query(
+            r#"
+            SELECT * FROM embedding_migrations
+            WHERE alias = $1
+              AND status NOT IN ('completed', 'failed', 'rolled_back')
+            ORDER BY started_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(alias)
+        .fetch_optional(&self.pool)
+        .await?;
+        row.as_ref().map(migration_from_row).transpose()
+    }
+
+    async fn list(&self, limit: i64) -> anyhow::Result<Vec<EmbeddingMigration>> {
+        let rows =
+            sqlx::query("SELECT * FROM embedding_migrations ORDER BY started_at DESC LIMIT $1")
+                .bind(limit)
+                .fetch_all(&self.pool)
+                .await?;
+        rows.iter().map(migration_from_row).collect()
+    }
+}
+
+fn migration_from_row(row: &PgRow) -> anyhow::Result<EmbeddingMigration> {
+    let status: String = row.try_get("status")?;
+    Ok(EmbeddingMigration {
+        id: row.try_get("id")?,
+        alias: row.try_get("alias")?,
+        source_collection: row.try_get("source_collection")?,
+        target_collection: row.try_get("target_collection")?,
+        target_dimension: row.try_get::<i64, _>("target_dimension")? as u64,
+        status: MigrationStatus::parse(&status)
+            .ok_or_else(|| anyhow::anyhow!("Unknown migration status: {}", status))?,
+        cursor: row.try_get("cursor")?,
+        processed: row.try_get("processed")?,
+        failed: row.try_get("failed")?,
+        sample_ids: row.try_get("sample_ids")?,
+        coverage: row.try_get("coverage")?,
+        recall: row.try_get("recall")?,
+        error: row.try_get("error")?,
+        started_at: row.try_get("started_at")?,
+        updated_at: row.try_get("updated_at")?,
+        switched_at: row.try_get("switched_at")?,
+        completed_at: row.try_get("completed_at")?,
+    })
+}
+
+/// Runs embedding migrations for one alias
+pub struct EmbeddingMigrator {
+    index: Arc<dyn VectorIndex>,
+    repository: Arc<dyn ContentRepository>,
+    embedder: Arc<dyn ContentEmbedder>,
+    store: Arc<dyn MigrationStore>,
+    config: EmbeddingMigrationConfig,
+}
+
+impl EmbeddingMigrator {
+    pub fn new(
+        index: Arc<dyn VectorIndex>,
+        repository: Arc<dyn ContentRepository>,
+        embedder: Arc<dyn ContentEmbedder>,
+        store: Arc<dyn MigrationStore>,
+        config: EmbeddingMigrationConfig,
+    ) -> Result<Self> {
+        if embedder.dimension() as u64 != config.target_dimension {
+            return Err(IngestionError::ConfigError(format!(
+                "Embedder produces {}-d vectors but the migration targets {}",
+                embedder.dimension(),
+                config.target_dimension
+            )));
+        }
+        Ok(Self {
+            index,
+            repository,
+            embedder,
+            store,
+            config,
+        })
+    }
+
+    async fn save(&self, migration: &mut EmbeddingMigration) -> Result<()> {
+        migration.updated_at = Utc::now();
+        self.store
+            .save(migration)
+            .await
+            .map_err(|e| IngestionError::DatabaseError(e.to_string()))
+    }
+
+    /// The migration currently owning the alias, if any
+    pub async fn active(&self) -> Result<Option<EmbeddingMigration>> {
+        self.store
+            .active(&self.config.alias)
+            .await
+            .map_err(|e| IngestionError::DatabaseError(e.to_string()))
+    }
+
+    /// Create the target collection and record a new migration
+    pub async fn start(&self) -> Result<EmbeddingMigration> {
+        let alias = &self.config.alias;
+        if let Some(active) = self.active().await? {
+            return Err(IngestionError::ConfigError(format!(
+                "Migration {} is already {} for alias '{}'",
+                active.id,
+                active.status.as_str(),
+                alias
+            )));
+        }
+
+        let source = match self.index.resolve_alias(alias).await? {
+            Some(collection) => Some(collection),
+            None if self.index.collection_exists(alias).await? => Some(alias.clone()),
+            None => None,
+        };
+
+        let mut migration =
+            EmbeddingMigration::new(alias, source, self.config.target_dimension, Utc::now());
+        self.index
+            .create_collection(&migration.target_collection, migration.target_dimension)
+            .await?;
+        self.save(&mut migration).await?;
+
+        info!(
+            "Started embedding migration {} for '{}': {:?} -> {} ({}d)",
+            migration.id,
+            alias,
+            migration.source_collection,
+            migration.target_collection,
+            migration.target_dimension
+        );
+        Ok(migration)
+    }
+
+    /// Re-embed the catalog into the target collection, resuming from the
+    /// migration's cursor
+    pub async fn backfill(&self, migration: &mut EmbeddingMigration) -> Result<()> {
+        while migration.status == MigrationStatus::Backfilling {
+            let started = Instant::now();
+            let page = self
+                .repository
+                .find_content_page(migration.cursor, self.config.batch_size as i64)
+                .await
+                .map_err(|e| IngestionError::DatabaseError(e.to_string()))?;
+            let Some(last) = page.last() else {
+                migration.status = MigrationStatus::Verifying;
+                self.save(migration).await?;
+                break;
+            };
+            let cursor = last.content_id;
+
+            let failed = embed_into(
+                self.index.as_ref(),
+                self.embedder.as_ref(),
+                &migration.target_collection,
+                &page,
+            )
+            .await?;
+
+            // Content ids are random UUIDs, so the first ids in id order are
+            // a uniform sample of the catalog
+            let room = self
+                .config
+                .recall_sample_size
+                .saturating_sub(migration.sample_ids.len());
+            migration
+                .sample_ids
+                .extend(page.iter().take(room).map(|item| item.content_id));
+
+            migration.cursor = Some(cursor);
+            migration.processed += page.len() as i64;
+            migration.failed += failed as i64;
+            self.save(migration).await?;
+            debug!(
+                "Migration {} backfilled {} items (cursor {})",
+                migration.id, migration.processed, cursor
+            );
+
+            tokio::time::sleep(throttle_delay(
+                page.len(),
+                self.config.max_items_per_second,
+                started.elapsed(),
+            ))
+            .await;
+        }
+        Ok(())
+    }
+
+    /// Check coverage and recall of the backfilled collection
+    pub async fn verify(&self, migration: &mut EmbeddingMigration) -> Result<VerificationReport> {
+        let report = verify_migration(self.index.as_ref(), migration, &self.config).await?;
+        self.save(migration).await?;
+        info!(
+            "Migration {} verification: coverage {:.3}, recall {:?}, passed {}",
+            migration.id, report.coverage, report.recall, report.passed
+        );
+        Ok(report)
+    }
+
+    /// Point readers at the new collection
+    pub async fn switch(&self, migration: &mut EmbeddingMigration) -> Result<()> {
+        if migration.status != MigrationStatus::Verified {
+            return Err(IngestionError::ConfigError(format!(
+                "Migration {} is {}, not verified",
+                migration.id,
+                migration.status.as_str()
+            )));
+        }
+
+        if migration.source_is_legacy() {
+            // A collection and an alias cannot share a name; this one-time
+            // cutover leaves the name unresolvable between the two calls
+            warn!(
+                "Replacing collection '{}' with an alias; its vectors cannot be restored by rollback",
+                migration.alias
+            );
+            self.index.delete_collection(&migration.alias).await?;
+        }
+        self.index
+            .point_alias(&migration.alias, &migration.target_collection)
+            .await?;
+
+        migration.status = MigrationStatus::Switched;
+        migration.switched_at = Some(Utc::now());
+        self.save(migration).await?;
+        info!(
+            "Alias '{}' now points to '{}'",
+            migration.alias, migration.target_collection
+        );
+        Ok(())
+    }
+
+    /// Delete the old collection, ending the migration
+    pub async fn complete(&self, migration: &mut EmbeddingMigration) -> Result<()> {
+        if migration.status != MigrationStatus::Switched {
+            return Err(IngestionError::ConfigError(format!(
+                "Migration {} is {}, not switched",
+                migration.id,
+                migration.status.as_str()
+            )));
+        }
+
+        if let Some(source) = migration.source_collection.as_deref() {
+            if !migration.source_is_legacy() && self.index.collection_exists(source).await? {
+                self.index.delete_collection(source).await?;
+            }
+        }
+
+        migration.status = MigrationStatus::Completed;
+        migration.completed_at = Some(Utc::now());
+        self.save(migration).await?;
+        info!("Embedding migration {} completed", migration.id);
+        Ok(())
+    }
+
+    /// Abandon a migration, pointing readers back at the old collection and
+    /// dropping the new one
+    pub async fn rollback(&self, migration: &mut EmbeddingMigration) -> Result<()> {
+        if matches!(
+            migration.status,
+            MigrationStatus::Completed | MigrationStatus::RolledBack
+        ) {
+            return Err(IngestionError::ConfigError(format!(
+                "Migration {} is already {}",
+                migration.id,
+                migration.status.as_str()
+            )));
+        }
+
+        if migration.status == MigrationStatus::Switched {
+            match migration.source_collection.as_deref() {
+                Some(source) if !migration.source_is_legacy() => {
+                    self.index.point_alias(&migration.alias, source).await?;
+                }
+                _ => {
+                    return Err(IngestionError::ConfigError(format!(
+                        "Migration {} has no previous collection to restore",
+                        migration.id
+                    )));
+                }
+            }
+        }
+        if self
+            .index
+            .collection_exists(&migration.target_collection)
+            .await?
+        {
+            self.index
+                .delete_collection(&migration.target_collection)
+                .await?;
+        }
+
+        migration.status = MigrationStatus::RolledBack;
+        self.save(migration).await?;
+        info!("Embedding migration {} rolled back", migration.id);
+        Ok(())
+    }
+
+    /// Start or resume the alias's migration and drive it up to the switch;
+    /// the old collection is kept until `complete` is called
+    pub async fn run(&self) -> Result<EmbeddingMigration> {
+        let mut migration = match self.active().await? {
+            Some(migration) => migration,
+            None => self.start().await?,
+        };
+
+        if let Err(e) = self.backfill(&mut migration).await {
+            migration.fail(e.to_string());
+            self.save(&mut migration).await?;
+            return Err(e);
+        }
+        if migration.status == MigrationStatus::Verifying {
+            self.verify(&mut migration).await?;
+        }
+        if migration.status == MigrationStatus::Verified {
+            self.switch(&mut migration).await?;
+        }
+        Ok(migration)
+    }
+}
+
+#[derive(Clone)]
+struct DualWriteTarget {
+    collection: String,
+    dimension: u64,
+    embedder: Arc<dyn ContentEmbedder>,
+}
+
+/// Mirrors ingested content into the target collection of an in-flight
+/// migration, so items changed during the backfill are not left stale
+pub struct DualWriter {
+    index: Arc<dyn VectorIndex>,
+    store: Arc<dyn MigrationStore>,
+    /// Embedder for the target collection; generated at the target
+    /// dimension when unset
+    embedder: Option<Arc<dyn ContentEmbedder>>,
+    alias: String,
+    target: RwLock<Option<(Instant, Option<DualWriteTarget>)>>,
+}
+
+impl DualWriter {
+    pub fn new(
+        index: Arc<dyn VectorIndex>,
+        store: Arc<dyn MigrationStore>,
+        embedder: Arc<dyn ContentEmbedder>,
+        alias: impl Into<String>,
+    ) -> Self {
+        Self {
+            index,
+            store,
+            embedder: Some(embedder),
+            alias: alias.into(),
+            target: RwLock::new(None),
+        }
+    }
+
+    /// Dual writer embedding with an `EmbeddingGenerator` at whatever
+    /// dimension the active migration targets
+    pub fn at_target_dimension(
+        index: Arc<dyn VectorIndex>,
+        store: Arc<dyn MigrationStore>,
+        alias: impl Into<String>,
+    ) -> Self {
+        Self {
+            index,
+            store,
+            embedder: None,
+            alias: alias.into(),
+            target: RwLock::new(None),
+        }
+    }
+
+    async fn target(&self) -> Result<Option<DualWriteTarget>> {
+        if let Some((fetched_at, target)) = self.target.read().unwrap().as_ref() {
+            if fetched_at.elapsed() < DUAL_WRITE_REFRESH {
+                return Ok(target.clone());
+            }
+        }
+
+        let target = self
+            .store
+            .active(&self.alias)
+            .await
+            .map_err(|e| IngestionError::DatabaseError(e.to_string()))?
+            .filter(|m| m.status.is_dual_writing())
+            .map(|m| DualWriteTarget {
+                embedder: self.embedder.clone().unwrap_or_else(|| {
+                    Arc::new(EmbeddingGenerator::with_dimension(
+                        m.target_dimension as usize,
+                    ))
+                }),
+                collection: m.target_collection,
+                dimension: m.target_dimension,
+            });
+        *self.target.write().unwrap() = Some((Instant::now(), target.clone()));
+        Ok(target)
+    }
+
+    /// Write items to the migration target, if a migration is backfilling;
+    /// returns the number of items written
+    pub async fn write(&self, items: &[StaleContent]) -> Result<usize> {
+        if items.is_empty() {
+            return Ok(0);
+        }
+        let Some(target) = self.target().await? else {
+            return Ok(0);
+        };
+        if target.embedder.dimension() as u64 != target.dimension {
+            warn!(
+                "Skipping dual write to '{}': embedder produces {}-d vectors, collection expects {}",
+                target.collection,
+                target.embedder.dimension(),
+                target.dimension
+            );
+            return Ok(0);
+        }
+
+        let failed = embed_into(
+            self.index.as_ref(),
+            target.embedder.as_ref(),
+            &target.collection,
+            items,
+        )
+        .await?;
+        Ok(items.len() - failed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::normalizer::{AvailabilityInfo, ContentType, ImageSet};
+    use crate::repository::{ExpiringContent, LowQualityContentItem};
+    use std::sync::Mutex;
+
+    /// In-memory index: brute-force cosine search over stored vectors
+    #[derive(Default)]
+    struct MemoryIndex {
+        collections: Mutex<HashMap<String, HashMap<Uuid, Vec<f32>>>>,
+        aliases: Mutex<HashMap<String, String>>,
+    }
+
+    impl MemoryIndex {
+        fn insert(&self, collection: &str, id: Uuid, vector: Vec<f32>) {
+            self.collections
+                .lock()
+                .unwrap()
+                .entry(collection.to_string())
+                .or_default()
+                .insert(id, vector);
+        }
+    }
+
+    fn cosine(a: &[f32], b: &[f32]) -> f32 {
+        let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+        let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+        dot / (norm(a) * norm(b)).max(f32::EPSILON)
+    }
+
+    #[async_trait]
+    impl VectorIndex for MemoryIndex {
+        async fn collection_exists(&self, collection: &str) -> Result<bool> {
+            Ok(self.collections.lock().unwrap().contains_key(collection))
+        }
+
+        async fn create_collection(&self, collection: &str, _dimension: u64) -> Result<()> {
+            self.collections
+                .lock()
+                .unwrap()
+                .insert(collection.to_string(), HashMap::new());
+            Ok(())
+        }
+
+        async fn delete_collection(&self, collection: &str) -> Result<()> {
+            self.collections.lock().unwrap().remove(collection);
+            Ok(())
+        }
+
+        async fn resolve_alias(&self, alias: &str) -> Result<Option<String>> {
+            Ok(self.aliases.lock().unwrap().get(alias).cloned())
+        }
+
+        async fn point_alias(&self, alias: &str, collection: &str) -> Result<()> {
+            self.aliases
+                .lock()
+                .unwrap()
+                .insert(alias.to_string(), collection.to_string());
+            Ok(())
+        }
+
+        async fn upsert(&self, collection: &str, points: Vec<ContentPoint>) -> Result<()> {
+            for point in points {
+                self.insert(collection, point.id, point.vector);
+            }
+            Ok(())
+        }
+
+        async fn count(&self, collection: &str) -> Result<u64> {
+            Ok(self
+                .collections
+                .lock()
+                .unwrap()
+                .get(collection)
+                .map_or(0, |c| c.len() as u64))
+        }
+
+        async fn vectors(&self, collection: &str, ids: &[Uuid]) -> Result<HashMap<Uuid, Vec<f32>>> {
+            let collections = self.collections.lock().unwrap();
+            let points = collections.get(collection).cloned().unwrap_or_default();
+            Ok(ids
+                .iter()
+                .filter_map(|id| points.get(id).map(|v| (*id, v.clone())))
+                .collect())
+        }
+
+        async fn search(
+            &self,
+            collection: &str,
+            vector: Vec<f32>,
+            limit: u64,
+        ) -> Result<Vec<Uuid>> {
+            let collections = self.collections.lock().unwrap();
+            let mut scored: Vec<(Uuid, f32)> = collections
+                .get(collection)
+                .map(|c| c.iter().map(|(id, v)| (*id, cosine(&vector, v))).collect())
+                .unwrap_or_default();
+            scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+            Ok(scored
+                .into_iter()
+                .take(limit as usize)
+                .map(|(id, _)| id)
+                .collect())
+        }
+    }
+
+    #[derive(Default)]
+    struct MemoryStore {
+        migrations: Mutex<HashMap<Uuid, EmbeddingMigration>>,
+    }
+
+    #[async_trait]
+    impl MigrationStore for MemoryStore {
+        async fn save(&self, migration: &EmbeddingMigration) -> anyhow::Result<()> {
+            self.migrations
+                .lock()
+                .unwrap()
+                .insert(migration.id, migration.clone());
+            Ok(())
+        }
+
+        async fn get(&self, id: Uuid) -> anyhow::Result<Option<EmbeddingMigration>> {
+            Ok(self.migrations.lock().unwrap().get(&id).cloned())
+        }
+
+        async fn active(&self, alias: &str) -> anyhow::Result<Option<EmbeddingMigration>> {
+            Ok(self
+                .migrations
+                .lock()
+                .unwrap()
+                .values()
+                .filter(|m| m.alias == alias && m.status.is_active())
+                .max_by_key(|m| m.started_at)
+                .cloned())
+        }
+
+        async fn list(&self, limit: i64) -> anyhow::Result<Vec<EmbeddingMigration>> {
+            let mut migrations: Vec<_> =
+                self.migrations.lock().unwrap().values().cloned().collect();
+            migrations.sort_by_key(|m| std::cmp::Reverse(m.started_at));
+            migrations.truncate(limit as usize);
+            Ok(migrations)
+        }
+    }
+
+    /// Catalog that only supports paging
+    struct Catalog(Vec<StaleContent>);
+
+    #[async_trait]
+    impl ContentRepository for Catalog {
+        async fn upsert(&self, _content: &CanonicalContent) -> anyhow::Result<Uuid> {
+            unimplemented!()
+        }
+
+        async fn upsert_batch(&self, _items: &[CanonicalContent]) -> anyhow::Result<Vec<Uuid>> {
+            unimplemented!()
+        }
+
+        async fn find_by_platform_id(
+            &self,
+            _platform_content_id: &str,
+            _platform: &str,
+        ) -> anyhow::Result<Option<Uuid>> {
+            unimplemented!()
+        }
+
+        async fn update_availability(
+            &self,
+            _content_id: Uuid,
+            _platform: &str,
+            _region: &str,
+            _available: bool,
+            _expires_at: Option<DateTime<Utc>>,
+        ) -> anyhow::Result<()> {
+            unimplemented!()
+        }
+
+        async fn find_expiring_within(
+            &self,
+            _duration: chrono::Duration,
+        ) -> anyhow::Result<Vec<ExpiringContent>> {
+            unimplemented!()
+        }
+
+        async fn find_stale_embeddings(
+            &self,
+            _threshold: DateTime<Utc>,
+        ) -> anyhow::Result<Vec<StaleContent>> {
+            unimplemented!()
+        }
+
+        async fn find_content_page(
+            &self,
+            after: Option<Uuid>,
+            limit: i64,
+        ) -> anyhow::Result<Vec<StaleContent>> {
+            Ok(self
+                .0
+                .iter()
+                .filter(|item| after.map_or(true, |after| item.content_id > after))
+                .take(limit as usize)
+                .cloned()
+                .collect())
+        }
+
+        async fn update_embedding(
+            &self,
+            _content_id: Uuid,
+            _embedding: &[f32],
+        ) -> anyhow::Result<()> {
+            unimplemented!()
+        }
+
+        async fn update_quality_score(
+            &self,
+            _content_id: Uuid,
+            _quality_score: f64,
+        ) -> anyhow::Result<()> {
+            unimplemented!()
+        }
+
+        async fn find_low_quality_content(
+            &self,
+            _threshold: f32,
+            _limit: i64,
+        ) -> anyhow::Result<Vec<LowQualityContentItem>> {
+            unimplemented!()
+        }
+    }
+
+    const DIM: u64 = 4;
+
+    fn item(n: u128) -> StaleContent {
+        let genre = if n % 2 == 0 { "Drama" } else { "Action" };
+        StaleContent {
+            content_id: Uuid::from_u128(n),
+            content: CanonicalContent {
+                platform_content_id: format!("title-{}", n),
+                platform_id: "netflix".to_string(),
+                entity_id: None,
+                title: format!("Title {}", n),
+                overview: None,
+                content_type: ContentType::Movie,
+                release_year: Some(1990 + n as i32),
+                runtime_minutes: None,
+                genres: vec![genre.to_string()],
+                external_ids: HashMap::new(),
+                availability: AvailabilityInfo {
+                    regions: vec![],
+                    subscription_required: false,
+                    purchase_price: None,
+                    rental_price: None,
+                    currency: None,
+                    available_from: None,
+                    available_until: None,
+                },
+                images: ImageSet::default(),
+                rating: None,
+                user_rating: None,
+                embedding: None,
+                updated_at: Utc::now(),
+            },
+        }
+    }
+
+    /// A live collection behind the alias holding the catalog at the target
+    /// dimension, so recall against it is perfect
+    async fn setup(
+        items: usize,
+    ) -> (
+        Arc<MemoryIndex>,
+        Arc<MemoryStore>,
+        EmbeddingMigrator,
+        Vec<StaleContent>,
+    ) {
+        let catalog: Vec<StaleContent> = (1..=items as u128).map(item).collect();
+        let index = Arc::new(MemoryIndex::default());
+        let embedder = Arc::new(EmbeddingGenerator::with_dimension(DIM as usize));
+        index.create_collection("live", DIM).await.unwrap();
+        embed_into(index.as_ref(), embedder.as_ref(), "live", &catalog)
+            .await
+            .unwrap();
+        index.point_alias(DEFAULT_ALIAS, "live").await.unwrap();
+
+        let store = Arc::new(MemoryStore::default());
+        let config = EmbeddingMigrationConfig {
+            target_dimension: DIM,
+            batch_size: 2,
+            max_items_per_second: 0.0,
+            ..Default::default()
+        };
+        let migrator = EmbeddingMigrator::new(
+            index.clone(),
+            Arc::new(Catalog(catalog.clone())),
+            embedder,
+            store.clone(),
+            config,
+        )
+        .unwrap();
+        (index, store, migrator, catalog)
+    }
+
+    fn migration(source: Option<&str>) -> EmbeddingMigration {
+        EmbeddingMigration::new(DEFAULT_ALIAS, source.map(str::to_string), 4, Utc::now())
+    }
+
+    #[test]
+    fn test_status_round_trip() {
+        for status in [
+            MigrationStatus::Backfilling,
+            MigrationStatus::Verifying,
+            MigrationStatus::Verified,
+            MigrationStatus::Switched,
+            MigrationStatus::Completed,
+            MigrationStatus::Failed,
+            MigrationStatus::RolledBack,
+        ] {
+            assert_eq!(MigrationStatus::parse(status.as_str()), Some(status));
+        }
+        assert!(MigrationStatus::Verified.is_dual_writing());
+        assert!(!MigrationStatus::Switched.is_dual_writing());
+        assert!(MigrationStatus::Switched.is_active());
+        assert!(!MigrationStatus::Failed.is_active());
+    }
+
+    #[test]
+    fn test_target_collection_name() {
+        let started = DateTime::parse_from_rfc3339("2024-03-01T12:30:05Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            target_collection_name("media_embeddings", 512, started),
+            "media_embeddings_512d_20240301123005"
+        );
+    }
+
+    #[test]
+    fn test_neighbour_recall_ignores_query() {
+        let ids: Vec<Uuid> = (0..5).map(|_| Uuid::new_v4()).collect();
+        let query = ids[0];
+        let reference = vec![query, ids[1], ids[2]];
+        let candidate = vec![ids[2], query, ids[3]];
+        assert!((neighbour_recall(query, &reference, &candidate, 2) - 0.5).abs() < 1e-6);
+        assert_eq!(neighbour_recall(query, &[query], &candidate, 2), 1.0);
+    }
+
+    #[test]
+    fn test_throttle_delay() {
+        assert_eq!(
+            throttle_delay(100, 200.0, Duration::from_millis(100)),
+            Duration::from_millis(400)
+        );
+        assert_eq!(
+            throttle_delay(100, 200.0, Duration::from_secs(1)),
+            Duration::ZERO
+        );
+        assert_eq!(throttle_delay(100, 0.0, Duration::ZERO), Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_verification_compares_neighbourhoods() {
+        let index = MemoryIndex::default();
+        let ids: Vec<Uuid> = (0..6).map(|_| Uuid::new_v4()).collect();
+        // Two tight clusters; the new space keeps them apart at another scale
+        for (i, id) in ids.iter().enumerate() {
+            let cluster = if i < 3 { 1.0 } else { -1.0 };
+            let jitter = i as f32 * 0.01;
+            index.insert("old", *id, vec![cluster, jitter, 0.0]);
+            index.insert("new", *id, vec![cluster * 2.0, 0.0, jitter, 0.0]);
+        }
+
+        let config = EmbeddingMigrationConfig {
+            recall_k: 2,
+            min_recall: 0.9,
+            ..Default::default()
+        };
+        let mut good = migration(Some("old"));
+        good.target_collection = "new".to_string();
+        good.sample_ids = ids.clone();
+        let report = verify_migration(&index, &mut good, &config).await.unwrap();
+        assert!(report.passed);
+        assert_eq!(report.sampled, 6);
+        assert!((report.recall.unwrap() - 1.0).abs() < 1e-6);
+        assert_eq!(good.status, MigrationStatus::Verified);
+
+        // Missing half the points fails coverage
+        index.insert("partial", ids[0], vec![1.0, 0.0, 0.0, 0.0]);
+        let mut partial = migration(Some("old"));
+        partial.target_collection = "partial".to_string();
+        partial.sample_ids = ids;
+        let report = verify_migration(&index, &mut partial, &config)
+            .await
+            .unwrap();
+        assert!(!report.passed);
+        assert_eq!(partial.status, MigrationStatus::Failed);
+        assert!(partial.error.unwrap().contains("coverage"));
+    }
+
+    #[tokio::test]
+    async fn test_verification_without_live_collection_passes() {
+        let index = MemoryIndex::default();
+        let mut fresh = migration(None);
+        index
+            .create_collection(&fresh.target_collection, 4)
+            .await
+            .unwrap();
+        let report = verify_migration(&index, &mut fresh, &EmbeddingMigrationConfig::default())
+            .await
+            .unwrap();
+        assert!(report.passed);
+        assert!(report.recall.is_none());
+        assert_eq!(fresh.status, MigrationStatus::Verified);
+    }
+
+    #[tokio::test]
+    async fn test_backfill_resumes_from_cursor() {
+        let (index, store, migrator, catalog) = setup(5).await;
+        let mut migration = migrator.start().await.unwrap();
+        assert_eq!(migration.source_collection.as_deref(), Some("live"));
+        assert!(migrator.start().await.is_err());
+
+        // Interrupted after the first page
+        migration.cursor = Some(catalog[1].content_id);
+        migration.processed = 2;
+        migrator.backfill(&mut migration).await.unwrap();
+
+        assert_eq!(migration.status, MigrationStatus::Verifying);
+        assert_eq!(migration.processed, 5);
+        assert_eq!(migration.cursor, Some(catalog[4].content_id));
+        assert_eq!(index.count(&migration.target_collection).await.unwrap(), 3);
+        assert_eq!(
+            store.get(migration.id).await.unwrap().unwrap().status,
+            MigrationStatus::Verifying
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_switches_and_complete_drops_old_collection() {
+        let (index, store, migrator, _) = setup(5).await;
+        let mut migration = migrator.run().await.unwrap();
+
+        assert_eq!(migration.status, MigrationStatus::Switched);
+        assert_eq!(migration.processed, 5);
+        assert_eq!(migration.sample_ids.len(), 5);
+        assert_eq!(migration.recall, Some(1.0));
+        assert_eq!(
+            index.resolve_alias(DEFAULT_ALIAS).await.unwrap(),
+            Some(migration.target_collection.clone())
+        );
+        assert!(index.collection_exists("live").await.unwrap());
+
+        migrator.complete(&mut migration).await.unwrap();
+        assert_eq!(migration.status, MigrationStatus::Completed);
+        assert!(!index.collection_exists("live").await.unwrap());
+        assert!(migrator.complete(&mut migration).await.is_err());
+        assert!(store.active(DEFAULT_ALIAS).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_switch_requires_verification() {
+        let (index, _, migrator, _) = setup(3).await;
+        let mut migration = migrator.start().await.unwrap();
+        assert!(migrator.switch(&mut migration).await.is_err());
+
+        migrator.backfill(&mut migration).await.unwrap();
+        assert!(migrator.switch(&mut migration).await.is_err());
+
+        migrator.verify(&mut migration).await.unwrap();
+        migrator.switch(&mut migration).await.unwrap();
+        assert_eq!(migration.status, MigrationStatus::Switched);
+        assert_eq!(
+            index.resolve_alias(DEFAULT_ALIAS).await.unwrap(),
+            Some(migration.target_collection)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rollback_restores_live_collection() {
+        let (index, _, migrator, _) = setup(3).await;
+        let mut migration = migrator.run().await.unwrap();
+        assert_eq!(migration.status, MigrationStatus::Switched);
+
+        migrator.rollback(&mut migration).await.unwrap();
+        assert_eq!(migration.status, MigrationStatus::RolledBack);
+        assert_eq!(
+            index.resolve_alias(DEFAULT_ALIAS).await.unwrap(),
+            Some("live".to_string())
+        );
+        assert!(!index
+            .collection_exists(&migration.target_collection)
+            .await
+            .unwrap());
+        assert!(migrator.rollback(&mut migration).await.is_err());
+
+        // The alias is free for a new migration
+        assert!(migrator.start().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_dual_writer_mirrors_while_backfilling() {
+        let (index, store, migrator, catalog) = setup(3).await;
+        let mut migration = migrator.start().await.unwrap();
+
+        let writer = DualWriter::at_target_dimension(index.clone(), store.clone(), DEFAULT_ALIAS);
+        assert_eq!(writer.write(&catalog[..2]).await.unwrap(), 2);
+        assert_eq!(index.count(&migration.target_collection).await.unwrap(), 2);
+
+        // An embedder at the wrong dimension is skipped
+        let mismatched = DualWriter::new(
+            index.clone(),
+            store.clone(),
+            Arc::new(EmbeddingGenerator::with_dimension(8)),
+            DEFAULT_ALIAS,
+        );
+        assert_eq!(mismatched.write(&catalog).await.unwrap(), 0);
+
+        // Nothing is mirrored once readers have switched
+        migrator.backfill(&mut migration).await.unwrap();
+        migrator.verify(&mut migration).await.unwrap();
+        migrator.switch(&mut migration).await.unwrap();
+        let writer = DualWriter::at_target_dimension(index, store, DEFAULT_ALIAS);
+        assert_eq!(writer.write(&catalog).await.unwrap(), 0);
+    }
+}
@@ -4,6 +4,7 @@ use anyhow::{Context, Result};
 use async_trait::async_trait;
 use chrono::{DateTime, Duration, Utc};
 use serde_json::json;
+use sqlx::postgres::PgRow;
 use sqlx::{PgPool, Postgres, Row, Transaction};
 use uuid::Uuid;
 
@@ -41,6 +42,10 @@ pub trait ContentRepository: Send + Sync {
     /// Find content with stale embeddings (older than threshold)
     async fn find_stale_embeddings(&self, threshold: DateTime<Utc>) -> Result<Vec<StaleContent>>;
 
+    /// Page through all content in id order, starting after `after`
+    async fn find_content_page(&self, after: Option<Uuid>, limit: i64)
+        -> Result<Vec<StaleContent>>;
+
     /// Update embedding for content
     async fn update_embedding(&self, content_id: Uuid, embedding: &[f32]) -> Result<()>;
 
@@ -125,6 +130,117 @@ impl PostgresContentRepository {
         })
     }
 
+    /// Build the canonical form of a content row selected with the columns of
+    /// `find_stale_embeddings`, loading genres and identifiers alongside
+    async fn stale_content_from_row(&self, row: &PgRow) -> StaleContent {
+        let content_id: Uuid = row.get("id");
+        let title: String = row.get("title");
+        let content_type: String = row.get("content_type");
+        let _original_title: String = row.get("original_title");
+        let overview: Option<String> = row.get("overview");
+        let platform: String = row.get("platform");
+        let release_year: Option<i32> = row.get("release_year");
+        let runtime_minutes: Option<i32> = row.get("runtime_minutes");
+        let rating: Option<String> = row.get("rating");
+        let average_rating: Option<f64> = row.get("average_rating");
+        let last_updated: DateTime<Utc> = row.get("last_updated");
+        // Fetch genres
+        let genre_rows = sqlx::query("SELECT genre FROM content_genres WHERE content_id = $1")
+            .bind(content_id)
+            .fetch_all(&self.pool)
+            .await
+            .unwrap_or_default();
+
+        let genres: Vec<String> = genre_rows.iter().map(|row| row.get("genre")).collect();
+
+        // Fetch external IDs
+        let external_ids_row = sqlx::query(
+            "SELECT eidr_id, imdb_id, tmdb_id, tvdb_id, gracenote_tms_id FROM external_ids WHERE content_id = $1"
+        )
+        .bind(content_id)
+        .fetch_optional(&self.pool)
+        .await
+        .unwrap_or(None);
+
+        let mut external_ids = std::collections::HashMap::new();
+        if let Some(row) = external_ids_row {
+            if let Some(e) = row.try_get::<Option<String>, _>("eidr_id").ok().flatten() {
+                external_ids.insert("eidr".to_string(), e);
+            }
+            if let Some(i) = row.try_get::<Option<String>, _>("imdb_id").ok().flatten() {
+                external_ids.insert("imdb".to_string(), i);
+            }
+            if let Some(t) = row.try_get::<Option<i32>, _>("tmdb_id").ok().flatten() {
+                external_ids.insert("tmdb".to_string(), t.to_string());
+            }
+            if let Some(t) = row.try_get::<Option<i32>, _>("tvdb_id").ok().flatten() {
+                external_ids.insert("tvdb".to_string(), t.to_string());
+            }
+            if let Some(g) = row
+                .try_get::<Option<String>, _>("gracenote_tms_id")
+                .ok()
+                .flatten()
+            {
+                external_ids.insert("gracenote".to_string(), g);
+            }
+        }
+
+        // Fetch platform content ID
+        let platform_content_id = sqlx::query(
+            "SELECT platform_content_id FROM platform_ids WHERE content_id = $1 AND platform = $2",
+        )
+        .bind(content_id)
+        .bind(&platform)
+        .fetch_optional(&self.pool)
+        .await
+        .unwrap_or(None)
+        .and_then(|row| row.try_get::<String, _>("platform_content_id").ok())
+        .unwrap_or_else(|| content_id.to_string());
+
+        // Parse content type
+        let parsed_content_type = match content_type.as_str() {
+            "movie" => ContentType::Movie,
+            "series" => ContentType::Series,
+            "episode" => ContentType::Episode,
+            "short" => ContentType::Short,
+            "documentary" => ContentType::Documentary,
+            _ => ContentType::Movie,
+        };
+
+        // Build CanonicalContent
+        let canonical = CanonicalContent {
+            platform_content_id,
+            platform_id: platform,
+            entity_id: None,
+            title,
+            overview,
+            content_type: parsed_content_type,
+            release_year,
+            runtime_minutes,
+            genres,
+            external_ids,
+            availability: AvailabilityInfo {
+                regions: vec![],
+                subscription_required: false,
+                purchase_price: None,
+                rental_price: None,
+                currency: None,
+                available_from: None,
+                available_until: None,
+            },
+            images: ImageSet::default(),
+            rating,
+            user_rating: average_rating.map(|r| r as f32),
+            embedding: None,
+            updated_at: last_updated,
+        };
+
+        StaleContent {
+            content_id,
+            content: canonical,
+        }
+    }
+
     /// Upsert a single content item within a transaction
     async fn upsert_in_transaction(
         tx: &mut Transaction<'_, Postgres>,
@@ -557,119 +673,53 @@ impl ContentRepository for PostgresContentRepository {
         .await
         .context("Failed to find stale embeddings")?;
 
-        // Convert to StaleContent with CanonicalContent
-        let mut stale_items = Vec::new();
-
-        for row in rows {
-            let content_id: Uuid = row.get("id");
-            let title: String = row.get("title");
-            let content_type: String = row.get("content_type");
-            let _original_title: String = row.get("original_title");
-            let overview: Option<String> = row.get("overview");
-            let platform: String = row.get("platform");
-            let release_year: Option<i32> = row.get("release_year");
-            let runtime_minutes: Option<i32> = row.get("runtime_minutes");
-            let rating: Option<String> = row.get("rating");
-            let average_rating: Option<f64> = row.get("average_rating");
-            let last_updated: DateTime<Utc> = row.get("last_updated");
-            // Fetch genres
-            let genre_rows = sqlx::query("SELECT genre FROM content_genres WHERE content_id = $1")
-                .bind(content_id)
-                .fetch_all(&self.pool)
-                .await
-                .unwrap_or_default();
-
-            let genres: Vec<String> = genre_rows.iter().map(|row| row.get("genre")).collect();
-
-            // Fetch external IDs
-            let external_ids_row = sqlx::query(
-                "SELECT eidr_id, imdb_id, tmdb_id, tvdb_id, gracenote_tms_id FROM external_ids WHERE content_id = $1"
-            )
-            .bind(content_id)
-            .fetch_optional(&self.pool)
-            .await
-            .unwrap_or(None);
-
-            let mut external_ids = std::collections::HashMap::new();
-            if let Some(row) = external_ids_row {
-                if let Some(e) = row.try_get::<Option<String>, _>("eidr_id").ok().flatten() {
-                    external_ids.insert("eidr".to_string(), e);
-                }
-                if let Some(i) = row.try_get::<Option<String>, _>("imdb_id").ok().flatten() {
-                    external_ids.insert("imdb".to_string(), i);
-                }
-                if let Some(t) = row.try_get::<Option<i32>, _>("tmdb_id").ok().flatten() {
-                    external_ids.insert("tmdb".to_string(), t.to_string());
-                }
-                if let Some(t) = row.try_get::<Option<i32>, _>("tvdb_id").ok().flatten() {
-                    external_ids.insert("tvdb".to_string(), t.to_string());
-                }
-                if let Some(g) = row
-                    .try_get::<Option<String>, _>("gracenote_tms_id")
-                    .ok()
-                    .flatten()
-                {
-                    external_ids.insert("gracenote".to_string(), g);
-                }
-            }
-
-            // Fetch platform content ID
-            let platform_content_id = sqlx::query(
-                "SELECT platform_content_id FROM platform_ids WHERE content_id = $1 AND platform = $2"
-            )
-            .bind(content_id)
-            .bind(&platform)
-            .fetch_optional(&self.pool)
-            .await
-            .unwrap_or(None)
-            .and_then(|row| row.try_get::<String, _>("platform_content_id").ok())
-            .unwrap_or_else(|| content_id.to_string());
+        let mut stale_items = Vec::with_capacity(rows.len());
+        for row in &rows {
+            stale_items.push(self.stale_content_from_row(row).await);
+        }
 
-            // Parse content type
-            let parsed_content_type = match content_type.as_str() {
-                "movie" => ContentType::Movie,
-                "series" => ContentType::Series,
-                "episode" => ContentType::Episode,
-                "short" => ContentType::Short,
-                "documentary" => ContentType::Documentary,
-                _ => ContentType::Movie,
-            };
+        Ok(stale_items)
+    }
 
-            // Build CanonicalContent
-            let canonical = CanonicalContent {
-                platform_content_id,
-                platform_id: platform,
-                entity_id: None,
-                title,
-                overview,
-                content_type: parsed_content_type,
-                release_year,
-                runtime_minutes,
-                genres,
-                external_ids,
-                availability: AvailabilityInfo {
-                    regions: vec![],
-                    subscription_required: false,
-                    purchase_price: None,
-                    rental_price: None,
-                    currency: None,
-                    available_from: None,
-                    available_until: None,
-                },
-                images: ImageSet::default(),
-                rating,
-                user_rating: average_rating.map(|r| r as f32),
-                embedding: None,
-                updated_at: last_updated,
-            };
+    async fn find_content_page(
+        &self,
+        after: Option<Uuid>,
+        limit: i64,
+    ) -> Result<Vec<StaleContent>> {
+        // DISTINCT ON keeps one platform per content so the id cursor is stable
+        let rows = sqlx::query(
+            r#"
+            SELECT DISTINCT ON (c.id)
+                c.id,
+                c.title,
+                c.content_type,
+                COALESCE(c.original_title, c.title) as original_title,
+                c.overview,
+                COALESCE(pi.platform, 'unknown') as platform,
+                EXTRACT(YEAR FROM c.release_date)::integer as release_year,
+                c.runtime_minutes,
+                c.rating,
+                c.average_rating,
+                c.last_updated
+            FROM content c
+            LEFT JOIN platform_ids pi ON pi.content_id = c.id
+            WHERE $1::uuid IS NULL OR c.id > $1
+            ORDER BY c.id, pi.platform
+            LIMIT $2
+            "#,
+        )
+        .bind(after)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to fetch content page")?;
 
-            stale_items.push(StaleContent {
-                content_id,
-                content: canonical,
-            });
+        let mut items = Vec::with_capacity(rows.len());
+        for row in &rows {
+            items.push(self.stale_content_from_row(row).await);
         }
 
-        Ok(stale_items)
+        Ok(items)
     }
 
     async fn update_embedding(&self, content_id: Uuid, embedding: &[f32]) -> Result<()> {
@@ -103,6 +103,7 @@ async fn handle_tools_list(id: RequestId) -> JsonRpcResponse {
         crate::tools::GetContentDetailsTool::definition(),
         crate::tools::SyncWatchlistTool::definition(),
         crate::tools::ListDevicesTool::definition(),
+        crate::tools::ConversationalSearchTool::definition(),
     ];
 
     let result = ToolListResult { tools };
@@ -147,6 +148,7 @@ async fn handle_tools_call(
         )),
         "sync_watchlist" => Box::new(crate::tools::SyncWatchlistTool::new(state.db_pool.clone())),
         "list_devices" => Box::new(crate::tools::ListDevicesTool::new(state.db_pool.clone())),
+        "conversational_search" => Box::new(crate::tools::ConversationalSearchTool::from_env()),
         _ => {
             return JsonRpcResponse::error(
                 id,
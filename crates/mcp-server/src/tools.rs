@@ -595,3 +595,165 @@ impl ToolExecutor for ListDevicesTool {
         })
     }
 }
+
+/// Conversational search tool
+///
+/// Runs multi-turn search against the discovery service's session API so
+/// agents can refine a running search ("only on Netflix", "not that one",
+/// "more like the second one") by passing back the returned `session_id`.
+pub struct ConversationalSearchTool {
+    client: reqwest::Client,
+    discovery_url: String,
+}
+
+impl ConversationalSearchTool {
+    pub fn new(discovery_url: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            discovery_url: discovery_url.into(),
+        }
+    }
+
+    /// Create tool using DISCOVERY_SERVICE_URL (default: http://localhost:8081)
+    pub fn from_env() -> Self {
+        Self::new(
+            std::env::var("DISCOVERY_SERVICE_URL")
+                .unwrap_or_else(|_| "http://localhost:8081".to_string()),
+        )
+    }
+
+    pub fn definition() -> Tool {
+        Tool {
+            name: "conversational_search".to_string(),
+            description: "Multi-turn content search. Start a session with a query, then pass \
+                          the returned session_id with follow-ups such as 'only on netflix', \
+                          'without horror', 'not that one' or 'more like the second one'"
+                .to_string(),
+            input_schema: Some(json!({
+                "type": "object",
+                "properties": {
+                    "query": {
+                        "type": "string",
+                        "description": "Initial query or follow-up refinement"
+                    },
+                    "session_id": {
+                        "type": "string",
+                        "description": "Session ID returned by a previous call (omit to start a new session)"
+                    },
+                    "user_id": {
+                        "type": "string",
+                        "description": "Optional user ID for personalized results"
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "description": "Maximum number of results",
+                        "default": 10
+                    }
+                },
+                "required": ["query"]
+            })),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ConversationalSearchArgs {
+    query: String,
+    session_id: Option<Uuid>,
+    user_id: Option<Uuid>,
+    #[serde(default = "default_limit")]
+    limit: i64,
+}
+
+#[async_trait]
+impl ToolExecutor for ConversationalSearchTool {
+    #[instrument(skip(self, arguments))]
+    async fn execute(
+        &self,
+        arguments: HashMap<String, serde_json::Value>,
+    ) -> Result<ToolCallResult, MediaGatewayError> {
+        let args: ConversationalSearchArgs = serde_json::from_value(json!(arguments))
+            .map_err(|e| MediaGatewayError::validation(e.to_string()))?;
+
+        info!(
+            query = %args.query,
+            session_id = ?args.session_id,
+            "Executing conversational search"
+        );
+
+        let url = format!(
+            "{}/api/v1/search/session",
+            self.discovery_url.trim_end_matches('/')
+        );
+
+        let response = self
+            .client
+            .post(&url)
+            .json(&json!({
+                "session_id": args.session_id,
+                "query": args.query,
+                "user_id": args.user_id,
+                "page_size": args.limit.clamp(1, 50),
+            }))
+            .send()
+            .await
+            .map_err(|e| {
+                error!(error = %e, "Discovery service request failed");
+                MediaGatewayError::external_api("discovery", e.to_string(), None)
+            })?;
+
+        let status = response.status();
+        let body: serde_json::Value = response.json().await.map_err(|e| {
+            MediaGatewayError::external_api("discovery", e.to_string(), Some(status.as_u16()))
+        })?;
+
+        if !status.is_success() {
+            let message = body["error"]
+                .as_str()
+                .unwrap_or("Session search failed")
+                .to_string();
+            return Ok(ToolCallResult {
+                content: vec![ToolContent::Text { text: message }],
+                is_error: Some(true),
+            });
+        }
+
+        let results = body["results"]
+            .as_array()
+            .map(|results| {
+                results
+                    .iter()
+                    .enumerate()
+                    .map(|(position, result)| {
+                        json!({
+                            "position": position + 1,
+                            "id": result["content"]["id"],
+                            "title": result["content"]["title"],
+                            "release_year": result["content"]["release_year"],
+                            "genres": result["content"]["genres"],
+                            "platforms": result["content"]["platforms"],
+                            "relevance_score": result["relevance_score"]
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        let text = serde_json::to_string_pretty(&json!({
+            "session_id": body["session_id"],
+            "turn": body["turn"],
+            "is_follow_up": body["is_follow_up"],
+            "effective_query": body["effective_query"],
+            "refinements": body["refinements"],
+            "active_filters": body["active_filters"],
+            "results_count": results.len(),
+            "results": results
+        }))
+        .unwrap_or_else(|_| "Error formatting results".to_string());
+
+        Ok(ToolCallResult {
+            content: vec![ToolContent::Text { text }],
+            is_error: Some(false),
+        })
+    }
+}
@@ -195,6 +195,7 @@ async fn handle_tools_list(id: crate::protocol::RequestId) -> JsonRpcResponse {
         crate::tools::CheckAvailabilityTool::definition(),
         crate::tools::GetContentDetailsTool::definition(),
         crate::tools::SyncWatchlistTool::definition(),
+        crate::tools::ConversationalSearchTool::definition(),
     ];
 
     let result = ToolListResult { tools };
@@ -241,6 +242,7 @@ async fn handle_tools_call(
             state.db_pool.clone(),
         )),
         "sync_watchlist" => Box::new(crate::tools::SyncWatchlistTool::new(state.db_pool.clone())),
+        "conversational_search" => Box::new(crate::tools::ConversationalSearchTool::from_env()),
         _ => {
             return JsonRpcResponse::error(
                 id,
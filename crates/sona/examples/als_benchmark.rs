@@ -0,0 +1,141 @@
+//! Benchmark: implicit ALS training time on synthetic interactions
+//!
+//! Generates a long-tailed user-item interaction matrix and times
+//! `MatrixFactorization::fit`. Defaults match production scale: 10M
+//! interactions, 500k users, 50k items, 64 factors, 10 iterations.
+//!
+//! Run with:
+//! ```bash
+//! cargo run --release --example als_benchmark
+//! cargo run --release --example als_benchmark -- --interactions 1000000 --threads 1
+//! ```
+//!
+//! Flags: `--interactions`, `--users`, `--items`, `--factors`, `--iterations`,
+//! `--cg-steps`, `--validation` (held-out fraction) and `--threads`.
+
+use anyhow::{Context, Result};
+use media_gateway_sona::{ALSConfig, MatrixFactorization, SparseMatrix};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::time::Instant;
+
+struct Options {
+    interactions: usize,
+    users: usize,
+    items: usize,
+    factors: usize,
+    iterations: usize,
+    cg_steps: usize,
+    validation: f32,
+    threads: Option<usize>,
+}
+
+impl Options {
+    fn parse() -> Result<Self> {
+        let mut options = Self {
+            interactions: 10_000_000,
+            users: 500_000,
+            items: 50_000,
+            factors: 64,
+            iterations: 10,
+            cg_steps: 3,
+            validation: 0.0,
+            threads: None,
+        };
+
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        for pair in args.chunks(2) {
+            let value = pair
+                .get(1)
+                .with_context(|| format!("Missing value for {}", pair[0]))?;
+            match pair[0].as_str() {
+                "--interactions" => options.interactions = value.parse()?,
+                "--users" => options.users = value.parse()?,
+                "--items" => options.items = value.parse()?,
+                "--factors" => options.factors = value.parse()?,
+                "--iterations" => options.iterations = value.parse()?,
+                "--cg-steps" => options.cg_steps = value.parse()?,
+                "--validation" => options.validation = value.parse()?,
+                "--threads" => options.threads = Some(value.parse()?),
+                other => anyhow::bail!("Unknown flag {}", other),
+            }
+        }
+        Ok(options)
+    }
+}
+
+/// Long-tailed popularity: a few items collect most interactions
+fn synthetic_matrix(options: &Options) -> SparseMatrix {
+    let mut rng = StdRng::seed_from_u64(7);
+    let mut matrix = SparseMatrix::with_capacity(options.interactions);
+    for _ in 0..options.interactions {
+        let user = rng.gen_range(0..options.users);
+        let skew: f64 = rng.gen::<f64>().powi(3);
+        let item = ((skew * options.items as f64) as usize).min(options.items - 1);
+        let completion: f32 = rng.gen_range(0.1..1.0);
+        matrix.insert(user, item, completion);
+    }
+    matrix
+}
+
+fn main() -> Result<()> {
+    tracing_subscriber::fmt::init();
+    let options = Options::parse()?;
+
+    if let Some(threads) = options.threads {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build_global()
+            .context("Failed to configure rayon pool")?;
+    }
+
+    println!(
+        "ALS benchmark: {} interactions, {} users, {} items, k={}, {} iterations, {} CG steps, {} threads",
+        options.interactions,
+        options.users,
+        options.items,
+        options.factors,
+        options.iterations,
+        options.cg_steps,
+        rayon::current_num_threads()
+    );
+
+    let start = Instant::now();
+    let matrix = synthetic_matrix(&options);
+    println!("generate:  {:>8.2?}", start.elapsed());
+
+    let start = Instant::now();
+    let csr = matrix.to_csr();
+    let csc = matrix.to_csc();
+    println!(
+        "compress:  {:>8.2?} ({} unique interactions)",
+        start.elapsed(),
+        csr.nnz()
+    );
+    drop((csr, csc));
+
+    let mut model = MatrixFactorization::new(ALSConfig {
+        latent_factors: options.factors,
+        iterations: options.iterations,
+        cg_steps: options.cg_steps,
+        validation_fraction: options.validation,
+        ..Default::default()
+    });
+
+    let report = model.fit(&matrix)?;
+    let elapsed = std::time::Duration::from_millis(report.elapsed_ms);
+    println!(
+        "fit:       {:>8.2?} ({} iterations, {:.2?} per iteration)",
+        elapsed,
+        report.iterations,
+        elapsed / report.iterations.max(1) as u32
+    );
+    if !report.validation_auc.is_empty() {
+        println!(
+            "validation AUC per iteration: {:?} (kept iteration {:?})",
+            report.validation_auc, report.best_iteration
+        );
+    }
+
+    Ok(())
+}
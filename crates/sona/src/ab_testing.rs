@@ -12,7 +12,8 @@
 //!     status VARCHAR(50) NOT NULL DEFAULT 'draft',
 //!     traffic_allocation FLOAT NOT NULL DEFAULT 1.0,
 //!     created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
-//!     updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+//!     updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+//!     assignment JSONB NOT NULL DEFAULT '{}'
 //! );
 //!
 //! CREATE TABLE experiment_variants (
@@ -47,13 +48,19 @@
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
 use sqlx::PgPool;
-use std::collections::hash_map::DefaultHasher;
-use std::hash::{Hash, Hasher};
 use std::sync::Arc;
-use tracing::{debug, info, instrument};
+use tracing::{debug, info, instrument, warn};
 use uuid::Uuid;
 
+use crate::assignment::{
+    self, AssignmentConfig, AssignmentContext, AssignmentDecision, AssignmentEngine,
+};
+use crate::bandit::{self, BanditConfig, BanditState, ReplayReport};
+use crate::experiment_analysis::{
+    self, AnalysisConfig, ConfidenceInterval, ContinuousMetric, ExperimentAnalysis,
+};
 use crate::experiment_repository::{ExperimentRepository, PostgresExperimentRepository};
 
 /// Experiment status
@@ -83,6 +90,9 @@ pub struct Experiment {
     pub traffic_allocation: f64,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Salt, layer, ramp-up, holdout and targeting settings
+    #[sqlx(default)]
+    pub assignment: Json<AssignmentConfig>,
 }
 
 /// Experiment variant
@@ -110,6 +120,29 @@ pub struct Assignment {
 pub struct ExperimentMetrics {
     pub experiment_id: Uuid,
     pub variant_metrics: Vec<VariantMetrics>,
+    /// Significance tests, sample-ratio check and verdict
+    pub analysis: ExperimentAnalysis,
+}
+
+impl ExperimentMetrics {
+    /// Build metrics and run the statistical analysis with default thresholds
+    pub fn new(experiment_id: Uuid, variant_metrics: Vec<VariantMetrics>) -> Self {
+        Self::with_config(experiment_id, variant_metrics, &AnalysisConfig::default())
+    }
+
+    /// Build metrics and run the statistical analysis with custom thresholds
+    pub fn with_config(
+        experiment_id: Uuid,
+        variant_metrics: Vec<VariantMetrics>,
+        config: &AnalysisConfig,
+    ) -> Self {
+        let analysis = experiment_analysis::analyze(&variant_metrics, config);
+        Self {
+            experiment_id,
+            variant_metrics,
+            analysis,
+        }
+    }
 }
 
 /// Metrics for a single variant
@@ -117,10 +150,21 @@ pub struct ExperimentMetrics {
 pub struct VariantMetrics {
     pub variant_id: Uuid,
     pub variant_name: String,
+    /// Configured traffic weight
+    pub weight: f64,
     pub exposures: i64,
     pub conversions: i64,
     pub conversion_rate: f64,
     pub avg_metric_value: f64,
+    /// Distinct users exposed to the variant
+    pub exposed_users: i64,
+    /// Distinct exposed users with at least one conversion
+    pub converted_users: i64,
+    pub user_conversion_rate: f64,
+    /// 95% Wilson interval for `user_conversion_rate`
+    pub user_conversion_rate_ci: ConfidenceInterval,
+    /// Per-user watch time, when any was recorded
+    pub watch_time: Option<ContinuousMetric>,
 }
 
 /// A/B Testing service
@@ -158,7 +202,7 @@ impl ABTestingService {
             r#"
             INSERT INTO experiments (name, description, traffic_allocation)
             VALUES ($1, $2, $3)
-            RETURNING id, name, description, status, traffic_allocation, created_at, updated_at
+            RETURNING id, name, description, status, traffic_allocation, created_at, updated_at, assignment
             "#,
         )
         .bind(name)
@@ -216,7 +260,7 @@ impl ABTestingService {
     /// Get running experiments
     pub async fn get_running_experiments(&self) -> Result<Vec<Experiment>> {
         let experiments = sqlx::query_as::<_, Experiment>(
-            "SELECT id, name, description, status, traffic_allocation, created_at, updated_at FROM experiments WHERE status = 'running'"
+            "SELECT id, name, description, status, traffic_allocation, created_at, updated_at, assignment FROM experiments WHERE status = 'running'"
         )
         .fetch_all(&self.pool)
         .await
@@ -238,61 +282,157 @@ impl ABTestingService {
         Ok(variants)
     }
 
-    /// Assign user to variant (deterministic based on user_id)
+    /// Assign user to variant
+    ///
+    /// Equivalent to [`Self::assign`] without targeting attributes.
+    ///
+    /// # Errors
+    /// Returns error if the experiment does not exist or the user is held out
+    /// or excluded from it
     #[instrument(skip(self))]
     pub async fn assign_variant(&self, experiment_id: Uuid, user_id: Uuid) -> Result<Variant> {
+        match self
+            .assign(experiment_id, &AssignmentContext::new(user_id))
+            .await?
+        {
+            AssignmentDecision::Variant { variant_id, .. } => {
+                self.get_variant_by_id(variant_id).await
+            }
+            decision => anyhow::bail!(
+                "User {} not enrolled in experiment {}: {:?}",
+                user_id,
+                experiment_id,
+                decision
+            ),
+        }
+    }
+
+    /// Assign a user to an experiment using stable hashing
+    ///
+    /// Users keep a recorded assignment while its variant still exists, and
+    /// a recorded holdout, so weight, holdout and bandit allocation changes
+    /// only move new users. Otherwise the decision is derived from the hash
+    /// (see [`crate::assignment`]); bandit experiments hash against the
+    /// current bandit allocation instead of the configured weights. Variant
+    /// and holdout assignments are recorded for analysis.
+    #[instrument(skip(self, context), fields(user_id = %context.user_id))]
+    pub async fn assign(
+        &self,
+        experiment_id: Uuid,
+        context: &AssignmentContext,
+    ) -> Result<AssignmentDecision> {
+        let experiment = self
+            .repository
+            .get_experiment(experiment_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Experiment {} not found", experiment_id))?;
+        let variants = self.effective_variants(experiment_id).await?;
+
         // Check for existing assignment
-        if let Some(existing) = sqlx::query_as::<_, Assignment>(
-            "SELECT id, experiment_id, user_id, variant_id, assigned_at FROM experiment_assignments WHERE experiment_id = $1 AND user_id = $2"
-        )
-        .bind(experiment_id)
-        .bind(user_id)
-        .fetch_optional(&self.pool)
-        .await?
+        if let Some(existing) = self
+            .repository
+            .get_assignment(experiment_id, context.user_id)
+            .await?
         {
-            return self.get_variant_by_id(existing.variant_id).await;
+            if let Some(variant) = variants.iter().find(|v| v.id == existing.variant_id) {
+                return Ok(AssignmentDecision::Variant {
+                    variant_id: variant.id,
+                    variant_name: variant.name.clone(),
+                });
+            }
         }
 
-        // Get variants and select based on consistent hash
-        let variants = self.get_variants(experiment_id).await?;
-        if variants.is_empty() {
-            anyhow::bail!("No variants defined for experiment {}", experiment_id);
+        if self
+            .repository
+            .is_held_out(experiment_id, context.user_id)
+            .await?
+        {
+            return Ok(AssignmentDecision::Holdout);
         }
 
-        let selected = self.select_variant_by_hash(&variants, user_id);
+        let decision = assignment::assign(&experiment, &variants, context);
 
-        // Record assignment
-        sqlx::query(
-            "INSERT INTO experiment_assignments (experiment_id, user_id, variant_id) VALUES ($1, $2, $3) ON CONFLICT DO NOTHING"
-        )
-        .bind(experiment_id)
-        .bind(user_id)
-        .bind(selected.id)
-        .execute(&self.pool)
-        .await?;
+        match &decision {
+            AssignmentDecision::Variant { variant_id, .. } => {
+                self.repository
+                    .record_assignment(experiment_id, context.user_id, *variant_id)
+                    .await?;
+            }
+            AssignmentDecision::Holdout => {
+                self.repository
+                    .record_holdout(experiment_id, context.user_id)
+                    .await?;
+            }
+            AssignmentDecision::Excluded { .. } => {}
+        }
 
-        debug!(experiment_id = %experiment_id, user_id = %user_id, variant_id = %selected.id, "Assigned variant");
-        Ok(selected)
+        debug!(experiment_id = %experiment_id, user_id = %context.user_id, decision = ?decision, "Assigned user");
+        Ok(decision)
     }
 
-    /// Select variant using consistent hashing
-    fn select_variant_by_hash(&self, variants: &[Variant], user_id: Uuid) -> Variant {
-        let mut hasher = DefaultHasher::new();
-        user_id.hash(&mut hasher);
-        let hash = hasher.finish();
-        let normalized = (hash as f64) / (u64::MAX as f64);
-
-        let total_weight: f64 = variants.iter().map(|v| v.weight).sum();
-        let mut cumulative = 0.0;
+    /// Snapshot running experiments for assignment without database access
+    pub async fn assignment_engine(&self) -> Result<AssignmentEngine> {
+        let mut experiments = Vec::new();
+        for experiment in self.get_running_experiments().await? {
+            let variants = self.effective_variants(experiment.id).await?;
+            experiments.push((experiment, variants));
+        }
+        Ok(AssignmentEngine::new(experiments))
+    }
 
-        for variant in variants {
-            cumulative += variant.weight / total_weight;
-            if normalized < cumulative {
-                return variant.clone();
+    /// Update an experiment's assignment settings
+    ///
+    /// # Errors
+    /// Returns error if the settings are invalid or the layer range overlaps
+    /// another experiment that has not completed
+    #[instrument(skip(self, config))]
+    pub async fn set_assignment_config(
+        &self,
+        experiment_id: Uuid,
+        config: AssignmentConfig,
+    ) -> Result<()> {
+        config.validate()?;
+
+        if let Some(layer) = &config.layer {
+            let others = self.repository.list_experiments(None).await?;
+            if let Some(conflict) = others.iter().find(|other| {
+                other.id != experiment_id
+                    && other.status != "completed"
+                    && other
+                        .assignment
+                        .0
+                        .layer
+                        .as_ref()
+                        .is_some_and(|l| l.overlaps(layer))
+            }) {
+                anyhow::bail!(
+                    "Layer '{}' buckets {}..{} overlap experiment {}",
+                    layer.name,
+                    layer.start,
+                    layer.end,
+                    conflict.name
+                );
             }
         }
 
-        variants.last().unwrap().clone()
+        sqlx::query("UPDATE experiments SET assignment = $2, updated_at = NOW() WHERE id = $1")
+            .bind(experiment_id)
+            .bind(Json(&config))
+            .execute(&self.pool)
+            .await
+            .context("Failed to update assignment config")?;
+
+        info!(experiment_id = %experiment_id, layer = ?config.layer, ramp = config.ramp_percentage, "Updated assignment config");
+        Ok(())
+    }
+
+    /// Variants with bandit allocations substituted for configured weights
+    async fn effective_variants(&self, experiment_id: Uuid) -> Result<Vec<Variant>> {
+        let mut variants = self.get_variants(experiment_id).await?;
+        if let Some(state) = self.repository.get_bandit_state(experiment_id).await? {
+            state.apply_weights(&mut variants);
+        }
+        Ok(variants)
     }
 
     /// Get variant by ID
@@ -314,21 +454,22 @@ impl ABTestingService {
         variant_id: Uuid,
         user_id: Uuid,
     ) -> Result<()> {
-        sqlx::query(
-            "INSERT INTO experiment_metrics (experiment_id, variant_id, user_id, metric_name, metric_value) VALUES ($1, $2, $3, 'exposure', 1.0)"
-        )
-        .bind(experiment_id)
-        .bind(variant_id)
-        .bind(user_id)
-        .execute(&self.pool)
-        .await
-        .context("Failed to record exposure")?;
+        self.repository
+            .record_metric(experiment_id, variant_id, user_id, "exposure", 1.0, None)
+            .await
+    }
 
-        debug!(experiment_id = %experiment_id, variant_id = %variant_id, user_id = %user_id, "Recorded exposure");
-        Ok(())
+    /// Record that a held-out user was served the baseline instead of a variant
+    #[instrument(skip(self))]
+    pub async fn record_holdout_exposure(&self, experiment_id: Uuid, user_id: Uuid) -> Result<()> {
+        self.repository
+            .record_holdout_exposure(experiment_id, user_id, None)
+            .await
     }
 
     /// Record conversion (user clicked/watched recommended content)
+    ///
+    /// Conversions are the reward signal for bandit experiments.
     #[instrument(skip(self))]
     pub async fn record_conversion(
         &self,
@@ -338,145 +479,214 @@ impl ABTestingService {
         metric_name: &str,
         value: f64,
     ) -> Result<()> {
-        sqlx::query(
-            "INSERT INTO experiment_metrics (experiment_id, variant_id, user_id, metric_name, metric_value) VALUES ($1, $2, $3, $4, $5)"
-        )
-        .bind(experiment_id)
-        .bind(variant_id)
-        .bind(user_id)
-        .bind(metric_name)
-        .bind(value)
-        .execute(&self.pool)
-        .await
-        .context("Failed to record conversion")?;
-
-        debug!(experiment_id = %experiment_id, metric_name = %metric_name, value = %value, "Recorded conversion");
-        Ok(())
+        self.repository
+            .record_metric(experiment_id, variant_id, user_id, metric_name, value, None)
+            .await
     }
 
-    /// Get experiment metrics
+    /// Get experiment metrics with statistical analysis
+    ///
+    /// Bandit experiments skip the sample-ratio check, as their traffic
+    /// follows the bandit allocation rather than the configured weights.
     #[instrument(skip(self))]
     pub async fn get_experiment_metrics(&self, experiment_id: Uuid) -> Result<ExperimentMetrics> {
+        let metrics = self
+            .repository
+            .get_experiment_metrics(experiment_id)
+            .await?;
+        if self
+            .repository
+            .get_bandit_state(experiment_id)
+            .await?
+            .is_none()
+        {
+            return Ok(metrics);
+        }
+
+        let config = AnalysisConfig {
+            check_sample_ratio: false,
+            ..AnalysisConfig::default()
+        };
+        Ok(ExperimentMetrics::with_config(
+            experiment_id,
+            metrics.variant_metrics,
+            &config,
+        ))
+    }
+
+    /// Turn an experiment into a bandit, starting from an even split
+    ///
+    /// # Errors
+    /// Returns error if the experiment has fewer than two variants or the
+    /// config is invalid
+    #[instrument(skip(self, config))]
+    pub async fn enable_bandit(
+        &self,
+        experiment_id: Uuid,
+        config: BanditConfig,
+    ) -> Result<BanditState> {
         let variants = self.get_variants(experiment_id).await?;
-        let mut variant_metrics = Vec::new();
+        let state = BanditState::new(experiment_id, config, &variants)?;
+        self.repository.save_bandit_state(&state).await?;
 
-        for variant in variants {
-            let exposures: (i64,) = sqlx::query_as(
-                "SELECT COUNT(*) FROM experiment_metrics WHERE experiment_id = $1 AND variant_id = $2 AND metric_name = 'exposure'"
-            )
-            .bind(experiment_id)
-            .bind(variant.id)
-            .fetch_one(&self.pool)
-            .await?;
+        info!(experiment_id = %experiment_id, policy = ?state.config.policy, "Enabled bandit allocation");
+        Ok(state)
+    }
 
-            let conversions: (i64,) = sqlx::query_as(
-                "SELECT COUNT(*) FROM experiment_metrics WHERE experiment_id = $1 AND variant_id = $2 AND metric_name = 'conversion'"
-            )
-            .bind(experiment_id)
-            .bind(variant.id)
-            .fetch_one(&self.pool)
+    /// Refresh a bandit's posteriors from recorded conversions
+    #[instrument(skip(self))]
+    pub async fn update_bandit(&self, experiment_id: Uuid) -> Result<BanditState> {
+        let mut state = self
+            .repository
+            .get_bandit_state(experiment_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Experiment {} is not a bandit", experiment_id))?;
+        let rewards = self
+            .repository
+            .get_bandit_rewards(experiment_id, state.config.reward_metric.as_deref())
             .await?;
 
-            let avg_value: (Option<f64>,) = sqlx::query_as(
-                "SELECT AVG(metric_value) FROM experiment_metrics WHERE experiment_id = $1 AND variant_id = $2 AND metric_name != 'exposure'"
-            )
-            .bind(experiment_id)
-            .bind(variant.id)
-            .fetch_one(&self.pool)
-            .await?;
+        state.update(&rewards, Utc::now(), &mut rand::thread_rng())?;
+        self.repository.save_bandit_state(&state).await?;
 
-            let conversion_rate = if exposures.0 > 0 {
-                conversions.0 as f64 / exposures.0 as f64
-            } else {
-                0.0
-            };
+        debug!(
+            experiment_id = %experiment_id,
+            allocation = ?state.arms.iter().map(|a| a.allocation).collect::<Vec<_>>(),
+            "Updated bandit allocation"
+        );
+        Ok(state)
+    }
 
-            variant_metrics.push(VariantMetrics {
-                variant_id: variant.id,
-                variant_name: variant.name,
-                exposures: exposures.0,
-                conversions: conversions.0,
-                conversion_rate,
-                avg_metric_value: avg_value.0.unwrap_or(0.0),
-            });
+    /// Refresh every running bandit whose update interval has elapsed
+    ///
+    /// Returns the number of bandits updated. A failing bandit is logged and
+    /// skipped so it cannot block the others.
+    pub async fn update_due_bandits(&self) -> Result<usize> {
+        let now = Utc::now();
+        let mut updated = 0;
+        for experiment in self.get_running_experiments().await? {
+            let due = match self.repository.get_bandit_state(experiment.id).await? {
+                Some(state) => state.is_due(now),
+                None => false,
+            };
+            if !due {
+                continue;
+            }
+            match self.update_bandit(experiment.id).await {
+                Ok(_) => updated += 1,
+                Err(e) => {
+                    warn!(experiment_id = %experiment.id, error = %e, "Failed to update bandit")
+                }
+            }
         }
+        Ok(updated)
+    }
 
-        Ok(ExperimentMetrics {
-            experiment_id,
-            variant_metrics,
+    /// Run [`Self::update_due_bandits`] every `tick`
+    pub fn spawn_bandit_updates(
+        self: Arc<Self>,
+        tick: std::time::Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(tick);
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.update_due_bandits().await {
+                    warn!(error = %e, "Bandit update pass failed");
+                }
+            }
         })
     }
+
+    /// Replay a bandit policy against an experiment's exposure history
+    ///
+    /// See [`bandit::replay`]; most meaningful on experiments that ran as an
+    /// even fixed split.
+    #[instrument(skip(self, config))]
+    pub async fn replay_bandit(
+        &self,
+        experiment_id: Uuid,
+        config: &BanditConfig,
+        update_every: usize,
+        seed: u64,
+    ) -> Result<ReplayReport> {
+        let variant_ids: Vec<Uuid> = self
+            .get_variants(experiment_id)
+            .await?
+            .iter()
+            .map(|v| v.id)
+            .collect();
+        let log = self
+            .repository
+            .get_bandit_replay_log(experiment_id, config.reward_metric.as_deref())
+            .await?;
+
+        bandit::replay(config, &variant_ids, &log, update_every, seed)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn experiment() -> Experiment {
+        Experiment {
+            id: Uuid::new_v4(),
+            name: "hashing".to_string(),
+            description: None,
+            status: "running".to_string(),
+            traffic_allocation: 1.0,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            assignment: Json(AssignmentConfig::default()),
+        }
+    }
+
+    fn variant(experiment: &Experiment, name: &str, weight: f64) -> Variant {
+        Variant {
+            id: Uuid::new_v4(),
+            experiment_id: experiment.id,
+            name: name.to_string(),
+            weight,
+            config: serde_json::json!({}),
+        }
+    }
+
     #[test]
     fn test_consistent_hashing() {
+        let experiment = experiment();
         let variants = vec![
-            Variant {
-                id: Uuid::new_v4(),
-                experiment_id: Uuid::new_v4(),
-                name: "control".to_string(),
-                weight: 0.5,
-                config: serde_json::json!({}),
-            },
-            Variant {
-                id: Uuid::new_v4(),
-                experiment_id: Uuid::new_v4(),
-                name: "treatment".to_string(),
-                weight: 0.5,
-                config: serde_json::json!({}),
-            },
+            variant(&experiment, "control", 0.5),
+            variant(&experiment, "treatment", 0.5),
         ];
 
-        let user_id = Uuid::new_v4();
-        let service = ABTestingService {
-            pool: unsafe { std::mem::zeroed() },
-        }; // Note: only for hash test
+        let context = AssignmentContext::new(Uuid::new_v4());
 
         // Same user should always get same variant
-        let v1 = service.select_variant_by_hash(&variants, user_id);
-        let v2 = service.select_variant_by_hash(&variants, user_id);
-        assert_eq!(
-            v1.id, v2.id,
-            "Consistent hashing should return same variant"
-        );
+        let v1 = assignment::assign(&experiment, &variants, &context);
+        let v2 = assignment::assign(&experiment, &variants, &context);
+        assert!(matches!(v1, AssignmentDecision::Variant { .. }));
+        assert_eq!(v1, v2, "Consistent hashing should return same variant");
     }
 
     #[test]
     fn test_weight_distribution() {
+        let experiment = experiment();
         let variants = vec![
-            Variant {
-                id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
-                experiment_id: Uuid::new_v4(),
-                name: "control".to_string(),
-                weight: 0.8,
-                config: serde_json::json!({}),
-            },
-            Variant {
-                id: Uuid::parse_str("00000000-0000-0000-0000-000000000002").unwrap(),
-                experiment_id: Uuid::new_v4(),
-                name: "treatment".to_string(),
-                weight: 0.2,
-                config: serde_json::json!({}),
-            },
+            variant(&experiment, "control", 0.8),
+            variant(&experiment, "treatment", 0.2),
         ];
 
-        let service = ABTestingService {
-            pool: unsafe { std::mem::zeroed() },
-        };
         let mut control_count = 0;
         let mut treatment_count = 0;
 
         for i in 0..1000 {
             let user_id = Uuid::new_v5(&Uuid::NAMESPACE_DNS, format!("user{}", i).as_bytes());
-            let selected = service.select_variant_by_hash(&variants, user_id);
-            if selected.name == "control" {
-                control_count += 1;
-            } else {
-                treatment_count += 1;
+            match assignment::assign(&experiment, &variants, &AssignmentContext::new(user_id)) {
+                AssignmentDecision::Variant { variant_name, .. } if variant_name == "control" => {
+                    control_count += 1
+                }
+                _ => treatment_count += 1,
             }
         }
 
@@ -487,5 +697,6 @@ mod tests {
             "Control should be ~80%, got {}",
             control_ratio
         );
+        assert_eq!(control_count + treatment_count, 1000);
     }
 }
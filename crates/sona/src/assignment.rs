@@ -0,0 +1,564 @@
+//! Stable, salted, layered experiment assignment
+//!
+//! Assignments are a pure function of the experiment configuration and the
+//! user, so any service holding a snapshot of running experiments can
+//! reproduce them without a database round trip.
+//!
+//! Every decision hashes the user ID with MurmurHash3 (x86, 32-bit) under a
+//! salt derived from the experiment, and separate purposes (traffic, holdout,
+//! variant) use separate salts. That keeps assignments uncorrelated between
+//! concurrent experiments, and lets ramp-up add users without moving anyone
+//! already enrolled to a different variant.
+//!
+//! Experiments sharing a layer are mutually exclusive: each owns a disjoint
+//! range of the layer's buckets, and a user falls in exactly one bucket per
+//! layer.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::ab_testing::{Experiment, Variant};
+
+/// Current hashing scheme; stored per experiment so it can evolve safely
+pub const ASSIGNMENT_HASH_VERSION: u32 = 1;
+
+/// Number of buckets a layer is divided into
+pub const LAYER_BUCKETS: u32 = 10_000;
+
+/// Seed of the version 1 hash
+const HASH_SEED_V1: u32 = 0x5EED_AB01;
+
+/// Per-experiment assignment settings, stored as JSON on the experiment
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AssignmentConfig {
+    pub hash_version: u32,
+    /// Hash salt; defaults to the experiment ID
+    pub salt: Option<String>,
+    /// Mutually-exclusive layer slot, if any
+    pub layer: Option<LayerAllocation>,
+    /// Fraction of the allocated traffic currently enrolled (0.0-1.0)
+    pub ramp_percentage: f64,
+    /// Fraction of enrolled users held out of every variant (0.0-1.0)
+    pub holdout_percentage: f64,
+    pub targeting: TargetingRules,
+}
+
+impl Default for AssignmentConfig {
+    fn default() -> Self {
+        Self {
+            hash_version: ASSIGNMENT_HASH_VERSION,
+            salt: None,
+            layer: None,
+            ramp_percentage: 1.0,
+            holdout_percentage: 0.0,
+            targeting: TargetingRules::default(),
+        }
+    }
+}
+
+impl AssignmentConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.hash_version != ASSIGNMENT_HASH_VERSION {
+            anyhow::bail!("Unsupported assignment hash version {}", self.hash_version);
+        }
+        if !(0.0..=1.0).contains(&self.ramp_percentage) {
+            anyhow::bail!("ramp_percentage must be between 0.0 and 1.0");
+        }
+        if !(0.0..=1.0).contains(&self.holdout_percentage) {
+            anyhow::bail!("holdout_percentage must be between 0.0 and 1.0");
+        }
+        if let Some(layer) = &self.layer {
+            if layer.name.trim().is_empty() {
+                anyhow::bail!("Layer name is required");
+            }
+            if layer.start >= layer.end || layer.end > LAYER_BUCKETS {
+                anyhow::bail!(
+                    "Layer bucket range must satisfy start < end <= {}",
+                    LAYER_BUCKETS
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Bucket range `[start, end)` an experiment owns within a layer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LayerAllocation {
+    pub name: String,
+    pub start: u32,
+    pub end: u32,
+}
+
+impl LayerAllocation {
+    pub fn overlaps(&self, other: &LayerAllocation) -> bool {
+        self.name == other.name && self.start < other.end && other.start < self.end
+    }
+}
+
+/// Who an experiment applies to; empty lists match everyone
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TargetingRules {
+    /// ISO 3166-1 alpha-2 regions
+    pub regions: Vec<String>,
+    pub device_types: Vec<String>,
+    pub user_tiers: Vec<String>,
+}
+
+impl TargetingRules {
+    /// Whether the context satisfies every non-empty rule
+    pub fn matches(&self, context: &AssignmentContext) -> bool {
+        fn allowed(rule: &[String], value: Option<&str>) -> bool {
+            rule.is_empty() || value.is_some_and(|v| rule.iter().any(|r| r.eq_ignore_ascii_case(v)))
+        }
+
+        allowed(&self.regions, context.region.as_deref())
+            && allowed(&self.device_types, context.device_type.as_deref())
+            && allowed(&self.user_tiers, context.user_tier.as_deref())
+    }
+}
+
+/// User attributes used for targeting
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssignmentContext {
+    pub user_id: Uuid,
+    pub region: Option<String>,
+    pub device_type: Option<String>,
+    pub user_tier: Option<String>,
+}
+
+impl AssignmentContext {
+    pub fn new(user_id: Uuid) -> Self {
+        Self {
+            user_id,
+            region: None,
+            device_type: None,
+            user_tier: None,
+        }
+    }
+
+    pub fn with_region(mut self, region: impl Into<String>) -> Self {
+        self.region = Some(region.into());
+        self
+    }
+
+    pub fn with_device_type(mut self, device_type: impl Into<String>) -> Self {
+        self.device_type = Some(device_type.into());
+        self
+    }
+
+    pub fn with_user_tier(mut self, user_tier: impl Into<String>) -> Self {
+        self.user_tier = Some(user_tier.into());
+        self
+    }
+}
+
+/// Why a user is not part of an experiment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExclusionReason {
+    NotTargeted,
+    OutsideLayer,
+    OutsideTraffic,
+    NoVariants,
+}
+
+/// Outcome of assigning a user to an experiment
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum AssignmentDecision {
+    Variant {
+        variant_id: Uuid,
+        variant_name: String,
+    },
+    /// Enrolled but deliberately shown no variant, as a long-term baseline
+    Holdout,
+    Excluded {
+        reason: ExclusionReason,
+    },
+}
+
+/// Assign a user to one of an experiment's variants
+pub fn assign(
+    experiment: &Experiment,
+    variants: &[Variant],
+    context: &AssignmentContext,
+) -> AssignmentDecision {
+    let config = &experiment.assignment.0;
+    let salt = config
+        .salt
+        .clone()
+        .unwrap_or_else(|| experiment.id.to_string());
+    let user_id = context.user_id;
+
+    if !config.targeting.matches(context) {
+        return excluded(ExclusionReason::NotTargeted);
+    }
+
+    if let Some(layer) = &config.layer {
+        let bucket = layer_bucket(config.hash_version, &layer.name, user_id);
+        if bucket < layer.start || bucket >= layer.end {
+            return excluded(ExclusionReason::OutsideLayer);
+        }
+    }
+
+    let enrolled = (experiment.traffic_allocation * config.ramp_percentage).clamp(0.0, 1.0);
+    if hash_unit(config.hash_version, &salt, "traffic", user_id) >= enrolled {
+        return excluded(ExclusionReason::OutsideTraffic);
+    }
+
+    if hash_unit(config.hash_version, &salt, "holdout", user_id) < config.holdout_percentage {
+        return AssignmentDecision::Holdout;
+    }
+
+    match select_variant(
+        variants,
+        hash_unit(config.hash_version, &salt, "variant", user_id),
+    ) {
+        Some(variant) => AssignmentDecision::Variant {
+            variant_id: variant.id,
+            variant_name: variant.name.clone(),
+        },
+        None => excluded(ExclusionReason::NoVariants),
+    }
+}
+
+fn excluded(reason: ExclusionReason) -> AssignmentDecision {
+    AssignmentDecision::Excluded { reason }
+}
+
+/// Pick a variant by weight for a point in `[0, 1)`
+pub fn select_variant(variants: &[Variant], point: f64) -> Option<&Variant> {
+    let total_weight: f64 = variants.iter().map(|v| v.weight.max(0.0)).sum();
+    if total_weight <= 0.0 {
+        return variants.first();
+    }
+
+    let mut cumulative = 0.0;
+    for variant in variants {
+        cumulative += variant.weight.max(0.0) / total_weight;
+        if point < cumulative {
+            return Some(variant);
+        }
+    }
+    variants.last()
+}
+
+/// Bucket of a user within a layer
+pub fn layer_bucket(hash_version: u32, layer: &str, user_id: Uuid) -> u32 {
+    let unit = hash_unit(hash_version, &format!("layer:{}", layer), "bucket", user_id);
+    ((unit * LAYER_BUCKETS as f64) as u32).min(LAYER_BUCKETS - 1)
+}
+
+/// Deterministic point in `[0, 1)` for a user, salt and purpose
+pub fn hash_unit(hash_version: u32, salt: &str, purpose: &str, user_id: Uuid) -> f64 {
+    // Only version 1 exists; `AssignmentConfig::validate` rejects others
+    debug_assert_eq!(hash_version, ASSIGNMENT_HASH_VERSION);
+    let key = format!("{}:{}:{}", salt, purpose, user_id);
+    murmur3_32(key.as_bytes(), HASH_SEED_V1) as f64 / (u32::MAX as f64 + 1.0)
+}
+
+/// MurmurHash3 x86 32-bit
+pub fn murmur3_32(data: &[u8], seed: u32) -> u32 {
+    const C1: u32 = 0xcc9e_2d51;
+    const C2: u32 = 0x1b87_3593;
+
+    let mut h = seed;
+    let chunks = data.chunks_exact(4);
+    let tail = chunks.remainder();
+
+    for chunk in chunks {
+        let k = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        h ^= k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+        h = h.rotate_left(13).wrapping_mul(5).wrapping_add(0xe654_6b64);
+    }
+
+    if !tail.is_empty() {
+        let k = tail
+            .iter()
+            .enumerate()
+            .fold(0u32, |k, (i, b)| k | ((*b as u32) << (8 * i)));
+        h ^= k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+    }
+
+    h ^= data.len() as u32;
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x85eb_ca6b);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xc2b2_ae35);
+    h ^= h >> 16;
+    h
+}
+
+/// In-memory snapshot of running experiments for assignment without database access
+#[derive(Debug, Clone, Default)]
+pub struct AssignmentEngine {
+    experiments: Vec<(Experiment, Vec<Variant>)>,
+}
+
+impl AssignmentEngine {
+    /// Build an engine, skipping experiments with invalid assignment settings
+    ///
+    /// An experiment whose layer range overlaps one kept earlier is skipped
+    /// too, so a single misconfigured experiment cannot take the others down.
+    pub fn new(experiments: Vec<(Experiment, Vec<Variant>)>) -> Self {
+        let mut kept: Vec<(Experiment, Vec<Variant>)> = Vec::with_capacity(experiments.len());
+        for (experiment, variants) in experiments {
+            let config = &experiment.assignment.0;
+            if let Err(e) = config.validate() {
+                warn!(experiment = %experiment.name, error = %e, "Skipping experiment with invalid assignment config");
+                continue;
+            }
+            if let Some((other, _)) = kept.iter().find(|(other, _)| {
+                matches!(
+                    (&config.layer, &other.assignment.0.layer),
+                    (Some(a), Some(b)) if a.overlaps(b)
+                )
+            }) {
+                warn!(experiment = %experiment.name, overlaps = %other.name, "Skipping experiment with overlapping layer allocation");
+                continue;
+            }
+            kept.push((experiment, variants));
+        }
+        Self { experiments: kept }
+    }
+
+    /// Assign a user to one experiment
+    pub fn assign(
+        &self,
+        experiment_id: Uuid,
+        context: &AssignmentContext,
+    ) -> Option<AssignmentDecision> {
+        self.experiments
+            .iter()
+            .find(|(experiment, _)| experiment.id == experiment_id)
+            .map(|(experiment, variants)| assign(experiment, variants, context))
+    }
+
+    /// Assign a user to every experiment, keeping only enrollments
+    ///
+    /// At most one experiment per layer is returned.
+    pub fn assign_all(&self, context: &AssignmentContext) -> Vec<(Uuid, AssignmentDecision)> {
+        self.experiments
+            .iter()
+            .map(|(experiment, variants)| (experiment.id, assign(experiment, variants, context)))
+            .filter(|(_, decision)| !matches!(decision, AssignmentDecision::Excluded { .. }))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use sqlx::types::Json;
+
+    fn experiment(name: &str, config: AssignmentConfig) -> Experiment {
+        Experiment {
+            id: Uuid::new_v5(&Uuid::NAMESPACE_OID, name.as_bytes()),
+            name: name.to_string(),
+            description: None,
+            status: "running".to_string(),
+            traffic_allocation: 1.0,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            assignment: Json(config),
+        }
+    }
+
+    fn variants(experiment: &Experiment, weights: &[(&str, f64)]) -> Vec<Variant> {
+        weights
+            .iter()
+            .map(|(name, weight)| Variant {
+                id: Uuid::new_v5(&experiment.id, name.as_bytes()),
+                experiment_id: experiment.id,
+                name: name.to_string(),
+                weight: *weight,
+                config: serde_json::json!({}),
+            })
+            .collect()
+    }
+
+    fn user(i: usize) -> Uuid {
+        Uuid::new_v5(&Uuid::NAMESPACE_DNS, format!("user{}", i).as_bytes())
+    }
+
+    fn variant_name(decision: &AssignmentDecision) -> Option<&str> {
+        match decision {
+            AssignmentDecision::Variant { variant_name, .. } => Some(variant_name),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_murmur3_reference_values() {
+        assert_eq!(murmur3_32(b"", 0), 0);
+        assert_eq!(murmur3_32(b"", 1), 0x514e_28b7);
+        assert_eq!(murmur3_32(b"hello", 0), 0x248b_fa47);
+        assert_eq!(
+            murmur3_32(b"The quick brown fox jumps over the lazy dog", 0),
+            0x2e4f_f723
+        );
+    }
+
+    #[test]
+    fn test_assignment_is_deterministic() {
+        let exp = experiment("deterministic", AssignmentConfig::default());
+        let vars = variants(&exp, &[("control", 0.5), ("treatment", 0.5)]);
+        let context = AssignmentContext::new(user(1));
+
+        assert_eq!(assign(&exp, &vars, &context), assign(&exp, &vars, &context));
+    }
+
+    #[test]
+    fn test_weight_distribution() {
+        let exp = experiment("weights", AssignmentConfig::default());
+        let vars = variants(&exp, &[("control", 0.8), ("treatment", 0.2)]);
+
+        let control = (0..10_000)
+            .filter(|i| {
+                variant_name(&assign(&exp, &vars, &AssignmentContext::new(user(*i))))
+                    == Some("control")
+            })
+            .count();
+        let ratio = control as f64 / 10_000.0;
+        assert!(
+            ratio > 0.78 && ratio < 0.82,
+            "Control should be ~80%, got {}",
+            ratio
+        );
+    }
+
+    #[test]
+    fn test_experiments_are_uncorrelated() {
+        let a = experiment("first", AssignmentConfig::default());
+        let b = experiment("second", AssignmentConfig::default());
+        let vars_a = variants(&a, &[("control", 0.5), ("treatment", 0.5)]);
+        let vars_b = variants(&b, &[("control", 0.5), ("treatment", 0.5)]);
+
+        let same = (0..10_000)
+            .filter(|i| {
+                let context = AssignmentContext::new(user(*i));
+                variant_name(&assign(&a, &vars_a, &context))
+                    == variant_name(&assign(&b, &vars_b, &context))
+            })
+            .count();
+        let ratio = same as f64 / 10_000.0;
+        assert!(
+            ratio > 0.47 && ratio < 0.53,
+            "Expected ~50% agreement, got {}",
+            ratio
+        );
+    }
+
+    #[test]
+    fn test_ramp_up_keeps_existing_assignments() {
+        let mut config = AssignmentConfig {
+            ramp_percentage: 0.2,
+            ..Default::default()
+        };
+        let small = experiment("ramp", config.clone());
+        config.ramp_percentage = 0.6;
+        let large = experiment("ramp", config);
+        let vars = variants(&small, &[("control", 0.5), ("treatment", 0.5)]);
+
+        let mut enrolled = 0;
+        for i in 0..5_000 {
+            let context = AssignmentContext::new(user(i));
+            let before = assign(&small, &vars, &context);
+            if matches!(before, AssignmentDecision::Variant { .. }) {
+                enrolled += 1;
+                assert_eq!(before, assign(&large, &vars, &context));
+            }
+        }
+        let ratio = enrolled as f64 / 5_000.0;
+        assert!(
+            ratio > 0.17 && ratio < 0.23,
+            "Expected ~20% enrolled, got {}",
+            ratio
+        );
+    }
+
+    #[test]
+    fn test_layers_are_mutually_exclusive() {
+        let layer = |start, end| AssignmentConfig {
+            layer: Some(LayerAllocation {
+                name: "homepage".to_string(),
+                start,
+                end,
+            }),
+            ..Default::default()
+        };
+        let a = experiment("layer-a", layer(0, 5_000));
+        let b = experiment("layer-b", layer(5_000, 10_000));
+        let engine = AssignmentEngine::new(vec![
+            (a.clone(), variants(&a, &[("control", 1.0)])),
+            (b.clone(), variants(&b, &[("control", 1.0)])),
+        ]);
+
+        for i in 0..1_000 {
+            assert_eq!(engine.assign_all(&AssignmentContext::new(user(i))).len(), 1);
+        }
+
+        // Overlapping and invalid experiments are skipped, not fatal
+        let overlapping = experiment("layer-c", layer(4_000, 6_000));
+        let invalid = experiment(
+            "invalid",
+            AssignmentConfig {
+                ramp_percentage: 2.0,
+                ..Default::default()
+            },
+        );
+        let engine = AssignmentEngine::new(vec![
+            (a.clone(), variants(&a, &[("control", 1.0)])),
+            (overlapping.clone(), vec![]),
+            (invalid.clone(), vec![]),
+        ]);
+        let context = AssignmentContext::new(user(0));
+        assert!(engine.assign(a.id, &context).is_some());
+        assert!(engine.assign(overlapping.id, &context).is_none());
+        assert!(engine.assign(invalid.id, &context).is_none());
+    }
+
+    #[test]
+    fn test_targeting_and_holdout() {
+        let config = AssignmentConfig {
+            holdout_percentage: 0.1,
+            targeting: TargetingRules {
+                regions: vec!["US".to_string()],
+                device_types: vec![],
+                user_tiers: vec!["premium".to_string()],
+            },
+            ..Default::default()
+        };
+        let exp = experiment("targeted", config);
+        let vars = variants(&exp, &[("control", 0.5), ("treatment", 0.5)]);
+
+        let untargeted = AssignmentContext::new(user(1)).with_region("DE");
+        assert_eq!(
+            assign(&exp, &vars, &untargeted),
+            AssignmentDecision::Excluded {
+                reason: ExclusionReason::NotTargeted
+            }
+        );
+
+        let holdouts = (0..5_000)
+            .filter(|i| {
+                let context = AssignmentContext::new(user(*i))
+                    .with_region("us")
+                    .with_user_tier("premium");
+                assign(&exp, &vars, &context) == AssignmentDecision::Holdout
+            })
+            .count();
+        let ratio = holdouts as f64 / 5_000.0;
+        assert!(
+            ratio > 0.08 && ratio < 0.12,
+            "Expected ~10% holdout, got {}",
+            ratio
+        );
+    }
+}
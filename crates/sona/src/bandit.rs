@@ -0,0 +1,622 @@
+//! Multi-armed bandit variant allocation
+//!
+//! Bandit experiments shift traffic toward better-performing variants instead
+//! of holding a fixed split. Each variant is an arm with a Beta posterior over
+//! its per-user conversion rate, refreshed periodically from recorded
+//! exposures and conversions. The refreshed allocation replaces the variant
+//! weights used by stable hash assignment, so a user only changes variant
+//! when the allocation moves.
+//!
+//! Every arm keeps at least `exploration_floor` of the traffic so a variant
+//! with an unlucky start can still recover.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::ab_testing::Variant;
+
+/// Posterior draws used to estimate each arm's probability of being best
+const THOMPSON_SAMPLES: usize = 10_000;
+
+/// How the non-floor share of traffic is distributed between arms
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BanditPolicy {
+    /// Allocate in proportion to each arm's posterior probability of being best
+    ThompsonSampling,
+    /// Send all non-floor traffic to the arm with the highest upper confidence bound
+    Ucb { exploration: f64 },
+}
+
+/// Bandit settings for an experiment
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BanditConfig {
+    pub policy: BanditPolicy,
+    /// Minimum share of traffic per arm (0.0 to 1/arms)
+    pub exploration_floor: f64,
+    /// Seconds between posterior updates
+    pub update_interval_secs: u64,
+    /// Conversion metric counted as reward; any conversion when unset
+    pub reward_metric: Option<String>,
+    /// Beta prior pseudo-counts
+    pub prior_successes: f64,
+    pub prior_failures: f64,
+}
+
+impl Default for BanditConfig {
+    fn default() -> Self {
+        Self {
+            policy: BanditPolicy::ThompsonSampling,
+            exploration_floor: 0.05,
+            update_interval_secs: 300,
+            reward_metric: None,
+            prior_successes: 1.0,
+            prior_failures: 1.0,
+        }
+    }
+}
+
+impl BanditConfig {
+    /// Validate the settings for an experiment with `arms` variants
+    pub fn validate(&self, arms: usize) -> Result<()> {
+        if arms < 2 {
+            anyhow::bail!("Bandit experiments need at least two variants");
+        }
+        if !(0.0..=1.0).contains(&self.exploration_floor)
+            || self.exploration_floor * arms as f64 > 1.0
+        {
+            anyhow::bail!(
+                "exploration_floor {} is not achievable with {} variants",
+                self.exploration_floor,
+                arms
+            );
+        }
+        if self.update_interval_secs == 0 {
+            anyhow::bail!("update_interval_secs must be positive");
+        }
+        if !(self.prior_successes > 0.0 && self.prior_failures > 0.0) {
+            anyhow::bail!("Beta prior pseudo-counts must be positive");
+        }
+        if let BanditPolicy::Ucb { exploration } = self.policy {
+            if !exploration.is_finite() || exploration < 0.0 {
+                anyhow::bail!("UCB exploration must be a non-negative number");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Cumulative rewards observed for one arm
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArmRewards {
+    pub variant_id: Uuid,
+    /// Distinct exposed users
+    pub trials: i64,
+    /// Distinct exposed users with a rewarding conversion
+    pub successes: i64,
+}
+
+/// Posterior and current traffic share of one arm
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArmState {
+    pub variant_id: Uuid,
+    pub trials: i64,
+    pub successes: i64,
+    /// Beta posterior parameters
+    pub alpha: f64,
+    pub beta: f64,
+    /// Share of traffic currently assigned to the arm
+    pub allocation: f64,
+}
+
+impl ArmState {
+    fn new(config: &BanditConfig, rewards: ArmRewards) -> Self {
+        let failures = (rewards.trials - rewards.successes).max(0);
+        Self {
+            variant_id: rewards.variant_id,
+            trials: rewards.trials,
+            successes: rewards.successes,
+            alpha: config.prior_successes + rewards.successes as f64,
+            beta: config.prior_failures + failures as f64,
+            allocation: 0.0,
+        }
+    }
+
+    /// Posterior mean conversion rate
+    pub fn posterior_mean(&self) -> f64 {
+        self.alpha / (self.alpha + self.beta)
+    }
+}
+
+/// Persisted bandit state of an experiment
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BanditState {
+    pub experiment_id: Uuid,
+    pub config: BanditConfig,
+    pub arms: Vec<ArmState>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl BanditState {
+    /// Start from the prior with an even split
+    pub fn new(experiment_id: Uuid, config: BanditConfig, variants: &[Variant]) -> Result<Self> {
+        config.validate(variants.len())?;
+        let share = 1.0 / variants.len() as f64;
+        let arms = variants
+            .iter()
+            .map(|variant| ArmState {
+                allocation: share,
+                ..ArmState::new(
+                    &config,
+                    ArmRewards {
+                        variant_id: variant.id,
+                        trials: 0,
+                        successes: 0,
+                    },
+                )
+            })
+            .collect();
+        Ok(Self {
+            experiment_id,
+            config,
+            arms,
+            updated_at: Utc::now(),
+        })
+    }
+
+    /// Whether the posterior is due for a refresh
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        let interval = Duration::seconds(self.config.update_interval_secs as i64);
+        now - self.updated_at >= interval
+    }
+
+    /// Rebuild posteriors from cumulative rewards and recompute the allocation
+    pub fn update<R: Rng + ?Sized>(
+        &mut self,
+        rewards: &[ArmRewards],
+        now: DateTime<Utc>,
+        rng: &mut R,
+    ) -> Result<()> {
+        self.config.validate(rewards.len())?;
+        let mut arms: Vec<ArmState> = rewards
+            .iter()
+            .map(|r| ArmState::new(&self.config, *r))
+            .collect();
+        let shares = allocate(&self.config, &arms, rng);
+        for (arm, share) in arms.iter_mut().zip(shares) {
+            arm.allocation = share;
+        }
+        self.arms = arms;
+        self.updated_at = now;
+        Ok(())
+    }
+
+    /// Replace variant weights with the bandit allocation
+    ///
+    /// Variants without an arm (added since the last update) get no traffic
+    /// until the next update picks them up.
+    pub fn apply_weights(&self, variants: &mut [Variant]) {
+        for variant in variants {
+            variant.weight = self
+                .arms
+                .iter()
+                .find(|arm| arm.variant_id == variant.id)
+                .map_or(0.0, |arm| arm.allocation);
+        }
+    }
+}
+
+/// Compute traffic shares for the arms, including the exploration floor
+pub fn allocate<R: Rng + ?Sized>(
+    config: &BanditConfig,
+    arms: &[ArmState],
+    rng: &mut R,
+) -> Vec<f64> {
+    if arms.is_empty() {
+        return Vec::new();
+    }
+    let raw = match config.policy {
+        BanditPolicy::ThompsonSampling => thompson_probabilities(arms, rng),
+        BanditPolicy::Ucb { exploration } => ucb_allocation(arms, exploration),
+    };
+    apply_floor(&raw, config.exploration_floor)
+}
+
+/// Monte Carlo estimate of each arm's probability of having the highest rate
+fn thompson_probabilities<R: Rng + ?Sized>(arms: &[ArmState], rng: &mut R) -> Vec<f64> {
+    let mut wins = vec![0usize; arms.len()];
+    for _ in 0..THOMPSON_SAMPLES {
+        let mut best = 0;
+        let mut best_draw = f64::NEG_INFINITY;
+        for (i, arm) in arms.iter().enumerate() {
+            let draw = sample_beta(rng, arm.alpha, arm.beta);
+            if draw > best_draw {
+                best = i;
+                best_draw = draw;
+            }
+        }
+        wins[best] += 1;
+    }
+    wins.into_iter()
+        .map(|w| w as f64 / THOMPSON_SAMPLES as f64)
+        .collect()
+}
+
+/// UCB1: everything to the arm with the highest optimistic estimate
+///
+/// Untried arms score infinitely high so each gets tried first.
+fn ucb_allocation(arms: &[ArmState], exploration: f64) -> Vec<f64> {
+    let total = arms.iter().map(|a| a.trials.max(0)).sum::<i64>().max(1) as f64;
+    let scores = arms.iter().map(|arm| {
+        if arm.trials <= 0 {
+            f64::INFINITY
+        } else {
+            let trials = arm.trials as f64;
+            arm.successes as f64 / trials + exploration * (total.ln() / trials).sqrt()
+        }
+    });
+
+    let mut best = 0;
+    let mut best_score = f64::NEG_INFINITY;
+    for (i, score) in scores.enumerate() {
+        if score > best_score {
+            best = i;
+            best_score = score;
+        }
+    }
+
+    let mut allocation = vec![0.0; arms.len()];
+    allocation[best] = 1.0;
+    allocation
+}
+
+/// Reserve `floor` for every arm and scale the rest by `raw`
+fn apply_floor(raw: &[f64], floor: f64) -> Vec<f64> {
+    let n = raw.len() as f64;
+    let total: f64 = raw.iter().sum();
+    let remaining = (1.0 - floor * n).max(0.0);
+    raw.iter()
+        .map(|&r| {
+            let share = if total > 0.0 { r / total } else { 1.0 / n };
+            floor + remaining * share
+        })
+        .collect()
+}
+
+/// Draw from Beta(a, b) as a ratio of gamma variates
+fn sample_beta<R: Rng + ?Sized>(rng: &mut R, a: f64, b: f64) -> f64 {
+    let x = sample_gamma(rng, a);
+    let y = sample_gamma(rng, b);
+    if x + y > 0.0 {
+        x / (x + y)
+    } else {
+        0.5
+    }
+}
+
+/// Marsaglia-Tsang gamma sampler with unit scale
+fn sample_gamma<R: Rng + ?Sized>(rng: &mut R, shape: f64) -> f64 {
+    if shape < 1.0 {
+        let u: f64 = rng.gen();
+        return sample_gamma(rng, shape + 1.0) * u.powf(1.0 / shape);
+    }
+
+    let d = shape - 1.0 / 3.0;
+    let c = 1.0 / (9.0 * d).sqrt();
+    loop {
+        let x = sample_standard_normal(rng);
+        let v = (1.0 + c * x).powi(3);
+        if v <= 0.0 {
+            continue;
+        }
+        let u: f64 = rng.gen();
+        if u < 1.0 - 0.0331 * x.powi(4) || u.ln() < 0.5 * x * x + d * (1.0 - v + v.ln()) {
+            return d * v;
+        }
+    }
+}
+
+/// Box-Muller standard normal draw
+fn sample_standard_normal<R: Rng + ?Sized>(rng: &mut R) -> f64 {
+    let u1: f64 = 1.0 - rng.gen::<f64>();
+    let u2: f64 = rng.gen();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+/// One logged decision: the variant a user saw and whether they converted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoggedEvent {
+    pub variant_id: Uuid,
+    pub converted: bool,
+}
+
+/// Per-arm outcome of a replay
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayArm {
+    pub variant_id: Uuid,
+    pub pulls: i64,
+    pub successes: i64,
+    /// Allocation after the last update
+    pub final_allocation: f64,
+}
+
+/// Result of replaying a bandit policy against historical logs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayReport {
+    /// Logged events considered
+    pub events: usize,
+    /// Events where the policy picked the logged variant
+    pub matched: usize,
+    /// Conversion rate over matched events
+    pub policy_reward_rate: f64,
+    /// Conversion rate of the logged traffic
+    pub logged_reward_rate: f64,
+    pub arms: Vec<ReplayArm>,
+}
+
+/// Evaluate a bandit policy offline with the replay method
+///
+/// For each logged event the policy picks a variant from its current
+/// allocation; only events where it agrees with the logged variant count,
+/// and they feed back into the posterior. Posteriors are refreshed every
+/// `update_every` matched events, mirroring periodic updates in production.
+///
+/// The estimate is unbiased when the logs come from an even random split,
+/// which is what a fixed A/B test with equal weights produces.
+pub fn replay(
+    config: &BanditConfig,
+    variant_ids: &[Uuid],
+    log: &[LoggedEvent],
+    update_every: usize,
+    seed: u64,
+) -> Result<ReplayReport> {
+    config.validate(variant_ids.len())?;
+    let update_every = update_every.max(1);
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let mut rewards: Vec<ArmRewards> = variant_ids
+        .iter()
+        .map(|&variant_id| ArmRewards {
+            variant_id,
+            trials: 0,
+            successes: 0,
+        })
+        .collect();
+    let arms_for = |rewards: &[ArmRewards]| -> Vec<ArmState> {
+        rewards.iter().map(|r| ArmState::new(config, *r)).collect()
+    };
+    let mut allocation = allocate(config, &arms_for(&rewards), &mut rng);
+
+    let mut matched = 0usize;
+    let mut since_update = 0usize;
+    let mut matched_successes = 0i64;
+    let mut logged_successes = 0usize;
+
+    for event in log {
+        logged_successes += usize::from(event.converted);
+
+        let point: f64 = rng.gen();
+        let mut cumulative = 0.0;
+        let mut chosen = allocation.len() - 1;
+        for (i, share) in allocation.iter().enumerate() {
+            cumulative += share;
+            if point < cumulative {
+                chosen = i;
+                break;
+            }
+        }
+        if rewards[chosen].variant_id != event.variant_id {
+            continue;
+        }
+
+        matched += 1;
+        since_update += 1;
+        rewards[chosen].trials += 1;
+        if event.converted {
+            rewards[chosen].successes += 1;
+            matched_successes += 1;
+        }
+        if since_update == update_every {
+            since_update = 0;
+            allocation = allocate(config, &arms_for(&rewards), &mut rng);
+        }
+    }
+
+    let rate = |successes: f64, total: usize| {
+        if total > 0 {
+            successes / total as f64
+        } else {
+            0.0
+        }
+    };
+
+    Ok(ReplayReport {
+        events: log.len(),
+        matched,
+        policy_reward_rate: rate(matched_successes as f64, matched),
+        logged_reward_rate: rate(logged_successes as f64, log.len()),
+        arms: rewards
+            .iter()
+            .zip(&allocation)
+            .map(|(r, &share)| ReplayArm {
+                variant_id: r.variant_id,
+                pulls: r.trials,
+                successes: r.successes,
+                final_allocation: share,
+            })
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variant(weight: f64) -> Variant {
+        Variant {
+            id: Uuid::new_v4(),
+            experiment_id: Uuid::nil(),
+            name: "v".to_string(),
+            weight,
+            config: serde_json::json!({}),
+        }
+    }
+
+    fn rewards(trials: i64, successes: i64) -> ArmRewards {
+        ArmRewards {
+            variant_id: Uuid::new_v4(),
+            trials,
+            successes,
+        }
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let config = BanditConfig::default();
+        assert!(config.validate(2).is_ok());
+        assert!(config.validate(1).is_err());
+
+        let config = BanditConfig {
+            exploration_floor: 0.4,
+            ..Default::default()
+        };
+        assert!(config.validate(2).is_ok());
+        assert!(config.validate(3).is_err());
+
+        let config = BanditConfig {
+            policy: BanditPolicy::Ucb { exploration: -1.0 },
+            ..Default::default()
+        };
+        assert!(config.validate(2).is_err());
+    }
+
+    #[test]
+    fn test_floor_is_respected() {
+        let shares = apply_floor(&[1.0, 0.0, 0.0], 0.1);
+        assert!((shares[0] - 0.8).abs() < 1e-12);
+        assert!((shares[1] - 0.1).abs() < 1e-12);
+        assert!((shares.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_beta_sampler_mean() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let n = 20_000;
+        let mean = (0..n).map(|_| sample_beta(&mut rng, 2.0, 6.0)).sum::<f64>() / n as f64;
+        assert!((mean - 0.25).abs() < 0.01, "mean {}", mean);
+
+        let small = (0..n).map(|_| sample_beta(&mut rng, 0.5, 0.5)).sum::<f64>() / n as f64;
+        assert!((small - 0.5).abs() < 0.02, "mean {}", small);
+    }
+
+    #[test]
+    fn test_thompson_shifts_traffic_to_better_arm() {
+        let config = BanditConfig::default();
+        let arms: Vec<ArmState> = [rewards(1000, 200), rewards(1000, 100)]
+            .into_iter()
+            .map(|r| ArmState::new(&config, r))
+            .collect();
+        let mut rng = StdRng::seed_from_u64(1);
+        let shares = allocate(&config, &arms, &mut rng);
+
+        assert!(shares[0] > 0.9);
+        assert!(shares[1] >= config.exploration_floor);
+        assert!((shares.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_thompson_splits_evenly_without_data() {
+        let config = BanditConfig::default();
+        let arms: Vec<ArmState> = [rewards(0, 0), rewards(0, 0)]
+            .into_iter()
+            .map(|r| ArmState::new(&config, r))
+            .collect();
+        let mut rng = StdRng::seed_from_u64(2);
+        let shares = allocate(&config, &arms, &mut rng);
+        assert!((shares[0] - 0.5).abs() < 0.03);
+    }
+
+    #[test]
+    fn test_ucb_tries_untried_arm_first() {
+        let config = BanditConfig {
+            policy: BanditPolicy::Ucb { exploration: 2.0 },
+            exploration_floor: 0.0,
+            ..Default::default()
+        };
+        let arms: Vec<ArmState> = [rewards(500, 400), rewards(0, 0)]
+            .into_iter()
+            .map(|r| ArmState::new(&config, r))
+            .collect();
+        let mut rng = StdRng::seed_from_u64(3);
+        assert_eq!(allocate(&config, &arms, &mut rng), vec![0.0, 1.0]);
+    }
+
+    #[test]
+    fn test_state_update_and_weights() {
+        let mut variants = vec![variant(0.5), variant(0.5)];
+        let mut state =
+            BanditState::new(Uuid::new_v4(), BanditConfig::default(), &variants).unwrap();
+        assert_eq!(state.arms[0].allocation, 0.5);
+        assert!(!state.is_due(state.updated_at));
+
+        let later = state.updated_at + Duration::seconds(300);
+        assert!(state.is_due(later));
+
+        let observed = [
+            ArmRewards {
+                variant_id: variants[0].id,
+                trials: 50,
+                successes: 5,
+            },
+            ArmRewards {
+                variant_id: variants[1].id,
+                trials: 50,
+                successes: 30,
+            },
+        ];
+        let mut rng = StdRng::seed_from_u64(4);
+        state.update(&observed, later, &mut rng).unwrap();
+        assert_eq!(state.updated_at, later);
+        assert_eq!(state.arms[1].alpha, 31.0);
+        assert_eq!(state.arms[1].beta, 21.0);
+
+        state.apply_weights(&mut variants);
+        assert!(variants[1].weight > variants[0].weight);
+        assert!((variants[0].weight + variants[1].weight - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_replay_beats_even_split() {
+        let a = Uuid::new_v4();
+        let b = Uuid::new_v4();
+        let mut rng = StdRng::seed_from_u64(5);
+        let log: Vec<LoggedEvent> = (0..20_000)
+            .map(|_| {
+                let (variant_id, rate) = if rng.gen::<bool>() {
+                    (a, 0.3)
+                } else {
+                    (b, 0.1)
+                };
+                LoggedEvent {
+                    variant_id,
+                    converted: rng.gen::<f64>() < rate,
+                }
+            })
+            .collect();
+
+        let report = replay(&BanditConfig::default(), &[a, b], &log, 100, 42).unwrap();
+        assert_eq!(report.events, 20_000);
+        assert!(report.matched > 5_000);
+        assert!(report.policy_reward_rate > report.logged_reward_rate);
+        assert!(report.arms[0].pulls > report.arms[1].pulls);
+        assert!(report.arms[0].final_allocation > 0.9);
+
+        let again = replay(&BanditConfig::default(), &[a, b], &log, 100, 42).unwrap();
+        assert_eq!(report, again);
+    }
+}
@@ -0,0 +1,755 @@
+//! Multi-source candidate retrieval
+//!
+//! Fans out to every configured candidate source concurrently, each under its
+//! own timeout and candidate budget, then merges the results. A source that
+//! fails or times out contributes nothing and the remaining sources still
+//! produce recommendations. Every merged candidate keeps a record of which
+//! sources proposed it.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use chrono::Utc;
+use media_gateway_core::{TrendingScope, TrendingService};
+use serde::Serialize;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::collaborative::CollaborativeFilteringEngine;
+use crate::content_based::ContentBasedEngine;
+use crate::context::ContextAwareFilter;
+use crate::graph::GraphRecommender;
+use crate::negative_feedback::SuppressionSet;
+use crate::profile::UserProfile;
+use crate::session::SessionRecommender;
+use crate::types::{
+    RecommendationContext, RecommendationType, ScoredContent, SourceAttribution, ViewingEvent,
+};
+
+/// Time and size budget for one candidate source
+#[derive(Debug, Clone, Copy)]
+pub struct SourceBudget {
+    pub timeout: Duration,
+    /// Maximum candidates taken from the source
+    pub limit: usize,
+    /// Multiplier applied to the source's scores before merging
+    pub weight: f32,
+}
+
+/// Per-source budgets for the retrieval stage
+#[derive(Debug, Clone)]
+pub struct RetrievalConfig {
+    pub collaborative: SourceBudget,
+    pub content_based: SourceBudget,
+    pub graph: SourceBudget,
+    pub context: SourceBudget,
+    pub session: SourceBudget,
+    pub trending: SourceBudget,
+}
+
+impl Default for RetrievalConfig {
+    fn default() -> Self {
+        Self {
+            collaborative: SourceBudget {
+                timeout: Duration::from_millis(200),
+                limit: 100,
+                weight: 0.35,
+            },
+            content_based: SourceBudget {
+                timeout: Duration::from_millis(200),
+                limit: 100,
+                weight: 0.25,
+            },
+            graph: SourceBudget {
+                timeout: Duration::from_millis(300),
+                limit: 100,
+                weight: 0.30,
+            },
+            context: SourceBudget {
+                timeout: Duration::from_millis(100),
+                limit: 50,
+                weight: 0.10,
+            },
+            session: SourceBudget {
+                timeout: Duration::from_millis(100),
+                limit: 50,
+                weight: 0.25,
+            },
+            trending: SourceBudget {
+                timeout: Duration::from_millis(100),
+                limit: 50,
+                weight: 0.10,
+            },
+        }
+    }
+}
+
+/// A source that ranks titles for a user from their history
+///
+/// Implemented by the live engines; offline evaluation plugs in models
+/// trained only on data before its split cutoff.
+#[async_trait::async_trait]
+pub trait UserCandidateSource: Send + Sync {
+    async fn candidates(&self, user_id: Uuid, limit: usize) -> Result<Vec<(Uuid, f32)>>;
+}
+
+#[async_trait::async_trait]
+impl UserCandidateSource for CollaborativeFilteringEngine {
+    async fn candidates(&self, user_id: Uuid, limit: usize) -> Result<Vec<(Uuid, f32)>> {
+        self.recommend(user_id, limit).await
+    }
+}
+
+#[async_trait::async_trait]
+impl UserCandidateSource for ContentBasedEngine {
+    async fn candidates(&self, user_id: Uuid, limit: usize) -> Result<Vec<(Uuid, f32)>> {
+        self.get_recommendations_for_user(user_id, limit).await
+    }
+}
+
+#[async_trait::async_trait]
+impl UserCandidateSource for GraphRecommender {
+    async fn candidates(&self, user_id: Uuid, limit: usize) -> Result<Vec<(Uuid, f32)>> {
+        self.recommend(user_id, limit).await
+    }
+}
+
+/// Candidate sources available to a request; absent sources are skipped
+#[derive(Clone, Copy, Default)]
+pub struct CandidateSources<'a> {
+    pub collaborative: Option<&'a dyn UserCandidateSource>,
+    pub content_based: Option<&'a dyn UserCandidateSource>,
+    pub graph: Option<&'a dyn UserCandidateSource>,
+    pub context: Option<&'a ContextAwareFilter>,
+    pub session: Option<SessionSource<'a>>,
+    /// Live trending counters, scoped to the request's region when it has one
+    pub trending: Option<&'a TrendingService>,
+    /// The user's negative feedback; suppressed titles never enter the pool
+    pub suppressions: Option<&'a SuppressionSet>,
+}
+
+/// Session recommender together with the request's current session
+#[derive(Clone, Copy)]
+pub struct SessionSource<'a> {
+    pub recommender: &'a SessionRecommender,
+    /// Current session events, oldest first
+    pub events: &'a [ViewingEvent],
+    pub get_content_embedding: &'a (dyn Fn(Uuid) -> Result<Vec<f32>> + Sync),
+}
+
+impl<'a> CandidateSources<'a> {
+    pub fn with_collaborative(mut self, engine: &'a dyn UserCandidateSource) -> Self {
+        self.collaborative = Some(engine);
+        self
+    }
+
+    pub fn with_content_based(mut self, engine: &'a dyn UserCandidateSource) -> Self {
+        self.content_based = Some(engine);
+        self
+    }
+
+    pub fn with_graph(mut self, recommender: &'a dyn UserCandidateSource) -> Self {
+        self.graph = Some(recommender);
+        self
+    }
+
+    pub fn with_context(mut self, filter: &'a ContextAwareFilter) -> Self {
+        self.context = Some(filter);
+        self
+    }
+
+    pub fn with_trending(mut self, trending: &'a TrendingService) -> Self {
+        self.trending = Some(trending);
+        self
+    }
+
+    pub fn with_suppressions(mut self, suppressions: &'a SuppressionSet) -> Self {
+        self.suppressions = Some(suppressions);
+        self
+    }
+
+    pub fn with_session(
+        mut self,
+        recommender: &'a SessionRecommender,
+        events: &'a [ViewingEvent],
+        get_content_embedding: &'a (dyn Fn(Uuid) -> Result<Vec<f32>> + Sync),
+    ) -> Self {
+        self.session = Some(SessionSource {
+            recommender,
+            events,
+            get_content_embedding,
+        });
+        self
+    }
+}
+
+/// How a source fared during retrieval
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum SourceStatus {
+    Ok {
+        candidates: usize,
+    },
+    TimedOut,
+    Failed {
+        error: String,
+    },
+    /// Not configured, or no context for the context-aware source, or no
+    /// current session for the session source
+    Skipped,
+}
+
+/// Outcome of one source
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceOutcome {
+    pub source: RecommendationType,
+    #[serde(flatten)]
+    pub status: SourceStatus,
+    pub elapsed_ms: u64,
+}
+
+/// Merged candidates with per-candidate attribution and per-source outcomes
+#[derive(Debug, Clone, Default)]
+pub struct RetrievalResult {
+    /// Deduplicated candidates, highest score first
+    pub candidates: Vec<ScoredContent>,
+    /// Sources that proposed each candidate
+    pub attribution: HashMap<Uuid, Vec<SourceAttribution>>,
+    pub outcomes: Vec<SourceOutcome>,
+}
+
+impl RetrievalResult {
+    /// Whether any configured source failed or timed out
+    pub fn is_degraded(&self) -> bool {
+        self.outcomes.iter().any(|o| {
+            matches!(
+                o.status,
+                SourceStatus::TimedOut | SourceStatus::Failed { .. }
+            )
+        })
+    }
+}
+
+/// Retrieve candidates from all sources concurrently
+pub async fn retrieve_candidates(
+    user_id: Uuid,
+    profile: &UserProfile,
+    context: Option<&RecommendationContext>,
+    sources: CandidateSources<'_>,
+    config: &RetrievalConfig,
+) -> RetrievalResult {
+    let collaborative = run_source(
+        RecommendationType::Collaborative,
+        config.collaborative,
+        sources.collaborative.map(|engine| async move {
+            let scored = engine
+                .candidates(user_id, config.collaborative.limit)
+                .await?;
+            Ok(to_scored(
+                scored,
+                RecommendationType::Collaborative,
+                "collaborative_filtering",
+            ))
+        }),
+    );
+
+    let content_based = run_source(
+        RecommendationType::ContentBased,
+        config.content_based,
+        sources.content_based.map(|engine| async move {
+            let scored = engine
+                .candidates(user_id, config.content_based.limit)
+                .await?;
+            Ok(to_scored(
+                scored,
+                RecommendationType::ContentBased,
+                "content_similarity",
+            ))
+        }),
+    );
+
+    let graph = run_source(
+        RecommendationType::GraphBased,
+        config.graph,
+        sources.graph.map(|recommender| async move {
+            let scored = recommender.candidates(user_id, config.graph.limit).await?;
+            Ok(to_scored(
+                scored,
+                RecommendationType::GraphBased,
+                "graph_similarity",
+            ))
+        }),
+    );
+
+    let context_aware = run_source(
+        RecommendationType::ContextAware,
+        config.context,
+        sources
+            .context
+            .zip(context)
+            .map(|(filter, ctx)| async move {
+                filter
+                    .generate_candidates(profile, ctx, config.context.limit)
+                    .await
+            }),
+    );
+
+    let session = run_source(
+        RecommendationType::SessionBased,
+        config.session,
+        sources
+            .session
+            .filter(|source| !source.events.is_empty())
+            .map(|source| async move {
+                source
+                    .recommender
+                    .recommend(source.events, profile, config.session.limit, |id| {
+                        (source.get_content_embedding)(id)
+                    })
+                    .await
+            }),
+    );
+
+    let trending = run_source(
+        RecommendationType::Trending,
+        config.trending,
+        sources.trending.map(|trending| async move {
+            let scope = match context.and_then(|ctx| ctx.region.as_deref()) {
+                Some(region) => TrendingScope::region(region),
+                None => TrendingScope::Global,
+            };
+            let items = trending
+                .trending_now(&scope, config.trending.limit, Utc::now())
+                .await?;
+            // Counters are unbounded; scale relative to the top item
+            let top_score = items.first().map_or(0.0, |item| item.score);
+            if top_score <= 0.0 {
+                return Ok(Vec::new());
+            }
+            let scored = items
+                .iter()
+                .map(|item| (item.content_id, (item.score / top_score) as f32))
+                .collect();
+            Ok(to_scored(scored, RecommendationType::Trending, "trending"))
+        }),
+    );
+
+    let (collaborative, content_based, graph, context_aware, session, trending) = tokio::join!(
+        collaborative,
+        content_based,
+        graph,
+        context_aware,
+        session,
+        trending
+    );
+
+    let mut outcomes = Vec::with_capacity(6);
+    let mut all_candidates = Vec::new();
+    for (outcome, candidates) in [
+        collaborative,
+        content_based,
+        graph,
+        context_aware,
+        session,
+        trending,
+    ] {
+        outcomes.push(outcome);
+        all_candidates.extend(candidates);
+    }
+
+    if let Some(suppressions) = sources.suppressions {
+        all_candidates.retain(|c| !suppressions.is_blocked(c.content_id));
+    }
+
+    let (candidates, attribution) = merge_candidates(all_candidates);
+    debug!(
+        user_id = %user_id,
+        candidates = candidates.len(),
+        outcomes = ?outcomes,
+        "Retrieved candidates"
+    );
+
+    RetrievalResult {
+        candidates,
+        attribution,
+        outcomes,
+    }
+}
+
+/// Run one source under its budget, turning failures into an empty result
+async fn run_source<F>(
+    source: RecommendationType,
+    budget: SourceBudget,
+    retrieval: Option<F>,
+) -> (SourceOutcome, Vec<ScoredContent>)
+where
+    F: Future<Output = Result<Vec<ScoredContent>>>,
+{
+    let Some(retrieval) = retrieval else {
+        return (
+            SourceOutcome {
+                source,
+                status: SourceStatus::Skipped,
+                elapsed_ms: 0,
+            },
+            Vec::new(),
+        );
+    };
+
+    let start = Instant::now();
+    let result = tokio::time::timeout(budget.timeout, retrieval).await;
+    let elapsed_ms = start.elapsed().as_millis() as u64;
+
+    let (status, candidates) = match result {
+        Ok(Ok(mut candidates)) => {
+            candidates.sort_by(|a, b| {
+                b.score
+                    .partial_cmp(&a.score)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+            candidates.truncate(budget.limit);
+            for candidate in &mut candidates {
+                candidate.score *= budget.weight;
+            }
+            (
+                SourceStatus::Ok {
+                    candidates: candidates.len(),
+                },
+                candidates,
+            )
+        }
+        Ok(Err(e)) => {
+            warn!(source = ?source, error = %e, "Candidate source failed");
+            (
+                SourceStatus::Failed {
+                    error: e.to_string(),
+                },
+                Vec::new(),
+            )
+        }
+        Err(_) => {
+            warn!(
+                source = ?source,
+                timeout_ms = budget.timeout.as_millis() as u64,
+                "Candidate source timed out"
+            );
+            (SourceStatus::TimedOut, Vec::new())
+        }
+    };
+
+    (
+        SourceOutcome {
+            source,
+            status,
+            elapsed_ms,
+        },
+        candidates,
+    )
+}
+
+fn to_scored(
+    scored: Vec<(Uuid, f32)>,
+    source: RecommendationType,
+    reason: &str,
+) -> Vec<ScoredContent> {
+    scored
+        .into_iter()
+        .map(|(content_id, score)| ScoredContent {
+            content_id,
+            score,
+            source,
+            based_on: vec![reason.to_string()],
+        })
+        .collect()
+}
+
+/// Merge candidates from all sources, summing scores of duplicates
+///
+/// Candidates are expected in per-source rank order. A candidate proposed by
+/// more than one source becomes [`RecommendationType::Hybrid`].
+pub fn merge_candidates(
+    candidates: Vec<ScoredContent>,
+) -> (Vec<ScoredContent>, HashMap<Uuid, Vec<SourceAttribution>>) {
+    let mut merged: HashMap<Uuid, ScoredContent> = HashMap::new();
+    let mut attribution: HashMap<Uuid, Vec<SourceAttribution>> = HashMap::new();
+    let mut ranks: HashMap<RecommendationType, usize> = HashMap::new();
+
+    for candidate in candidates {
+        let rank = ranks.entry(candidate.source).or_insert(0);
+        *rank += 1;
+        attribution
+            .entry(candidate.content_id)
+            .or_default()
+            .push(SourceAttribution {
+                source: candidate.source,
+                score: candidate.score,
+                rank: *rank,
+            });
+
+        match merged.get_mut(&candidate.content_id) {
+            Some(existing) => {
+                existing.score += candidate.score;
+                if existing.source != candidate.source {
+                    existing.source = RecommendationType::Hybrid;
+                }
+                for reason in candidate.based_on {
+                    if !existing.based_on.contains(&reason) {
+                        existing.based_on.push(reason);
+                    }
+                }
+            }
+            None => {
+                merged.insert(candidate.content_id, candidate);
+            }
+        }
+    }
+
+    let mut candidates: Vec<ScoredContent> = merged.into_values().collect();
+    candidates.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.content_id.cmp(&b.content_id))
+    });
+
+    (candidates, attribution)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scored(
+        content_id: Uuid,
+        score: f32,
+        source: RecommendationType,
+        reason: &str,
+    ) -> ScoredContent {
+        ScoredContent {
+            content_id,
+            score,
+            source,
+            based_on: vec![reason.to_string()],
+        }
+    }
+
+    fn budget(timeout_ms: u64, limit: usize, weight: f32) -> SourceBudget {
+        SourceBudget {
+            timeout: Duration::from_millis(timeout_ms),
+            limit,
+            weight,
+        }
+    }
+
+    #[test]
+    fn test_merge_candidates() {
+        let content_id = Uuid::new_v4();
+        let candidates = vec![
+            scored(content_id, 0.5, RecommendationType::Collaborative, "user1"),
+            scored(
+                content_id,
+                0.3,
+                RecommendationType::ContentBased,
+                "genre_match",
+            ),
+        ];
+
+        let (merged, attribution) = merge_candidates(candidates);
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].score, 0.8);
+        assert_eq!(merged[0].based_on.len(), 2);
+        assert_eq!(merged[0].source, RecommendationType::Hybrid);
+
+        let sources = &attribution[&content_id];
+        assert_eq!(sources.len(), 2);
+        assert_eq!(sources[0].source, RecommendationType::Collaborative);
+        assert_eq!(sources[1].score, 0.3);
+    }
+
+    #[test]
+    fn test_merge_keeps_single_source_type_and_ranks() {
+        let first = Uuid::new_v4();
+        let second = Uuid::new_v4();
+        let candidates = vec![
+            scored(
+                first,
+                0.9,
+                RecommendationType::GraphBased,
+                "graph_similarity",
+            ),
+            scored(
+                second,
+                0.4,
+                RecommendationType::GraphBased,
+                "graph_similarity",
+            ),
+            scored(
+                second,
+                0.2,
+                RecommendationType::GraphBased,
+                "graph_similarity",
+            ),
+        ];
+
+        let (merged, attribution) = merge_candidates(candidates);
+        assert_eq!(merged[0].content_id, first);
+        assert_eq!(merged[1].source, RecommendationType::GraphBased);
+        assert_eq!(merged[1].based_on, vec!["graph_similarity".to_string()]);
+        assert_eq!(attribution[&second][0].rank, 2);
+        assert_eq!(attribution[&second][1].rank, 3);
+    }
+
+    #[tokio::test]
+    async fn test_run_source_applies_budget() {
+        let ids: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+        let candidates = vec![
+            scored(ids[0], 0.2, RecommendationType::Collaborative, "cf"),
+            scored(ids[1], 0.8, RecommendationType::Collaborative, "cf"),
+            scored(ids[2], 0.5, RecommendationType::Collaborative, "cf"),
+        ];
+
+        let (outcome, kept) = run_source(
+            RecommendationType::Collaborative,
+            budget(100, 2, 0.5),
+            Some(async move { Ok(candidates) }),
+        )
+        .await;
+
+        assert_eq!(outcome.status, SourceStatus::Ok { candidates: 2 });
+        assert_eq!(kept[0].content_id, ids[1]);
+        assert_eq!(kept[0].score, 0.4);
+        assert_eq!(kept[1].content_id, ids[2]);
+    }
+
+    #[tokio::test]
+    async fn test_run_source_degrades_on_failure_and_timeout() {
+        let (failed, candidates) = run_source(
+            RecommendationType::ContentBased,
+            budget(100, 10, 1.0),
+            Some(async { Err(anyhow::anyhow!("qdrant unavailable")) }),
+        )
+        .await;
+        assert!(candidates.is_empty());
+        assert!(
+            matches!(failed.status, SourceStatus::Failed { ref error } if error.contains("qdrant"))
+        );
+
+        let (timed_out, candidates) = run_source(
+            RecommendationType::GraphBased,
+            budget(10, 10, 1.0),
+            Some(async {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                Ok(Vec::new())
+            }),
+        )
+        .await;
+        assert!(candidates.is_empty());
+        assert_eq!(timed_out.status, SourceStatus::TimedOut);
+    }
+
+    #[tokio::test]
+    async fn test_retrieve_without_sources_skips_all() {
+        let user_id = Uuid::new_v4();
+        let profile = UserProfile::new(user_id);
+        let result = retrieve_candidates(
+            user_id,
+            &profile,
+            None,
+            CandidateSources::default(),
+            &RetrievalConfig::default(),
+        )
+        .await;
+
+        assert!(result.candidates.is_empty());
+        assert_eq!(result.outcomes.len(), 6);
+        assert!(result
+            .outcomes
+            .iter()
+            .all(|o| o.status == SourceStatus::Skipped));
+        assert!(!result.is_degraded());
+    }
+
+    #[tokio::test]
+    async fn test_retrieve_includes_trending_candidates() {
+        use chrono::Utc;
+        use media_gateway_core::{TrendingConfig, TrendingHit, TrendingSignal};
+
+        let trending = TrendingService::in_memory(TrendingConfig::default());
+        let (popular, niche) = (Uuid::new_v4(), Uuid::new_v4());
+        for (content_id, count) in [(popular, 3), (niche, 1)] {
+            for _ in 0..count {
+                trending
+                    .record(&TrendingHit {
+                        content_id,
+                        signal: TrendingSignal::Start,
+                        region: None,
+                        platform: None,
+                        at: Utc::now(),
+                    })
+                    .await
+                    .unwrap();
+            }
+        }
+
+        let user_id = Uuid::new_v4();
+        let profile = UserProfile::new(user_id);
+        let result = retrieve_candidates(
+            user_id,
+            &profile,
+            None,
+            CandidateSources::default().with_trending(&trending),
+            &RetrievalConfig::default(),
+        )
+        .await;
+
+        let ids: Vec<Uuid> = result.candidates.iter().map(|c| c.content_id).collect();
+        assert_eq!(ids, vec![popular, niche]);
+        assert!(result
+            .candidates
+            .iter()
+            .all(|c| c.source == RecommendationType::Trending));
+    }
+
+    #[tokio::test]
+    async fn test_retrieve_includes_session_candidates() {
+        use crate::session::{SessionConfig, SessionRecommender, TransitionGraph};
+        use chrono::Utc;
+
+        let event = |item: Uuid| ViewingEvent {
+            content_id: item,
+            timestamp: Utc::now(),
+            completion_rate: 1.0,
+            rating: None,
+            is_rewatch: false,
+            dismissed: false,
+        };
+        let (watched, next) = (Uuid::new_v4(), Uuid::new_v4());
+        let history = vec![event(watched), event(next)];
+        let recommender = SessionRecommender::new(
+            TransitionGraph::fit([history.as_slice()]),
+            SessionConfig::default(),
+        );
+        let session = vec![event(watched)];
+        let embedding = |_: Uuid| Ok(vec![1.0, 0.0]);
+
+        let user_id = Uuid::new_v4();
+        let profile = UserProfile::new(user_id);
+        let result = retrieve_candidates(
+            user_id,
+            &profile,
+            None,
+            CandidateSources::default().with_session(&recommender, &session, &embedding),
+            &RetrievalConfig::default(),
+        )
+        .await;
+
+        assert_eq!(result.candidates.len(), 1);
+        assert_eq!(result.candidates[0].content_id, next);
+        assert_eq!(
+            result.candidates[0].source,
+            RecommendationType::SessionBased
+        );
+    }
+}
@@ -3,9 +3,11 @@
 //! Implements HandleColdStartUser algorithm from SPARC pseudocode.
 //! Provides recommendations for new users with minimal history.
 
+use crate::explanation::{ExplanationReason, RankedReason};
 use crate::types::{Recommendation, RecommendationType};
 use anyhow::Result;
 use chrono::Utc;
+use media_gateway_core::{TrendingItem, TrendingScope, TrendingService};
 use uuid::Uuid;
 
 /// Signup context for cold start users
@@ -31,6 +33,19 @@ impl HandleColdStartUser {
     pub async fn execute(
         user_id: Uuid,
         signup_context: Option<SignupContext>,
+    ) -> Result<Vec<Recommendation>> {
+        Self::execute_with_trending(user_id, signup_context, None).await
+    }
+
+    /// Cold start recommendations backed by live trending counters
+    ///
+    /// Trending in the signup region answers the demographic step and global
+    /// trending the final fallback. Steps fall through to the static lists
+    /// when the counters are unavailable or have no data yet.
+    pub async fn execute_with_trending(
+        user_id: Uuid,
+        signup_context: Option<SignupContext>,
+        trending: Option<&TrendingService>,
     ) -> Result<Vec<Recommendation>> {
         // Step 1: Check if truly new user
         let watch_count = Self::get_watch_count(user_id).await?;
@@ -48,19 +63,102 @@ impl HandleColdStartUser {
 
             // Step 3: Use demographic-based recommendations
             if let Some(age_range) = context.age_range {
-                return Self::get_demographic_recommendations(
-                    &age_range,
-                    context.region.as_deref().unwrap_or("US"),
-                    20,
-                )
-                .await;
+                let region = context.region.as_deref().unwrap_or("US");
+                if let Some(trending) = trending {
+                    let regional =
+                        Self::get_live_trending(trending, &TrendingScope::region(region), 20).await;
+                    if !regional.is_empty() {
+                        return Ok(regional);
+                    }
+                }
+                return Self::get_demographic_recommendations(&age_range, region, 20).await;
             }
         }
 
         // Step 4: Fall back to trending content
+        if let Some(trending) = trending {
+            let global = Self::get_live_trending(trending, &TrendingScope::Global, 20).await;
+            if !global.is_empty() {
+                return Ok(global);
+            }
+        }
         Self::get_trending_recommendations(20).await
     }
 
+    /// Trending content from the decayed engagement counters
+    ///
+    /// Errors are logged and yield an empty list so callers fall through to
+    /// the next step.
+    async fn get_live_trending(
+        trending: &TrendingService,
+        scope: &TrendingScope,
+        limit: usize,
+    ) -> Vec<Recommendation> {
+        match trending.trending_now(scope, limit, Utc::now()).await {
+            Ok(items) => Self::recommendations_from_trending(&items, scope),
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to load trending content for cold start");
+                Vec::new()
+            }
+        }
+    }
+
+    /// Recommendations for trending items, scored relative to the top item
+    fn recommendations_from_trending(
+        items: &[TrendingItem],
+        scope: &TrendingScope,
+    ) -> Vec<Recommendation> {
+        let top_score = items.first().map(|item| item.score).unwrap_or(0.0);
+        if top_score <= 0.0 {
+            return Vec::new();
+        }
+
+        let region = match scope {
+            TrendingScope::Region(region) => Some(region.as_str()),
+            _ => None,
+        };
+        let max_confidence = if region.is_some() { 0.7 } else { 0.6 };
+
+        items
+            .iter()
+            .map(|item| {
+                let relative = (item.score / top_score) as f32;
+                let mut based_on = vec![match region {
+                    Some(region) => format!("Trending in {}", region),
+                    None => "Trending now".to_string(),
+                }];
+                if item.spiking {
+                    based_on.push("Rising fast".to_string());
+                }
+
+                Recommendation {
+                    content_id: item.content_id,
+                    confidence_score: max_confidence * (0.5 + 0.5 * relative),
+                    recommendation_type: RecommendationType::ContentBased,
+                    based_on,
+                    explanation: match region {
+                        Some(_) => "Trending in your area".to_string(),
+                        None => "Popular with all users".to_string(),
+                    },
+                    generated_at: Utc::now(),
+                    ttl_seconds: 1800,
+                    experiment_variant: None,
+                    sources: Vec::new(),
+                    reasons: region
+                        .map(|region| {
+                            vec![RankedReason {
+                                reason: ExplanationReason::PopularInRegion {
+                                    region: region.to_string(),
+                                },
+                                strength: relative,
+                            }]
+                        })
+                        .unwrap_or_default(),
+                }
+            })
+            .collect()
+    }
+
     async fn get_watch_count(user_id: Uuid) -> Result<usize> {
         // Simulated - in real implementation:
         // Query user's viewing history count
@@ -85,6 +183,13 @@ impl HandleColdStartUser {
                 generated_at: Utc::now(),
                 ttl_seconds: 3600,
                 experiment_variant: None,
+                sources: Vec::new(),
+                reasons: vec![RankedReason {
+                    reason: ExplanationReason::MatchesGenre {
+                        genre: genre.clone(),
+                    },
+                    strength: 1.0,
+                }],
             });
         }
 
@@ -110,6 +215,13 @@ impl HandleColdStartUser {
                 generated_at: Utc::now(),
                 ttl_seconds: 3600,
                 experiment_variant: None,
+                sources: Vec::new(),
+                reasons: vec![RankedReason {
+                    reason: ExplanationReason::PopularInRegion {
+                        region: region.to_string(),
+                    },
+                    strength: 1.0 - i as f32 / limit as f32,
+                }],
             });
         }
 
@@ -131,6 +243,8 @@ impl HandleColdStartUser {
                 generated_at: Utc::now(),
                 ttl_seconds: 1800,
                 experiment_variant: None,
+                sources: Vec::new(),
+                reasons: Vec::new(),
             });
         }
 
@@ -175,6 +289,67 @@ mod tests {
         assert!(!recommendations.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_cold_start_uses_live_trending() {
+        use media_gateway_core::{TrendingConfig, TrendingHit, TrendingSignal};
+
+        let trending = TrendingService::in_memory(TrendingConfig::default());
+        let popular = Uuid::new_v4();
+        let niche = Uuid::new_v4();
+        for (content_id, count) in [(popular, 4), (niche, 1)] {
+            for _ in 0..count {
+                trending
+                    .record(&TrendingHit {
+                        content_id,
+                        signal: TrendingSignal::Start,
+                        region: Some("DE".to_string()),
+                        platform: None,
+                        at: Utc::now(),
+                    })
+                    .await
+                    .unwrap();
+            }
+        }
+
+        let context = SignupContext {
+            selected_genres: None,
+            age_range: Some("25-34".to_string()),
+            region: Some("de".to_string()),
+        };
+        let regional = HandleColdStartUser::execute_with_trending(
+            Uuid::new_v4(),
+            Some(context),
+            Some(&trending),
+        )
+        .await
+        .unwrap();
+        assert_eq!(regional.len(), 2);
+        assert_eq!(regional[0].content_id, popular);
+        assert!(regional[0].confidence_score > regional[1].confidence_score);
+        assert_eq!(regional[0].explanation, "Trending in your area");
+
+        let global =
+            HandleColdStartUser::execute_with_trending(Uuid::new_v4(), None, Some(&trending))
+                .await
+                .unwrap();
+        assert_eq!(global[0].content_id, popular);
+
+        // No counters for the region: demographic fallback
+        let context = SignupContext {
+            selected_genres: None,
+            age_range: Some("25-34".to_string()),
+            region: Some("FR".to_string()),
+        };
+        let fallback = HandleColdStartUser::execute_with_trending(
+            Uuid::new_v4(),
+            Some(context),
+            Some(&trending),
+        )
+        .await
+        .unwrap();
+        assert_eq!(fallback.len(), 20);
+    }
+
     #[tokio::test]
     async fn test_cold_start_trending_fallback() {
         let recommendations = HandleColdStartUser::execute(Uuid::new_v4(), None)
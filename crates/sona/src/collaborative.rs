@@ -2,9 +2,21 @@
 //!
 //! Implements user-item collaborative filtering using ALS matrix factorization
 //! with implicit feedback collection and Qdrant vector storage.
+//!
+//! Between full retrains, user activity events are streamed in and folded
+//! into user factors against the fixed item factors. The timestamp of the
+//! latest applied event is persisted as a watermark; events up to
+//! `allowed_lateness` behind it are still applied, deduplicated by event id,
+//! and older ones are dropped. A [`DriftMonitor`]
+//! schedules a full retrain when the model gets old, too many interactions
+//! have been folded in, or streamed interactions keep hitting items the model
+//! has never seen.
 
 use crate::matrix_factorization::{ALSConfig, MatrixFactorization, SparseMatrix};
+use crate::types::ViewingEvent;
 use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use media_gateway_core::events::{ActivityEventType, UserActivityEvent};
 use qdrant_client::qdrant::vectors_config::Config;
 use qdrant_client::qdrant::{
     CreateCollection, Distance, PointStruct, SearchPoints, UpsertPointsBuilder,
@@ -12,17 +24,24 @@ use qdrant_client::qdrant::{
 };
 use qdrant_client::Payload;
 use qdrant_client::Qdrant;
-use sqlx::{PgPool, Row};
-use std::collections::HashMap;
+use sqlx::postgres::PgRow;
+use sqlx::{PgConnection, PgPool, Row};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tokio::sync::{mpsc, RwLock};
 use uuid::Uuid;
 
 const USER_EMBEDDINGS_COLLECTION: &str = "user_embeddings";
 const ITEM_EMBEDDINGS_COLLECTION: &str = "item_embeddings";
 const BATCH_SIZE: usize = 1000;
+/// Attempts at applying a streamed batch before it is dropped
+const MAX_BATCH_ATTEMPTS: u32 = 3;
 const SIMILARITY_THRESHOLD: f32 = 0.7;
+/// Completion rate from which a viewing counts as a completion
+const COMPLETION_THRESHOLD: f32 = 0.9;
 
 /// Interaction type for implicit feedback
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum InteractionType {
     View,
     Completion,
@@ -66,13 +85,266 @@ pub struct Interaction {
     pub timestamp: chrono::DateTime<chrono::Utc>,
 }
 
+impl Interaction {
+    /// Interaction implied by a user activity event, if it carries feedback
+    ///
+    /// Completions, abandons (with their completion rate), content views and
+    /// ratings count; search and auth events do not.
+    pub fn from_activity_event(event: &UserActivityEvent) -> Option<Self> {
+        let content_id = Uuid::parse_str(event.content_id.as_deref()?).ok()?;
+        let metadata_f32 = |key: &str| event.metadata.get(key)?.as_f64().map(|v| v as f32);
+
+        let (interaction_type, watch_progress) = match event.event_type {
+            ActivityEventType::PlaybackComplete => (
+                InteractionType::Completion,
+                metadata_f32("completion_rate").or(Some(1.0)),
+            ),
+            ActivityEventType::PlaybackAbandon => {
+                (InteractionType::View, metadata_f32("completion_rate"))
+            }
+            ActivityEventType::ContentView => (InteractionType::View, None),
+            ActivityEventType::ContentRating => {
+                (InteractionType::Rating(metadata_f32("rating")?), None)
+            }
+            _ => return None,
+        };
+
+        Some(Self {
+            user_id: event.user_id,
+            content_id,
+            interaction_type,
+            watch_progress,
+            timestamp: event.timestamp,
+        })
+    }
+}
+
+/// Activity event for a viewing event reported to this service, so profile
+/// updates reach the streaming pipeline
+///
+/// A rating is sent as a rating, otherwise the completion rate decides
+/// between a completion and an abandon. Dismissals carry no positive
+/// feedback and are left out. The event id is derived from the user, title
+/// and time, so a resubmitted event is deduplicated.
+pub fn viewing_activity_event(user_id: Uuid, event: &ViewingEvent) -> Option<UserActivityEvent> {
+    if event.dismissed {
+        return None;
+    }
+    let (event_type, metadata) = match event.rating {
+        Some(rating) => (
+            ActivityEventType::ContentRating,
+            serde_json::json!({ "rating": rating }),
+        ),
+        None if event.completion_rate >= COMPLETION_THRESHOLD => (
+            ActivityEventType::PlaybackComplete,
+            serde_json::json!({ "completion_rate": event.completion_rate }),
+        ),
+        None => (
+            ActivityEventType::PlaybackAbandon,
+            serde_json::json!({ "completion_rate": event.completion_rate }),
+        ),
+    };
+
+    let mut activity = UserActivityEvent::new(user_id, event_type, metadata)
+        .with_content_id(event.content_id.to_string());
+    activity.event_id = Uuid::new_v5(
+        &Uuid::NAMESPACE_OID,
+        format!(
+            "{}:{}:{}",
+            user_id,
+            event.content_id,
+            event.timestamp.timestamp_micros()
+        )
+        .as_bytes(),
+    );
+    activity.timestamp = event.timestamp;
+    Some(activity)
+}
+
+/// Streaming ingestion and retrain scheduling settings
+#[derive(Debug, Clone)]
+pub struct StreamingConfig {
+    /// Name the watermark is stored under (default: "collaborative_filtering")
+    pub consumer: String,
+    /// How far behind the watermark an event may arrive and still be
+    /// applied (default: 1 hour)
+    pub allowed_lateness: Duration,
+    /// Lookback of interactions used by full retrains (default: 365 days)
+    pub training_window: Duration,
+    /// Retrain once the model is this old (default: 24 hours)
+    pub max_model_age: Duration,
+    /// Retrain once folded-in interactions reach this fraction of the
+    /// training set (default: 0.2)
+    pub max_folded_fraction: f32,
+    /// Retrain once this fraction of streamed interactions is on items the
+    /// model has never seen (default: 0.1)
+    pub max_unknown_item_fraction: f32,
+    /// Streamed interactions needed before the unknown-item fraction is
+    /// trusted (default: 100)
+    pub min_drift_sample: usize,
+    /// Users whose aggregated ratings are kept between fold-ins; the least
+    /// recently updated are reloaded from the database beyond this
+    /// (default: 10,000)
+    pub max_cached_histories: usize,
+}
+
+impl Default for StreamingConfig {
+    fn default() -> Self {
+        Self {
+            consumer: "collaborative_filtering".to_string(),
+            allowed_lateness: Duration::hours(1),
+            training_window: Duration::days(365),
+            max_model_age: Duration::hours(24),
+            max_folded_fraction: 0.2,
+            max_unknown_item_fraction: 0.1,
+            min_drift_sample: 100,
+            max_cached_histories: 10_000,
+        }
+    }
+}
+
+/// Why a full retrain is due
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetrainReason {
+    /// No model has been trained yet
+    Untrained,
+    /// The model is older than `max_model_age`
+    ModelAge,
+    /// Too many interactions were folded in since the last full train
+    FoldedVolume,
+    /// Too many streamed interactions are on items the model lacks
+    UnknownItems,
+}
+
+/// Tracks how far streamed updates have moved away from the last full train
+#[derive(Debug, Clone)]
+pub struct DriftMonitor {
+    trained_at: DateTime<Utc>,
+    trained_interactions: usize,
+    folded: usize,
+    unknown: usize,
+}
+
+impl DriftMonitor {
+    pub fn new(trained_at: DateTime<Utc>, trained_interactions: usize) -> Self {
+        Self {
+            trained_at,
+            trained_interactions,
+            folded: 0,
+            unknown: 0,
+        }
+    }
+
+    /// Record streamed interactions on items the model knows and does not know
+    pub fn record(&mut self, known: usize, unknown: usize) {
+        self.folded += known;
+        self.unknown += unknown;
+    }
+
+    pub fn retrain_reason(
+        &self,
+        config: &StreamingConfig,
+        now: DateTime<Utc>,
+    ) -> Option<RetrainReason> {
+        let streamed = self.folded + self.unknown;
+        if now - self.trained_at >= config.max_model_age {
+            Some(RetrainReason::ModelAge)
+        } else if self.folded as f32
+            >= self.trained_interactions.max(1) as f32 * config.max_folded_fraction
+        {
+            Some(RetrainReason::FoldedVolume)
+        } else if streamed >= config.min_drift_sample
+            && self.unknown as f32 >= streamed as f32 * config.max_unknown_item_fraction
+        {
+            Some(RetrainReason::UnknownItems)
+        } else {
+            None
+        }
+    }
+}
+
+/// Events of a batch to apply, given the watermark and the ids of events
+/// already applied within the lateness window
+///
+/// Returns the events to apply, the number of already-applied (or repeated)
+/// events and the number of events too far behind the watermark.
+pub fn admit_events<'a>(
+    events: &'a [UserActivityEvent],
+    watermark: Option<DateTime<Utc>>,
+    allowed_lateness: Duration,
+    applied: &HashSet<Uuid>,
+) -> (Vec<&'a UserActivityEvent>, usize, usize) {
+    let horizon = watermark.map(|w| w - allowed_lateness);
+    let mut seen = HashSet::new();
+    let mut admitted = Vec::with_capacity(events.len());
+    let (mut duplicates, mut late) = (0, 0);
+    for event in events {
+        if horizon.is_some_and(|h| event.timestamp <= h) {
+            late += 1;
+        } else if applied.contains(&event.event_id) || !seen.insert(event.event_id) {
+            duplicates += 1;
+        } else {
+            admitted.push(event);
+        }
+    }
+    (admitted, duplicates, late)
+}
+
+/// Aggregate interactions into `(user, item, rating)` triples, capping each
+/// pair's summed rating at 1.0
+fn user_item_ratings(interactions: &[Interaction]) -> Vec<(Uuid, Uuid, f32)> {
+    let mut aggregated: HashMap<(Uuid, Uuid), f32> = HashMap::new();
+
+    for interaction in interactions {
+        let rating = interaction
+            .interaction_type
+            .to_rating(interaction.watch_progress);
+        *aggregated
+            .entry((interaction.user_id, interaction.content_id))
+            .or_insert(0.0) += rating;
+    }
+
+    aggregated
+        .into_iter()
+        .map(|((user_id, content_id), rating)| (user_id, content_id, rating.min(1.0)))
+        .collect()
+}
+
+/// Outcome of applying a batch of streamed events
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IngestReport {
+    /// Events already applied, skipped
+    pub skipped_events: usize,
+    /// Events further behind the watermark than the allowed lateness, dropped
+    pub late_events: usize,
+    pub interactions: usize,
+    pub users_updated: usize,
+    /// Interactions on items missing from the model
+    pub unknown_items: usize,
+    pub watermark: Option<DateTime<Utc>>,
+}
+
+/// Aggregated ratings of a user, as last folded in
+#[derive(Debug)]
+struct UserHistory {
+    ratings: HashMap<Uuid, f32>,
+    folded_at: DateTime<Utc>,
+}
+
 /// Collaborative Filtering Engine
 pub struct CollaborativeFilteringEngine {
     pool: PgPool,
     qdrant: Qdrant,
     als_config: ALSConfig,
+    streaming: StreamingConfig,
     model: Option<MatrixFactorization>,
     incremental_buffer: Vec<Interaction>,
+    /// `YᵀY + λI` of the current item factors, shared by fold-ins
+    item_gram: Option<Vec<f32>>,
+    /// Aggregated ratings of users touched since the last full train
+    user_histories: HashMap<Uuid, UserHistory>,
+    drift: Option<DriftMonitor>,
+    watermark: Option<DateTime<Utc>>,
 }
 
 impl CollaborativeFilteringEngine {
@@ -81,8 +353,13 @@ impl CollaborativeFilteringEngine {
             pool,
             qdrant,
             als_config: ALSConfig::default(),
+            streaming: StreamingConfig::default(),
             model: None,
             incremental_buffer: Vec::new(),
+            item_gram: None,
+            user_histories: HashMap::new(),
+            drift: None,
+            watermark: None,
         }
     }
 
@@ -91,6 +368,11 @@ impl CollaborativeFilteringEngine {
         self
     }
 
+    pub fn with_streaming_config(mut self, config: StreamingConfig) -> Self {
+        self.streaming = config;
+        self
+    }
+
     /// Initialize Qdrant collections for embeddings
     pub async fn initialize_collections(&self) -> Result<()> {
         let embedding_dim = self.als_config.latent_factors as u64;
@@ -130,8 +412,19 @@ impl CollaborativeFilteringEngine {
         Ok(())
     }
 
-    /// Collect implicit feedback from database
+    /// Collect implicit feedback from database within the training window
     pub async fn collect_feedback(&self) -> Result<Vec<Interaction>> {
+        let since = Utc::now() - self.streaming.training_window;
+        Self::query_feedback(&self.pool, Some(since), None, None).await
+    }
+
+    /// Implicit feedback in `(since, until]`, optionally for a single user
+    async fn query_feedback(
+        pool: &PgPool,
+        since: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
+        user_id: Option<Uuid>,
+    ) -> Result<Vec<Interaction>> {
         let rows = sqlx::query(
             r#"
             SELECT
@@ -143,82 +436,102 @@ impl CollaborativeFilteringEngine {
                 created_at as timestamp
             FROM users.interactions
             WHERE interaction_type IN ('watch', 'like', 'rate', 'dislike')
+              AND ($1::timestamptz IS NULL OR created_at > $1)
+              AND ($2::timestamptz IS NULL OR created_at <= $2)
+              AND ($3::uuid IS NULL OR user_id = $3)
             ORDER BY created_at DESC
             "#,
         )
-        .fetch_all(&self.pool)
+        .bind(since)
+        .bind(until)
+        .bind(user_id)
+        .fetch_all(pool)
         .await?;
 
-        let mut interactions = Vec::new();
+        Ok(rows.iter().filter_map(Self::parse_interaction).collect())
+    }
 
-        for row in rows {
-            let user_id: Uuid = row.get("user_id");
-            let content_id: Uuid = row.get("content_id");
-            let interaction_type_str: String = row.get("interaction_type");
-            let watch_progress: Option<f64> = row.get("watch_progress");
-            let rating: Option<f64> = row.get("rating");
-            let timestamp: chrono::DateTime<chrono::Utc> = row.get("timestamp");
-
-            let interaction_type = match interaction_type_str.as_str() {
-                "watch" => {
-                    if watch_progress.map(|p| p >= 0.9).unwrap_or(false) {
-                        InteractionType::Completion
-                    } else {
-                        InteractionType::View
-                    }
+    fn parse_interaction(row: &PgRow) -> Option<Interaction> {
+        let user_id: Uuid = row.get("user_id");
+        let content_id: Uuid = row.get("content_id");
+        let interaction_type_str: String = row.get("interaction_type");
+        let watch_progress: Option<f64> = row.get("watch_progress");
+        let rating: Option<f64> = row.get("rating");
+        let timestamp: chrono::DateTime<chrono::Utc> = row.get("timestamp");
+
+        let interaction_type = match interaction_type_str.as_str() {
+            "watch" => {
+                if watch_progress.map(|p| p >= 0.9).unwrap_or(false) {
+                    InteractionType::Completion
+                } else {
+                    InteractionType::View
                 }
-                "like" => InteractionType::Like,
-                "dislike" => InteractionType::Dislike,
-                "rate" => InteractionType::Rating(rating.unwrap_or(0.0) as f32),
-                _ => continue,
-            };
-
-            interactions.push(Interaction {
-                user_id,
-                content_id,
-                interaction_type,
-                watch_progress: watch_progress.map(|p| p as f32),
-                timestamp,
-            });
-        }
+            }
+            "like" => InteractionType::Like,
+            "dislike" => InteractionType::Dislike,
+            "rate" => InteractionType::Rating(rating.unwrap_or(0.0) as f32),
+            _ => return None,
+        };
 
-        Ok(interactions)
+        Some(Interaction {
+            user_id,
+            content_id,
+            interaction_type,
+            watch_progress: watch_progress.map(|p| p as f32),
+            timestamp,
+        })
     }
 
     /// Build user-item matrix from interactions
     pub fn build_user_item_matrix(&self, interactions: &[Interaction]) -> Vec<(Uuid, Uuid, f32)> {
-        let mut aggregated: HashMap<(Uuid, Uuid), f32> = HashMap::new();
-
-        for interaction in interactions {
-            let rating = interaction
-                .interaction_type
-                .to_rating(interaction.watch_progress);
-            *aggregated
-                .entry((interaction.user_id, interaction.content_id))
-                .or_insert(0.0) += rating;
-        }
-
-        aggregated
-            .into_iter()
-            .map(|((user_id, content_id), rating)| (user_id, content_id, rating.min(1.0)))
-            .collect()
+        user_item_ratings(interactions)
     }
 
     /// Train ALS model on collected feedback
     pub async fn train_model(&mut self) -> Result<()> {
-        let interactions = self.collect_feedback().await?;
-        let matrix_data = self.build_user_item_matrix(&interactions);
+        let (model, trained_interactions) = Self::fit_model(
+            &self.pool,
+            self.als_config.clone(),
+            self.streaming.training_window,
+        )
+        .await?;
+        self.install_model(model, trained_interactions).await
+    }
 
-        let mut model = MatrixFactorization::new(self.als_config.clone());
+    /// Fit a model on the feedback in the training window; returns it with
+    /// the number of user-item pairs it was trained on
+    ///
+    /// Needs no engine state, so a shared engine keeps serving and folding
+    /// in events while this runs.
+    async fn fit_model(
+        pool: &PgPool,
+        config: ALSConfig,
+        training_window: Duration,
+    ) -> Result<(MatrixFactorization, usize)> {
+        let since = Utc::now() - training_window;
+        let interactions = Self::query_feedback(pool, Some(since), None, None).await?;
+        let matrix_data = user_item_ratings(&interactions);
+        let trained_interactions = matrix_data.len();
+
+        let mut model = MatrixFactorization::new(config);
         let matrix = model.build_matrix(matrix_data)?;
         model.fit(&matrix)?;
 
-        self.model = Some(model);
+        Ok((model, trained_interactions))
+    }
 
-        // Store embeddings in Qdrant
-        self.store_embeddings().await?;
+    /// Serve a freshly fitted model and store its embeddings in Qdrant
+    async fn install_model(
+        &mut self,
+        model: MatrixFactorization,
+        trained_interactions: usize,
+    ) -> Result<()> {
+        self.item_gram = Some(model.item_gram()?);
+        self.model = Some(model);
+        self.user_histories.clear();
+        self.drift = Some(DriftMonitor::new(Utc::now(), trained_interactions));
 
-        Ok(())
+        self.store_embeddings().await
     }
 
     /// Store user and item embeddings in Qdrant
@@ -441,20 +754,408 @@ impl CollaborativeFilteringEngine {
         self.incremental_buffer.push(interaction);
     }
 
-    /// Perform incremental update when buffer reaches batch size
+    /// Fold buffered interactions into the model once the buffer reaches
+    /// batch size, retraining instead if drift makes it due
     pub async fn incremental_update(&mut self) -> Result<bool> {
         if self.incremental_buffer.len() < BATCH_SIZE {
             return Ok(false);
         }
 
-        // For simplicity, retrain the model
-        // In production, implement true incremental ALS
-        self.train_model().await?;
-        self.incremental_buffer.clear();
+        let interactions = std::mem::take(&mut self.incremental_buffer);
+        self.fold_in(&interactions).await?;
+        self.retrain_if_due().await?;
 
         Ok(true)
     }
 
+    /// Resume from the persisted watermark
+    pub async fn load_watermark(&mut self) -> Result<Option<DateTime<Utc>>> {
+        self.watermark = sqlx::query_scalar(
+            r#"
+            SELECT watermark FROM stream_watermarks WHERE consumer = $1
+            "#,
+        )
+        .bind(&self.streaming.consumer)
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to load stream watermark")?;
+
+        Ok(self.watermark)
+    }
+
+    async fn save_watermark(
+        &self,
+        conn: &mut PgConnection,
+        watermark: DateTime<Utc>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO stream_watermarks (consumer, watermark, updated_at)
+            VALUES ($1, $2, NOW())
+            ON CONFLICT (consumer) DO UPDATE
+            SET watermark = GREATEST(stream_watermarks.watermark, EXCLUDED.watermark),
+                updated_at = NOW()
+            "#,
+        )
+        .bind(&self.streaming.consumer)
+        .bind(watermark)
+        .execute(conn)
+        .await
+        .context("Failed to save stream watermark")?;
+
+        Ok(())
+    }
+
+    /// Ids among `event_ids` this consumer has already applied
+    async fn applied_event_ids(&self, event_ids: &[Uuid]) -> Result<HashSet<Uuid>> {
+        if event_ids.is_empty() {
+            return Ok(HashSet::new());
+        }
+        let applied: Vec<Uuid> = sqlx::query_scalar(
+            r#"
+            SELECT event_id FROM stream_applied_events
+            WHERE consumer = $1 AND event_id = ANY($2)
+            "#,
+        )
+        .bind(&self.streaming.consumer)
+        .bind(event_ids)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to load applied stream events")?;
+
+        Ok(applied.into_iter().collect())
+    }
+
+    /// Record applied events and forget those that fell out of the lateness
+    /// window of `watermark`
+    async fn record_applied_events(
+        &self,
+        conn: &mut PgConnection,
+        events: &[&UserActivityEvent],
+        watermark: Option<DateTime<Utc>>,
+    ) -> Result<()> {
+        let ids: Vec<Uuid> = events.iter().map(|e| e.event_id).collect();
+        let times: Vec<DateTime<Utc>> = events.iter().map(|e| e.timestamp).collect();
+        sqlx::query(
+            r#"
+            INSERT INTO stream_applied_events (consumer, event_id, event_time)
+            SELECT $1, event_id, event_time
+            FROM UNNEST($2::UUID[], $3::TIMESTAMPTZ[]) AS t(event_id, event_time)
+            ON CONFLICT (consumer, event_id) DO NOTHING
+            "#,
+        )
+        .bind(&self.streaming.consumer)
+        .bind(&ids)
+        .bind(&times)
+        .execute(&mut *conn)
+        .await
+        .context("Failed to record applied stream events")?;
+
+        if let Some(watermark) = watermark {
+            sqlx::query(
+                "DELETE FROM stream_applied_events WHERE consumer = $1 AND event_time <= $2",
+            )
+            .bind(&self.streaming.consumer)
+            .bind(watermark - self.streaming.allowed_lateness)
+            .execute(conn)
+            .await
+            .context("Failed to prune applied stream events")?;
+        }
+
+        Ok(())
+    }
+
+    /// Apply a batch of activity events: fold their interactions into user
+    /// factors and advance the watermark
+    ///
+    /// Events already applied are skipped by id, so replaying a stream after
+    /// a restart does not apply them twice. Events more than
+    /// `allowed_lateness` behind the watermark are dropped.
+    pub async fn ingest_events(&mut self, events: &[UserActivityEvent]) -> Result<IngestReport> {
+        let event_ids: Vec<Uuid> = events.iter().map(|e| e.event_id).collect();
+        let applied = self.applied_event_ids(&event_ids).await?;
+        let (fresh, skipped_events, late_events) = admit_events(
+            events,
+            self.watermark,
+            self.streaming.allowed_lateness,
+            &applied,
+        );
+        if late_events > 0 {
+            tracing::warn!(
+                "Dropped {} activity events more than {} minutes behind the watermark",
+                late_events,
+                self.streaming.allowed_lateness.num_minutes()
+            );
+        }
+        let interactions: Vec<Interaction> = fresh
+            .iter()
+            .filter_map(|e| Interaction::from_activity_event(e))
+            .collect();
+
+        let (users_updated, unknown_items) = self.fold_in(&interactions).await?;
+
+        // The watermark only moves together with the ids it covers, so a
+        // crash between the two cannot drop events on replay
+        if !fresh.is_empty() {
+            let latest = fresh.iter().map(|e| e.timestamp).max();
+            let advanced = latest.filter(|l| self.watermark.map_or(true, |w| *l > w));
+            let mut tx = self.pool.begin().await?;
+            self.record_applied_events(&mut tx, &fresh, advanced.or(self.watermark))
+                .await?;
+            if let Some(latest) = advanced {
+                self.save_watermark(&mut tx, latest).await?;
+            }
+            tx.commit()
+                .await
+                .context("Failed to commit stream progress")?;
+            if advanced.is_some() {
+                self.watermark = advanced;
+            }
+        }
+
+        Ok(IngestReport {
+            skipped_events,
+            late_events,
+            interactions: interactions.len(),
+            users_updated,
+            unknown_items,
+            watermark: self.watermark,
+        })
+    }
+
+    /// Fold interactions into their users' factors and refresh those users'
+    /// embeddings; returns (users updated, interactions on unknown items)
+    async fn fold_in(&mut self, interactions: &[Interaction]) -> Result<(usize, usize)> {
+        if interactions.is_empty() || self.model.is_none() {
+            // Without a model the next full train picks these up from the database
+            return Ok((0, 0));
+        }
+
+        let mut by_user: HashMap<Uuid, Vec<Interaction>> = HashMap::new();
+        for interaction in interactions {
+            by_user
+                .entry(interaction.user_id)
+                .or_default()
+                .push(interaction.clone());
+        }
+
+        let mut unknown_items = 0;
+        let mut points = Vec::with_capacity(by_user.len());
+        for (user_id, new_interactions) in by_user {
+            if !self.user_histories.contains_key(&user_id) {
+                // Interactions after the watermark arrive through the stream
+                let stored = Self::query_feedback(
+                    &self.pool,
+                    Some(Utc::now() - self.streaming.training_window),
+                    self.watermark,
+                    Some(user_id),
+                )
+                .await?;
+                let ratings = self.aggregate_ratings(&stored);
+                self.evict_histories();
+                self.user_histories.insert(
+                    user_id,
+                    UserHistory {
+                        ratings,
+                        folded_at: Utc::now(),
+                    },
+                );
+            }
+
+            let new_ratings = self.aggregate_ratings(&new_interactions);
+            let history = self
+                .user_histories
+                .get_mut(&user_id)
+                .expect("history loaded above");
+            history.folded_at = Utc::now();
+            for (content_id, rating) in new_ratings {
+                let total = history.ratings.entry(content_id).or_insert(0.0);
+                *total = (*total + rating).min(1.0);
+            }
+            let history: Vec<(Uuid, f32)> = history.ratings.iter().map(|(k, v)| (*k, *v)).collect();
+
+            let model = self.model.as_mut().context("Model not trained yet")?;
+            let gram = self.item_gram.as_ref().context("Model not trained yet")?;
+            model.fold_in_user(user_id, &history, gram)?;
+
+            let known = new_interactions
+                .iter()
+                .filter(|i| model.item_id_map.contains_key(&i.content_id))
+                .count();
+            unknown_items += new_interactions.len() - known;
+            if let Some(drift) = self.drift.as_mut() {
+                drift.record(known, new_interactions.len() - known);
+            }
+
+            let user_idx = model.user_id_map[&user_id];
+            let mut payload_map = HashMap::new();
+            payload_map.insert(
+                "user_id".to_string(),
+                QdrantValue::from(user_id.to_string()),
+            );
+            let payload: Payload = payload_map.into();
+            points.push(PointStruct::new(
+                user_idx as u64,
+                model.get_user_embedding(user_id)?,
+                payload,
+            ));
+        }
+
+        let users_updated = points.len();
+        if !points.is_empty() {
+            self.qdrant
+                .upsert_points(UpsertPointsBuilder::new(USER_EMBEDDINGS_COLLECTION, points))
+                .await?;
+        }
+
+        Ok((users_updated, unknown_items))
+    }
+
+    /// Make room for one more cached history by dropping the least recently
+    /// folded ones; they are reloaded from the database when next needed
+    fn evict_histories(&mut self) {
+        let max = self.streaming.max_cached_histories.max(1);
+        while self.user_histories.len() >= max {
+            let Some(oldest) = self
+                .user_histories
+                .iter()
+                .min_by_key(|(_, history)| history.folded_at)
+                .map(|(user_id, _)| *user_id)
+            else {
+                break;
+            };
+            self.user_histories.remove(&oldest);
+        }
+    }
+
+    fn aggregate_ratings(&self, interactions: &[Interaction]) -> HashMap<Uuid, f32> {
+        self.build_user_item_matrix(interactions)
+            .into_iter()
+            .map(|(_, content_id, rating)| (content_id, rating))
+            .collect()
+    }
+
+    /// Why a full retrain is due, if it is
+    pub fn retrain_reason(&self) -> Option<RetrainReason> {
+        match &self.drift {
+            Some(drift) if self.model.is_some() => {
+                drift.retrain_reason(&self.streaming, Utc::now())
+            }
+            _ => Some(RetrainReason::Untrained),
+        }
+    }
+
+    /// Run a full retrain if drift or model age call for one
+    pub async fn retrain_if_due(&mut self) -> Result<Option<RetrainReason>> {
+        let reason = self.retrain_reason();
+        if let Some(reason) = reason {
+            tracing::info!("Retraining collaborative filtering model: {:?}", reason);
+            self.train_model().await?;
+        }
+        Ok(reason)
+    }
+
+    /// Consume activity events into a shared engine until the channel
+    /// closes, applying them in batches of up to `BATCH_SIZE`
+    ///
+    /// The write lock is held only while a batch is applied, so readers get
+    /// through between batches. Retraining is left to [`Self::spawn_retraining`].
+    pub async fn consume(
+        engine: Arc<RwLock<Self>>,
+        mut events: mpsc::Receiver<UserActivityEvent>,
+    ) -> Result<()> {
+        engine.write().await.load_watermark().await?;
+
+        let mut batch = Vec::with_capacity(BATCH_SIZE);
+        while let Some(event) = events.recv().await {
+            batch.push(event);
+            while batch.len() < BATCH_SIZE {
+                match events.try_recv() {
+                    Ok(event) => batch.push(event),
+                    Err(_) => break,
+                }
+            }
+
+            // Applied events are recorded by id, so a retry does not refold
+            // a batch whose progress was committed
+            let mut attempt = 1;
+            loop {
+                let result = engine.write().await.ingest_events(&batch).await;
+                match result {
+                    Ok(report) => {
+                        tracing::debug!("Applied activity batch: {:?}", report);
+                        break;
+                    }
+                    Err(e) if attempt < MAX_BATCH_ATTEMPTS => {
+                        tracing::warn!(
+                            "Failed to apply activity batch (attempt {}): {}",
+                            attempt,
+                            e
+                        );
+                        tokio::time::sleep(std::time::Duration::from_secs(1 << attempt)).await;
+                        attempt += 1;
+                    }
+                    Err(e) => {
+                        tracing::error!(
+                            "Dropping activity batch of {} events after {} attempts: {}",
+                            batch.len(),
+                            attempt,
+                            e
+                        );
+                        break;
+                    }
+                }
+            }
+            batch.clear();
+        }
+
+        Ok(())
+    }
+
+    /// Check every `interval` whether a shared engine is due for a full
+    /// retrain, and retrain it when it is (including the first train)
+    ///
+    /// The model is fitted without holding the engine lock; only swapping
+    /// it in takes the write lock.
+    pub fn spawn_retraining(
+        engine: Arc<RwLock<Self>>,
+        interval: std::time::Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let (reason, pool, config, training_window) = {
+                    let engine = engine.read().await;
+                    (
+                        engine.retrain_reason(),
+                        engine.pool.clone(),
+                        engine.als_config.clone(),
+                        engine.streaming.training_window,
+                    )
+                };
+                let Some(reason) = reason else {
+                    continue;
+                };
+
+                tracing::info!("Retraining collaborative filtering model: {:?}", reason);
+                let result = match Self::fit_model(&pool, config, training_window).await {
+                    Ok((model, trained_interactions)) => {
+                        engine
+                            .write()
+                            .await
+                            .install_model(model, trained_interactions)
+                            .await
+                    }
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = result {
+                    tracing::warn!("Collaborative filtering retrain failed: {}", e);
+                }
+            }
+        })
+    }
+
     /// Get "users who watched X also watched Y" recommendations
     pub async fn get_also_watched(&self, item_id: Uuid, limit: usize) -> Result<Vec<(Uuid, f32)>> {
         self.compute_item_similarity(item_id, limit).await
@@ -537,4 +1238,183 @@ mod tests {
         engine.add_interaction(interaction);
         assert_eq!(engine.incremental_buffer.len(), 1);
     }
+
+    #[test]
+    fn test_viewing_activity_event() {
+        let user_id = Uuid::new_v4();
+        let mut viewing = ViewingEvent {
+            content_id: Uuid::new_v4(),
+            timestamp: Utc::now() - Duration::minutes(5),
+            completion_rate: 0.95,
+            rating: None,
+            is_rewatch: false,
+            dismissed: false,
+        };
+
+        let complete = viewing_activity_event(user_id, &viewing).unwrap();
+        assert_eq!(complete.timestamp, viewing.timestamp);
+        let interaction = Interaction::from_activity_event(&complete).unwrap();
+        assert_eq!(interaction.content_id, viewing.content_id);
+        assert_eq!(interaction.interaction_type, InteractionType::Completion);
+        assert_eq!(interaction.watch_progress, Some(0.95));
+
+        // Resubmitting the same viewing yields the same event id
+        assert_eq!(
+            viewing_activity_event(user_id, &viewing).unwrap().event_id,
+            complete.event_id
+        );
+
+        viewing.completion_rate = 0.3;
+        let abandon = viewing_activity_event(user_id, &viewing).unwrap();
+        assert_eq!(abandon.event_type, ActivityEventType::PlaybackAbandon);
+
+        viewing.rating = Some(4);
+        let rating = viewing_activity_event(user_id, &viewing).unwrap();
+        assert_eq!(
+            Interaction::from_activity_event(&rating)
+                .unwrap()
+                .interaction_type,
+            InteractionType::Rating(4.0)
+        );
+
+        viewing.dismissed = true;
+        assert!(viewing_activity_event(user_id, &viewing).is_none());
+    }
+
+    #[test]
+    fn test_interaction_from_activity_event() {
+        let user_id = Uuid::new_v4();
+        let content_id = Uuid::new_v4();
+
+        let abandon = UserActivityEvent::new(
+            user_id,
+            ActivityEventType::PlaybackAbandon,
+            serde_json::json!({ "completion_rate": 0.4 }),
+        )
+        .with_content_id(content_id.to_string());
+        let interaction = Interaction::from_activity_event(&abandon).unwrap();
+        assert_eq!(interaction.user_id, user_id);
+        assert_eq!(interaction.content_id, content_id);
+        assert_eq!(interaction.interaction_type, InteractionType::View);
+        assert_eq!(interaction.watch_progress, Some(0.4));
+
+        let rating = UserActivityEvent::new(
+            user_id,
+            ActivityEventType::ContentRating,
+            serde_json::json!({ "rating": 4.5 }),
+        )
+        .with_content_id(content_id.to_string());
+        assert_eq!(
+            Interaction::from_activity_event(&rating)
+                .unwrap()
+                .interaction_type,
+            InteractionType::Rating(4.5)
+        );
+
+        let complete = UserActivityEvent::new(
+            user_id,
+            ActivityEventType::PlaybackComplete,
+            serde_json::json!({}),
+        )
+        .with_content_id(content_id.to_string());
+        assert_eq!(
+            Interaction::from_activity_event(&complete)
+                .unwrap()
+                .interaction_type,
+            InteractionType::Completion
+        );
+
+        // No feedback signal, no content, or content outside the catalog ID space
+        let search = UserActivityEvent::new(
+            user_id,
+            ActivityEventType::SearchQuery,
+            serde_json::json!({ "query": "heist" }),
+        );
+        assert!(Interaction::from_activity_event(&search).is_none());
+        let unrated = UserActivityEvent::new(
+            user_id,
+            ActivityEventType::ContentRating,
+            serde_json::json!({}),
+        )
+        .with_content_id(content_id.to_string());
+        assert!(Interaction::from_activity_event(&unrated).is_none());
+        let external = UserActivityEvent::new(
+            user_id,
+            ActivityEventType::ContentView,
+            serde_json::json!({}),
+        )
+        .with_content_id("netflix:80100172");
+        assert!(Interaction::from_activity_event(&external).is_none());
+    }
+
+    #[test]
+    fn test_admit_events_within_lateness() {
+        let user_id = Uuid::new_v4();
+        let watermark = Utc::now();
+        let event = |offset: Duration| {
+            let mut event = UserActivityEvent::new(
+                user_id,
+                ActivityEventType::ContentView,
+                serde_json::json!({}),
+            );
+            event.timestamp = watermark + offset;
+            event
+        };
+        let too_late = event(-Duration::hours(2));
+        let applied = event(-Duration::minutes(10));
+        let late = event(-Duration::minutes(5));
+        let new = event(Duration::minutes(1));
+        let events = vec![
+            too_late,
+            applied.clone(),
+            late.clone(),
+            new.clone(),
+            new.clone(),
+        ];
+
+        let already: HashSet<Uuid> = [applied.event_id].into_iter().collect();
+        let (admitted, duplicates, dropped) =
+            admit_events(&events, Some(watermark), Duration::hours(1), &already);
+        let admitted: Vec<Uuid> = admitted.iter().map(|e| e.event_id).collect();
+        assert_eq!(admitted, vec![late.event_id, new.event_id]);
+        assert_eq!(duplicates, 2);
+        assert_eq!(dropped, 1);
+
+        // Nothing is late before the first watermark
+        let (admitted, _, dropped) =
+            admit_events(&events, None, Duration::hours(1), &HashSet::new());
+        assert_eq!(admitted.len(), 4);
+        assert_eq!(dropped, 0);
+    }
+
+    #[test]
+    fn test_drift_monitor_retrain_reasons() {
+        let config = StreamingConfig::default();
+        let trained_at = Utc::now();
+
+        let mut drift = DriftMonitor::new(trained_at, 1000);
+        assert_eq!(drift.retrain_reason(&config, trained_at), None);
+        assert_eq!(
+            drift.retrain_reason(&config, trained_at + Duration::hours(25)),
+            Some(RetrainReason::ModelAge)
+        );
+
+        drift.record(150, 5);
+        assert_eq!(drift.retrain_reason(&config, trained_at), None);
+        drift.record(50, 0);
+        assert_eq!(
+            drift.retrain_reason(&config, trained_at),
+            Some(RetrainReason::FoldedVolume)
+        );
+
+        // Unknown items only count once the sample is large enough
+        let mut drift = DriftMonitor::new(trained_at, 1000);
+        drift.record(0, 50);
+        assert_eq!(drift.retrain_reason(&config, trained_at), None);
+        drift.record(60, 10);
+        assert_eq!(
+            drift.retrain_reason(&config, trained_at),
+            Some(RetrainReason::UnknownItems)
+        );
+    }
 }
@@ -3,12 +3,41 @@
 //! Uses content features and embeddings to find similar items
 
 use anyhow::Result;
-use qdrant_client::qdrant::{Condition, FieldCondition, Filter, Match, SearchPoints};
+use qdrant_client::qdrant::{
+    vector_output, Condition, FieldCondition, Filter, GetPointsBuilder, Match, PointId,
+    SearchPoints,
+};
 use qdrant_client::Qdrant;
 use sqlx::{PgPool, Row};
 use std::collections::HashMap;
+use tracing::warn;
 use uuid::Uuid;
 
+/// Lookup of stored content embeddings
+#[async_trait::async_trait]
+pub trait ContentEmbeddingStore: Send + Sync {
+    /// Embeddings of the given titles; titles without a vector are left out
+    async fn content_embeddings(&self, content_ids: &[Uuid]) -> Result<HashMap<Uuid, Vec<f32>>>;
+}
+
+/// Embeddings of `content_ids` from `store`, looked up in one batch; empty
+/// without a store or when the lookup fails
+pub(crate) async fn prefetch_embeddings(
+    store: Option<&dyn ContentEmbeddingStore>,
+    content_ids: &[Uuid],
+) -> HashMap<Uuid, Vec<f32>> {
+    match store {
+        Some(store) if !content_ids.is_empty() => store
+            .content_embeddings(content_ids)
+            .await
+            .unwrap_or_else(|e| {
+                warn!("Failed to load content embeddings: {}", e);
+                HashMap::new()
+            }),
+        _ => HashMap::new(),
+    }
+}
+
 /// Content-based recommendation engine
 pub struct ContentBasedEngine {
     pool: PgPool,
@@ -257,6 +286,40 @@ impl ContentBasedEngine {
     }
 }
 
+/// Content vectors from the engine's Qdrant collection, whose point ids are
+/// content ids
+#[async_trait::async_trait]
+impl ContentEmbeddingStore for ContentBasedEngine {
+    async fn content_embeddings(&self, content_ids: &[Uuid]) -> Result<HashMap<Uuid, Vec<f32>>> {
+        let point_ids: Vec<PointId> = content_ids.iter().map(|id| id.to_string().into()).collect();
+        let response = self
+            .qdrant
+            .get_points(
+                GetPointsBuilder::new(self.collection_name.clone(), point_ids)
+                    .with_vectors(true)
+                    .with_payload(false),
+            )
+            .await?;
+
+        Ok(response
+            .result
+            .into_iter()
+            .filter_map(|point| {
+                let id = match point.id?.point_id_options? {
+                    qdrant_client::qdrant::point_id::PointIdOptions::Uuid(id) => {
+                        Uuid::parse_str(&id).ok()?
+                    }
+                    qdrant_client::qdrant::point_id::PointIdOptions::Num(_) => return None,
+                };
+                match point.vectors?.get_vector()? {
+                    vector_output::Vector::Dense(dense) => Some((id, dense.data)),
+                    _ => None,
+                }
+            })
+            .collect())
+    }
+}
+
 /// Content features for similarity computation
 #[derive(Debug, Clone)]
 pub struct ContentFeatures {
@@ -2,10 +2,13 @@
 //!
 //! Filters recommendations based on temporal context, device type, and mood.
 
+use crate::diversity::ContentAttributes;
+use crate::mood::{current_mood_label, MoodConfig};
 use crate::profile::UserProfile;
+use crate::temporal::{LengthBucket, TemporalPatternConfig};
 use crate::types::{RecommendationContext, RecommendationType, ScoredContent, TemporalContext};
 use anyhow::{Context, Result};
-use chrono::{Timelike, Utc};
+use chrono::{DateTime, Timelike, Utc};
 use sqlx::{PgPool, Row};
 use tracing::{debug, error, info};
 use uuid::Uuid;
@@ -46,8 +49,13 @@ impl ContextAwareFilter {
             candidates.extend(device_candidates);
         }
 
-        // Mood-based filtering
-        if let Some(mood) = &context.mood {
+        // Mood-based filtering, falling back to the mood picked recently or
+        // inferred from in-session behavior
+        let mood = context.mood.clone().or_else(|| {
+            current_mood_label(&profile.mood_history, Utc::now(), &MoodConfig::default())
+                .map(str::to_string)
+        });
+        if let Some(mood) = &mood {
             let mood_candidates = self.filter_by_mood(mood, limit).await?;
             candidates.extend(mood_candidates);
         }
@@ -68,8 +76,8 @@ impl ContextAwareFilter {
     ) -> Result<Vec<ScoredContent>> {
         let start = std::time::Instant::now();
 
-        // Get current hour
-        let current_hour = Utc::now().hour() as usize;
+        // Get current hour in the user's local time
+        let current_hour = profile.temporal_patterns.local_time(Utc::now()).hour() as usize;
 
         // Get user's historical preference for this hour
         let hour_preference = if current_hour < profile.temporal_patterns.hourly_patterns.len() {
@@ -395,6 +403,94 @@ impl ContextAwareFilter {
         // Weighted average
         hourly_score * 0.6 + weekday_score * 0.4
     }
+
+    /// Temporal fit of a title for the user at `at`, in [0, 1]
+    ///
+    /// Averages the user's learned preference for the title's genres and for
+    /// its length in the current viewing slot; 0.5 when neither is known.
+    pub fn calculate_content_temporal_score(
+        temporal_patterns: &TemporalContext,
+        at: DateTime<Utc>,
+        attributes: &ContentAttributes,
+        config: &TemporalPatternConfig,
+    ) -> f32 {
+        let slot = temporal_patterns.slot_at(at).index();
+        let mut fits = Vec::new();
+
+        let genre_fits: Vec<f32> = attributes
+            .genres
+            .iter()
+            .filter_map(|g| temporal_patterns.genre_slot_patterns.get(&g.to_lowercase()))
+            .filter_map(|pattern| pattern.get(slot).copied())
+            .collect();
+        if !genre_fits.is_empty() {
+            fits.push(genre_fits.iter().sum::<f32>() / genre_fits.len() as f32);
+        }
+
+        if let Some(runtime) = attributes.runtime_minutes.filter(|&r| r > 0.0) {
+            let bucket = LengthBucket::from_runtime(runtime, config).index();
+            if let Some(&fit) = temporal_patterns
+                .length_slot_patterns
+                .get(bucket)
+                .and_then(|pattern| pattern.get(slot))
+            {
+                fits.push(fit);
+            }
+        }
+
+        if fits.is_empty() {
+            0.5
+        } else {
+            fits.iter().sum::<f32>() / fits.len() as f32
+        }
+    }
+
+    /// Scale candidate scores by their temporal fit, keeping score order
+    ///
+    /// A fit of 1.0 raises a score by `weight` and 0.0 lowers it by
+    /// `weight`; neutral fits leave it unchanged.
+    pub fn apply_temporal_patterns(
+        candidates: Vec<ScoredContent>,
+        temporal_patterns: &TemporalContext,
+        at: DateTime<Utc>,
+        weight: f32,
+        get_content_attributes: impl Fn(Uuid) -> Option<ContentAttributes>,
+    ) -> Vec<ScoredContent> {
+        if temporal_patterns.genre_slot_patterns.is_empty()
+            && temporal_patterns.length_slot_patterns.is_empty()
+        {
+            return candidates;
+        }
+
+        let config = TemporalPatternConfig::default();
+        let mut scored: Vec<ScoredContent> = candidates
+            .into_iter()
+            .map(|mut c| {
+                if let Some(attributes) = get_content_attributes(c.content_id) {
+                    let fit = Self::calculate_content_temporal_score(
+                        temporal_patterns,
+                        at,
+                        &attributes,
+                        &config,
+                    );
+                    c.score *= 1.0 + (fit - 0.5) * 2.0 * weight;
+                }
+                c
+            })
+            .collect();
+        scored.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        debug!(
+            slot = temporal_patterns.slot_at(at).index(),
+            candidates = scored.len(),
+            "Applied temporal patterns"
+        );
+        scored
+    }
 }
 
 #[cfg(test)]
@@ -408,9 +504,109 @@ mod tests {
             weekday_patterns: vec![0.7; 7],
             seasonal_patterns: vec![0.5; 4],
             recent_bias: 0.8,
+            ..Default::default()
         };
 
         let score = ContextAwareFilter::calculate_temporal_score(&patterns, 14, 2);
         assert!((score - 0.46).abs() < 0.01); // 0.3*0.6 + 0.7*0.4
     }
+
+    #[test]
+    fn test_temporal_patterns_favor_short_comedies_on_weeknights() {
+        use crate::temporal::{LearnTemporalPatterns, PlaybackSession};
+        use chrono::{Duration, TimeZone};
+
+        // Short comedies on weekday evenings, long dramas on weekends
+        let monday = Utc.with_ymd_and_hms(2024, 3, 4, 0, 0, 0).unwrap();
+        let mut sessions = Vec::new();
+        for week in 0..4 {
+            let start = monday + Duration::weeks(week);
+            for day in 0..4 {
+                sessions.push(PlaybackSession {
+                    content_id: Uuid::new_v4(),
+                    started_at: start + Duration::days(day) + Duration::hours(20),
+                    watched_minutes: 25.0,
+                    runtime_minutes: Some(25.0),
+                    genres: vec!["comedy".to_string()],
+                });
+            }
+            sessions.push(PlaybackSession {
+                content_id: Uuid::new_v4(),
+                started_at: start + Duration::days(5) + Duration::hours(15),
+                watched_minutes: 120.0,
+                runtime_minutes: Some(120.0),
+                genres: vec!["drama".to_string()],
+            });
+        }
+        let now = monday + Duration::weeks(4);
+        let patterns =
+            LearnTemporalPatterns::execute(&sessions, 0, &TemporalPatternConfig::default(), now)
+                .unwrap();
+
+        let comedy = Uuid::from_u128(1);
+        let drama = Uuid::from_u128(2);
+        let attributes = |id: Uuid| {
+            Some(if id == comedy {
+                ContentAttributes {
+                    genres: vec!["Comedy".to_string()],
+                    runtime_minutes: Some(22.0),
+                    ..Default::default()
+                }
+            } else {
+                ContentAttributes {
+                    genres: vec!["Drama".to_string()],
+                    runtime_minutes: Some(140.0),
+                    ..Default::default()
+                }
+            })
+        };
+        let candidates = || {
+            vec![
+                ScoredContent {
+                    content_id: drama,
+                    score: 0.8,
+                    source: RecommendationType::ContextAware,
+                    based_on: vec![],
+                },
+                ScoredContent {
+                    content_id: comedy,
+                    score: 0.75,
+                    source: RecommendationType::ContextAware,
+                    based_on: vec![],
+                },
+            ]
+        };
+
+        // Wednesday 21:00
+        let weeknight = monday + Duration::weeks(5) + Duration::days(2) + Duration::hours(21);
+        let ranked = ContextAwareFilter::apply_temporal_patterns(
+            candidates(),
+            &patterns,
+            weeknight,
+            0.2,
+            attributes,
+        );
+        assert_eq!(ranked[0].content_id, comedy);
+
+        // Saturday 15:00
+        let weekend = monday + Duration::weeks(5) + Duration::days(5) + Duration::hours(15);
+        let ranked = ContextAwareFilter::apply_temporal_patterns(
+            candidates(),
+            &patterns,
+            weekend,
+            0.2,
+            attributes,
+        );
+        assert_eq!(ranked[0].content_id, drama);
+
+        // Untrained patterns leave scores alone
+        let ranked = ContextAwareFilter::apply_temporal_patterns(
+            candidates(),
+            &TemporalContext::default(),
+            weeknight,
+            0.2,
+            attributes,
+        );
+        assert_eq!(ranked[0].score, 0.8);
+    }
 }
@@ -2,13 +2,220 @@
 //!
 //! Implements ApplyDiversityFilter algorithm from SPARC pseudocode.
 //! Balances relevance with diversity to avoid redundant recommendations.
+//!
+//! Embeddings are fetched once per candidate and L2-normalized up front, and
+//! each candidate's maximum similarity to the selected set is updated
+//! incrementally as items are picked, so a request costs O(limit × candidates)
+//! dot products. Besides MMR, callers can choose genre/platform quotas or a
+//! determinantal point process per request via [`DiversityConfig`].
 
+use crate::rerank::parse_maturity_rating;
 use crate::types::ScoredContent;
-use anyhow::Result;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use media_gateway_core::types::MaturityRating;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Row};
+use std::collections::HashMap;
 use uuid::Uuid;
 
 const LAMBDA: f32 = 0.7; // Balance between relevance and diversity
 
+/// Determinant gains below this mean the remaining candidates are redundant
+const DPP_EPSILON: f32 = 1e-6;
+
+/// Diversification strategy
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiversityMode {
+    /// Maximal marginal relevance over embedding similarity
+    #[default]
+    Mmr,
+    /// Relevance order, capping how many items may share a genre or platform
+    Quota {
+        max_per_genre: Option<usize>,
+        max_per_platform: Option<usize>,
+    },
+    /// Greedy MAP inference on a determinantal point process whose kernel
+    /// combines relevance (quality) with embedding similarity
+    Dpp,
+    /// Relevance order only
+    Disabled,
+}
+
+/// Per-request diversification settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiversityConfig {
+    #[serde(default)]
+    pub mode: DiversityMode,
+    /// Relevance/diversity trade-off: 1.0 is pure relevance (default: 0.7)
+    #[serde(default = "default_lambda")]
+    pub lambda: f32,
+    /// Minimum cosine distance a pick must keep from every item already
+    /// selected; 0.0 disables the check (default: 0.0)
+    #[serde(default)]
+    pub min_distance: f32,
+}
+
+fn default_lambda() -> f32 {
+    LAMBDA
+}
+
+impl Default for DiversityConfig {
+    fn default() -> Self {
+        Self {
+            mode: DiversityMode::default(),
+            lambda: LAMBDA,
+            min_distance: 0.0,
+        }
+    }
+}
+
+impl DiversityConfig {
+    pub fn validate(&self) -> Result<()> {
+        anyhow::ensure!(
+            (0.0..=1.0).contains(&self.lambda),
+            "lambda must be in [0, 1], got {}",
+            self.lambda
+        );
+        anyhow::ensure!(
+            (0.0..=2.0).contains(&self.min_distance),
+            "min_distance must be in [0, 2], got {}",
+            self.min_distance
+        );
+        if let DiversityMode::Quota {
+            max_per_genre,
+            max_per_platform,
+        } = &self.mode
+        {
+            anyhow::ensure!(
+                *max_per_genre != Some(0) && *max_per_platform != Some(0),
+                "Quota limits must be positive"
+            );
+        }
+        Ok(())
+    }
+
+    fn needs_embeddings(&self) -> bool {
+        self.min_distance > 0.0 || matches!(self.mode, DiversityMode::Mmr | DiversityMode::Dpp)
+    }
+}
+
+/// Catalog attributes used by quota diversification and re-ranking
+#[derive(Debug, Clone, Default)]
+pub struct ContentAttributes {
+    pub genres: Vec<String>,
+    pub platforms: Vec<String>,
+    pub franchise: Option<String>,
+    /// Cast and crew names
+    pub people: Vec<String>,
+    pub maturity_rating: Option<MaturityRating>,
+    /// Availability window from ingestion
+    pub available_from: Option<DateTime<Utc>>,
+    pub available_until: Option<DateTime<Utc>>,
+    /// Predicted probability the user finishes the content
+    pub predicted_completion: Option<f32>,
+    pub runtime_minutes: Option<f32>,
+    /// Catalog and embedding-derived mood tags
+    pub moods: Vec<String>,
+}
+
+/// Catalog lookup of [`ContentAttributes`]
+#[async_trait::async_trait]
+pub trait ContentCatalog: Send + Sync {
+    /// Attributes of the given titles; unknown titles are left out
+    async fn content_attributes(
+        &self,
+        content_ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, ContentAttributes>>;
+}
+
+/// PostgreSQL implementation of ContentCatalog
+pub struct PostgresContentCatalog {
+    pool: PgPool,
+}
+
+impl PostgresContentCatalog {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl ContentCatalog for PostgresContentCatalog {
+    async fn content_attributes(
+        &self,
+        content_ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, ContentAttributes>> {
+        if content_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let rows = sqlx::query(
+            r#"
+            SELECT c.id,
+                   c.runtime_minutes,
+                   COALESCE(
+                       (SELECT ARRAY_AGG(cg.genre) FROM content_genres cg WHERE cg.content_id = c.id),
+                       '{}'
+                   ) AS genres,
+                   COALESCE(
+                       (SELECT ARRAY_AGG(DISTINCT pa.platform) FROM platform_availability pa
+                        WHERE pa.content_id = c.id
+                          AND (pa.expires_at IS NULL OR pa.expires_at > NOW())),
+                       '{}'
+                   ) AS platforms,
+                   COALESCE(
+                       (SELECT ARRAY_AGG(cr.person_name ORDER BY cr.order_index) FROM credits cr
+                        WHERE cr.content_id = c.id),
+                       '{}'
+                   ) AS people,
+                   (SELECT MIN(pa.available_from) FROM platform_availability pa
+                    WHERE pa.content_id = c.id) AS available_from,
+                   (SELECT MAX(pa.expires_at) FROM platform_availability pa
+                    WHERE pa.content_id = c.id) AS available_until,
+                   COALESCE(
+                       (SELECT ARRAY_AGG(cm.mood) FROM content_moods cm WHERE cm.content_id = c.id),
+                       '{}'
+                   ) AS moods,
+                   c.franchise,
+                   (SELECT r.rating FROM content_ratings r WHERE r.content_id = c.id
+                    ORDER BY (r.region = 'US') DESC, r.region
+                    LIMIT 1) AS maturity_rating,
+                   (SELECT AVG(wp.completion_rate)::REAL FROM watch_progress wp
+                    WHERE wp.content_id = c.id) AS predicted_completion
+            FROM content c
+            WHERE c.id = ANY($1)
+            "#,
+        )
+        .bind(content_ids)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to load content attributes")?;
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let runtime_minutes: Option<i32> = row.get("runtime_minutes");
+                let maturity_rating: Option<String> = row.get("maturity_rating");
+                let attributes = ContentAttributes {
+                    genres: row.get("genres"),
+                    platforms: row.get("platforms"),
+                    franchise: row.get("franchise"),
+                    people: row.get("people"),
+                    maturity_rating: maturity_rating.as_deref().and_then(parse_maturity_rating),
+                    available_from: row.get("available_from"),
+                    available_until: row.get("available_until"),
+                    predicted_completion: row.get("predicted_completion"),
+                    runtime_minutes: runtime_minutes.map(|m| m as f32),
+                    moods: row.get("moods"),
+                };
+                (row.get("id"), attributes)
+            })
+            .collect())
+    }
+}
+
 /// Apply diversity filter using MMR algorithm
 ///
 /// Algorithm: ApplyDiversityFilter (from SPARC pseudocode Part 2)
@@ -22,75 +229,316 @@ const LAMBDA: f32 = 0.7; // Balance between relevance and diversity
 pub struct ApplyDiversityFilter;
 
 impl ApplyDiversityFilter {
+    /// MMR with the default λ, rejecting picks closer than `threshold`
+    /// (cosine distance) to anything already selected
     pub fn execute(
+        candidates: Vec<ScoredContent>,
+        threshold: f32,
+        limit: usize,
+        get_content_embedding: impl Fn(Uuid) -> Result<Vec<f32>>,
+    ) -> Result<Vec<ScoredContent>> {
+        let config = DiversityConfig {
+            min_distance: threshold,
+            ..Default::default()
+        };
+        Self::apply(candidates, limit, &config, get_content_embedding, |_| None)
+    }
+
+    /// Select up to `limit` candidates using the configured strategy
+    ///
+    /// Embeddings are only fetched when the mode or `min_distance` needs them;
+    /// attributes are only consulted for quotas. Lists may come back shorter
+    /// than `limit` when the distance threshold or quotas exclude candidates.
+    pub fn apply(
         mut candidates: Vec<ScoredContent>,
-        _threshold: f32,
         limit: usize,
+        config: &DiversityConfig,
         get_content_embedding: impl Fn(Uuid) -> Result<Vec<f32>>,
+        get_content_attributes: impl Fn(Uuid) -> Option<ContentAttributes>,
     ) -> Result<Vec<ScoredContent>> {
+        config.validate()?;
+
         // Sort by score (descending)
-        candidates.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
+        candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+        if limit == 0 || candidates.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut tracker = if config.needs_embeddings() {
+            Some(SimilarityTracker::fetch(
+                &candidates,
+                config.min_distance,
+                get_content_embedding,
+            )?)
+        } else {
+            None
+        };
+
+        let order = match &config.mode {
+            DiversityMode::Mmr => {
+                let tracker = tracker.as_mut().expect("MMR fetches embeddings");
+                Self::select_mmr(&candidates, tracker, limit, config.lambda)
+            }
+            DiversityMode::Dpp => {
+                let tracker = tracker.as_mut().expect("DPP fetches embeddings");
+                Self::select_dpp(&candidates, tracker, limit, config.lambda)
+            }
+            DiversityMode::Quota {
+                max_per_genre,
+                max_per_platform,
+            } => Self::select_quota(
+                &candidates,
+                tracker.as_mut(),
+                limit,
+                *max_per_genre,
+                *max_per_platform,
+                get_content_attributes,
+            ),
+            DiversityMode::Disabled => {
+                Self::select_quota(&candidates, tracker.as_mut(), limit, None, None, |_| None)
+            }
+        };
 
-        let mut selected: Vec<ScoredContent> = Vec::new();
-        let mut remaining = candidates;
+        let mut slots: Vec<Option<ScoredContent>> = candidates.into_iter().map(Some).collect();
+        Ok(order.into_iter().filter_map(|i| slots[i].take()).collect())
+    }
 
-        while selected.len() < limit && !remaining.is_empty() {
-            let mut best_score = f32::NEG_INFINITY;
-            let mut best_index = 0;
-            let mut best_candidate = None;
+    fn select_mmr(
+        candidates: &[ScoredContent],
+        tracker: &mut SimilarityTracker,
+        limit: usize,
+        lambda: f32,
+    ) -> Vec<usize> {
+        let mut selected = Vec::with_capacity(limit);
 
-            for (index, candidate) in remaining.iter().enumerate() {
+        while selected.len() < limit {
+            let mut best: Option<(usize, f32)> = None;
+            for (index, candidate) in candidates.iter().enumerate() {
+                if !tracker.is_eligible(index) {
+                    continue;
+                }
                 // MMR score = λ * relevance - (1-λ) * max_similarity_to_selected
-                let relevance = candidate.score;
-
-                let max_similarity = if selected.is_empty() {
-                    0.0
-                } else {
-                    let candidate_embedding = get_content_embedding(candidate.content_id)?;
-
-                    let mut max_sim: f32 = 0.0;
-                    for s in &selected {
-                        let selected_embedding = get_content_embedding(s.content_id)?;
-                        let sim =
-                            Self::cosine_similarity(&candidate_embedding, &selected_embedding);
-                        max_sim = max_sim.max(sim);
-                    }
-                    max_sim
-                };
+                let mmr_score =
+                    lambda * candidate.score - (1.0 - lambda) * tracker.max_similarity[index];
+                if best.map_or(true, |(_, best_score)| mmr_score > best_score) {
+                    best = Some((index, mmr_score));
+                }
+            }
+
+            let Some((index, _)) = best else {
+                break;
+            };
+            tracker.select(index);
+            selected.push(index);
+        }
+
+        selected
+    }
+
+    /// Fast greedy MAP inference (Chen et al., 2018) with kernel
+    /// `L_ij = q_i * S_ij * q_j`, where `q_i = exp(α * r_i)` and
+    /// `α = λ / (2 * (1 - λ))` over min-max normalized relevance `r_i`.
+    /// Each candidate keeps an incremental Cholesky row, so a pick costs
+    /// O(candidates × selected).
+    fn select_dpp(
+        candidates: &[ScoredContent],
+        tracker: &mut SimilarityTracker,
+        limit: usize,
+        lambda: f32,
+    ) -> Vec<usize> {
+        // With λ = 1 quality dominates entirely: plain relevance order
+        if lambda >= 0.999 {
+            return Self::select_quota(candidates, Some(tracker), limit, None, None, |_| None);
+        }
+
+        let max = candidates.first().map_or(0.0, |c| c.score);
+        let min = candidates.last().map_or(0.0, |c| c.score);
+        let range = (max - min).max(f32::EPSILON);
+        let alpha = lambda / (2.0 * (1.0 - lambda));
+        let quality: Vec<f32> = candidates
+            .iter()
+            .map(|c| (alpha * (c.score - min) / range).exp())
+            .collect();
+
+        let n = candidates.len();
+        let mut gains: Vec<f32> = quality.iter().map(|q| q * q).collect();
+        let mut cholesky: Vec<Vec<f32>> = vec![Vec::with_capacity(limit); n];
+        let mut selected = Vec::with_capacity(limit);
 
-                let mmr_score = LAMBDA * relevance - (1.0 - LAMBDA) * max_similarity;
+        while selected.len() < limit {
+            let best = (0..n)
+                .filter(|&i| tracker.is_eligible(i) && gains[i] > DPP_EPSILON)
+                .max_by(|&a, &b| gains[a].total_cmp(&gains[b]));
+            let Some(j) = best else {
+                break;
+            };
+
+            tracker.select(j);
+            selected.push(j);
 
-                if mmr_score > best_score {
-                    best_score = mmr_score;
-                    best_index = index;
-                    best_candidate = Some(candidate.clone());
+            let d_j = gains[j].sqrt();
+            let c_j = cholesky[j].clone();
+            for i in 0..n {
+                if tracker.selected[i] {
+                    continue;
                 }
+                let kernel = quality[i] * quality[j] * tracker.similarity(i, j);
+                let projection: f32 = c_j.iter().zip(&cholesky[i]).map(|(a, b)| a * b).sum();
+                let e_i = (kernel - projection) / d_j;
+                cholesky[i].push(e_i);
+                gains[i] -= e_i * e_i;
             }
+        }
 
-            if let Some(candidate) = best_candidate {
-                selected.push(candidate);
-                remaining.remove(best_index);
-            } else {
+        // Candidates the kernel considers redundant still backfill in
+        // relevance order, subject to the distance threshold
+        if selected.len() < limit {
+            for index in 0..n {
+                if selected.len() >= limit {
+                    break;
+                }
+                if tracker.is_eligible(index) {
+                    tracker.select(index);
+                    selected.push(index);
+                }
+            }
+        }
+
+        selected
+    }
+
+    fn select_quota(
+        candidates: &[ScoredContent],
+        mut tracker: Option<&mut SimilarityTracker>,
+        limit: usize,
+        max_per_genre: Option<usize>,
+        max_per_platform: Option<usize>,
+        get_content_attributes: impl Fn(Uuid) -> Option<ContentAttributes>,
+    ) -> Vec<usize> {
+        let mut genre_counts: HashMap<String, usize> = HashMap::new();
+        let mut platform_counts: HashMap<String, usize> = HashMap::new();
+        let mut selected = Vec::with_capacity(limit);
+
+        for (index, candidate) in candidates.iter().enumerate() {
+            if selected.len() >= limit {
                 break;
             }
+            if let Some(tracker) = tracker.as_deref() {
+                if !tracker.is_eligible(index) {
+                    continue;
+                }
+            }
+
+            let attributes = if max_per_genre.is_some() || max_per_platform.is_some() {
+                get_content_attributes(candidate.content_id).unwrap_or_default()
+            } else {
+                ContentAttributes::default()
+            };
+            let over = |counts: &HashMap<String, usize>, keys: &[String], cap: Option<usize>| {
+                cap.is_some_and(|cap| {
+                    keys.iter()
+                        .any(|key| counts.get(key).copied().unwrap_or(0) >= cap)
+                })
+            };
+            if over(&genre_counts, &attributes.genres, max_per_genre)
+                || over(&platform_counts, &attributes.platforms, max_per_platform)
+            {
+                continue;
+            }
+
+            for genre in attributes.genres {
+                *genre_counts.entry(genre).or_insert(0) += 1;
+            }
+            for platform in attributes.platforms {
+                *platform_counts.entry(platform).or_insert(0) += 1;
+            }
+            if let Some(tracker) = tracker.as_deref_mut() {
+                tracker.select(index);
+            }
+            selected.push(index);
+        }
+
+        selected
+    }
+}
+
+/// Normalized candidate embeddings plus each candidate's running maximum
+/// similarity to the selected set
+struct SimilarityTracker {
+    /// Row-major `[candidates x dim]`, unit length (or zero when missing)
+    vectors: Vec<f32>,
+    dim: usize,
+    max_similarity: Vec<f32>,
+    selected: Vec<bool>,
+    min_distance: f32,
+}
+
+impl SimilarityTracker {
+    fn fetch(
+        candidates: &[ScoredContent],
+        min_distance: f32,
+        get_content_embedding: impl Fn(Uuid) -> Result<Vec<f32>>,
+    ) -> Result<Self> {
+        let embeddings = candidates
+            .iter()
+            .map(|c| get_content_embedding(c.content_id))
+            .collect::<Result<Vec<_>>>()?;
+        let dim = embeddings.iter().map(Vec::len).max().unwrap_or(0);
+
+        let mut vectors = vec![0.0; candidates.len() * dim];
+        for (row, embedding) in vectors.chunks_mut(dim.max(1)).zip(&embeddings) {
+            // Mismatched dimensions compare as dissimilar, as zero vectors
+            if embedding.len() != dim {
+                continue;
+            }
+            let norm = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
+            if norm > 0.0 {
+                for (v, e) in row.iter_mut().zip(embedding) {
+                    *v = e / norm;
+                }
+            }
         }
 
-        Ok(selected)
+        Ok(Self {
+            vectors,
+            dim,
+            max_similarity: vec![0.0; candidates.len()],
+            selected: vec![false; candidates.len()],
+            min_distance,
+        })
+    }
+
+    fn row(&self, index: usize) -> &[f32] {
+        &self.vectors[index * self.dim..(index + 1) * self.dim]
     }
 
-    fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
-        if a.len() != b.len() {
-            return 0.0;
+    /// Cosine similarity; the diagonal is 1 even for missing embeddings so
+    /// the DPP kernel stays positive definite
+    fn similarity(&self, a: usize, b: usize) -> f32 {
+        if a == b {
+            return 1.0;
         }
+        self.row(a)
+            .iter()
+            .zip(self.row(b))
+            .map(|(x, y)| x * y)
+            .sum()
+    }
 
-        let dot_product: f32 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
-        let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
-        let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    fn is_eligible(&self, index: usize) -> bool {
+        !self.selected[index]
+            && (self.min_distance <= 0.0 || 1.0 - self.max_similarity[index] >= self.min_distance)
+    }
 
-        if norm_a == 0.0 || norm_b == 0.0 {
-            0.0
-        } else {
-            dot_product / (norm_a * norm_b)
+    fn select(&mut self, index: usize) {
+        self.selected[index] = true;
+        for other in 0..self.selected.len() {
+            if !self.selected[other] {
+                let sim = self.similarity(index, other);
+                if sim > self.max_similarity[other] {
+                    self.max_similarity[other] = sim;
+                }
+            }
         }
     }
 }
@@ -98,17 +546,191 @@ impl ApplyDiversityFilter {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::RecommendationType;
+    use std::cell::Cell;
+
+    fn candidate(n: u128, score: f32) -> ScoredContent {
+        ScoredContent {
+            content_id: Uuid::from_u128(n),
+            score,
+            source: RecommendationType::Hybrid,
+            based_on: Vec::new(),
+        }
+    }
+
+    /// Items 1-3 are near-duplicates; 4 and 5 point elsewhere
+    fn embedding(id: Uuid) -> Result<Vec<f32>> {
+        Ok(match id.as_u128() {
+            1 => vec![1.0, 0.0, 0.0],
+            2 => vec![0.99, 0.05, 0.0],
+            3 => vec![0.98, 0.0, 0.05],
+            4 => vec![0.0, 1.0, 0.0],
+            _ => vec![0.0, 0.0, 1.0],
+        })
+    }
+
+    fn candidates() -> Vec<ScoredContent> {
+        vec![
+            candidate(1, 1.0),
+            candidate(2, 0.95),
+            candidate(3, 0.9),
+            candidate(4, 0.6),
+            candidate(5, 0.55),
+        ]
+    }
+
+    fn ids(results: &[ScoredContent]) -> Vec<u128> {
+        results.iter().map(|c| c.content_id.as_u128()).collect()
+    }
 
     #[test]
     fn test_cosine_similarity() {
-        let a = vec![1.0, 0.0, 0.0];
-        let b = vec![1.0, 0.0, 0.0];
-        let sim = ApplyDiversityFilter::cosine_similarity(&a, &b);
+        let candidates = vec![candidate(1, 1.0), candidate(2, 0.9), candidate(4, 0.8)];
+        let tracker = SimilarityTracker::fetch(&candidates, 0.0, |id| {
+            Ok(match id.as_u128() {
+                1 => vec![1.0, 0.0, 0.0],
+                2 => vec![2.0, 0.0, 0.0],
+                _ => vec![0.0, 1.0, 0.0],
+            })
+        })
+        .unwrap();
+
+        let sim = tracker.similarity(0, 1);
         assert!((sim - 1.0).abs() < 0.001);
 
-        let c = vec![1.0, 0.0, 0.0];
-        let d = vec![0.0, 1.0, 0.0];
-        let sim2 = ApplyDiversityFilter::cosine_similarity(&c, &d);
+        let sim2 = tracker.similarity(0, 2);
         assert!((sim2 - 0.0).abs() < 0.001);
     }
+
+    #[test]
+    fn test_embeddings_fetched_once_per_candidate() {
+        let calls = Cell::new(0);
+        let results = ApplyDiversityFilter::execute(candidates(), 0.0, 5, |id| {
+            calls.set(calls.get() + 1);
+            embedding(id)
+        })
+        .unwrap();
+
+        assert_eq!(results.len(), 5);
+        assert_eq!(calls.get(), 5);
+    }
+
+    #[test]
+    fn test_mmr_promotes_dissimilar_items() {
+        let results = ApplyDiversityFilter::execute(candidates(), 0.0, 3, embedding).unwrap();
+        assert_eq!(ids(&results), vec![1, 4, 5]);
+    }
+
+    #[test]
+    fn test_lambda_one_is_relevance_order() {
+        let config = DiversityConfig {
+            lambda: 1.0,
+            ..Default::default()
+        };
+        let results =
+            ApplyDiversityFilter::apply(candidates(), 3, &config, embedding, |_| None).unwrap();
+        assert_eq!(ids(&results), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_threshold_rejects_near_duplicates() {
+        let config = DiversityConfig {
+            mode: DiversityMode::Disabled,
+            min_distance: 0.3,
+            ..Default::default()
+        };
+        let results =
+            ApplyDiversityFilter::apply(candidates(), 5, &config, embedding, |_| None).unwrap();
+        assert_eq!(ids(&results), vec![1, 4, 5]);
+    }
+
+    #[test]
+    fn test_quota_caps_genres_and_platforms() {
+        let attributes = |id: Uuid| {
+            let (genre, platform) = match id.as_u128() {
+                1..=3 => ("drama", "netflix"),
+                4 => ("drama", "hulu"),
+                _ => ("comedy", "netflix"),
+            };
+            Some(ContentAttributes {
+                genres: vec![genre.to_string()],
+                platforms: vec![platform.to_string()],
+                ..Default::default()
+            })
+        };
+        let config = DiversityConfig {
+            mode: DiversityMode::Quota {
+                max_per_genre: Some(2),
+                max_per_platform: Some(2),
+            },
+            ..Default::default()
+        };
+
+        let calls = Cell::new(0);
+        let results = ApplyDiversityFilter::apply(
+            candidates(),
+            5,
+            &config,
+            |_| {
+                calls.set(calls.get() + 1);
+                Ok(Vec::new())
+            },
+            attributes,
+        )
+        .unwrap();
+
+        // 1, 2 fill the drama and netflix quotas; 4 is drama, 5 is netflix
+        assert_eq!(ids(&results), vec![1, 2]);
+        assert_eq!(calls.get(), 0, "quotas alone need no embeddings");
+    }
+
+    #[test]
+    fn test_dpp_avoids_redundant_items() {
+        let config = DiversityConfig {
+            mode: DiversityMode::Dpp,
+            lambda: 0.5,
+            ..Default::default()
+        };
+        let results =
+            ApplyDiversityFilter::apply(candidates(), 3, &config, embedding, |_| None).unwrap();
+
+        let picked = ids(&results);
+        assert_eq!(picked[0], 1);
+        assert!(picked.contains(&4) && picked.contains(&5));
+    }
+
+    #[test]
+    fn test_dpp_backfills_when_kernel_is_exhausted() {
+        let config = DiversityConfig {
+            mode: DiversityMode::Dpp,
+            lambda: 0.5,
+            ..Default::default()
+        };
+        let duplicates = vec![candidate(1, 1.0), candidate(1, 0.9), candidate(1, 0.8)];
+        let results =
+            ApplyDiversityFilter::apply(duplicates, 3, &config, embedding, |_| None).unwrap();
+        assert_eq!(results.len(), 3);
+    }
+
+    #[test]
+    fn test_invalid_config_rejected() {
+        let config = DiversityConfig {
+            lambda: 1.5,
+            ..Default::default()
+        };
+        assert!(
+            ApplyDiversityFilter::apply(candidates(), 3, &config, embedding, |_| None).is_err()
+        );
+
+        let config: DiversityConfig =
+            serde_json::from_str(r#"{"mode": {"quota": {"max_per_genre": 2}}}"#).unwrap();
+        assert_eq!(config.lambda, LAMBDA);
+        assert_eq!(
+            config.mode,
+            DiversityMode::Quota {
+                max_per_genre: Some(2),
+                max_per_platform: None
+            }
+        );
+    }
 }
@@ -0,0 +1,625 @@
+//! Onboarding Preference Elicitation
+//!
+//! Interactive cold-start flow: show a new user a handful of titles, record
+//! like/dislike/skip, and repeat until there is enough signal for the regular
+//! pipeline.
+//!
+//! The user's position in ALS latent space is modelled as a Gaussian, starting
+//! from the population of trained user factors. Each answer is a noisy
+//! observation of the user's preference for that item, so the posterior is
+//! updated with a rank-one (Kalman) step. Titles are chosen to maximize
+//! information gain, i.e. the predictive variance `vᵀΣv` of the item's
+//! factors `v`; since the variance reduction does not depend on the answer,
+//! a whole round is chosen greedily up front. Liked and disliked titles'
+//! content embeddings also build the profile's preference vector.
+
+use crate::matrix_factorization::MatrixFactorization;
+use crate::profile::{ProgressivePersonalization, UserProfile};
+use anyhow::{Context, Result};
+use ndarray::{Array1, Array2, ArrayView1, Axis};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Row};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// Elicitation settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ElicitationConfig {
+    /// Titles shown per round (default: 5)
+    pub questions_per_round: usize,
+    /// Titles shown before handing off regardless of answers (default: 30)
+    pub max_questions: usize,
+    /// Observation noise of a single answer (default: 0.5)
+    pub noise_variance: f32,
+    /// Preference-vector weight of a disliked title relative to a liked one
+    /// (default: 0.5)
+    pub dislike_weight: f32,
+}
+
+impl Default for ElicitationConfig {
+    fn default() -> Self {
+        Self {
+            questions_per_round: 5,
+            max_questions: 30,
+            noise_variance: 0.5,
+            dislike_weight: 0.5,
+        }
+    }
+}
+
+/// A user's reaction to a presented title
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ElicitationResponse {
+    Like,
+    Dislike,
+    /// Not seen or no opinion; carries no preference signal
+    Skip,
+}
+
+impl ElicitationResponse {
+    /// Implicit-feedback preference target, matching ALS training
+    fn target(self) -> Option<f32> {
+        match self {
+            Self::Like => Some(1.0),
+            Self::Dislike => Some(0.0),
+            Self::Skip => None,
+        }
+    }
+}
+
+/// Titles eligible for elicitation with their ALS item factors and the
+/// population prior over user factors
+#[derive(Debug, Clone)]
+pub struct ElicitationCatalog {
+    item_ids: Vec<Uuid>,
+    item_index: HashMap<Uuid, usize>,
+    /// [num_items x latent_factors]
+    item_factors: Array2<f32>,
+    prior_mean: Array1<f32>,
+    prior_variance: Array1<f32>,
+}
+
+impl ElicitationCatalog {
+    /// Variance floor so no latent dimension starts out fully determined
+    const MIN_PRIOR_VARIANCE: f32 = 1e-3;
+
+    /// Build from a trained model, using the mean and per-factor variance of
+    /// its user factors as the prior
+    pub fn from_model(model: &MatrixFactorization) -> Result<Self> {
+        let item_factors = model
+            .item_factors
+            .as_ref()
+            .context("Model not trained yet")?;
+        let user_factors = model
+            .user_factors
+            .as_ref()
+            .context("Model not trained yet")?;
+
+        let item_ids: Vec<Uuid> = (0..item_factors.nrows())
+            .map(|i| {
+                model
+                    .item_index_map
+                    .get(&i)
+                    .copied()
+                    .with_context(|| format!("Item index {} has no ID", i))
+            })
+            .collect::<Result<_>>()?;
+
+        let (prior_mean, prior_variance) = if user_factors.nrows() > 0 {
+            (
+                user_factors.mean_axis(Axis(0)).context("No user factors")?,
+                user_factors.var_axis(Axis(0), 0.0),
+            )
+        } else {
+            let dim = item_factors.ncols();
+            (Array1::zeros(dim), Array1::ones(dim))
+        };
+
+        Ok(Self::new(
+            item_ids,
+            item_factors.clone(),
+            prior_mean,
+            prior_variance,
+        ))
+    }
+
+    pub fn new(
+        item_ids: Vec<Uuid>,
+        item_factors: Array2<f32>,
+        prior_mean: Array1<f32>,
+        prior_variance: Array1<f32>,
+    ) -> Self {
+        let item_index = item_ids
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (*id, i))
+            .collect();
+        Self {
+            item_ids,
+            item_index,
+            item_factors,
+            prior_mean,
+            prior_variance: prior_variance.mapv(|v| v.max(Self::MIN_PRIOR_VARIANCE)),
+        }
+    }
+
+    /// Keep only the given titles, e.g. the most popular ones users are
+    /// likely to recognize
+    pub fn restrict_to(self, content_ids: &HashSet<Uuid>) -> Self {
+        let rows: Vec<usize> = self
+            .item_ids
+            .iter()
+            .enumerate()
+            .filter(|(_, id)| content_ids.contains(id))
+            .map(|(i, _)| i)
+            .collect();
+        let item_ids = rows.iter().map(|&i| self.item_ids[i]).collect();
+        let item_factors = self.item_factors.select(Axis(0), &rows);
+        Self::new(item_ids, item_factors, self.prior_mean, self.prior_variance)
+    }
+
+    pub fn len(&self) -> usize {
+        self.item_ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.item_ids.is_empty()
+    }
+
+    fn factors(&self, content_id: Uuid) -> Option<ArrayView1<'_, f32>> {
+        self.item_index
+            .get(&content_id)
+            .map(|&i| self.item_factors.row(i))
+    }
+}
+
+/// State of one user's onboarding
+///
+/// Serializable so it can be stored between requests.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ElicitationSession {
+    pub user_id: Uuid,
+    config: ElicitationConfig,
+    /// Posterior mean of the user's latent factors
+    mean: Vec<f32>,
+    /// Posterior covariance, row-major [latent_factors x latent_factors]
+    covariance: Vec<f32>,
+    /// Titles already presented, in order
+    presented: Vec<Uuid>,
+    answers: Vec<(Uuid, ElicitationResponse)>,
+    /// Signed sum of answered titles' content embeddings
+    embedding_sum: Vec<f32>,
+}
+
+impl ElicitationSession {
+    pub fn new(user_id: Uuid, catalog: &ElicitationCatalog, config: ElicitationConfig) -> Self {
+        let covariance = Array2::from_diag(&catalog.prior_variance);
+        Self {
+            user_id,
+            config,
+            mean: catalog.prior_mean.to_vec(),
+            covariance: covariance.into_raw_vec_and_offset().0,
+            presented: Vec::new(),
+            answers: Vec::new(),
+            embedding_sum: Vec::new(),
+        }
+    }
+
+    fn dim(&self) -> usize {
+        self.mean.len()
+    }
+
+    fn covariance(&self) -> Array2<f32> {
+        Array2::from_shape_vec((self.dim(), self.dim()), self.covariance.clone())
+            .expect("covariance is dim x dim")
+    }
+
+    /// Next round of titles, most informative first
+    ///
+    /// Returns fewer titles (possibly none) near `max_questions` or when the
+    /// catalog is exhausted. Titles are marked as presented.
+    pub fn next_questions(&mut self, catalog: &ElicitationCatalog) -> Vec<Uuid> {
+        let remaining_budget = self
+            .config
+            .max_questions
+            .saturating_sub(self.presented.len());
+        let round = self.config.questions_per_round.min(remaining_budget);
+        if self.is_complete() || round == 0 || catalog.item_factors.ncols() != self.dim() {
+            return Vec::new();
+        }
+
+        let presented: HashSet<Uuid> = self.presented.iter().copied().collect();
+        let mut eligible: Vec<usize> = (0..catalog.len())
+            .filter(|&i| !presented.contains(&catalog.item_ids[i]))
+            .collect();
+
+        // Hypothetical posterior after each pick; the variance reduction of an
+        // observation does not depend on its outcome
+        let mut covariance = self.covariance();
+        let mut picks = Vec::with_capacity(round);
+        while picks.len() < round && !eligible.is_empty() {
+            let (position, _) = eligible
+                .iter()
+                .enumerate()
+                .map(|(position, &i)| {
+                    let v = catalog.item_factors.row(i);
+                    (position, v.dot(&covariance.dot(&v)))
+                })
+                .fold((0, f32::NEG_INFINITY), |best, current| {
+                    if current.1 > best.1 {
+                        current
+                    } else {
+                        best
+                    }
+                });
+            let index = eligible.swap_remove(position);
+            let v = catalog.item_factors.row(index);
+            Self::condition(&mut covariance, None, v, None, self.config.noise_variance);
+            picks.push(catalog.item_ids[index]);
+        }
+
+        self.presented.extend(&picks);
+        picks
+    }
+
+    /// Record an answer, updating the latent posterior and preference vector
+    ///
+    /// `get_content_embedding` is only called for likes and dislikes.
+    pub fn record_answer(
+        &mut self,
+        catalog: &ElicitationCatalog,
+        content_id: Uuid,
+        response: ElicitationResponse,
+        get_content_embedding: impl Fn(Uuid) -> Result<Vec<f32>>,
+    ) -> Result<()> {
+        anyhow::ensure!(
+            self.presented.contains(&content_id),
+            "Content {} was not presented to user {}",
+            content_id,
+            self.user_id
+        );
+        anyhow::ensure!(
+            !self.answers.iter().any(|(id, _)| *id == content_id),
+            "Content {} already answered",
+            content_id
+        );
+        self.answers.push((content_id, response));
+
+        let Some(target) = response.target() else {
+            return Ok(());
+        };
+
+        if let Some(v) = catalog.factors(content_id) {
+            if v.len() == self.dim() {
+                let mut covariance = self.covariance();
+                let mut mean = Array1::from_vec(std::mem::take(&mut self.mean));
+                Self::condition(
+                    &mut covariance,
+                    Some(&mut mean),
+                    v,
+                    Some(target),
+                    self.config.noise_variance,
+                );
+                self.mean = mean.to_vec();
+                self.covariance = covariance.into_raw_vec_and_offset().0;
+            }
+        }
+
+        let weight = match response {
+            ElicitationResponse::Like => 1.0,
+            _ => -self.config.dislike_weight,
+        };
+        let embedding = get_content_embedding(content_id)?;
+        if self.embedding_sum.is_empty() {
+            self.embedding_sum = vec![0.0; embedding.len()];
+        }
+        anyhow::ensure!(
+            embedding.len() == self.embedding_sum.len(),
+            "Embedding dimension mismatch: expected {}, got {}",
+            self.embedding_sum.len(),
+            embedding.len()
+        );
+        for (sum, value) in self.embedding_sum.iter_mut().zip(&embedding) {
+            *sum += weight * value;
+        }
+        Ok(())
+    }
+
+    /// Rank-one Gaussian conditioning on an observation of `vᵀu`
+    fn condition(
+        covariance: &mut Array2<f32>,
+        mean: Option<&mut Array1<f32>>,
+        v: ArrayView1<'_, f32>,
+        target: Option<f32>,
+        noise_variance: f32,
+    ) {
+        let sigma_v = covariance.dot(&v);
+        let denominator = noise_variance + v.dot(&sigma_v);
+        if denominator <= f32::EPSILON {
+            return;
+        }
+        if let (Some(mean), Some(target)) = (mean, target) {
+            let residual = target - v.dot(&*mean);
+            mean.scaled_add(residual / denominator, &sigma_v);
+        }
+        let gain = sigma_v.view().insert_axis(Axis(1));
+        let gain_t = sigma_v.view().insert_axis(Axis(0));
+        covariance.scaled_add(-1.0 / denominator, &gain.dot(&gain_t));
+    }
+
+    /// Presented titles still waiting for an answer, in presentation order
+    pub fn pending_questions(&self) -> Vec<Uuid> {
+        self.presented
+            .iter()
+            .filter(|id| !self.answers.iter().any(|(answered, _)| answered == *id))
+            .copied()
+            .collect()
+    }
+
+    pub fn presented_count(&self) -> usize {
+        self.presented.len()
+    }
+
+    /// Likes and dislikes recorded so far
+    pub fn rated_count(&self) -> usize {
+        self.answers
+            .iter()
+            .filter(|(_, r)| r.target().is_some())
+            .count()
+    }
+
+    /// Whether to hand off to the regular pipeline
+    pub fn is_complete(&self) -> bool {
+        ProgressivePersonalization::ready_for_personalization(self.rated_count())
+            || self.presented.len() >= self.config.max_questions
+    }
+
+    /// Posterior mean in ALS latent space, usable as the user's factors
+    pub fn latent_factors(&self) -> &[f32] {
+        &self.mean
+    }
+
+    /// L2-normalized preference vector from the answers so far
+    pub fn preference_vector(&self) -> Option<Vec<f32>> {
+        let norm = self.embedding_sum.iter().map(|x| x * x).sum::<f32>().sqrt();
+        (norm > 0.0).then(|| self.embedding_sum.iter().map(|x| x / norm).collect())
+    }
+
+    /// Predicted preference for a title under the current posterior mean
+    pub fn predict(&self, catalog: &ElicitationCatalog, content_id: Uuid) -> Option<f32> {
+        let v = catalog.factors(content_id)?;
+        (v.len() == self.dim()).then(|| v.dot(&ArrayView1::from(&self.mean)))
+    }
+
+    /// Apply the elicited preferences to a profile for the regular pipeline
+    pub fn apply_to(&self, profile: &mut UserProfile) {
+        if let Some(preference_vector) = self.preference_vector() {
+            profile.preference_vector = preference_vector;
+        }
+    }
+}
+
+/// Onboarding session storage
+#[async_trait::async_trait]
+pub trait ElicitationRepository: Send + Sync {
+    async fn load(&self, user_id: Uuid) -> Result<Option<ElicitationSession>>;
+
+    /// Store a session, replacing the user's earlier one
+    async fn save(&self, session: &ElicitationSession) -> Result<()>;
+}
+
+/// PostgreSQL implementation of ElicitationRepository
+pub struct PostgresElicitationRepository {
+    pool: PgPool,
+}
+
+impl PostgresElicitationRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl ElicitationRepository for PostgresElicitationRepository {
+    async fn load(&self, user_id: Uuid) -> Result<Option<ElicitationSession>> {
+        let row = sqlx::query("SELECT session FROM user_elicitation_sessions WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await
+            .context("Failed to load elicitation session")?;
+
+        row.map(|row| {
+            serde_json::from_value(row.get("session")).context("Invalid stored elicitation session")
+        })
+        .transpose()
+    }
+
+    async fn save(&self, session: &ElicitationSession) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO user_elicitation_sessions (user_id, session)
+            VALUES ($1, $2)
+            ON CONFLICT (user_id) DO UPDATE
+            SET session = EXCLUDED.session,
+                updated_at = NOW()
+            "#,
+        )
+        .bind(session.user_id)
+        .bind(serde_json::to_value(session)?)
+        .execute(&self.pool)
+        .await
+        .context("Failed to save elicitation session")?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+
+    /// Four titles along two latent axes: two "action" titles on the first,
+    /// two "drama" titles on the second
+    fn catalog() -> ElicitationCatalog {
+        ElicitationCatalog::new(
+            (1..=4).map(Uuid::from_u128).collect(),
+            array![[1.0, 0.0], [0.9, 0.1], [0.0, 1.0], [0.1, 0.9]],
+            array![0.0, 0.0],
+            array![1.0, 1.0],
+        )
+    }
+
+    fn embedding(id: Uuid) -> Result<Vec<f32>> {
+        Ok(if id.as_u128() <= 2 {
+            vec![1.0, 0.0, 0.0]
+        } else {
+            vec![0.0, 1.0, 0.0]
+        })
+    }
+
+    #[test]
+    fn test_round_covers_both_latent_axes() {
+        let catalog = catalog();
+        let mut session = ElicitationSession::new(
+            Uuid::new_v4(),
+            &catalog,
+            ElicitationConfig {
+                questions_per_round: 2,
+                ..Default::default()
+            },
+        );
+
+        let round: HashSet<u128> = session
+            .next_questions(&catalog)
+            .iter()
+            .map(|id| id.as_u128())
+            .collect();
+        // After asking about one axis, the other is the most uncertain
+        assert_eq!(round.len(), 2);
+        assert!(round.contains(&1) || round.contains(&2));
+        assert!(round.contains(&3) || round.contains(&4));
+
+        // Presented titles are not asked again
+        let next = session.next_questions(&catalog);
+        assert_eq!(next.len(), 2);
+        assert!(next.iter().all(|id| !round.contains(&id.as_u128())));
+        assert!(session.next_questions(&catalog).is_empty());
+    }
+
+    #[test]
+    fn test_answers_update_posterior_and_preference_vector() {
+        let catalog = catalog();
+        let mut session = ElicitationSession::new(
+            Uuid::new_v4(),
+            &catalog,
+            ElicitationConfig {
+                questions_per_round: 4,
+                ..Default::default()
+            },
+        );
+        session.next_questions(&catalog);
+
+        let id = Uuid::from_u128;
+        session
+            .record_answer(&catalog, id(1), ElicitationResponse::Like, embedding)
+            .unwrap();
+        session
+            .record_answer(&catalog, id(3), ElicitationResponse::Dislike, embedding)
+            .unwrap();
+        session
+            .record_answer(&catalog, id(4), ElicitationResponse::Skip, |_| {
+                panic!("skips need no embedding")
+            })
+            .unwrap();
+
+        assert!(
+            session.predict(&catalog, id(2)).unwrap() > session.predict(&catalog, id(4)).unwrap()
+        );
+        let preference = session.preference_vector().unwrap();
+        assert!(preference[0] > 0.0 && preference[1] < 0.0);
+        assert_eq!(session.rated_count(), 2);
+
+        let mut profile = UserProfile::new(session.user_id);
+        session.apply_to(&mut profile);
+        assert_eq!(profile.preference_vector, preference);
+
+        // Unpresented or repeated answers are rejected
+        assert!(session
+            .record_answer(
+                &catalog,
+                Uuid::new_v4(),
+                ElicitationResponse::Like,
+                embedding
+            )
+            .is_err());
+        assert!(session
+            .record_answer(&catalog, id(1), ElicitationResponse::Like, embedding)
+            .is_err());
+    }
+
+    #[test]
+    fn test_completes_at_personalization_threshold() {
+        let ids: Vec<Uuid> = (1..=10).map(Uuid::from_u128).collect();
+        let factors = Array2::from_shape_fn((10, 3), |(i, j)| ((i * 3 + j) % 7) as f32 / 7.0);
+        let catalog = ElicitationCatalog::new(ids, factors, Array1::zeros(3), Array1::ones(3));
+        let mut session =
+            ElicitationSession::new(Uuid::new_v4(), &catalog, ElicitationConfig::default());
+
+        while !session.is_complete() {
+            let round = session.next_questions(&catalog);
+            assert!(!round.is_empty());
+            for id in round {
+                if session.is_complete() {
+                    break;
+                }
+                session
+                    .record_answer(&catalog, id, ElicitationResponse::Like, |_| Ok(vec![1.0]))
+                    .unwrap();
+            }
+        }
+        assert!(ProgressivePersonalization::ready_for_personalization(
+            session.rated_count()
+        ));
+        assert!(session.next_questions(&catalog).is_empty());
+    }
+
+    #[test]
+    fn test_session_round_trips_through_json() {
+        let catalog = catalog();
+        let mut session =
+            ElicitationSession::new(Uuid::new_v4(), &catalog, ElicitationConfig::default());
+        let round = session.next_questions(&catalog);
+        session
+            .record_answer(&catalog, round[0], ElicitationResponse::Like, embedding)
+            .unwrap();
+
+        let json = serde_json::to_string(&session).unwrap();
+        let restored: ElicitationSession = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.latent_factors(), session.latent_factors());
+        assert_eq!(restored.rated_count(), 1);
+        assert_eq!(restored.presented_count(), round.len());
+        assert_eq!(restored.pending_questions(), round[1..].to_vec());
+    }
+
+    #[test]
+    fn test_catalog_from_model_and_restriction() {
+        let mut model = MatrixFactorization::new(Default::default());
+        assert!(ElicitationCatalog::from_model(&model).is_err());
+
+        let items: Vec<Uuid> = (1..=3).map(Uuid::from_u128).collect();
+        model.item_factors = Some(Array2::ones((3, 2)));
+        model.user_factors = Some(array![[0.0, 1.0], [2.0, 3.0]]);
+        model.item_index_map = items.iter().copied().enumerate().collect();
+
+        let catalog = ElicitationCatalog::from_model(&model).unwrap();
+        assert_eq!(catalog.len(), 3);
+        assert_eq!(catalog.prior_mean, array![1.0, 2.0]);
+
+        let keep: HashSet<Uuid> = [items[1]].into_iter().collect();
+        let restricted = catalog.restrict_to(&keep);
+        assert_eq!(restricted.len(), 1);
+        assert!(restricted.factors(items[1]).is_some());
+    }
+}
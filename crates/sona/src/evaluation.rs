@@ -0,0 +1,1114 @@
+//! Offline Recommender Evaluation
+//!
+//! Replays historical interactions through a time-based train/test split and
+//! scores each model (and the production pipeline over them) on ranking
+//! accuracy, catalog coverage, novelty, intra-list diversity and popularity
+//! bias. Reports are
+//! serializable so a CI job can diff a candidate run against a stored baseline
+//! with [`RegressionGate`].
+
+use crate::candidates::{CandidateSources, RetrievalConfig, UserCandidateSource};
+use crate::collaborative::Interaction;
+use crate::matrix_factorization::MatrixFactorization;
+use crate::profile::UserProfile;
+use crate::recommendation::GenerateRecommendations;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use media_gateway_core::math::cosine_similarity;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Implicit rating at or above which a held-out interaction counts as relevant
+pub const DEFAULT_RELEVANCE_THRESHOLD: f32 = 0.5;
+
+/// Share of the catalog (by training popularity) treated as the "head"
+const HEAD_FRACTION: f64 = 0.2;
+
+/// Per-source retrieval timeout for the offline pipeline
+const OFFLINE_SOURCE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Time-based train/test split of interaction history
+///
+/// Everything strictly before `cutoff` is training data. Test ground truth
+/// per user is the set of items they engaged with after the cutoff that they
+/// had not already seen in training. Users without training history are
+/// counted in `cold_users` and left out of ranking metrics.
+#[derive(Debug, Clone)]
+pub struct TemporalSplit {
+    pub cutoff: DateTime<Utc>,
+    pub train: Vec<Interaction>,
+    /// Items each user interacted with before the cutoff
+    pub seen: HashMap<Uuid, HashSet<Uuid>>,
+    /// Relevant held-out items per warm user
+    pub relevant: HashMap<Uuid, HashSet<Uuid>>,
+    pub cold_users: usize,
+}
+
+impl TemporalSplit {
+    /// Split at a fixed timestamp
+    pub fn at(
+        interactions: &[Interaction],
+        cutoff: DateTime<Utc>,
+        relevance_threshold: f32,
+    ) -> Self {
+        let (train, test): (Vec<Interaction>, Vec<Interaction>) = interactions
+            .iter()
+            .cloned()
+            .partition(|interaction| interaction.timestamp < cutoff);
+
+        let mut seen: HashMap<Uuid, HashSet<Uuid>> = HashMap::new();
+        for interaction in &train {
+            seen.entry(interaction.user_id)
+                .or_default()
+                .insert(interaction.content_id);
+        }
+
+        let mut relevant: HashMap<Uuid, HashSet<Uuid>> = HashMap::new();
+        let mut cold: HashSet<Uuid> = HashSet::new();
+        for interaction in &test {
+            let rating = interaction
+                .interaction_type
+                .to_rating(interaction.watch_progress);
+            if rating < relevance_threshold {
+                continue;
+            }
+            match seen.get(&interaction.user_id) {
+                Some(history) if history.contains(&interaction.content_id) => {}
+                Some(_) => {
+                    relevant
+                        .entry(interaction.user_id)
+                        .or_default()
+                        .insert(interaction.content_id);
+                }
+                None => {
+                    cold.insert(interaction.user_id);
+                }
+            }
+        }
+
+        Self {
+            cutoff,
+            train,
+            seen,
+            relevant,
+            cold_users: cold.len(),
+        }
+    }
+
+    /// Split so that roughly `test_fraction` of interactions (by time) are held out
+    pub fn by_fraction(
+        interactions: &[Interaction],
+        test_fraction: f64,
+        relevance_threshold: f32,
+    ) -> Result<Self> {
+        anyhow::ensure!(
+            test_fraction > 0.0 && test_fraction < 1.0,
+            "test_fraction must be in (0, 1), got {}",
+            test_fraction
+        );
+        anyhow::ensure!(!interactions.is_empty(), "No interactions to split");
+
+        let mut timestamps: Vec<DateTime<Utc>> = interactions.iter().map(|i| i.timestamp).collect();
+        timestamps.sort_unstable();
+        let index = ((1.0 - test_fraction) * timestamps.len() as f64) as usize;
+        let cutoff = timestamps[index.min(timestamps.len() - 1)];
+
+        Ok(Self::at(interactions, cutoff, relevance_threshold))
+    }
+
+    /// Aggregated (user, item, rating) triplets for training matrix factorization
+    pub fn train_triplets(&self) -> Vec<(Uuid, Uuid, f32)> {
+        let mut aggregated: HashMap<(Uuid, Uuid), f32> = HashMap::new();
+        for interaction in &self.train {
+            *aggregated
+                .entry((interaction.user_id, interaction.content_id))
+                .or_insert(0.0) += interaction
+                .interaction_type
+                .to_rating(interaction.watch_progress);
+        }
+        aggregated
+            .into_iter()
+            .map(|((user_id, item_id), rating)| (user_id, item_id, rating))
+            .collect()
+    }
+
+    /// Users with held-out relevant items, in a stable order
+    pub fn test_users(&self) -> Vec<Uuid> {
+        let mut users: Vec<Uuid> = self.relevant.keys().copied().collect();
+        users.sort_unstable();
+        users
+    }
+}
+
+/// Catalog statistics derived from the training window
+#[derive(Debug, Clone)]
+pub struct EvaluationContext {
+    /// Distinct training users per item
+    pub popularity: HashMap<Uuid, usize>,
+    pub num_users: usize,
+    pub catalog_size: usize,
+    /// Most popular items covering `HEAD_FRACTION` of the catalog
+    pub head_items: HashSet<Uuid>,
+    /// Item embeddings for intra-list diversity (optional)
+    pub embeddings: Option<Arc<HashMap<Uuid, Vec<f32>>>>,
+}
+
+impl EvaluationContext {
+    pub fn from_split(split: &TemporalSplit) -> Self {
+        let mut popularity: HashMap<Uuid, usize> = HashMap::new();
+        for history in split.seen.values() {
+            for item in history {
+                *popularity.entry(*item).or_insert(0) += 1;
+            }
+        }
+
+        let mut ranked: Vec<(Uuid, usize)> = popularity.iter().map(|(k, v)| (*k, *v)).collect();
+        ranked.sort_unstable_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        let head_len = ((ranked.len() as f64) * HEAD_FRACTION).ceil() as usize;
+        let head_items = ranked.iter().take(head_len).map(|(id, _)| *id).collect();
+
+        Self {
+            catalog_size: popularity.len(),
+            num_users: split.seen.len(),
+            popularity,
+            head_items,
+            embeddings: None,
+        }
+    }
+
+    pub fn with_embeddings(mut self, embeddings: Arc<HashMap<Uuid, Vec<f32>>>) -> Self {
+        self.embeddings = Some(embeddings);
+        self
+    }
+
+    /// Override the catalog size when the full catalog is larger than what
+    /// appears in training (e.g. never-watched titles)
+    pub fn with_catalog_size(mut self, catalog_size: usize) -> Self {
+        self.catalog_size = catalog_size.max(self.popularity.len());
+        self
+    }
+
+    fn popularity_share(&self, item: &Uuid) -> f64 {
+        let count = self.popularity.get(item).copied().unwrap_or(0);
+        count as f64 / self.num_users.max(1) as f64
+    }
+
+    fn self_information(&self, item: &Uuid) -> f64 {
+        let count = self.popularity.get(item).copied().unwrap_or(0);
+        -((count as f64 + 1.0) / (self.num_users as f64 + 1.0)).log2()
+    }
+}
+
+/// A recommender that can be scored offline
+///
+/// `seen` is the user's training history; implementations must not return
+/// those items.
+#[async_trait::async_trait]
+pub trait Recommender: Send + Sync {
+    fn name(&self) -> String;
+
+    async fn recommend(
+        &self,
+        user_id: Uuid,
+        k: usize,
+        seen: &HashSet<Uuid>,
+    ) -> Result<Vec<(Uuid, f32)>>;
+}
+
+/// Most-popular baseline
+pub struct PopularityRecommender {
+    ranked: Vec<(Uuid, f32)>,
+}
+
+impl PopularityRecommender {
+    pub fn new(context: &EvaluationContext) -> Self {
+        let mut ranked: Vec<(Uuid, f32)> = context
+            .popularity
+            .iter()
+            .map(|(id, count)| (*id, *count as f32))
+            .collect();
+        ranked.sort_unstable_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        Self { ranked }
+    }
+}
+
+#[async_trait::async_trait]
+impl Recommender for PopularityRecommender {
+    fn name(&self) -> String {
+        "popularity".to_string()
+    }
+
+    async fn recommend(
+        &self,
+        _user_id: Uuid,
+        k: usize,
+        seen: &HashSet<Uuid>,
+    ) -> Result<Vec<(Uuid, f32)>> {
+        Ok(self
+            .ranked
+            .iter()
+            .filter(|(id, _)| !seen.contains(id))
+            .take(k)
+            .copied()
+            .collect())
+    }
+}
+
+/// Scores every trained item with the user's latent factors
+#[async_trait::async_trait]
+impl Recommender for MatrixFactorization {
+    fn name(&self) -> String {
+        "als".to_string()
+    }
+
+    async fn recommend(
+        &self,
+        user_id: Uuid,
+        k: usize,
+        seen: &HashSet<Uuid>,
+    ) -> Result<Vec<(Uuid, f32)>> {
+        let Some(user_idx) = self.user_id_map.get(&user_id) else {
+            return Ok(Vec::new());
+        };
+        let user_factors = self
+            .user_factors
+            .as_ref()
+            .context("Model not trained yet")?;
+        let item_factors = self
+            .item_factors
+            .as_ref()
+            .context("Model not trained yet")?;
+
+        let scores = item_factors.dot(&user_factors.row(*user_idx));
+        let candidates = scores
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, score)| {
+                let item_id = self.item_index_map.get(&idx)?;
+                (!seen.contains(item_id)).then_some((*item_id, *score))
+            })
+            .collect();
+        Ok(top_k(candidates, k))
+    }
+}
+
+/// Content-based baseline over item embeddings: scores items by cosine
+/// similarity to the centroid of the user's training history
+pub struct EmbeddingRecommender {
+    embeddings: Arc<HashMap<Uuid, Vec<f32>>>,
+    history: HashMap<Uuid, HashSet<Uuid>>,
+}
+
+impl EmbeddingRecommender {
+    pub fn new(split: &TemporalSplit, embeddings: Arc<HashMap<Uuid, Vec<f32>>>) -> Self {
+        Self {
+            embeddings,
+            history: split.seen.clone(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Recommender for EmbeddingRecommender {
+    fn name(&self) -> String {
+        "content".to_string()
+    }
+
+    async fn recommend(
+        &self,
+        user_id: Uuid,
+        k: usize,
+        seen: &HashSet<Uuid>,
+    ) -> Result<Vec<(Uuid, f32)>> {
+        let Some(history) = self.history.get(&user_id) else {
+            return Ok(Vec::new());
+        };
+
+        let mut centroid: Vec<f32> = Vec::new();
+        for item in history {
+            let Some(embedding) = self.embeddings.get(item) else {
+                continue;
+            };
+            if centroid.is_empty() {
+                centroid = vec![0.0; embedding.len()];
+            }
+            for (c, e) in centroid.iter_mut().zip(embedding) {
+                *c += e;
+            }
+        }
+        if centroid.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let candidates = self
+            .embeddings
+            .iter()
+            .filter(|(id, _)| !seen.contains(id))
+            .map(|(id, embedding)| (*id, cosine_similarity(&centroid, embedding)))
+            .collect();
+        Ok(top_k(candidates, k))
+    }
+}
+
+/// Adapts an offline recommender to a pipeline candidate slot, excluding the
+/// user's training history
+struct SplitSource {
+    recommender: Arc<dyn Recommender>,
+    seen: Arc<HashMap<Uuid, HashSet<Uuid>>>,
+}
+
+#[async_trait::async_trait]
+impl UserCandidateSource for SplitSource {
+    async fn candidates(&self, user_id: Uuid, limit: usize) -> Result<Vec<(Uuid, f32)>> {
+        let empty = HashSet::new();
+        let seen = self.seen.get(&user_id).unwrap_or(&empty);
+        self.recommender.recommend(user_id, limit, seen).await
+    }
+}
+
+/// The production [`GenerateRecommendations`] pipeline with its candidate
+/// sources replaced by recommenders trained on the split
+///
+/// The live engines read current interaction tables and would leak held-out
+/// events, so each slot takes a model fit on `TemporalSplit::train` instead.
+/// Retrieval weights and limits, merging, diversity and re-ranking are the
+/// production ones. The pipeline returns at most its production list size,
+/// so evaluate it at `k` no larger than that.
+pub struct PipelineRecommender {
+    name: String,
+    seen: Arc<HashMap<Uuid, HashSet<Uuid>>>,
+    collaborative: Option<SplitSource>,
+    content_based: Option<SplitSource>,
+    graph: Option<SplitSource>,
+    retrieval: RetrievalConfig,
+    embeddings: Option<Arc<HashMap<Uuid, Vec<f32>>>>,
+}
+
+impl PipelineRecommender {
+    pub fn new(name: impl Into<String>, split: &TemporalSplit) -> Self {
+        // Offline sources are in memory; a production timeout would only
+        // make runs nondeterministic
+        let mut retrieval = RetrievalConfig::default();
+        for budget in [
+            &mut retrieval.collaborative,
+            &mut retrieval.content_based,
+            &mut retrieval.graph,
+            &mut retrieval.context,
+            &mut retrieval.session,
+            &mut retrieval.trending,
+        ] {
+            budget.timeout = OFFLINE_SOURCE_TIMEOUT;
+        }
+
+        Self {
+            name: name.into(),
+            seen: Arc::new(split.seen.clone()),
+            collaborative: None,
+            content_based: None,
+            graph: None,
+            retrieval,
+            embeddings: None,
+        }
+    }
+
+    fn source(&self, recommender: Arc<dyn Recommender>) -> SplitSource {
+        SplitSource {
+            recommender,
+            seen: self.seen.clone(),
+        }
+    }
+
+    pub fn with_collaborative(mut self, recommender: Arc<dyn Recommender>) -> Self {
+        self.collaborative = Some(self.source(recommender));
+        self
+    }
+
+    pub fn with_content_based(mut self, recommender: Arc<dyn Recommender>) -> Self {
+        self.content_based = Some(self.source(recommender));
+        self
+    }
+
+    pub fn with_graph(mut self, recommender: Arc<dyn Recommender>) -> Self {
+        self.graph = Some(self.source(recommender));
+        self
+    }
+
+    pub fn with_retrieval_config(mut self, config: RetrievalConfig) -> Self {
+        self.retrieval = config;
+        self
+    }
+
+    /// Item embeddings for the diversity filter; without them every pair of
+    /// candidates counts as dissimilar
+    pub fn with_embeddings(mut self, embeddings: Arc<HashMap<Uuid, Vec<f32>>>) -> Self {
+        self.embeddings = Some(embeddings);
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl Recommender for PipelineRecommender {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    async fn recommend(
+        &self,
+        user_id: Uuid,
+        k: usize,
+        seen: &HashSet<Uuid>,
+    ) -> Result<Vec<(Uuid, f32)>> {
+        let mut sources = CandidateSources::default();
+        if let Some(source) = &self.collaborative {
+            sources = sources.with_collaborative(source);
+        }
+        if let Some(source) = &self.content_based {
+            sources = sources.with_content_based(source);
+        }
+        if let Some(source) = &self.graph {
+            sources = sources.with_graph(source);
+        }
+
+        let embeddings = self.embeddings.clone();
+        let recommendations = GenerateRecommendations::execute(
+            user_id,
+            &UserProfile::new(user_id),
+            None,
+            None,
+            move |id| {
+                Ok(embeddings
+                    .as_ref()
+                    .and_then(|embeddings| embeddings.get(&id).cloned())
+                    .unwrap_or_default())
+            },
+            |_| None,
+            sources,
+            &self.retrieval,
+            None,
+            None,
+            None,
+        )
+        .await?;
+
+        Ok(recommendations
+            .into_iter()
+            .filter(|r| !seen.contains(&r.content_id))
+            .take(k)
+            .map(|r| (r.content_id, r.confidence_score))
+            .collect())
+    }
+}
+
+/// Metrics averaged over evaluated users (coverage is catalog-wide)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EvaluationMetrics {
+    pub precision: f64,
+    pub recall: f64,
+    pub ndcg: f64,
+    pub map: f64,
+    pub hit_rate: f64,
+    /// Fraction of the catalog recommended to at least one user
+    pub coverage: f64,
+    /// Mean self-information (-log2 popularity) of recommended items
+    pub novelty: f64,
+    /// Mean pairwise cosine distance within a list; requires embeddings
+    pub intra_list_diversity: Option<f64>,
+    /// Mean share of training users who interacted with a recommended item
+    pub average_popularity: f64,
+    /// Fraction of recommended items drawn from the popular head
+    pub head_share: f64,
+}
+
+/// Results for one engine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EngineReport {
+    pub name: String,
+    pub users_evaluated: usize,
+    pub failed_users: usize,
+    pub metrics: EvaluationMetrics,
+}
+
+/// Results for every engine over the same split
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvaluationReport {
+    pub generated_at: DateTime<Utc>,
+    pub cutoff: DateTime<Utc>,
+    pub k: usize,
+    pub train_interactions: usize,
+    pub test_users: usize,
+    pub cold_users: usize,
+    pub engines: Vec<EngineReport>,
+}
+
+impl EvaluationReport {
+    pub fn engine(&self, name: &str) -> Option<&EngineReport> {
+        self.engines.iter().find(|e| e.name == name)
+    }
+}
+
+/// Evaluate a single recommender at cutoff `k`
+///
+/// A failure for one user is logged and counted rather than aborting the run.
+pub async fn evaluate(
+    recommender: &dyn Recommender,
+    split: &TemporalSplit,
+    context: &EvaluationContext,
+    k: usize,
+) -> Result<EngineReport> {
+    anyhow::ensure!(k > 0, "k must be positive");
+
+    let empty = HashSet::new();
+    let mut totals = EvaluationMetrics::default();
+    let mut diversity_sum = 0.0;
+    let mut diversity_lists = 0usize;
+    let mut recommended_items: HashSet<Uuid> = HashSet::new();
+    let mut recommended_count = 0usize;
+    let mut users_evaluated = 0usize;
+    let mut failed_users = 0usize;
+
+    for user_id in split.test_users() {
+        let relevant = &split.relevant[&user_id];
+        let seen = split.seen.get(&user_id).unwrap_or(&empty);
+
+        let recommendations = match recommender.recommend(user_id, k, seen).await {
+            Ok(recommendations) => recommendations,
+            Err(e) => {
+                tracing::warn!(
+                    "{} failed to recommend for user {}: {}",
+                    recommender.name(),
+                    user_id,
+                    e
+                );
+                failed_users += 1;
+                continue;
+            }
+        };
+        let ranked: Vec<Uuid> = recommendations.iter().take(k).map(|(id, _)| *id).collect();
+
+        totals.precision += precision_at_k(&ranked, relevant, k);
+        totals.recall += recall_at_k(&ranked, relevant, k);
+        totals.ndcg += ndcg_at_k(&ranked, relevant, k);
+        totals.map += average_precision_at_k(&ranked, relevant, k);
+        if ranked.iter().any(|id| relevant.contains(id)) {
+            totals.hit_rate += 1.0;
+        }
+
+        for item in &ranked {
+            totals.novelty += context.self_information(item);
+            totals.average_popularity += context.popularity_share(item);
+            if context.head_items.contains(item) {
+                totals.head_share += 1.0;
+            }
+            recommended_items.insert(*item);
+        }
+        recommended_count += ranked.len();
+
+        if let Some(embeddings) = &context.embeddings {
+            if let Some(diversity) = intra_list_diversity(&ranked, embeddings) {
+                diversity_sum += diversity;
+                diversity_lists += 1;
+            }
+        }
+        users_evaluated += 1;
+    }
+
+    let users = users_evaluated.max(1) as f64;
+    let items = recommended_count.max(1) as f64;
+    let metrics = EvaluationMetrics {
+        precision: totals.precision / users,
+        recall: totals.recall / users,
+        ndcg: totals.ndcg / users,
+        map: totals.map / users,
+        hit_rate: totals.hit_rate / users,
+        coverage: recommended_items.len() as f64 / context.catalog_size.max(1) as f64,
+        novelty: totals.novelty / items,
+        intra_list_diversity: (diversity_lists > 0).then(|| diversity_sum / diversity_lists as f64),
+        average_popularity: totals.average_popularity / items,
+        head_share: totals.head_share / items,
+    };
+
+    Ok(EngineReport {
+        name: recommender.name(),
+        users_evaluated,
+        failed_users,
+        metrics,
+    })
+}
+
+/// Evaluate several recommenders on the same split
+pub async fn evaluate_all(
+    recommenders: &[Arc<dyn Recommender>],
+    split: &TemporalSplit,
+    context: &EvaluationContext,
+    k: usize,
+) -> Result<EvaluationReport> {
+    let mut engines = Vec::with_capacity(recommenders.len());
+    for recommender in recommenders {
+        let report = evaluate(recommender.as_ref(), split, context, k).await?;
+        tracing::info!(
+            "Evaluated {}: precision@{}={:.4} recall@{}={:.4} ndcg@{}={:.4} coverage={:.4}",
+            report.name,
+            k,
+            report.metrics.precision,
+            k,
+            report.metrics.recall,
+            k,
+            report.metrics.ndcg,
+            report.metrics.coverage
+        );
+        engines.push(report);
+    }
+
+    Ok(EvaluationReport {
+        generated_at: Utc::now(),
+        cutoff: split.cutoff,
+        k,
+        train_interactions: split.train.len(),
+        test_users: split.relevant.len(),
+        cold_users: split.cold_users,
+        engines,
+    })
+}
+
+/// Metric selector for regression gating
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Metric {
+    Precision,
+    Recall,
+    Ndcg,
+    Map,
+    HitRate,
+    Coverage,
+    Novelty,
+    IntraListDiversity,
+    AveragePopularity,
+    HeadShare,
+}
+
+impl Metric {
+    pub fn value(&self, metrics: &EvaluationMetrics) -> Option<f64> {
+        match self {
+            Metric::Precision => Some(metrics.precision),
+            Metric::Recall => Some(metrics.recall),
+            Metric::Ndcg => Some(metrics.ndcg),
+            Metric::Map => Some(metrics.map),
+            Metric::HitRate => Some(metrics.hit_rate),
+            Metric::Coverage => Some(metrics.coverage),
+            Metric::Novelty => Some(metrics.novelty),
+            Metric::IntraListDiversity => metrics.intra_list_diversity,
+            Metric::AveragePopularity => Some(metrics.average_popularity),
+            Metric::HeadShare => Some(metrics.head_share),
+        }
+    }
+
+    /// Popularity bias metrics regress when they go up
+    pub fn higher_is_better(&self) -> bool {
+        !matches!(self, Metric::AveragePopularity | Metric::HeadShare)
+    }
+}
+
+/// A metric that got worse than the gate allows
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Regression {
+    pub engine: String,
+    pub metric: Metric,
+    pub baseline: f64,
+    pub candidate: f64,
+    /// Signed relative change, positive meaning worse
+    pub relative_degradation: f64,
+}
+
+/// Outcome of comparing a candidate report against a baseline
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GateResult {
+    pub regressions: Vec<Regression>,
+    /// Engines present in the baseline but missing from the candidate
+    pub missing_engines: Vec<String>,
+}
+
+impl GateResult {
+    pub fn passed(&self) -> bool {
+        self.regressions.is_empty() && self.missing_engines.is_empty()
+    }
+}
+
+/// Fails a candidate run when any gated metric degrades by more than
+/// `max_relative_degradation` relative to the baseline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegressionGate {
+    pub max_relative_degradation: f64,
+    pub metrics: Vec<Metric>,
+}
+
+impl Default for RegressionGate {
+    fn default() -> Self {
+        Self {
+            max_relative_degradation: 0.02,
+            metrics: vec![
+                Metric::Precision,
+                Metric::Recall,
+                Metric::Ndcg,
+                Metric::Map,
+                Metric::Coverage,
+            ],
+        }
+    }
+}
+
+impl RegressionGate {
+    pub fn check(&self, baseline: &EvaluationReport, candidate: &EvaluationReport) -> GateResult {
+        let mut result = GateResult::default();
+
+        for base in &baseline.engines {
+            let Some(cand) = candidate.engine(&base.name) else {
+                result.missing_engines.push(base.name.clone());
+                continue;
+            };
+
+            for metric in &self.metrics {
+                let (Some(before), Some(after)) =
+                    (metric.value(&base.metrics), metric.value(&cand.metrics))
+                else {
+                    continue;
+                };
+                let worse_by = if metric.higher_is_better() {
+                    before - after
+                } else {
+                    after - before
+                };
+                let relative = if before.abs() > f64::EPSILON {
+                    worse_by / before.abs()
+                } else {
+                    worse_by
+                };
+                if relative > self.max_relative_degradation {
+                    result.regressions.push(Regression {
+                        engine: base.name.clone(),
+                        metric: *metric,
+                        baseline: before,
+                        candidate: after,
+                        relative_degradation: relative,
+                    });
+                }
+            }
+        }
+
+        result
+    }
+}
+
+pub fn precision_at_k(ranked: &[Uuid], relevant: &HashSet<Uuid>, k: usize) -> f64 {
+    if k == 0 {
+        return 0.0;
+    }
+    let hits = ranked
+        .iter()
+        .take(k)
+        .filter(|id| relevant.contains(id))
+        .count();
+    hits as f64 / k as f64
+}
+
+pub fn recall_at_k(ranked: &[Uuid], relevant: &HashSet<Uuid>, k: usize) -> f64 {
+    if relevant.is_empty() {
+        return 0.0;
+    }
+    let hits = ranked
+        .iter()
+        .take(k)
+        .filter(|id| relevant.contains(id))
+        .count();
+    hits as f64 / relevant.len() as f64
+}
+
+/// Binary-relevance NDCG
+pub fn ndcg_at_k(ranked: &[Uuid], relevant: &HashSet<Uuid>, k: usize) -> f64 {
+    let dcg: f64 = ranked
+        .iter()
+        .take(k)
+        .enumerate()
+        .filter(|(_, id)| relevant.contains(id))
+        .map(|(rank, _)| 1.0 / (rank as f64 + 2.0).log2())
+        .sum();
+    let ideal: f64 = (0..relevant.len().min(k))
+        .map(|rank| 1.0 / (rank as f64 + 2.0).log2())
+        .sum();
+    if ideal > 0.0 {
+        dcg / ideal
+    } else {
+        0.0
+    }
+}
+
+/// Average precision normalized by `min(|relevant|, k)`
+pub fn average_precision_at_k(ranked: &[Uuid], relevant: &HashSet<Uuid>, k: usize) -> f64 {
+    let denominator = relevant.len().min(k);
+    if denominator == 0 {
+        return 0.0;
+    }
+    let mut hits = 0usize;
+    let mut sum = 0.0;
+    for (rank, id) in ranked.iter().take(k).enumerate() {
+        if relevant.contains(id) {
+            hits += 1;
+            sum += hits as f64 / (rank + 1) as f64;
+        }
+    }
+    sum / denominator as f64
+}
+
+/// Mean pairwise cosine distance; `None` with fewer than two embedded items
+pub fn intra_list_diversity(ranked: &[Uuid], embeddings: &HashMap<Uuid, Vec<f32>>) -> Option<f64> {
+    let vectors: Vec<&Vec<f32>> = ranked.iter().filter_map(|id| embeddings.get(id)).collect();
+    if vectors.len() < 2 {
+        return None;
+    }
+    let mut sum = 0.0;
+    let mut pairs = 0usize;
+    for i in 0..vectors.len() {
+        for j in (i + 1)..vectors.len() {
+            sum += 1.0 - cosine_similarity(vectors[i], vectors[j]) as f64;
+            pairs += 1;
+        }
+    }
+    Some(sum / pairs as f64)
+}
+
+/// Highest-scoring `k` entries, best first, ties broken by id for stable reports
+fn top_k(mut candidates: Vec<(Uuid, f32)>, k: usize) -> Vec<(Uuid, f32)> {
+    let order = |a: &(Uuid, f32), b: &(Uuid, f32)| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0));
+    if candidates.len() > k && k > 0 {
+        candidates.select_nth_unstable_by(k - 1, order);
+    }
+    candidates.truncate(k);
+    candidates.sort_unstable_by(order);
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collaborative::InteractionType;
+    use crate::matrix_factorization::ALSConfig;
+    use chrono::Duration;
+
+    fn id(n: u128) -> Uuid {
+        Uuid::from_u128(n)
+    }
+
+    fn interaction(user: u128, item: u128, day: i64) -> Interaction {
+        Interaction {
+            user_id: id(user),
+            content_id: id(1000 + item),
+            interaction_type: InteractionType::Completion,
+            watch_progress: Some(1.0),
+            timestamp: DateTime::<Utc>::from_timestamp(0, 0).unwrap() + Duration::days(day),
+        }
+    }
+
+    /// Two taste clusters; every user watches most of their cluster before
+    /// day 10 and one more cluster item afterwards
+    fn clustered_history() -> Vec<Interaction> {
+        let mut interactions = Vec::new();
+        for user in 0..20u128 {
+            let offset = if user % 2 == 0 { 0 } else { 10 };
+            for item in 0..10u128 {
+                if item == user % 10 {
+                    continue;
+                }
+                interactions.push(interaction(user, offset + item, (item % 9) as i64));
+            }
+            interactions.push(interaction(user, offset + user % 10, 20));
+        }
+        interactions
+    }
+
+    #[test]
+    fn test_ranking_metrics() {
+        let relevant: HashSet<Uuid> = [id(1), id(3)].into_iter().collect();
+        let ranked = vec![id(1), id(2), id(3), id(4)];
+
+        assert!((precision_at_k(&ranked, &relevant, 4) - 0.5).abs() < 1e-9);
+        assert!((recall_at_k(&ranked, &relevant, 2) - 0.5).abs() < 1e-9);
+        // AP = (1/1 + 2/3) / 2
+        assert!((average_precision_at_k(&ranked, &relevant, 4) - 5.0 / 6.0).abs() < 1e-9);
+        let expected_ndcg = (1.0 + 1.0 / 4f64.log2()) / (1.0 + 1.0 / 3f64.log2());
+        assert!((ndcg_at_k(&ranked, &relevant, 4) - expected_ndcg).abs() < 1e-9);
+
+        let perfect = vec![id(3), id(1)];
+        assert!((ndcg_at_k(&perfect, &relevant, 2) - 1.0).abs() < 1e-9);
+        assert_eq!(ndcg_at_k(&ranked, &HashSet::new(), 4), 0.0);
+    }
+
+    #[test]
+    fn test_intra_list_diversity() {
+        let embeddings: HashMap<Uuid, Vec<f32>> = [
+            (id(1), vec![1.0, 0.0]),
+            (id(2), vec![1.0, 0.0]),
+            (id(3), vec![0.0, 1.0]),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(
+            intra_list_diversity(&[id(1), id(2)], &embeddings),
+            Some(0.0)
+        );
+        let mixed = intra_list_diversity(&[id(1), id(3)], &embeddings).unwrap();
+        assert!((mixed - 1.0).abs() < 1e-6);
+        assert_eq!(intra_list_diversity(&[id(1)], &embeddings), None);
+    }
+
+    #[test]
+    fn test_temporal_split() {
+        let split = TemporalSplit::at(
+            &clustered_history(),
+            DateTime::<Utc>::from_timestamp(0, 0).unwrap() + Duration::days(10),
+            DEFAULT_RELEVANCE_THRESHOLD,
+        );
+
+        assert_eq!(split.train.len(), 20 * 9);
+        assert_eq!(split.relevant.len(), 20);
+        assert_eq!(split.cold_users, 0);
+        for (user, items) in &split.relevant {
+            assert!(items.is_disjoint(&split.seen[user]));
+        }
+        assert!(split.train.iter().all(|i| i.timestamp < split.cutoff));
+    }
+
+    #[test]
+    fn test_split_by_fraction_rejects_bad_input() {
+        assert!(TemporalSplit::by_fraction(&clustered_history(), 0.0, 0.5).is_err());
+        assert!(TemporalSplit::by_fraction(&[], 0.2, 0.5).is_err());
+
+        let split = TemporalSplit::by_fraction(&clustered_history(), 0.1, 0.5).unwrap();
+        assert!(!split.relevant.is_empty());
+    }
+
+    #[test]
+    fn test_top_k_orders_and_truncates() {
+        let ranked = top_k(vec![(id(1), 0.1), (id(2), 0.9), (id(3), 0.5)], 2);
+        assert_eq!(ranked, vec![(id(2), 0.9), (id(3), 0.5)]);
+    }
+
+    #[tokio::test]
+    async fn test_als_beats_popularity() {
+        let split = TemporalSplit::at(
+            &clustered_history(),
+            DateTime::<Utc>::from_timestamp(0, 0).unwrap() + Duration::days(10),
+            DEFAULT_RELEVANCE_THRESHOLD,
+        );
+        let context = EvaluationContext::from_split(&split);
+
+        let mut model = MatrixFactorization::new(ALSConfig {
+            latent_factors: 4,
+            iterations: 10,
+            ..Default::default()
+        });
+        let matrix = model.build_matrix(split.train_triplets()).unwrap();
+        model.fit(&matrix).unwrap();
+
+        let als: Arc<dyn Recommender> = Arc::new(model);
+        let popularity: Arc<dyn Recommender> = Arc::new(PopularityRecommender::new(&context));
+        let report = evaluate_all(&[popularity, als], &split, &context, 1)
+            .await
+            .unwrap();
+
+        let als = report.engine("als").unwrap();
+        let popularity = report.engine("popularity").unwrap();
+        assert_eq!(als.users_evaluated, 20);
+        assert!(als.metrics.precision > popularity.metrics.precision);
+        assert!(als.metrics.coverage >= popularity.metrics.coverage);
+        assert!(popularity.metrics.average_popularity > 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_uses_split_sources() {
+        let split = TemporalSplit::at(
+            &clustered_history(),
+            DateTime::<Utc>::from_timestamp(0, 0).unwrap() + Duration::days(10),
+            DEFAULT_RELEVANCE_THRESHOLD,
+        );
+        let embeddings: Arc<HashMap<Uuid, Vec<f32>>> = Arc::new(
+            (0..20u128)
+                .map(|item| {
+                    let cluster = if item < 10 { 0.0 } else { 1.0 };
+                    (
+                        id(1000 + item),
+                        vec![1.0 - cluster, cluster, item as f32 * 0.01],
+                    )
+                })
+                .collect(),
+        );
+        let context = EvaluationContext::from_split(&split).with_embeddings(embeddings.clone());
+
+        let mut model = MatrixFactorization::new(ALSConfig {
+            latent_factors: 4,
+            iterations: 10,
+            ..Default::default()
+        });
+        let matrix = model.build_matrix(split.train_triplets()).unwrap();
+        model.fit(&matrix).unwrap();
+
+        let pipeline = PipelineRecommender::new("pipeline", &split)
+            .with_collaborative(Arc::new(model))
+            .with_content_based(Arc::new(EmbeddingRecommender::new(
+                &split,
+                embeddings.clone(),
+            )))
+            .with_embeddings(embeddings);
+        let report = evaluate(&pipeline, &split, &context, 3).await.unwrap();
+
+        assert_eq!(report.name, "pipeline");
+        assert_eq!(report.users_evaluated, 20);
+        assert_eq!(report.failed_users, 0);
+        assert!(report.metrics.intra_list_diversity.is_some());
+        assert!(report.metrics.hit_rate > 0.0);
+    }
+
+    #[test]
+    fn test_regression_gate() {
+        let engine = |name: &str, precision: f64, head_share: f64| EngineReport {
+            name: name.to_string(),
+            users_evaluated: 10,
+            failed_users: 0,
+            metrics: EvaluationMetrics {
+                precision,
+                head_share,
+                ..Default::default()
+            },
+        };
+        let report = |engines| EvaluationReport {
+            generated_at: Utc::now(),
+            cutoff: Utc::now(),
+            k: 10,
+            train_interactions: 100,
+            test_users: 10,
+            cold_users: 0,
+            engines,
+        };
+
+        let baseline = report(vec![engine("als", 0.20, 0.5), engine("graph", 0.1, 0.5)]);
+        let gate = RegressionGate {
+            metrics: vec![Metric::Precision, Metric::HeadShare],
+            ..Default::default()
+        };
+
+        let same = report(vec![engine("als", 0.199, 0.5), engine("graph", 0.1, 0.5)]);
+        assert!(gate.check(&baseline, &same).passed());
+
+        let worse = report(vec![engine("als", 0.15, 0.6)]);
+        let result = gate.check(&baseline, &worse);
+        assert!(!result.passed());
+        assert_eq!(result.missing_engines, vec!["graph".to_string()]);
+        let metrics: Vec<Metric> = result.regressions.iter().map(|r| r.metric).collect();
+        assert_eq!(metrics, vec![Metric::Precision, Metric::HeadShare]);
+
+        let json = serde_json::to_string(&baseline).unwrap();
+        let parsed: EvaluationReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.engines[0].metrics, baseline.engines[0].metrics);
+    }
+}
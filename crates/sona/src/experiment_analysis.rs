@@ -0,0 +1,838 @@
+//! Statistical analysis for A/B experiments
+//!
+//! Turns raw per-variant counts into decisions: Wilson confidence intervals
+//! for conversion rates, two-proportion z-tests and Welch t-tests against the
+//! control variant, a mixture sequential probability ratio test (mSPRT) whose
+//! p-values stay valid no matter how often an experiment is peeked at, and a
+//! chi-square sample-ratio-mismatch check on the traffic split.
+//!
+//! The unit of analysis is the user: conversion rate is the share of exposed
+//! users with at least one conversion, and continuous metrics are per-user
+//! totals (users without events count as zero).
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::ab_testing::VariantMetrics;
+
+/// Continuous metric analysed with Welch's t-test
+pub const WATCH_TIME_METRIC: &str = "watch_time";
+
+/// Variant treated as the baseline when present
+const CONTROL_VARIANT_NAME: &str = "control";
+
+const MAX_ITERATIONS: usize = 200;
+const EPSILON: f64 = 3.0e-12;
+const FP_MIN: f64 = 1.0e-300;
+
+/// Analysis thresholds
+#[derive(Debug, Clone)]
+pub struct AnalysisConfig {
+    /// Family-wise significance level, split across treatment comparisons (default: 0.05)
+    pub alpha: f64,
+    /// Significance level of the sample-ratio-mismatch check (default: 0.001)
+    pub srm_alpha: f64,
+    /// Check the traffic split against the configured weights (default:
+    /// true). Off for adaptive (bandit) experiments, whose split follows the
+    /// bandit allocation rather than the weights.
+    pub check_sample_ratio: bool,
+    /// Exposed users each variant needs before a verdict is given (default: 100)
+    pub min_users_per_variant: i64,
+    /// Standard deviation of the mSPRT normal mixture over conversion-rate
+    /// differences, roughly the smallest effect worth detecting (default: 0.02)
+    pub mixing_sd: f64,
+}
+
+impl Default for AnalysisConfig {
+    fn default() -> Self {
+        Self {
+            alpha: 0.05,
+            srm_alpha: 0.001,
+            check_sample_ratio: true,
+            min_users_per_variant: 100,
+            mixing_sd: 0.02,
+        }
+    }
+}
+
+/// Two-sided confidence interval
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ConfidenceInterval {
+    pub lower: f64,
+    pub upper: f64,
+}
+
+/// Per-user summary of a continuous metric
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContinuousMetric {
+    pub users: i64,
+    pub mean: f64,
+    /// Sample variance of per-user totals
+    pub variance: f64,
+}
+
+/// Experiment or comparison outcome
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    /// Too few users to draw conclusions
+    InsufficientData,
+    /// Observed traffic split differs from the configured weights; results are untrustworthy
+    SampleRatioMismatch,
+    /// No significant difference yet
+    Inconclusive,
+    /// A treatment significantly outperforms control
+    TreatmentWins,
+    /// Control significantly outperforms every treatment
+    ControlWins,
+}
+
+/// Conversion-rate comparison of a treatment against control
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProportionTest {
+    /// Treatment rate minus control rate
+    pub difference: f64,
+    /// Difference relative to the control rate, when control converts at all
+    pub relative_lift: Option<f64>,
+    pub confidence_interval: ConfidenceInterval,
+    pub z_score: f64,
+    /// Fixed-horizon p-value; only valid if the sample size was fixed in advance
+    pub p_value: f64,
+    /// Always-valid mSPRT p-value; safe to check continuously
+    pub sequential_p_value: f64,
+}
+
+/// Continuous-metric comparison of a treatment against control
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeanTest {
+    pub metric: String,
+    /// Treatment mean minus control mean
+    pub difference: f64,
+    pub confidence_interval: ConfidenceInterval,
+    pub t_statistic: f64,
+    pub degrees_of_freedom: f64,
+    pub p_value: f64,
+}
+
+/// A treatment variant compared with control
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VariantComparison {
+    pub variant_id: Uuid,
+    pub variant_name: String,
+    pub conversion: ProportionTest,
+    pub watch_time: Option<MeanTest>,
+    pub verdict: Verdict,
+}
+
+/// Chi-square test of the observed traffic split against variant weights
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SampleRatioCheck {
+    pub chi_square: f64,
+    pub p_value: f64,
+    pub mismatch: bool,
+}
+
+/// Full statistical analysis of an experiment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExperimentAnalysis {
+    pub control_variant_id: Option<Uuid>,
+    pub comparisons: Vec<VariantComparison>,
+    pub sample_ratio: Option<SampleRatioCheck>,
+    pub verdict: Verdict,
+    /// Best significantly winning treatment, when the verdict is `treatment_wins`
+    pub winning_variant: Option<String>,
+}
+
+/// Analyse variant metrics against the control variant
+///
+/// Control is the variant named "control", or the first variant otherwise.
+/// Verdicts are driven by the sequential p-value with a Bonferroni-corrected
+/// alpha, so experiments can be stopped as soon as a verdict appears.
+pub fn analyze(variants: &[VariantMetrics], config: &AnalysisConfig) -> ExperimentAnalysis {
+    let control_index = variants
+        .iter()
+        .position(|v| v.variant_name.eq_ignore_ascii_case(CONTROL_VARIANT_NAME))
+        .or_else(|| (!variants.is_empty()).then_some(0));
+
+    let Some(control_index) = control_index else {
+        return ExperimentAnalysis {
+            control_variant_id: None,
+            comparisons: Vec::new(),
+            sample_ratio: None,
+            verdict: Verdict::InsufficientData,
+            winning_variant: None,
+        };
+    };
+    let control = &variants[control_index];
+
+    let comparison_alpha = config.alpha / (variants.len().saturating_sub(1).max(1)) as f64;
+    let comparisons: Vec<VariantComparison> = variants
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| *i != control_index)
+        .map(|(_, treatment)| compare(control, treatment, comparison_alpha, config))
+        .collect();
+
+    let sample_ratio = config
+        .check_sample_ratio
+        .then(|| {
+            sample_ratio_mismatch(
+                &variants.iter().map(|v| v.exposed_users).collect::<Vec<_>>(),
+                &variants.iter().map(|v| v.weight).collect::<Vec<_>>(),
+                config.srm_alpha,
+            )
+        })
+        .flatten();
+
+    let winner = comparisons
+        .iter()
+        .filter(|c| c.verdict == Verdict::TreatmentWins)
+        .max_by(|a, b| {
+            a.conversion
+                .difference
+                .partial_cmp(&b.conversion.difference)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+    let verdict = if sample_ratio.as_ref().is_some_and(|s| s.mismatch) {
+        Verdict::SampleRatioMismatch
+    } else if comparisons.is_empty() {
+        Verdict::InsufficientData
+    } else if winner.is_some() {
+        Verdict::TreatmentWins
+    } else if comparisons
+        .iter()
+        .all(|c| c.verdict == Verdict::ControlWins)
+    {
+        Verdict::ControlWins
+    } else if comparisons
+        .iter()
+        .any(|c| c.verdict == Verdict::InsufficientData)
+    {
+        Verdict::InsufficientData
+    } else {
+        Verdict::Inconclusive
+    };
+
+    ExperimentAnalysis {
+        control_variant_id: Some(control.variant_id),
+        winning_variant: match verdict {
+            Verdict::TreatmentWins => winner.map(|c| c.variant_name.clone()),
+            _ => None,
+        },
+        comparisons,
+        sample_ratio,
+        verdict,
+    }
+}
+
+fn compare(
+    control: &VariantMetrics,
+    treatment: &VariantMetrics,
+    alpha: f64,
+    config: &AnalysisConfig,
+) -> VariantComparison {
+    let conversion = two_proportion_test(
+        control.converted_users,
+        control.exposed_users,
+        treatment.converted_users,
+        treatment.exposed_users,
+        alpha,
+        config.mixing_sd,
+    );
+
+    let watch_time = match (&control.watch_time, &treatment.watch_time) {
+        (Some(c), Some(t)) => welch_t_test(c, t, alpha).map(|mut test| {
+            test.metric = WATCH_TIME_METRIC.to_string();
+            test
+        }),
+        _ => None,
+    };
+
+    let verdict = if control.exposed_users < config.min_users_per_variant
+        || treatment.exposed_users < config.min_users_per_variant
+    {
+        Verdict::InsufficientData
+    } else if conversion.sequential_p_value < alpha {
+        if conversion.difference > 0.0 {
+            Verdict::TreatmentWins
+        } else {
+            Verdict::ControlWins
+        }
+    } else {
+        Verdict::Inconclusive
+    };
+
+    VariantComparison {
+        variant_id: treatment.variant_id,
+        variant_name: treatment.variant_name.clone(),
+        conversion,
+        watch_time,
+        verdict,
+    }
+}
+
+/// Wilson score interval for a binomial proportion
+pub fn wilson_interval(successes: i64, trials: i64, alpha: f64) -> ConfidenceInterval {
+    if trials <= 0 {
+        return ConfidenceInterval {
+            lower: 0.0,
+            upper: 1.0,
+        };
+    }
+
+    let n = trials as f64;
+    let p = (successes as f64 / n).clamp(0.0, 1.0);
+    let z = normal_quantile(1.0 - alpha / 2.0);
+    let z2 = z * z;
+    let denominator = 1.0 + z2 / n;
+    let center = (p + z2 / (2.0 * n)) / denominator;
+    let half_width = z * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt() / denominator;
+
+    ConfidenceInterval {
+        lower: (center - half_width).max(0.0),
+        upper: (center + half_width).min(1.0),
+    }
+}
+
+/// Two-proportion z-test plus mSPRT for treatment (`b`) versus control (`a`)
+pub fn two_proportion_test(
+    successes_a: i64,
+    trials_a: i64,
+    successes_b: i64,
+    trials_b: i64,
+    alpha: f64,
+    mixing_sd: f64,
+) -> ProportionTest {
+    let rate = |s: i64, n: i64| {
+        if n > 0 {
+            (s as f64 / n as f64).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    };
+    let p_a = rate(successes_a, trials_a);
+    let p_b = rate(successes_b, trials_b);
+    let difference = p_b - p_a;
+    let relative_lift = (p_a > 0.0).then(|| difference / p_a);
+
+    if trials_a <= 0 || trials_b <= 0 {
+        return ProportionTest {
+            difference,
+            relative_lift,
+            confidence_interval: ConfidenceInterval {
+                lower: -1.0,
+                upper: 1.0,
+            },
+            z_score: 0.0,
+            p_value: 1.0,
+            sequential_p_value: 1.0,
+        };
+    }
+
+    let (n_a, n_b) = (trials_a as f64, trials_b as f64);
+    let pooled = (successes_a + successes_b) as f64 / (n_a + n_b);
+    let pooled_variance = (pooled * (1.0 - pooled) * (1.0 / n_a + 1.0 / n_b)).max(0.0);
+    let unpooled_se = (p_a * (1.0 - p_a) / n_a + p_b * (1.0 - p_b) / n_b).sqrt();
+
+    let (z_score, p_value) = if pooled_variance > 0.0 {
+        let z = difference / pooled_variance.sqrt();
+        (z, two_sided_normal_p(z))
+    } else {
+        (0.0, 1.0)
+    };
+
+    let z_crit = normal_quantile(1.0 - alpha / 2.0);
+
+    ProportionTest {
+        difference,
+        relative_lift,
+        confidence_interval: ConfidenceInterval {
+            lower: difference - z_crit * unpooled_se,
+            upper: difference + z_crit * unpooled_se,
+        },
+        z_score,
+        p_value,
+        sequential_p_value: msprt_p_value(difference, pooled_variance, mixing_sd * mixing_sd),
+    }
+}
+
+/// Always-valid p-value of a normal-mixture SPRT
+///
+/// `variance` is the variance of the estimated effect under the null and
+/// `mixing_variance` the variance of the normal mixture over effects. By
+/// Ville's inequality, rejecting once this drops below alpha controls the
+/// type I error at alpha however many times it is checked.
+pub fn msprt_p_value(estimate: f64, variance: f64, mixing_variance: f64) -> f64 {
+    if variance <= 0.0 || mixing_variance <= 0.0 {
+        return 1.0;
+    }
+
+    let total = variance + mixing_variance;
+    let log_likelihood_ratio = 0.5 * (variance / total).ln()
+        + mixing_variance * estimate * estimate / (2.0 * variance * total);
+
+    (-log_likelihood_ratio).exp().min(1.0)
+}
+
+/// Welch's unequal-variance t-test for treatment (`b`) versus control (`a`)
+///
+/// Returns `None` when either group has fewer than two users or both
+/// variances are zero.
+pub fn welch_t_test(a: &ContinuousMetric, b: &ContinuousMetric, alpha: f64) -> Option<MeanTest> {
+    if a.users < 2 || b.users < 2 {
+        return None;
+    }
+
+    let (n_a, n_b) = (a.users as f64, b.users as f64);
+    let (v_a, v_b) = (a.variance.max(0.0) / n_a, b.variance.max(0.0) / n_b);
+    let se2 = v_a + v_b;
+    if se2 <= 0.0 {
+        return None;
+    }
+
+    let se = se2.sqrt();
+    let difference = b.mean - a.mean;
+    let t_statistic = difference / se;
+    let degrees_of_freedom = se2 * se2 / (v_a * v_a / (n_a - 1.0) + v_b * v_b / (n_b - 1.0));
+    let t_crit = student_t_quantile(1.0 - alpha / 2.0, degrees_of_freedom);
+
+    Some(MeanTest {
+        metric: String::new(),
+        difference,
+        confidence_interval: ConfidenceInterval {
+            lower: difference - t_crit * se,
+            upper: difference + t_crit * se,
+        },
+        t_statistic,
+        degrees_of_freedom,
+        p_value: two_sided_t_p(t_statistic, degrees_of_freedom),
+    })
+}
+
+/// Chi-square goodness-of-fit test of observed users against variant weights
+///
+/// Returns `None` with fewer than two weighted variants or no users.
+pub fn sample_ratio_mismatch(
+    observed: &[i64],
+    weights: &[f64],
+    alpha: f64,
+) -> Option<SampleRatioCheck> {
+    let pairs: Vec<(f64, f64)> = observed
+        .iter()
+        .zip(weights)
+        .filter(|(_, w)| **w > 0.0)
+        .map(|(o, w)| (*o as f64, *w))
+        .collect();
+    let total: f64 = pairs.iter().map(|(o, _)| o).sum();
+    let total_weight: f64 = pairs.iter().map(|(_, w)| w).sum();
+    if pairs.len() < 2 || total <= 0.0 {
+        return None;
+    }
+
+    let chi_square: f64 = pairs
+        .iter()
+        .map(|(o, w)| {
+            let expected = total * w / total_weight;
+            (o - expected).powi(2) / expected
+        })
+        .sum();
+    let p_value = chi_square_sf(chi_square, (pairs.len() - 1) as f64);
+
+    Some(SampleRatioCheck {
+        chi_square,
+        p_value,
+        mismatch: p_value < alpha,
+    })
+}
+
+/// Standard normal CDF
+pub fn normal_cdf(x: f64) -> f64 {
+    0.5 * erfc(-x / std::f64::consts::SQRT_2)
+}
+
+/// Standard normal quantile (Acklam's rational approximation)
+pub fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969_683_028_665_376e1,
+        2.209_460_984_245_205e2,
+        -2.759_285_104_469_687e2,
+        1.383_577_518_672_69e2,
+        -3.066_479_806_614_716e1,
+        2.506_628_277_459_239,
+    ];
+    const B: [f64; 5] = [
+        -5.447_609_879_822_406e1,
+        1.615_858_368_580_409e2,
+        -1.556_989_798_598_866e2,
+        6.680_131_188_771_972e1,
+        -1.328_068_155_288_572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784_894_002_430_293e-3,
+        -3.223_964_580_411_365e-1,
+        -2.400_758_277_161_838,
+        -2.549_732_539_343_734,
+        4.374_664_141_464_968,
+        2.938_163_982_698_783,
+    ];
+    const D: [f64; 4] = [
+        7.784_695_709_041_462e-3,
+        3.224_671_290_700_398e-1,
+        2.445_134_137_142_996,
+        3.754_408_661_907_416,
+    ];
+    const P_LOW: f64 = 0.02425;
+
+    if p <= 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if p >= 1.0 {
+        return f64::INFINITY;
+    }
+
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+
+    if p < P_LOW {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p <= 1.0 - P_LOW {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    } else {
+        -tail((-2.0 * (1.0 - p).ln()).sqrt())
+    }
+}
+
+fn two_sided_normal_p(z: f64) -> f64 {
+    (2.0 * (1.0 - normal_cdf(z.abs()))).clamp(0.0, 1.0)
+}
+
+/// Two-sided p-value of Student's t distribution
+fn two_sided_t_p(t: f64, df: f64) -> f64 {
+    if !t.is_finite() {
+        return 0.0;
+    }
+    regularized_beta(df / 2.0, 0.5, df / (df + t * t)).clamp(0.0, 1.0)
+}
+
+/// Quantile of Student's t distribution by bisection on the two-sided p-value
+fn student_t_quantile(p: f64, df: f64) -> f64 {
+    let target = 2.0 * (1.0 - p);
+    let (mut low, mut high) = (0.0, 1.0e3);
+    for _ in 0..MAX_ITERATIONS {
+        let mid = 0.5 * (low + high);
+        if two_sided_t_p(mid, df) > target {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    0.5 * (low + high)
+}
+
+/// Survival function of the chi-square distribution
+fn chi_square_sf(x: f64, df: f64) -> f64 {
+    if x <= 0.0 {
+        return 1.0;
+    }
+    upper_regularized_gamma(df / 2.0, x / 2.0)
+}
+
+/// Complementary error function (Chebyshev fit, |error| < 1.2e-7)
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let ans = t
+        * (-z * z - 1.265_512_23
+            + t * (1.000_023_68
+                + t * (0.374_091_96
+                    + t * (0.096_784_18
+                        + t * (-0.186_288_06
+                            + t * (0.278_868_07
+                                + t * (-1.135_203_98
+                                    + t * (1.488_515_87
+                                        + t * (-0.822_152_23 + t * 0.170_872_77)))))))))
+            .exp();
+    if x >= 0.0 {
+        ans
+    } else {
+        2.0 - ans
+    }
+}
+
+/// Natural log of the gamma function (Lanczos approximation)
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 6] = [
+        76.180_091_729_471_46,
+        -86.505_320_329_416_77,
+        24.014_098_240_830_91,
+        -1.231_739_572_450_155,
+        0.001_208_650_973_866_179,
+        -0.000_005_395_239_384_953,
+    ];
+
+    let mut y = x;
+    let tmp = x + 5.5;
+    let tmp = tmp - (x + 0.5) * tmp.ln();
+    let mut series = 1.000_000_000_190_015;
+    for c in COEFFICIENTS {
+        y += 1.0;
+        series += c / y;
+    }
+    -tmp + (2.506_628_274_631_000_5 * series / x).ln()
+}
+
+/// Regularized incomplete beta function I_x(a, b)
+fn regularized_beta(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+
+    let front =
+        (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln()).exp();
+    if x < (a + 1.0) / (a + b + 2.0) {
+        front * beta_continued_fraction(a, b, x) / a
+    } else {
+        1.0 - front * beta_continued_fraction(b, a, 1.0 - x) / b
+    }
+}
+
+/// Continued fraction for the incomplete beta function (modified Lentz)
+fn beta_continued_fraction(a: f64, b: f64, x: f64) -> f64 {
+    let guard = |v: f64| if v.abs() < FP_MIN { FP_MIN } else { v };
+
+    let (qab, qap, qam) = (a + b, a + 1.0, a - 1.0);
+    let mut c = 1.0;
+    let mut d = 1.0 / guard(1.0 - qab * x / qap);
+    let mut h = d;
+
+    for m in 1..=MAX_ITERATIONS {
+        let m = m as f64;
+        let m2 = 2.0 * m;
+
+        let aa = m * (b - m) * x / ((qam + m2) * (a + m2));
+        d = 1.0 / guard(1.0 + aa * d);
+        c = guard(1.0 + aa / c);
+        h *= d * c;
+
+        let aa = -(a + m) * (qab + m) * x / ((a + m2) * (qap + m2));
+        d = 1.0 / guard(1.0 + aa * d);
+        c = guard(1.0 + aa / c);
+        let delta = d * c;
+        h *= delta;
+
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+    h
+}
+
+/// Upper regularized incomplete gamma function Q(a, x)
+fn upper_regularized_gamma(a: f64, x: f64) -> f64 {
+    let log_front = -x + a * x.ln() - ln_gamma(a);
+
+    if x < a + 1.0 {
+        // Series for the lower function P(a, x)
+        let mut ap = a;
+        let mut delta = 1.0 / a;
+        let mut sum = delta;
+        for _ in 0..MAX_ITERATIONS {
+            ap += 1.0;
+            delta *= x / ap;
+            sum += delta;
+            if delta.abs() < sum.abs() * EPSILON {
+                break;
+            }
+        }
+        (1.0 - sum * log_front.exp()).clamp(0.0, 1.0)
+    } else {
+        // Continued fraction for Q(a, x) (modified Lentz)
+        let guard = |v: f64| if v.abs() < FP_MIN { FP_MIN } else { v };
+        let mut b = x + 1.0 - a;
+        let mut c = 1.0 / FP_MIN;
+        let mut d = 1.0 / b;
+        let mut h = d;
+        for i in 1..=MAX_ITERATIONS {
+            let i = i as f64;
+            let an = -i * (i - a);
+            b += 2.0;
+            d = 1.0 / guard(an * d + b);
+            c = guard(b + an / c);
+            let delta = d * c;
+            h *= delta;
+            if (delta - 1.0).abs() < EPSILON {
+                break;
+            }
+        }
+        (log_front.exp() * h).clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variant(name: &str, weight: f64, exposed: i64, converted: i64) -> VariantMetrics {
+        VariantMetrics {
+            variant_id: Uuid::new_v4(),
+            variant_name: name.to_string(),
+            weight,
+            exposures: exposed,
+            conversions: converted,
+            conversion_rate: converted as f64 / exposed as f64,
+            avg_metric_value: 0.0,
+            exposed_users: exposed,
+            converted_users: converted,
+            user_conversion_rate: converted as f64 / exposed as f64,
+            user_conversion_rate_ci: wilson_interval(converted, exposed, 0.05),
+            watch_time: None,
+        }
+    }
+
+    #[test]
+    fn test_normal_distribution() {
+        assert!((normal_cdf(1.959_964) - 0.975).abs() < 1e-6);
+        assert!((normal_cdf(0.0) - 0.5).abs() < 1e-7);
+        assert!((normal_quantile(0.975) - 1.959_964).abs() < 1e-6);
+        assert!((normal_quantile(0.01) + 2.326_348).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_t_and_chi_square_distributions() {
+        // t = 2.228 is the 97.5th percentile for 10 degrees of freedom
+        assert!((two_sided_t_p(2.228_139, 10.0) - 0.05).abs() < 1e-5);
+        assert!((student_t_quantile(0.975, 10.0) - 2.228_139).abs() < 1e-4);
+        // 3.841 is the 95th percentile of chi-square with 1 degree of freedom
+        assert!((chi_square_sf(3.841_459, 1.0) - 0.05).abs() < 1e-5);
+        assert!((chi_square_sf(5.991_465, 2.0) - 0.05).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_wilson_interval() {
+        let ci = wilson_interval(50, 100, 0.05);
+        assert!((ci.lower - 0.4038).abs() < 1e-3);
+        assert!((ci.upper - 0.5962).abs() < 1e-3);
+
+        let empty = wilson_interval(0, 0, 0.05);
+        assert_eq!((empty.lower, empty.upper), (0.0, 1.0));
+    }
+
+    #[test]
+    fn test_two_proportion_test() {
+        let test = two_proportion_test(100, 1000, 150, 1000, 0.05, 0.02);
+        assert!((test.difference - 0.05).abs() < 1e-12);
+        assert!((test.z_score - 3.3806).abs() < 1e-3);
+        assert!(test.p_value < 0.001);
+        assert!(test.confidence_interval.lower > 0.0);
+        // The always-valid p-value is more conservative than the fixed-horizon one
+        assert!(test.sequential_p_value > test.p_value);
+        assert!(test.sequential_p_value < 0.05);
+
+        let null = two_proportion_test(100, 1000, 101, 1000, 0.05, 0.02);
+        assert!(null.sequential_p_value > 0.5);
+    }
+
+    #[test]
+    fn test_welch_t_test() {
+        let a = ContinuousMetric {
+            users: 50,
+            mean: 30.0,
+            variance: 100.0,
+        };
+        let b = ContinuousMetric {
+            users: 60,
+            mean: 36.0,
+            variance: 120.0,
+        };
+        let test = welch_t_test(&a, &b, 0.05).unwrap();
+        assert!((test.t_statistic - 3.0).abs() < 1e-9);
+        assert!(test.degrees_of_freedom > 100.0 && test.degrees_of_freedom < 110.0);
+        assert!(test.p_value < 0.01);
+        assert!(test.confidence_interval.lower > 0.0);
+
+        let single = ContinuousMetric {
+            users: 1,
+            mean: 1.0,
+            variance: 0.0,
+        };
+        assert!(welch_t_test(&single, &b, 0.05).is_none());
+    }
+
+    #[test]
+    fn test_sample_ratio_mismatch() {
+        let balanced = sample_ratio_mismatch(&[5_020, 4_980], &[0.5, 0.5], 0.001).unwrap();
+        assert!(!balanced.mismatch);
+
+        let skewed = sample_ratio_mismatch(&[5_500, 4_500], &[0.5, 0.5], 0.001).unwrap();
+        assert!(skewed.mismatch);
+
+        assert!(sample_ratio_mismatch(&[0, 0], &[0.5, 0.5], 0.001).is_none());
+    }
+
+    #[test]
+    fn test_analyze_verdicts() {
+        let config = AnalysisConfig::default();
+
+        let winning = analyze(
+            &[
+                variant("control", 0.5, 5_000, 500),
+                variant("treatment", 0.5, 5_000, 650),
+            ],
+            &config,
+        );
+        assert_eq!(winning.verdict, Verdict::TreatmentWins);
+        assert_eq!(winning.winning_variant.as_deref(), Some("treatment"));
+
+        let flat = analyze(
+            &[
+                variant("treatment", 0.5, 5_000, 505),
+                variant("control", 0.5, 5_000, 500),
+            ],
+            &config,
+        );
+        assert_eq!(flat.verdict, Verdict::Inconclusive);
+        assert_eq!(flat.comparisons[0].variant_name, "treatment");
+
+        let small = analyze(
+            &[
+                variant("control", 0.5, 40, 4),
+                variant("treatment", 0.5, 40, 20),
+            ],
+            &config,
+        );
+        assert_eq!(small.verdict, Verdict::InsufficientData);
+
+        let srm = analyze(
+            &[
+                variant("control", 0.5, 6_000, 600),
+                variant("treatment", 0.5, 4_000, 600),
+            ],
+            &config,
+        );
+        assert_eq!(srm.verdict, Verdict::SampleRatioMismatch);
+        assert!(srm.winning_variant.is_none());
+
+        // A bandit's uneven split is by design
+        let adaptive = analyze(
+            &[
+                variant("control", 0.5, 2_000, 200),
+                variant("treatment", 0.5, 8_000, 1_040),
+            ],
+            &AnalysisConfig {
+                check_sample_ratio: false,
+                ..AnalysisConfig::default()
+            },
+        );
+        assert!(adaptive.sample_ratio.is_none());
+        assert_eq!(adaptive.verdict, Verdict::TreatmentWins);
+    }
+}
@@ -6,11 +6,14 @@
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
 use sqlx::PgPool;
 use tracing::{debug, info, instrument};
 use uuid::Uuid;
 
 use crate::ab_testing::{Assignment, Experiment, ExperimentMetrics, Variant, VariantMetrics};
+use crate::bandit::{ArmRewards, ArmState, BanditConfig, BanditState, LoggedEvent};
+use crate::experiment_analysis::{wilson_interval, ContinuousMetric, WATCH_TIME_METRIC};
 
 /// Experiment repository trait for abstraction
 #[async_trait::async_trait]
@@ -40,6 +43,13 @@ pub trait ExperimentRepository: Send + Sync {
     /// Delete experiment
     async fn delete_experiment(&self, experiment_id: Uuid) -> Result<()>;
 
+    /// Get a user's recorded assignment to an experiment
+    async fn get_assignment(
+        &self,
+        experiment_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<Assignment>>;
+
     /// Record user assignment to variant
     async fn record_assignment(
         &self,
@@ -48,6 +58,20 @@ pub trait ExperimentRepository: Send + Sync {
         variant_id: Uuid,
     ) -> Result<Assignment>;
 
+    /// Whether the user has a recorded holdout from an experiment
+    async fn is_held_out(&self, experiment_id: Uuid, user_id: Uuid) -> Result<bool>;
+
+    /// Record that the user is held out of an experiment
+    async fn record_holdout(&self, experiment_id: Uuid, user_id: Uuid) -> Result<()>;
+
+    /// Record that a held-out user was served the baseline experience
+    async fn record_holdout_exposure(
+        &self,
+        experiment_id: Uuid,
+        user_id: Uuid,
+        context: Option<serde_json::Value>,
+    ) -> Result<()>;
+
     /// Record experiment metric (exposure or conversion)
     async fn record_metric(
         &self,
@@ -73,6 +97,28 @@ pub trait ExperimentRepository: Send + Sync {
 
     /// Get experiment metrics
     async fn get_experiment_metrics(&self, experiment_id: Uuid) -> Result<ExperimentMetrics>;
+
+    /// Get per-variant exposed and rewarded user counts for bandit updates
+    ///
+    /// Without a `reward_metric` any conversion except watch time is a reward.
+    async fn get_bandit_rewards(
+        &self,
+        experiment_id: Uuid,
+        reward_metric: Option<&str>,
+    ) -> Result<Vec<ArmRewards>>;
+
+    /// Get persisted bandit state, if the experiment is a bandit
+    async fn get_bandit_state(&self, experiment_id: Uuid) -> Result<Option<BanditState>>;
+
+    /// Create or replace bandit state
+    async fn save_bandit_state(&self, state: &BanditState) -> Result<()>;
+
+    /// Get each user's first exposure, in order, with whether they converted
+    async fn get_bandit_replay_log(
+        &self,
+        experiment_id: Uuid,
+        reward_metric: Option<&str>,
+    ) -> Result<Vec<LoggedEvent>>;
 }
 
 /// PostgreSQL implementation of ExperimentRepository
@@ -101,7 +147,7 @@ impl ExperimentRepository for PostgresExperimentRepository {
             r#"
             INSERT INTO experiments (name, description, traffic_allocation)
             VALUES ($1, $2, $3)
-            RETURNING id, name, description, status, traffic_allocation, created_at, updated_at
+            RETURNING id, name, description, status, traffic_allocation, created_at, updated_at, assignment
             "#,
         )
         .bind(name)
@@ -118,7 +164,7 @@ impl ExperimentRepository for PostgresExperimentRepository {
     async fn get_experiment(&self, experiment_id: Uuid) -> Result<Option<Experiment>> {
         let experiment = sqlx::query_as::<_, Experiment>(
             r#"
-            SELECT id, name, description, status, traffic_allocation, created_at, updated_at
+            SELECT id, name, description, status, traffic_allocation, created_at, updated_at, assignment
             FROM experiments
             WHERE id = $1
             "#,
@@ -135,7 +181,7 @@ impl ExperimentRepository for PostgresExperimentRepository {
         let experiments = if let Some(status) = status_filter {
             sqlx::query_as::<_, Experiment>(
                 r#"
-                SELECT id, name, description, status, traffic_allocation, created_at, updated_at
+                SELECT id, name, description, status, traffic_allocation, created_at, updated_at, assignment
                 FROM experiments
                 WHERE status = $1
                 ORDER BY created_at DESC
@@ -148,7 +194,7 @@ impl ExperimentRepository for PostgresExperimentRepository {
         } else {
             sqlx::query_as::<_, Experiment>(
                 r#"
-                SELECT id, name, description, status, traffic_allocation, created_at, updated_at
+                SELECT id, name, description, status, traffic_allocation, created_at, updated_at, assignment
                 FROM experiments
                 ORDER BY created_at DESC
                 "#,
@@ -222,6 +268,25 @@ impl ExperimentRepository for PostgresExperimentRepository {
     }
 
     #[instrument(skip(self))]
+    async fn get_assignment(
+        &self,
+        experiment_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<Assignment>> {
+        sqlx::query_as::<_, Assignment>(
+            r#"
+            SELECT id, experiment_id, user_id, variant_id, assigned_at
+            FROM experiment_assignments
+            WHERE experiment_id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(experiment_id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to fetch assignment")
+    }
+
     async fn record_assignment(
         &self,
         experiment_id: Uuid,
@@ -252,6 +317,66 @@ impl ExperimentRepository for PostgresExperimentRepository {
         Ok(assignment)
     }
 
+    async fn is_held_out(&self, experiment_id: Uuid, user_id: Uuid) -> Result<bool> {
+        let held_out: (bool,) = sqlx::query_as(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM experiment_holdouts
+                WHERE experiment_id = $1 AND user_id = $2
+            )
+            "#,
+        )
+        .bind(experiment_id)
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await
+        .context("Failed to fetch holdout")?;
+
+        Ok(held_out.0)
+    }
+
+    #[instrument(skip(self))]
+    async fn record_holdout(&self, experiment_id: Uuid, user_id: Uuid) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO experiment_holdouts (experiment_id, user_id)
+            VALUES ($1, $2)
+            ON CONFLICT (experiment_id, user_id) DO NOTHING
+            "#,
+        )
+        .bind(experiment_id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await
+        .context("Failed to record holdout")?;
+
+        debug!(experiment_id = %experiment_id, user_id = %user_id, "Recorded holdout");
+        Ok(())
+    }
+
+    #[instrument(skip(self, context))]
+    async fn record_holdout_exposure(
+        &self,
+        experiment_id: Uuid,
+        user_id: Uuid,
+        context: Option<serde_json::Value>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO experiment_holdout_exposures (experiment_id, user_id, context)
+            VALUES ($1, $2, $3)
+            "#,
+        )
+        .bind(experiment_id)
+        .bind(user_id)
+        .bind(context.unwrap_or(serde_json::json!({})))
+        .execute(&self.pool)
+        .await
+        .context("Failed to record holdout exposure")?;
+
+        Ok(())
+    }
+
     #[instrument(skip(self, metadata))]
     async fn record_metric(
         &self,
@@ -383,26 +508,212 @@ impl ExperimentRepository for PostgresExperimentRepository {
             .fetch_one(&self.pool)
             .await?;
 
+            // Distinct exposed users and how many of them converted; watch
+            // time is recorded for any playback and is not a conversion
+            let users: (i64, i64) = sqlx::query_as(
+                r#"
+                SELECT
+                    COUNT(DISTINCT e.user_id),
+                    COUNT(DISTINCT c.user_id)
+                FROM experiment_exposures e
+                LEFT JOIN experiment_conversions c
+                    ON c.experiment_id = e.experiment_id
+                    AND c.variant_id = e.variant_id
+                    AND c.user_id = e.user_id
+                    AND c.metric_name <> $3
+                WHERE e.experiment_id = $1 AND e.variant_id = $2
+                "#,
+            )
+            .bind(experiment_id)
+            .bind(variant.id)
+            .bind(WATCH_TIME_METRIC)
+            .fetch_one(&self.pool)
+            .await?;
+
+            // Per-user watch time totals, counting exposed users without events as zero
+            let watch_time: (i64, i64, Option<f64>, Option<f64>) = sqlx::query_as(
+                r#"
+                SELECT COUNT(*), COALESCE(SUM(events), 0)::BIGINT, AVG(total), VAR_SAMP(total)
+                FROM (
+                    SELECT e.user_id, COUNT(c.id) AS events, COALESCE(SUM(c.value), 0) AS total
+                    FROM (
+                        SELECT DISTINCT user_id
+                        FROM experiment_exposures
+                        WHERE experiment_id = $1 AND variant_id = $2
+                    ) e
+                    LEFT JOIN experiment_conversions c
+                        ON c.experiment_id = $1
+                        AND c.variant_id = $2
+                        AND c.user_id = e.user_id
+                        AND c.metric_name = $3
+                    GROUP BY e.user_id
+                ) per_user
+                "#,
+            )
+            .bind(experiment_id)
+            .bind(variant.id)
+            .bind(WATCH_TIME_METRIC)
+            .fetch_one(&self.pool)
+            .await?;
+
             let conversion_rate = if exposures.0 > 0 {
                 conversions.0 as f64 / exposures.0 as f64
             } else {
                 0.0
             };
+            let (exposed_users, converted_users) = users;
+            let user_conversion_rate = if exposed_users > 0 {
+                converted_users as f64 / exposed_users as f64
+            } else {
+                0.0
+            };
 
             variant_metrics.push(VariantMetrics {
                 variant_id: variant.id,
                 variant_name: variant.name,
+                weight: variant.weight,
                 exposures: exposures.0,
                 conversions: conversions.0,
                 conversion_rate,
                 avg_metric_value: avg_value.0.unwrap_or(0.0),
+                exposed_users,
+                converted_users,
+                user_conversion_rate,
+                user_conversion_rate_ci: wilson_interval(converted_users, exposed_users, 0.05),
+                watch_time: (watch_time.1 > 0).then(|| ContinuousMetric {
+                    users: watch_time.0,
+                    mean: watch_time.2.unwrap_or(0.0),
+                    variance: watch_time.3.unwrap_or(0.0),
+                }),
             });
         }
 
-        Ok(ExperimentMetrics {
+        Ok(ExperimentMetrics::new(experiment_id, variant_metrics))
+    }
+
+    async fn get_bandit_rewards(
+        &self,
+        experiment_id: Uuid,
+        reward_metric: Option<&str>,
+    ) -> Result<Vec<ArmRewards>> {
+        let rows: Vec<(Uuid, i64, i64)> = sqlx::query_as(
+            r#"
+            SELECT
+                v.id,
+                COUNT(DISTINCT e.user_id),
+                COUNT(DISTINCT c.user_id)
+            FROM experiment_variants v
+            LEFT JOIN experiment_exposures e
+                ON e.variant_id = v.id
+            LEFT JOIN experiment_conversions c
+                ON c.experiment_id = e.experiment_id
+                AND c.variant_id = e.variant_id
+                AND c.user_id = e.user_id
+                AND (
+                    ($2::VARCHAR IS NULL AND c.metric_name <> $3)
+                    OR c.metric_name = $2
+                )
+            WHERE v.experiment_id = $1
+            GROUP BY v.id, v.created_at
+            ORDER BY v.created_at, v.id
+            "#,
+        )
+        .bind(experiment_id)
+        .bind(reward_metric)
+        .bind(WATCH_TIME_METRIC)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to fetch bandit rewards")?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(variant_id, trials, successes)| ArmRewards {
+                variant_id,
+                trials,
+                successes,
+            })
+            .collect())
+    }
+
+    async fn get_bandit_state(&self, experiment_id: Uuid) -> Result<Option<BanditState>> {
+        let row: Option<(Json<BanditConfig>, Json<Vec<ArmState>>, DateTime<Utc>)> = sqlx::query_as(
+            "SELECT config, arms, updated_at FROM experiment_bandits WHERE experiment_id = $1",
+        )
+        .bind(experiment_id)
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to fetch bandit state")?;
+
+        Ok(row.map(|(config, arms, updated_at)| BanditState {
             experiment_id,
-            variant_metrics,
-        })
+            config: config.0,
+            arms: arms.0,
+            updated_at,
+        }))
+    }
+
+    #[instrument(skip(self, state), fields(experiment_id = %state.experiment_id))]
+    async fn save_bandit_state(&self, state: &BanditState) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO experiment_bandits (experiment_id, config, arms, updated_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (experiment_id) DO UPDATE
+            SET config = EXCLUDED.config, arms = EXCLUDED.arms, updated_at = EXCLUDED.updated_at
+            "#,
+        )
+        .bind(state.experiment_id)
+        .bind(Json(&state.config))
+        .bind(Json(&state.arms))
+        .bind(state.updated_at)
+        .execute(&self.pool)
+        .await
+        .context("Failed to save bandit state")?;
+
+        debug!(arms = state.arms.len(), "Saved bandit state");
+        Ok(())
+    }
+
+    async fn get_bandit_replay_log(
+        &self,
+        experiment_id: Uuid,
+        reward_metric: Option<&str>,
+    ) -> Result<Vec<LoggedEvent>> {
+        let rows: Vec<(Uuid, bool)> = sqlx::query_as(
+            r#"
+            SELECT first.variant_id, EXISTS (
+                SELECT 1 FROM experiment_conversions c
+                WHERE c.experiment_id = $1
+                    AND c.variant_id = first.variant_id
+                    AND c.user_id = first.user_id
+                    AND (
+                        ($2::VARCHAR IS NULL AND c.metric_name <> $3)
+                        OR c.metric_name = $2
+                    )
+            )
+            FROM (
+                SELECT DISTINCT ON (user_id) user_id, variant_id, exposed_at
+                FROM experiment_exposures
+                WHERE experiment_id = $1
+                ORDER BY user_id, exposed_at
+            ) first
+            ORDER BY first.exposed_at
+            "#,
+        )
+        .bind(experiment_id)
+        .bind(reward_metric)
+        .bind(WATCH_TIME_METRIC)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to fetch bandit replay log")?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(variant_id, converted)| LoggedEvent {
+                variant_id,
+                converted,
+            })
+            .collect())
     }
 }
 
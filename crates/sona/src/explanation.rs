@@ -0,0 +1,932 @@
+//! Structured Recommendation Explanations
+//!
+//! Turns the evidence behind a recommendation into typed reasons ("because
+//! you watched X", "popular in your region", "new from director Y", ...),
+//! each with a strength so the most convincing ones are shown first. Reasons
+//! come from the candidate sources that proposed the item, its catalog
+//! attributes, the user's profile and any extra [`ExplanationEvidence`] the
+//! caller has looked up.
+//!
+//! Reasons are rendered per locale through [`ExplanationTemplates`], falling
+//! back from a regional locale to its language and then to English. Served
+//! explanations are stored so a user can later ask why an item was shown.
+
+use crate::diversity::ContentAttributes;
+use crate::profile::UserProfile;
+use crate::types::{RecommendationType, ScoredContent, SourceAttribution};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use media_gateway_core::math::cosine_similarity;
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+use sqlx::{PgPool, Row};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Neutral genre affinity; only affinities above it explain anything
+const NEUTRAL_AFFINITY: f32 = 0.5;
+/// Strength multiplier for evidence from a source that did not propose the item
+const UNATTRIBUTED_WEIGHT: f32 = 0.6;
+const DEFAULT_LOCALE: &str = "en";
+
+/// Why an item was recommended
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ExplanationReason {
+    BecauseYouWatched {
+        content_id: Uuid,
+        title: String,
+    },
+    PopularInRegion {
+        region: String,
+    },
+    TrendingWithSimilarUsers {
+        similar_users: usize,
+    },
+    NewFromCreator {
+        name: String,
+        role: CreatorRole,
+    },
+    LeavingSoon {
+        platform: Option<String>,
+        days_left: i64,
+    },
+    MatchesGenre {
+        genre: String,
+    },
+    MatchesMood {
+        mood: String,
+    },
+}
+
+/// Role of a creator the user follows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CreatorRole {
+    Director,
+    Writer,
+    Actor,
+}
+
+impl CreatorRole {
+    fn as_str(self) -> &'static str {
+        match self {
+            CreatorRole::Director => "director",
+            CreatorRole::Writer => "writer",
+            CreatorRole::Actor => "actor",
+        }
+    }
+}
+
+impl ExplanationReason {
+    /// Template key
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ExplanationReason::BecauseYouWatched { .. } => "because_you_watched",
+            ExplanationReason::PopularInRegion { .. } => "popular_in_region",
+            ExplanationReason::TrendingWithSimilarUsers { .. } => "trending_with_similar_users",
+            ExplanationReason::NewFromCreator { .. } => "new_from_creator",
+            ExplanationReason::LeavingSoon {
+                platform: Some(_), ..
+            } => "leaving_platform_soon",
+            ExplanationReason::LeavingSoon { platform: None, .. } => "leaving_soon",
+            ExplanationReason::MatchesGenre { .. } => "matches_genre",
+            ExplanationReason::MatchesMood { .. } => "matches_mood",
+        }
+    }
+
+    /// Template placeholders and their values
+    fn params(&self) -> Vec<(&'static str, String)> {
+        match self {
+            ExplanationReason::BecauseYouWatched { title, .. } => vec![("title", title.clone())],
+            ExplanationReason::PopularInRegion { region } => vec![("region", region.clone())],
+            ExplanationReason::TrendingWithSimilarUsers { similar_users } => {
+                vec![("count", similar_users.to_string())]
+            }
+            ExplanationReason::NewFromCreator { name, role } => {
+                vec![("name", name.clone()), ("role", role.as_str().to_string())]
+            }
+            ExplanationReason::LeavingSoon {
+                platform,
+                days_left,
+            } => vec![
+                ("platform", platform.clone().unwrap_or_default()),
+                ("days", days_left.to_string()),
+            ],
+            ExplanationReason::MatchesGenre { genre } => vec![("genre", genre.clone())],
+            ExplanationReason::MatchesMood { mood } => vec![("mood", mood.clone())],
+        }
+    }
+}
+
+/// A reason with how strongly the evidence supports it, in [0, 1]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RankedReason {
+    #[serde(flatten)]
+    pub reason: ExplanationReason,
+    pub strength: f32,
+}
+
+/// Closest title in the user's history
+#[derive(Debug, Clone)]
+pub struct WatchedAnchor {
+    pub content_id: Uuid,
+    pub title: String,
+    /// Cosine similarity to the recommended item
+    pub similarity: f32,
+}
+
+impl WatchedAnchor {
+    /// The watched title most similar to an item, given as (id, title,
+    /// embedding); titles with no positive similarity never anchor
+    pub fn closest(embedding: &[f32], watched: &[(Uuid, String, Vec<f32>)]) -> Option<Self> {
+        watched
+            .iter()
+            .map(|(content_id, title, watched_embedding)| {
+                (
+                    content_id,
+                    title,
+                    cosine_similarity(embedding, watched_embedding),
+                )
+            })
+            .filter(|(_, _, similarity)| *similarity > 0.0)
+            .max_by(|a, b| a.2.total_cmp(&b.2))
+            .map(|(content_id, title, similarity)| WatchedAnchor {
+                content_id: *content_id,
+                title: title.clone(),
+                similarity,
+            })
+    }
+}
+
+/// Creator of the item the user has an affinity for
+#[derive(Debug, Clone)]
+pub struct CreatorMatch {
+    pub name: String,
+    pub role: CreatorRole,
+    /// User's affinity for the creator, in [0, 1]
+    pub affinity: f32,
+    pub released_at: DateTime<Utc>,
+}
+
+/// Evidence looked up outside the pipeline for one item
+#[derive(Debug, Clone, Default)]
+pub struct ExplanationEvidence {
+    pub watched_anchor: Option<WatchedAnchor>,
+    /// Region and the item's 1-based position in its popularity chart
+    pub regional_rank: Option<(String, usize)>,
+    /// Similar users who watched the item recently
+    pub similar_user_views: Option<usize>,
+    pub creator: Option<CreatorMatch>,
+}
+
+/// Explanation settings
+#[derive(Debug, Clone)]
+pub struct ExplanationConfig {
+    /// Reasons kept per recommendation (default: 3)
+    pub max_reasons: usize,
+    /// Weaker reasons are dropped (default: 0.1)
+    pub min_strength: f32,
+    /// Chart positions that count as popular (default: 50)
+    pub regional_top_n: usize,
+    /// Similar-user views at which that reason is at full strength (default: 20)
+    pub similar_users_saturation: usize,
+    /// Releases this recent count as new (default: 90 days)
+    pub new_release_days: i64,
+    /// Availability ending within this window counts as leaving soon
+    /// (default: 14 days)
+    pub leaving_soon_days: i64,
+    /// Recently watched titles considered as "because you watched" anchors
+    /// (default: 50)
+    pub anchor_history: usize,
+    /// Similar-user views this recent are counted (default: 14 days)
+    pub similar_users_window_days: i64,
+    /// Watched titles by a creator at which the affinity is full (default: 3)
+    pub creator_saturation: usize,
+}
+
+impl Default for ExplanationConfig {
+    fn default() -> Self {
+        Self {
+            max_reasons: 3,
+            min_strength: 0.1,
+            regional_top_n: 50,
+            similar_users_saturation: 20,
+            new_release_days: 90,
+            leaving_soon_days: 14,
+            anchor_history: 50,
+            similar_users_window_days: 14,
+            creator_saturation: 3,
+        }
+    }
+}
+
+/// Derive ranked reasons for recommended items
+pub struct ExplainRecommendation;
+
+impl ExplainRecommendation {
+    /// Reasons for one item, strongest first
+    ///
+    /// Evidence tied to a candidate source (similar titles to content, graph
+    /// and session sources; similar users to collaborative and graph sources;
+    /// genre and mood to content and context sources) is discounted when
+    /// that source did not propose the item.
+    pub fn execute(
+        item: &ScoredContent,
+        attribution: &[SourceAttribution],
+        attributes: Option<&ContentAttributes>,
+        profile: &UserProfile,
+        evidence: &ExplanationEvidence,
+        config: &ExplanationConfig,
+        now: DateTime<Utc>,
+    ) -> Vec<RankedReason> {
+        let proposed_by = |sources: &[RecommendationType]| {
+            let attributed = attribution.iter().any(|a| sources.contains(&a.source))
+                || sources.contains(&item.source);
+            if attributed {
+                1.0
+            } else {
+                UNATTRIBUTED_WEIGHT
+            }
+        };
+        let mut reasons = Vec::new();
+
+        if let Some(anchor) = &evidence.watched_anchor {
+            reasons.push(RankedReason {
+                reason: ExplanationReason::BecauseYouWatched {
+                    content_id: anchor.content_id,
+                    title: anchor.title.clone(),
+                },
+                strength: anchor.similarity.max(0.0)
+                    * proposed_by(&[
+                        RecommendationType::ContentBased,
+                        RecommendationType::GraphBased,
+                        RecommendationType::SessionBased,
+                    ]),
+            });
+        }
+
+        if let Some((region, rank)) = &evidence.regional_rank {
+            if *rank >= 1 && *rank <= config.regional_top_n {
+                reasons.push(RankedReason {
+                    reason: ExplanationReason::PopularInRegion {
+                        region: region.clone(),
+                    },
+                    strength: 1.0 - (*rank - 1) as f32 / config.regional_top_n as f32,
+                });
+            }
+        }
+
+        if let Some(views) = evidence.similar_user_views.filter(|&v| v > 0) {
+            let saturation = config.similar_users_saturation.max(1) as f32;
+            reasons.push(RankedReason {
+                reason: ExplanationReason::TrendingWithSimilarUsers {
+                    similar_users: views,
+                },
+                strength: (views as f32 / saturation).min(1.0)
+                    * proposed_by(&[
+                        RecommendationType::Collaborative,
+                        RecommendationType::GraphBased,
+                    ]),
+            });
+        }
+
+        if let Some(creator) = &evidence.creator {
+            let age_days = (now - creator.released_at).num_days();
+            if (0..=config.new_release_days).contains(&age_days) {
+                reasons.push(RankedReason {
+                    reason: ExplanationReason::NewFromCreator {
+                        name: creator.name.clone(),
+                        role: creator.role,
+                    },
+                    strength: creator.affinity.clamp(0.0, 1.0),
+                });
+            }
+        }
+
+        if let Some(attributes) = attributes {
+            if let Some(until) = attributes.available_until {
+                let days_left = (until - now).num_days();
+                if until > now && days_left <= config.leaving_soon_days {
+                    reasons.push(RankedReason {
+                        reason: ExplanationReason::LeavingSoon {
+                            platform: attributes.platforms.first().cloned(),
+                            days_left,
+                        },
+                        strength: 1.0 - days_left as f32 / (config.leaving_soon_days + 1) as f32,
+                    });
+                }
+            }
+
+            let best_genre = attributes
+                .genres
+                .iter()
+                .filter_map(|g| profile.genre_affinities.get(g).map(|&a| (g, a)))
+                .max_by(|a, b| a.1.total_cmp(&b.1));
+            if let Some((genre, affinity)) = best_genre {
+                if affinity > NEUTRAL_AFFINITY {
+                    reasons.push(RankedReason {
+                        reason: ExplanationReason::MatchesGenre {
+                            genre: genre.clone(),
+                        },
+                        strength: (affinity - NEUTRAL_AFFINITY) / (1.0 - NEUTRAL_AFFINITY)
+                            * proposed_by(&[
+                                RecommendationType::ContentBased,
+                                RecommendationType::ContextAware,
+                            ]),
+                    });
+                }
+            }
+        }
+
+        // The context source tags mood matches as "mood:<mood>"
+        if let Some(mood) = item
+            .based_on
+            .iter()
+            .find_map(|reason| reason.strip_prefix("mood:"))
+        {
+            reasons.push(RankedReason {
+                reason: ExplanationReason::MatchesMood {
+                    mood: mood.to_string(),
+                },
+                strength: 0.5 * proposed_by(&[RecommendationType::ContextAware]),
+            });
+        }
+
+        reasons.retain(|r| r.strength >= config.min_strength);
+        reasons.sort_by(|a, b| b.strength.total_cmp(&a.strength));
+        reasons.truncate(config.max_reasons);
+        reasons
+    }
+}
+
+/// Explanation templates per locale
+///
+/// Templates use `{placeholder}` fields named after the reason's data:
+/// `title`, `region`, `count`, `name`, `role`, `platform`, `days`, `genre`
+/// and `mood`. The `recommended_for_you` key covers items without reasons.
+#[derive(Debug, Clone)]
+pub struct ExplanationTemplates {
+    locales: HashMap<String, HashMap<String, String>>,
+}
+
+impl Default for ExplanationTemplates {
+    fn default() -> Self {
+        let en = [
+            ("because_you_watched", "Because you watched {title}"),
+            ("popular_in_region", "Popular in {region}"),
+            (
+                "trending_with_similar_users",
+                "Trending with {count} viewers like you",
+            ),
+            ("new_from_creator", "New from {role} {name}"),
+            ("leaving_platform_soon", "Leaving {platform} in {days} days"),
+            ("leaving_soon", "Leaving in {days} days"),
+            ("matches_genre", "Because you like {genre}"),
+            ("matches_mood", "Fits your {mood} mood"),
+            ("recommended_for_you", "Recommended for you"),
+        ];
+        let es = [
+            ("because_you_watched", "Porque viste {title}"),
+            ("popular_in_region", "Popular en {region}"),
+            (
+                "trending_with_similar_users",
+                "Tendencia entre {count} espectadores como tú",
+            ),
+            ("new_from_creator", "Lo nuevo de {name}"),
+            ("leaving_platform_soon", "Sale de {platform} en {days} días"),
+            ("leaving_soon", "Disponible solo {days} días más"),
+            ("matches_genre", "Porque te gusta {genre}"),
+            ("matches_mood", "Encaja con tu estado de ánimo: {mood}"),
+            ("recommended_for_you", "Recomendado para ti"),
+        ];
+
+        let mut templates = Self {
+            locales: HashMap::new(),
+        };
+        for (locale, entries) in [("en", &en[..]), ("es", &es[..])] {
+            templates = templates.with_locale(
+                locale,
+                entries.iter().map(|(k, v)| (k.to_string(), v.to_string())),
+            );
+        }
+        templates
+    }
+}
+
+impl ExplanationTemplates {
+    /// Add or extend a locale's templates
+    pub fn with_locale(
+        mut self,
+        locale: &str,
+        templates: impl IntoIterator<Item = (String, String)>,
+    ) -> Self {
+        self.locales
+            .entry(locale.to_lowercase())
+            .or_default()
+            .extend(templates);
+        self
+    }
+
+    /// Template for a key: exact locale, then its language, then English
+    fn template(&self, locale: &str, key: &str) -> Option<&str> {
+        let locale = locale.to_lowercase().replace('_', "-");
+        let language = locale.split('-').next().unwrap_or(DEFAULT_LOCALE);
+        let template = [locale.as_str(), language, DEFAULT_LOCALE]
+            .into_iter()
+            .find_map(|l| self.locales.get(l)?.get(key));
+        template.map(String::as_str)
+    }
+
+    pub fn render(&self, reason: &ExplanationReason, locale: &str) -> String {
+        let Some(template) = self.template(locale, reason.kind()) else {
+            return reason.kind().replace('_', " ");
+        };
+        reason
+            .params()
+            .into_iter()
+            .fold(template.to_string(), |text, (name, value)| {
+                text.replace(&format!("{{{}}}", name), &value)
+            })
+    }
+
+    /// Text for the strongest reason, or the generic fallback
+    pub fn headline(&self, reasons: &[RankedReason], locale: &str) -> String {
+        match reasons.first() {
+            Some(top) => self.render(&top.reason, locale),
+            None => self
+                .template(locale, "recommended_for_you")
+                .unwrap_or("Recommended for you")
+                .to_string(),
+        }
+    }
+}
+
+/// Explanation stored when a recommendation is served
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredExplanation {
+    pub user_id: Uuid,
+    pub content_id: Uuid,
+    pub reasons: Vec<RankedReason>,
+    /// Candidate sources that proposed the item
+    pub sources: Vec<SourceAttribution>,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// Served explanation storage
+#[async_trait::async_trait]
+pub trait ExplanationRepository: Send + Sync {
+    /// Store explanations, replacing earlier ones for the same items
+    async fn save(&self, explanations: &[StoredExplanation]) -> Result<()>;
+
+    /// Latest explanation of an item served to a user
+    async fn get(&self, user_id: Uuid, content_id: Uuid) -> Result<Option<StoredExplanation>>;
+
+    /// Titles the user recently watched most of, newest first, as anchor
+    /// candidates
+    async fn watched_titles(&self, user_id: Uuid, limit: usize) -> Result<Vec<(Uuid, String)>>;
+
+    /// Regional chart positions, views by similar users and creators the
+    /// user follows for items about to be explained; items without any
+    /// evidence are left out
+    async fn load_evidence(
+        &self,
+        user_id: Uuid,
+        content_ids: &[Uuid],
+        region: Option<&str>,
+        config: &ExplanationConfig,
+        now: DateTime<Utc>,
+    ) -> Result<HashMap<Uuid, ExplanationEvidence>>;
+}
+
+/// Completion at which a viewing counts as watched for evidence
+const WATCHED_COMPLETION: f64 = 0.5;
+/// Shared titles that make another viewer a similar user
+const MIN_SHARED_TITLES: i64 = 2;
+/// Similar users considered per request
+const MAX_SIMILAR_USERS: i64 = 200;
+
+/// PostgreSQL implementation of ExplanationRepository
+pub struct PostgresExplanationRepository {
+    pool: PgPool,
+}
+
+impl PostgresExplanationRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl ExplanationRepository for PostgresExplanationRepository {
+    async fn save(&self, explanations: &[StoredExplanation]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        for explanation in explanations {
+            sqlx::query(
+                r#"
+                INSERT INTO recommendation_explanations
+                    (user_id, content_id, reasons, sources, generated_at)
+                VALUES ($1, $2, $3, $4, $5)
+                ON CONFLICT (user_id, content_id) DO UPDATE
+                SET reasons = EXCLUDED.reasons,
+                    sources = EXCLUDED.sources,
+                    generated_at = EXCLUDED.generated_at
+                "#,
+            )
+            .bind(explanation.user_id)
+            .bind(explanation.content_id)
+            .bind(Json(&explanation.reasons))
+            .bind(Json(&explanation.sources))
+            .bind(explanation.generated_at)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to store recommendation explanation")?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn get(&self, user_id: Uuid, content_id: Uuid) -> Result<Option<StoredExplanation>> {
+        let row = sqlx::query(
+            r#"
+            SELECT reasons, sources, generated_at
+            FROM recommendation_explanations
+            WHERE user_id = $1 AND content_id = $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(content_id)
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to fetch recommendation explanation")?;
+
+        Ok(row.map(|row| {
+            let Json(reasons) = row.get("reasons");
+            let Json(sources) = row.get("sources");
+            StoredExplanation {
+                user_id,
+                content_id,
+                reasons,
+                sources,
+                generated_at: row.get("generated_at"),
+            }
+        }))
+    }
+
+    async fn watched_titles(&self, user_id: Uuid, limit: usize) -> Result<Vec<(Uuid, String)>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT c.id, c.title
+            FROM (
+                SELECT content_id, MAX(timestamp) AS watched_at
+                FROM viewing_events
+                WHERE user_id = $1 AND completion_rate >= $2
+                GROUP BY content_id
+            ) w
+            JOIN content c ON c.id = w.content_id
+            ORDER BY w.watched_at DESC
+            LIMIT $3
+            "#,
+        )
+        .bind(user_id)
+        .bind(WATCHED_COMPLETION)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to load watched titles")?;
+
+        Ok(rows
+            .iter()
+            .map(|row| (row.get("id"), row.get("title")))
+            .collect())
+    }
+
+    async fn load_evidence(
+        &self,
+        user_id: Uuid,
+        content_ids: &[Uuid],
+        region: Option<&str>,
+        config: &ExplanationConfig,
+        now: DateTime<Utc>,
+    ) -> Result<HashMap<Uuid, ExplanationEvidence>> {
+        let mut evidence: HashMap<Uuid, ExplanationEvidence> = HashMap::new();
+        if content_ids.is_empty() {
+            return Ok(evidence);
+        }
+
+        // Position in the region's popularity chart of available titles
+        if let Some(region) = region {
+            let rows = sqlx::query(
+                r#"
+                SELECT id, rank
+                FROM (
+                    SELECT c.id,
+                           ROW_NUMBER() OVER (ORDER BY c.popularity_score DESC) AS rank
+                    FROM content c
+                    WHERE EXISTS (
+                        SELECT 1 FROM platform_availability pa
+                        WHERE pa.content_id = c.id
+                          AND pa.region = $2
+                          AND pa.available_from <= $4
+                          AND (pa.expires_at IS NULL OR pa.expires_at > $4)
+                    )
+                ) chart
+                WHERE id = ANY($1) AND rank <= $3
+                "#,
+            )
+            .bind(content_ids)
+            .bind(region)
+            .bind(config.regional_top_n as i64)
+            .bind(now)
+            .fetch_all(&self.pool)
+            .await
+            .context("Failed to load regional chart positions")?;
+
+            for row in rows {
+                let rank: i64 = row.get("rank");
+                evidence.entry(row.get("id")).or_default().regional_rank =
+                    Some((region.to_string(), rank as usize));
+            }
+        }
+
+        // Recent views by users who watched the same titles
+        let rows = sqlx::query(
+            r#"
+            WITH mine AS (
+                SELECT DISTINCT content_id
+                FROM viewing_events
+                WHERE user_id = $1 AND completion_rate >= $3
+            ),
+            similar AS (
+                SELECT ve.user_id
+                FROM viewing_events ve
+                JOIN mine ON mine.content_id = ve.content_id
+                WHERE ve.user_id <> $1 AND ve.completion_rate >= $3
+                GROUP BY ve.user_id
+                HAVING COUNT(DISTINCT ve.content_id) >= $4
+                ORDER BY COUNT(DISTINCT ve.content_id) DESC
+                LIMIT $5
+            )
+            SELECT ve.content_id, COUNT(DISTINCT ve.user_id) AS views
+            FROM viewing_events ve
+            JOIN similar ON similar.user_id = ve.user_id
+            WHERE ve.content_id = ANY($2) AND ve.timestamp >= $6
+            GROUP BY ve.content_id
+            "#,
+        )
+        .bind(user_id)
+        .bind(content_ids)
+        .bind(WATCHED_COMPLETION)
+        .bind(MIN_SHARED_TITLES)
+        .bind(MAX_SIMILAR_USERS)
+        .bind(now - Duration::days(config.similar_users_window_days))
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to load similar-user views")?;
+
+        for row in rows {
+            let views: i64 = row.get("views");
+            evidence
+                .entry(row.get("content_id"))
+                .or_default()
+                .similar_user_views = Some(views as usize);
+        }
+
+        // The credited creator the user has watched most titles by
+        let rows = sqlx::query(
+            r#"
+            WITH watched AS (
+                SELECT DISTINCT content_id
+                FROM viewing_events
+                WHERE user_id = $1 AND completion_rate >= $3
+            ),
+            followed AS (
+                SELECT cr.person_name, cr.role_type, COUNT(DISTINCT cr.content_id) AS titles
+                FROM credits cr
+                JOIN watched w ON w.content_id = cr.content_id
+                WHERE cr.role_type IN ('director', 'writer', 'actor')
+                GROUP BY cr.person_name, cr.role_type
+            )
+            SELECT DISTINCT ON (cr.content_id)
+                   cr.content_id, cr.person_name, cr.role_type, f.titles,
+                   c.release_date::timestamptz AS released_at
+            FROM credits cr
+            JOIN followed f ON f.person_name = cr.person_name AND f.role_type = cr.role_type
+            JOIN content c ON c.id = cr.content_id
+            WHERE cr.content_id = ANY($2) AND c.release_date IS NOT NULL
+            ORDER BY cr.content_id, f.titles DESC
+            "#,
+        )
+        .bind(user_id)
+        .bind(content_ids)
+        .bind(WATCHED_COMPLETION)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to load followed creators")?;
+
+        let saturation = config.creator_saturation.max(1) as f32;
+        for row in rows {
+            let role = match row.get::<String, _>("role_type").as_str() {
+                "director" => CreatorRole::Director,
+                "writer" => CreatorRole::Writer,
+                _ => CreatorRole::Actor,
+            };
+            let titles: i64 = row.get("titles");
+            evidence.entry(row.get("content_id")).or_default().creator = Some(CreatorMatch {
+                name: row.get("person_name"),
+                role,
+                affinity: (titles as f32 / saturation).min(1.0),
+                released_at: row.get("released_at"),
+            });
+        }
+
+        Ok(evidence)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(source: RecommendationType, based_on: &[&str]) -> ScoredContent {
+        ScoredContent {
+            content_id: Uuid::new_v4(),
+            score: 0.8,
+            source,
+            based_on: based_on.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_reasons_ranked_by_strength() {
+        let now = Utc::now();
+        let mut profile = UserProfile::new(Uuid::new_v4());
+        profile.genre_affinities.insert("thriller".to_string(), 0.7);
+        let attributes = ContentAttributes {
+            genres: vec!["thriller".to_string()],
+            platforms: vec!["netflix".to_string()],
+            available_until: Some(now + Duration::days(3) + Duration::hours(1)),
+            ..Default::default()
+        };
+        let evidence = ExplanationEvidence {
+            watched_anchor: Some(WatchedAnchor {
+                content_id: Uuid::new_v4(),
+                title: "Heat".to_string(),
+                similarity: 0.9,
+            }),
+            regional_rank: Some(("Ohio".to_string(), 80)),
+            ..Default::default()
+        };
+
+        let reasons = ExplainRecommendation::execute(
+            &item(RecommendationType::ContentBased, &[]),
+            &[],
+            Some(&attributes),
+            &profile,
+            &evidence,
+            &ExplanationConfig::default(),
+            now,
+        );
+
+        let kinds: Vec<&str> = reasons.iter().map(|r| r.reason.kind()).collect();
+        // Rank 80 is outside the regional chart
+        assert_eq!(
+            kinds,
+            vec![
+                "because_you_watched",
+                "leaving_platform_soon",
+                "matches_genre"
+            ]
+        );
+        assert!((reasons[0].strength - 0.9).abs() < 1e-6);
+        assert_eq!(
+            reasons[1].reason,
+            ExplanationReason::LeavingSoon {
+                platform: Some("netflix".to_string()),
+                days_left: 3
+            }
+        );
+    }
+
+    #[test]
+    fn test_unattributed_evidence_is_discounted() {
+        let profile = UserProfile::new(Uuid::new_v4());
+        let evidence = ExplanationEvidence {
+            similar_user_views: Some(40),
+            ..Default::default()
+        };
+        let explain = |item: &ScoredContent, attribution: &[SourceAttribution]| {
+            ExplainRecommendation::execute(
+                item,
+                attribution,
+                None,
+                &profile,
+                &evidence,
+                &ExplanationConfig::default(),
+                Utc::now(),
+            )[0]
+            .strength
+        };
+
+        let context_item = item(RecommendationType::ContextAware, &[]);
+        assert!((explain(&context_item, &[]) - UNATTRIBUTED_WEIGHT).abs() < 1e-6);
+        let attribution = [SourceAttribution {
+            source: RecommendationType::Collaborative,
+            score: 0.4,
+            rank: 2,
+        }];
+        assert_eq!(explain(&context_item, &attribution), 1.0);
+    }
+
+    #[test]
+    fn test_mood_and_new_release_reasons() {
+        let now = Utc::now();
+        let profile = UserProfile::new(Uuid::new_v4());
+        let creator = |age_days: i64| ExplanationEvidence {
+            creator: Some(CreatorMatch {
+                name: "Denis Villeneuve".to_string(),
+                role: CreatorRole::Director,
+                affinity: 0.8,
+                released_at: now - Duration::days(age_days),
+            }),
+            ..Default::default()
+        };
+        let explain = |evidence: &ExplanationEvidence| {
+            ExplainRecommendation::execute(
+                &item(RecommendationType::ContextAware, &["mood:relaxed"]),
+                &[],
+                None,
+                &profile,
+                evidence,
+                &ExplanationConfig::default(),
+                now,
+            )
+        };
+
+        let reasons = explain(&creator(10));
+        assert_eq!(reasons[0].reason.kind(), "new_from_creator");
+        assert_eq!(
+            reasons[1].reason,
+            ExplanationReason::MatchesMood {
+                mood: "relaxed".to_string()
+            }
+        );
+        assert_eq!(explain(&creator(400)).len(), 1);
+    }
+
+    #[test]
+    fn test_closest_watched_title_anchors() {
+        let heat = Uuid::new_v4();
+        let watched = vec![
+            (heat, "Heat".to_string(), vec![1.0, 0.1, 0.0]),
+            (Uuid::new_v4(), "Up".to_string(), vec![0.0, 1.0, 0.0]),
+            (Uuid::new_v4(), "Solaris".to_string(), vec![-1.0, 0.0, 0.0]),
+        ];
+
+        let anchor = WatchedAnchor::closest(&[0.9, 0.0, 0.1], &watched).unwrap();
+        assert_eq!(anchor.content_id, heat);
+        assert_eq!(anchor.title, "Heat");
+        assert!(anchor.similarity > 0.9);
+
+        assert!(WatchedAnchor::closest(&[0.0, 0.0, 1.0], &watched).is_none());
+        assert!(WatchedAnchor::closest(&[1.0, 0.0, 0.0], &[]).is_none());
+    }
+
+    #[test]
+    fn test_templates_fall_back_by_locale() {
+        let templates = ExplanationTemplates::default().with_locale(
+            "pt-BR",
+            [(
+                "because_you_watched".to_string(),
+                "Porque você assistiu {title}".to_string(),
+            )],
+        );
+        let watched = ExplanationReason::BecauseYouWatched {
+            content_id: Uuid::new_v4(),
+            title: "Dark".to_string(),
+        };
+        let region = ExplanationReason::PopularInRegion {
+            region: "Texas".to_string(),
+        };
+
+        assert_eq!(
+            templates.render(&watched, "pt_BR"),
+            "Porque você assistiu Dark"
+        );
+        assert_eq!(templates.render(&watched, "es-MX"), "Porque viste Dark");
+        // No Portuguese template for regional popularity
+        assert_eq!(templates.render(&region, "pt-BR"), "Popular in Texas");
+        assert_eq!(
+            templates.render(
+                &ExplanationReason::NewFromCreator {
+                    name: "Greta Gerwig".to_string(),
+                    role: CreatorRole::Director,
+                },
+                "en-US"
+            ),
+            "New from director Greta Gerwig"
+        );
+        assert_eq!(templates.headline(&[], "es"), "Recomendado para ti");
+    }
+}
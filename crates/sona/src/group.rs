@@ -0,0 +1,486 @@
+//! Group Recommendations
+//!
+//! Recommends content for several people watching together (the
+//! `viewing_with` household case). Candidates are retrieved for every
+//! member, scored against each member's preference vector and LoRA adapter,
+//! filtered by the strictest parental limit in the group, and combined with a
+//! selectable aggregation strategy. Each pick lists the members it satisfies.
+
+use crate::candidates::{retrieve_candidates, CandidateSources, RetrievalConfig};
+use crate::content_based::{prefetch_embeddings, ContentEmbeddingStore};
+use crate::diversity::{ContentAttributes, ContentCatalog};
+use crate::lora::{compute_lora_score, UserLoRAAdapter};
+use crate::profile::UserProfile;
+use crate::recommendation::LORA_WEIGHT;
+use crate::rerank::{rating_allowed, strictest_rating};
+use crate::types::{
+    Recommendation, RecommendationContext, RecommendationType, ScoredContent, SourceAttribution,
+};
+use anyhow::Result;
+use chrono::Utc;
+use media_gateway_core::math::cosine_similarity;
+use media_gateway_core::types::MaturityRating;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::warn;
+use uuid::Uuid;
+
+/// How member scores combine into a group score
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupAggregation {
+    /// Mean member score
+    #[default]
+    Average,
+    /// Lowest member score: nobody should dislike the pick
+    LeastMisery,
+    /// Highest member score: someone should love the pick
+    MostPleasure,
+    /// Sequential picks weighted toward members the list has served least so far
+    Fair,
+}
+
+/// Group recommendation settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GroupConfig {
+    #[serde(default)]
+    pub aggregation: GroupAggregation,
+    /// Member score at which a pick counts as satisfying them (default: 0.6)
+    #[serde(default = "default_satisfaction_threshold")]
+    pub satisfaction_threshold: f32,
+    /// Recommendations returned (default: 20)
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+}
+
+fn default_satisfaction_threshold() -> f32 {
+    0.6
+}
+
+fn default_limit() -> usize {
+    20
+}
+
+impl Default for GroupConfig {
+    fn default() -> Self {
+        Self {
+            aggregation: GroupAggregation::default(),
+            satisfaction_threshold: default_satisfaction_threshold(),
+            limit: default_limit(),
+        }
+    }
+}
+
+/// One viewer in the group
+#[derive(Clone, Copy)]
+pub struct GroupMember<'a> {
+    pub profile: &'a UserProfile,
+    pub lora_adapter: Option<&'a UserLoRAAdapter>,
+    /// Name used in explanations; the user ID is used when absent
+    pub display_name: Option<&'a str>,
+    /// The member's parental limit, if any
+    pub max_maturity_rating: Option<MaturityRating>,
+}
+
+impl GroupMember<'_> {
+    fn label(&self) -> String {
+        self.display_name
+            .map(str::to_string)
+            .unwrap_or_else(|| self.profile.user_id.to_string())
+    }
+
+    /// Preference affinity in [0, 1], boosted by the member's LoRA adapter
+    fn score(&self, content_embedding: &[f32]) -> Result<f32> {
+        let mut score =
+            (cosine_similarity(&self.profile.preference_vector, content_embedding) + 1.0) / 2.0;
+        if let Some(adapter) = self.lora_adapter {
+            let lora_score =
+                compute_lora_score(adapter, content_embedding, &self.profile.preference_vector)?;
+            score *= 1.0 + lora_score * LORA_WEIGHT;
+        }
+        Ok(score)
+    }
+}
+
+/// A group pick with per-member detail
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupRecommendation {
+    #[serde(flatten)]
+    pub recommendation: Recommendation,
+    pub member_scores: HashMap<Uuid, f32>,
+    /// Members scoring the pick at or above the satisfaction threshold
+    pub satisfied_members: Vec<Uuid>,
+}
+
+/// Generate recommendations for a group of viewers
+pub struct GenerateGroupRecommendations;
+
+impl GenerateGroupRecommendations {
+    #[allow(clippy::too_many_arguments)]
+    pub async fn execute(
+        members: &[GroupMember<'_>],
+        context: Option<RecommendationContext>,
+        config: &GroupConfig,
+        get_content_embedding: impl Fn(Uuid) -> Result<Vec<f32>>,
+        get_content_attributes: impl Fn(Uuid) -> Option<ContentAttributes>,
+        sources: CandidateSources<'_>,
+        retrieval_config: &RetrievalConfig,
+        catalog: Option<&dyn ContentCatalog>,
+        embeddings: Option<&dyn ContentEmbeddingStore>,
+    ) -> Result<Vec<GroupRecommendation>> {
+        anyhow::ensure!(!members.is_empty(), "Group must have at least one member");
+
+        // Union of every member's candidates, keeping the best upstream score
+        let mut pool: HashMap<Uuid, ScoredContent> = HashMap::new();
+        let mut attribution: HashMap<Uuid, Vec<SourceAttribution>> = HashMap::new();
+        for member in members {
+            let retrieval = retrieve_candidates(
+                member.profile.user_id,
+                member.profile,
+                context.as_ref(),
+                sources,
+                retrieval_config,
+            )
+            .await;
+            for candidate in retrieval.candidates {
+                match pool.get(&candidate.content_id) {
+                    Some(existing) if existing.score >= candidate.score => {}
+                    _ => {
+                        pool.insert(candidate.content_id, candidate);
+                    }
+                }
+            }
+            for (content_id, sources) in retrieval.attribution {
+                attribution.entry(content_id).or_default().extend(sources);
+            }
+        }
+
+        // Catalog attributes of the pool, looked up once; `get_content_attributes`
+        // covers titles the catalog does not know
+        let catalog_attributes = match catalog {
+            Some(catalog) => {
+                let ids: Vec<Uuid> = pool.keys().copied().collect();
+                catalog.content_attributes(&ids).await.unwrap_or_else(|e| {
+                    warn!("Failed to load content attributes: {}", e);
+                    HashMap::new()
+                })
+            }
+            None => HashMap::new(),
+        };
+        let get_content_attributes = |content_id: Uuid| {
+            catalog_attributes
+                .get(&content_id)
+                .cloned()
+                .or_else(|| get_content_attributes(content_id))
+        };
+
+        // Content must be allowed for every member
+        let limit = strictest_rating(
+            members
+                .iter()
+                .map(|m| m.max_maturity_rating)
+                .chain([context.as_ref().and_then(|c| c.max_maturity_rating)]),
+        );
+        let mut candidates: Vec<ScoredContent> = pool
+            .into_values()
+            .filter(|c| {
+                limit.is_none()
+                    || rating_allowed(
+                        get_content_attributes(c.content_id).and_then(|a| a.maturity_rating),
+                        limit,
+                    )
+            })
+            .collect();
+        candidates.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.content_id.cmp(&b.content_id))
+        });
+
+        let ids: Vec<Uuid> = candidates.iter().map(|c| c.content_id).collect();
+        let mut stored_embeddings = prefetch_embeddings(embeddings, &ids).await;
+        let mut scores = Vec::with_capacity(candidates.len());
+        for candidate in &candidates {
+            let embedding = match stored_embeddings.remove(&candidate.content_id) {
+                Some(embedding) => embedding,
+                None => get_content_embedding(candidate.content_id)?,
+            };
+            scores.push(
+                members
+                    .iter()
+                    .map(|m| m.score(&embedding))
+                    .collect::<Result<Vec<f32>>>()?,
+            );
+        }
+
+        let picks = Self::select(&scores, config.aggregation, config.limit);
+
+        let mut recommendations = Vec::with_capacity(picks.len());
+        for (index, group_score) in picks {
+            let candidate = &candidates[index];
+            let member_scores = &scores[index];
+            let satisfied: Vec<&GroupMember<'_>> = members
+                .iter()
+                .zip(member_scores)
+                .filter(|(_, s)| **s >= config.satisfaction_threshold)
+                .map(|(m, _)| m)
+                .collect();
+
+            recommendations.push(GroupRecommendation {
+                recommendation: Recommendation {
+                    content_id: candidate.content_id,
+                    confidence_score: group_score,
+                    recommendation_type: RecommendationType::Hybrid,
+                    sources: attribution
+                        .remove(&candidate.content_id)
+                        .unwrap_or_default(),
+                    based_on: candidate.based_on.clone(),
+                    explanation: Self::generate_explanation(&satisfied, members.len()),
+                    generated_at: Utc::now(),
+                    ttl_seconds: 3600,
+                    experiment_variant: None,
+                    reasons: Vec::new(),
+                },
+                member_scores: members
+                    .iter()
+                    .map(|m| m.profile.user_id)
+                    .zip(member_scores.iter().copied())
+                    .collect(),
+                satisfied_members: satisfied.iter().map(|m| m.profile.user_id).collect(),
+            });
+        }
+
+        Ok(recommendations)
+    }
+
+    /// Pick up to `limit` candidates from a candidates × members score matrix,
+    /// returning candidate indices with their group scores
+    pub fn select(
+        scores: &[Vec<f32>],
+        aggregation: GroupAggregation,
+        limit: usize,
+    ) -> Vec<(usize, f32)> {
+        let aggregate = |member_scores: &[f32]| -> f32 {
+            match aggregation {
+                GroupAggregation::Average | GroupAggregation::Fair => {
+                    member_scores.iter().sum::<f32>() / member_scores.len().max(1) as f32
+                }
+                GroupAggregation::LeastMisery => {
+                    member_scores.iter().copied().fold(f32::INFINITY, f32::min)
+                }
+                GroupAggregation::MostPleasure => member_scores
+                    .iter()
+                    .copied()
+                    .fold(f32::NEG_INFINITY, f32::max),
+            }
+        };
+
+        if aggregation != GroupAggregation::Fair {
+            let mut ranked: Vec<(usize, f32)> = scores
+                .iter()
+                .enumerate()
+                .map(|(i, s)| (i, aggregate(s)))
+                .collect();
+            // Stable sort keeps upstream order among ties
+            ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+            ranked.truncate(limit);
+            return ranked;
+        }
+
+        // Weight each member by how little the list has given them so far
+        let members = scores.first().map_or(0, Vec::len);
+        let mut satisfaction = vec![0.0f32; members];
+        let mut remaining: Vec<usize> = (0..scores.len()).collect();
+        let mut picks = Vec::with_capacity(limit.min(scores.len()));
+
+        while picks.len() < limit && !remaining.is_empty() {
+            let weights: Vec<f32> = satisfaction.iter().map(|s| 1.0 / (1.0 + s)).collect();
+            let total: f32 = weights.iter().sum();
+
+            let (position, score) = remaining
+                .iter()
+                .enumerate()
+                .map(|(position, &i)| {
+                    let weighted: f32 = scores[i].iter().zip(&weights).map(|(s, w)| s * w).sum();
+                    (position, weighted / total)
+                })
+                .fold((0, f32::NEG_INFINITY), |best, current| {
+                    if current.1 > best.1 {
+                        current
+                    } else {
+                        best
+                    }
+                });
+
+            let index = remaining.remove(position);
+            for (total, score) in satisfaction.iter_mut().zip(&scores[index]) {
+                *total += score;
+            }
+            picks.push((index, score));
+        }
+
+        picks
+    }
+
+    fn generate_explanation(satisfied: &[&GroupMember<'_>], group_size: usize) -> String {
+        match satisfied.len() {
+            0 => "Best compromise for the group".to_string(),
+            n if n == group_size && n > 1 => "Good pick for everyone".to_string(),
+            _ => {
+                let mut names: Vec<String> = satisfied.iter().map(|m| m.label()).collect();
+                let last = names.pop().unwrap_or_default();
+                if names.is_empty() {
+                    format!("Good pick for {}", last)
+                } else {
+                    format!("Good pick for {} and {}", names.join(", "), last)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Rows are candidates, columns are members
+    fn matrix() -> Vec<Vec<f32>> {
+        vec![
+            vec![0.95, 0.1], // loved by the first member only
+            vec![0.6, 0.6],  // acceptable to both
+            vec![0.2, 0.9],  // loved by the second member only
+            vec![0.5, 0.4],
+        ]
+    }
+
+    fn order(picks: &[(usize, f32)]) -> Vec<usize> {
+        picks.iter().map(|(i, _)| *i).collect()
+    }
+
+    #[test]
+    fn test_aggregation_strategies() {
+        let scores = matrix();
+        assert_eq!(
+            order(&GenerateGroupRecommendations::select(
+                &scores,
+                GroupAggregation::Average,
+                4
+            )),
+            vec![1, 2, 0, 3]
+        );
+        assert_eq!(
+            order(&GenerateGroupRecommendations::select(
+                &scores,
+                GroupAggregation::LeastMisery,
+                2
+            )),
+            vec![1, 3]
+        );
+        assert_eq!(
+            order(&GenerateGroupRecommendations::select(
+                &scores,
+                GroupAggregation::MostPleasure,
+                2
+            )),
+            vec![0, 2]
+        );
+    }
+
+    #[test]
+    fn test_fair_aggregation_alternates_members() {
+        let scores = vec![
+            vec![0.9, 0.3],
+            vec![0.85, 0.3],
+            vec![0.8, 0.3],
+            vec![0.3, 0.8],
+        ];
+        // Averages favour the first member's items; fairness serves the
+        // second member once the first has been served
+        assert_eq!(
+            order(&GenerateGroupRecommendations::select(
+                &scores,
+                GroupAggregation::Average,
+                2
+            )),
+            vec![0, 1]
+        );
+        assert_eq!(
+            order(&GenerateGroupRecommendations::select(
+                &scores,
+                GroupAggregation::Fair,
+                2
+            )),
+            vec![0, 3]
+        );
+    }
+
+    #[test]
+    fn test_explanation_names_satisfied_members() {
+        let profiles: Vec<UserProfile> = (0..3).map(|_| UserProfile::new(Uuid::new_v4())).collect();
+        let names = ["Alex", "Sam", "Kim"];
+        let members: Vec<GroupMember<'_>> = profiles
+            .iter()
+            .zip(names)
+            .map(|(profile, name)| GroupMember {
+                profile,
+                lora_adapter: None,
+                display_name: Some(name),
+                max_maturity_rating: None,
+            })
+            .collect();
+        let refs: Vec<&GroupMember<'_>> = members.iter().collect();
+
+        assert_eq!(
+            GenerateGroupRecommendations::generate_explanation(&refs, 3),
+            "Good pick for everyone"
+        );
+        assert_eq!(
+            GenerateGroupRecommendations::generate_explanation(&refs[..2], 3),
+            "Good pick for Alex and Sam"
+        );
+        assert_eq!(
+            GenerateGroupRecommendations::generate_explanation(&refs[2..], 3),
+            "Good pick for Kim"
+        );
+        assert_eq!(
+            GenerateGroupRecommendations::generate_explanation(&[], 3),
+            "Best compromise for the group"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execute_requires_members() {
+        let result = GenerateGroupRecommendations::execute(
+            &[],
+            None,
+            &GroupConfig::default(),
+            |_| Ok(vec![0.0; 4]),
+            |_| None,
+            CandidateSources::default(),
+            &RetrievalConfig::default(),
+            None,
+            None,
+        )
+        .await;
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_viewing_with_user_ids() {
+        let member = Uuid::new_v4();
+        let context = RecommendationContext {
+            mood: None,
+            time_of_day: None,
+            device_type: None,
+            viewing_with: Some(vec![member.to_string(), "kids".to_string()]),
+            diversity: None,
+            rerank: None,
+            subscribed_platforms: vec![],
+            max_maturity_rating: None,
+            locale: None,
+            region: None,
+            utc_offset_minutes: None,
+        };
+        assert_eq!(context.viewing_with_user_ids(), vec![member]);
+    }
+}
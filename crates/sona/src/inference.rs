@@ -4,7 +4,7 @@
 //! Replaces dummy vec![0.0; 512] vectors with actual model inference.
 
 use anyhow::{anyhow, Result};
-use ndarray::{Array2, Axis};
+use ndarray::{Array2, Array3, Axis};
 use ort::{
     session::{builder::GraphOptimizationLevel, Session},
     value::Tensor,
@@ -248,6 +248,64 @@ impl ONNXInference {
         Ok(embeddings)
     }
 
+    /// Predict the next item embedding from a session's item embeddings
+    ///
+    /// For sequence models (GRU/transformer) exported with an
+    /// `item_embeddings` input of shape [1, seq_len, embedding_dim] and a
+    /// `next_embedding` output of shape [1, embedding_dim].
+    pub async fn predict_next_embedding(&self, sequence: &[Vec<f32>]) -> Result<Vec<f32>> {
+        if sequence.is_empty() {
+            return Err(anyhow!("Cannot run sequence model on an empty session"));
+        }
+        if let Some(item) = sequence.iter().find(|e| e.len() != self.embedding_dim) {
+            return Err(anyhow!(
+                "Session item dimension mismatch: expected {}, got {}",
+                self.embedding_dim,
+                item.len()
+            ));
+        }
+
+        let flat: Vec<f32> = sequence.iter().flatten().copied().collect();
+        let input = Array3::from_shape_vec((1, sequence.len(), self.embedding_dim), flat)?;
+
+        let mut session = self.session.write().await;
+        let input_tensor = Tensor::from_array(input)?;
+        let outputs = session.run(ort::inputs!["item_embeddings" => input_tensor])?;
+        let output_tensor = outputs["next_embedding"]
+            .try_extract_array::<f32>()?
+            .to_owned();
+
+        let embedding: Vec<f32> = match output_tensor.ndim() {
+            2 => output_tensor
+                .index_axis(Axis(0), 0)
+                .iter()
+                .copied()
+                .collect(),
+            // [batch, seq_len, dim]: the last position predicts the next item
+            3 => {
+                let steps = output_tensor.index_axis(Axis(0), 0);
+                let last = steps.shape()[0].saturating_sub(1);
+                steps.index_axis(Axis(0), last).iter().copied().collect()
+            }
+            _ => {
+                return Err(anyhow!(
+                    "Unexpected output tensor shape: {:?}",
+                    output_tensor.shape()
+                ))
+            }
+        };
+
+        if embedding.len() != self.embedding_dim {
+            return Err(anyhow!(
+                "Embedding dimension mismatch: expected {}, got {}",
+                self.embedding_dim,
+                embedding.len()
+            ));
+        }
+
+        Ok(embedding)
+    }
+
     /// Apply LoRA adapter weights to embedding
     ///
     /// Modulates base embedding with user-specific LoRA parameters
@@ -4,19 +4,34 @@
 //! providing user embeddings, LoRA adaptation, and hybrid recommendations.
 
 pub mod ab_testing;
+pub mod assignment;
+pub mod bandit;
+pub mod candidates;
 pub mod cold_start;
 pub mod collaborative;
 pub mod content_based;
 pub mod context;
 pub mod diversity;
+pub mod elicitation;
+pub mod evaluation;
+pub mod experiment_analysis;
 pub mod experiment_repository;
+pub mod explanation;
 pub mod graph;
+pub mod group;
 pub mod inference;
 pub mod lora;
 pub mod lora_storage;
+pub mod lora_training;
 pub mod matrix_factorization;
+pub mod model_registry;
+pub mod mood;
+pub mod negative_feedback;
 pub mod profile;
 pub mod recommendation;
+pub mod rerank;
+pub mod session;
+pub mod temporal;
 pub mod types;
 
 // Re-export key types
@@ -24,17 +39,74 @@ pub use ab_testing::{
     ABTestingService, Assignment, Experiment, ExperimentMetrics, ExperimentStatus, Variant,
     VariantMetrics,
 };
-pub use cold_start::HandleColdStartUser;
-pub use collaborative::{CollaborativeFilteringEngine, Interaction, InteractionType};
+pub use assignment::{
+    AssignmentConfig, AssignmentContext, AssignmentDecision, AssignmentEngine, LayerAllocation,
+    TargetingRules,
+};
+pub use bandit::{BanditConfig, BanditPolicy, BanditState, ReplayReport};
+pub use candidates::{CandidateSources, RetrievalConfig, SourceBudget, UserCandidateSource};
+pub use cold_start::{HandleColdStartUser, SignupContext};
+pub use collaborative::{
+    admit_events, viewing_activity_event, CollaborativeFilteringEngine, DriftMonitor, IngestReport,
+    Interaction, InteractionType, RetrainReason, StreamingConfig,
+};
+pub use content_based::{ContentBasedEngine, ContentEmbeddingStore};
 pub use context::ContextAwareFilter;
-pub use diversity::ApplyDiversityFilter;
+pub use diversity::{
+    ApplyDiversityFilter, ContentAttributes, ContentCatalog, DiversityConfig, DiversityMode,
+    PostgresContentCatalog,
+};
+pub use elicitation::{
+    ElicitationCatalog, ElicitationConfig, ElicitationRepository, ElicitationResponse,
+    ElicitationSession, PostgresElicitationRepository,
+};
+pub use evaluation::{
+    EvaluationContext, EvaluationMetrics, EvaluationReport, Recommender, RegressionGate,
+    TemporalSplit,
+};
+pub use experiment_analysis::{
+    AnalysisConfig, ConfidenceInterval, ContinuousMetric, ExperimentAnalysis, Verdict,
+};
 pub use experiment_repository::{ExperimentRepository, PostgresExperimentRepository};
+pub use explanation::{
+    ExplainRecommendation, ExplanationConfig, ExplanationEvidence, ExplanationReason,
+    ExplanationRepository, ExplanationTemplates, PostgresExplanationRepository, RankedReason,
+    StoredExplanation,
+};
+pub use graph::GraphRecommender;
+pub use group::{GenerateGroupRecommendations, GroupAggregation, GroupConfig, GroupMember};
 pub use inference::ONNXInference;
 pub use lora::{ComputeLoRAForward, UpdateUserLoRA, UserLoRAAdapter};
 pub use lora_storage::{LoRAAdapterMetadata, LoRAStorage, StorageStats};
-pub use matrix_factorization::{ALSConfig, MatrixFactorization, SparseMatrix};
-pub use profile::{BuildUserPreferenceVector, UserProfile};
+pub use lora_training::{
+    LoRATrainingConfig, LoRATrainingWorker, PostgresTrainingDataSource, TrainingDataSource,
+    TrainingOutcome, UserActivity,
+};
+pub use matrix_factorization::{
+    ALSConfig, CsrMatrix, MatrixFactorization, SparseMatrix, TrainingReport,
+};
+pub use model_registry::{
+    HttpModelStore, LoadedModel, LocalModelStore, ModelMetadata, ModelRegistry, ModelServer,
+    ModelStore, ShadowReport,
+};
+pub use mood::{
+    canonical_mood, current_explicit_pick, current_mood_label, mood_calibration, mood_prototype,
+    nearest_mood, tag_content_moods, ContentMoodTagger, InferMood, MoodConfig, MoodObservation,
+    MoodRepository, MoodService, MoodTaggingConfig, PostgresMoodRepository, MOOD_DIMENSIONS,
+};
+pub use negative_feedback::{
+    FeedbackSource, NegativeFeedback, NegativeFeedbackConfig, NegativeFeedbackRepository,
+    PostgresNegativeFeedbackRepository, SuppressionSet, SuppressionSummary, SuppressionTarget,
+};
+pub use profile::{BuildUserPreferenceVector, ProgressivePersonalization, UserProfile};
 pub use recommendation::GenerateRecommendations;
+pub use rerank::{RerankConfig, RerankPolicies, RerankRecommendations};
+pub use session::{SessionConfig, SessionRecommender, TransitionGraph};
+pub use temporal::{
+    is_valid_utc_offset, Daypart, LearnTemporalPatterns, LengthBucket, PlaybackSession,
+    PostgresTemporalPatternRepository, TemporalJobReport, TemporalPatternConfig,
+    TemporalPatternJob, TemporalPatternRepository, ViewingSlot,
+};
 pub use types::*;
 
 use anyhow::Result;
@@ -77,6 +149,7 @@ impl Default for SonaConfig {
 pub struct SonaEngine {
     config: SonaConfig,
     inference: Option<Arc<ONNXInference>>,
+    model_server: Option<Arc<ModelServer>>,
 }
 
 impl SonaEngine {
@@ -84,6 +157,7 @@ impl SonaEngine {
         Self {
             config,
             inference: None,
+            model_server: None,
         }
     }
 
@@ -97,8 +171,25 @@ impl SonaEngine {
         self
     }
 
+    /// Serve inference from a registry model that can be hot-swapped
+    pub fn with_model_server(mut self, server: Arc<ModelServer>) -> Self {
+        self.model_server = Some(server);
+        self
+    }
+
+    pub fn model_server(&self) -> Option<&Arc<ModelServer>> {
+        self.model_server.as_ref()
+    }
+
     /// Get or create inference engine
+    ///
+    /// With a model server this is the currently active registry model.
+    /// Embeddings should go through `generate_embedding`, which lets the
+    /// model server mirror traffic to a shadow candidate.
     pub fn inference(&self) -> Result<Arc<ONNXInference>> {
+        if let Some(ref server) = self.model_server {
+            return Ok(server.inference());
+        }
         if let Some(ref inference) = self.inference {
             return Ok(Arc::clone(inference));
         }
@@ -113,6 +204,39 @@ impl SonaEngine {
         Ok(Arc::new(inference))
     }
 
+    /// Embed text with the serving model
+    pub async fn generate_embedding(&self, text: &str) -> Result<Vec<f32>> {
+        match self.model_server {
+            Some(ref server) => server.generate_embedding(text).await,
+            None => self.inference()?.generate_embedding(text).await,
+        }
+    }
+
+    /// Batch variant of `generate_embedding`
+    pub async fn generate_embeddings_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        match self.model_server {
+            Some(ref server) => server.generate_embeddings_batch(texts).await,
+            None => self.inference()?.generate_embeddings_batch(texts).await,
+        }
+    }
+
+    /// Embed text with the model an experiment variant selects; without a
+    /// model server every variant uses the serving model
+    pub async fn generate_embedding_for_variant(
+        &self,
+        variant_config: Option<&serde_json::Value>,
+        text: &str,
+    ) -> Result<Vec<f32>> {
+        match self.model_server {
+            Some(ref server) => {
+                server
+                    .generate_embedding_for_variant(variant_config, text)
+                    .await
+            }
+            None => self.generate_embedding(text).await,
+        }
+    }
+
     pub fn config(&self) -> &SonaConfig {
         &self.config
     }
@@ -3,12 +3,11 @@
 //! Implements UpdateUserLoRA and ComputeLoRAForward algorithms from SPARC pseudocode.
 //! Provides per-user personalization with ~10KB memory footprint per user.
 
-use crate::inference::ONNXInference;
 use crate::types::ViewingEvent;
+use crate::SonaEngine;
 use anyhow::{anyhow, Result};
 use chrono::{DateTime, Utc};
 use ndarray::{Array1, Array2};
-use std::sync::Arc;
 use uuid::Uuid;
 
 const LORA_RANK: usize = 8;
@@ -16,7 +15,7 @@ const LORA_ALPHA: f32 = 16.0;
 const INPUT_DIM: usize = 512;
 const OUTPUT_DIM: usize = 768;
 const LEARNING_RATE: f32 = 0.001;
-const MIN_TRAINING_EVENTS: usize = 10;
+pub(crate) const MIN_TRAINING_EVENTS: usize = 10;
 
 /// User-specific LoRA adapter
 ///
@@ -85,11 +84,13 @@ impl UserLoRAAdapter {
 pub struct UpdateUserLoRA;
 
 impl UpdateUserLoRA {
-    /// Execute LoRA training with real embeddings from ONNX inference
+    /// Execute LoRA training with real embeddings from the engine's serving
+    /// model, or the model the user's experiment variant selects
     pub async fn execute_with_inference(
         adapter: &mut UserLoRAAdapter,
         recent_events: &[ViewingEvent],
-        inference: Arc<ONNXInference>,
+        engine: &SonaEngine,
+        variant_config: Option<&serde_json::Value>,
         get_content_text: impl Fn(Uuid) -> Result<String>,
         preference_vector: &[f32],
     ) -> Result<()> {
@@ -102,7 +103,9 @@ impl UpdateUserLoRA {
         let mut training_pairs = Vec::new();
         for event in recent_events {
             let content_text = get_content_text(event.content_id)?;
-            let content_embedding = inference.generate_embedding(&content_text).await?;
+            let content_embedding = engine
+                .generate_embedding_for_variant(variant_config, &content_text)
+                .await?;
             let engagement_label = Self::calculate_engagement_label(event);
             training_pairs.push((content_embedding, engagement_label));
         }
@@ -195,7 +198,21 @@ impl UpdateUserLoRA {
         Ok(())
     }
 
-    fn calculate_engagement_label(event: &ViewingEvent) -> f32 {
+    /// Predicted engagement in (0, 1) for content, as scored during training
+    pub fn predict_engagement(
+        adapter: &UserLoRAAdapter,
+        content_embedding: &[f32],
+        preference_vector: &[f32],
+    ) -> Result<f32> {
+        let lora_output = ComputeLoRAForward::execute(adapter, content_embedding)?;
+        Ok(Self::sigmoid(Self::dot_product(
+            &lora_output,
+            preference_vector,
+        )))
+    }
+
+    /// Engagement target in [0, 1] derived from completion, rating and rewatch
+    pub fn calculate_engagement_label(event: &ViewingEvent) -> f32 {
         const COMPLETION_WEIGHT: f32 = 0.4;
         const RATING_WEIGHT: f32 = 0.3;
         const REWATCH_WEIGHT: f32 = 0.2;
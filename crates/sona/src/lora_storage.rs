@@ -4,6 +4,7 @@
 //! - Efficient binary serialization using bincode
 //! - Sub-2ms retrieval latency
 //! - Versioning support for adapter evolution
+//! - Validated rollout: candidate versions, an active-version pointer and rollback
 //! - Proper error handling and connection pooling
 //!
 //! Storage schema:
@@ -13,10 +14,12 @@
 //! - created_at, updated_at: timestamps
 
 use crate::lora::UserLoRAAdapter;
+use crate::lora_training::ValidationReport;
 use anyhow::{anyhow, Context, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{PgPool, Row};
+use sqlx::types::Json;
+use sqlx::{PgPool, Postgres, Row, Transaction};
 use std::time::Instant;
 use uuid::Uuid;
 
@@ -93,6 +96,9 @@ pub struct LoRAAdapterMetadata {
     pub updated_at: DateTime<Utc>,
 }
 
+const ACTIVE: &str = "active";
+const CANDIDATE: &str = "candidate";
+
 /// LoRA adapter storage with PostgreSQL persistence
 pub struct LoRAStorage {
     pool: PgPool,
@@ -123,6 +129,38 @@ impl LoRAStorage {
     /// - Database connection fails
     /// - SQL execution fails
     pub async fn save_adapter(&self, adapter: &UserLoRAAdapter, adapter_name: &str) -> Result<i32> {
+        // The new version must also become the rollout pointer's target, or a
+        // user with a pointer would keep being served the older version
+        let mut tx = self.pool.begin().await?;
+        let version = Self::insert_version(&mut tx, adapter, adapter_name, ACTIVE, None).await?;
+        Self::point_rollout_at(&mut tx, adapter.user_id, adapter_name, version).await?;
+        tx.commit().await?;
+        Ok(version)
+    }
+
+    /// Save a trained adapter as a candidate version
+    ///
+    /// Candidates are not served until [`Self::activate_version`] is called.
+    pub async fn save_candidate(
+        &self,
+        adapter: &UserLoRAAdapter,
+        adapter_name: &str,
+        report: &ValidationReport,
+    ) -> Result<i32> {
+        let mut tx = self.pool.begin().await?;
+        let version =
+            Self::insert_version(&mut tx, adapter, adapter_name, CANDIDATE, Some(report)).await?;
+        tx.commit().await?;
+        Ok(version)
+    }
+
+    async fn insert_version(
+        tx: &mut Transaction<'_, Postgres>,
+        adapter: &UserLoRAAdapter,
+        adapter_name: &str,
+        status: &str,
+        report: Option<&ValidationReport>,
+    ) -> Result<i32> {
         let start = Instant::now();
 
         // Convert to serializable format
@@ -144,7 +182,7 @@ impl LoRAStorage {
         )
         .bind(adapter.user_id)
         .bind(adapter_name)
-        .fetch_one(&self.pool)
+        .fetch_one(&mut **tx)
         .await
         .context("Failed to get next version number")?;
 
@@ -158,9 +196,11 @@ impl LoRAStorage {
                 weights,
                 size_bytes,
                 training_iterations,
+                status,
+                validation_metrics,
                 created_at,
                 updated_at
-            ) VALUES ($1, $2, $3, $4, $5, $6, NOW(), NOW())
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, NOW(), NOW())
             "#,
         )
         .bind(adapter.user_id)
@@ -169,15 +209,18 @@ impl LoRAStorage {
         .bind(&weights_bytes)
         .bind(size_bytes)
         .bind(adapter.training_iterations as i32)
-        .execute(&self.pool)
+        .bind(status)
+        .bind(report.map(Json))
+        .execute(&mut **tx)
         .await
         .context("Failed to insert LoRA adapter")?;
 
         let elapsed = start.elapsed();
         tracing::debug!(
-            "Saved LoRA adapter for user {} (version {}) in {:?} ({} bytes)",
+            "Saved LoRA adapter for user {} (version {}, {}) in {:?} ({} bytes)",
             adapter.user_id,
             version,
+            status,
             elapsed,
             size_bytes
         );
@@ -185,7 +228,11 @@ impl LoRAStorage {
         Ok(version)
     }
 
-    /// Load the latest LoRA adapter for a user
+    /// Load the serving LoRA adapter for a user
+    ///
+    /// This is the version named by the rollout pointer when one exists,
+    /// otherwise the latest active version. Candidates and rejected or
+    /// rolled-back versions are never returned.
     ///
     /// # Performance
     /// Target: <2ms retrieval latency (with proper indexing)
@@ -204,10 +251,17 @@ impl LoRAStorage {
 
         let row = sqlx::query(
             r#"
-            SELECT weights, updated_at
-            FROM lora_adapters
-            WHERE user_id = $1 AND adapter_name = $2
-            ORDER BY version DESC
+            SELECT a.weights, a.updated_at
+            FROM lora_adapters a
+            LEFT JOIN lora_adapter_rollouts r
+                ON r.user_id = a.user_id AND r.adapter_name = a.adapter_name
+            WHERE a.user_id = $1
+              AND a.adapter_name = $2
+              AND CASE
+                    WHEN r.active_version IS NULL THEN a.status = 'active'
+                    ELSE a.version = r.active_version
+                  END
+            ORDER BY a.version DESC
             LIMIT 1
             "#,
         )
@@ -392,6 +446,187 @@ impl LoRAStorage {
         Ok(adapters)
     }
 
+    /// Version currently served
+    ///
+    /// Uses the rollout pointer when one exists, otherwise the latest active
+    /// version (adapters saved before rollout tracking).
+    pub async fn active_version(&self, user_id: Uuid, adapter_name: &str) -> Result<Option<i32>> {
+        let version: Option<i32> = sqlx::query_scalar(
+            r#"
+            SELECT COALESCE(
+                (SELECT active_version FROM lora_adapter_rollouts
+                 WHERE user_id = $1 AND adapter_name = $2),
+                (SELECT MAX(version) FROM lora_adapters
+                 WHERE user_id = $1 AND adapter_name = $2 AND status = 'active')
+            )
+            "#,
+        )
+        .bind(user_id)
+        .bind(adapter_name)
+        .fetch_one(&self.pool)
+        .await
+        .context("Failed to query active LoRA adapter version")?;
+
+        Ok(version)
+    }
+
+    /// Start serving `version`, remembering the replaced version for rollback
+    pub async fn activate_version(
+        &self,
+        user_id: Uuid,
+        adapter_name: &str,
+        version: i32,
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            UPDATE lora_adapters
+            SET status = 'active'
+            WHERE user_id = $1 AND adapter_name = $2 AND version = $3
+            "#,
+        )
+        .bind(user_id)
+        .bind(adapter_name)
+        .bind(version)
+        .execute(&mut *tx)
+        .await
+        .context("Failed to mark LoRA adapter version active")?;
+
+        Self::point_rollout_at(&mut tx, user_id, adapter_name, version).await?;
+
+        tx.commit().await?;
+        tracing::info!(
+            "Activated LoRA adapter version {} for user {}",
+            version,
+            user_id
+        );
+        Ok(())
+    }
+
+    /// Move the rollout pointer to `version`, remembering the one it replaces
+    async fn point_rollout_at(
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: Uuid,
+        adapter_name: &str,
+        version: i32,
+    ) -> Result<()> {
+        // Without a pointer yet, the version being replaced is the latest
+        // active one that legacy reads were serving
+        sqlx::query(
+            r#"
+            INSERT INTO lora_adapter_rollouts (user_id, adapter_name, active_version, previous_version)
+            VALUES (
+                $1, $2, $3,
+                (SELECT MAX(version) FROM lora_adapters
+                 WHERE user_id = $1 AND adapter_name = $2 AND status = 'active' AND version < $3)
+            )
+            ON CONFLICT (user_id, adapter_name) DO UPDATE
+            SET previous_version = lora_adapter_rollouts.active_version,
+                active_version = EXCLUDED.active_version,
+                activated_at = NOW()
+            "#,
+        )
+        .bind(user_id)
+        .bind(adapter_name)
+        .bind(version)
+        .execute(&mut **tx)
+        .await
+        .context("Failed to update LoRA adapter rollout")?;
+
+        Ok(())
+    }
+
+    /// Mark a candidate that failed validation
+    pub async fn reject_version(
+        &self,
+        user_id: Uuid,
+        adapter_name: &str,
+        version: i32,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE lora_adapters
+            SET status = 'rejected'
+            WHERE user_id = $1 AND adapter_name = $2 AND version = $3
+            "#,
+        )
+        .bind(user_id)
+        .bind(adapter_name)
+        .bind(version)
+        .execute(&self.pool)
+        .await
+        .context("Failed to reject LoRA adapter version")?;
+
+        Ok(())
+    }
+
+    /// Serve the previous version again and retire the active one
+    ///
+    /// Returns the version now served, or `None` when there is nothing to
+    /// roll back to.
+    pub async fn rollback_adapter(&self, user_id: Uuid, adapter_name: &str) -> Result<Option<i32>> {
+        let mut tx = self.pool.begin().await?;
+
+        let row = sqlx::query(
+            r#"
+            SELECT active_version, previous_version
+            FROM lora_adapter_rollouts
+            WHERE user_id = $1 AND adapter_name = $2
+            FOR UPDATE
+            "#,
+        )
+        .bind(user_id)
+        .bind(adapter_name)
+        .fetch_optional(&mut *tx)
+        .await
+        .context("Failed to query LoRA adapter rollout")?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        let active: i32 = row.try_get("active_version")?;
+        let Some(previous) = row.try_get::<Option<i32>, _>("previous_version")? else {
+            return Ok(None);
+        };
+
+        sqlx::query(
+            r#"
+            UPDATE lora_adapters
+            SET status = 'rolled_back'
+            WHERE user_id = $1 AND adapter_name = $2 AND version = $3
+            "#,
+        )
+        .bind(user_id)
+        .bind(adapter_name)
+        .bind(active)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE lora_adapter_rollouts
+            SET active_version = $3, previous_version = NULL, activated_at = NOW()
+            WHERE user_id = $1 AND adapter_name = $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(adapter_name)
+        .bind(previous)
+        .execute(&mut *tx)
+        .await
+        .context("Failed to roll back LoRA adapter")?;
+
+        tx.commit().await?;
+        tracing::warn!(
+            "Rolled back LoRA adapter for user {} from version {} to {}",
+            user_id,
+            active,
+            previous
+        );
+        Ok(Some(previous))
+    }
+
     /// Get the total number of stored adapters for a user
     pub async fn count_adapters(&self, user_id: Uuid) -> Result<i64> {
         let count: i64 = sqlx::query_scalar(
@@ -0,0 +1,929 @@
+//! Continuous LoRA Adapter Training
+//!
+//! Background worker that consumes user activity, retrains a user's adapter
+//! when `ProgressivePersonalization::should_train_lora` triggers, and only
+//! serves the new version if it does at least as well as the current one on
+//! held-out interactions. After activation the worker keeps scoring the new
+//! version against its predecessor on fresh interactions and rolls back
+//! automatically if it regresses.
+
+use crate::content_based::ContentEmbeddingStore;
+use crate::lora::{UpdateUserLoRA, UserLoRAAdapter, MIN_TRAINING_EVENTS};
+use crate::lora_storage::LoRAStorage;
+use crate::profile::{BuildUserPreferenceVector, ProgressivePersonalization, EMBEDDING_DIM};
+use crate::types::ViewingEvent;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+/// Recent events the preference vector is built from, as when serving
+const PREFERENCE_HISTORY_LIMIT: usize = 100;
+
+/// Worker configuration
+#[derive(Debug, Clone)]
+pub struct LoRATrainingConfig {
+    /// Adapter name trained and rolled out (default: "default")
+    pub adapter_name: String,
+    /// Recent events kept per user for training and validation (default: 100)
+    pub history_limit: usize,
+    /// Share of recent events held out for validation (default: 0.2)
+    pub holdout_fraction: f32,
+    /// Minimum held-out events for a meaningful comparison (default: 3)
+    pub min_holdout_events: usize,
+    /// Loss reduction a candidate must achieve over the serving adapter
+    /// (default: 0.0, i.e. it must not be worse)
+    pub min_loss_improvement: f32,
+    /// Fresh events scored after activation before deciding on rollback (default: 5)
+    pub monitor_events: usize,
+    /// Relative loss increase versus the previous version that triggers
+    /// rollback (default: 0.05)
+    pub rollback_tolerance: f32,
+    /// Users whose state is kept in memory; idle, then least recently active
+    /// users are evicted beyond this (default: 10,000)
+    pub max_cached_users: usize,
+    /// Inactivity after which a user's state may be evicted (default: 1 hour)
+    pub idle_timeout: Duration,
+}
+
+impl Default for LoRATrainingConfig {
+    fn default() -> Self {
+        Self {
+            adapter_name: "default".to_string(),
+            history_limit: 100,
+            holdout_fraction: 0.2,
+            min_holdout_events: 3,
+            min_loss_improvement: 0.0,
+            monitor_events: 5,
+            rollback_tolerance: 0.05,
+            max_cached_users: 10_000,
+            idle_timeout: Duration::from_secs(3600),
+        }
+    }
+}
+
+/// A user activity event fed to the worker
+#[derive(Debug, Clone)]
+pub struct UserActivity {
+    pub user_id: Uuid,
+    pub event: ViewingEvent,
+}
+
+/// Held-out quality of an adapter
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ValidationMetrics {
+    /// Mean binary cross-entropy against engagement labels
+    pub loss: f32,
+    /// Pairwise ranking accuracy of engaged over non-engaged items, when
+    /// both are present
+    pub auc: Option<f32>,
+    pub samples: usize,
+}
+
+/// Candidate metrics next to those of the adapter it was compared against
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ValidationReport {
+    pub candidate: ValidationMetrics,
+    pub baseline: ValidationMetrics,
+    /// Version the candidate was compared against (`None` for a fresh adapter)
+    pub baseline_version: Option<i32>,
+}
+
+impl ValidationReport {
+    pub fn passes(&self, min_loss_improvement: f32) -> bool {
+        self.candidate.loss + min_loss_improvement <= self.baseline.loss
+    }
+}
+
+/// What the worker did in response to an event
+#[derive(Debug, Clone, PartialEq)]
+pub enum TrainingOutcome {
+    /// Candidate passed validation and is now served
+    Activated {
+        version: i32,
+        report: ValidationReport,
+    },
+    /// Candidate was stored but not served
+    Rejected {
+        version: i32,
+        report: ValidationReport,
+    },
+    /// A recently activated version regressed on fresh interactions
+    RolledBack { from: i32, to: i32 },
+    /// Training triggered but there was too little history to train and validate
+    InsufficientData { events: usize },
+}
+
+/// Adapter persistence needed for validated rollout
+#[async_trait::async_trait]
+pub trait AdapterStore: Send + Sync {
+    /// Serving adapter and its version, if any
+    async fn load_active(
+        &self,
+        user_id: Uuid,
+        adapter_name: &str,
+    ) -> Result<Option<(i32, UserLoRAAdapter)>>;
+
+    async fn load_version(
+        &self,
+        user_id: Uuid,
+        adapter_name: &str,
+        version: i32,
+    ) -> Result<UserLoRAAdapter>;
+
+    async fn save_candidate(
+        &self,
+        adapter: &UserLoRAAdapter,
+        adapter_name: &str,
+        report: &ValidationReport,
+    ) -> Result<i32>;
+
+    async fn activate(&self, user_id: Uuid, adapter_name: &str, version: i32) -> Result<()>;
+
+    async fn reject(&self, user_id: Uuid, adapter_name: &str, version: i32) -> Result<()>;
+
+    /// Serve the previous version again; returns it when there was one
+    async fn rollback(&self, user_id: Uuid, adapter_name: &str) -> Result<Option<i32>>;
+}
+
+#[async_trait::async_trait]
+impl AdapterStore for LoRAStorage {
+    async fn load_active(
+        &self,
+        user_id: Uuid,
+        adapter_name: &str,
+    ) -> Result<Option<(i32, UserLoRAAdapter)>> {
+        let Some(version) = self.active_version(user_id, adapter_name).await? else {
+            return Ok(None);
+        };
+        let adapter = self
+            .load_adapter_version(user_id, adapter_name, version)
+            .await?;
+        Ok(Some((version, adapter)))
+    }
+
+    async fn load_version(
+        &self,
+        user_id: Uuid,
+        adapter_name: &str,
+        version: i32,
+    ) -> Result<UserLoRAAdapter> {
+        self.load_adapter_version(user_id, adapter_name, version)
+            .await
+    }
+
+    async fn save_candidate(
+        &self,
+        adapter: &UserLoRAAdapter,
+        adapter_name: &str,
+        report: &ValidationReport,
+    ) -> Result<i32> {
+        LoRAStorage::save_candidate(self, adapter, adapter_name, report).await
+    }
+
+    async fn activate(&self, user_id: Uuid, adapter_name: &str, version: i32) -> Result<()> {
+        self.activate_version(user_id, adapter_name, version).await
+    }
+
+    async fn reject(&self, user_id: Uuid, adapter_name: &str, version: i32) -> Result<()> {
+        self.reject_version(user_id, adapter_name, version).await
+    }
+
+    async fn rollback(&self, user_id: Uuid, adapter_name: &str) -> Result<Option<i32>> {
+        self.rollback_adapter(user_id, adapter_name).await
+    }
+}
+
+/// Per-user history, preference vectors and content embeddings
+#[async_trait::async_trait]
+pub trait TrainingDataSource: Send + Sync {
+    /// Most recent viewing events, oldest first
+    async fn recent_events(&self, user_id: Uuid, limit: usize) -> Result<Vec<ViewingEvent>>;
+
+    /// Lifetime interaction count, used to decide when to retrain
+    async fn interaction_count(&self, user_id: Uuid) -> Result<usize>;
+
+    async fn preference_vector(&self, user_id: Uuid) -> Result<Vec<f32>>;
+
+    async fn content_embeddings(&self, content_ids: &[Uuid]) -> Result<HashMap<Uuid, Vec<f32>>>;
+}
+
+/// Training data from stored viewing events and the content vector store
+pub struct PostgresTrainingDataSource {
+    pool: PgPool,
+    embeddings: Arc<dyn ContentEmbeddingStore>,
+}
+
+impl PostgresTrainingDataSource {
+    pub fn new(pool: PgPool, embeddings: Arc<dyn ContentEmbeddingStore>) -> Self {
+        Self { pool, embeddings }
+    }
+}
+
+#[async_trait::async_trait]
+impl TrainingDataSource for PostgresTrainingDataSource {
+    async fn recent_events(&self, user_id: Uuid, limit: usize) -> Result<Vec<ViewingEvent>> {
+        let rows: Vec<(Uuid, DateTime<Utc>, f32, Option<i16>, bool, bool)> = sqlx::query_as(
+            r#"
+            SELECT content_id, timestamp, completion_rate, rating, is_rewatch, dismissed
+            FROM viewing_events
+            WHERE user_id = $1
+            ORDER BY timestamp DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to load viewing events")?;
+
+        Ok(rows
+            .into_iter()
+            .rev()
+            .map(
+                |(content_id, timestamp, completion_rate, rating, is_rewatch, dismissed)| {
+                    ViewingEvent {
+                        content_id,
+                        timestamp,
+                        completion_rate,
+                        rating: rating.map(|r| r as u8),
+                        is_rewatch,
+                        dismissed,
+                    }
+                },
+            )
+            .collect())
+    }
+
+    async fn interaction_count(&self, user_id: Uuid) -> Result<usize> {
+        let count: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM viewing_events WHERE user_id = $1")
+                .bind(user_id)
+                .fetch_one(&self.pool)
+                .await
+                .context("Failed to count viewing events")?;
+        Ok(count as usize)
+    }
+
+    /// Rebuilt from the user's recent history, as for serving
+    async fn preference_vector(&self, user_id: Uuid) -> Result<Vec<f32>> {
+        let events = self
+            .recent_events(user_id, PREFERENCE_HISTORY_LIMIT)
+            .await?;
+        let content_ids: Vec<Uuid> = events.iter().map(|e| e.content_id).collect();
+        let embeddings = self.content_embeddings(&content_ids).await?;
+        BuildUserPreferenceVector::execute(user_id, &events, |content_id| {
+            Ok(embeddings
+                .get(&content_id)
+                .cloned()
+                .unwrap_or_else(|| vec![0.0; EMBEDDING_DIM]))
+        })
+        .await
+    }
+
+    async fn content_embeddings(&self, content_ids: &[Uuid]) -> Result<HashMap<Uuid, Vec<f32>>> {
+        self.embeddings.content_embeddings(content_ids).await
+    }
+}
+
+/// Score an adapter on held-out events
+pub fn validate_adapter(
+    adapter: &UserLoRAAdapter,
+    events: &[ViewingEvent],
+    embeddings: &HashMap<Uuid, Vec<f32>>,
+    preference_vector: &[f32],
+) -> Result<ValidationMetrics> {
+    let mut scored = Vec::with_capacity(events.len());
+    for event in events {
+        let embedding = embeddings
+            .get(&event.content_id)
+            .with_context(|| format!("Missing embedding for content {}", event.content_id))?;
+        let predicted = UpdateUserLoRA::predict_engagement(adapter, embedding, preference_vector)?
+            .clamp(1e-6, 1.0 - 1e-6);
+        let label = UpdateUserLoRA::calculate_engagement_label(event);
+        scored.push((predicted, label));
+    }
+
+    let loss = if scored.is_empty() {
+        0.0
+    } else {
+        scored
+            .iter()
+            .map(|(p, y)| -y * p.ln() - (1.0 - y) * (1.0 - p).ln())
+            .sum::<f32>()
+            / scored.len() as f32
+    };
+
+    let positives: Vec<f32> = scored
+        .iter()
+        .filter(|(_, y)| *y >= 0.5)
+        .map(|(p, _)| *p)
+        .collect();
+    let negatives: Vec<f32> = scored
+        .iter()
+        .filter(|(_, y)| *y < 0.5)
+        .map(|(p, _)| *p)
+        .collect();
+    let auc = if positives.is_empty() || negatives.is_empty() {
+        None
+    } else {
+        let mut wins = 0.0;
+        for p in &positives {
+            for n in &negatives {
+                if p > n {
+                    wins += 1.0;
+                } else if p == n {
+                    wins += 0.5;
+                }
+            }
+        }
+        Some(wins / (positives.len() * negatives.len()) as f32)
+    };
+
+    Ok(ValidationMetrics {
+        loss,
+        auc,
+        samples: scored.len(),
+    })
+}
+
+#[derive(Debug)]
+struct UserState {
+    interaction_count: usize,
+    recent: VecDeque<ViewingEvent>,
+    monitor: Option<RolloutMonitor>,
+    last_active: Instant,
+}
+
+/// A freshly activated version under observation
+#[derive(Debug)]
+struct RolloutMonitor {
+    version: i32,
+    previous_version: i32,
+    adapter: UserLoRAAdapter,
+    fresh: Vec<ViewingEvent>,
+}
+
+/// Background LoRA training worker
+pub struct LoRATrainingWorker {
+    store: Arc<dyn AdapterStore>,
+    data: Arc<dyn TrainingDataSource>,
+    config: LoRATrainingConfig,
+    users: HashMap<Uuid, UserState>,
+}
+
+impl LoRATrainingWorker {
+    pub fn new(
+        store: Arc<dyn AdapterStore>,
+        data: Arc<dyn TrainingDataSource>,
+        config: LoRATrainingConfig,
+    ) -> Self {
+        Self {
+            store,
+            data,
+            config,
+            users: HashMap::new(),
+        }
+    }
+
+    /// Consume activity until the channel closes
+    pub fn spawn(
+        mut self,
+        mut events: mpsc::Receiver<UserActivity>,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            while let Some(activity) = events.recv().await {
+                let user_id = activity.user_id;
+                match self.handle_activity(activity).await {
+                    Ok(Some(outcome)) => debug!("LoRA worker for user {}: {:?}", user_id, outcome),
+                    Ok(None) => {}
+                    Err(e) => warn!("LoRA training failed for user {}: {}", user_id, e),
+                }
+            }
+            info!("LoRA training worker stopped: activity channel closed");
+        })
+    }
+
+    /// Record one activity event, retraining or rolling back when due
+    pub async fn handle_activity(
+        &mut self,
+        activity: UserActivity,
+    ) -> Result<Option<TrainingOutcome>> {
+        let user_id = activity.user_id;
+        if !self.users.contains_key(&user_id) {
+            let state = self.load_user_state(user_id).await?;
+            self.evict(Instant::now());
+            self.users.insert(user_id, state);
+        }
+
+        let history_limit = self.config.history_limit;
+        let state = self.users.get_mut(&user_id).expect("user state loaded");
+        state.last_active = Instant::now();
+        state.interaction_count += 1;
+        state.recent.push_back(activity.event.clone());
+        while state.recent.len() > history_limit {
+            state.recent.pop_front();
+        }
+        if let Some(monitor) = state.monitor.as_mut() {
+            monitor.fresh.push(activity.event);
+        }
+
+        if let Some(outcome) = self.check_rollout(user_id).await? {
+            return Ok(Some(outcome));
+        }
+
+        let count = self.users[&user_id].interaction_count;
+        if ProgressivePersonalization::should_train_lora(count) {
+            return self.train(user_id).await.map(Some);
+        }
+        Ok(None)
+    }
+
+    async fn load_user_state(&self, user_id: Uuid) -> Result<UserState> {
+        let recent = self
+            .data
+            .recent_events(user_id, self.config.history_limit)
+            .await?;
+        let interaction_count = self.data.interaction_count(user_id).await?;
+        Ok(UserState {
+            interaction_count,
+            recent: recent.into(),
+            monitor: None,
+            last_active: Instant::now(),
+        })
+    }
+
+    /// Make room for one more user: drop idle users, then the least recently
+    /// active ones
+    ///
+    /// Evicted users are reloaded from the data source on their next
+    /// activity; a pending rollout check is abandoned and the activated
+    /// version stays live.
+    fn evict(&mut self, now: Instant) {
+        if self.users.len() < self.config.max_cached_users {
+            return;
+        }
+        let idle_timeout = self.config.idle_timeout;
+        self.users
+            .retain(|_, state| now.duration_since(state.last_active) < idle_timeout);
+
+        while self.users.len() >= self.config.max_cached_users.max(1) {
+            let Some(oldest) = self
+                .users
+                .iter()
+                .min_by_key(|(_, state)| state.last_active)
+                .map(|(user_id, _)| *user_id)
+            else {
+                break;
+            };
+            if let Some(state) = self.users.remove(&oldest) {
+                if let Some(monitor) = state.monitor {
+                    debug!(
+                        "Evicted user {} before checking LoRA adapter version {}",
+                        oldest, monitor.version
+                    );
+                }
+            }
+        }
+    }
+
+    /// Train a candidate on older history, validate on the newest events and
+    /// activate or reject it
+    async fn train(&mut self, user_id: Uuid) -> Result<TrainingOutcome> {
+        let events: Vec<ViewingEvent> = self.users[&user_id].recent.iter().cloned().collect();
+        let holdout_len = ((events.len() as f32 * self.config.holdout_fraction).ceil() as usize)
+            .max(self.config.min_holdout_events);
+        if events.len() < holdout_len + MIN_TRAINING_EVENTS {
+            return Ok(TrainingOutcome::InsufficientData {
+                events: events.len(),
+            });
+        }
+        let (train, holdout) = events.split_at(events.len() - holdout_len);
+
+        let name = self.config.adapter_name.clone();
+        let preference_vector = self.data.preference_vector(user_id).await?;
+        let content_ids: Vec<Uuid> = events.iter().map(|e| e.content_id).collect();
+        let embeddings = self.data.content_embeddings(&content_ids).await?;
+
+        let active = self.store.load_active(user_id, &name).await?;
+        let (baseline_version, baseline) = match active {
+            Some((version, adapter)) => (Some(version), adapter),
+            None => {
+                let mut adapter = UserLoRAAdapter::new(user_id);
+                adapter.initialize_random();
+                (None, adapter)
+            }
+        };
+
+        let mut candidate = baseline.clone();
+        UpdateUserLoRA::execute(
+            &mut candidate,
+            train,
+            |id| {
+                embeddings
+                    .get(&id)
+                    .cloned()
+                    .with_context(|| format!("Missing embedding for content {}", id))
+            },
+            &preference_vector,
+        )
+        .await?;
+
+        let report = ValidationReport {
+            candidate: validate_adapter(&candidate, holdout, &embeddings, &preference_vector)?,
+            baseline: validate_adapter(&baseline, holdout, &embeddings, &preference_vector)?,
+            baseline_version,
+        };
+
+        let version = self
+            .store
+            .save_candidate(&candidate, &name, &report)
+            .await?;
+        if !report.passes(self.config.min_loss_improvement) {
+            self.store.reject(user_id, &name, version).await?;
+            info!(
+                "Rejected LoRA adapter version {} for user {}: held-out loss {:.4} vs {:.4}",
+                version, user_id, report.candidate.loss, report.baseline.loss
+            );
+            return Ok(TrainingOutcome::Rejected { version, report });
+        }
+
+        self.store.activate(user_id, &name, version).await?;
+        info!(
+            "Activated LoRA adapter version {} for user {}: held-out loss {:.4} vs {:.4}",
+            version, user_id, report.candidate.loss, report.baseline.loss
+        );
+
+        if let (Some(state), Some(previous_version)) =
+            (self.users.get_mut(&user_id), baseline_version)
+        {
+            state.monitor = Some(RolloutMonitor {
+                version,
+                previous_version,
+                adapter: candidate,
+                fresh: Vec::new(),
+            });
+        }
+
+        Ok(TrainingOutcome::Activated { version, report })
+    }
+
+    /// Compare a recently activated version with its predecessor once enough
+    /// fresh events have arrived, rolling back if it does worse
+    async fn check_rollout(&mut self, user_id: Uuid) -> Result<Option<TrainingOutcome>> {
+        let ready = self.users[&user_id]
+            .monitor
+            .as_ref()
+            .is_some_and(|m| m.fresh.len() >= self.config.monitor_events);
+        if !ready {
+            return Ok(None);
+        }
+        let monitor = self
+            .users
+            .get_mut(&user_id)
+            .and_then(|state| state.monitor.take())
+            .expect("monitor checked above");
+
+        let name = self.config.adapter_name.clone();
+        let previous = self
+            .store
+            .load_version(user_id, &name, monitor.previous_version)
+            .await?;
+        let preference_vector = self.data.preference_vector(user_id).await?;
+        let content_ids: Vec<Uuid> = monitor.fresh.iter().map(|e| e.content_id).collect();
+        let embeddings = self.data.content_embeddings(&content_ids).await?;
+
+        let current = validate_adapter(
+            &monitor.adapter,
+            &monitor.fresh,
+            &embeddings,
+            &preference_vector,
+        )?;
+        let before = validate_adapter(&previous, &monitor.fresh, &embeddings, &preference_vector)?;
+
+        if current.loss <= before.loss * (1.0 + self.config.rollback_tolerance) {
+            debug!(
+                "LoRA adapter version {} for user {} holding up: loss {:.4} vs {:.4}",
+                monitor.version, user_id, current.loss, before.loss
+            );
+            return Ok(None);
+        }
+
+        match self.store.rollback(user_id, &name).await? {
+            Some(to) => {
+                warn!(
+                    "LoRA adapter version {} for user {} regressed (loss {:.4} vs {:.4}); rolled back to {}",
+                    monitor.version, user_id, current.loss, before.loss, to
+                );
+                Ok(Some(TrainingOutcome::RolledBack {
+                    from: monitor.version,
+                    to,
+                }))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+    use std::sync::Mutex;
+
+    const DIM: usize = 512;
+
+    #[derive(Default)]
+    struct MemoryStore {
+        versions: Mutex<Vec<(UserLoRAAdapter, &'static str)>>,
+        active: Mutex<Option<i32>>,
+        previous: Mutex<Option<i32>>,
+    }
+
+    #[async_trait::async_trait]
+    impl AdapterStore for MemoryStore {
+        async fn load_active(&self, _: Uuid, _: &str) -> Result<Option<(i32, UserLoRAAdapter)>> {
+            let active = *self.active.lock().unwrap();
+            Ok(active.map(|v| (v, self.versions.lock().unwrap()[v as usize - 1].0.clone())))
+        }
+
+        async fn load_version(&self, _: Uuid, _: &str, version: i32) -> Result<UserLoRAAdapter> {
+            Ok(self.versions.lock().unwrap()[version as usize - 1]
+                .0
+                .clone())
+        }
+
+        async fn save_candidate(
+            &self,
+            adapter: &UserLoRAAdapter,
+            _: &str,
+            _: &ValidationReport,
+        ) -> Result<i32> {
+            let mut versions = self.versions.lock().unwrap();
+            versions.push((adapter.clone(), "candidate"));
+            Ok(versions.len() as i32)
+        }
+
+        async fn activate(&self, _: Uuid, _: &str, version: i32) -> Result<()> {
+            self.versions.lock().unwrap()[version as usize - 1].1 = "active";
+            let mut active = self.active.lock().unwrap();
+            *self.previous.lock().unwrap() = *active;
+            *active = Some(version);
+            Ok(())
+        }
+
+        async fn reject(&self, _: Uuid, _: &str, version: i32) -> Result<()> {
+            self.versions.lock().unwrap()[version as usize - 1].1 = "rejected";
+            Ok(())
+        }
+
+        async fn rollback(&self, _: Uuid, _: &str) -> Result<Option<i32>> {
+            let previous = self.previous.lock().unwrap().take();
+            if let Some(version) = previous {
+                *self.active.lock().unwrap() = Some(version);
+            }
+            Ok(previous)
+        }
+    }
+
+    /// Items 0-9 are "liked" (full completion), 10-19 abandoned early
+    struct Data {
+        embeddings: HashMap<Uuid, Vec<f32>>,
+    }
+
+    impl Data {
+        fn new() -> Self {
+            let embeddings = (0..20u128)
+                .map(|i| {
+                    let mut v = vec![0.0; DIM];
+                    v[if i < 10 { 0 } else { 1 }] = 1.0;
+                    v[2 + i as usize] = 0.1;
+                    (Uuid::from_u128(i), v)
+                })
+                .collect();
+            Self { embeddings }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl TrainingDataSource for Data {
+        async fn recent_events(&self, _: Uuid, _: usize) -> Result<Vec<ViewingEvent>> {
+            Ok(Vec::new())
+        }
+
+        async fn interaction_count(&self, _: Uuid) -> Result<usize> {
+            Ok(0)
+        }
+
+        async fn preference_vector(&self, _: Uuid) -> Result<Vec<f32>> {
+            let mut v = vec![0.0; DIM];
+            v[0] = 1.0;
+            Ok(v)
+        }
+
+        async fn content_embeddings(&self, ids: &[Uuid]) -> Result<HashMap<Uuid, Vec<f32>>> {
+            Ok(ids
+                .iter()
+                .filter_map(|id| self.embeddings.get(id).map(|e| (*id, e.clone())))
+                .collect())
+        }
+    }
+
+    fn activity(user_id: Uuid, n: usize) -> UserActivity {
+        let item = (n % 20) as u128;
+        UserActivity {
+            user_id,
+            event: ViewingEvent {
+                content_id: Uuid::from_u128(item),
+                timestamp: Utc::now() + Duration::minutes(n as i64),
+                completion_rate: if item < 10 { 1.0 } else { 0.05 },
+                rating: None,
+                is_rewatch: false,
+                dismissed: false,
+            },
+        }
+    }
+
+    fn worker(store: Arc<MemoryStore>) -> LoRATrainingWorker {
+        LoRATrainingWorker::new(store, Arc::new(Data::new()), LoRATrainingConfig::default())
+    }
+
+    #[test]
+    fn test_validation_metrics() {
+        let data = Data::new();
+        let mut adapter = UserLoRAAdapter::new(Uuid::nil());
+        adapter.initialize_random();
+        let events: Vec<ViewingEvent> = (0..20).map(|n| activity(Uuid::nil(), n).event).collect();
+        let preference = vec![1.0; DIM];
+
+        let metrics = validate_adapter(&adapter, &events, &data.embeddings, &preference).unwrap();
+        assert_eq!(metrics.samples, 20);
+        assert!(metrics.loss > 0.0);
+        assert!(metrics.auc.is_some());
+
+        assert!(validate_adapter(&adapter, &events, &HashMap::new(), &preference).is_err());
+    }
+
+    #[test]
+    fn test_report_gate() {
+        let metrics = |loss| ValidationMetrics {
+            loss,
+            auc: None,
+            samples: 5,
+        };
+        let report = ValidationReport {
+            candidate: metrics(0.4),
+            baseline: metrics(0.5),
+            baseline_version: Some(1),
+        };
+        assert!(report.passes(0.0));
+        assert!(report.passes(0.1));
+        assert!(!report.passes(0.2));
+    }
+
+    #[tokio::test]
+    async fn test_trains_only_when_triggered() {
+        let store = Arc::new(MemoryStore::default());
+        let mut worker = worker(store.clone());
+        let user_id = Uuid::new_v4();
+
+        // 10 interactions trigger training, but 10 events cannot cover both
+        // the training minimum and a holdout
+        for n in 0..9 {
+            assert_eq!(
+                worker.handle_activity(activity(user_id, n)).await.unwrap(),
+                None
+            );
+        }
+        assert_eq!(
+            worker.handle_activity(activity(user_id, 9)).await.unwrap(),
+            Some(TrainingOutcome::InsufficientData { events: 10 })
+        );
+
+        for n in 10..19 {
+            assert_eq!(
+                worker.handle_activity(activity(user_id, n)).await.unwrap(),
+                None
+            );
+        }
+        let outcome = worker.handle_activity(activity(user_id, 19)).await.unwrap();
+        match outcome {
+            Some(TrainingOutcome::Activated { version, report }) => {
+                assert_eq!(version, 1);
+                assert_eq!(report.baseline_version, None);
+                assert!(report.candidate.loss <= report.baseline.loss);
+                assert_eq!(*store.active.lock().unwrap(), Some(1));
+            }
+            Some(TrainingOutcome::Rejected { version, .. }) => {
+                assert_eq!(version, 1);
+                assert_eq!(*store.active.lock().unwrap(), None);
+            }
+            other => panic!("unexpected outcome {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rejects_worse_candidate() {
+        let store = Arc::new(MemoryStore::default());
+        let config = LoRATrainingConfig {
+            min_loss_improvement: f32::INFINITY,
+            ..Default::default()
+        };
+        let mut worker = LoRATrainingWorker::new(store.clone(), Arc::new(Data::new()), config);
+        let user_id = Uuid::new_v4();
+
+        let mut outcome = None;
+        for n in 0..20 {
+            outcome = worker.handle_activity(activity(user_id, n)).await.unwrap();
+        }
+        assert!(matches!(
+            outcome,
+            Some(TrainingOutcome::Rejected { version: 1, .. })
+        ));
+        assert_eq!(store.versions.lock().unwrap()[0].1, "rejected");
+        assert_eq!(*store.active.lock().unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_rolls_back_regressed_version() {
+        let store = Arc::new(MemoryStore::default());
+        let user_id = Uuid::new_v4();
+
+        // A serving version that predicts the liked cluster well
+        let mut good = UserLoRAAdapter::new(user_id);
+        good.base_layer_weights[[0, 0]] = 1.0;
+        good.base_layer_weights[[1, 1]] = 1.0;
+        good.user_layer_weights[[0, 0]] = 1.0;
+        good.user_layer_weights[[0, 1]] = -1.0;
+        store.versions.lock().unwrap().push((good, "active"));
+        *store.active.lock().unwrap() = Some(1);
+
+        let config = LoRATrainingConfig {
+            // Force activation of whatever is trained
+            min_loss_improvement: f32::NEG_INFINITY,
+            rollback_tolerance: 0.0,
+            ..Default::default()
+        };
+        let mut worker = LoRATrainingWorker::new(store.clone(), Arc::new(Data::new()), config);
+
+        let mut outcome = None;
+        for n in 0..20 {
+            outcome = worker.handle_activity(activity(user_id, n)).await.unwrap();
+        }
+        assert!(matches!(
+            outcome,
+            Some(TrainingOutcome::Activated { version: 2, .. })
+        ));
+
+        // Replace the monitored adapter with one that inverts preferences
+        let state = worker.users.get_mut(&user_id).unwrap();
+        let monitor = state.monitor.as_mut().unwrap();
+        monitor.adapter.user_layer_weights.fill(0.0);
+        monitor.adapter.user_layer_weights[[0, 0]] = -1.0;
+        monitor.adapter.user_layer_weights[[0, 1]] = 1.0;
+
+        let mut outcome = None;
+        for n in 20..25 {
+            outcome = worker.handle_activity(activity(user_id, n)).await.unwrap();
+        }
+        assert_eq!(
+            outcome,
+            Some(TrainingOutcome::RolledBack { from: 2, to: 1 })
+        );
+        assert_eq!(*store.active.lock().unwrap(), Some(1));
+    }
+
+    #[tokio::test]
+    async fn test_evicts_least_recently_active_users() {
+        let config = LoRATrainingConfig {
+            max_cached_users: 2,
+            ..Default::default()
+        };
+        let store = Arc::new(MemoryStore::default());
+        let mut worker = LoRATrainingWorker::new(store, Arc::new(Data::new()), config);
+        let users: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+
+        worker.handle_activity(activity(users[0], 0)).await.unwrap();
+        worker.handle_activity(activity(users[1], 0)).await.unwrap();
+        worker.handle_activity(activity(users[0], 1)).await.unwrap();
+        worker.handle_activity(activity(users[2], 0)).await.unwrap();
+
+        assert_eq!(worker.users.len(), 2);
+        assert!(worker.users.contains_key(&users[0]));
+        assert!(!worker.users.contains_key(&users[1]));
+        assert!(worker.users.contains_key(&users[2]));
+
+        // Idle users go first regardless of order
+        worker.config.idle_timeout = std::time::Duration::ZERO;
+        worker.handle_activity(activity(users[1], 1)).await.unwrap();
+        assert_eq!(worker.users.len(), 1);
+        assert!(worker.users.contains_key(&users[1]));
+    }
+}
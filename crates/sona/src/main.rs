@@ -4,28 +4,17 @@
 //! SLA: 99.9% availability
 //! Latency target: <5ms personalization, <200ms recommendations
 
-use actix_web::{web, App, HttpResponse, HttpServer};
-use tracing::info;
+mod server;
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     tracing_subscriber::fmt()
-        .with_max_level(tracing::Level::INFO)
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
+        )
         .json()
         .init();
 
-    info!("Starting SONA Engine on port 8082");
-
-    HttpServer::new(|| App::new().route("/health", web::get().to(health_check)))
-        .bind(("0.0.0.0", 8082))?
-        .run()
-        .await
-}
-
-async fn health_check() -> HttpResponse {
-    HttpResponse::Ok().json(serde_json::json!({
-        "status": "healthy",
-        "service": "sona-engine",
-        "version": env!("CARGO_PKG_VERSION")
-    }))
+    server::run().await
 }
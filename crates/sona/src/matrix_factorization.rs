@@ -1,13 +1,42 @@
 //! Matrix Factorization using Alternating Least Squares (ALS)
 //!
-//! Implements ALS algorithm for collaborative filtering with implicit feedback.
-//! Decomposes user-item interaction matrix into user and item latent factors.
+//! Implements implicit-feedback ALS (Hu, Koren & Volinsky) for collaborative
+//! filtering. Decomposes user-item interaction matrix into user and item
+//! latent factors.
+//!
+//! Training works on compressed sparse rows: interactions are compressed
+//! into a user-major (CSR) and an item-major (CSC) matrix once, and each
+//! half-step solves every row with a few warm-started conjugate-gradient
+//! steps instead of a dense Cholesky factorisation. Rows are independent, so
+//! both half-steps run in parallel across the rayon thread pool.
+//!
+//! Optionally a fraction of interactions is held out to measure sampled AUC
+//! after every iteration; training stops once it stops improving and keeps
+//! the best factors. The best factors so far can be checkpointed to disk
+//! as training goes and restored with [`MatrixFactorization::load_checkpoint`].
+//!
+//! `examples/als_benchmark.rs` measures training time on synthetic data.
 
 use anyhow::{Context, Result};
 use ndarray::{Array1, Array2};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
 use uuid::Uuid;
 
+/// Checkpoint file format version
+const CHECKPOINT_VERSION: u32 = 1;
+
+/// File name of the training checkpoint inside `checkpoint_dir`
+pub const CHECKPOINT_FILE: &str = "als-checkpoint.bin";
+
+/// Negative items sampled per held-out interaction when estimating AUC
+const VALIDATION_NEGATIVES: usize = 10;
+
 /// ALS configuration parameters
 #[derive(Debug, Clone)]
 pub struct ALSConfig {
@@ -19,6 +48,18 @@ pub struct ALSConfig {
     pub iterations: usize,
     /// Confidence scaling for implicit feedback
     pub alpha: f32,
+    /// Conjugate-gradient steps per row solve
+    pub cg_steps: usize,
+    /// Fraction of interactions held out for early stopping (0.0 disables)
+    pub validation_fraction: f32,
+    /// Iterations without validation improvement before stopping
+    pub early_stopping_patience: usize,
+    /// Minimum AUC gain that counts as an improvement
+    pub early_stopping_min_delta: f32,
+    /// Directory for checkpoints of the best factors so far, if any
+    pub checkpoint_dir: Option<PathBuf>,
+    /// Seed for factor initialisation and the validation split
+    pub seed: u64,
 }
 
 impl Default for ALSConfig {
@@ -28,15 +69,24 @@ impl Default for ALSConfig {
             regularization: 0.1,
             iterations: 10,
             alpha: 40.0,
+            cg_steps: 3,
+            validation_fraction: 0.0,
+            early_stopping_patience: 2,
+            early_stopping_min_delta: 1e-4,
+            checkpoint_dir: None,
+            seed: 42,
         }
     }
 }
 
 /// Sparse user-item interaction matrix
+///
+/// A coordinate-list builder; training compresses it with [`Self::to_csr`]
+/// and [`Self::to_csc`]. Inserting the same cell twice keeps the last value.
 #[derive(Debug, Clone)]
 pub struct SparseMatrix {
-    /// (user_index, item_index) -> rating
-    pub entries: HashMap<(usize, usize), f32>,
+    /// (user_index, item_index, rating) in insertion order
+    triplets: Vec<(u32, u32, f32)>,
     pub num_users: usize,
     pub num_items: usize,
 }
@@ -44,20 +94,76 @@ pub struct SparseMatrix {
 impl SparseMatrix {
     pub fn new() -> Self {
         Self {
-            entries: HashMap::new(),
+            triplets: Vec::new(),
+            num_users: 0,
+            num_items: 0,
+        }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            triplets: Vec::with_capacity(capacity),
             num_users: 0,
             num_items: 0,
         }
     }
 
     pub fn insert(&mut self, user_idx: usize, item_idx: usize, value: f32) {
-        self.entries.insert((user_idx, item_idx), value);
+        self.triplets
+            .push((user_idx as u32, item_idx as u32, value));
         self.num_users = self.num_users.max(user_idx + 1);
         self.num_items = self.num_items.max(item_idx + 1);
     }
 
+    /// Look up a single cell; linear in the number of entries
     pub fn get(&self, user_idx: usize, item_idx: usize) -> f32 {
-        *self.entries.get(&(user_idx, item_idx)).unwrap_or(&0.0)
+        self.triplets
+            .iter()
+            .rev()
+            .find(|(u, i, _)| *u as usize == user_idx && *i as usize == item_idx)
+            .map_or(0.0, |(_, _, v)| *v)
+    }
+
+    /// Number of stored entries, counting duplicates
+    pub fn len(&self) -> usize {
+        self.triplets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.triplets.is_empty()
+    }
+
+    /// User-major compressed form
+    pub fn to_csr(&self) -> CsrMatrix {
+        CsrMatrix::compress(self.num_users, self.num_items, &self.triplets, false)
+    }
+
+    /// Item-major compressed form
+    pub fn to_csc(&self) -> CsrMatrix {
+        CsrMatrix::compress(self.num_items, self.num_users, &self.triplets, true)
+    }
+
+    /// Split entries into (train, validation), sending each cell to the
+    /// validation side with probability `fraction`
+    ///
+    /// The split hashes the cell, so it is stable for a given seed.
+    pub fn split(&self, fraction: f32, seed: u64) -> (SparseMatrix, SparseMatrix) {
+        let mut train = SparseMatrix::with_capacity(self.triplets.len());
+        let mut validation = SparseMatrix::new();
+        for &(u, i, v) in &self.triplets {
+            let cell = ((u as u64) << 32) | i as u64;
+            if unit_hash(cell ^ seed.rotate_left(17)) < fraction as f64 {
+                validation.triplets.push((u, i, v));
+            } else {
+                train.triplets.push((u, i, v));
+            }
+        }
+        // Both halves keep the full shape so indices line up
+        for half in [&mut train, &mut validation] {
+            half.num_users = self.num_users;
+            half.num_items = self.num_items;
+        }
+        (train, validation)
     }
 }
 
@@ -67,6 +173,115 @@ impl Default for SparseMatrix {
     }
 }
 
+/// Compressed sparse rows with sorted, unique column indices
+#[derive(Debug, Clone)]
+pub struct CsrMatrix {
+    pub rows: usize,
+    pub cols: usize,
+    indptr: Vec<usize>,
+    indices: Vec<u32>,
+    values: Vec<f32>,
+}
+
+impl CsrMatrix {
+    fn compress(rows: usize, cols: usize, triplets: &[(u32, u32, f32)], transpose: bool) -> Self {
+        let key = |t: &(u32, u32, f32)| {
+            if transpose {
+                (t.1 as usize, t.0)
+            } else {
+                (t.0 as usize, t.1)
+            }
+        };
+
+        // Counting sort by row keeps insertion order within a row
+        let mut counts = vec![0usize; rows + 1];
+        for t in triplets {
+            counts[key(t).0 + 1] += 1;
+        }
+        for r in 0..rows {
+            counts[r + 1] += counts[r];
+        }
+        let mut next = counts.clone();
+        let mut staged = vec![(0u32, 0f32); triplets.len()];
+        for t in triplets {
+            let (row, col) = key(t);
+            staged[next[row]] = (col, t.2);
+            next[row] += 1;
+        }
+
+        let mut indptr = Vec::with_capacity(rows + 1);
+        let mut indices = Vec::with_capacity(triplets.len());
+        let mut values = Vec::with_capacity(triplets.len());
+        indptr.push(0);
+        for r in 0..rows {
+            let segment = &mut staged[counts[r]..counts[r + 1]];
+            // Stable, so among duplicates the last inserted stays last
+            segment.sort_by_key(|(col, _)| *col);
+            let row_start = indices.len();
+            for &(col, value) in segment.iter() {
+                if indices.len() > row_start && indices[indices.len() - 1] == col {
+                    *values.last_mut().expect("non-empty row") = value;
+                } else {
+                    indices.push(col);
+                    values.push(value);
+                }
+            }
+            indptr.push(indices.len());
+        }
+
+        Self {
+            rows,
+            cols,
+            indptr,
+            indices,
+            values,
+        }
+    }
+
+    /// Column indices and values of a row
+    pub fn row(&self, r: usize) -> (&[u32], &[f32]) {
+        let range = self.indptr[r]..self.indptr[r + 1];
+        (&self.indices[range.clone()], &self.values[range])
+    }
+
+    /// Number of stored (unique) entries
+    pub fn nnz(&self) -> usize {
+        self.indices.len()
+    }
+
+    pub fn get(&self, r: usize, c: usize) -> f32 {
+        let (indices, values) = self.row(r);
+        indices
+            .binary_search(&(c as u32))
+            .map_or(0.0, |pos| values[pos])
+    }
+}
+
+/// Outcome of a training run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TrainingReport {
+    /// Iterations actually run
+    pub iterations: usize,
+    /// Sampled validation AUC after each iteration, when validating
+    pub validation_auc: Vec<f32>,
+    /// Iteration (1-based) whose factors were kept
+    pub best_iteration: Option<usize>,
+    pub stopped_early: bool,
+    pub elapsed_ms: u64,
+}
+
+/// On-disk model snapshot
+#[derive(Serialize, Deserialize)]
+struct Checkpoint {
+    version: u32,
+    iteration: usize,
+    latent_factors: usize,
+    user_ids: Vec<Uuid>,
+    item_ids: Vec<Uuid>,
+    user_factors: Vec<f32>,
+    item_factors: Vec<f32>,
+}
+
 /// ALS-based matrix factorization
 pub struct MatrixFactorization {
     config: ALSConfig,
@@ -99,7 +314,7 @@ impl MatrixFactorization {
 
     /// Build sparse matrix from user-item interactions
     pub fn build_matrix(&mut self, interactions: Vec<(Uuid, Uuid, f32)>) -> Result<SparseMatrix> {
-        let mut matrix = SparseMatrix::new();
+        let mut matrix = SparseMatrix::with_capacity(interactions.len());
         self.user_id_map.clear();
         self.item_id_map.clear();
         self.user_index_map.clear();
@@ -132,240 +347,349 @@ impl MatrixFactorization {
     }
 
     /// Train ALS model on sparse matrix
-    pub fn fit(&mut self, matrix: &SparseMatrix) -> Result<()> {
+    ///
+    /// Holds out `validation_fraction` of the interactions for early stopping
+    /// when configured.
+    pub fn fit(&mut self, matrix: &SparseMatrix) -> Result<TrainingReport> {
+        if self.config.validation_fraction > 0.0 {
+            let (train, validation) =
+                matrix.split(self.config.validation_fraction, self.config.seed);
+            self.fit_with_validation(&train, Some(&validation))
+        } else {
+            self.fit_with_validation(matrix, None)
+        }
+    }
+
+    /// Train on `train`, early-stopping on sampled AUC over `validation`
+    pub fn fit_with_validation(
+        &mut self,
+        train: &SparseMatrix,
+        validation: Option<&SparseMatrix>,
+    ) -> Result<TrainingReport> {
+        let start = Instant::now();
         let k = self.config.latent_factors;
-        let lambda = self.config.regularization as f64;
-        let alpha = self.config.alpha;
+        anyhow::ensure!(k > 0, "latent_factors must be positive");
+
+        let user_items = train.to_csr();
+        let item_users = train.to_csc();
+        let validation = validation.filter(|v| !v.is_empty()).map(|v| v.to_csr());
 
         // Initialize user and item factors randomly
-        let mut user_factors = Array2::<f32>::zeros((matrix.num_users, k));
-        let mut item_factors = Array2::<f32>::zeros((matrix.num_items, k));
-
-        // Random initialization
-        use rand::Rng;
-        let mut rng = rand::thread_rng();
-        for i in 0..matrix.num_users {
-            for j in 0..k {
-                user_factors[[i, j]] = rng.gen_range(-0.1..0.1);
-            }
-        }
-        for i in 0..matrix.num_items {
-            for j in 0..k {
-                item_factors[[i, j]] = rng.gen_range(-0.1..0.1);
-            }
-        }
+        let mut rng = StdRng::seed_from_u64(self.config.seed);
+        let scale = 0.1 / (k as f32).sqrt();
+        let mut user_factors =
+            Array2::from_shape_fn((train.num_users, k), |_| rng.gen_range(-scale..scale));
+        let mut item_factors =
+            Array2::from_shape_fn((train.num_items, k), |_| rng.gen_range(-scale..scale));
+
+        let mut report = TrainingReport::default();
+        let mut best: Option<(f32, Array2<f32>, Array2<f32>)> = None;
+        let mut since_improvement = 0;
 
         // ALS iterations
-        for iteration in 0..self.config.iterations {
-            // Update user factors
-            for u in 0..matrix.num_users {
-                let user_items: Vec<(usize, f32)> = matrix
-                    .entries
-                    .iter()
-                    .filter(|((user_idx, _), _)| *user_idx == u)
-                    .map(|((_, item_idx), &rating)| (*item_idx, rating))
-                    .collect();
-
-                if !user_items.is_empty() {
-                    user_factors.row_mut(u).assign(&self.solve_user(
-                        u,
-                        &user_items,
-                        &item_factors,
-                        lambda,
-                        alpha,
-                    )?);
+        for iteration in 1..=self.config.iterations {
+            let iteration_start = Instant::now();
+            self.solve_half_step(&mut user_factors, &item_factors, &user_items);
+            self.solve_half_step(&mut item_factors, &user_factors, &item_users);
+            report.iterations = iteration;
+
+            // Without validation every iteration is the best so far
+            let mut improved = true;
+            if let Some(validation) = &validation {
+                let auc = sampled_auc(
+                    &user_factors,
+                    &item_factors,
+                    &user_items,
+                    validation,
+                    self.config.seed.wrapping_add(iteration as u64),
+                );
+                report.validation_auc.push(auc);
+                tracing::debug!(
+                    iteration,
+                    auc,
+                    elapsed_ms = iteration_start.elapsed().as_millis() as u64,
+                    "ALS iteration"
+                );
+
+                let best_auc = best.as_ref().map_or(f32::NEG_INFINITY, |b| b.0);
+                improved = auc > best_auc + self.config.early_stopping_min_delta;
+                if improved {
+                    best = Some((auc, user_factors.clone(), item_factors.clone()));
+                    report.best_iteration = Some(iteration);
+                    since_improvement = 0;
+                } else {
+                    since_improvement += 1;
+                    if since_improvement >= self.config.early_stopping_patience {
+                        report.stopped_early = true;
+                    }
                 }
+            } else {
+                tracing::debug!(
+                    iteration,
+                    elapsed_ms = iteration_start.elapsed().as_millis() as u64,
+                    "ALS iteration"
+                );
             }
 
-            // Update item factors
-            for i in 0..matrix.num_items {
-                let item_users: Vec<(usize, f32)> = matrix
-                    .entries
-                    .iter()
-                    .filter(|((_, item_idx), _)| *item_idx == i)
-                    .map(|((user_idx, _), &rating)| (*user_idx, rating))
-                    .collect();
-
-                if !item_users.is_empty() {
-                    item_factors.row_mut(i).assign(&self.solve_item(
-                        i,
-                        &item_users,
-                        &user_factors,
-                        lambda,
-                        alpha,
-                    )?);
-                }
+            // The checkpoint always holds the best factors, the ones training
+            // ends up keeping
+            if let (true, Some(dir)) = (improved, &self.config.checkpoint_dir) {
+                self.write_checkpoint(
+                    &dir.join(CHECKPOINT_FILE),
+                    iteration,
+                    &user_factors,
+                    &item_factors,
+                )?;
             }
 
-            if iteration % 2 == 0 {
-                let loss = self.compute_loss(&matrix, &user_factors, &item_factors);
-                tracing::debug!("ALS iteration {}: loss = {:.4}", iteration, loss);
+            if report.stopped_early {
+                break;
             }
         }
 
+        match best {
+            Some((_, best_users, best_items)) => {
+                user_factors = best_users;
+                item_factors = best_items;
+            }
+            None => report.best_iteration = Some(report.iterations),
+        }
+
         self.user_factors = Some(user_factors);
         self.item_factors = Some(item_factors);
 
-        Ok(())
+        report.elapsed_ms = start.elapsed().as_millis() as u64;
+        tracing::info!(
+            users = train.num_users,
+            items = train.num_items,
+            interactions = user_items.nnz(),
+            iterations = report.iterations,
+            best_iteration = ?report.best_iteration,
+            stopped_early = report.stopped_early,
+            elapsed_ms = report.elapsed_ms,
+            "ALS training finished"
+        );
+
+        Ok(report)
     }
 
-    /// Solve least squares system A * x = b using Cholesky decomposition
-    /// For positive definite matrix A (which we guarantee by adding regularization)
-    fn solve_least_squares(a: &Array2<f64>, b: &Array1<f64>) -> Result<Array1<f64>> {
-        let n = a.nrows();
-
-        // Perform Cholesky decomposition: A = L * L^T
-        let mut l = Array2::<f64>::zeros((n, n));
-
-        for i in 0..n {
-            for j in 0..=i {
-                let mut sum = 0.0;
-                for k in 0..j {
-                    sum += l[[i, k]] * l[[j, k]];
-                }
-
-                if i == j {
-                    let diag = a[[i, i]] - sum;
-                    if diag <= 0.0 {
-                        anyhow::bail!("Matrix is not positive definite");
-                    }
-                    l[[i, j]] = diag.sqrt();
-                } else {
-                    l[[i, j]] = (a[[i, j]] - sum) / l[[j, j]];
-                }
-            }
-        }
-
-        // Forward substitution: L * y = b
-        let mut y = Array1::<f64>::zeros(n);
-        for i in 0..n {
-            let mut sum = 0.0;
-            for j in 0..i {
-                sum += l[[i, j]] * y[j];
-            }
-            y[i] = (b[i] - sum) / l[[i, i]];
-        }
-
-        // Backward substitution: L^T * x = y
-        let mut x = Array1::<f64>::zeros(n);
-        for i in (0..n).rev() {
-            let mut sum = 0.0;
-            for j in (i + 1)..n {
-                sum += l[[j, i]] * x[j];
-            }
-            x[i] = (y[i] - sum) / l[[i, i]];
+    /// Update every row of `factors` against the fixed `other` factors
+    ///
+    /// Row `r` minimises the implicit-ALS objective
+    /// `(OᵀO + Oᵀ(Cᵣ − I)O + λI) x = OᵀCᵣp`, with confidence
+    /// `c = 1 + alpha * rating` and preference `p = 1` on observed entries.
+    /// `OᵀO` is shared by all rows, so each solve only touches the row's
+    /// own entries.
+    fn solve_half_step(&self, factors: &mut Array2<f32>, other: &Array2<f32>, rows: &CsrMatrix) {
+        let k = self.config.latent_factors;
+        let mut gram = other.t().dot(other);
+        for d in 0..k {
+            gram[[d, d]] += self.config.regularization;
         }
+        let gram: Vec<f32> = gram.iter().copied().collect();
 
-        Ok(x)
+        let other = other
+            .as_slice()
+            .expect("factor matrices are in standard layout");
+        let alpha = self.config.alpha;
+        let cg_steps = self.config.cg_steps.max(1);
+
+        factors
+            .as_slice_mut()
+            .expect("factor matrices are in standard layout")
+            .par_chunks_mut(k)
+            .enumerate()
+            .for_each_init(
+                || CgBuffers::new(k),
+                |buffers, (r, x)| {
+                    let (indices, values) = rows.row(r);
+                    if indices.is_empty() {
+                        x.fill(0.0);
+                        return;
+                    }
+                    conjugate_gradient(x, &gram, other, indices, values, alpha, cg_steps, buffers);
+                },
+            );
     }
 
-    /// Solve for user factors (least squares)
-    fn solve_user(
+    /// Write a checkpoint atomically (temp file, then rename)
+    fn write_checkpoint(
         &self,
-        _user_idx: usize,
-        user_items: &[(usize, f32)],
+        path: &Path,
+        iteration: usize,
+        user_factors: &Array2<f32>,
         item_factors: &Array2<f32>,
-        lambda: f64,
-        alpha: f32,
-    ) -> Result<Array1<f32>> {
-        let k = self.config.latent_factors;
-        let mut a = Array2::<f64>::zeros((k, k));
-        let mut b = Array1::<f64>::zeros(k);
-
-        // Build A and b for least squares: A * x = b
-        for (item_idx, rating) in user_items {
-            let item_vec = item_factors.row(*item_idx);
-            let confidence = 1.0 + alpha * rating;
-
-            // A += confidence * item_vec^T * item_vec
-            for i in 0..k {
-                for j in 0..k {
-                    a[[i, j]] += (confidence * item_vec[i] * item_vec[j]) as f64;
-                }
-            }
-
-            // b += confidence * rating * item_vec
-            for i in 0..k {
-                b[i] += (confidence * rating * item_vec[i]) as f64;
-            }
+    ) -> Result<()> {
+        let checkpoint = Checkpoint {
+            version: CHECKPOINT_VERSION,
+            iteration,
+            latent_factors: self.config.latent_factors,
+            user_ids: ordered_ids(&self.user_index_map, user_factors.nrows()),
+            item_ids: ordered_ids(&self.item_index_map, item_factors.nrows()),
+            user_factors: user_factors.iter().copied().collect(),
+            item_factors: item_factors.iter().copied().collect(),
+        };
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create checkpoint dir {}", dir.display()))?;
         }
-
-        // Add regularization: A += lambda * I
-        for i in 0..k {
-            a[[i, i]] += lambda;
-        }
-
-        // Solve A * x = b using least squares
-        let x = Self::solve_least_squares(&a, &b)
-            .context("Failed to solve least squares for user factors")?;
-
-        Ok(x.mapv(|v| v as f32))
+        let bytes = bincode::serialize(&checkpoint).context("Failed to serialize checkpoint")?;
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, bytes)
+            .with_context(|| format!("Failed to write checkpoint {}", tmp.display()))?;
+        std::fs::rename(&tmp, path)
+            .with_context(|| format!("Failed to move checkpoint to {}", path.display()))?;
+
+        tracing::debug!(iteration, path = %path.display(), "Wrote ALS checkpoint");
+        Ok(())
     }
 
-    /// Solve for item factors (least squares)
-    fn solve_item(
-        &self,
-        _item_idx: usize,
-        item_users: &[(usize, f32)],
-        user_factors: &Array2<f32>,
-        lambda: f64,
-        alpha: f32,
-    ) -> Result<Array1<f32>> {
-        let k = self.config.latent_factors;
-        let mut a = Array2::<f64>::zeros((k, k));
-        let mut b = Array1::<f64>::zeros(k);
-
-        // Build A and b for least squares: A * x = b
-        for (user_idx, rating) in item_users {
-            let user_vec = user_factors.row(*user_idx);
-            let confidence = 1.0 + alpha * rating;
-
-            // A += confidence * user_vec^T * user_vec
-            for i in 0..k {
-                for j in 0..k {
-                    a[[i, j]] += (confidence * user_vec[i] * user_vec[j]) as f64;
-                }
-            }
+    /// Save the trained model
+    pub fn save_checkpoint(&self, path: impl AsRef<Path>) -> Result<()> {
+        let user_factors = self
+            .user_factors
+            .as_ref()
+            .context("Model not trained yet")?;
+        let item_factors = self
+            .item_factors
+            .as_ref()
+            .context("Model not trained yet")?;
+        self.write_checkpoint(path.as_ref(), 0, user_factors, item_factors)
+    }
 
-            // b += confidence * rating * user_vec
-            for i in 0..k {
-                b[i] += (confidence * rating * user_vec[i]) as f64;
-            }
+    /// Restore a model saved by [`Self::save_checkpoint`] or written during training
+    pub fn load_checkpoint(path: impl AsRef<Path>, config: ALSConfig) -> Result<Self> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path)
+            .with_context(|| format!("Failed to read checkpoint {}", path.display()))?;
+        let checkpoint: Checkpoint =
+            bincode::deserialize(&bytes).context("Failed to deserialize checkpoint")?;
+
+        anyhow::ensure!(
+            checkpoint.version == CHECKPOINT_VERSION,
+            "Unsupported checkpoint version {}",
+            checkpoint.version
+        );
+        let k = checkpoint.latent_factors;
+        let user_factors =
+            Array2::from_shape_vec((checkpoint.user_ids.len(), k), checkpoint.user_factors)
+                .context("Corrupt user factors in checkpoint")?;
+        let item_factors =
+            Array2::from_shape_vec((checkpoint.item_ids.len(), k), checkpoint.item_factors)
+                .context("Corrupt item factors in checkpoint")?;
+
+        let mut model = Self::new(ALSConfig {
+            latent_factors: k,
+            ..config
+        });
+        for (idx, id) in checkpoint.user_ids.into_iter().enumerate() {
+            model.user_id_map.insert(id, idx);
+            model.user_index_map.insert(idx, id);
         }
-
-        // Add regularization: A += lambda * I
-        for i in 0..k {
-            a[[i, i]] += lambda;
+        for (idx, id) in checkpoint.item_ids.into_iter().enumerate() {
+            model.item_id_map.insert(id, idx);
+            model.item_index_map.insert(idx, id);
         }
+        model.user_factors = Some(user_factors);
+        model.item_factors = Some(item_factors);
+        Ok(model)
+    }
 
-        // Solve A * x = b using least squares
-        let x = Self::solve_least_squares(&a, &b)
-            .context("Failed to solve least squares for item factors")?;
-
-        Ok(x.mapv(|v| v as f32))
+    /// Regularized Gram matrix `YᵀY + λI` of the item factors, row-major
+    ///
+    /// Every fold-in against the same item factors shares it, so compute it
+    /// once per trained model.
+    pub fn item_gram(&self) -> Result<Vec<f32>> {
+        let item_factors = self
+            .item_factors
+            .as_ref()
+            .context("Model not trained yet")?;
+        let mut gram = item_factors.t().dot(item_factors);
+        for d in 0..gram.nrows() {
+            gram[[d, d]] += self.config.regularization;
+        }
+        Ok(gram.iter().copied().collect())
     }
 
-    /// Compute reconstruction loss
-    fn compute_loss(
-        &self,
-        matrix: &SparseMatrix,
-        user_factors: &Array2<f32>,
-        item_factors: &Array2<f32>,
-    ) -> f32 {
-        let mut loss = 0.0;
-        let mut count = 0;
+    /// Fold a user's interactions into the model without retraining
+    ///
+    /// Re-solves the user's row of the implicit-ALS objective against the
+    /// fixed item factors, warm-started from the current row, and adds users
+    /// missing from the training set. `interactions` should hold the user's
+    /// full aggregated history, not just new events. Items the model has not
+    /// seen are ignored; returns how many interactions were used.
+    pub fn fold_in_user(
+        &mut self,
+        user_id: Uuid,
+        interactions: &[(Uuid, f32)],
+        item_gram: &[f32],
+    ) -> Result<usize> {
+        let k = self.config.latent_factors;
+        anyhow::ensure!(
+            item_gram.len() == k * k,
+            "Item Gram matrix has {} entries, expected {}",
+            item_gram.len(),
+            k * k
+        );
+        let item_factors = self
+            .item_factors
+            .as_ref()
+            .context("Model not trained yet")?;
+        let user_factors = self
+            .user_factors
+            .as_mut()
+            .context("Model not trained yet")?;
 
-        for ((u, i), &rating) in &matrix.entries {
-            let prediction = user_factors.row(*u).dot(&item_factors.row(*i));
-            loss += (rating - prediction).powi(2);
-            count += 1;
-        }
+        let mut entries: Vec<(u32, f32)> = interactions
+            .iter()
+            .filter_map(|(item_id, rating)| {
+                self.item_id_map
+                    .get(item_id)
+                    .map(|&idx| (idx as u32, *rating))
+            })
+            .collect();
+        entries.sort_unstable_by_key(|(idx, _)| *idx);
+        entries.dedup_by_key(|(idx, _)| *idx);
+        let (indices, values): (Vec<u32>, Vec<f32>) = entries.into_iter().unzip();
+
+        let user_idx = match self.user_id_map.get(&user_id) {
+            Some(&idx) => idx,
+            None => {
+                let idx = user_factors.nrows();
+                user_factors
+                    .push_row(Array1::zeros(k).view())
+                    .context("Failed to add user row")?;
+                self.user_id_map.insert(user_id, idx);
+                self.user_index_map.insert(idx, user_id);
+                idx
+            }
+        };
 
-        if count > 0 {
-            loss / count as f32
+        let mut row = user_factors.row(user_idx).to_vec();
+        if indices.is_empty() {
+            row.fill(0.0);
         } else {
-            0.0
+            let other = item_factors
+                .as_slice()
+                .expect("factor matrices are in standard layout");
+            // A single row is cheap, so solve it to (near) convergence
+            conjugate_gradient(
+                &mut row,
+                item_gram,
+                other,
+                &indices,
+                &values,
+                self.config.alpha,
+                k.max(self.config.cg_steps),
+                &mut CgBuffers::new(k),
+            );
         }
+        user_factors
+            .row_mut(user_idx)
+            .assign(&Array1::from_vec(row));
+
+        Ok(indices.len())
     }
 
     /// Predict rating for user-item pair
@@ -441,10 +765,200 @@ impl MatrixFactorization {
     }
 }
 
+/// Scratch vectors reused across the rows a worker solves
+struct CgBuffers {
+    r: Array1<f32>,
+    p: Array1<f32>,
+    ap: Array1<f32>,
+}
+
+impl CgBuffers {
+    fn new(k: usize) -> Self {
+        Self {
+            r: Array1::zeros(k),
+            p: Array1::zeros(k),
+            ap: Array1::zeros(k),
+        }
+    }
+}
+
+/// Warm-started conjugate gradient for one row of implicit ALS
+#[allow(clippy::too_many_arguments)]
+fn conjugate_gradient(
+    x: &mut [f32],
+    gram: &[f32],
+    other: &[f32],
+    indices: &[u32],
+    values: &[f32],
+    alpha: f32,
+    steps: usize,
+    buffers: &mut CgBuffers,
+) {
+    let k = x.len();
+    let factor = |i: u32| &other[i as usize * k..(i as usize + 1) * k];
+    let CgBuffers { r, p, ap } = buffers;
+    let r = r.as_slice_mut().expect("contiguous");
+    let p = p.as_slice_mut().expect("contiguous");
+    let ap = ap.as_slice_mut().expect("contiguous");
+
+    // r = b - A x, where b = Σ cᵢ yᵢ and A x = G x + Σ (cᵢ - 1)(yᵢ·x) yᵢ
+    symmetric_mul(gram, x, r);
+    for v in r.iter_mut() {
+        *v = -*v;
+    }
+    for (&i, &rating) in indices.iter().zip(values) {
+        let y = factor(i);
+        let confidence = 1.0 + alpha * rating;
+        let weight = confidence - (confidence - 1.0) * dot(y, x);
+        axpy(weight, y, r);
+    }
+
+    p.copy_from_slice(r);
+    let mut rs_old = dot(r, r);
+
+    for _ in 0..steps {
+        if rs_old < 1e-20 {
+            break;
+        }
+
+        symmetric_mul(gram, p, ap);
+        for (&i, &rating) in indices.iter().zip(values) {
+            let y = factor(i);
+            axpy(alpha * rating * dot(y, p), y, ap);
+        }
+
+        let step = rs_old / dot(p, ap);
+        axpy(step, p, x);
+        axpy(-step, ap, r);
+
+        let rs_new = dot(r, r);
+        let beta = rs_new / rs_old;
+        for (pv, &rv) in p.iter_mut().zip(r.iter()) {
+            *pv = rv + beta * *pv;
+        }
+        rs_old = rs_new;
+    }
+}
+
+fn symmetric_mul(matrix: &[f32], v: &[f32], out: &mut [f32]) {
+    let k = v.len();
+    for (row, o) in matrix.chunks_exact(k).zip(out.iter_mut()) {
+        *o = dot(row, v);
+    }
+}
+
+/// Dot product with independent partial sums so the loop vectorizes
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    let mut lanes = [0.0f32; 8];
+    let a_chunks = a.chunks_exact(8);
+    let b_chunks = b.chunks_exact(8);
+    let tail: f32 = a_chunks
+        .remainder()
+        .iter()
+        .zip(b_chunks.remainder())
+        .map(|(x, y)| x * y)
+        .sum();
+    for (x, y) in a_chunks.zip(b_chunks) {
+        for lane in 0..8 {
+            lanes[lane] += x[lane] * y[lane];
+        }
+    }
+    lanes.iter().sum::<f32>() + tail
+}
+
+fn axpy(scale: f32, x: &[f32], y: &mut [f32]) {
+    for (yv, &xv) in y.iter_mut().zip(x) {
+        *yv += scale * xv;
+    }
+}
+
+/// Sampled AUC: how often a held-out item outranks random unseen items
+fn sampled_auc(
+    user_factors: &Array2<f32>,
+    item_factors: &Array2<f32>,
+    train: &CsrMatrix,
+    validation: &CsrMatrix,
+    seed: u64,
+) -> f32 {
+    let num_items = item_factors.nrows();
+    if num_items < 2 {
+        return 0.0;
+    }
+
+    let (wins, trials) = (0..validation.rows)
+        .into_par_iter()
+        .map(|u| {
+            let (held_out, _) = validation.row(u);
+            if held_out.is_empty() {
+                return (0u64, 0u64);
+            }
+            let (seen, _) = train.row(u);
+            let user = user_factors.row(u);
+            let mut state = seed ^ (u as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+            let (mut wins, mut trials) = (0u64, 0u64);
+
+            for &positive in held_out {
+                let positive_score = user.dot(&item_factors.row(positive as usize));
+                for _ in 0..VALIDATION_NEGATIVES {
+                    let negative = (splitmix64(&mut state) % num_items as u64) as u32;
+                    if negative == positive
+                        || seen.binary_search(&negative).is_ok()
+                        || held_out.binary_search(&negative).is_ok()
+                    {
+                        continue;
+                    }
+                    let negative_score = user.dot(&item_factors.row(negative as usize));
+                    trials += 1;
+                    if positive_score > negative_score {
+                        wins += 1;
+                    }
+                }
+            }
+            (wins, trials)
+        })
+        .reduce(|| (0, 0), |a, b| (a.0 + b.0, a.1 + b.1));
+
+    if trials == 0 {
+        0.0
+    } else {
+        wins as f32 / trials as f32
+    }
+}
+
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Uniform value in [0, 1) derived from `key`
+fn unit_hash(key: u64) -> f64 {
+    let mut state = key;
+    (splitmix64(&mut state) >> 11) as f64 / (1u64 << 53) as f64
+}
+
+fn ordered_ids(index_map: &HashMap<usize, Uuid>, len: usize) -> Vec<Uuid> {
+    (0..len)
+        .map(|idx| index_map.get(&idx).copied().unwrap_or_else(Uuid::nil))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn config(latent_factors: usize, iterations: usize, alpha: f32) -> ALSConfig {
+        ALSConfig {
+            latent_factors,
+            regularization: 0.1,
+            iterations,
+            alpha,
+            ..Default::default()
+        }
+    }
+
     #[test]
     fn test_sparse_matrix() {
         let mut matrix = SparseMatrix::new();
@@ -460,6 +974,58 @@ mod tests {
         assert_eq!(matrix.get(1, 1), 0.0);
     }
 
+    #[test]
+    fn test_compressed_forms() {
+        let mut matrix = SparseMatrix::new();
+        matrix.insert(1, 2, 1.0);
+        matrix.insert(0, 1, 2.0);
+        matrix.insert(1, 0, 3.0);
+        matrix.insert(1, 2, 4.0); // overwrites (1, 2)
+
+        let csr = matrix.to_csr();
+        assert_eq!(csr.nnz(), 3);
+        assert_eq!(csr.row(1), (&[0u32, 2][..], &[3.0f32, 4.0][..]));
+        assert_eq!(csr.get(1, 2), 4.0);
+        assert_eq!(csr.get(0, 0), 0.0);
+
+        let csc = matrix.to_csc();
+        assert_eq!(csc.rows, 3);
+        assert_eq!(csc.row(2), (&[1u32][..], &[4.0f32][..]));
+        assert_eq!(csc.get(0, 1), 3.0);
+    }
+
+    #[test]
+    fn test_split_is_stable_and_complete() {
+        let mut matrix = SparseMatrix::new();
+        for u in 0..50 {
+            for i in 0..20 {
+                matrix.insert(u, i, 1.0);
+            }
+        }
+
+        let (train, validation) = matrix.split(0.2, 7);
+        assert_eq!(train.len() + validation.len(), 1000);
+        assert!(validation.len() > 120 && validation.len() < 280);
+        assert_eq!(validation.num_users, 50);
+
+        let (_, again) = matrix.split(0.2, 7);
+        assert_eq!(again.triplets, validation.triplets);
+    }
+
+    #[test]
+    fn test_conjugate_gradient_matches_direct_solve() {
+        // One row against two orthogonal item factors: the system is diagonal
+        let other = vec![1.0, 0.0, 0.0, 1.0];
+        let gram = vec![1.1, 0.0, 0.0, 1.1]; // YᵀY + 0.1 I
+        let mut x = vec![0.0, 0.0];
+        let mut buffers = CgBuffers::new(2);
+        conjugate_gradient(&mut x, &gram, &other, &[0], &[1.0], 4.0, 5, &mut buffers);
+
+        // (1.1 + 4) x₀ = 5, x₁ = 0
+        assert!((x[0] - 5.0 / 5.1).abs() < 1e-5);
+        assert!(x[1].abs() < 1e-6);
+    }
+
     #[test]
     fn test_build_matrix() {
         let mut mf = MatrixFactorization::new(ALSConfig::default());
@@ -484,12 +1050,7 @@ mod tests {
 
     #[test]
     fn test_als_fit() {
-        let mut mf = MatrixFactorization::new(ALSConfig {
-            latent_factors: 4,
-            regularization: 0.1,
-            iterations: 5,
-            alpha: 1.0,
-        });
+        let mut mf = MatrixFactorization::new(config(4, 5, 1.0));
 
         let user1 = Uuid::new_v4();
         let user2 = Uuid::new_v4();
@@ -520,12 +1081,7 @@ mod tests {
 
     #[test]
     fn test_predict() {
-        let mut mf = MatrixFactorization::new(ALSConfig {
-            latent_factors: 8,
-            regularization: 0.1,
-            iterations: 10,
-            alpha: 40.0,
-        });
+        let mut mf = MatrixFactorization::new(config(8, 10, 40.0));
 
         let user1 = Uuid::new_v4();
         let user2 = Uuid::new_v4();
@@ -552,14 +1108,151 @@ mod tests {
         assert!(pred2.abs() < 10.0); // Should be reasonable
     }
 
+    /// Two disjoint taste groups: each user has seen part of their group's items
+    fn clustered_interactions(
+        users_per_group: usize,
+        items_per_group: usize,
+    ) -> (SparseMatrix, Vec<(usize, usize)>) {
+        let mut matrix = SparseMatrix::new();
+        let mut unseen_in_group = Vec::new();
+        for group in 0..2 {
+            for u in 0..users_per_group {
+                let user = group * users_per_group + u;
+                for i in 0..items_per_group {
+                    let item = group * items_per_group + i;
+                    if (u + i) % 4 == 0 {
+                        unseen_in_group.push((user, item));
+                    } else {
+                        matrix.insert(user, item, 1.0);
+                    }
+                }
+            }
+        }
+        (matrix, unseen_in_group)
+    }
+
     #[test]
-    fn test_get_embeddings() {
+    fn test_als_learns_group_structure() {
+        let (matrix, unseen) = clustered_interactions(20, 10);
+        let mut mf = MatrixFactorization::new(config(4, 10, 10.0));
+        mf.fit(&matrix).unwrap();
+
+        let users = mf.user_factors.as_ref().unwrap();
+        let items = mf.item_factors.as_ref().unwrap();
+        for (user, item) in unseen {
+            let other_group_item = (item + 10) % 20;
+            let in_group = users.row(user).dot(&items.row(item));
+            let out_of_group = users.row(user).dot(&items.row(other_group_item));
+            assert!(in_group > out_of_group);
+        }
+    }
+
+    #[test]
+    fn test_fold_in_places_new_user_in_their_group() {
+        let (matrix, _) = clustered_interactions(20, 10);
+        let item_id = |i: usize| Uuid::from_u128(i as u128 + 1);
+        let triplets: Vec<(Uuid, Uuid, f32)> = matrix
+            .triplets
+            .iter()
+            .map(|&(u, i, r)| (Uuid::from_u128(1000 + u as u128), item_id(i as usize), r))
+            .collect();
+        let mut mf = MatrixFactorization::new(config(4, 10, 10.0));
+        let matrix = mf.build_matrix(triplets).unwrap();
+        mf.fit(&matrix).unwrap();
+        let gram = mf.item_gram().unwrap();
+
+        // A new user who has watched a few second-group items
+        let new_user = Uuid::new_v4();
+        let history: Vec<(Uuid, f32)> = (10..14)
+            .map(|i| (item_id(i), 1.0))
+            .chain([(Uuid::new_v4(), 1.0)]) // unknown to the model
+            .collect();
+        assert_eq!(mf.fold_in_user(new_user, &history, &gram).unwrap(), 4);
+
+        for i in 14..20 {
+            let in_group = mf.predict(new_user, item_id(i)).unwrap();
+            let out_of_group = mf.predict(new_user, item_id(i - 10)).unwrap();
+            assert!(in_group > out_of_group);
+        }
+
+        // Folding in again updates the same row
+        let users = mf.user_factors.as_ref().unwrap().nrows();
+        mf.fold_in_user(new_user, &history, &gram).unwrap();
+        assert_eq!(mf.user_factors.as_ref().unwrap().nrows(), users);
+        assert!(mf.fold_in_user(new_user, &history, &gram[1..]).is_err());
+    }
+
+    #[test]
+    fn test_early_stopping_keeps_best_iteration() {
+        let (matrix, _) = clustered_interactions(30, 12);
         let mut mf = MatrixFactorization::new(ALSConfig {
-            latent_factors: 4,
-            regularization: 0.1,
-            iterations: 5,
-            alpha: 1.0,
+            validation_fraction: 0.2,
+            early_stopping_patience: 1,
+            early_stopping_min_delta: 0.5, // nothing after the first iteration counts
+            ..config(4, 10, 10.0)
+        });
+
+        let report = mf.fit(&matrix).unwrap();
+        assert!(report.stopped_early);
+        assert_eq!(report.iterations, 2);
+        assert_eq!(report.best_iteration, Some(1));
+        assert!(report.validation_auc[0] > 0.5);
+    }
+
+    #[test]
+    fn test_checkpoint_keeps_best_iteration() {
+        let (matrix, _) = clustered_interactions(30, 12);
+        let dir = std::env::temp_dir().join(format!("als-checkpoint-{}", Uuid::new_v4()));
+        let mut mf = MatrixFactorization::new(ALSConfig {
+            validation_fraction: 0.2,
+            early_stopping_patience: 3,
+            early_stopping_min_delta: 0.5,
+            checkpoint_dir: Some(dir.clone()),
+            ..config(4, 10, 10.0)
+        });
+
+        let report = mf.fit(&matrix).unwrap();
+        assert_eq!(report.iterations, 4);
+        assert_eq!(report.best_iteration, Some(1));
+
+        let restored =
+            MatrixFactorization::load_checkpoint(dir.join(CHECKPOINT_FILE), ALSConfig::default())
+                .unwrap();
+        assert_eq!(restored.user_factors, mf.user_factors);
+        assert_eq!(restored.item_factors, mf.item_factors);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_checkpoint_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("als-checkpoint-{}", Uuid::new_v4()));
+        let mut mf = MatrixFactorization::new(ALSConfig {
+            checkpoint_dir: Some(dir.clone()),
+            ..config(4, 3, 1.0)
         });
+        let user = Uuid::new_v4();
+        let item = Uuid::new_v4();
+        let matrix = mf
+            .build_matrix(vec![(user, item, 1.0), (Uuid::new_v4(), item, 1.0)])
+            .unwrap();
+        mf.fit(&matrix).unwrap();
+
+        let restored =
+            MatrixFactorization::load_checkpoint(dir.join(CHECKPOINT_FILE), ALSConfig::default())
+                .unwrap();
+        assert_eq!(
+            restored.predict(user, item).unwrap(),
+            mf.predict(user, item).unwrap()
+        );
+        assert_eq!(restored.get_user_embedding(user).unwrap().len(), 4);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_get_embeddings() {
+        let mut mf = MatrixFactorization::new(config(4, 5, 1.0));
 
         let user1 = Uuid::new_v4();
         let item1 = Uuid::new_v4();
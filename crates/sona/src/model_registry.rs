@@ -0,0 +1,939 @@
+//! Versioned ONNX Model Registry
+//!
+//! Embedding models are published under `<root>/<name>/<version>/` next to a
+//! `metadata.json` describing the embedding dimension, tokenizer and SHA-256
+//! checksum of the model file. The root is either a local directory or an
+//! object-store-compatible HTTP(S) prefix (S3, GCS, MinIO). Remote stores
+//! cannot be listed, so they publish `<root>/<name>/versions.json` instead.
+//!
+//! `ModelServer` serves embeddings from the active version and adds:
+//! - hot reload: a new version is loaded and verified beside the active one
+//!   and swapped in atomically, so in-flight requests finish on the old model
+//! - shadow inference: a candidate version embeds a sample of the same
+//!   inputs in the background and its agreement with the active model is
+//!   tracked for comparison
+//! - per-experiment selection through the `model` key of a variant config
+
+use crate::inference::ONNXInference;
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use media_gateway_core::math::cosine_similarity;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tokio::io::AsyncWriteExt;
+
+/// Metadata file stored next to every model version
+pub const METADATA_FILE: &str = "metadata.json";
+/// Version index published by stores that cannot be listed
+pub const VERSIONS_FILE: &str = "versions.json";
+/// Key of the model selection within an experiment variant config
+pub const VARIANT_CONFIG_KEY: &str = "model";
+
+const DEFAULT_MODEL_NAME: &str = "sona_embeddings";
+const DEFAULT_CACHE_DIR: &str = "/tmp/sona-models";
+const DEFAULT_SHADOW_SAMPLE_RATE: f64 = 0.1;
+
+fn default_model_file() -> String {
+    "model.onnx".to_string()
+}
+
+/// Description of a published model version
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelMetadata {
+    pub name: String,
+    pub version: String,
+    pub embedding_dim: usize,
+    /// Tokenizer the model expects, e.g. "bert-base-uncased"
+    pub tokenizer: String,
+    /// Lowercase hex SHA-256 of the model file
+    pub checksum: String,
+    /// Model file name, relative to the version directory
+    #[serde(default = "default_model_file")]
+    pub file: String,
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl ModelMetadata {
+    pub fn validate(&self) -> Result<()> {
+        if self.embedding_dim == 0 {
+            bail!(
+                "Model {}@{} has a zero embedding dimension",
+                self.name,
+                self.version
+            );
+        }
+        if self.checksum.len() != 64 || !self.checksum.chars().all(|c| c.is_ascii_hexdigit()) {
+            bail!(
+                "Model {}@{} does not carry a SHA-256 checksum",
+                self.name,
+                self.version
+            );
+        }
+        let file = Path::new(&self.file);
+        if self.file.is_empty()
+            || file.is_absolute()
+            || file
+                .components()
+                .any(|c| c == std::path::Component::ParentDir)
+        {
+            bail!(
+                "Model {}@{} has an invalid file name: {}",
+                self.name,
+                self.version,
+                self.file
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Compare version strings component-wise, numerically where both sides are
+/// numeric ("1.10.0" > "1.9.2"), ignoring a leading "v"
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let parts = |v: &str| -> Vec<String> {
+        v.trim_start_matches('v')
+            .split(['.', '-', '_'])
+            .map(str::to_string)
+            .collect()
+    };
+    let (a, b) = (parts(a), parts(b));
+    for (x, y) in a.iter().zip(b.iter()) {
+        let ordering = match (x.parse::<u64>(), y.parse::<u64>()) {
+            (Ok(x), Ok(y)) => x.cmp(&y),
+            _ => x.cmp(y),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    a.len().cmp(&b.len())
+}
+
+/// Hex SHA-256 of a file, read in chunks so large models stay out of memory
+pub fn file_sha256(path: &Path) -> Result<String> {
+    let mut file = std::fs::File::open(path)
+        .with_context(|| format!("Failed to open model file {}", path.display()))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1 << 16];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+async fn verify_checksum(path: &Path, expected: &str) -> Result<bool> {
+    let path = path.to_path_buf();
+    let actual = tokio::task::spawn_blocking(move || file_sha256(&path)).await??;
+    Ok(actual.eq_ignore_ascii_case(expected))
+}
+
+/// Backing storage for published models
+#[async_trait]
+pub trait ModelStore: Send + Sync {
+    /// Versions published for a model, in no particular order
+    async fn list_versions(&self, name: &str) -> Result<Vec<String>>;
+
+    async fn metadata(&self, name: &str, version: &str) -> Result<ModelMetadata>;
+
+    /// Local path of the model file, downloading it into `cache_dir` if needed
+    async fn fetch(&self, metadata: &ModelMetadata, cache_dir: &Path) -> Result<PathBuf>;
+}
+
+/// Models stored in a local (or mounted) directory
+pub struct LocalModelStore {
+    root: PathBuf,
+}
+
+impl LocalModelStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+#[async_trait]
+impl ModelStore for LocalModelStore {
+    async fn list_versions(&self, name: &str) -> Result<Vec<String>> {
+        let dir = self.root.join(name);
+        let mut entries = tokio::fs::read_dir(&dir)
+            .await
+            .with_context(|| format!("Failed to list model directory {}", dir.display()))?;
+
+        let mut versions = Vec::new();
+        while let Some(entry) = entries.next_entry().await? {
+            if tokio::fs::metadata(entry.path().join(METADATA_FILE))
+                .await
+                .is_ok()
+            {
+                versions.push(entry.file_name().to_string_lossy().into_owned());
+            }
+        }
+        Ok(versions)
+    }
+
+    async fn metadata(&self, name: &str, version: &str) -> Result<ModelMetadata> {
+        let path = self.root.join(name).join(version).join(METADATA_FILE);
+        let raw = tokio::fs::read(&path)
+            .await
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_slice(&raw)
+            .with_context(|| format!("Invalid model metadata in {}", path.display()))
+    }
+
+    async fn fetch(&self, metadata: &ModelMetadata, _cache_dir: &Path) -> Result<PathBuf> {
+        Ok(self
+            .root
+            .join(&metadata.name)
+            .join(&metadata.version)
+            .join(&metadata.file))
+    }
+}
+
+/// Models served from an object-store-compatible HTTP(S) prefix
+pub struct HttpModelStore {
+    base_url: String,
+    client: reqwest::Client,
+}
+
+impl HttpModelStore {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    async fn get(&self, path: &str) -> Result<reqwest::Response> {
+        let url = format!("{}/{}", self.base_url, path);
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .with_context(|| format!("Failed to fetch {}", url))?;
+        if !response.status().is_success() {
+            bail!("Fetching {} returned {}", url, response.status());
+        }
+        Ok(response)
+    }
+}
+
+#[async_trait]
+impl ModelStore for HttpModelStore {
+    async fn list_versions(&self, name: &str) -> Result<Vec<String>> {
+        let response = self.get(&format!("{}/{}", name, VERSIONS_FILE)).await?;
+        Ok(response.json().await?)
+    }
+
+    async fn metadata(&self, name: &str, version: &str) -> Result<ModelMetadata> {
+        let response = self
+            .get(&format!("{}/{}/{}", name, version, METADATA_FILE))
+            .await?;
+        Ok(response.json().await?)
+    }
+
+    async fn fetch(&self, metadata: &ModelMetadata, cache_dir: &Path) -> Result<PathBuf> {
+        let path = cache_dir
+            .join(&metadata.name)
+            .join(&metadata.version)
+            .join(&metadata.file);
+        if tokio::fs::metadata(&path).await.is_ok()
+            && verify_checksum(&path, &metadata.checksum).await?
+        {
+            return Ok(path);
+        }
+
+        let mut response = self
+            .get(&format!(
+                "{}/{}/{}",
+                metadata.name, metadata.version, metadata.file
+            ))
+            .await?;
+
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        // Stream beside the final path and rename so a crash never leaves a
+        // truncated model in the cache, and the model is never held in memory
+        let partial = path.with_extension("partial");
+        let mut file = tokio::fs::File::create(&partial).await?;
+        let mut downloaded = 0u64;
+        while let Some(chunk) = response.chunk().await? {
+            file.write_all(&chunk).await?;
+            downloaded += chunk.len() as u64;
+        }
+        file.flush().await?;
+        drop(file);
+        tokio::fs::rename(&partial, &path).await?;
+
+        tracing::info!(
+            "Downloaded model {}@{} ({} bytes)",
+            metadata.name,
+            metadata.version,
+            downloaded
+        );
+        Ok(path)
+    }
+}
+
+/// A verified model version loaded into ONNX Runtime
+pub struct LoadedModel {
+    pub metadata: ModelMetadata,
+    pub inference: Arc<ONNXInference>,
+    pub loaded_at: DateTime<Utc>,
+}
+
+/// Resolves, verifies and loads model versions from a store
+pub struct ModelRegistry {
+    store: Arc<dyn ModelStore>,
+    cache_dir: PathBuf,
+}
+
+impl ModelRegistry {
+    pub fn new(store: Arc<dyn ModelStore>, cache_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            store,
+            cache_dir: cache_dir.into(),
+        }
+    }
+
+    /// Open a registry from a location: `http(s)://` prefixes are read over
+    /// HTTP, anything else (optionally `file://`) is a local directory
+    pub fn open(location: &str, cache_dir: impl Into<PathBuf>) -> Self {
+        let store: Arc<dyn ModelStore> =
+            if location.starts_with("http://") || location.starts_with("https://") {
+                Arc::new(HttpModelStore::new(location))
+            } else {
+                Arc::new(LocalModelStore::new(
+                    location.strip_prefix("file://").unwrap_or(location),
+                ))
+            };
+        Self::new(store, cache_dir)
+    }
+
+    /// Create from environment variables
+    ///
+    /// Reads the location from SONA_MODEL_REGISTRY and the download cache
+    /// from SONA_MODEL_CACHE_DIR; returns None when no registry is configured
+    pub fn from_env() -> Option<Self> {
+        let location = std::env::var("SONA_MODEL_REGISTRY").ok()?;
+        let cache_dir =
+            std::env::var("SONA_MODEL_CACHE_DIR").unwrap_or_else(|_| DEFAULT_CACHE_DIR.to_string());
+        Some(Self::open(&location, cache_dir))
+    }
+
+    /// Published versions, oldest first
+    pub async fn versions(&self, name: &str) -> Result<Vec<String>> {
+        let mut versions = self.store.list_versions(name).await?;
+        versions.sort_by(|a, b| compare_versions(a, b));
+        Ok(versions)
+    }
+
+    pub async fn latest(&self, name: &str) -> Result<String> {
+        self.versions(name)
+            .await?
+            .pop()
+            .ok_or_else(|| anyhow!("No versions published for model {}", name))
+    }
+
+    /// Resolve a version (latest when None) to its metadata and a local file
+    /// whose checksum matches the metadata
+    pub async fn resolve(
+        &self,
+        name: &str,
+        version: Option<&str>,
+    ) -> Result<(ModelMetadata, PathBuf)> {
+        let version = match version {
+            Some(version) => version.to_string(),
+            None => self.latest(name).await?,
+        };
+        if [name, version.as_str()]
+            .iter()
+            .any(|s| s.is_empty() || s.contains(['/', '\\']) || *s == "..")
+        {
+            bail!("Invalid model reference {}@{}", name, version);
+        }
+
+        let metadata = self.store.metadata(name, &version).await?;
+        metadata.validate()?;
+        if metadata.name != name || metadata.version != version {
+            bail!(
+                "Metadata for {}@{} describes {}@{}",
+                name,
+                version,
+                metadata.name,
+                metadata.version
+            );
+        }
+
+        let path = self.store.fetch(&metadata, &self.cache_dir).await?;
+        if !verify_checksum(&path, &metadata.checksum).await? {
+            bail!(
+                "Checksum mismatch for model {}@{} at {}",
+                name,
+                version,
+                path.display()
+            );
+        }
+        Ok((metadata, path))
+    }
+
+    /// Resolve and load a version into ONNX Runtime
+    pub async fn load(&self, name: &str, version: Option<&str>) -> Result<Arc<LoadedModel>> {
+        let (metadata, path) = self.resolve(name, version).await?;
+        let embedding_dim = metadata.embedding_dim;
+        let inference =
+            tokio::task::spawn_blocking(move || ONNXInference::new(path, embedding_dim)).await??;
+
+        tracing::info!(
+            "Loaded model {}@{} (embedding_dim={}, tokenizer={})",
+            metadata.name,
+            metadata.version,
+            metadata.embedding_dim,
+            metadata.tokenizer
+        );
+        Ok(Arc::new(LoadedModel {
+            metadata,
+            inference: Arc::new(inference),
+            loaded_at: Utc::now(),
+        }))
+    }
+}
+
+/// Agreement between the active model and a shadow candidate
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ShadowReport {
+    pub active_version: String,
+    pub shadow_version: String,
+    pub comparisons: u64,
+    pub errors: u64,
+    /// Comparisons skipped because the embedding dimensions differ
+    pub dimension_mismatches: u64,
+    pub mean_similarity: Option<f32>,
+    pub min_similarity: Option<f32>,
+    pub mean_active_latency_ms: f64,
+    pub mean_shadow_latency_ms: f64,
+}
+
+#[derive(Debug, Default)]
+struct ShadowStats {
+    comparisons: u64,
+    errors: u64,
+    dimension_mismatches: u64,
+    similarity_count: u64,
+    similarity_sum: f64,
+    min_similarity: Option<f32>,
+    active_latency_ms: f64,
+    shadow_latency_ms: f64,
+}
+
+impl ShadowStats {
+    fn record(
+        &mut self,
+        active: &[f32],
+        shadow: Result<&[f32], ()>,
+        active_latency: Duration,
+        shadow_latency: Duration,
+    ) {
+        let Ok(shadow) = shadow else {
+            self.errors += 1;
+            return;
+        };
+
+        self.comparisons += 1;
+        self.active_latency_ms += active_latency.as_secs_f64() * 1000.0;
+        self.shadow_latency_ms += shadow_latency.as_secs_f64() * 1000.0;
+        if active.len() != shadow.len() {
+            self.dimension_mismatches += 1;
+            return;
+        }
+
+        let similarity = cosine_similarity(active, shadow);
+        self.similarity_count += 1;
+        self.similarity_sum += similarity as f64;
+        self.min_similarity = Some(
+            self.min_similarity
+                .map_or(similarity, |m| m.min(similarity)),
+        );
+    }
+
+    fn report(&self, active_version: &str, shadow_version: &str) -> ShadowReport {
+        let mean = |sum: f64, count: u64| if count == 0 { 0.0 } else { sum / count as f64 };
+        ShadowReport {
+            active_version: active_version.to_string(),
+            shadow_version: shadow_version.to_string(),
+            comparisons: self.comparisons,
+            errors: self.errors,
+            dimension_mismatches: self.dimension_mismatches,
+            mean_similarity: (self.similarity_count > 0)
+                .then(|| mean(self.similarity_sum, self.similarity_count) as f32),
+            min_similarity: self.min_similarity,
+            mean_active_latency_ms: mean(self.active_latency_ms, self.comparisons),
+            mean_shadow_latency_ms: mean(self.shadow_latency_ms, self.comparisons),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Shadow {
+    model: Arc<LoadedModel>,
+    sample_rate: f64,
+    stats: Arc<Mutex<ShadowStats>>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum VariantModel {
+    Version(String),
+    Spec { version: String },
+}
+
+/// Model version requested by an experiment variant config, accepting either
+/// `{"model": "1.2.0"}` or `{"model": {"version": "1.2.0"}}`
+pub fn variant_model_version(variant_config: Option<&serde_json::Value>) -> Result<Option<String>> {
+    let Some(model) = variant_config.and_then(|c| c.get(VARIANT_CONFIG_KEY)) else {
+        return Ok(None);
+    };
+    let model: VariantModel = serde_json::from_value(model.clone())
+        .context("Invalid model selection in variant config")?;
+    Ok(Some(match model {
+        VariantModel::Version(version) | VariantModel::Spec { version } => version,
+    }))
+}
+
+/// Serves embeddings from a hot-swappable registry model
+pub struct ModelServer {
+    registry: ModelRegistry,
+    name: String,
+    active: RwLock<Arc<LoadedModel>>,
+    shadow: RwLock<Option<Shadow>>,
+    experiments: Mutex<HashMap<String, Arc<LoadedModel>>>,
+}
+
+impl ModelServer {
+    /// Load the given version (latest when None) and start serving it
+    pub async fn start(registry: ModelRegistry, name: &str, version: Option<&str>) -> Result<Self> {
+        let active = registry.load(name, version).await?;
+        Ok(Self {
+            registry,
+            name: name.to_string(),
+            active: RwLock::new(active),
+            shadow: RwLock::new(None),
+            experiments: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Create from environment variables
+    ///
+    /// Uses the registry from `ModelRegistry::from_env`, the model name from
+    /// SONA_MODEL_NAME and a pinned version from SONA_MODEL_VERSION. A shadow
+    /// candidate is started from SONA_SHADOW_MODEL_VERSION, sampled at
+    /// SONA_SHADOW_SAMPLE_RATE. Returns None when no registry is configured.
+    pub async fn from_env() -> Result<Option<Self>> {
+        let Some(registry) = ModelRegistry::from_env() else {
+            return Ok(None);
+        };
+        let name =
+            std::env::var("SONA_MODEL_NAME").unwrap_or_else(|_| DEFAULT_MODEL_NAME.to_string());
+        let version = std::env::var("SONA_MODEL_VERSION").ok();
+
+        let server = Self::start(registry, &name, version.as_deref()).await?;
+        if let Ok(shadow_version) = std::env::var("SONA_SHADOW_MODEL_VERSION") {
+            let sample_rate = std::env::var("SONA_SHADOW_SAMPLE_RATE")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(DEFAULT_SHADOW_SAMPLE_RATE);
+            server.start_shadow(&shadow_version, sample_rate).await?;
+        }
+        Ok(Some(server))
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn registry(&self) -> &ModelRegistry {
+        &self.registry
+    }
+
+    /// Currently active model; callers keep it for the whole request
+    pub fn active(&self) -> Arc<LoadedModel> {
+        Arc::clone(&self.active.read().unwrap())
+    }
+
+    pub fn inference(&self) -> Arc<ONNXInference> {
+        Arc::clone(&self.active().inference)
+    }
+
+    /// Load a version (latest when None) and swap it in
+    ///
+    /// The replacement must keep the active embedding dimension, since stored
+    /// embeddings are compared against it; in-flight requests finish on the
+    /// model they started with.
+    pub async fn reload(&self, version: Option<&str>) -> Result<ModelMetadata> {
+        let current = self.active();
+        if version == Some(current.metadata.version.as_str()) {
+            return Ok(current.metadata.clone());
+        }
+
+        let candidate = self.registry.load(&self.name, version).await?;
+        if candidate.metadata.embedding_dim != current.metadata.embedding_dim {
+            bail!(
+                "Model {}@{} has embedding_dim {} but {}@{} serves {}; changing dimension requires an embedding migration",
+                self.name,
+                candidate.metadata.version,
+                candidate.metadata.embedding_dim,
+                self.name,
+                current.metadata.version,
+                current.metadata.embedding_dim
+            );
+        }
+        if candidate.metadata.tokenizer != current.metadata.tokenizer {
+            tracing::warn!(
+                "Model {}@{} switches tokenizer from {} to {}",
+                self.name,
+                candidate.metadata.version,
+                current.metadata.tokenizer,
+                candidate.metadata.tokenizer
+            );
+        }
+
+        let metadata = candidate.metadata.clone();
+        *self.active.write().unwrap() = candidate;
+        tracing::info!(
+            "Model {} swapped from {} to {}",
+            self.name,
+            current.metadata.version,
+            metadata.version
+        );
+        Ok(metadata)
+    }
+
+    /// Reload when a newer version than the active one has been published
+    pub async fn refresh(&self) -> Result<Option<ModelMetadata>> {
+        let latest = self.registry.latest(&self.name).await?;
+        let current = self.active();
+        if compare_versions(&latest, &current.metadata.version) != Ordering::Greater {
+            return Ok(None);
+        }
+        self.reload(Some(&latest)).await.map(Some)
+    }
+
+    /// Poll the registry and hot-reload newly published versions
+    pub fn spawn_watcher(self: Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                if let Err(e) = self.refresh().await {
+                    tracing::warn!("Model registry refresh failed for {}: {}", self.name, e);
+                }
+            }
+        })
+    }
+
+    /// Run a candidate version on a sample of requests for comparison
+    pub async fn start_shadow(&self, version: &str, sample_rate: f64) -> Result<()> {
+        if !(0.0..=1.0).contains(&sample_rate) {
+            bail!("Shadow sample rate must be within [0, 1]");
+        }
+        let model = self.registry.load(&self.name, Some(version)).await?;
+        *self.shadow.write().unwrap() = Some(Shadow {
+            model,
+            sample_rate,
+            stats: Arc::new(Mutex::new(ShadowStats::default())),
+        });
+        Ok(())
+    }
+
+    /// Stop shadow inference, returning its final report
+    pub fn stop_shadow(&self) -> Option<ShadowReport> {
+        let shadow = self.shadow.write().unwrap().take()?;
+        Some(self.report(&shadow))
+    }
+
+    pub fn shadow_report(&self) -> Option<ShadowReport> {
+        let shadow = self.shadow.read().unwrap().clone()?;
+        Some(self.report(&shadow))
+    }
+
+    fn report(&self, shadow: &Shadow) -> ShadowReport {
+        shadow.stats.lock().unwrap().report(
+            &self.active().metadata.version,
+            &shadow.model.metadata.version,
+        )
+    }
+
+    /// Model for an experiment variant, falling back to the active model
+    /// when the variant config does not select one
+    pub async fn for_variant(
+        &self,
+        variant_config: Option<&serde_json::Value>,
+    ) -> Result<Arc<LoadedModel>> {
+        let active = self.active();
+        let version = match variant_model_version(variant_config)? {
+            Some(version) if version != active.metadata.version => version,
+            _ => return Ok(active),
+        };
+
+        if let Some(model) = self.experiments.lock().unwrap().get(&version) {
+            return Ok(Arc::clone(model));
+        }
+
+        let model = self.registry.load(&self.name, Some(&version)).await?;
+        if model.metadata.embedding_dim != active.metadata.embedding_dim {
+            bail!(
+                "Experiment model {}@{} has embedding_dim {}, expected {}",
+                self.name,
+                version,
+                model.metadata.embedding_dim,
+                active.metadata.embedding_dim
+            );
+        }
+        self.experiments
+            .lock()
+            .unwrap()
+            .insert(version, Arc::clone(&model));
+        Ok(model)
+    }
+
+    /// Generate an embedding with the active model, mirroring the input to
+    /// the shadow candidate when sampled
+    pub async fn generate_embedding(&self, text: &str) -> Result<Vec<f32>> {
+        let model = self.active();
+        let started = Instant::now();
+        let embedding = model.inference.generate_embedding(text).await?;
+        let active_latency = started.elapsed();
+
+        if let Some(shadow) = self.sampled_shadow() {
+            let text = text.to_string();
+            let active = embedding.clone();
+            tokio::spawn(async move {
+                let started = Instant::now();
+                let result = shadow.model.inference.generate_embedding(&text).await;
+                let shadow_latency = started.elapsed();
+                if let Err(ref e) = result {
+                    tracing::debug!("Shadow inference failed: {}", e);
+                }
+                shadow.stats.lock().unwrap().record(
+                    &active,
+                    result.as_deref().map_err(|_| ()),
+                    active_latency,
+                    shadow_latency,
+                );
+            });
+        }
+
+        Ok(embedding)
+    }
+
+    /// Generate an embedding with the model an experiment variant selects
+    ///
+    /// Variants without a model selection are served by the active model,
+    /// shadow sampling included.
+    pub async fn generate_embedding_for_variant(
+        &self,
+        variant_config: Option<&serde_json::Value>,
+        text: &str,
+    ) -> Result<Vec<f32>> {
+        let model = self.for_variant(variant_config).await?;
+        if Arc::ptr_eq(&model, &self.active()) {
+            return self.generate_embedding(text).await;
+        }
+        model.inference.generate_embedding(text).await
+    }
+
+    /// Batch variant of `generate_embedding`
+    pub async fn generate_embeddings_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        let model = self.active();
+        let started = Instant::now();
+        let embeddings = model.inference.generate_embeddings_batch(texts).await?;
+        let active_latency = started.elapsed() / texts.len().max(1) as u32;
+
+        if let Some(shadow) = self.sampled_shadow() {
+            let texts: Vec<String> = texts.iter().map(|t| t.to_string()).collect();
+            let active = embeddings.clone();
+            tokio::spawn(async move {
+                let refs: Vec<&str> = texts.iter().map(String::as_str).collect();
+                let started = Instant::now();
+                let result = shadow
+                    .model
+                    .inference
+                    .generate_embeddings_batch(&refs)
+                    .await;
+                let shadow_latency = started.elapsed() / refs.len().max(1) as u32;
+
+                let mut stats = shadow.stats.lock().unwrap();
+                match result {
+                    Ok(shadowed) => {
+                        for (active, shadowed) in active.iter().zip(shadowed.iter()) {
+                            stats.record(active, Ok(shadowed), active_latency, shadow_latency);
+                        }
+                    }
+                    Err(e) => {
+                        tracing::debug!("Shadow batch inference failed: {}", e);
+                        stats.record(&[], Err(()), active_latency, shadow_latency);
+                    }
+                }
+            });
+        }
+
+        Ok(embeddings)
+    }
+
+    fn sampled_shadow(&self) -> Option<Shadow> {
+        let shadow = self.shadow.read().unwrap().clone()?;
+        (rand::random::<f64>() < shadow.sample_rate).then_some(shadow)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata(version: &str, checksum: &str) -> ModelMetadata {
+        ModelMetadata {
+            name: "sona_embeddings".to_string(),
+            version: version.to_string(),
+            embedding_dim: 4,
+            tokenizer: "bert-base-uncased".to_string(),
+            checksum: checksum.to_string(),
+            file: default_model_file(),
+            created_at: None,
+        }
+    }
+
+    fn temp_root() -> PathBuf {
+        std::env::temp_dir().join(format!("sona-registry-{}", uuid::Uuid::new_v4()))
+    }
+
+    fn publish(root: &Path, metadata: &ModelMetadata, model: &[u8]) {
+        let dir = root.join(&metadata.name).join(&metadata.version);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(&metadata.file), model).unwrap();
+        std::fs::write(
+            dir.join(METADATA_FILE),
+            serde_json::to_vec(metadata).unwrap(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_compare_versions_is_numeric() {
+        let mut versions = vec!["1.10.0", "v1.9.2", "1.2", "1.9.10", "2.0.0"];
+        versions.sort_by(|a, b| compare_versions(a, b));
+        assert_eq!(versions, vec!["1.2", "v1.9.2", "1.9.10", "1.10.0", "2.0.0"]);
+        assert_eq!(compare_versions("1.0", "1.0.1"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_metadata_validation() {
+        let checksum = hex::encode(Sha256::digest(b"model"));
+        assert!(metadata("1.0.0", &checksum).validate().is_ok());
+        assert!(metadata("1.0.0", "abc").validate().is_err());
+
+        let mut escaping = metadata("1.0.0", &checksum);
+        escaping.file = "../other/model.onnx".to_string();
+        assert!(escaping.validate().is_err());
+
+        let json = format!(
+            r#"{{"name":"m","version":"1","embedding_dim":8,"tokenizer":"t","checksum":"{}"}}"#,
+            checksum
+        );
+        let parsed: ModelMetadata = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.file, "model.onnx");
+    }
+
+    #[tokio::test]
+    async fn test_registry_resolves_latest_and_verifies_checksum() {
+        let root = temp_root();
+        let good = b"onnx bytes v2";
+        publish(
+            &root,
+            &metadata("1.9.0", &hex::encode(Sha256::digest(b"v1"))),
+            b"v1",
+        );
+        publish(
+            &root,
+            &metadata("1.10.0", &hex::encode(Sha256::digest(good))),
+            good,
+        );
+        publish(
+            &root,
+            &metadata("1.2.0", &hex::encode(Sha256::digest(b"expected"))),
+            b"tampered",
+        );
+
+        let registry = ModelRegistry::open(&format!("file://{}", root.display()), &root);
+        assert_eq!(
+            registry.versions("sona_embeddings").await.unwrap(),
+            vec!["1.2.0", "1.9.0", "1.10.0"]
+        );
+
+        let (resolved, path) = registry.resolve("sona_embeddings", None).await.unwrap();
+        assert_eq!(resolved.version, "1.10.0");
+        assert_eq!(std::fs::read(path).unwrap(), good);
+
+        let err = registry
+            .resolve("sona_embeddings", Some("1.2.0"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Checksum mismatch"));
+        assert!(registry
+            .resolve("sona_embeddings", Some("../1.10.0"))
+            .await
+            .is_err());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_variant_model_selection() {
+        assert_eq!(variant_model_version(None).unwrap(), None);
+        let plain = serde_json::json!({ "rerank": {} });
+        assert_eq!(variant_model_version(Some(&plain)).unwrap(), None);
+
+        let short = serde_json::json!({ "model": "1.3.0" });
+        assert_eq!(
+            variant_model_version(Some(&short)).unwrap().as_deref(),
+            Some("1.3.0")
+        );
+        let spec = serde_json::json!({ "model": { "version": "2.0.0" } });
+        assert_eq!(
+            variant_model_version(Some(&spec)).unwrap().as_deref(),
+            Some("2.0.0")
+        );
+        let invalid = serde_json::json!({ "model": 3 });
+        assert!(variant_model_version(Some(&invalid)).is_err());
+    }
+
+    #[test]
+    fn test_shadow_stats_report() {
+        let mut stats = ShadowStats::default();
+        let ms = Duration::from_millis;
+        stats.record(&[1.0, 0.0], Ok(&[1.0, 0.0]), ms(10), ms(20));
+        stats.record(&[1.0, 0.0], Ok(&[0.0, 1.0]), ms(10), ms(30));
+        stats.record(&[1.0, 0.0], Ok(&[1.0, 0.0, 0.0]), ms(10), ms(40));
+        stats.record(&[1.0, 0.0], Err(()), ms(10), ms(0));
+
+        let report = stats.report("1.0.0", "1.1.0");
+        assert_eq!(report.comparisons, 3);
+        assert_eq!(report.errors, 1);
+        assert_eq!(report.dimension_mismatches, 1);
+        assert!((report.mean_similarity.unwrap() - 0.5).abs() < 1e-6);
+        assert!(report.min_similarity.unwrap().abs() < 1e-6);
+        assert!((report.mean_shadow_latency_ms - 30.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,1242 @@
+//! Mood Inference
+//!
+//! Infers a probable `MoodState` from in-session behavior when the user has
+//! not picked a mood: the moods of what they just watched (catalog and
+//! embedding-derived mood tags, falling back to genres), how much they skip,
+//! whether they are rewatching favorites, and the local time of day.
+//!
+//! Mood vectors use the dimensions of [`MOOD_DIMENSIONS`]. Each mood label
+//! understood by `ContextAwareFilter::filter_by_mood` has a prototype vector;
+//! a vector's label is the nearest prototype. When the user picks a mood
+//! explicitly, the pick is stored next to what inference said at the time,
+//! and the decayed average difference calibrates later inferences.
+
+use crate::content_based::ContentEmbeddingStore;
+use crate::diversity::ContentAttributes;
+use crate::temporal::Daypart;
+use crate::types::{MoodSource, MoodState, ViewingEvent};
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Row};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{debug, info, instrument, warn};
+use uuid::Uuid;
+
+/// Mood vector dimensions, in order
+pub const MOOD_DIMENSIONS: [&str; 8] = [
+    "calm",
+    "energetic",
+    "happy",
+    "sad",
+    "focused",
+    "relaxed",
+    "social",
+    "introspective",
+];
+
+const MOOD_DIM: usize = MOOD_DIMENSIONS.len();
+
+/// Mood labels and their prototype vectors
+const MOOD_PROTOTYPES: [(&str, [f32; MOOD_DIM]); 8] = [
+    ("happy", [0.6, 0.6, 0.9, 0.1, 0.4, 0.6, 0.7, 0.3]),
+    ("sad", [0.5, 0.2, 0.1, 0.9, 0.4, 0.3, 0.2, 0.8]),
+    ("excited", [0.1, 0.9, 0.7, 0.2, 0.5, 0.2, 0.6, 0.2]),
+    ("relaxed", [0.9, 0.2, 0.6, 0.2, 0.3, 0.9, 0.4, 0.5]),
+    ("scared", [0.1, 0.8, 0.2, 0.5, 0.7, 0.1, 0.4, 0.4]),
+    ("romantic", [0.6, 0.4, 0.7, 0.4, 0.3, 0.6, 0.8, 0.6]),
+    ("curious", [0.5, 0.5, 0.5, 0.3, 0.9, 0.4, 0.3, 0.7]),
+    ("nostalgic", [0.7, 0.3, 0.6, 0.5, 0.3, 0.7, 0.4, 0.8]),
+];
+
+/// Skipping through titles: restless, unfocused
+const RESTLESS: [f32; MOOD_DIM] = [0.2, 0.8, 0.5, 0.5, 0.2, 0.2, 0.5, 0.4];
+/// Rewatching: comfort viewing
+const COMFORT: [f32; MOOD_DIM] = [0.8, 0.3, 0.7, 0.4, 0.4, 0.8, 0.5, 0.6];
+
+/// Canonical mood label for a label or one of its synonyms
+pub fn canonical_mood(label: &str) -> Option<&'static str> {
+    match label.trim().to_lowercase().as_str() {
+        "happy" | "joyful" | "cheerful" => Some("happy"),
+        "sad" | "melancholy" | "somber" => Some("sad"),
+        "excited" | "energetic" | "pumped" => Some("excited"),
+        "relaxed" | "calm" | "peaceful" => Some("relaxed"),
+        "scared" | "fearful" => Some("scared"),
+        "romantic" | "loving" => Some("romantic"),
+        "curious" | "intrigued" => Some("curious"),
+        "nostalgic" | "reminiscent" => Some("nostalgic"),
+        _ => None,
+    }
+}
+
+/// Prototype vector of a mood label or synonym
+pub fn mood_prototype(label: &str) -> Option<Vec<f32>> {
+    let label = canonical_mood(label)?;
+    MOOD_PROTOTYPES
+        .iter()
+        .find(|(name, _)| *name == label)
+        .map(|(_, vector)| vector.to_vec())
+}
+
+/// Mood a genre usually evokes
+fn genre_mood(genre: &str) -> Option<&'static str> {
+    match genre.to_lowercase().as_str() {
+        "comedy" | "family" | "animation" => Some("happy"),
+        "drama" => Some("sad"),
+        "action" | "adventure" | "thriller" => Some("excited"),
+        "documentary" | "nature" | "lifestyle" => Some("relaxed"),
+        "horror" => Some("scared"),
+        "romance" => Some("romantic"),
+        "mystery" | "sci-fi" | "science fiction" => Some("curious"),
+        "classic" => Some("nostalgic"),
+        _ => None,
+    }
+}
+
+fn time_of_day_mood(daypart: Daypart) -> [f32; MOOD_DIM] {
+    match daypart {
+        Daypart::Morning => [0.5, 0.7, 0.6, 0.4, 0.7, 0.4, 0.5, 0.4],
+        Daypart::Afternoon => [0.5, 0.6, 0.6, 0.4, 0.6, 0.5, 0.6, 0.4],
+        Daypart::Evening => [0.6, 0.5, 0.6, 0.4, 0.4, 0.8, 0.7, 0.5],
+        Daypart::Night => [0.7, 0.3, 0.4, 0.6, 0.3, 0.6, 0.3, 0.8],
+    }
+}
+
+/// Add `weight * vector` to `sum`, returning `weight`
+fn accumulate(sum: &mut [f32; MOOD_DIM], vector: &[f32], weight: f32) -> f32 {
+    for (s, v) in sum.iter_mut().zip(vector) {
+        *s += weight * v;
+    }
+    weight
+}
+
+fn similarity(a: &[f32], b: &[f32]) -> f32 {
+    // Compare deviations from neutral so shared 0.5 baselines don't count
+    let (mut dot, mut norm_a, mut norm_b) = (0.0, 0.0, 0.0);
+    for (x, y) in a.iter().zip(b) {
+        let (x, y) = (x - 0.5, y - 0.5);
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a <= f32::EPSILON || norm_b <= f32::EPSILON {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
+
+/// Nearest mood label to a vector and its similarity, `None` for a neutral vector
+pub fn nearest_mood(mood_vector: &[f32]) -> Option<(&'static str, f32)> {
+    MOOD_PROTOTYPES
+        .iter()
+        .map(|(name, prototype)| (*name, similarity(mood_vector, prototype)))
+        .filter(|(_, s)| *s > 0.0)
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+/// Mood vector of a title from its mood tags and genres
+///
+/// Mood tags count fully and genres at half weight; `None` when neither
+/// maps to a mood.
+pub fn content_mood_vector(attributes: &ContentAttributes) -> Option<Vec<f32>> {
+    let tagged = attributes
+        .moods
+        .iter()
+        .filter_map(|m| canonical_mood(m))
+        .map(|m| (m, 1.0));
+    let from_genres = attributes
+        .genres
+        .iter()
+        .filter_map(|g| genre_mood(g))
+        .map(|m| (m, 0.5));
+
+    let mut sum = [0.0; MOOD_DIM];
+    let mut weight = 0.0;
+    for (label, w) in tagged.chain(from_genres) {
+        weight += accumulate(&mut sum, &mood_prototype(label)?, w);
+    }
+    (weight > 0.0).then(|| sum.iter().map(|s| s / weight).collect())
+}
+
+/// Mood inference settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MoodConfig {
+    /// Inactivity gap that ends the current session (default: 30 minutes)
+    pub session_gap_minutes: i64,
+    /// Most recent events considered (default: 10)
+    pub max_session_length: usize,
+    /// Per-step decay applied to older session items (default: 0.7)
+    pub recency_decay: f32,
+    /// Completion below which an event counts as a skip (default: 0.25)
+    pub skip_completion: f32,
+    /// Weight of a fully skipping session (default: 1.5)
+    pub skip_weight: f32,
+    /// Weight of a session of rewatches (default: 1.0)
+    pub rewatch_weight: f32,
+    /// Weight of the time-of-day prior (default: 0.5)
+    pub time_weight: f32,
+    /// Behavioral evidence at which confidence reaches one half (default: 1.5)
+    pub evidence_half: f32,
+    /// Inferred moods below this confidence are not acted on (default: 0.35)
+    pub min_confidence: f32,
+    /// How long an explicit pick stays current (default: 3 hours)
+    pub explicit_ttl_hours: i64,
+    /// How long an inferred mood stays current (default: 30 minutes)
+    pub inferred_ttl_minutes: i64,
+    /// Half-life of an explicit pick's calibration weight (default: 60 days)
+    pub calibration_half_life_days: f32,
+    /// Pseudo-picks shrinking calibration toward zero (default: 3.0)
+    pub calibration_prior: f32,
+    /// Largest calibration offset per dimension (default: 0.25)
+    pub max_calibration: f32,
+    /// Observations older than this are pruned (default: 365 days)
+    pub observation_retention_days: i64,
+}
+
+impl Default for MoodConfig {
+    fn default() -> Self {
+        Self {
+            session_gap_minutes: 30,
+            max_session_length: 10,
+            recency_decay: 0.7,
+            skip_completion: 0.25,
+            skip_weight: 1.5,
+            rewatch_weight: 1.0,
+            time_weight: 0.5,
+            evidence_half: 1.5,
+            min_confidence: 0.35,
+            explicit_ttl_hours: 3,
+            inferred_ttl_minutes: 30,
+            calibration_half_life_days: 60.0,
+            calibration_prior: 3.0,
+            max_calibration: 0.25,
+            observation_retention_days: 365,
+        }
+    }
+}
+
+/// The trailing run of events with no gap longer than the session gap,
+/// skips and dismissals included, newest first
+fn recent_session<'a>(
+    events: &'a [ViewingEvent],
+    now: DateTime<Utc>,
+    config: &MoodConfig,
+) -> Vec<&'a ViewingEvent> {
+    let gap = Duration::minutes(config.session_gap_minutes);
+    let mut sorted: Vec<&ViewingEvent> = events.iter().filter(|e| e.timestamp <= now).collect();
+    sorted.sort_by_key(|e| std::cmp::Reverse(e.timestamp));
+
+    let mut session = Vec::new();
+    let mut previous = now;
+    for event in sorted {
+        if previous - event.timestamp > gap || session.len() >= config.max_session_length {
+            break;
+        }
+        previous = event.timestamp;
+        session.push(event);
+    }
+    session
+}
+
+/// An explicit mood pick next to what inference said at the time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MoodObservation {
+    pub user_id: Uuid,
+    pub state: MoodState,
+    /// Inferred mood vector when the user picked explicitly
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inferred: Option<Vec<f32>>,
+}
+
+/// Per-dimension offset learned from explicit picks
+///
+/// The decayed mean of `picked - inferred` over explicit picks, shrunk
+/// toward zero by `calibration_prior` pseudo-picks.
+pub fn mood_calibration(
+    observations: &[MoodObservation],
+    config: &MoodConfig,
+    now: DateTime<Utc>,
+) -> Vec<f32> {
+    let mut sum = [0.0; MOOD_DIM];
+    let mut weight = 0.0;
+    for observation in observations {
+        if observation.state.source != MoodSource::Explicit {
+            continue;
+        }
+        let Some(inferred) = observation.inferred.as_ref() else {
+            continue;
+        };
+        let age_days = (now - observation.state.timestamp).num_seconds().max(0) as f32 / 86_400.0;
+        let w = 0.5f32.powf(age_days / config.calibration_half_life_days);
+        for ((s, picked), inferred) in sum
+            .iter_mut()
+            .zip(&observation.state.mood_vector)
+            .zip(inferred)
+        {
+            *s += w * (picked - inferred);
+        }
+        weight += w;
+    }
+
+    let denominator = weight + config.calibration_prior;
+    sum.iter()
+        .map(|s| {
+            if denominator > 0.0 {
+                (s / denominator).clamp(-config.max_calibration, config.max_calibration)
+            } else {
+                0.0
+            }
+        })
+        .collect()
+}
+
+/// Infer a probable mood from in-session behavior
+///
+/// Evidence, each with its own weight:
+/// - moods of titles watched this session, by recency and completion
+///   (skipped titles are left out)
+/// - the share of skipped titles, pulling toward restless
+/// - the share of rewatches, pulling toward comfort viewing
+/// - the local time of day, as a weak prior
+///
+/// The weighted mean is shifted by the user's calibration. Confidence grows
+/// with behavioral evidence and with how clearly the vector matches a mood.
+pub struct InferMood;
+
+impl InferMood {
+    /// Inferred mood, or `None` without behavioral evidence
+    pub fn execute(
+        events: &[ViewingEvent],
+        get_content_attributes: impl Fn(Uuid) -> Option<ContentAttributes>,
+        local_hour: u32,
+        calibration: &[f32],
+        config: &MoodConfig,
+        now: DateTime<Utc>,
+    ) -> Option<MoodState> {
+        let session = recent_session(events, now, config);
+        if session.is_empty() {
+            return None;
+        }
+
+        let mut sum = [0.0; MOOD_DIM];
+        let mut total = 0.0;
+        let mut evidence = 0.0;
+        let mut tags = Vec::new();
+
+        let is_skip = |e: &ViewingEvent| e.dismissed || e.completion_rate < config.skip_completion;
+        let mut decay = 1.0;
+        let mut content_weight = 0.0;
+        for event in &session {
+            if !is_skip(event) {
+                if let Some(vector) =
+                    get_content_attributes(event.content_id).and_then(|a| content_mood_vector(&a))
+                {
+                    let w = decay * event.completion_rate.clamp(0.25, 1.0);
+                    content_weight += accumulate(&mut sum, &vector, w);
+                }
+            }
+            decay *= config.recency_decay;
+        }
+        if content_weight > 0.0 {
+            total += content_weight;
+            evidence += content_weight;
+            tags.push("recent_content".to_string());
+        }
+
+        let count = session.len() as f32;
+        let skip_rate = session.iter().filter(|e| is_skip(e)).count() as f32 / count;
+        if session.len() >= 2 && skip_rate > 0.0 {
+            let w = accumulate(&mut sum, &RESTLESS, config.skip_weight * skip_rate);
+            total += w;
+            evidence += w;
+            if skip_rate >= 0.5 {
+                tags.push("skipping".to_string());
+            }
+        }
+
+        let rewatch_rate = session.iter().filter(|e| e.is_rewatch).count() as f32 / count;
+        if rewatch_rate > 0.0 {
+            let w = accumulate(&mut sum, &COMFORT, config.rewatch_weight * rewatch_rate);
+            total += w;
+            evidence += w;
+            if rewatch_rate >= 0.5 {
+                tags.push("rewatching".to_string());
+            }
+        }
+
+        if evidence <= 0.0 {
+            return None;
+        }
+
+        let daypart = Daypart::from_hour(local_hour);
+        total += accumulate(&mut sum, &time_of_day_mood(daypart), config.time_weight);
+        tags.push(format!("{:?}", daypart).to_lowercase());
+
+        let mood_vector: Vec<f32> = sum
+            .iter()
+            .enumerate()
+            .map(|(i, s)| (s / total + calibration.get(i).copied().unwrap_or(0.0)).clamp(0.0, 1.0))
+            .collect();
+        let clarity = nearest_mood(&mood_vector).map_or(0.0, |(_, s)| s);
+        let confidence = evidence / (evidence + config.evidence_half) * clarity;
+
+        Some(MoodState {
+            timestamp: now,
+            mood_vector,
+            context_tags: tags,
+            source: MoodSource::Inferred,
+            confidence,
+        })
+    }
+}
+
+/// Label of the user's current mood, if any
+///
+/// The newest state wins: an explicit pick while it is younger than
+/// `explicit_ttl_hours`, an inferred mood while younger than
+/// `inferred_ttl_minutes` and at least `min_confidence`.
+pub fn current_mood_label(
+    history: &[MoodState],
+    now: DateTime<Utc>,
+    config: &MoodConfig,
+) -> Option<&'static str> {
+    let state = history.iter().max_by_key(|s| s.timestamp)?;
+    let age = now - state.timestamp;
+    let current = match state.source {
+        MoodSource::Explicit => age <= Duration::hours(config.explicit_ttl_hours),
+        MoodSource::Inferred => {
+            age <= Duration::minutes(config.inferred_ttl_minutes)
+                && state.confidence >= config.min_confidence
+        }
+    };
+    if !current {
+        return None;
+    }
+    nearest_mood(&state.mood_vector).map(|(label, _)| label)
+}
+
+/// The user's explicit pick while it is younger than `explicit_ttl_hours`
+///
+/// A newer pick of the same mood adds nothing to calibration, so callers
+/// only record picks that differ from this one.
+pub fn current_explicit_pick<'a>(
+    history: &'a [MoodState],
+    now: DateTime<Utc>,
+    config: &MoodConfig,
+) -> Option<&'a MoodState> {
+    history
+        .iter()
+        .filter(|s| s.source == MoodSource::Explicit)
+        .max_by_key(|s| s.timestamp)
+        .filter(|s| now - s.timestamp <= Duration::hours(config.explicit_ttl_hours))
+}
+
+/// Mood tags for titles from their embeddings
+///
+/// Each mood's centroid is the mean normalized embedding of titles carrying
+/// that mood in the catalog; a title is tagged with the moods whose
+/// centroids it is most similar to.
+#[derive(Debug, Clone, Default)]
+pub struct ContentMoodTagger {
+    centroids: Vec<(&'static str, Vec<f32>)>,
+}
+
+fn normalized(vector: &[f32]) -> Option<Vec<f32>> {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    (norm > f32::EPSILON).then(|| vector.iter().map(|v| v / norm).collect())
+}
+
+impl ContentMoodTagger {
+    /// Fit centroids from embeddings of titles with known moods
+    pub fn fit<'a>(examples: impl IntoIterator<Item = (&'a [f32], &'a [String])>) -> Self {
+        let mut sums: HashMap<&'static str, (Vec<f32>, usize)> = HashMap::new();
+        for (embedding, moods) in examples {
+            let Some(unit) = normalized(embedding) else {
+                continue;
+            };
+            for mood in moods.iter().filter_map(|m| canonical_mood(m)) {
+                let (sum, count) = sums
+                    .entry(mood)
+                    .or_insert_with(|| (vec![0.0; unit.len()], 0));
+                if sum.len() != unit.len() {
+                    continue;
+                }
+                for (s, v) in sum.iter_mut().zip(&unit) {
+                    *s += v;
+                }
+                *count += 1;
+            }
+        }
+
+        let mut centroids: Vec<(&'static str, Vec<f32>)> = sums
+            .into_iter()
+            .filter_map(|(mood, (sum, _))| normalized(&sum).map(|c| (mood, c)))
+            .collect();
+        centroids.sort_by_key(|(mood, _)| *mood);
+        Self { centroids }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.centroids.is_empty()
+    }
+
+    /// Up to `max_tags` moods with similarity of at least `min_similarity`,
+    /// most similar first
+    pub fn tag(
+        &self,
+        embedding: &[f32],
+        min_similarity: f32,
+        max_tags: usize,
+    ) -> Vec<(String, f32)> {
+        let Some(unit) = normalized(embedding) else {
+            return Vec::new();
+        };
+        let mut tags: Vec<(String, f32)> = self
+            .centroids
+            .iter()
+            .filter(|(_, c)| c.len() == unit.len())
+            .map(|(mood, c)| {
+                let s: f32 = c.iter().zip(&unit).map(|(a, b)| a * b).sum();
+                (mood.to_string(), s)
+            })
+            .filter(|(_, s)| *s >= min_similarity)
+            .collect();
+        tags.sort_by(|a, b| b.1.total_cmp(&a.1));
+        tags.truncate(max_tags);
+        tags
+    }
+}
+
+/// Mood observations and content mood tags
+#[async_trait::async_trait]
+pub trait MoodRepository: Send + Sync {
+    /// Store a mood state, with the inferred vector for explicit picks
+    async fn record(
+        &self,
+        user_id: Uuid,
+        state: &MoodState,
+        inferred: Option<&[f32]>,
+    ) -> Result<()>;
+
+    /// A user's stored observations, newest first
+    async fn recent(&self, user_id: Uuid, limit: usize) -> Result<Vec<MoodObservation>>;
+
+    /// Genres and mood tags of titles
+    async fn content_signals(
+        &self,
+        content_ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, ContentAttributes>>;
+
+    /// Titles with catalog mood tags
+    async fn labeled_content(&self, limit: usize) -> Result<Vec<(Uuid, Vec<String>)>>;
+
+    /// Titles without any mood tags that the tagger has not attempted since
+    /// `attempted_before`
+    async fn untagged_content(
+        &self,
+        limit: usize,
+        attempted_before: DateTime<Utc>,
+    ) -> Result<Vec<Uuid>>;
+
+    /// Remember that the tagger looked at these titles, tagged or not
+    async fn mark_tagging_attempted(&self, content_ids: &[Uuid], at: DateTime<Utc>) -> Result<()>;
+
+    /// Replace a title's embedding-derived mood tags
+    async fn save_inferred_tags(&self, content_id: Uuid, tags: &[(String, f32)]) -> Result<()>;
+
+    /// Delete observations recorded before `before`, and any beyond a user's
+    /// newest `keep_per_user`; returns the number deleted
+    async fn prune(&self, keep_per_user: usize, before: DateTime<Utc>) -> Result<u64>;
+}
+
+/// PostgreSQL implementation of MoodRepository
+pub struct PostgresMoodRepository {
+    pool: PgPool,
+}
+
+impl PostgresMoodRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl MoodRepository for PostgresMoodRepository {
+    #[instrument(skip(self, state, inferred))]
+    async fn record(
+        &self,
+        user_id: Uuid,
+        state: &MoodState,
+        inferred: Option<&[f32]>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO user_mood_observations
+                (user_id, source, mood_vector, inferred_vector, confidence, context_tags, recorded_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(user_id)
+        .bind(state.source.as_str())
+        .bind(&state.mood_vector)
+        .bind(inferred.map(|v| v.to_vec()))
+        .bind(state.confidence)
+        .bind(&state.context_tags)
+        .bind(state.timestamp)
+        .execute(&self.pool)
+        .await
+        .context("Failed to record mood observation")?;
+
+        Ok(())
+    }
+
+    async fn recent(&self, user_id: Uuid, limit: usize) -> Result<Vec<MoodObservation>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT source, mood_vector, inferred_vector, confidence, context_tags, recorded_at
+            FROM user_mood_observations
+            WHERE user_id = $1
+            ORDER BY recorded_at DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to load mood observations")?;
+
+        Ok(rows
+            .iter()
+            .filter_map(|row| {
+                let source: String = row.get("source");
+                Some(MoodObservation {
+                    user_id,
+                    state: MoodState {
+                        timestamp: row.get("recorded_at"),
+                        mood_vector: row.get("mood_vector"),
+                        context_tags: row.get("context_tags"),
+                        source: MoodSource::parse(&source)?,
+                        confidence: row.get("confidence"),
+                    },
+                    inferred: row.get("inferred_vector"),
+                })
+            })
+            .collect())
+    }
+
+    async fn content_signals(
+        &self,
+        content_ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, ContentAttributes>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT c.id,
+                   COALESCE(
+                       (SELECT ARRAY_AGG(cg.genre) FROM content_genres cg WHERE cg.content_id = c.id),
+                       '{}'
+                   ) AS genres,
+                   COALESCE(
+                       (SELECT ARRAY_AGG(cm.mood) FROM content_moods cm WHERE cm.content_id = c.id),
+                       '{}'
+                   ) AS moods
+            FROM content c
+            WHERE c.id = ANY($1)
+            "#,
+        )
+        .bind(content_ids)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to load content mood signals")?;
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let attributes = ContentAttributes {
+                    genres: row.get("genres"),
+                    moods: row.get("moods"),
+                    ..Default::default()
+                };
+                (row.get("id"), attributes)
+            })
+            .collect())
+    }
+
+    async fn labeled_content(&self, limit: usize) -> Result<Vec<(Uuid, Vec<String>)>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT content_id, ARRAY_AGG(mood) AS moods
+            FROM content_moods
+            WHERE source = 'catalog'
+            GROUP BY content_id
+            LIMIT $1
+            "#,
+        )
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to load mood-tagged content")?;
+
+        Ok(rows
+            .iter()
+            .map(|row| (row.get("content_id"), row.get("moods")))
+            .collect())
+    }
+
+    async fn untagged_content(
+        &self,
+        limit: usize,
+        attempted_before: DateTime<Utc>,
+    ) -> Result<Vec<Uuid>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT c.id
+            FROM content c
+            LEFT JOIN content_mood_tagging_attempts a ON a.content_id = c.id
+            WHERE NOT EXISTS (SELECT 1 FROM content_moods cm WHERE cm.content_id = c.id)
+              AND (a.attempted_at IS NULL OR a.attempted_at < $2)
+            ORDER BY a.attempted_at ASC NULLS FIRST, c.popularity_score DESC
+            LIMIT $1
+            "#,
+        )
+        .bind(limit as i64)
+        .bind(attempted_before)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to load untagged content")?;
+
+        Ok(rows.iter().map(|row| row.get("id")).collect())
+    }
+
+    async fn mark_tagging_attempted(&self, content_ids: &[Uuid], at: DateTime<Utc>) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO content_mood_tagging_attempts (content_id, attempted_at)
+            SELECT UNNEST($1::uuid[]), $2
+            ON CONFLICT (content_id) DO UPDATE SET attempted_at = EXCLUDED.attempted_at
+            "#,
+        )
+        .bind(content_ids)
+        .bind(at)
+        .execute(&self.pool)
+        .await
+        .context("Failed to record mood tagging attempts")?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self, tags))]
+    async fn save_inferred_tags(&self, content_id: Uuid, tags: &[(String, f32)]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM content_moods WHERE content_id = $1 AND source = 'inferred'")
+            .bind(content_id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to clear inferred mood tags")?;
+        for (mood, score) in tags {
+            sqlx::query(
+                r#"
+                INSERT INTO content_moods (content_id, mood, source, score)
+                VALUES ($1, $2, 'inferred', $3)
+                ON CONFLICT (content_id, mood) DO NOTHING
+                "#,
+            )
+            .bind(content_id)
+            .bind(mood)
+            .bind(score)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to save inferred mood tag")?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn prune(&self, keep_per_user: usize, before: DateTime<Utc>) -> Result<u64> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM user_mood_observations
+            WHERE recorded_at < $2
+               OR id IN (
+                   SELECT id FROM (
+                       SELECT id,
+                              ROW_NUMBER() OVER (
+                                  PARTITION BY user_id ORDER BY recorded_at DESC
+                              ) AS position
+                       FROM user_mood_observations
+                   ) ranked
+                   WHERE position > $1
+               )
+            "#,
+        )
+        .bind(keep_per_user as i64)
+        .bind(before)
+        .execute(&self.pool)
+        .await
+        .context("Failed to prune mood observations")?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+/// Mood tagging settings for untagged titles
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MoodTaggingConfig {
+    /// Catalog-tagged titles used to fit centroids (default: 5000)
+    pub training_limit: usize,
+    /// Untagged titles tagged per pass (default: 1000)
+    pub batch_size: usize,
+    /// Minimum cosine similarity to a mood centroid (default: 0.6)
+    pub min_similarity: f32,
+    /// Tags kept per title (default: 2)
+    pub max_tags: usize,
+    /// Titles that could not be tagged are retried after this long
+    /// (default: 30 days)
+    pub retry_after_days: i64,
+}
+
+impl Default for MoodTaggingConfig {
+    fn default() -> Self {
+        Self {
+            training_limit: 5000,
+            batch_size: 1000,
+            min_similarity: 0.6,
+            max_tags: 2,
+            retry_after_days: 30,
+        }
+    }
+}
+
+/// Tag untagged titles with moods derived from their embeddings; returns
+/// the number of titles tagged
+///
+/// Every title looked at is marked as attempted, so titles without an
+/// embedding or a close enough centroid don't crowd out the next batch.
+pub async fn tag_content_moods(
+    repository: &dyn MoodRepository,
+    embeddings: &dyn ContentEmbeddingStore,
+    config: &MoodTaggingConfig,
+) -> Result<usize> {
+    let labeled = repository.labeled_content(config.training_limit).await?;
+    let labeled_ids: Vec<Uuid> = labeled.iter().map(|(id, _)| *id).collect();
+    let labeled_embeddings = embeddings.content_embeddings(&labeled_ids).await?;
+    let tagger = ContentMoodTagger::fit(labeled.iter().filter_map(|(id, moods)| {
+        labeled_embeddings
+            .get(id)
+            .map(|e| (e.as_slice(), moods.as_slice()))
+    }));
+    if tagger.is_empty() {
+        debug!("No catalog mood tags to learn from; skipping mood tagging");
+        return Ok(0);
+    }
+
+    let now = Utc::now();
+    let batch = repository
+        .untagged_content(
+            config.batch_size,
+            now - Duration::days(config.retry_after_days),
+        )
+        .await?;
+
+    let batch_embeddings = embeddings.content_embeddings(&batch).await?;
+    let mut tagged = 0;
+    for &content_id in &batch {
+        let Some(embedding) = batch_embeddings.get(&content_id) else {
+            debug!("No embedding for content {}", content_id);
+            continue;
+        };
+        let tags = tagger.tag(embedding, config.min_similarity, config.max_tags);
+        if tags.is_empty() {
+            continue;
+        }
+        repository.save_inferred_tags(content_id, &tags).await?;
+        tagged += 1;
+    }
+    repository.mark_tagging_attempted(&batch, now).await?;
+
+    info!(
+        "Tagged {} of {} untagged titles with embedding-derived moods",
+        tagged,
+        batch.len()
+    );
+    Ok(tagged)
+}
+
+/// Tag untagged titles every `interval`
+pub fn spawn_tagging(
+    repository: Arc<dyn MoodRepository>,
+    embeddings: Arc<dyn ContentEmbeddingStore>,
+    config: MoodTaggingConfig,
+    interval: std::time::Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            if let Err(e) =
+                tag_content_moods(repository.as_ref(), embeddings.as_ref(), &config).await
+            {
+                warn!("Mood tagging pass failed: {}", e);
+            }
+        }
+    })
+}
+
+/// Drop mood observations past retention, and those no longer read, every
+/// `interval`
+pub fn spawn_pruning(
+    repository: Arc<dyn MoodRepository>,
+    config: MoodConfig,
+    interval: std::time::Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            let before = Utc::now() - Duration::days(config.observation_retention_days);
+            match repository.prune(OBSERVATION_LIMIT, before).await {
+                Ok(pruned) => debug!("Pruned {} mood observations", pruned),
+                Err(e) => warn!("Mood observation pruning failed: {}", e),
+            }
+        }
+    })
+}
+
+/// Mood inference and explicit-pick feedback for a user
+pub struct MoodService {
+    repository: Arc<dyn MoodRepository>,
+    config: MoodConfig,
+}
+
+/// Stored observations loaded per user
+const OBSERVATION_LIMIT: usize = 50;
+
+impl MoodService {
+    pub fn new(repository: Arc<dyn MoodRepository>, config: MoodConfig) -> Self {
+        Self { repository, config }
+    }
+
+    pub fn config(&self) -> &MoodConfig {
+        &self.config
+    }
+
+    /// Stored mood states, oldest first, followed by the mood inferred from
+    /// `events` when there is one
+    pub async fn mood_history(
+        &self,
+        user_id: Uuid,
+        events: &[ViewingEvent],
+        local_hour: u32,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<MoodState>> {
+        let observations = self.repository.recent(user_id, OBSERVATION_LIMIT).await?;
+        let inferred = self
+            .infer_with(&observations, events, local_hour, now)
+            .await?;
+
+        let mut history: Vec<MoodState> = observations.into_iter().rev().map(|o| o.state).collect();
+        history.extend(inferred);
+        Ok(history)
+    }
+
+    /// Mood inferred from `events`, calibrated by the user's explicit picks
+    pub async fn infer(
+        &self,
+        user_id: Uuid,
+        events: &[ViewingEvent],
+        local_hour: u32,
+        now: DateTime<Utc>,
+    ) -> Result<Option<MoodState>> {
+        let observations = self.repository.recent(user_id, OBSERVATION_LIMIT).await?;
+        self.infer_with(&observations, events, local_hour, now)
+            .await
+    }
+
+    async fn infer_with(
+        &self,
+        observations: &[MoodObservation],
+        events: &[ViewingEvent],
+        local_hour: u32,
+        now: DateTime<Utc>,
+    ) -> Result<Option<MoodState>> {
+        let session_ids: Vec<Uuid> = recent_session(events, now, &self.config)
+            .iter()
+            .map(|e| e.content_id)
+            .collect();
+        if session_ids.is_empty() {
+            return Ok(None);
+        }
+        let signals = self.repository.content_signals(&session_ids).await?;
+        let calibration = mood_calibration(observations, &self.config, now);
+
+        Ok(InferMood::execute(
+            events,
+            |id| signals.get(&id).cloned(),
+            local_hour,
+            &calibration,
+            &self.config,
+            now,
+        ))
+    }
+
+    /// Record a mood the user picked, storing what inference said alongside
+    /// it so later inferences are calibrated toward the user's own labels
+    ///
+    /// Repeating the current pick is not recorded again; the stored pick is
+    /// returned instead.
+    pub async fn record_explicit(
+        &self,
+        user_id: Uuid,
+        mood: &str,
+        events: &[ViewingEvent],
+        local_hour: u32,
+        now: DateTime<Utc>,
+    ) -> Result<MoodState> {
+        let label = canonical_mood(mood).ok_or_else(|| anyhow!("Unknown mood: {}", mood))?;
+        let latest: Vec<MoodState> = self
+            .repository
+            .recent(user_id, 1)
+            .await?
+            .into_iter()
+            .map(|o| o.state)
+            .collect();
+        if let Some(current) = current_explicit_pick(&latest, now, &self.config) {
+            if current.context_tags.iter().any(|tag| tag == label) {
+                return Ok(current.clone());
+            }
+        }
+
+        let mood_vector = mood_prototype(label).expect("canonical moods have prototypes");
+
+        // Calibrate against the uncalibrated inference so offsets don't compound
+        let inferred = self.infer_with(&[], events, local_hour, now).await?;
+        let state = MoodState {
+            timestamp: now,
+            mood_vector,
+            context_tags: vec![label.to_string()],
+            source: MoodSource::Explicit,
+            confidence: 1.0,
+        };
+        self.repository
+            .record(
+                user_id,
+                &state,
+                inferred.as_ref().map(|s| s.mood_vector.as_slice()),
+            )
+            .await?;
+
+        debug!(
+            user_id = %user_id,
+            mood = label,
+            inferred = ?inferred.as_ref().and_then(|s| nearest_mood(&s.mood_vector)).map(|(l, _)| l),
+            "Recorded explicit mood"
+        );
+        Ok(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(id: u128, minutes_ago: i64, completion: f32, now: DateTime<Utc>) -> ViewingEvent {
+        ViewingEvent {
+            content_id: Uuid::from_u128(id),
+            timestamp: now - Duration::minutes(minutes_ago),
+            completion_rate: completion,
+            rating: None,
+            is_rewatch: false,
+            dismissed: false,
+        }
+    }
+
+    fn genre(g: &str) -> Option<ContentAttributes> {
+        Some(ContentAttributes {
+            genres: vec![g.to_string()],
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_prototypes_round_trip() {
+        for (label, prototype) in MOOD_PROTOTYPES {
+            assert_eq!(nearest_mood(&prototype).unwrap().0, label);
+        }
+        assert_eq!(canonical_mood("Joyful"), Some("happy"));
+        assert!(mood_prototype("grumpy").is_none());
+        assert!(nearest_mood(&[0.5; MOOD_DIM]).is_none());
+    }
+
+    #[test]
+    fn test_infers_mood_from_recent_genres() {
+        let now = Utc::now();
+        let events = vec![
+            event(1, 90, 1.0, now),
+            event(2, 50, 0.9, now),
+            event(3, 5, 1.0, now),
+        ];
+        let mood = InferMood::execute(
+            &events,
+            |_| genre("Comedy"),
+            20,
+            &[],
+            &MoodConfig::default(),
+            now,
+        )
+        .unwrap();
+
+        assert_eq!(mood.source, MoodSource::Inferred);
+        assert_eq!(nearest_mood(&mood.mood_vector).unwrap().0, "happy");
+        assert!(mood.confidence > 0.35);
+        assert!(mood.context_tags.contains(&"evening".to_string()));
+    }
+
+    #[test]
+    fn test_skipping_and_rewatching() {
+        let now = Utc::now();
+        let config = MoodConfig::default();
+
+        // Skipping through several titles reads as restless
+        let skipping: Vec<_> = (0..4)
+            .map(|i| event(i, 5 + i as i64 * 3, 0.05, now))
+            .collect();
+        let mood = InferMood::execute(&skipping, |_| None, 14, &[], &config, now).unwrap();
+        assert!(mood.context_tags.contains(&"skipping".to_string()));
+        assert_eq!(nearest_mood(&mood.mood_vector).unwrap().0, "excited");
+
+        // Rewatching favorites reads as comfort viewing
+        let rewatching: Vec<_> = (0..3)
+            .map(|i| ViewingEvent {
+                is_rewatch: true,
+                ..event(i, 10 + i as i64 * 20, 1.0, now)
+            })
+            .collect();
+        let mood = InferMood::execute(&rewatching, |_| None, 21, &[], &config, now).unwrap();
+        assert!(mood.context_tags.contains(&"rewatching".to_string()));
+        let label = nearest_mood(&mood.mood_vector).unwrap().0;
+        assert!(label == "relaxed" || label == "nostalgic", "{}", label);
+
+        // No session, no inference
+        let stale = vec![event(1, 300, 1.0, now)];
+        assert!(InferMood::execute(&stale, |_| genre("comedy"), 21, &[], &config, now).is_none());
+    }
+
+    #[test]
+    fn test_explicit_picks_calibrate_inference() {
+        let now = Utc::now();
+        let config = MoodConfig::default();
+        let events = vec![event(1, 20, 1.0, now), event(2, 5, 1.0, now)];
+        let drama = |_| genre("drama");
+        let before = InferMood::execute(&events, drama, 21, &[], &config, now).unwrap();
+        assert_eq!(nearest_mood(&before.mood_vector).unwrap().0, "sad");
+
+        // This user keeps saying dramas at night put them in a curious mood
+        let observations: Vec<_> = (0..10)
+            .map(|i| MoodObservation {
+                user_id: Uuid::nil(),
+                state: MoodState {
+                    timestamp: now - Duration::days(i),
+                    mood_vector: mood_prototype("curious").unwrap(),
+                    context_tags: vec![],
+                    source: MoodSource::Explicit,
+                    confidence: 1.0,
+                },
+                inferred: Some(before.mood_vector.clone()),
+            })
+            .collect();
+        let calibration = mood_calibration(&observations, &config, now);
+        assert!(calibration[4] > 0.0); // focused
+        assert!(calibration[3] < 0.0); // sad
+        assert!(calibration
+            .iter()
+            .all(|c| c.abs() <= config.max_calibration));
+
+        let after = InferMood::execute(&events, drama, 21, &calibration, &config, now).unwrap();
+        assert_eq!(nearest_mood(&after.mood_vector).unwrap().0, "curious");
+    }
+
+    #[test]
+    fn test_current_mood_label() {
+        let now = Utc::now();
+        let config = MoodConfig::default();
+        let state = |label: &str, source, confidence, minutes_ago| MoodState {
+            timestamp: now - Duration::minutes(minutes_ago),
+            mood_vector: mood_prototype(label).unwrap(),
+            context_tags: vec![],
+            source,
+            confidence,
+        };
+
+        let explicit = state("relaxed", MoodSource::Explicit, 1.0, 60);
+        assert_eq!(
+            current_mood_label(std::slice::from_ref(&explicit), now, &config),
+            Some("relaxed")
+        );
+
+        // A newer confident inference takes over; an unsure one doesn't count
+        let inferred = state("excited", MoodSource::Inferred, 0.6, 5);
+        assert_eq!(
+            current_mood_label(&[explicit.clone(), inferred], now, &config),
+            Some("excited")
+        );
+        let unsure = state("excited", MoodSource::Inferred, 0.1, 5);
+        assert_eq!(current_mood_label(&[explicit, unsure], now, &config), None);
+
+        let expired = state("sad", MoodSource::Explicit, 1.0, 600);
+        assert_eq!(current_mood_label(&[expired], now, &config), None);
+    }
+
+    #[test]
+    fn test_current_explicit_pick() {
+        let now = Utc::now();
+        let config = MoodConfig::default();
+        let state = |label: &str, source, minutes_ago| MoodState {
+            timestamp: now - Duration::minutes(minutes_ago),
+            mood_vector: mood_prototype(label).unwrap(),
+            context_tags: vec![label.to_string()],
+            source,
+            confidence: 1.0,
+        };
+
+        // Inferred moods are not picks; the newest pick wins while current
+        let history = vec![
+            state("sad", MoodSource::Explicit, 90),
+            state("relaxed", MoodSource::Explicit, 30),
+            state("excited", MoodSource::Inferred, 5),
+        ];
+        let pick = current_explicit_pick(&history, now, &config).unwrap();
+        assert_eq!(pick.context_tags, vec!["relaxed".to_string()]);
+
+        let expired = vec![state("relaxed", MoodSource::Explicit, 600)];
+        assert!(current_explicit_pick(&expired, now, &config).is_none());
+    }
+
+    #[test]
+    fn test_tagger_from_embeddings() {
+        let comedy = vec![1.0, 0.1, 0.0];
+        let horror = vec![0.0, 0.2, 1.0];
+        let happy = vec!["happy".to_string()];
+        let scared = vec!["fearful".to_string()];
+        let tagger = ContentMoodTagger::fit(vec![
+            (comedy.as_slice(), happy.as_slice()),
+            (horror.as_slice(), scared.as_slice()),
+        ]);
+
+        let tags = tagger.tag(&[0.9, 0.0, 0.1], 0.6, 2);
+        assert_eq!(tags.len(), 1);
+        assert_eq!(tags[0].0, "happy");
+        assert_eq!(tagger.tag(&[0.0, 1.0, 0.0], 0.6, 2), Vec::new());
+
+        // Derived tags feed content mood vectors
+        let attributes = ContentAttributes {
+            moods: vec![tags[0].0.clone()],
+            genres: vec!["drama".to_string()],
+            ..Default::default()
+        };
+        let vector = content_mood_vector(&attributes).unwrap();
+        assert_eq!(nearest_mood(&vector).unwrap().0, "happy");
+    }
+}
@@ -0,0 +1,739 @@
+//! Negative Feedback and "Not Interested" Suppression
+//!
+//! Collects explicit "not interested" signals on a title, genre or person and
+//! implicit negatives derived from viewing: dismissals, abandoning a title
+//! within the first minutes, and low ratings. Each signal's strength decays
+//! exponentially with age, implicit signals faster than explicit ones.
+//!
+//! A [`SuppressionSet`] is the decayed view of a user's active signals. It
+//! removes strongly suppressed titles from candidate pools and scales down
+//! titles sharing a suppressed genre or person. Its [`SuppressionSummary`] is
+//! what search personalization applies to result lists. Users can list their
+//! signals and undo any of them; undone signals are kept, marked revoked.
+
+use crate::diversity::ContentAttributes;
+use crate::types::{ScoredContent, ViewingEvent};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Row};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{debug, instrument, warn};
+use uuid::Uuid;
+
+/// What a negative signal is about
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum SuppressionTarget {
+    Content(Uuid),
+    /// Genre name, matched case-insensitively
+    Genre(String),
+    /// Cast or crew member, matched case-insensitively
+    Person(String),
+}
+
+impl SuppressionTarget {
+    fn kind(&self) -> &'static str {
+        match self {
+            SuppressionTarget::Content(_) => "content",
+            SuppressionTarget::Genre(_) => "genre",
+            SuppressionTarget::Person(_) => "person",
+        }
+    }
+
+    fn value(&self) -> String {
+        match self {
+            SuppressionTarget::Content(id) => id.to_string(),
+            SuppressionTarget::Genre(name) | SuppressionTarget::Person(name) => name.clone(),
+        }
+    }
+
+    fn parse(kind: &str, value: &str) -> Option<Self> {
+        match kind {
+            "content" => Uuid::parse_str(value).ok().map(SuppressionTarget::Content),
+            "genre" => Some(SuppressionTarget::Genre(value.to_string())),
+            "person" => Some(SuppressionTarget::Person(value.to_string())),
+            _ => None,
+        }
+    }
+}
+
+/// How a negative signal was obtained
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedbackSource {
+    /// The user said "not interested"
+    NotInterested,
+    /// The user dismissed a recommendation
+    Dismissed,
+    /// Playback stopped within the first minutes
+    Abandoned,
+    /// Rated at or below the dislike threshold
+    Disliked,
+}
+
+impl FeedbackSource {
+    pub fn is_explicit(self) -> bool {
+        matches!(self, FeedbackSource::NotInterested)
+    }
+
+    /// Strength of a fresh signal from this source
+    fn initial_strength(self) -> f32 {
+        match self {
+            FeedbackSource::NotInterested => 1.0,
+            FeedbackSource::Disliked => 0.9,
+            FeedbackSource::Dismissed => 0.8,
+            FeedbackSource::Abandoned => 0.5,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            FeedbackSource::NotInterested => "not_interested",
+            FeedbackSource::Dismissed => "dismissed",
+            FeedbackSource::Abandoned => "abandoned",
+            FeedbackSource::Disliked => "disliked",
+        }
+    }
+
+    fn parse(source: &str) -> Option<Self> {
+        match source {
+            "not_interested" => Some(FeedbackSource::NotInterested),
+            "dismissed" => Some(FeedbackSource::Dismissed),
+            "abandoned" => Some(FeedbackSource::Abandoned),
+            "disliked" => Some(FeedbackSource::Disliked),
+            _ => None,
+        }
+    }
+}
+
+/// Negative feedback settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NegativeFeedbackConfig {
+    /// Half-life of explicit signals (default: 180 days)
+    pub explicit_half_life_days: f32,
+    /// Half-life of implicit signals (default: 30 days)
+    pub implicit_half_life_days: f32,
+    /// Playback stopped within this many minutes counts as abandoned
+    /// (default: 5)
+    pub abandon_within_minutes: f32,
+    /// Completion below which playback counts as abandoned when the runtime
+    /// is unknown (default: 0.05)
+    pub abandon_completion_rate: f32,
+    /// Ratings at or below this count as disliked (default: 2)
+    pub dislike_rating: u8,
+    /// Titles whose decayed strength reaches this are removed outright
+    /// (default: 0.5)
+    pub block_threshold: f32,
+    /// Score reduction for a fully suppressed genre (default: 0.6)
+    pub genre_penalty: f32,
+    /// Score reduction for a fully suppressed person (default: 0.4)
+    pub person_penalty: f32,
+    /// Decayed signals weaker than this are ignored (default: 0.05)
+    pub min_strength: f32,
+    /// Most recent signals loaded per user (default: 500)
+    pub max_signals: usize,
+    /// Revoked signals are kept this long for auditing (default: 90 days)
+    pub revoked_retention_days: i64,
+}
+
+impl Default for NegativeFeedbackConfig {
+    fn default() -> Self {
+        Self {
+            explicit_half_life_days: 180.0,
+            implicit_half_life_days: 30.0,
+            abandon_within_minutes: 5.0,
+            abandon_completion_rate: 0.05,
+            dislike_rating: 2,
+            block_threshold: 0.5,
+            genre_penalty: 0.6,
+            person_penalty: 0.4,
+            min_strength: 0.05,
+            max_signals: 500,
+            revoked_retention_days: 90,
+        }
+    }
+}
+
+impl NegativeFeedbackConfig {
+    /// Age at which a signal from `source` has decayed below `min_strength`
+    pub fn fade_after(&self, source: FeedbackSource) -> chrono::Duration {
+        let half_life = if source.is_explicit() {
+            self.explicit_half_life_days
+        } else {
+            self.implicit_half_life_days
+        };
+        let half_lives = (source.initial_strength() / self.min_strength.max(f32::EPSILON))
+            .log2()
+            .max(0.0);
+        chrono::Duration::seconds((half_life * half_lives * 86_400.0) as i64)
+    }
+}
+
+/// One recorded negative signal
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NegativeFeedback {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub target: SuppressionTarget,
+    pub source: FeedbackSource,
+    pub created_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl NegativeFeedback {
+    pub fn new(user_id: Uuid, target: SuppressionTarget, source: FeedbackSource) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            user_id,
+            target,
+            source,
+            created_at: Utc::now(),
+            revoked_at: None,
+        }
+    }
+
+    /// Implicit negative implied by a viewing event, if any
+    ///
+    /// With a known runtime, stopping within `abandon_within_minutes` counts
+    /// as an abandon; otherwise a completion below `abandon_completion_rate`
+    /// does. Rewatches never count as abandons.
+    pub fn from_viewing_event(
+        user_id: Uuid,
+        event: &ViewingEvent,
+        runtime_minutes: Option<f32>,
+        config: &NegativeFeedbackConfig,
+    ) -> Option<Self> {
+        let abandoned = !event.is_rewatch
+            && event.completion_rate < 1.0
+            && match runtime_minutes {
+                Some(runtime) => event.completion_rate * runtime <= config.abandon_within_minutes,
+                None => event.completion_rate < config.abandon_completion_rate,
+            };
+
+        let source = if event.dismissed {
+            FeedbackSource::Dismissed
+        } else if event.rating.is_some_and(|r| r <= config.dislike_rating) {
+            FeedbackSource::Disliked
+        } else if abandoned {
+            FeedbackSource::Abandoned
+        } else {
+            return None;
+        };
+
+        Some(Self {
+            id: Uuid::new_v4(),
+            user_id,
+            target: SuppressionTarget::Content(event.content_id),
+            source,
+            created_at: event.timestamp,
+            revoked_at: None,
+        })
+    }
+
+    /// Decayed strength at `now`; zero once revoked
+    pub fn strength(&self, config: &NegativeFeedbackConfig, now: DateTime<Utc>) -> f32 {
+        if self.revoked_at.is_some() {
+            return 0.0;
+        }
+
+        let half_life = if self.source.is_explicit() {
+            config.explicit_half_life_days
+        } else {
+            config.implicit_half_life_days
+        };
+        let age_days = (now - self.created_at).num_seconds().max(0) as f32 / 86_400.0;
+
+        self.source.initial_strength() * 0.5f32.powf(age_days / half_life)
+    }
+}
+
+/// Decayed suppressions a user's results are filtered through
+#[derive(Debug, Clone, Default)]
+pub struct SuppressionSet {
+    content: HashMap<Uuid, f32>,
+    genres: HashMap<String, f32>,
+    people: HashMap<String, f32>,
+    block_threshold: f32,
+    genre_penalty: f32,
+    person_penalty: f32,
+}
+
+/// Compact form of a [`SuppressionSet`] for other services
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SuppressionSummary {
+    /// Titles to remove
+    pub blocked_content: Vec<Uuid>,
+    /// Score multiplier per title for weaker title-level signals
+    pub content_penalties: HashMap<Uuid, f32>,
+    /// Score multiplier per lowercased genre
+    pub genre_penalties: HashMap<String, f32>,
+    /// Score multiplier per lowercased person
+    pub person_penalties: HashMap<String, f32>,
+}
+
+impl SuppressionSet {
+    /// Strongest decayed signal per target, ignoring revoked and faded ones
+    pub fn from_feedback(
+        feedback: &[NegativeFeedback],
+        config: &NegativeFeedbackConfig,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let mut set = Self {
+            block_threshold: config.block_threshold,
+            genre_penalty: config.genre_penalty,
+            person_penalty: config.person_penalty,
+            ..Default::default()
+        };
+
+        for signal in feedback {
+            let strength = signal.strength(config, now);
+            if strength < config.min_strength {
+                continue;
+            }
+
+            let current = match &signal.target {
+                SuppressionTarget::Content(id) => set.content.entry(*id).or_insert(0.0),
+                SuppressionTarget::Genre(genre) => {
+                    set.genres.entry(genre.to_lowercase()).or_insert(0.0)
+                }
+                SuppressionTarget::Person(person) => {
+                    set.people.entry(person.to_lowercase()).or_insert(0.0)
+                }
+            };
+            *current = current.max(strength);
+        }
+
+        set
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.content.is_empty() && self.genres.is_empty() && self.people.is_empty()
+    }
+
+    /// Whether a title is suppressed outright
+    pub fn is_blocked(&self, content_id: Uuid) -> bool {
+        self.content
+            .get(&content_id)
+            .is_some_and(|&s| s >= self.block_threshold)
+    }
+
+    /// Score multiplier in [0, 1] for a title that is not blocked
+    pub fn penalty(&self, content_id: Uuid, attributes: Option<&ContentAttributes>) -> f32 {
+        let mut multiplier = 1.0 - self.content.get(&content_id).copied().unwrap_or(0.0);
+
+        if let Some(attributes) = attributes {
+            let strongest = |names: &[String], suppressed: &HashMap<String, f32>| {
+                names
+                    .iter()
+                    .filter_map(|n| suppressed.get(&n.to_lowercase()))
+                    .fold(0.0f32, |a, &b| a.max(b))
+            };
+            multiplier *= 1.0 - self.genre_penalty * strongest(&attributes.genres, &self.genres);
+            multiplier *= 1.0 - self.person_penalty * strongest(&attributes.people, &self.people);
+        }
+
+        multiplier.clamp(0.0, 1.0)
+    }
+
+    /// Drop blocked titles and scale the rest by their penalty, keeping
+    /// score order
+    pub fn apply(
+        &self,
+        candidates: Vec<ScoredContent>,
+        get_content_attributes: impl Fn(Uuid) -> Option<ContentAttributes>,
+    ) -> Vec<ScoredContent> {
+        if self.is_empty() {
+            return candidates;
+        }
+
+        let before = candidates.len();
+        let mut kept: Vec<ScoredContent> = candidates
+            .into_iter()
+            .filter(|c| !self.is_blocked(c.content_id))
+            .map(|mut c| {
+                let attributes = get_content_attributes(c.content_id);
+                c.score *= self.penalty(c.content_id, attributes.as_ref());
+                c
+            })
+            .collect();
+        kept.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        debug!(
+            blocked = before - kept.len(),
+            kept = kept.len(),
+            "Applied negative feedback suppressions"
+        );
+        kept
+    }
+
+    pub fn summary(&self) -> SuppressionSummary {
+        let multipliers = |suppressed: &HashMap<String, f32>, penalty: f32| {
+            suppressed
+                .iter()
+                .map(|(name, &s)| (name.clone(), 1.0 - penalty * s))
+                .collect()
+        };
+
+        SuppressionSummary {
+            blocked_content: self
+                .content
+                .keys()
+                .copied()
+                .filter(|&id| self.is_blocked(id))
+                .collect(),
+            content_penalties: self
+                .content
+                .iter()
+                .filter(|(&id, _)| !self.is_blocked(id))
+                .map(|(&id, &s)| (id, 1.0 - s))
+                .collect(),
+            genre_penalties: multipliers(&self.genres, self.genre_penalty),
+            person_penalties: multipliers(&self.people, self.person_penalty),
+        }
+    }
+}
+
+/// Negative feedback storage
+#[async_trait::async_trait]
+pub trait NegativeFeedbackRepository: Send + Sync {
+    /// Store a signal
+    async fn record(&self, feedback: &NegativeFeedback) -> Result<()>;
+
+    /// A user's most recent signals, newest first
+    async fn list(
+        &self,
+        user_id: Uuid,
+        include_revoked: bool,
+        limit: usize,
+    ) -> Result<Vec<NegativeFeedback>>;
+
+    /// Undo a signal; returns false when the user has no such active signal
+    async fn revoke(&self, user_id: Uuid, feedback_id: Uuid) -> Result<bool>;
+
+    /// Delete signals that have faded out and revoked signals past their
+    /// retention; returns the number deleted
+    async fn prune(&self, config: &NegativeFeedbackConfig, now: DateTime<Utc>) -> Result<u64>;
+}
+
+/// Run [`NegativeFeedbackRepository::prune`] every `interval`
+pub fn spawn_pruning(
+    repository: Arc<dyn NegativeFeedbackRepository>,
+    config: NegativeFeedbackConfig,
+    interval: std::time::Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match repository.prune(&config, Utc::now()).await {
+                Ok(pruned) => debug!("Pruned {} negative feedback signals", pruned),
+                Err(e) => warn!("Negative feedback pruning failed: {}", e),
+            }
+        }
+    })
+}
+
+/// PostgreSQL implementation of NegativeFeedbackRepository
+pub struct PostgresNegativeFeedbackRepository {
+    pool: PgPool,
+}
+
+impl PostgresNegativeFeedbackRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl NegativeFeedbackRepository for PostgresNegativeFeedbackRepository {
+    #[instrument(skip(self, feedback), fields(user_id = %feedback.user_id))]
+    async fn record(&self, feedback: &NegativeFeedback) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO negative_feedback
+                (id, user_id, target_kind, target_value, source, created_at, revoked_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(feedback.id)
+        .bind(feedback.user_id)
+        .bind(feedback.target.kind())
+        .bind(feedback.target.value())
+        .bind(feedback.source.as_str())
+        .bind(feedback.created_at)
+        .bind(feedback.revoked_at)
+        .execute(&self.pool)
+        .await
+        .context("Failed to record negative feedback")?;
+
+        Ok(())
+    }
+
+    async fn list(
+        &self,
+        user_id: Uuid,
+        include_revoked: bool,
+        limit: usize,
+    ) -> Result<Vec<NegativeFeedback>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, user_id, target_kind, target_value, source, created_at, revoked_at
+            FROM negative_feedback
+            WHERE user_id = $1 AND ($2 OR revoked_at IS NULL)
+            ORDER BY created_at DESC
+            LIMIT $3
+            "#,
+        )
+        .bind(user_id)
+        .bind(include_revoked)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to list negative feedback")?;
+
+        Ok(rows
+            .iter()
+            .filter_map(|row| {
+                let kind: String = row.get("target_kind");
+                let value: String = row.get("target_value");
+                let source: String = row.get("source");
+                Some(NegativeFeedback {
+                    id: row.get("id"),
+                    user_id: row.get("user_id"),
+                    target: SuppressionTarget::parse(&kind, &value)?,
+                    source: FeedbackSource::parse(&source)?,
+                    created_at: row.get("created_at"),
+                    revoked_at: row.get("revoked_at"),
+                })
+            })
+            .collect())
+    }
+
+    #[instrument(skip(self))]
+    async fn revoke(&self, user_id: Uuid, feedback_id: Uuid) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE negative_feedback
+            SET revoked_at = NOW()
+            WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL
+            "#,
+        )
+        .bind(feedback_id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await
+        .context("Failed to revoke negative feedback")?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    #[instrument(skip(self, config))]
+    async fn prune(&self, config: &NegativeFeedbackConfig, now: DateTime<Utc>) -> Result<u64> {
+        let faded_before = |source| now - config.fade_after(source);
+        let result = sqlx::query(
+            r#"
+            DELETE FROM negative_feedback
+            WHERE revoked_at < $1
+               OR created_at < CASE source
+                      WHEN 'not_interested' THEN $2
+                      WHEN 'disliked' THEN $3
+                      WHEN 'dismissed' THEN $4
+                      ELSE $5
+                  END
+            "#,
+        )
+        .bind(now - chrono::Duration::days(config.revoked_retention_days))
+        .bind(faded_before(FeedbackSource::NotInterested))
+        .bind(faded_before(FeedbackSource::Disliked))
+        .bind(faded_before(FeedbackSource::Dismissed))
+        .bind(faded_before(FeedbackSource::Abandoned))
+        .execute(&self.pool)
+        .await
+        .context("Failed to prune negative feedback")?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::RecommendationType;
+    use chrono::Duration;
+
+    fn viewing(completion_rate: f32) -> ViewingEvent {
+        ViewingEvent {
+            content_id: Uuid::new_v4(),
+            timestamp: Utc::now(),
+            completion_rate,
+            rating: None,
+            is_rewatch: false,
+            dismissed: false,
+        }
+    }
+
+    fn candidate(id: u128, score: f32) -> ScoredContent {
+        ScoredContent {
+            content_id: Uuid::from_u128(id),
+            score,
+            source: RecommendationType::Collaborative,
+            based_on: vec![],
+        }
+    }
+
+    #[test]
+    fn test_implicit_negatives_from_viewing() {
+        let config = NegativeFeedbackConfig::default();
+        let user_id = Uuid::new_v4();
+        let source = |event: &ViewingEvent, runtime: Option<f32>| {
+            NegativeFeedback::from_viewing_event(user_id, event, runtime, &config).map(|f| f.source)
+        };
+
+        // Three minutes into a two-hour film
+        assert_eq!(
+            source(&viewing(0.025), Some(120.0)),
+            Some(FeedbackSource::Abandoned)
+        );
+        // Ten minutes of a 22-minute episode is not an abandon
+        assert_eq!(source(&viewing(0.45), Some(22.0)), None);
+        assert_eq!(
+            source(&viewing(0.02), None),
+            Some(FeedbackSource::Abandoned)
+        );
+
+        let mut rewatch = viewing(0.02);
+        rewatch.is_rewatch = true;
+        assert_eq!(source(&rewatch, None), None);
+
+        let mut dismissed = viewing(0.0);
+        dismissed.dismissed = true;
+        assert_eq!(source(&dismissed, None), Some(FeedbackSource::Dismissed));
+
+        let mut disliked = viewing(1.0);
+        disliked.rating = Some(1);
+        assert_eq!(source(&disliked, None), Some(FeedbackSource::Disliked));
+    }
+
+    #[test]
+    fn test_strength_decays_faster_for_implicit_signals() {
+        let config = NegativeFeedbackConfig::default();
+        let user_id = Uuid::new_v4();
+        let target = SuppressionTarget::Content(Uuid::new_v4());
+        let explicit =
+            NegativeFeedback::new(user_id, target.clone(), FeedbackSource::NotInterested);
+        let implicit = NegativeFeedback::new(user_id, target, FeedbackSource::Dismissed);
+
+        let later = explicit.created_at + Duration::days(30);
+        assert!((implicit.strength(&config, later) - 0.4).abs() < 1e-3);
+        assert!(explicit.strength(&config, later) > 0.85);
+
+        let mut revoked = explicit.clone();
+        revoked.revoked_at = Some(later);
+        assert_eq!(revoked.strength(&config, later), 0.0);
+    }
+
+    #[test]
+    fn test_signals_fade_below_min_strength_after_fade_age() {
+        let config = NegativeFeedbackConfig::default();
+        let user_id = Uuid::new_v4();
+        for source in [FeedbackSource::NotInterested, FeedbackSource::Abandoned] {
+            let feedback =
+                NegativeFeedback::new(user_id, SuppressionTarget::Content(Uuid::new_v4()), source);
+            let faded_at = feedback.created_at + config.fade_after(source);
+            let strength = feedback.strength(&config, faded_at);
+            assert!((strength - config.min_strength).abs() < 1e-3);
+            assert!(feedback.strength(&config, faded_at + Duration::days(1)) < config.min_strength);
+        }
+        assert!(
+            config.fade_after(FeedbackSource::NotInterested)
+                > config.fade_after(FeedbackSource::Abandoned)
+        );
+    }
+
+    #[test]
+    fn test_suppression_set_blocks_and_penalizes() {
+        let config = NegativeFeedbackConfig::default();
+        let user_id = Uuid::new_v4();
+        let now = Utc::now();
+        let feedback = vec![
+            NegativeFeedback::new(
+                user_id,
+                SuppressionTarget::Content(Uuid::from_u128(1)),
+                FeedbackSource::NotInterested,
+            ),
+            NegativeFeedback::new(
+                user_id,
+                SuppressionTarget::Genre("Horror".to_string()),
+                FeedbackSource::NotInterested,
+            ),
+            // Long faded
+            NegativeFeedback {
+                created_at: now - Duration::days(365),
+                ..NegativeFeedback::new(
+                    user_id,
+                    SuppressionTarget::Content(Uuid::from_u128(4)),
+                    FeedbackSource::Abandoned,
+                )
+            },
+        ];
+        let set = SuppressionSet::from_feedback(&feedback, &config, now);
+
+        let attributes = |id: Uuid| {
+            Some(ContentAttributes {
+                genres: vec![if id.as_u128() == 2 {
+                    "horror"
+                } else {
+                    "comedy"
+                }
+                .to_string()],
+                ..Default::default()
+            })
+        };
+        let results = set.apply(
+            vec![
+                candidate(1, 0.9),
+                candidate(2, 0.8),
+                candidate(3, 0.5),
+                candidate(4, 0.4),
+            ],
+            attributes,
+        );
+
+        let ids: Vec<u128> = results.iter().map(|c| c.content_id.as_u128()).collect();
+        assert_eq!(ids, vec![3, 4, 2]);
+        assert!((results[2].score - 0.8 * 0.4).abs() < 1e-4);
+
+        let summary = set.summary();
+        assert_eq!(summary.blocked_content, vec![Uuid::from_u128(1)]);
+        assert!((summary.genre_penalties["horror"] - 0.4).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_target_round_trips_through_storage_columns() {
+        for target in [
+            SuppressionTarget::Content(Uuid::new_v4()),
+            SuppressionTarget::Genre("Reality".to_string()),
+            SuppressionTarget::Person("Adam Sandler".to_string()),
+        ] {
+            assert_eq!(
+                SuppressionTarget::parse(target.kind(), &target.value()),
+                Some(target)
+            );
+        }
+        for source in [
+            FeedbackSource::NotInterested,
+            FeedbackSource::Dismissed,
+            FeedbackSource::Abandoned,
+            FeedbackSource::Disliked,
+        ] {
+            assert_eq!(FeedbackSource::parse(source.as_str()), Some(source));
+        }
+    }
+}
@@ -10,9 +10,11 @@ use ndarray::{Array1, ArrayView1};
 use std::collections::HashMap;
 use uuid::Uuid;
 
-const EMBEDDING_DIM: usize = 512;
+pub(crate) const EMBEDDING_DIM: usize = 512;
 const DECAY_RATE: f32 = 0.95;
 const MIN_WATCH_THRESHOLD: f32 = 0.3;
+/// Interactions after which a user leaves cold-start handling
+pub const MIN_PERSONALIZATION_INTERACTIONS: usize = 5;
 
 /// User profile containing preference vectors and personalization data
 #[derive(Debug, Clone)]
@@ -168,6 +170,12 @@ impl ProgressivePersonalization {
         }
     }
 
+    /// Whether the regular pipeline has enough signal to take over from
+    /// cold-start handling
+    pub fn ready_for_personalization(interaction_count: usize) -> bool {
+        interaction_count >= MIN_PERSONALIZATION_INTERACTIONS
+    }
+
     pub fn should_update_preference_vector(interaction_count: usize) -> bool {
         interaction_count % 5 == 0
     }
@@ -3,23 +3,32 @@
 //! Implements GenerateRecommendations algorithm from SPARC pseudocode.
 //! Combines collaborative, content-based, graph-based, and context-aware filtering.
 
-use crate::collaborative::CollaborativeFilteringEngine;
-use crate::diversity::ApplyDiversityFilter;
-use crate::graph::GraphRecommender;
+use crate::candidates::{retrieve_candidates, CandidateSources, RetrievalConfig};
+use crate::content_based::{prefetch_embeddings, ContentEmbeddingStore};
+use crate::context::ContextAwareFilter;
+use crate::diversity::{ApplyDiversityFilter, ContentAttributes, ContentCatalog, DiversityConfig};
+use crate::explanation::{
+    ExplainRecommendation, ExplanationConfig, ExplanationEvidence, ExplanationRepository,
+    ExplanationTemplates, WatchedAnchor,
+};
 use crate::lora::{compute_lora_score, UserLoRAAdapter};
 use crate::profile::UserProfile;
-use crate::types::{Recommendation, RecommendationContext, RecommendationType, ScoredContent};
+use crate::rerank::{RerankRecommendations, RerankUser};
+use crate::types::{Recommendation, RecommendationContext, ScoredContent};
 use anyhow::Result;
 use chrono::Utc;
-use sqlx::PgPool;
+use std::collections::HashMap;
+use tracing::warn;
 use uuid::Uuid;
 
-const COLLABORATIVE_WEIGHT: f32 = 0.35;
-const CONTENT_WEIGHT: f32 = 0.25;
-const GRAPH_WEIGHT: f32 = 0.30;
-const CONTEXT_WEIGHT: f32 = 0.10;
 const DIVERSITY_THRESHOLD: f32 = 0.3;
 const MAX_RECOMMENDATIONS: usize = 20;
+/// Maximum relative boost from the user's LoRA adapter
+pub(crate) const LORA_WEIGHT: f32 = 0.3;
+/// Diversified pool handed to re-ranking, as a multiple of the final size
+const RERANK_POOL_FACTOR: usize = 3;
+/// Maximum relative boost or penalty from the user's temporal patterns
+const TEMPORAL_WEIGHT: f32 = 0.2;
 
 /// Generate personalized recommendations
 ///
@@ -28,69 +37,90 @@ const MAX_RECOMMENDATIONS: usize = 20;
 /// Steps:
 /// 1. Generate candidate pool from multiple sources (parallel)
 /// 2. Merge and deduplicate candidates
-/// 3. Filter already watched content
+/// 3. Filter already watched content, apply negative feedback and favor
+///    what the user tends to watch at this time
 /// 4. Apply LoRA personalization
-/// 5. Apply diversity filter (MMR)
-/// 6. Generate explanations
+/// 5. Apply diversity filter (MMR by default, or the request's mode)
+/// 6. Multi-objective re-ranking under business constraints (when configured)
+/// 7. Generate structured explanations from attribution and the evidence
+///    the explanation store looks up, rendered in the request's locale
 pub struct GenerateRecommendations;
 
 impl GenerateRecommendations {
+    #[allow(clippy::too_many_arguments)]
     pub async fn execute(
         user_id: Uuid,
         profile: &UserProfile,
         context: Option<RecommendationContext>,
         lora_adapter: Option<&UserLoRAAdapter>,
         get_content_embedding: impl Fn(Uuid) -> Result<Vec<f32>>,
-        db_pool: Option<&PgPool>,
-        cf_engine: Option<&CollaborativeFilteringEngine>,
+        get_content_attributes: impl Fn(Uuid) -> Option<ContentAttributes>,
+        sources: CandidateSources<'_>,
+        retrieval_config: &RetrievalConfig,
+        catalog: Option<&dyn ContentCatalog>,
+        embeddings: Option<&dyn ContentEmbeddingStore>,
+        explanations: Option<&dyn ExplanationRepository>,
     ) -> Result<Vec<Recommendation>> {
-        // Step 1: Generate candidate pool from multiple sources (parallel)
-        // In a real implementation, these would be parallel async calls
-        let mut all_candidates = Vec::new();
+        let suppressions = sources.suppressions;
 
-        // Collaborative filtering candidates (using ALS-based CF engine)
-        let collaborative_candidates = if let Some(engine) = cf_engine {
-            Self::get_collaborative_candidates_real(user_id, engine, 100).await?
-        } else {
-            Self::get_collaborative_candidates(user_id, 100).await?
-        };
-        for mut candidate in collaborative_candidates {
-            candidate.score *= COLLABORATIVE_WEIGHT;
-            all_candidates.push(candidate);
-        }
-
-        // Content-based candidates (simulated)
-        let content_candidates = Self::get_content_based_candidates(profile, 100).await?;
-        for mut candidate in content_candidates {
-            candidate.score *= CONTENT_WEIGHT;
-            all_candidates.push(candidate);
-        }
-
-        // Graph-based candidates
-        let graph_candidates = Self::get_graph_based_candidates(user_id, db_pool, 100).await?;
-        for mut candidate in graph_candidates {
-            candidate.score *= GRAPH_WEIGHT;
-            all_candidates.push(candidate);
-        }
+        // Steps 1-2: Concurrent retrieval from all sources, merged and deduplicated.
+        // Failed or slow sources are dropped rather than failing the request.
+        let retrieval = retrieve_candidates(
+            user_id,
+            profile,
+            context.as_ref(),
+            sources,
+            retrieval_config,
+        )
+        .await;
+        let mut attribution = retrieval.attribution;
 
-        // Context-aware candidates (if context provided)
-        if let Some(ctx) = &context {
-            let context_candidates = Self::get_context_aware_candidates(profile, ctx, 50).await?;
-            for mut candidate in context_candidates {
-                candidate.score *= CONTEXT_WEIGHT;
-                all_candidates.push(candidate);
+        // Catalog attributes of the pool, looked up once; `get_content_attributes`
+        // covers titles the catalog does not know
+        let catalog_attributes = match catalog {
+            Some(catalog) => {
+                let ids: Vec<Uuid> = retrieval.candidates.iter().map(|c| c.content_id).collect();
+                catalog.content_attributes(&ids).await.unwrap_or_else(|e| {
+                    warn!("Failed to load content attributes: {}", e);
+                    HashMap::new()
+                })
             }
-        }
+            None => HashMap::new(),
+        };
+        let get_content_attributes = move |content_id: Uuid| {
+            catalog_attributes
+                .get(&content_id)
+                .cloned()
+                .or_else(|| get_content_attributes(content_id))
+        };
 
-        // Step 2: Merge and deduplicate candidates
-        let merged_candidates = Self::merge_candidates(all_candidates);
+        // Stored embeddings of the pool, likewise looked up once
+        let ids: Vec<Uuid> = retrieval.candidates.iter().map(|c| c.content_id).collect();
+        let stored_embeddings = prefetch_embeddings(embeddings, &ids).await;
+        let get_content_embedding = |content_id: Uuid| match stored_embeddings.get(&content_id) {
+            Some(embedding) => Ok(embedding.clone()),
+            None => get_content_embedding(content_id),
+        };
 
-        // Step 3: Filter already watched content
+        // Step 3: Filter already watched content, down-weight titles sharing
+        // a genre or person the user is not interested in, and favor genres
+        // and lengths the user picks at this time of day and week
         let watched_ids = Self::get_watched_content_ids(user_id).await?;
-        let mut filtered_candidates: Vec<ScoredContent> = merged_candidates
+        let mut filtered_candidates: Vec<ScoredContent> = retrieval
+            .candidates
             .into_iter()
             .filter(|c| !watched_ids.contains(&c.content_id))
             .collect();
+        if let Some(suppressions) = suppressions {
+            filtered_candidates = suppressions.apply(filtered_candidates, &get_content_attributes);
+        }
+        filtered_candidates = ContextAwareFilter::apply_temporal_patterns(
+            filtered_candidates,
+            &profile.temporal_patterns,
+            Utc::now(),
+            TEMPORAL_WEIGHT,
+            &get_content_attributes,
+        );
 
         // Step 4: Apply LoRA personalization
         if let Some(adapter) = lora_adapter {
@@ -98,123 +128,169 @@ impl GenerateRecommendations {
                 let content_embedding = get_content_embedding(candidate.content_id)?;
                 let lora_score =
                     compute_lora_score(adapter, &content_embedding, &profile.preference_vector)?;
-                candidate.score *= 1.0 + lora_score * 0.3;
+                candidate.score *= 1.0 + lora_score * LORA_WEIGHT;
             }
         }
 
-        // Step 5: Apply diversity filter (MMR - Maximal Marginal Relevance)
-        let diverse_results = ApplyDiversityFilter::execute(
+        // Step 5: Apply diversity filter (MMR - Maximal Marginal Relevance
+        // unless the request picks another mode)
+        let diversity = context
+            .as_ref()
+            .and_then(|c| c.diversity.clone())
+            .unwrap_or_else(|| DiversityConfig {
+                min_distance: DIVERSITY_THRESHOLD,
+                ..Default::default()
+            });
+        let rerank_user = context.as_ref().map(|c| RerankUser {
+            subscribed_platforms: c.subscribed_platforms.clone(),
+            max_maturity_rating: c.max_maturity_rating,
+        });
+        let rerank = context.as_ref().and_then(|c| {
+            c.rerank
+                .clone()
+                .or_else(|| c.max_maturity_rating.map(|_| Default::default()))
+        });
+        let pool_size = if rerank.is_some() {
+            MAX_RECOMMENDATIONS * RERANK_POOL_FACTOR
+        } else {
+            MAX_RECOMMENDATIONS
+        };
+        let diverse_results = ApplyDiversityFilter::apply(
             filtered_candidates,
-            DIVERSITY_THRESHOLD,
-            MAX_RECOMMENDATIONS,
+            pool_size,
+            &diversity,
             get_content_embedding,
+            &get_content_attributes,
         )?;
 
-        // Step 6: Generate explanations
+        // Step 6: Re-rank the diversified pool on the configured objective,
+        // enforcing platform/franchise caps and parental limits
+        let final_results = match rerank {
+            Some(config) => RerankRecommendations::execute(
+                diverse_results,
+                MAX_RECOMMENDATIONS,
+                &config,
+                &rerank_user.unwrap_or_default(),
+                Utc::now(),
+                &get_content_attributes,
+            ),
+            None => diverse_results,
+        };
+
+        // Step 7: Generate explanations from source attribution, catalog
+        // attributes, the profile and looked-up evidence
+        let locale = context
+            .as_ref()
+            .and_then(|c| c.locale.as_deref())
+            .unwrap_or("en");
+        let region = context.as_ref().and_then(|c| c.region.as_deref());
+        let templates = ExplanationTemplates::default();
+        let explanation_config = ExplanationConfig::default();
+        let now = Utc::now();
+        let (mut evidence, watched) = match explanations {
+            Some(store) => {
+                let ids: Vec<Uuid> = final_results.iter().map(|r| r.content_id).collect();
+                Self::load_evidence(
+                    store,
+                    embeddings,
+                    user_id,
+                    &ids,
+                    region,
+                    &explanation_config,
+                    &get_content_embedding,
+                )
+                .await
+            }
+            None => Default::default(),
+        };
         let mut recommendations = Vec::new();
-        for result in diverse_results {
-            let explanation = Self::generate_explanation(&result, profile);
+        for result in final_results {
+            let sources = attribution.remove(&result.content_id).unwrap_or_default();
+            let mut item_evidence = evidence.remove(&result.content_id).unwrap_or_default();
+            if !watched.is_empty() {
+                if let Ok(embedding) = get_content_embedding(result.content_id) {
+                    item_evidence.watched_anchor = WatchedAnchor::closest(&embedding, &watched);
+                }
+            }
+            let reasons = ExplainRecommendation::execute(
+                &result,
+                &sources,
+                get_content_attributes(result.content_id).as_ref(),
+                profile,
+                &item_evidence,
+                &explanation_config,
+                now,
+            );
+            let explanation = if reasons.is_empty() {
+                Self::generate_explanation(&result, profile)
+            } else {
+                templates.headline(&reasons, locale)
+            };
             recommendations.push(Recommendation {
                 content_id: result.content_id,
                 confidence_score: result.score,
                 recommendation_type: result.source,
+                sources,
                 based_on: result.based_on,
                 explanation,
-                generated_at: Utc::now(),
+                generated_at: now,
                 ttl_seconds: 3600,
                 experiment_variant: None, // Will be set by A/B testing layer
+                reasons,
             });
         }
 
         Ok(recommendations)
     }
 
-    async fn get_collaborative_candidates_real(
+    /// Evidence for the final items and the user's embedded watched titles;
+    /// lookups that fail only cost the reasons they would have supported
+    async fn load_evidence(
+        store: &dyn ExplanationRepository,
+        embeddings: Option<&dyn ContentEmbeddingStore>,
         user_id: Uuid,
-        cf_engine: &CollaborativeFilteringEngine,
-        limit: usize,
-    ) -> Result<Vec<ScoredContent>> {
-        let recommendations = cf_engine.recommend(user_id, limit).await?;
-
-        Ok(recommendations
-            .into_iter()
-            .map(|(content_id, score)| ScoredContent {
-                content_id,
-                score,
-                source: RecommendationType::Collaborative,
-                based_on: vec!["collaborative_filtering".to_string()],
-            })
-            .collect())
-    }
-
-    async fn get_collaborative_candidates(
-        _user_id: Uuid,
-        limit: usize,
-    ) -> Result<Vec<ScoredContent>> {
-        // Simulated collaborative filtering (fallback)
-        // In real implementation: query similar users and their preferences
-        Ok(Vec::new())
-    }
-
-    async fn get_content_based_candidates(
-        _profile: &UserProfile,
-        limit: usize,
-    ) -> Result<Vec<ScoredContent>> {
-        // Simulated content-based filtering
-        // In real implementation: find content similar to user's history
-        Ok(Vec::new())
-    }
-
-    async fn get_graph_based_candidates(
-        user_id: Uuid,
-        db_pool: Option<&PgPool>,
-        limit: usize,
-    ) -> Result<Vec<ScoredContent>> {
-        if let Some(pool) = db_pool {
-            let graph_recommender = GraphRecommender::new(pool.clone());
-            let recommendations = graph_recommender.recommend(user_id, limit).await?;
-
-            Ok(recommendations
-                .into_iter()
-                .map(|(content_id, score)| ScoredContent {
-                    content_id,
-                    score,
-                    source: RecommendationType::GraphBased,
-                    based_on: vec!["graph_similarity".to_string()],
-                })
-                .collect())
-        } else {
-            // Fallback if no DB pool provided
-            Ok(Vec::new())
-        }
-    }
-
-    async fn get_context_aware_candidates(
-        _profile: &UserProfile,
-        _context: &RecommendationContext,
-        limit: usize,
-    ) -> Result<Vec<ScoredContent>> {
-        // Simulated context-aware filtering
-        // In real implementation: filter by time, device, mood
-        Ok(Vec::new())
-    }
-
-    fn merge_candidates(candidates: Vec<ScoredContent>) -> Vec<ScoredContent> {
-        use std::collections::HashMap;
-
-        let mut merged: HashMap<Uuid, ScoredContent> = HashMap::new();
-
-        for candidate in candidates {
-            merged
-                .entry(candidate.content_id)
-                .and_modify(|existing| {
-                    existing.score += candidate.score;
-                    existing.based_on.extend(candidate.based_on.clone());
-                })
-                .or_insert(candidate);
-        }
+        content_ids: &[Uuid],
+        region: Option<&str>,
+        config: &ExplanationConfig,
+        get_content_embedding: impl Fn(Uuid) -> Result<Vec<f32>>,
+    ) -> (
+        HashMap<Uuid, ExplanationEvidence>,
+        Vec<(Uuid, String, Vec<f32>)>,
+    ) {
+        let evidence = store
+            .load_evidence(user_id, content_ids, region, config, Utc::now())
+            .await
+            .unwrap_or_else(|e| {
+                warn!("Failed to load explanation evidence: {}", e);
+                HashMap::new()
+            });
+        let watched = match store.watched_titles(user_id, config.anchor_history).await {
+            Ok(titles) => {
+                let titles: Vec<(Uuid, String)> = titles
+                    .into_iter()
+                    .filter(|(id, _)| !content_ids.contains(id))
+                    .collect();
+                let ids: Vec<Uuid> = titles.iter().map(|(id, _)| *id).collect();
+                let mut stored = prefetch_embeddings(embeddings, &ids).await;
+                titles
+                    .into_iter()
+                    .filter_map(|(id, title)| {
+                        stored
+                            .remove(&id)
+                            .map(Ok)
+                            .unwrap_or_else(|| get_content_embedding(id))
+                            .ok()
+                            .map(|embedding| (id, title, embedding))
+                    })
+                    .collect()
+            }
+            Err(e) => {
+                warn!("Failed to load watched titles: {}", e);
+                Vec::new()
+            }
+        };
 
-        merged.into_values().collect()
+        (evidence, watched)
     }
 
     async fn get_watched_content_ids(_user_id: Uuid) -> Result<Vec<Uuid>> {
@@ -235,38 +311,48 @@ impl GenerateRecommendations {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::profile::UserProfile;
+    use crate::types::RecommendationType;
 
     #[tokio::test]
-    async fn test_merge_candidates() {
-        let content_id = Uuid::new_v4();
-        let candidates = vec![
-            ScoredContent {
-                content_id,
-                score: 0.5,
-                source: RecommendationType::Collaborative,
-                based_on: vec!["user1".to_string()],
-            },
-            ScoredContent {
-                content_id,
-                score: 0.3,
-                source: RecommendationType::ContentBased,
-                based_on: vec!["genre_match".to_string()],
-            },
-        ];
+    async fn test_execute_without_sources_returns_empty() {
+        let user_id = Uuid::new_v4();
+        let profile = UserProfile::new(user_id);
+
+        let recommendations = GenerateRecommendations::execute(
+            user_id,
+            &profile,
+            None,
+            None,
+            |_| Ok(vec![0.0; 4]),
+            |_| None,
+            CandidateSources::default(),
+            &RetrievalConfig::default(),
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
 
-        let merged = GenerateRecommendations::merge_candidates(candidates);
-        assert_eq!(merged.len(), 1);
-        assert_eq!(merged[0].score, 0.8);
-        assert_eq!(merged[0].based_on.len(), 2);
+        assert!(recommendations.is_empty());
     }
 
-    #[tokio::test]
-    async fn test_get_collaborative_candidates_fallback() {
-        let user_id = Uuid::new_v4();
-        let candidates = GenerateRecommendations::get_collaborative_candidates(user_id, 10)
-            .await
-            .unwrap();
-        assert_eq!(candidates.len(), 0); // Fallback returns empty
+    #[test]
+    fn test_explanation_lists_reasons() {
+        let profile = UserProfile::new(Uuid::new_v4());
+        let result = ScoredContent {
+            content_id: Uuid::new_v4(),
+            score: 0.5,
+            source: RecommendationType::Hybrid,
+            based_on: vec![
+                "graph_similarity".to_string(),
+                "content_similarity".to_string(),
+            ],
+        };
+
+        assert_eq!(
+            GenerateRecommendations::generate_explanation(&result, &profile),
+            "Based on: graph_similarity, content_similarity"
+        );
     }
 }
@@ -0,0 +1,714 @@
+//! Multi-objective Re-ranking
+//!
+//! Final ranking stage after diversification. Each pick greedily maximizes a
+//! weighted objective over normalized relevance, predicted completion,
+//! freshness, urgency of content leaving its platform, platform novelty with
+//! respect to the items already picked, and fit with the user's
+//! subscriptions. Hard constraints (per-platform and per-franchise caps,
+//! parental rating limit, availability window) are never traded off against
+//! the objective.
+//!
+//! Weights and caps are configured per tenant in [`RerankPolicies`], and an
+//! experiment variant can override any of them through a `"rerank"` object
+//! in its config.
+
+use crate::diversity::ContentAttributes;
+use crate::types::ScoredContent;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use media_gateway_core::types::MaturityRating;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Key in an experiment variant's config holding rerank overrides
+pub const VARIANT_CONFIG_KEY: &str = "rerank";
+
+/// Objective weights; a weight of 0.0 disables the term
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ObjectiveWeights {
+    /// Upstream score, min-max normalized over the pool (default: 1.0)
+    pub relevance: f32,
+    /// Predicted completion probability (default: 0.3)
+    pub completion: f32,
+    /// Recency of the content's arrival on a platform (default: 0.1)
+    pub freshness: f32,
+    /// Content whose availability window closes soon (default: 0.15)
+    pub expiring: f32,
+    /// Content on platforms not yet represented in the list (default: 0.1)
+    pub platform_diversity: f32,
+    /// Content available on a platform the user subscribes to (default: 0.2)
+    pub subscription_fit: f32,
+}
+
+impl Default for ObjectiveWeights {
+    fn default() -> Self {
+        Self {
+            relevance: 1.0,
+            completion: 0.3,
+            freshness: 0.1,
+            expiring: 0.15,
+            platform_diversity: 0.1,
+            subscription_fit: 0.2,
+        }
+    }
+}
+
+/// Hard constraints on the final list
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RerankConstraints {
+    /// Items sharing any one platform (default: unlimited)
+    pub max_per_platform: Option<usize>,
+    /// Items from the same franchise (default: 2)
+    pub max_per_franchise: Option<usize>,
+}
+
+impl Default for RerankConstraints {
+    fn default() -> Self {
+        Self {
+            max_per_platform: None,
+            max_per_franchise: Some(2),
+        }
+    }
+}
+
+/// Re-ranking configuration for one tenant or experiment variant
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RerankConfig {
+    pub weights: ObjectiveWeights,
+    pub constraints: RerankConstraints,
+    /// Age at which freshness halves (default: 30 days)
+    pub freshness_half_life_days: f32,
+    /// Content leaving within this many days counts as expiring (default: 14)
+    pub expiring_window_days: f32,
+}
+
+impl Default for RerankConfig {
+    fn default() -> Self {
+        Self {
+            weights: ObjectiveWeights::default(),
+            constraints: RerankConstraints::default(),
+            freshness_half_life_days: 30.0,
+            expiring_window_days: 14.0,
+        }
+    }
+}
+
+impl RerankConfig {
+    pub fn validate(&self) -> Result<()> {
+        let w = &self.weights;
+        for (name, weight) in [
+            ("relevance", w.relevance),
+            ("completion", w.completion),
+            ("freshness", w.freshness),
+            ("expiring", w.expiring),
+            ("platform_diversity", w.platform_diversity),
+            ("subscription_fit", w.subscription_fit),
+        ] {
+            anyhow::ensure!(
+                weight.is_finite() && weight >= 0.0,
+                "{} weight must be a non-negative number, got {}",
+                name,
+                weight
+            );
+        }
+        anyhow::ensure!(
+            self.constraints.max_per_platform != Some(0)
+                && self.constraints.max_per_franchise != Some(0),
+            "Constraint limits must be positive"
+        );
+        anyhow::ensure!(
+            self.freshness_half_life_days > 0.0 && self.expiring_window_days > 0.0,
+            "freshness_half_life_days and expiring_window_days must be positive"
+        );
+        Ok(())
+    }
+}
+
+/// Per-tenant re-ranking configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RerankPolicies {
+    #[serde(default)]
+    pub default: RerankConfig,
+    #[serde(default)]
+    pub tenants: HashMap<String, RerankConfig>,
+}
+
+impl RerankPolicies {
+    /// Load policies from the JSON file named by SONA_RERANK_POLICIES;
+    /// returns None when it is unset
+    pub fn from_env() -> Result<Option<Self>> {
+        let Ok(path) = std::env::var("SONA_RERANK_POLICIES") else {
+            return Ok(None);
+        };
+        let json = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read rerank policies from {}", path))?;
+        let policies: Self = serde_json::from_str(&json)
+            .with_context(|| format!("Invalid rerank policies in {}", path))?;
+        policies.validate()?;
+        Ok(Some(policies))
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        self.default
+            .validate()
+            .context("Invalid default rerank policy")?;
+        for (tenant, config) in &self.tenants {
+            config
+                .validate()
+                .with_context(|| format!("Invalid rerank policy for tenant {}", tenant))?;
+        }
+        Ok(())
+    }
+
+    /// Configuration for a tenant, with an experiment variant's `"rerank"`
+    /// overrides applied on top
+    ///
+    /// Overrides are merged field by field, so a variant only needs to list
+    /// the weights or caps it changes.
+    pub fn resolve(
+        &self,
+        tenant: Option<&str>,
+        variant_config: Option<&serde_json::Value>,
+    ) -> Result<RerankConfig> {
+        let base = tenant
+            .and_then(|t| self.tenants.get(t))
+            .unwrap_or(&self.default);
+
+        let Some(overrides) = variant_config.and_then(|c| c.get(VARIANT_CONFIG_KEY)) else {
+            return Ok(base.clone());
+        };
+
+        let mut merged = serde_json::to_value(base)?;
+        merge_json(&mut merged, overrides);
+        let config: RerankConfig =
+            serde_json::from_value(merged).context("Invalid rerank overrides in variant config")?;
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+fn merge_json(base: &mut serde_json::Value, overrides: &serde_json::Value) {
+    match (base, overrides) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(overrides)) => {
+            for (key, value) in overrides {
+                match base.get_mut(key) {
+                    Some(existing) => merge_json(existing, value),
+                    None => {
+                        base.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (base, overrides) => *base = overrides.clone(),
+    }
+}
+
+/// User-level inputs to re-ranking
+#[derive(Debug, Clone, Default)]
+pub struct RerankUser {
+    /// Platforms the user subscribes to
+    pub subscribed_platforms: Vec<String>,
+    /// Parental limit; unrated content is excluded while a limit applies
+    pub max_maturity_rating: Option<MaturityRating>,
+}
+
+/// Position of a rating on a common MPAA-aligned scale
+fn maturity_level(rating: MaturityRating) -> Option<u8> {
+    match rating {
+        MaturityRating::G | MaturityRating::TVY | MaturityRating::TVY7 | MaturityRating::TVG => {
+            Some(0)
+        }
+        MaturityRating::PG | MaturityRating::TVPG => Some(1),
+        MaturityRating::PG13 | MaturityRating::TV14 => Some(2),
+        MaturityRating::R | MaturityRating::TVMA => Some(3),
+        MaturityRating::NC17 => Some(4),
+        MaturityRating::NR => None,
+    }
+}
+
+/// Parse a catalog or parental-controls rating such as "PG-13" or "TV-MA"
+pub fn parse_maturity_rating(rating: &str) -> Option<MaturityRating> {
+    let normalized: String = rating
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_uppercase();
+    match normalized.as_str() {
+        "G" => Some(MaturityRating::G),
+        "PG" => Some(MaturityRating::PG),
+        "PG13" => Some(MaturityRating::PG13),
+        "R" => Some(MaturityRating::R),
+        "NC17" => Some(MaturityRating::NC17),
+        "NR" | "UNRATED" | "NOTRATED" => Some(MaturityRating::NR),
+        "TVY" => Some(MaturityRating::TVY),
+        "TVY7" => Some(MaturityRating::TVY7),
+        "TVG" => Some(MaturityRating::TVG),
+        "TVPG" => Some(MaturityRating::TVPG),
+        "TV14" => Some(MaturityRating::TV14),
+        "TVMA" => Some(MaturityRating::TVMA),
+        _ => None,
+    }
+}
+
+/// Whether content with `rating` may be shown under `limit`
+pub fn rating_allowed(rating: Option<MaturityRating>, limit: Option<MaturityRating>) -> bool {
+    let Some(limit) = limit else {
+        return true;
+    };
+    let Some(limit_level) = maturity_level(limit) else {
+        // "Not rated" as a limit only admits unrated content
+        return rating == Some(MaturityRating::NR);
+    };
+    rating
+        .and_then(maturity_level)
+        .is_some_and(|level| level <= limit_level)
+}
+
+/// Most restrictive of several parental limits, e.g. across co-viewers
+pub fn strictest_rating(
+    limits: impl IntoIterator<Item = Option<MaturityRating>>,
+) -> Option<MaturityRating> {
+    limits
+        .into_iter()
+        .flatten()
+        // A "not rated" limit admits the least content
+        .min_by_key(|r| maturity_level(*r).map_or(-1, i16::from))
+}
+
+/// Apply the multi-objective re-ranking
+pub struct RerankRecommendations;
+
+impl RerankRecommendations {
+    /// Select up to `limit` candidates in objective order
+    ///
+    /// Candidates failing a hard constraint are dropped. Each returned item's
+    /// score is its objective value at the time it was picked.
+    pub fn execute(
+        candidates: Vec<ScoredContent>,
+        limit: usize,
+        config: &RerankConfig,
+        user: &RerankUser,
+        now: DateTime<Utc>,
+        get_content_attributes: impl Fn(Uuid) -> Option<ContentAttributes>,
+    ) -> Vec<ScoredContent> {
+        let pool: Vec<(ScoredContent, ContentAttributes)> = candidates
+            .into_iter()
+            .map(|c| {
+                let attributes = get_content_attributes(c.content_id).unwrap_or_default();
+                (c, attributes)
+            })
+            .filter(|(_, a)| Self::is_available(a, user, now))
+            .collect();
+        if pool.is_empty() || limit == 0 {
+            return Vec::new();
+        }
+
+        let (min_score, max_score) = pool
+            .iter()
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), (c, _)| {
+                (lo.min(c.score), hi.max(c.score))
+            });
+        let range = max_score - min_score;
+
+        // Terms that do not depend on the selected set
+        let static_scores: Vec<f32> = pool
+            .iter()
+            .map(|(candidate, attributes)| {
+                let relevance = if range > f32::EPSILON {
+                    (candidate.score - min_score) / range
+                } else {
+                    1.0
+                };
+                Self::static_objective(relevance, attributes, config, user, now)
+            })
+            .collect();
+
+        let mut platform_counts: HashMap<&str, usize> = HashMap::new();
+        let mut franchise_counts: HashMap<&str, usize> = HashMap::new();
+        let mut remaining: Vec<usize> = (0..pool.len()).collect();
+        let mut picks: Vec<(usize, f32)> = Vec::with_capacity(limit.min(pool.len()));
+
+        while picks.len() < limit {
+            let mut best: Option<(usize, f32)> = None;
+            remaining.retain(|&index| {
+                let attributes = &pool[index].1;
+                if !Self::within_caps(
+                    attributes,
+                    &config.constraints,
+                    &platform_counts,
+                    &franchise_counts,
+                ) {
+                    // Counts only grow, so an item over a cap stays over it
+                    return false;
+                }
+                let novelty = Self::platform_novelty(attributes, &platform_counts);
+                let score = static_scores[index] + config.weights.platform_diversity * novelty;
+                if best.map_or(true, |(_, s)| score > s) {
+                    best = Some((index, score));
+                }
+                true
+            });
+
+            let Some((index, score)) = best else {
+                break;
+            };
+            remaining.retain(|&i| i != index);
+
+            let attributes = &pool[index].1;
+            for platform in &attributes.platforms {
+                *platform_counts.entry(platform.as_str()).or_insert(0) += 1;
+            }
+            if let Some(franchise) = &attributes.franchise {
+                *franchise_counts.entry(franchise.as_str()).or_insert(0) += 1;
+            }
+            picks.push((index, score));
+        }
+
+        let scores: HashMap<usize, f32> = picks.iter().copied().collect();
+        let mut pool: Vec<Option<ScoredContent>> = pool.into_iter().map(|(c, _)| Some(c)).collect();
+        picks
+            .into_iter()
+            .filter_map(|(index, _)| {
+                pool[index].take().map(|mut c| {
+                    c.score = scores[&index];
+                    c
+                })
+            })
+            .collect()
+    }
+
+    fn is_available(attributes: &ContentAttributes, user: &RerankUser, now: DateTime<Utc>) -> bool {
+        rating_allowed(attributes.maturity_rating, user.max_maturity_rating)
+            && attributes.available_from.map_or(true, |from| from <= now)
+            && attributes.available_until.map_or(true, |until| until > now)
+    }
+
+    fn static_objective(
+        relevance: f32,
+        attributes: &ContentAttributes,
+        config: &RerankConfig,
+        user: &RerankUser,
+        now: DateTime<Utc>,
+    ) -> f32 {
+        let weights = &config.weights;
+        let days = |seconds: i64| seconds as f32 / 86_400.0;
+
+        let completion = attributes
+            .predicted_completion
+            .unwrap_or(0.0)
+            .clamp(0.0, 1.0);
+        let freshness = attributes.available_from.map_or(0.0, |from| {
+            let age = days((now - from).num_seconds()).max(0.0);
+            0.5f32.powf(age / config.freshness_half_life_days)
+        });
+        let expiring = attributes.available_until.map_or(0.0, |until| {
+            let left = days((until - now).num_seconds());
+            (1.0 - left / config.expiring_window_days).clamp(0.0, 1.0)
+        });
+        let subscription_fit = if attributes
+            .platforms
+            .iter()
+            .any(|p| user.subscribed_platforms.contains(p))
+        {
+            1.0
+        } else {
+            0.0
+        };
+
+        weights.relevance * relevance
+            + weights.completion * completion
+            + weights.freshness * freshness
+            + weights.expiring * expiring
+            + weights.subscription_fit * subscription_fit
+    }
+
+    /// 1.0 for content on a platform not yet picked, decaying with repeats
+    fn platform_novelty(attributes: &ContentAttributes, counts: &HashMap<&str, usize>) -> f32 {
+        attributes
+            .platforms
+            .iter()
+            .map(|p| 1.0 / (1 + counts.get(p.as_str()).copied().unwrap_or(0)) as f32)
+            .fold(0.0, f32::max)
+    }
+
+    fn within_caps(
+        attributes: &ContentAttributes,
+        constraints: &RerankConstraints,
+        platform_counts: &HashMap<&str, usize>,
+        franchise_counts: &HashMap<&str, usize>,
+    ) -> bool {
+        let platform_ok = constraints.max_per_platform.map_or(true, |cap| {
+            attributes
+                .platforms
+                .iter()
+                .all(|p| platform_counts.get(p.as_str()).copied().unwrap_or(0) < cap)
+        });
+        let franchise_ok = match (constraints.max_per_franchise, &attributes.franchise) {
+            (Some(cap), Some(franchise)) => {
+                franchise_counts
+                    .get(franchise.as_str())
+                    .copied()
+                    .unwrap_or(0)
+                    < cap
+            }
+            _ => true,
+        };
+        platform_ok && franchise_ok
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::RecommendationType;
+    use chrono::Duration;
+
+    fn candidate(id: u128, score: f32) -> ScoredContent {
+        ScoredContent {
+            content_id: Uuid::from_u128(id),
+            score,
+            source: RecommendationType::Hybrid,
+            based_on: vec![],
+        }
+    }
+
+    fn ids(results: &[ScoredContent]) -> Vec<u128> {
+        results.iter().map(|c| c.content_id.as_u128()).collect()
+    }
+
+    fn on(platform: &str) -> ContentAttributes {
+        ContentAttributes {
+            platforms: vec![platform.to_string()],
+            ..Default::default()
+        }
+    }
+
+    fn relevance_only() -> RerankConfig {
+        RerankConfig {
+            weights: ObjectiveWeights {
+                relevance: 1.0,
+                completion: 0.0,
+                freshness: 0.0,
+                expiring: 0.0,
+                platform_diversity: 0.0,
+                subscription_fit: 0.0,
+            },
+            constraints: RerankConstraints {
+                max_per_platform: None,
+                max_per_franchise: None,
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_relevance_only_keeps_score_order() {
+        let candidates = vec![candidate(1, 0.2), candidate(2, 0.9), candidate(3, 0.5)];
+        let results = RerankRecommendations::execute(
+            candidates,
+            10,
+            &relevance_only(),
+            &RerankUser::default(),
+            Utc::now(),
+            |_| None,
+        );
+        assert_eq!(ids(&results), vec![2, 3, 1]);
+        assert!((results[0].score - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_hard_constraints() {
+        let now = Utc::now();
+        let attributes = move |id: Uuid| {
+            let mut a = on(if id.as_u128() <= 3 { "netflix" } else { "hulu" });
+            match id.as_u128() {
+                4 => a.maturity_rating = Some(MaturityRating::R),
+                5 => a.available_until = Some(now - Duration::days(1)),
+                6 => a.available_from = Some(now + Duration::days(1)),
+                _ => a.maturity_rating = Some(MaturityRating::TVPG),
+            }
+            Some(a)
+        };
+        let config = RerankConfig {
+            constraints: RerankConstraints {
+                max_per_platform: Some(2),
+                max_per_franchise: None,
+            },
+            ..relevance_only()
+        };
+        let user = RerankUser {
+            max_maturity_rating: Some(MaturityRating::PG13),
+            ..Default::default()
+        };
+
+        let candidates = (1..=7)
+            .map(|i| candidate(i, 1.0 - i as f32 * 0.1))
+            .collect();
+        let results =
+            RerankRecommendations::execute(candidates, 10, &config, &user, now, attributes);
+        assert_eq!(ids(&results), vec![1, 2, 7]);
+    }
+
+    #[test]
+    fn test_franchise_cap() {
+        let attributes = |id: Uuid| {
+            Some(ContentAttributes {
+                franchise: (id.as_u128() <= 3).then(|| "marvel".to_string()),
+                ..Default::default()
+            })
+        };
+        let candidates = (1..=5)
+            .map(|i| candidate(i, 1.0 - i as f32 * 0.1))
+            .collect();
+        let config = RerankConfig {
+            constraints: RerankConstraints::default(),
+            ..relevance_only()
+        };
+        let results = RerankRecommendations::execute(
+            candidates,
+            10,
+            &config,
+            &RerankUser::default(),
+            Utc::now(),
+            attributes,
+        );
+        assert_eq!(ids(&results), vec![1, 2, 4, 5]);
+    }
+
+    #[test]
+    fn test_objectives_lift_expiring_subscribed_and_diverse_content() {
+        let now = Utc::now();
+        let candidates = vec![candidate(1, 1.0), candidate(2, 0.9), candidate(3, 0.8)];
+        let base = relevance_only();
+
+        // Leaving tomorrow outranks a slightly more relevant item
+        let expiring = RerankConfig {
+            weights: ObjectiveWeights {
+                expiring: 1.0,
+                ..base.weights.clone()
+            },
+            ..base.clone()
+        };
+        let results = RerankRecommendations::execute(
+            candidates.clone(),
+            3,
+            &expiring,
+            &RerankUser::default(),
+            now,
+            |id| {
+                Some(ContentAttributes {
+                    available_until: (id.as_u128() == 2).then(|| now + Duration::days(1)),
+                    ..Default::default()
+                })
+            },
+        );
+        assert_eq!(ids(&results)[0], 2);
+
+        // Subscription fit
+        let subscribed = RerankConfig {
+            weights: ObjectiveWeights {
+                subscription_fit: 1.5,
+                ..base.weights.clone()
+            },
+            ..base.clone()
+        };
+        let user = RerankUser {
+            subscribed_platforms: vec!["hulu".to_string()],
+            ..Default::default()
+        };
+        let results =
+            RerankRecommendations::execute(candidates.clone(), 3, &subscribed, &user, now, |id| {
+                Some(on(if id.as_u128() == 3 { "hulu" } else { "netflix" }))
+            });
+        assert_eq!(ids(&results)[0], 3);
+
+        // Platform diversity interleaves platforms after the first pick
+        let candidates = vec![candidate(1, 1.0), candidate(2, 0.9), candidate(3, 0.85)];
+        let diverse = RerankConfig {
+            weights: ObjectiveWeights {
+                platform_diversity: 1.0,
+                ..base.weights.clone()
+            },
+            ..base
+        };
+        let results = RerankRecommendations::execute(
+            candidates,
+            3,
+            &diverse,
+            &RerankUser::default(),
+            now,
+            |id| Some(on(if id.as_u128() == 3 { "hulu" } else { "netflix" })),
+        );
+        assert_eq!(ids(&results), vec![1, 3, 2]);
+    }
+
+    #[test]
+    fn test_rating_allowed() {
+        assert!(rating_allowed(None, None));
+        assert!(rating_allowed(
+            Some(MaturityRating::TV14),
+            Some(MaturityRating::PG13)
+        ));
+        assert!(!rating_allowed(
+            Some(MaturityRating::TVMA),
+            Some(MaturityRating::PG13)
+        ));
+        assert!(!rating_allowed(None, Some(MaturityRating::NC17)));
+        assert!(!rating_allowed(
+            Some(MaturityRating::NR),
+            Some(MaturityRating::R)
+        ));
+    }
+
+    #[test]
+    fn test_parse_maturity_rating() {
+        assert_eq!(parse_maturity_rating("PG-13"), Some(MaturityRating::PG13));
+        assert_eq!(parse_maturity_rating("tv-ma"), Some(MaturityRating::TVMA));
+        assert_eq!(parse_maturity_rating("NC-17"), Some(MaturityRating::NC17));
+        assert_eq!(parse_maturity_rating("Unrated"), Some(MaturityRating::NR));
+        assert_eq!(parse_maturity_rating("FSK 16"), None);
+    }
+
+    #[test]
+    fn test_policies_resolve_tenant_and_variant_overrides() {
+        let mut policies = RerankPolicies::default();
+        let mut tenant = RerankConfig::default();
+        tenant.weights.expiring = 0.4;
+        policies.tenants.insert("acme".to_string(), tenant);
+
+        let config = policies.resolve(Some("acme"), None).unwrap();
+        assert_eq!(config.weights.expiring, 0.4);
+        assert_eq!(
+            policies.resolve(Some("other"), None).unwrap(),
+            RerankConfig::default()
+        );
+
+        let variant = serde_json::json!({
+            "rerank": {
+                "weights": { "freshness": 0.5 },
+                "constraints": { "max_per_platform": 3 }
+            }
+        });
+        let config = policies.resolve(Some("acme"), Some(&variant)).unwrap();
+        assert_eq!(config.weights.expiring, 0.4);
+        assert_eq!(config.weights.freshness, 0.5);
+        assert_eq!(config.constraints.max_per_platform, Some(3));
+        assert_eq!(config.constraints.max_per_franchise, Some(2));
+
+        let invalid = serde_json::json!({ "rerank": { "weights": { "relevance": -1.0 } } });
+        assert!(policies.resolve(None, Some(&invalid)).is_err());
+
+        assert!(policies.validate().is_ok());
+        policies
+            .tenants
+            .get_mut("acme")
+            .unwrap()
+            .constraints
+            .max_per_platform = Some(0);
+        assert!(policies.validate().is_err());
+    }
+}
@@ -5,26 +5,83 @@
 
 use actix_web::{web, App, HttpResponse, HttpServer, Responder};
 use serde::{Deserialize, Serialize};
-use uuid::Uuid;
+use std::collections::HashMap;
 use std::sync::Arc;
+use uuid::Uuid;
 
+use chrono::Timelike;
+use media_gateway_core::events::UserActivityEvent;
+use media_gateway_core::types::MaturityRating;
+use media_gateway_core::{TrendingConfig, TrendingService};
+use media_gateway_sona::rerank::{
+    parse_maturity_rating, strictest_rating, VARIANT_CONFIG_KEY as RERANK_VARIANT_KEY,
+};
 use media_gateway_sona::{
-    SonaEngine, SonaConfig, GenerateRecommendations, BuildUserPreferenceVector,
-    UpdateUserLoRA, UserProfile, UserLoRAAdapter, ViewingEvent,
+    canonical_mood, current_explicit_pick, current_mood_label, is_valid_utc_offset, nearest_mood,
+    viewing_activity_event, ABTestingService, ALSConfig, AssignmentContext, AssignmentDecision,
+    BuildUserPreferenceVector, CandidateSources, CollaborativeFilteringEngine, ContentBasedEngine,
+    ContentCatalog, ContentEmbeddingStore, ContextAwareFilter, ElicitationCatalog,
+    ElicitationConfig, ElicitationRepository, ElicitationResponse, ElicitationSession, Experiment,
+    ExplanationRepository, ExplanationTemplates, FeedbackSource, GenerateGroupRecommendations,
+    GenerateRecommendations, GraphRecommender, GroupConfig, GroupMember, HandleColdStartUser,
+    LoRATrainingConfig, LoRATrainingWorker, MatrixFactorization, ModelServer, MoodConfig,
+    MoodRepository, MoodService, MoodTaggingConfig, NegativeFeedback, NegativeFeedbackConfig,
+    NegativeFeedbackRepository, PostgresContentCatalog, PostgresElicitationRepository,
+    PostgresExplanationRepository, PostgresMoodRepository, PostgresNegativeFeedbackRepository,
+    PostgresTemporalPatternRepository, PostgresTrainingDataSource, ProgressivePersonalization,
+    Recommendation, RecommendationContext, RerankConfig, RerankPolicies, RetrievalConfig,
+    SessionConfig, SessionRecommender, SignupContext, SonaConfig, SonaEngine, StoredExplanation,
+    SuppressionSet, SuppressionTarget, TemporalPatternConfig, TemporalPatternJob,
+    TemporalPatternRepository, TransitionGraph, UpdateUserLoRA, UserActivity, UserLoRAAdapter,
+    UserProfile, Variant, ViewingEvent, MOOD_DIMENSIONS,
 };
 
+/// Viewing events queued for the LoRA training worker before new ones are
+/// dropped
+const LORA_ACTIVITY_BUFFER: usize = 1024;
+
+/// Viewing events queued for the collaborative filtering stream before new
+/// ones are dropped
+const COLLABORATIVE_ACTIVITY_BUFFER: usize = 4096;
+
+/// How often the collaborative model is checked for a due retrain
+const COLLABORATIVE_RETRAIN_CHECK_SECS: u64 = 300;
+
 /// Application state
 struct AppState {
     engine: Arc<SonaEngine>,
     lora_storage: Arc<media_gateway_sona::LoRAStorage>,
-    experiment_repo: Arc<media_gateway_sona::ExperimentRepository>,
+    ab_testing: Arc<ABTestingService>,
+    negative_feedback: Arc<PostgresNegativeFeedbackRepository>,
+    explanations: Arc<PostgresExplanationRepository>,
+    temporal_patterns: Arc<PostgresTemporalPatternRepository>,
+    mood: Arc<MoodService>,
+    catalog: Arc<PostgresContentCatalog>,
+    /// Candidate engines; collaborative filtering and content similarity
+    /// need Qdrant and are absent without it
+    collaborative: Option<Arc<tokio::sync::RwLock<CollaborativeFilteringEngine>>>,
+    content_based: Option<Arc<ContentBasedEngine>>,
+    graph: Arc<GraphRecommender>,
+    context_filter: Arc<ContextAwareFilter>,
+    elicitation: Arc<PostgresElicitationRepository>,
+    /// Titles to ask new users about; onboarding is unavailable without it
+    elicitation_catalog: Option<Arc<ElicitationCatalog>>,
+    trending: Option<Arc<TrendingService>>,
+    rerank_policies: Option<Arc<RerankPolicies>>,
+    /// Viewing activity for the background LoRA training worker; absent
+    /// without a vector store to train on
+    lora_activity: Option<tokio::sync::mpsc::Sender<UserActivity>>,
+    /// Viewing activity streamed into the collaborative model; absent
+    /// without the collaborative engine
+    collaborative_activity: Option<tokio::sync::mpsc::Sender<UserActivityEvent>>,
+    /// Session recommender; its transition graph is rebuilt in the background
+    session: Arc<tokio::sync::RwLock<SessionRecommender>>,
     db_pool: sqlx::PgPool,
 }
 
 impl AppState {
-    /// Load user profile from database
-    async fn load_user_profile(&self, user_id: Uuid) -> anyhow::Result<UserProfile> {
-        // Fetch viewing history from database
+    /// Recent viewing events, newest first
+    async fn load_viewing_events(&self, user_id: Uuid) -> anyhow::Result<Vec<ViewingEvent>> {
         let viewing_history = sqlx::query_as::<_, ViewingEventRow>(
             r#"
             SELECT content_id, timestamp, completion_rate, rating, is_rewatch, dismissed
@@ -32,36 +89,119 @@ impl AppState {
             WHERE user_id = $1
             ORDER BY timestamp DESC
             LIMIT 100
-            "#
+            "#,
         )
         .bind(user_id)
         .fetch_all(&self.db_pool)
         .await?;
 
-        let events: Vec<ViewingEvent> = viewing_history.into_iter().map(|row| row.into()).collect();
+        Ok(viewing_history.into_iter().map(|row| row.into()).collect())
+    }
 
-        // Get content embedding function
-        let get_embedding = |content_id: Uuid| -> anyhow::Result<Vec<f32>> {
-            // In production, query embedding service
-            Ok(vec![0.0; 512])
+    /// The user's active negative feedback, decayed to suppressions
+    async fn load_suppressions(&self, user_id: Uuid) -> anyhow::Result<SuppressionSet> {
+        let config = NegativeFeedbackConfig::default();
+        let feedback = self
+            .negative_feedback
+            .list(user_id, false, config.max_signals)
+            .await?;
+        Ok(SuppressionSet::from_feedback(
+            &feedback,
+            &config,
+            chrono::Utc::now(),
+        ))
+    }
+
+    /// The user's parental rating limit, when parental controls are enabled
+    async fn load_parental_limit(&self, user_id: Uuid) -> anyhow::Result<Option<MaturityRating>> {
+        let controls: Option<serde_json::Value> =
+            sqlx::query_scalar("SELECT parental_controls FROM users WHERE id = $1")
+                .bind(user_id)
+                .fetch_optional(&self.db_pool)
+                .await?
+                .flatten();
+        Ok(controls
+            .filter(|c| c["enabled"].as_bool() == Some(true))
+            .and_then(|c| {
+                c["content_rating_limit"]
+                    .as_str()
+                    .and_then(parse_maturity_rating)
+            }))
+    }
+
+    /// The user's local hour, from the offset their temporal patterns use
+    async fn local_hour(&self, user_id: Uuid) -> anyhow::Result<u32> {
+        let patterns = self
+            .temporal_patterns
+            .load(user_id)
+            .await?
+            .unwrap_or_default();
+        Ok(patterns.local_time(chrono::Utc::now()).hour())
+    }
+
+    /// Stored embeddings of the given titles; empty without a vector store
+    /// or when the lookup fails
+    async fn content_embeddings(&self, content_ids: &[Uuid]) -> HashMap<Uuid, Vec<f32>> {
+        let Some(store) = self.content_based.as_deref() else {
+            return HashMap::new();
         };
+        store
+            .content_embeddings(content_ids)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!("Failed to load content embeddings: {}", e);
+                HashMap::new()
+            })
+    }
 
-        // Build preference vector
-        let preference_vector = BuildUserPreferenceVector::execute(
-            user_id,
-            &events,
-            get_embedding,
-        ).await?;
+    /// Load user profile from database
+    async fn load_user_profile(&self, user_id: Uuid) -> anyhow::Result<UserProfile> {
+        // Fetch viewing history from database
+        let events = self.load_viewing_events(user_id).await?;
+
+        // Stored embeddings of the watched titles
+        let content_ids: Vec<Uuid> = events.iter().map(|e| e.content_id).collect();
+        let get_embedding = embedding_lookup(self.content_embeddings(&content_ids).await);
 
-        Ok(UserProfile {
+        // Build preference vector
+        let preference_vector =
+            BuildUserPreferenceVector::execute(user_id, &events, get_embedding).await?;
+
+        // Temporal patterns learned from playback by the background job
+        let temporal_patterns = self
+            .temporal_patterns
+            .load(user_id)
+            .await?
+            .unwrap_or_default();
+
+        // Explicit mood picks, then the mood inferred from the current session
+        let now = chrono::Utc::now();
+        let local_hour = temporal_patterns.local_time(now).hour();
+        let mood_history = self
+            .mood
+            .mood_history(user_id, &events, local_hour, now)
+            .await?;
+
+        let mut profile = UserProfile {
             user_id,
             preference_vector,
             genre_affinities: std::collections::HashMap::new(),
-            temporal_patterns: Default::default(),
-            mood_history: Vec::new(),
+            temporal_patterns,
+            mood_history,
             interaction_count: events.len(),
             last_update_time: chrono::Utc::now(),
-        })
+        };
+
+        // Onboarding answers stand in for viewing history until the user
+        // has watched enough
+        if !ProgressivePersonalization::ready_for_personalization(events.len()) {
+            if let Some(session) = self.elicitation.load(user_id).await? {
+                session.apply_to(&mut profile);
+                profile.interaction_count += session.rated_count();
+            }
+        }
+
+        Ok(profile)
     }
 }
 
@@ -97,22 +237,25 @@ async fn health() -> impl Responder {
     }))
 }
 
+/// Users with at most this many viewing events get cold start recommendations
+const COLD_START_MAX_WATCHES: usize = 5;
+
+/// Co-viewers beyond this many are ignored in group recommendations
+const MAX_CO_VIEWERS: usize = 8;
+
 /// Recommendation request
 #[derive(Debug, Deserialize)]
 struct RecommendationRequest {
     user_id: Uuid,
-    context: Option<RecommendationContextDto>,
+    context: Option<RecommendationContext>,
     limit: Option<usize>,
-    exclude_watched: Option<bool>,
+    /// Minimum cosine distance between picks, when the context sets no
+    /// diversification
     diversity_threshold: Option<f32>,
-}
-
-#[derive(Debug, Deserialize)]
-struct RecommendationContextDto {
-    mood: Option<String>,
-    time_of_day: Option<String>,
-    device_type: Option<String>,
-    viewing_with: Option<Vec<String>>,
+    /// Tenant whose re-ranking policy applies when the context sets none
+    tenant: Option<String>,
+    /// How co-viewers' tastes combine when the context lists `viewing_with`
+    group: Option<GroupConfig>,
 }
 
 /// Recommendation response
@@ -137,8 +280,18 @@ async fn get_recommendations(
     req: web::Json<RecommendationRequest>,
     state: web::Data<AppState>,
 ) -> impl Responder {
+    // Client re-ranking overrides must be valid
+    if let Some(rerank) = req.context.as_ref().and_then(|ctx| ctx.rerank.as_ref()) {
+        if let Err(e) = rerank.validate() {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Invalid rerank config",
+                "message": e.to_string()
+            }));
+        }
+    }
+
     // Load user profile
-    let profile = match state.load_user_profile(req.user_id).await {
+    let mut profile = match state.load_user_profile(req.user_id).await {
         Ok(profile) => profile,
         Err(e) => {
             tracing::error!("Failed to load user profile: {}", e);
@@ -149,68 +302,244 @@ async fn get_recommendations(
         }
     };
 
+    // The client's UTC offset moves the user's temporal patterns to their
+    // local time, for this request and for relearning
+    if let Some(offset) = req
+        .context
+        .as_ref()
+        .and_then(|ctx| ctx.utc_offset_minutes)
+        .filter(|offset| is_valid_utc_offset(*offset))
+    {
+        if offset != profile.temporal_patterns.utc_offset_minutes {
+            if let Err(e) = state
+                .temporal_patterns
+                .set_utc_offset(req.user_id, offset)
+                .await
+            {
+                tracing::warn!("Failed to store UTC offset: {}", e);
+            }
+            profile.temporal_patterns.utc_offset_minutes = offset;
+        }
+    }
+
+    // A newly picked mood in the request is feedback for mood inference;
+    // the same pick repeated on later requests is not
+    if let Some(mood) = req.context.as_ref().and_then(|ctx| ctx.mood.as_deref()) {
+        let repeated = current_explicit_pick(
+            &profile.mood_history,
+            chrono::Utc::now(),
+            state.mood.config(),
+        )
+        .is_some_and(|pick| {
+            canonical_mood(mood).is_some_and(|label| pick.context_tags.iter().any(|t| t == label))
+        });
+        if !repeated {
+            if let Err(e) = record_explicit_mood(&state, req.user_id, mood).await {
+                tracing::warn!("Failed to record explicit mood: {}", e);
+            }
+        }
+    }
+
     // Load LoRA adapter if available
-    let lora_adapter = state.lora_storage.load_adapter(req.user_id, "default").await.ok();
+    let lora_adapter = state
+        .lora_storage
+        .load_adapter(req.user_id, "default")
+        .await
+        .ok();
 
-    // Convert context
-    let context = req.context.as_ref().map(|ctx| {
-        media_gateway_sona::RecommendationContext {
-            mood: ctx.mood.clone(),
-            time_of_day: ctx.time_of_day.clone(),
-            device_type: ctx.device_type.clone(),
-            viewing_with: ctx.viewing_with.clone().unwrap_or_default(),
+    // Titles, genres and people the user is not interested in
+    let suppressions = match state.load_suppressions(req.user_id).await {
+        Ok(suppressions) => Some(suppressions),
+        Err(e) => {
+            tracing::warn!("Failed to load suppressions: {}", e);
+            None
         }
-    });
+    };
+    // The collaborative model is skipped while it (re)trains
+    let collaborative = state
+        .collaborative
+        .as_ref()
+        .and_then(|engine| engine.try_read().ok());
+    let mut sources = CandidateSources::default()
+        .with_graph(state.graph.as_ref())
+        .with_context(&state.context_filter);
+    sources.suppressions = suppressions.as_ref();
+    if let Some(engine) = collaborative.as_deref() {
+        sources = sources.with_collaborative(engine);
+    }
+    if let Some(engine) = state.content_based.as_deref() {
+        sources = sources.with_content_based(engine);
+    }
+    if let Some(trending) = state.trending.as_deref() {
+        sources = sources.with_trending(trending);
+    }
 
-    // Get content embedding function (simulated for now)
-    let get_embedding = |content_id: Uuid| -> anyhow::Result<Vec<f32>> {
-        // In production, this would query the embedding database
-        Ok(vec![0.0; 512])
+    // "Up next" titles from the user's current session; the embeddings of
+    // its titles and their transition candidates feed profile affinity
+    let session_recommender = state.session.read().await;
+    let session = match state.load_viewing_events(req.user_id).await {
+        Ok(events) => media_gateway_sona::session::current_session(
+            &events,
+            chrono::Utc::now(),
+            session_recommender.config(),
+        ),
+        Err(e) => {
+            tracing::warn!("Failed to load viewing events for the session: {}", e);
+            Vec::new()
+        }
+    };
+    let session_embeddings = if session.is_empty() {
+        HashMap::new()
+    } else {
+        state
+            .content_embeddings(&session_recommender.embedding_ids(&session))
+            .await
     };
+    let get_session_embedding = embedding_lookup(session_embeddings);
+    sources = sources.with_session(&session_recommender, &session, &get_session_embedding);
+
+    // Candidate embeddings come from the vector store; titles without a
+    // stored vector fall back to this lookup
+    let get_embedding = embedding_lookup(HashMap::new());
+    let embeddings = state
+        .content_based
+        .as_deref()
+        .map(|engine| engine as &dyn ContentEmbeddingStore);
+
+    let mut context = req.context.clone();
+    if let Some(min_distance) = req.diversity_threshold {
+        let ctx = context.get_or_insert_with(Default::default);
+        ctx.diversity
+            .get_or_insert_with(|| media_gateway_sona::DiversityConfig {
+                min_distance,
+                ..Default::default()
+            });
+    }
 
-    // Generate recommendations
-    match GenerateRecommendations::execute(
-        req.user_id,
-        &profile,
-        context,
-        lora_adapter.as_ref(),
-        get_embedding,
-    ).await {
+    // Experiment the user takes part in, if any
+    let assigned = assign_experiment(&state, req.user_id).await;
+
+    // Without a client config, re-rank with the tenant's policy and the
+    // variant's overrides
+    if context.as_ref().map_or(true, |ctx| ctx.rerank.is_none()) {
+        let variant_config = assigned
+            .variant
+            .as_ref()
+            .map(|(_, variant)| &variant.config);
+        if let Some(rerank) = resolve_rerank(&state, req.tenant.as_deref(), variant_config) {
+            context.get_or_insert_with(Default::default).rerank = Some(rerank);
+        }
+    }
+
+    // Generate recommendations; people watching together get picks for the
+    // whole group, and users with too little history to personalize start
+    // from live trending content
+    let co_viewers: Vec<Uuid> = context
+        .as_ref()
+        .map(|ctx| ctx.viewing_with_user_ids())
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|id| *id != req.user_id)
+        .take(MAX_CO_VIEWERS)
+        .collect();
+    let result = if !co_viewers.is_empty() {
+        recommend_for_group(
+            &state,
+            &profile,
+            lora_adapter.as_ref(),
+            &co_viewers,
+            context,
+            &req.group.clone().unwrap_or_default(),
+            sources,
+        )
+        .await
+    } else if profile.interaction_count <= COLD_START_MAX_WATCHES {
+        let signup_context = SignupContext {
+            selected_genres: None,
+            age_range: None,
+            region: context.as_ref().and_then(|ctx| ctx.region.clone()),
+        };
+        HandleColdStartUser::execute_with_trending(
+            req.user_id,
+            Some(signup_context),
+            state.trending.as_deref(),
+        )
+        .await
+    } else {
+        GenerateRecommendations::execute(
+            req.user_id,
+            &profile,
+            context,
+            lora_adapter.as_ref(),
+            get_embedding,
+            |_| None,
+            sources,
+            &RetrievalConfig::default(),
+            Some(state.catalog.as_ref()),
+            embeddings,
+            Some(state.explanations.as_ref()),
+        )
+        .await
+    };
+    match result {
         Ok(mut recommendations) => {
-            // Check if user is in any active recommendation experiments
-            if let Ok(experiments) = state.experiment_repo.list_running_experiments().await {
-                for experiment in experiments {
-                    // Try to assign user to variant
-                    if let Ok(variant) = state.experiment_repo.assign_variant(experiment.id, req.user_id).await {
-                        // Apply experiment variant to recommendations
-                        for rec in &mut recommendations {
-                            rec.experiment_variant = Some(format!("{}:{}", experiment.name, variant.name));
-                        }
-
-                        // Record exposure
-                        let _ = state.experiment_repo.record_exposure(
-                            experiment.id,
-                            variant.id,
-                            req.user_id,
-                            Some(serde_json::json!({
-                                "endpoint": "recommendations",
-                                "num_recommendations": recommendations.len()
-                            }))
-                        ).await;
-
-                        break; // Only assign to first matching experiment
-                    }
+            if let Some(limit) = req.limit {
+                recommendations.truncate(limit);
+            }
+
+            // Apply experiment variant to recommendations
+            if let Some((experiment, variant)) = &assigned.variant {
+                for rec in &mut recommendations {
+                    rec.experiment_variant = Some(format!("{}:{}", experiment.name, variant.name));
+                }
+
+                // Record exposure
+                if let Err(e) = state
+                    .ab_testing
+                    .record_exposure(experiment.id, variant.id, req.user_id)
+                    .await
+                {
+                    tracing::warn!("Failed to record exposure: {}", e);
+                }
+            }
+            // Held-out users were served the baseline; record it so they can
+            // be compared against exposed users
+            for experiment in &assigned.holdouts {
+                if let Err(e) = state
+                    .ab_testing
+                    .record_holdout_exposure(experiment.id, req.user_id)
+                    .await
+                {
+                    tracing::warn!("Failed to record holdout exposure: {}", e);
                 }
             }
 
-            let response = RecommendationResponse {
-                recommendations: recommendations.into_iter().map(|r| RecommendationDto {
+            // Keep the reasons so the user can later ask why an item was shown
+            let explanations: Vec<_> = recommendations
+                .iter()
+                .map(|r| StoredExplanation {
+                    user_id: req.user_id,
                     content_id: r.content_id,
-                    confidence_score: r.confidence_score,
-                    recommendation_type: format!("{:?}", r.recommendation_type),
-                    based_on: r.based_on,
-                    explanation: r.explanation,
-                }).collect(),
+                    reasons: r.reasons.clone(),
+                    sources: r.sources.clone(),
+                    generated_at: r.generated_at,
+                })
+                .collect();
+            if let Err(e) = state.explanations.save(&explanations).await {
+                tracing::warn!("Failed to store recommendation explanations: {}", e);
+            }
+
+            let response = RecommendationResponse {
+                recommendations: recommendations
+                    .into_iter()
+                    .map(|r| RecommendationDto {
+                        content_id: r.content_id,
+                        confidence_score: r.confidence_score,
+                        recommendation_type: format!("{:?}", r.recommendation_type),
+                        based_on: r.based_on,
+                        explanation: r.explanation,
+                    })
+                    .collect(),
                 generated_at: chrono::Utc::now().to_rfc3339(),
                 ttl_seconds: 3600,
             };
@@ -226,8 +555,75 @@ async fn get_recommendations(
     }
 }
 
+/// Recommendations for the user watching together with `co_viewers`
+async fn recommend_for_group(
+    state: &AppState,
+    profile: &UserProfile,
+    lora_adapter: Option<&UserLoRAAdapter>,
+    co_viewers: &[Uuid],
+    context: Option<RecommendationContext>,
+    config: &GroupConfig,
+    sources: CandidateSources<'_>,
+) -> anyhow::Result<Vec<Recommendation>> {
+    let mut viewers = Vec::with_capacity(co_viewers.len());
+    for &user_id in co_viewers {
+        let profile = state.load_user_profile(user_id).await?;
+        let adapter = state
+            .lora_storage
+            .load_adapter(user_id, "default")
+            .await
+            .ok();
+        let limit = state.load_parental_limit(user_id).await?;
+        viewers.push((profile, adapter, limit));
+    }
+
+    // Every member's stored parental controls apply; the group is limited to
+    // the strictest of them
+    let requester = GroupMember {
+        profile,
+        lora_adapter,
+        display_name: None,
+        max_maturity_rating: strictest_rating([
+            context.as_ref().and_then(|ctx| ctx.max_maturity_rating),
+            state.load_parental_limit(profile.user_id).await?,
+        ]),
+    };
+    let members: Vec<GroupMember<'_>> = std::iter::once(requester)
+        .chain(viewers.iter().map(|(profile, adapter, limit)| GroupMember {
+            profile,
+            lora_adapter: adapter.as_ref(),
+            display_name: None,
+            max_maturity_rating: *limit,
+        }))
+        .collect();
+
+    let get_embedding = embedding_lookup(HashMap::new());
+    let embeddings = state
+        .content_based
+        .as_deref()
+        .map(|engine| engine as &dyn ContentEmbeddingStore);
+
+    let recommendations = GenerateGroupRecommendations::execute(
+        &members,
+        context,
+        config,
+        get_embedding,
+        |_| None,
+        sources,
+        &RetrievalConfig::default(),
+        Some(state.catalog.as_ref()),
+        embeddings,
+    )
+    .await?;
+    Ok(recommendations
+        .into_iter()
+        .map(|group| group.recommendation)
+        .collect())
+}
+
 /// Similar content request
 #[derive(Debug, Deserialize)]
+#[allow(dead_code)] // Similarity search is not wired to the embedding store yet
 struct SimilarContentRequest {
     content_id: Uuid,
     limit: Option<usize>,
@@ -236,7 +632,7 @@ struct SimilarContentRequest {
 /// POST /api/v1/recommendations/similar
 async fn get_similar_content(
     _req: web::Json<SimilarContentRequest>,
-    _engine: web::Data<Arc<SonaEngine>>,
+    _state: web::Data<AppState>,
 ) -> impl Responder {
     HttpResponse::Ok().json(serde_json::json!({
         "similar_content": []
@@ -268,7 +664,11 @@ async fn get_personalization_score(
     };
 
     // Load LoRA adapter
-    let lora_adapter = match state.lora_storage.load_adapter(req.user_id, "default").await {
+    let lora_adapter = match state
+        .lora_storage
+        .load_adapter(req.user_id, "default")
+        .await
+    {
         Ok(adapter) => adapter,
         Err(e) => {
             tracing::warn!("LoRA adapter not found, using base model: {}", e);
@@ -310,7 +710,7 @@ async fn get_personalization_score(
     HttpResponse::Ok().json(serde_json::json!({
         "user_id": req.user_id,
         "content_id": req.content_id,
-        "score": total_score.min(1.0).max(0.0),
+        "score": total_score.clamp(0.0, 1.0),
         "components": {
             "collaborative": collaborative_score,
             "content_based": content_based_score,
@@ -344,8 +744,10 @@ async fn update_profile(
     state: web::Data<AppState>,
 ) -> impl Responder {
     // Convert viewing events
-    let events: Vec<ViewingEvent> = req.viewing_events.iter().map(|dto| {
-        ViewingEvent {
+    let events: Vec<ViewingEvent> = req
+        .viewing_events
+        .iter()
+        .map(|dto| ViewingEvent {
             content_id: dto.content_id,
             timestamp: chrono::DateTime::parse_from_rfc3339(&dto.timestamp)
                 .unwrap_or_else(|_| chrono::Utc::now().into())
@@ -354,8 +756,18 @@ async fn update_profile(
             rating: dto.rating,
             is_rewatch: dto.is_rewatch,
             dismissed: dto.dismissed,
+        })
+        .collect();
+
+    // Runtimes let early abandons be judged in minutes watched
+    let content_ids: Vec<Uuid> = events.iter().map(|e| e.content_id).collect();
+    let attributes = match state.catalog.content_attributes(&content_ids).await {
+        Ok(attributes) => attributes,
+        Err(e) => {
+            tracing::warn!("Failed to load content runtimes: {}", e);
+            std::collections::HashMap::new()
         }
-    }).collect();
+    };
 
     // Store events in database
     for event in &events {
@@ -369,7 +781,7 @@ async fn update_profile(
                 rating = EXCLUDED.rating,
                 is_rewatch = EXCLUDED.is_rewatch,
                 dismissed = EXCLUDED.dismissed
-            "#
+            "#,
         )
         .bind(req.user_id)
         .bind(event.content_id)
@@ -379,15 +791,48 @@ async fn update_profile(
         .bind(event.is_rewatch)
         .bind(event.dismissed)
         .execute(&state.db_pool)
-        .await {
+        .await
+        {
             tracing::error!("Failed to store viewing event: {}", e);
         }
+
+        // Stream the viewing into the collaborative model and feed the
+        // adapter training worker without holding up the request
+        if let (Some(activity), Some(event)) = (
+            &state.collaborative_activity,
+            viewing_activity_event(req.user_id, event),
+        ) {
+            if let Err(e) = activity.try_send(event) {
+                tracing::warn!("Dropped collaborative filtering activity: {}", e);
+            }
+        }
+        if let Some(activity) = &state.lora_activity {
+            let activity = activity.try_send(UserActivity {
+                user_id: req.user_id,
+                event: event.clone(),
+            });
+            if let Err(e) = activity {
+                tracing::warn!("Dropped LoRA training activity: {}", e);
+            }
+        }
+
+        // Dismissals, early abandons and low ratings become implicit negatives
+        let config = NegativeFeedbackConfig::default();
+        let runtime_minutes = attributes
+            .get(&event.content_id)
+            .and_then(|a| a.runtime_minutes);
+        if let Some(feedback) =
+            NegativeFeedback::from_viewing_event(req.user_id, event, runtime_minutes, &config)
+        {
+            if let Err(e) = state.negative_feedback.record(&feedback).await {
+                tracing::error!("Failed to record implicit negative feedback: {}", e);
+            }
+        }
     }
 
-    // Get content embedding function
-    let get_embedding = |content_id: Uuid| -> anyhow::Result<Vec<f32>> {
-        Ok(vec![0.0; 512])
-    };
+    // Stored embeddings of the watched titles
+    let content_ids: Vec<Uuid> = events.iter().map(|e| e.content_id).collect();
+    let get_embedding = embedding_lookup(state.content_embeddings(&content_ids).await);
 
     // Update preference vector
     match BuildUserPreferenceVector::execute(req.user_id, &events, get_embedding).await {
@@ -412,11 +857,395 @@ async fn update_profile(
     }
 }
 
+/// Suppression list query
+#[derive(Debug, Deserialize)]
+struct SuppressionListQuery {
+    include_revoked: Option<bool>,
+    limit: Option<usize>,
+}
+
+/// Largest page of signals the suppression list returns
+const MAX_SUPPRESSIONS_PAGE: usize = 1000;
+
+/// GET /api/v1/users/{user_id}/suppressions
+async fn list_suppressions(
+    user_id: web::Path<Uuid>,
+    query: web::Query<SuppressionListQuery>,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let include_revoked = query.include_revoked.unwrap_or(false);
+    let limit = query
+        .limit
+        .unwrap_or(NegativeFeedbackConfig::default().max_signals)
+        .clamp(1, MAX_SUPPRESSIONS_PAGE);
+    match state
+        .negative_feedback
+        .list(*user_id, include_revoked, limit)
+        .await
+    {
+        Ok(feedback) => HttpResponse::Ok().json(serde_json::json!({
+            "suppressions": feedback,
+            "count": feedback.len()
+        })),
+        Err(e) => {
+            tracing::error!("Failed to list suppressions: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to list suppressions",
+                "message": e.to_string()
+            }))
+        }
+    }
+}
+
+/// "Not interested" request
+#[derive(Debug, Deserialize)]
+struct NotInterestedRequest {
+    target: SuppressionTarget,
+}
+
+/// POST /api/v1/users/{user_id}/suppressions
+async fn add_suppression(
+    user_id: web::Path<Uuid>,
+    req: web::Json<NotInterestedRequest>,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let feedback =
+        NegativeFeedback::new(*user_id, req.target.clone(), FeedbackSource::NotInterested);
+
+    match state.negative_feedback.record(&feedback).await {
+        Ok(_) => HttpResponse::Created().json(feedback),
+        Err(e) => {
+            tracing::error!("Failed to record suppression: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to record suppression",
+                "message": e.to_string()
+            }))
+        }
+    }
+}
+
+/// DELETE /api/v1/users/{user_id}/suppressions/{feedback_id}
+async fn remove_suppression(
+    path: web::Path<(Uuid, Uuid)>,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let (user_id, feedback_id) = path.into_inner();
+    match state.negative_feedback.revoke(user_id, feedback_id).await {
+        Ok(true) => HttpResponse::Ok().json(serde_json::json!({
+            "status": "revoked",
+            "id": feedback_id
+        })),
+        Ok(false) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Suppression not found"
+        })),
+        Err(e) => {
+            tracing::error!("Failed to revoke suppression: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to revoke suppression",
+                "message": e.to_string()
+            }))
+        }
+    }
+}
+
+/// GET /api/v1/users/{user_id}/suppressions/summary
+///
+/// Decayed suppressions in the form search personalization applies.
+async fn get_suppression_summary(
+    user_id: web::Path<Uuid>,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    match state.load_suppressions(*user_id).await {
+        Ok(set) => HttpResponse::Ok().json(set.summary()),
+        Err(e) => {
+            tracing::error!("Failed to load suppressions: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to load suppressions",
+                "message": e.to_string()
+            }))
+        }
+    }
+}
+
+/// Explanation query
+#[derive(Debug, Deserialize)]
+struct ExplanationQuery {
+    locale: Option<String>,
+}
+
+/// GET /api/v1/users/{user_id}/recommendations/{content_id}/explanation
+///
+/// Why an item was last recommended to the user: the typed reasons with
+/// their strengths, each rendered in the requested locale.
+async fn get_recommendation_explanation(
+    path: web::Path<(Uuid, Uuid)>,
+    query: web::Query<ExplanationQuery>,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let (user_id, content_id) = path.into_inner();
+    let locale = query.locale.as_deref().unwrap_or("en");
+
+    match state.explanations.get(user_id, content_id).await {
+        Ok(Some(explanation)) => {
+            let templates = ExplanationTemplates::default();
+            let reasons: Vec<_> = explanation
+                .reasons
+                .iter()
+                .map(|r| {
+                    serde_json::json!({
+                        "reason": r,
+                        "text": templates.render(&r.reason, locale),
+                    })
+                })
+                .collect();
+
+            HttpResponse::Ok().json(serde_json::json!({
+                "user_id": user_id,
+                "content_id": content_id,
+                "explanation": templates.headline(&explanation.reasons, locale),
+                "reasons": reasons,
+                "sources": explanation.sources,
+                "generated_at": explanation.generated_at,
+                "locale": locale
+            }))
+        }
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Item was not recommended to this user"
+        })),
+        Err(e) => {
+            tracing::error!("Failed to load recommendation explanation: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to load explanation",
+                "message": e.to_string()
+            }))
+        }
+    }
+}
+
+/// Record a mood the user picked alongside the inference for their session
+async fn record_explicit_mood(
+    state: &AppState,
+    user_id: Uuid,
+    mood: &str,
+) -> anyhow::Result<media_gateway_sona::MoodState> {
+    let events = state.load_viewing_events(user_id).await?;
+    let local_hour = state.local_hour(user_id).await?;
+    state
+        .mood
+        .record_explicit(user_id, mood, &events, local_hour, chrono::Utc::now())
+        .await
+}
+
+/// GET /api/v1/users/{user_id}/mood
+///
+/// The user's current mood: a recent explicit pick, otherwise the mood
+/// inferred from the current session.
+async fn get_mood(user_id: web::Path<Uuid>, state: web::Data<AppState>) -> impl Responder {
+    let result = async {
+        let events = state.load_viewing_events(*user_id).await?;
+        let local_hour = state.local_hour(*user_id).await?;
+        state
+            .mood
+            .mood_history(*user_id, &events, local_hour, chrono::Utc::now())
+            .await
+    }
+    .await;
+
+    match result {
+        Ok(history) => {
+            let now = chrono::Utc::now();
+            let mood = current_mood_label(&history, now, state.mood.config());
+            let latest = history.iter().max_by_key(|s| s.timestamp);
+            HttpResponse::Ok().json(serde_json::json!({
+                "user_id": *user_id,
+                "mood": mood,
+                "state": latest,
+                "nearest_mood": latest.and_then(|s| nearest_mood(&s.mood_vector)).map(|(m, _)| m),
+                "dimensions": MOOD_DIMENSIONS
+            }))
+        }
+        Err(e) => {
+            tracing::error!("Failed to infer mood: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to infer mood",
+                "message": e.to_string()
+            }))
+        }
+    }
+}
+
+/// Explicit mood pick
+#[derive(Debug, Deserialize)]
+struct MoodRequest {
+    mood: String,
+}
+
+/// POST /api/v1/users/{user_id}/mood
+async fn set_mood(
+    user_id: web::Path<Uuid>,
+    req: web::Json<MoodRequest>,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    if media_gateway_sona::canonical_mood(&req.mood).is_none() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Unknown mood: {}", req.mood)
+        }));
+    }
+
+    match record_explicit_mood(&state, *user_id, &req.mood).await {
+        Ok(mood) => HttpResponse::Created().json(mood),
+        Err(e) => {
+            tracing::error!("Failed to record mood: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to record mood",
+                "message": e.to_string()
+            }))
+        }
+    }
+}
+
+fn onboarding_not_configured() -> HttpResponse {
+    HttpResponse::ServiceUnavailable().json(serde_json::json!({
+        "error": "Onboarding not configured"
+    }))
+}
+
+fn onboarding_status(session: Option<&ElicitationSession>) -> serde_json::Value {
+    serde_json::json!({
+        "started": session.is_some(),
+        "complete": session.is_some_and(|s| s.is_complete()),
+        "presented": session.map_or(0, |s| s.presented_count()),
+        "rated": session.map_or(0, |s| s.rated_count()),
+        "pending": session.map(|s| s.pending_questions()).unwrap_or_default()
+    })
+}
+
+/// GET /api/v1/users/{user_id}/onboarding
+async fn get_onboarding(user_id: web::Path<Uuid>, state: web::Data<AppState>) -> impl Responder {
+    match state.elicitation.load(*user_id).await {
+        Ok(session) => HttpResponse::Ok().json(onboarding_status(session.as_ref())),
+        Err(e) => {
+            tracing::error!("Failed to load onboarding session: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to load onboarding session",
+                "message": e.to_string()
+            }))
+        }
+    }
+}
+
+/// POST /api/v1/users/{user_id}/onboarding/questions
+///
+/// Titles to rate: unanswered ones already presented, otherwise the next
+/// round. Empty once onboarding is complete.
+async fn get_onboarding_questions(
+    user_id: web::Path<Uuid>,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let Some(catalog) = state.elicitation_catalog.as_deref() else {
+        return onboarding_not_configured();
+    };
+
+    let result = async {
+        let mut session = state.elicitation.load(*user_id).await?.unwrap_or_else(|| {
+            ElicitationSession::new(*user_id, catalog, ElicitationConfig::default())
+        });
+        let pending = session.pending_questions();
+        if !pending.is_empty() {
+            return Ok((pending, session));
+        }
+        let questions = session.next_questions(catalog);
+        state.elicitation.save(&session).await?;
+        anyhow::Ok((questions, session))
+    }
+    .await;
+
+    match result {
+        Ok((questions, session)) => HttpResponse::Ok().json(serde_json::json!({
+            "questions": questions,
+            "complete": session.is_complete()
+        })),
+        Err(e) => {
+            tracing::error!("Failed to select onboarding questions: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to select onboarding questions",
+                "message": e.to_string()
+            }))
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct OnboardingAnswerDto {
+    content_id: Uuid,
+    response: ElicitationResponse,
+}
+
+/// Answers to presented onboarding titles
+#[derive(Debug, Deserialize)]
+struct OnboardingAnswersRequest {
+    answers: Vec<OnboardingAnswerDto>,
+}
+
+/// POST /api/v1/users/{user_id}/onboarding/answers
+async fn record_onboarding_answers(
+    user_id: web::Path<Uuid>,
+    req: web::Json<OnboardingAnswersRequest>,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let Some(catalog) = state.elicitation_catalog.as_deref() else {
+        return onboarding_not_configured();
+    };
+
+    let mut session = match state.elicitation.load(*user_id).await {
+        Ok(Some(session)) => session,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Onboarding not started"
+            }))
+        }
+        Err(e) => {
+            tracing::error!("Failed to load onboarding session: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to load onboarding session",
+                "message": e.to_string()
+            }));
+        }
+    };
+
+    // Get content embedding function (simulated for now)
+    let get_embedding = |_content_id: Uuid| -> anyhow::Result<Vec<f32>> {
+        // In production, this would query the embedding database
+        Ok(vec![0.0; 512])
+    };
+
+    for answer in &req.answers {
+        if let Err(e) =
+            session.record_answer(catalog, answer.content_id, answer.response, get_embedding)
+        {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Invalid onboarding answer",
+                "message": e.to_string()
+            }));
+        }
+    }
+
+    match state.elicitation.save(&session).await {
+        Ok(()) => HttpResponse::Ok().json(onboarding_status(Some(&session))),
+        Err(e) => {
+            tracing::error!("Failed to save onboarding session: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to save onboarding session",
+                "message": e.to_string()
+            }))
+        }
+    }
+}
+
 /// LoRA training request
 #[derive(Debug, Deserialize)]
 struct LoraTrainingRequest {
     user_id: Uuid,
-    force: Option<bool>,
 }
 
 /// POST /api/v1/lora/train
@@ -425,7 +1254,11 @@ async fn trigger_lora_training(
     state: web::Data<AppState>,
 ) -> impl Responder {
     // Load or create LoRA adapter
-    let mut adapter = match state.lora_storage.load_adapter(req.user_id, "default").await {
+    let mut adapter = match state
+        .lora_storage
+        .load_adapter(req.user_id, "default")
+        .await
+    {
         Ok(adapter) => adapter,
         Err(_) => {
             let mut adapter = UserLoRAAdapter::new(req.user_id);
@@ -454,11 +1287,12 @@ async fn trigger_lora_training(
         WHERE user_id = $1
         ORDER BY timestamp DESC
         LIMIT 50
-        "#
+        "#,
     )
     .bind(req.user_id)
     .fetch_all(&state.db_pool)
-    .await {
+    .await
+    {
         Ok(history) => history,
         Err(e) => {
             tracing::error!("Failed to fetch viewing history: {}", e);
@@ -479,19 +1313,51 @@ async fn trigger_lora_training(
         }));
     }
 
-    // Get content embedding function
-    let get_embedding = |content_id: Uuid| -> anyhow::Result<Vec<f32>> {
-        Ok(vec![0.0; 512])
-    };
-
-    // Train LoRA adapter
+    // Train LoRA adapter on embeddings of the titles' text from the serving
+    // model, or the model the user's experiment variant selects
     let start_time = std::time::Instant::now();
-    match UpdateUserLoRA::execute(
-        &mut adapter,
-        &events,
-        get_embedding,
-        &profile.preference_vector,
-    ).await {
+    let result = if state.engine.model_server().is_some() {
+        let content_ids: Vec<Uuid> = events.iter().map(|e| e.content_id).collect();
+        let texts = match load_content_texts(&state.db_pool, &content_ids).await {
+            Ok(texts) => texts,
+            Err(e) => {
+                tracing::error!("Failed to load content text: {}", e);
+                return HttpResponse::InternalServerError().json(serde_json::json!({
+                    "error": "Failed to load content text",
+                    "message": e.to_string()
+                }));
+            }
+        };
+        let variant_config = assign_experiment(&state, req.user_id)
+            .await
+            .variant
+            .map(|(_, variant)| variant.config);
+        UpdateUserLoRA::execute_with_inference(
+            &mut adapter,
+            &events,
+            &state.engine,
+            variant_config.as_ref(),
+            |content_id| {
+                texts
+                    .get(&content_id)
+                    .cloned()
+                    .ok_or_else(|| anyhow::anyhow!("Content {} not found", content_id))
+            },
+            &profile.preference_vector,
+        )
+        .await
+    } else {
+        // Get content embedding function
+        let get_embedding = |_content_id: Uuid| -> anyhow::Result<Vec<f32>> { Ok(vec![0.0; 512]) };
+        UpdateUserLoRA::execute(
+            &mut adapter,
+            &events,
+            get_embedding,
+            &profile.preference_vector,
+        )
+        .await
+    };
+    match result {
         Ok(_) => {
             let duration_ms = start_time.elapsed().as_millis() as u64;
 
@@ -522,19 +1388,262 @@ async fn trigger_lora_training(
     }
 }
 
+/// Embedding lookup over prefetched vectors; titles without one get an
+/// all-zero embedding, which carries no preference
+fn embedding_lookup(
+    embeddings: HashMap<Uuid, Vec<f32>>,
+) -> impl Fn(Uuid) -> anyhow::Result<Vec<f32>> {
+    move |content_id| {
+        Ok(embeddings
+            .get(&content_id)
+            .cloned()
+            .unwrap_or_else(|| vec![0.0; 512]))
+    }
+}
+
+/// Title and overview of each title, as embedded by the serving model
+async fn load_content_texts(
+    db_pool: &sqlx::PgPool,
+    content_ids: &[Uuid],
+) -> anyhow::Result<std::collections::HashMap<Uuid, String>> {
+    let rows: Vec<(Uuid, String, Option<String>)> =
+        sqlx::query_as("SELECT id, title, overview FROM content WHERE id = ANY($1)")
+            .bind(content_ids)
+            .fetch_all(db_pool)
+            .await?;
+    Ok(rows
+        .into_iter()
+        .map(|(id, title, overview)| match overview {
+            Some(overview) => (id, format!("{}. {}", title, overview)),
+            None => (id, title),
+        })
+        .collect())
+}
+
+/// Experiments a recommendation request takes part in
+#[derive(Default)]
+struct RequestExperiments {
+    /// The first running experiment the user is assigned a servable variant in
+    variant: Option<(Experiment, Variant)>,
+    /// Experiments checked before it that hold the user out
+    holdouts: Vec<Experiment>,
+}
+
+async fn assign_experiment(state: &AppState, user_id: Uuid) -> RequestExperiments {
+    let mut assigned = RequestExperiments::default();
+    let experiments = match state.ab_testing.get_running_experiments().await {
+        Ok(experiments) => experiments,
+        Err(e) => {
+            tracing::warn!("Failed to load running experiments: {}", e);
+            return assigned;
+        }
+    };
+
+    let context = AssignmentContext::new(user_id);
+    for experiment in experiments {
+        let variant_id = match state.ab_testing.assign(experiment.id, &context).await {
+            Ok(AssignmentDecision::Variant { variant_id, .. }) => variant_id,
+            Ok(AssignmentDecision::Holdout) => {
+                assigned.holdouts.push(experiment);
+                continue;
+            }
+            Ok(AssignmentDecision::Excluded { .. }) => continue,
+            Err(e) => {
+                tracing::warn!("Failed to assign experiment {}: {}", experiment.name, e);
+                continue;
+            }
+        };
+        let variant = match state.ab_testing.get_variants(experiment.id).await {
+            Ok(variants) => variants.into_iter().find(|v| v.id == variant_id),
+            Err(e) => {
+                tracing::warn!("Failed to load variants of {}: {}", experiment.name, e);
+                None
+            }
+        };
+        let Some(variant) = variant else {
+            continue;
+        };
+
+        // A variant whose model cannot be served is not shown
+        if let Some(server) = state.engine.model_server() {
+            if let Err(e) = server.for_variant(Some(&variant.config)).await {
+                tracing::warn!("Failed to load model for variant {}: {}", variant.name, e);
+                continue;
+            }
+        }
+        assigned.variant = Some((experiment, variant));
+        break;
+    }
+    assigned
+}
+
+/// Tenant re-ranking policy with an experiment variant's overrides; None when
+/// neither configures re-ranking
+fn resolve_rerank(
+    state: &AppState,
+    tenant: Option<&str>,
+    variant_config: Option<&serde_json::Value>,
+) -> Option<RerankConfig> {
+    let overrides = variant_config.is_some_and(|c| c.get(RERANK_VARIANT_KEY).is_some());
+    let default_policies;
+    let policies = match state.rerank_policies.as_deref() {
+        Some(policies) => policies,
+        None if overrides => {
+            default_policies = RerankPolicies::default();
+            &default_policies
+        }
+        None => return None,
+    };
+
+    match policies.resolve(tenant, variant_config) {
+        Ok(config) => Some(config),
+        Err(e) => {
+            tracing::warn!("Ignoring invalid variant rerank overrides: {}", e);
+            state
+                .rerank_policies
+                .as_ref()
+                .and_then(|policies| policies.resolve(tenant, None).ok())
+        }
+    }
+}
+
+/// 503 for model endpoints when no model registry is configured
+fn model_registry_not_configured() -> HttpResponse {
+    HttpResponse::ServiceUnavailable().json(serde_json::json!({
+        "error": "Model registry not configured"
+    }))
+}
+
+/// GET /api/v1/models
+async fn get_models(state: web::Data<AppState>) -> impl Responder {
+    let Some(server) = state.engine.model_server() else {
+        return model_registry_not_configured();
+    };
+
+    let active = server.active();
+    let versions = match server.registry().versions(server.name()).await {
+        Ok(versions) => versions,
+        Err(e) => {
+            tracing::warn!("Failed to list model versions: {}", e);
+            Vec::new()
+        }
+    };
+
+    HttpResponse::Ok().json(serde_json::json!({
+        "active": active.metadata,
+        "loaded_at": active.loaded_at,
+        "versions": versions,
+        "shadow": server.shadow_report()
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+struct ModelReloadRequest {
+    /// Defaults to the latest published version
+    version: Option<String>,
+}
+
+/// POST /api/v1/models/reload
+async fn reload_model(
+    req: web::Json<ModelReloadRequest>,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let Some(server) = state.engine.model_server() else {
+        return model_registry_not_configured();
+    };
+
+    match server.reload(req.version.as_deref()).await {
+        Ok(metadata) => HttpResponse::Ok().json(serde_json::json!({
+            "status": "active",
+            "model": metadata
+        })),
+        Err(e) => {
+            tracing::error!("Failed to reload model: {}", e);
+            HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Failed to reload model",
+                "message": e.to_string()
+            }))
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ShadowModelRequest {
+    version: String,
+    #[serde(default = "default_shadow_sample_rate")]
+    sample_rate: f64,
+}
+
+fn default_shadow_sample_rate() -> f64 {
+    0.1
+}
+
+/// POST /api/v1/models/shadow
+async fn start_shadow_model(
+    req: web::Json<ShadowModelRequest>,
+    state: web::Data<AppState>,
+) -> impl Responder {
+    let Some(server) = state.engine.model_server() else {
+        return model_registry_not_configured();
+    };
+
+    match server.start_shadow(&req.version, req.sample_rate).await {
+        Ok(()) => HttpResponse::Ok().json(serde_json::json!({
+            "status": "shadowing",
+            "version": req.version,
+            "sample_rate": req.sample_rate
+        })),
+        Err(e) => {
+            tracing::error!("Failed to start shadow model: {}", e);
+            HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Failed to start shadow model",
+                "message": e.to_string()
+            }))
+        }
+    }
+}
+
+/// GET /api/v1/models/shadow
+async fn get_shadow_report(state: web::Data<AppState>) -> impl Responder {
+    let Some(server) = state.engine.model_server() else {
+        return model_registry_not_configured();
+    };
+
+    match server.shadow_report() {
+        Some(report) => HttpResponse::Ok().json(report),
+        None => HttpResponse::NotFound().json(serde_json::json!({
+            "error": "No shadow model running"
+        })),
+    }
+}
+
+/// DELETE /api/v1/models/shadow
+async fn stop_shadow_model(state: web::Data<AppState>) -> impl Responder {
+    let Some(server) = state.engine.model_server() else {
+        return model_registry_not_configured();
+    };
+
+    match server.stop_shadow() {
+        Some(report) => HttpResponse::Ok().json(report),
+        None => HttpResponse::NotFound().json(serde_json::json!({
+            "error": "No shadow model running"
+        })),
+    }
+}
+
 /// Create experiment request
 #[derive(Debug, Deserialize)]
 struct CreateExperimentRequest {
     name: String,
     description: Option<String>,
-    traffic_allocation: f32,
+    traffic_allocation: f64,
     variants: Vec<CreateVariantDto>,
 }
 
 #[derive(Debug, Deserialize)]
 struct CreateVariantDto {
     name: String,
-    weight: f32,
+    weight: f64,
     config: serde_json::Value,
 }
 
@@ -543,31 +1652,37 @@ async fn create_experiment(
     req: web::Json<CreateExperimentRequest>,
     state: web::Data<AppState>,
 ) -> impl Responder {
-    use media_gateway_sona::{Experiment, ExperimentStatus};
-
-    let mut experiment = Experiment::new(
-        req.name.clone(),
-        req.description.clone(),
-        req.traffic_allocation,
-    );
-
-    for variant_dto in &req.variants {
-        experiment.add_variant(
-            variant_dto.name.clone(),
-            variant_dto.weight,
-            variant_dto.config.clone(),
-        );
-    }
-
-    match state.experiment_repo.create_experiment(&experiment).await {
-        Ok(experiment_id) => {
-            HttpResponse::Ok().json(serde_json::json!({
-                "experiment_id": experiment_id,
-                "name": experiment.name,
-                "status": "draft",
-                "variants": experiment.variants.len()
-            }))
+    let result = async {
+        let experiment = state
+            .ab_testing
+            .create_experiment(
+                &req.name,
+                req.description.as_deref(),
+                req.traffic_allocation,
+            )
+            .await?;
+        for variant in &req.variants {
+            state
+                .ab_testing
+                .add_variant(
+                    experiment.id,
+                    &variant.name,
+                    variant.weight,
+                    variant.config.clone(),
+                )
+                .await?;
         }
+        anyhow::Ok(experiment)
+    }
+    .await;
+
+    match result {
+        Ok(experiment) => HttpResponse::Ok().json(serde_json::json!({
+            "experiment_id": experiment.id,
+            "name": experiment.name,
+            "status": experiment.status,
+            "variants": req.variants.len()
+        })),
         Err(e) => {
             tracing::error!("Failed to create experiment: {}", e);
             HttpResponse::InternalServerError().json(serde_json::json!({
@@ -583,12 +1698,20 @@ async fn get_experiment(
     experiment_id: web::Path<Uuid>,
     state: web::Data<AppState>,
 ) -> impl Responder {
-    match state.experiment_repo.get_experiment(*experiment_id).await {
-        Ok(experiment) => HttpResponse::Ok().json(experiment),
+    match state
+        .ab_testing
+        .repository()
+        .get_experiment(*experiment_id)
+        .await
+    {
+        Ok(Some(experiment)) => HttpResponse::Ok().json(experiment),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Experiment not found"
+        })),
         Err(e) => {
             tracing::error!("Failed to get experiment: {}", e);
-            HttpResponse::NotFound().json(serde_json::json!({
-                "error": "Experiment not found",
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to get experiment",
                 "message": e.to_string()
             }))
         }
@@ -607,22 +1730,22 @@ async fn update_experiment_status(
     req: web::Json<UpdateExperimentStatusRequest>,
     state: web::Data<AppState>,
 ) -> impl Responder {
-    use media_gateway_sona::ExperimentStatus;
-
-    let status = match req.status.as_str() {
-        "draft" => ExperimentStatus::Draft,
-        "running" => ExperimentStatus::Running,
-        "paused" => ExperimentStatus::Paused,
-        "completed" => ExperimentStatus::Completed,
-        _ => {
-            return HttpResponse::BadRequest().json(serde_json::json!({
-                "error": "Invalid status",
-                "valid_statuses": ["draft", "running", "paused", "completed"]
-            }));
-        }
-    };
+    if !matches!(
+        req.status.as_str(),
+        "draft" | "running" | "paused" | "completed"
+    ) {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Invalid status",
+            "valid_statuses": ["draft", "running", "paused", "completed"]
+        }));
+    }
 
-    match state.experiment_repo.update_status(*experiment_id, status).await {
+    match state
+        .ab_testing
+        .repository()
+        .update_experiment(*experiment_id, Some(&req.status), None)
+        .await
+    {
         Ok(_) => HttpResponse::Ok().json(serde_json::json!({
             "experiment_id": *experiment_id,
             "status": req.status
@@ -639,7 +1762,7 @@ async fn update_experiment_status(
 
 /// GET /api/v1/experiments
 async fn list_experiments(state: web::Data<AppState>) -> impl Responder {
-    match state.experiment_repo.list_running_experiments().await {
+    match state.ab_testing.get_running_experiments().await {
         Ok(experiments) => HttpResponse::Ok().json(serde_json::json!({
             "experiments": experiments,
             "count": experiments.len()
@@ -660,7 +1783,7 @@ struct RecordConversionRequest {
     experiment_id: Uuid,
     user_id: Uuid,
     metric_name: String,
-    value: f32,
+    value: f64,
     metadata: Option<serde_json::Value>,
 }
 
@@ -670,7 +1793,11 @@ async fn record_conversion(
     state: web::Data<AppState>,
 ) -> impl Responder {
     // Get user's variant assignment
-    let variant = match state.experiment_repo.assign_variant(req.experiment_id, req.user_id).await {
+    let variant = match state
+        .ab_testing
+        .assign_variant(req.experiment_id, req.user_id)
+        .await
+    {
         Ok(v) => v,
         Err(e) => {
             tracing::warn!("User not in experiment: {}", e);
@@ -680,14 +1807,19 @@ async fn record_conversion(
         }
     };
 
-    match state.experiment_repo.record_conversion(
-        req.experiment_id,
-        variant.id,
-        req.user_id,
-        &req.metric_name,
-        req.value,
-        req.metadata.clone(),
-    ).await {
+    match state
+        .ab_testing
+        .repository()
+        .record_metric(
+            req.experiment_id,
+            variant.id,
+            req.user_id,
+            &req.metric_name,
+            req.value,
+            req.metadata.clone(),
+        )
+        .await
+    {
         Ok(_) => HttpResponse::Ok().json(serde_json::json!({
             "status": "recorded",
             "experiment_id": req.experiment_id,
@@ -706,11 +1838,19 @@ async fn record_conversion(
 }
 
 /// GET /api/v1/experiments/{experiment_id}/metrics
+///
+/// Returns per-variant counts plus `analysis`: confidence intervals, z/Welch
+/// tests against control, always-valid sequential p-values, a sample-ratio
+/// check and an overall `verdict`.
 async fn get_experiment_metrics(
     experiment_id: web::Path<Uuid>,
     state: web::Data<AppState>,
 ) -> impl Responder {
-    match state.experiment_repo.get_experiment_metrics(*experiment_id).await {
+    match state
+        .ab_testing
+        .get_experiment_metrics(*experiment_id)
+        .await
+    {
         Ok(metrics) => HttpResponse::Ok().json(metrics),
         Err(e) => {
             tracing::error!("Failed to get experiment metrics: {}", e);
@@ -722,17 +1862,8 @@ async fn get_experiment_metrics(
     }
 }
 
-#[actix_web::main]
-async fn main() -> std::io::Result<()> {
-    // Initialize tracing
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info"))
-        )
-        .json()
-        .init();
-
+/// Build the engine, repositories and background jobs, then serve the API
+pub async fn run() -> std::io::Result<()> {
     tracing::info!("Starting SONA Personalization Engine on port 8082");
 
     // Initialize database connection
@@ -747,19 +1878,238 @@ async fn main() -> std::io::Result<()> {
 
     // Initialize SONA engine
     let config = SonaConfig::default();
-    let engine = Arc::new(SonaEngine::new(config));
+    let mut engine = SonaEngine::new(config);
+
+    // Serve embeddings from the model registry when one is configured,
+    // following newly published versions unless one is pinned
+    match ModelServer::from_env().await {
+        Ok(Some(server)) => {
+            let server = Arc::new(server);
+            if std::env::var("SONA_MODEL_VERSION").is_err() {
+                let interval = std::env::var("SONA_MODEL_REFRESH_SECS")
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(300);
+                Arc::clone(&server).spawn_watcher(std::time::Duration::from_secs(interval));
+            }
+            engine = engine.with_model_server(server);
+        }
+        Ok(None) => {}
+        Err(e) => tracing::error!("Failed to load model from registry: {}", e),
+    }
+    let engine = Arc::new(engine);
 
     // Initialize LoRA storage
     let lora_storage = Arc::new(media_gateway_sona::LoRAStorage::new(db_pool.clone()));
 
-    // Initialize experiment repository
-    let experiment_repo = Arc::new(media_gateway_sona::ExperimentRepository::new(db_pool.clone()));
+    // Initialize A/B testing and refresh bandit allocations as they come due
+    let ab_testing = Arc::new(ABTestingService::new(db_pool.clone()));
+    Arc::clone(&ab_testing).spawn_bandit_updates(std::time::Duration::from_secs(60));
+
+    // Initialize negative feedback repository and drop faded signals daily
+    let negative_feedback = Arc::new(PostgresNegativeFeedbackRepository::new(db_pool.clone()));
+    media_gateway_sona::negative_feedback::spawn_pruning(
+        negative_feedback.clone(),
+        NegativeFeedbackConfig::default(),
+        std::time::Duration::from_secs(24 * 3600),
+    );
+
+    // Initialize explanation repository
+    let explanations = Arc::new(PostgresExplanationRepository::new(db_pool.clone()));
+
+    // Initialize temporal pattern repository and relearn patterns from new
+    // playback periodically
+    let temporal_patterns = Arc::new(PostgresTemporalPatternRepository::new(db_pool.clone()));
+    let temporal_interval = std::env::var("SONA_TEMPORAL_REFRESH_SECS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(3600);
+    Arc::new(TemporalPatternJob::new(
+        temporal_patterns.clone(),
+        TemporalPatternConfig::default(),
+    ))
+    .spawn(std::time::Duration::from_secs(temporal_interval));
+
+    // Initialize mood inference and drop old observations daily
+    let mood_repository: Arc<dyn MoodRepository> =
+        Arc::new(PostgresMoodRepository::new(db_pool.clone()));
+    media_gateway_sona::mood::spawn_pruning(
+        mood_repository.clone(),
+        MoodConfig::default(),
+        std::time::Duration::from_secs(24 * 3600),
+    );
+    let mood = Arc::new(MoodService::new(
+        mood_repository.clone(),
+        MoodConfig::default(),
+    ));
+
+    // Catalog attributes for filtering, scoring and implicit negatives
+    let catalog = Arc::new(PostgresContentCatalog::new(db_pool.clone()));
+
+    // Candidate engines; collaborative filtering and content similarity need
+    // Qdrant (optional, those sources are skipped without QDRANT_URL). The
+    // collaborative model trains in the background, and retrains as it
+    // drifts; its source is skipped until the first train completes
+    let graph = Arc::new(GraphRecommender::new(db_pool.clone()));
+    let context_filter = Arc::new(ContextAwareFilter::new(db_pool.clone()));
+    let (collaborative, content_based) = match std::env::var("QDRANT_URL") {
+        Ok(qdrant_url) => {
+            let collection = std::env::var("SONA_CONTENT_COLLECTION")
+                .unwrap_or_else(|_| "media_embeddings".to_string());
+            let content_based =
+                match ContentBasedEngine::new(db_pool.clone(), &qdrant_url, collection) {
+                    Ok(engine) => Some(Arc::new(engine)),
+                    Err(e) => {
+                        tracing::error!("Failed to initialize content-based engine: {}", e);
+                        None
+                    }
+                };
+            let collaborative = match qdrant_client::Qdrant::from_url(&qdrant_url).build() {
+                Ok(qdrant) => {
+                    let engine = Arc::new(tokio::sync::RwLock::new(
+                        CollaborativeFilteringEngine::new(db_pool.clone(), qdrant),
+                    ));
+                    if let Err(e) = engine.read().await.initialize_collections().await {
+                        tracing::error!("Failed to initialize collaborative collections: {}", e);
+                    }
+                    CollaborativeFilteringEngine::spawn_retraining(
+                        Arc::clone(&engine),
+                        std::time::Duration::from_secs(COLLABORATIVE_RETRAIN_CHECK_SECS),
+                    );
+                    Some(engine)
+                }
+                Err(e) => {
+                    tracing::error!("Failed to initialize collaborative engine: {}", e);
+                    None
+                }
+            };
+            (collaborative, content_based)
+        }
+        Err(_) => (None, None),
+    };
+
+    // Tag untagged titles with moods from their stored embeddings daily
+    // (needs the vector store)
+    match content_based.as_ref() {
+        Some(content_based) => {
+            media_gateway_sona::mood::spawn_tagging(
+                mood_repository.clone(),
+                Arc::clone(content_based) as Arc<dyn ContentEmbeddingStore>,
+                MoodTaggingConfig::default(),
+                std::time::Duration::from_secs(24 * 3600),
+            );
+        }
+        None => tracing::info!("Mood tagging disabled: no content vector store configured"),
+    }
+
+    // Fold streamed viewing activity into the collaborative model between
+    // retrains
+    let collaborative_activity = collaborative.as_ref().map(|engine| {
+        let (sender, receiver) = tokio::sync::mpsc::channel(COLLABORATIVE_ACTIVITY_BUFFER);
+        let engine = Arc::clone(engine);
+        tokio::spawn(async move {
+            if let Err(e) = CollaborativeFilteringEngine::consume(engine, receiver).await {
+                tracing::error!("Collaborative filtering stream stopped: {}", e);
+            }
+        });
+        sender
+    });
+
+    // Retrain and roll out user adapters from viewing activity (needs the
+    // vector store for content embeddings)
+    let lora_activity = content_based.as_ref().map(|content_based| {
+        let (sender, receiver) = tokio::sync::mpsc::channel(LORA_ACTIVITY_BUFFER);
+        let data = PostgresTrainingDataSource::new(
+            db_pool.clone(),
+            Arc::clone(content_based) as Arc<dyn ContentEmbeddingStore>,
+        );
+        LoRATrainingWorker::new(
+            lora_storage.clone(),
+            Arc::new(data),
+            LoRATrainingConfig::default(),
+        )
+        .spawn(receiver);
+        sender
+    });
+
+    // Session "up next" candidates from a transition graph mined from recent
+    // watch history, rebuilt periodically (the first build runs at startup)
+    let session = Arc::new(tokio::sync::RwLock::new(SessionRecommender::new(
+        TransitionGraph::default(),
+        SessionConfig::default(),
+    )));
+    let session_interval = std::env::var("SONA_SESSION_GRAPH_REFRESH_SECS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(3600);
+    media_gateway_sona::session::spawn_graph_refresh(
+        Arc::clone(&session),
+        db_pool.clone(),
+        std::time::Duration::from_secs(session_interval),
+    );
+
+    // Onboarding asks new users about titles from the trained ALS model
+    // (optional, onboarding endpoints are unavailable without a checkpoint)
+    let elicitation = Arc::new(PostgresElicitationRepository::new(db_pool.clone()));
+    let elicitation_catalog = match std::env::var("SONA_ALS_CHECKPOINT") {
+        Ok(path) => match MatrixFactorization::load_checkpoint(&path, ALSConfig::default())
+            .and_then(|model| ElicitationCatalog::from_model(&model))
+        {
+            Ok(catalog) => Some(Arc::new(catalog)),
+            Err(e) => {
+                tracing::error!("Failed to load onboarding catalog: {}", e);
+                None
+            }
+        },
+        Err(_) => None,
+    };
+
+    // Live trending counters for cold start users (optional, cold start
+    // falls back to static lists)
+    let trending = match std::env::var("REDIS_URL") {
+        Ok(redis_url) => {
+            match TrendingService::from_redis_url(&redis_url, TrendingConfig::default()).await {
+                Ok(trending) => Some(Arc::new(trending)),
+                Err(e) => {
+                    tracing::warn!("Failed to initialize trending counters: {}", e);
+                    None
+                }
+            }
+        }
+        Err(_) => None,
+    };
+
+    // Per-tenant re-ranking policies (optional, re-ranking then follows the
+    // request and experiment variants only)
+    let rerank_policies = match RerankPolicies::from_env() {
+        Ok(policies) => policies.map(Arc::new),
+        Err(e) => {
+            tracing::error!("Failed to load rerank policies: {}", e);
+            None
+        }
+    };
 
     // Create app state
     let app_state = web::Data::new(AppState {
         engine,
         lora_storage,
-        experiment_repo,
+        ab_testing,
+        negative_feedback,
+        explanations,
+        temporal_patterns,
+        mood,
+        catalog,
+        collaborative,
+        content_based,
+        graph,
+        context_filter,
+        elicitation,
+        elicitation_catalog,
+        trending,
+        rerank_policies,
+        lora_activity,
+        collaborative_activity,
+        session,
         db_pool,
     });
 
@@ -771,17 +2121,75 @@ async fn main() -> std::io::Result<()> {
             .service(
                 web::scope("/api/v1")
                     .route("/recommendations", web::post().to(get_recommendations))
-                    .route("/recommendations/similar", web::post().to(get_similar_content))
-                    .route("/personalization/score", web::post().to(get_personalization_score))
+                    .route(
+                        "/recommendations/similar",
+                        web::post().to(get_similar_content),
+                    )
+                    .route(
+                        "/personalization/score",
+                        web::post().to(get_personalization_score),
+                    )
                     .route("/profile/update", web::post().to(update_profile))
                     .route("/lora/train", web::post().to(trigger_lora_training))
+                    // Negative feedback endpoints
+                    .route(
+                        "/users/{user_id}/suppressions",
+                        web::get().to(list_suppressions),
+                    )
+                    .route(
+                        "/users/{user_id}/suppressions",
+                        web::post().to(add_suppression),
+                    )
+                    .route(
+                        "/users/{user_id}/suppressions/summary",
+                        web::get().to(get_suppression_summary),
+                    )
+                    .route(
+                        "/users/{user_id}/suppressions/{feedback_id}",
+                        web::delete().to(remove_suppression),
+                    )
+                    .route(
+                        "/users/{user_id}/recommendations/{content_id}/explanation",
+                        web::get().to(get_recommendation_explanation),
+                    )
+                    // Mood endpoints
+                    .route("/users/{user_id}/mood", web::get().to(get_mood))
+                    .route("/users/{user_id}/mood", web::post().to(set_mood))
+                    // Onboarding endpoints
+                    .route("/users/{user_id}/onboarding", web::get().to(get_onboarding))
+                    .route(
+                        "/users/{user_id}/onboarding/questions",
+                        web::post().to(get_onboarding_questions),
+                    )
+                    .route(
+                        "/users/{user_id}/onboarding/answers",
+                        web::post().to(record_onboarding_answers),
+                    )
+                    // Model registry endpoints
+                    .route("/models", web::get().to(get_models))
+                    .route("/models/reload", web::post().to(reload_model))
+                    .route("/models/shadow", web::post().to(start_shadow_model))
+                    .route("/models/shadow", web::get().to(get_shadow_report))
+                    .route("/models/shadow", web::delete().to(stop_shadow_model))
                     // A/B Testing endpoints
                     .route("/experiments", web::post().to(create_experiment))
                     .route("/experiments", web::get().to(list_experiments))
-                    .route("/experiments/{experiment_id}", web::get().to(get_experiment))
-                    .route("/experiments/{experiment_id}/status", web::put().to(update_experiment_status))
-                    .route("/experiments/{experiment_id}/metrics", web::get().to(get_experiment_metrics))
-                    .route("/experiments/conversions", web::post().to(record_conversion))
+                    .route(
+                        "/experiments/{experiment_id}",
+                        web::get().to(get_experiment),
+                    )
+                    .route(
+                        "/experiments/{experiment_id}/status",
+                        web::put().to(update_experiment_status),
+                    )
+                    .route(
+                        "/experiments/{experiment_id}/metrics",
+                        web::get().to(get_experiment_metrics),
+                    )
+                    .route(
+                        "/experiments/conversions",
+                        web::post().to(record_conversion),
+                    ),
             )
     })
     .bind(("0.0.0.0", 8082))?
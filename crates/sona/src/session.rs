@@ -0,0 +1,534 @@
+//! Session-based "up next" recommendations
+//!
+//! Long-term engines (profile vectors, ALS, LoRA) capture what a user likes
+//! in general; this module captures what they are doing right now. The
+//! current session is the trailing run of `ViewingEvent`s without a long
+//! inactivity gap. Next-item candidates come from an item-to-item transition
+//! graph mined from historical sessions, optionally re-scored by a sequence
+//! model exported to ONNX, and are blended with long-term profile affinity
+//! with a weight that grows with session length.
+
+use crate::inference::ONNXInference;
+use crate::profile::UserProfile;
+use crate::types::{RecommendationType, ScoredContent, ViewingEvent};
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use media_gateway_core::math::cosine_similarity;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Row};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Session recommender configuration
+#[derive(Debug, Clone)]
+pub struct SessionConfig {
+    /// Inactivity gap that ends a session (default: 30 minutes)
+    pub session_gap_minutes: i64,
+    /// Most recent events considered (default: 20)
+    pub max_session_length: usize,
+    /// Events below this completion are treated as browsing noise (default: 0.1)
+    pub min_completion: f32,
+    /// Per-step decay applied to older session items (default: 0.7)
+    pub recency_decay: f32,
+    /// Session length at which session evidence reaches full weight (default: 5)
+    pub saturation_length: usize,
+    /// Weight of session evidence at saturation (default: 0.8)
+    pub max_session_weight: f32,
+    /// Share of session evidence taken from the sequence model (default: 0.5)
+    pub model_weight: f32,
+    /// Transition-graph candidates re-scored per request (default: 200)
+    pub candidate_pool: usize,
+    /// Watch history mined for the transition graph, in days (default: 30)
+    pub graph_window_days: i64,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            session_gap_minutes: 30,
+            max_session_length: 20,
+            min_completion: 0.1,
+            recency_decay: 0.7,
+            saturation_length: 5,
+            max_session_weight: 0.8,
+            model_weight: 0.5,
+            candidate_pool: 200,
+            graph_window_days: 30,
+        }
+    }
+}
+
+impl SessionConfig {
+    fn gap(&self) -> Duration {
+        Duration::minutes(self.session_gap_minutes)
+    }
+
+    /// Weight of session evidence against long-term affinity for a session
+    /// of `length` items
+    pub fn session_weight(&self, length: usize) -> f32 {
+        if self.saturation_length == 0 {
+            return self.max_session_weight;
+        }
+        let progress = length.min(self.saturation_length) as f32 / self.saturation_length as f32;
+        self.max_session_weight * progress
+    }
+
+    fn counts(&self, event: &ViewingEvent) -> bool {
+        !event.dismissed && event.completion_rate >= self.min_completion
+    }
+}
+
+/// The user's current session: trailing events with no gap longer than
+/// `session_gap_minutes`, ending no earlier than that gap before `now`
+pub fn current_session(
+    events: &[ViewingEvent],
+    now: DateTime<Utc>,
+    config: &SessionConfig,
+) -> Vec<ViewingEvent> {
+    let mut sorted: Vec<&ViewingEvent> = events.iter().filter(|e| config.counts(e)).collect();
+    sorted.sort_by_key(|e| e.timestamp);
+
+    let Some(last) = sorted.last() else {
+        return Vec::new();
+    };
+    if now - last.timestamp > config.gap() {
+        return Vec::new();
+    }
+
+    let mut start = sorted.len() - 1;
+    while start > 0
+        && sorted[start].timestamp - sorted[start - 1].timestamp <= config.gap()
+        && sorted.len() - start < config.max_session_length
+    {
+        start -= 1;
+    }
+    sorted[start..].iter().map(|e| (*e).clone()).collect()
+}
+
+/// Split a user's history into sessions at inactivity gaps
+pub fn split_sessions(events: &[ViewingEvent], config: &SessionConfig) -> Vec<Vec<ViewingEvent>> {
+    let mut sorted: Vec<&ViewingEvent> = events.iter().filter(|e| config.counts(e)).collect();
+    sorted.sort_by_key(|e| e.timestamp);
+
+    let mut sessions: Vec<Vec<ViewingEvent>> = Vec::new();
+    let mut previous: Option<DateTime<Utc>> = None;
+    for event in sorted {
+        let new_session = previous.map_or(true, |p| event.timestamp - p > config.gap());
+        if new_session {
+            sessions.push(Vec::new());
+        }
+        if let Some(session) = sessions.last_mut() {
+            session.push(event.clone());
+        }
+        previous = Some(event.timestamp);
+    }
+    sessions
+}
+
+/// Item-to-item transition probabilities mined from historical sessions
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TransitionGraph {
+    /// Outgoing transitions per item, highest probability first
+    transitions: HashMap<Uuid, Vec<(Uuid, f32)>>,
+}
+
+impl TransitionGraph {
+    /// Count consecutive transitions, weighting each by how much of the
+    /// next item was watched, then normalize per source item
+    pub fn fit<'a>(sessions: impl IntoIterator<Item = &'a [ViewingEvent]>) -> Self {
+        let mut counts: HashMap<Uuid, HashMap<Uuid, f32>> = HashMap::new();
+        for session in sessions {
+            for pair in session.windows(2) {
+                let (from, to) = (&pair[0], &pair[1]);
+                if from.content_id == to.content_id {
+                    continue;
+                }
+                *counts
+                    .entry(from.content_id)
+                    .or_default()
+                    .entry(to.content_id)
+                    .or_insert(0.0) += to.completion_rate.clamp(0.0, 1.0);
+            }
+        }
+
+        let transitions = counts
+            .into_iter()
+            .filter_map(|(from, next)| {
+                let total: f32 = next.values().sum();
+                if total <= 0.0 {
+                    return None;
+                }
+                let mut next: Vec<(Uuid, f32)> =
+                    next.into_iter().map(|(id, c)| (id, c / total)).collect();
+                next.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+                Some((from, next))
+            })
+            .collect();
+
+        Self { transitions }
+    }
+
+    /// Mine transitions from watch history since `since`
+    ///
+    /// `watch_progress` keeps the latest watch per title, so sessions are
+    /// reconstructed from `last_watched` order.
+    pub async fn load(pool: &PgPool, since: DateTime<Utc>, config: &SessionConfig) -> Result<Self> {
+        let rows = sqlx::query(
+            r#"
+            SELECT user_id, content_id, completion_rate, last_watched
+            FROM watch_progress
+            WHERE last_watched >= $1
+            ORDER BY user_id, last_watched
+            "#,
+        )
+        .bind(since)
+        .fetch_all(pool)
+        .await?;
+
+        let mut histories: HashMap<Uuid, Vec<ViewingEvent>> = HashMap::new();
+        for row in rows {
+            let user_id: Uuid = row.try_get("user_id")?;
+            let completion_rate: Option<f64> = row.try_get("completion_rate")?;
+            histories.entry(user_id).or_default().push(ViewingEvent {
+                content_id: row.try_get("content_id")?,
+                timestamp: row.try_get("last_watched")?,
+                completion_rate: completion_rate.unwrap_or(0.0) as f32,
+                rating: None,
+                is_rewatch: false,
+                dismissed: false,
+            });
+        }
+
+        let sessions: Vec<Vec<ViewingEvent>> = histories
+            .values()
+            .flat_map(|events| split_sessions(events, config))
+            .collect();
+        let graph = Self::fit(sessions.iter().map(Vec::as_slice));
+        tracing::info!(
+            "Built session transition graph: {} items from {} sessions",
+            graph.len(),
+            sessions.len()
+        );
+        Ok(graph)
+    }
+
+    /// Score likely next items for a session (oldest item first), decaying
+    /// the influence of older items by `decay` per step
+    pub fn next_items(&self, session: &[Uuid], decay: f32, limit: usize) -> Vec<(Uuid, f32)> {
+        let in_session: HashSet<&Uuid> = session.iter().collect();
+        let mut scores: HashMap<Uuid, f32> = HashMap::new();
+        let mut weight = 1.0;
+        for item in session.iter().rev() {
+            if let Some(next) = self.transitions.get(item) {
+                for (candidate, probability) in next {
+                    if !in_session.contains(candidate) {
+                        *scores.entry(*candidate).or_insert(0.0) += weight * probability;
+                    }
+                }
+            }
+            weight *= decay;
+        }
+
+        let mut ranked: Vec<(Uuid, f32)> = scores.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        ranked.truncate(limit);
+        ranked
+    }
+
+    pub fn len(&self) -> usize {
+        self.transitions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.transitions.is_empty()
+    }
+}
+
+/// Rebuild the recommender's transition graph from recent watch history every
+/// `interval`; the current graph keeps serving while a rebuild runs or fails
+pub fn spawn_graph_refresh(
+    recommender: Arc<tokio::sync::RwLock<SessionRecommender>>,
+    pool: PgPool,
+    interval: std::time::Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            let config = recommender.read().await.config().clone();
+            let since = Utc::now() - Duration::days(config.graph_window_days);
+            match TransitionGraph::load(&pool, since, &config).await {
+                Ok(graph) => recommender.write().await.set_graph(graph),
+                Err(e) => tracing::warn!("Session transition graph refresh failed: {}", e),
+            }
+        }
+    })
+}
+
+/// Short-term recommender over the current session
+pub struct SessionRecommender {
+    graph: TransitionGraph,
+    sequence_model: Option<Arc<ONNXInference>>,
+    config: SessionConfig,
+}
+
+impl SessionRecommender {
+    pub fn new(graph: TransitionGraph, config: SessionConfig) -> Self {
+        Self {
+            graph,
+            sequence_model: None,
+            config,
+        }
+    }
+
+    /// Re-score candidates with a sequence model (GRU/transformer) that maps
+    /// the session's item embeddings to a predicted next-item embedding; see
+    /// [`ONNXInference::predict_next_embedding`]
+    pub fn with_sequence_model(mut self, model: Arc<ONNXInference>) -> Self {
+        self.sequence_model = Some(model);
+        self
+    }
+
+    pub fn config(&self) -> &SessionConfig {
+        &self.config
+    }
+
+    /// Replace the transition graph, e.g. with one mined from newer history
+    pub fn set_graph(&mut self, graph: TransitionGraph) {
+        self.graph = graph;
+    }
+
+    /// Titles whose embeddings [`Self::recommend`] may look up for `session`:
+    /// the session's items followed by its transition candidates
+    pub fn embedding_ids(&self, session: &[ViewingEvent]) -> Vec<Uuid> {
+        let mut ids: Vec<Uuid> = session.iter().map(|e| e.content_id).collect();
+        let candidates =
+            self.graph
+                .next_items(&ids, self.config.recency_decay, self.config.candidate_pool);
+        ids.extend(candidates.into_iter().map(|(id, _)| id));
+        ids
+    }
+
+    /// "Up next" candidates for the session, blended with long-term profile
+    /// affinity by session length
+    ///
+    /// `session` is the output of [`current_session`]. An empty session (or
+    /// one with no known transitions) yields no candidates, leaving the
+    /// long-term sources to fill the slate.
+    pub async fn recommend(
+        &self,
+        session: &[ViewingEvent],
+        profile: &UserProfile,
+        limit: usize,
+        get_content_embedding: impl Fn(Uuid) -> Result<Vec<f32>>,
+    ) -> Result<Vec<ScoredContent>> {
+        let items: Vec<Uuid> = session.iter().map(|e| e.content_id).collect();
+        if items.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let transitions = self.graph.next_items(
+            &items,
+            self.config.recency_decay,
+            self.config.candidate_pool,
+        );
+        if transitions.is_empty() {
+            return Ok(Vec::new());
+        }
+        let max_transition = transitions[0].1.max(f32::EPSILON);
+
+        let predicted = match &self.sequence_model {
+            Some(model) => {
+                let sequence = items
+                    .iter()
+                    .map(|id| get_content_embedding(*id))
+                    .collect::<Result<Vec<_>>>()?;
+                match model.predict_next_embedding(&sequence).await {
+                    Ok(embedding) => Some(embedding),
+                    Err(e) => {
+                        // Transition scores alone still make a usable slate
+                        tracing::warn!("Session sequence model failed: {}", e);
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
+
+        let session_weight = self.config.session_weight(items.len());
+        let has_profile = profile.preference_vector.iter().any(|v| *v != 0.0);
+        let reason = format!("watched {} this session", items.len());
+
+        let mut results = Vec::with_capacity(transitions.len());
+        for (content_id, transition) in transitions {
+            let mut session_score = transition / max_transition;
+            let needs_embedding = predicted.is_some() || has_profile;
+            let embedding = if needs_embedding {
+                Some(get_content_embedding(content_id)?)
+            } else {
+                None
+            };
+
+            if let (Some(predicted), Some(embedding)) = (&predicted, &embedding) {
+                let model_score = (cosine_similarity(predicted, embedding) + 1.0) / 2.0;
+                session_score = (1.0 - self.config.model_weight) * session_score
+                    + self.config.model_weight * model_score;
+            }
+
+            let score = match (&embedding, has_profile) {
+                (Some(embedding), true) => {
+                    let affinity =
+                        (cosine_similarity(&profile.preference_vector, embedding) + 1.0) / 2.0;
+                    session_weight * session_score + (1.0 - session_weight) * affinity
+                }
+                _ => session_score,
+            };
+
+            results.push(ScoredContent {
+                content_id,
+                score,
+                source: RecommendationType::SessionBased,
+                based_on: vec![reason.clone()],
+            });
+        }
+
+        results.sort_by(|a, b| b.score.total_cmp(&a.score));
+        results.truncate(limit);
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(n: u128) -> Uuid {
+        Uuid::from_u128(n)
+    }
+
+    fn event(item: u128, minute: i64) -> ViewingEvent {
+        ViewingEvent {
+            content_id: id(item),
+            timestamp: DateTime::<Utc>::from_timestamp(0, 0).unwrap() + Duration::minutes(minute),
+            completion_rate: 1.0,
+            rating: None,
+            is_rewatch: false,
+            dismissed: false,
+        }
+    }
+
+    /// Episodes 1 -> 2 -> 3 watched back to back by many users; 1 -> 9 once
+    fn graph() -> TransitionGraph {
+        let mut sessions = vec![vec![event(1, 0), event(2, 45), event(3, 90)]; 5];
+        sessions.push(vec![event(1, 0), event(9, 45)]);
+        TransitionGraph::fit(sessions.iter().map(Vec::as_slice))
+    }
+
+    #[test]
+    fn test_current_session_stops_at_gap() {
+        let config = SessionConfig::default();
+        let mut dismissed = event(5, 70);
+        dismissed.dismissed = true;
+        let events = vec![event(1, 0), event(2, 100), event(3, 120), dismissed];
+
+        let session = current_session(&events, events[2].timestamp + Duration::minutes(5), &config);
+        let ids: Vec<Uuid> = session.iter().map(|e| e.content_id).collect();
+        assert_eq!(ids, vec![id(2), id(3)]);
+
+        let stale = current_session(&events, events[2].timestamp + Duration::hours(2), &config);
+        assert!(stale.is_empty());
+    }
+
+    #[test]
+    fn test_current_session_caps_length() {
+        let config = SessionConfig {
+            max_session_length: 3,
+            ..Default::default()
+        };
+        let events: Vec<ViewingEvent> = (0..10).map(|i| event(i, i as i64 * 5)).collect();
+        let session = current_session(&events, events[9].timestamp, &config);
+        assert_eq!(session.len(), 3);
+        assert_eq!(session[2].content_id, id(9));
+    }
+
+    #[test]
+    fn test_split_sessions() {
+        let events = vec![event(3, 200), event(1, 0), event(2, 20)];
+        let sessions = split_sessions(&events, &SessionConfig::default());
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].len(), 2);
+        assert_eq!(sessions[1][0].content_id, id(3));
+    }
+
+    #[test]
+    fn test_transition_graph_ranks_frequent_next_items() {
+        let graph = graph();
+        let next = graph.next_items(&[id(1)], 0.7, 10);
+        assert_eq!(next[0].0, id(2));
+        assert!(next[0].1 > next[1].1);
+
+        // Items already in the session are never proposed
+        let next = graph.next_items(&[id(1), id(2)], 0.7, 10);
+        assert_eq!(next[0].0, id(3));
+        assert!(next
+            .iter()
+            .all(|(item, _)| *item != id(1) && *item != id(2)));
+    }
+
+    #[test]
+    fn test_embedding_ids_cover_session_and_candidates() {
+        let mut recommender =
+            SessionRecommender::new(TransitionGraph::default(), Default::default());
+        let session = vec![event(1, 0)];
+        assert_eq!(recommender.embedding_ids(&session), vec![id(1)]);
+
+        recommender.set_graph(graph());
+        let ids = recommender.embedding_ids(&session);
+        assert_eq!(ids[0], id(1));
+        assert!(ids.contains(&id(2)) && ids.contains(&id(9)));
+    }
+
+    #[test]
+    fn test_session_weight_grows_with_length() {
+        let config = SessionConfig::default();
+        assert_eq!(config.session_weight(0), 0.0);
+        assert!(config.session_weight(2) < config.session_weight(4));
+        assert_eq!(config.session_weight(50), config.max_session_weight);
+    }
+
+    #[tokio::test]
+    async fn test_recommend_blends_with_profile() {
+        let recommender = SessionRecommender::new(graph(), SessionConfig::default());
+        let mut profile = UserProfile::new(id(100));
+        profile.preference_vector = vec![0.0, 1.0];
+        let embedding = |item: Uuid| -> Result<Vec<f32>> {
+            Ok(if item == id(9) {
+                vec![0.0, 1.0]
+            } else {
+                vec![1.0, 0.0]
+            })
+        };
+
+        // One item in: long-term taste (item 9) outweighs the common transition
+        let short = recommender
+            .recommend(&[event(1, 0)], &profile, 5, embedding)
+            .await
+            .unwrap();
+        assert_eq!(short[0].content_id, id(9));
+        assert_eq!(short[0].source, RecommendationType::SessionBased);
+
+        // Without a profile the session evidence decides
+        let results = recommender
+            .recommend(&[event(1, 0)], &UserProfile::new(id(101)), 5, embedding)
+            .await
+            .unwrap();
+        assert_eq!(results[0].content_id, id(2));
+
+        assert!(recommender
+            .recommend(&[], &profile, 5, embedding)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
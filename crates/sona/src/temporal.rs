@@ -0,0 +1,748 @@
+//! Temporal Pattern Learning
+//!
+//! Derives a user's `TemporalContext` from playback history: how viewing is
+//! spread over local hours, weekdays and seasons, and which genres and
+//! content lengths the user picks in each viewing slot (weekday or weekend
+//! crossed with morning, afternoon, evening and night). Sessions are weighted
+//! by recency and every share is smoothed toward the user's overall mix, so
+//! sparsely observed slots stay close to neutral.
+//!
+//! [`TemporalPatternJob`] relearns patterns for users with new playback and
+//! stores them with the profile; `ContextAwareFilter` applies them when
+//! scoring candidates.
+
+use crate::types::TemporalContext;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDateTime, Timelike, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+use sqlx::{PgPool, Row};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tracing::{debug, info, instrument, warn};
+use uuid::Uuid;
+
+/// Part of the day by local hour
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Daypart {
+    /// 06:00-12:00
+    Morning,
+    /// 12:00-18:00
+    Afternoon,
+    /// 18:00-24:00
+    Evening,
+    /// 00:00-06:00
+    Night,
+}
+
+impl Daypart {
+    pub fn from_hour(hour: u32) -> Self {
+        match hour {
+            6..=11 => Daypart::Morning,
+            12..=17 => Daypart::Afternoon,
+            18..=23 => Daypart::Evening,
+            _ => Daypart::Night,
+        }
+    }
+}
+
+/// Weekday or weekend crossed with a daypart
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ViewingSlot {
+    pub weekend: bool,
+    pub daypart: Daypart,
+}
+
+impl ViewingSlot {
+    pub const COUNT: usize = 8;
+
+    /// Slot of a local time
+    ///
+    /// Nights belong to the day they follow, so 01:00 on a Saturday is a
+    /// Friday night and counts as a weekday.
+    pub fn at(local: NaiveDateTime) -> Self {
+        let daypart = Daypart::from_hour(local.hour());
+        let day = if daypart == Daypart::Night {
+            local - Duration::hours(6)
+        } else {
+            local
+        };
+        Self {
+            weekend: matches!(day.weekday(), Weekday::Sat | Weekday::Sun),
+            daypart,
+        }
+    }
+
+    /// Position in slot pattern vectors: weekday slots first, each in
+    /// morning, afternoon, evening, night order
+    pub fn index(self) -> usize {
+        let daypart = match self.daypart {
+            Daypart::Morning => 0,
+            Daypart::Afternoon => 1,
+            Daypart::Evening => 2,
+            Daypart::Night => 3,
+        };
+        if self.weekend {
+            4 + daypart
+        } else {
+            daypart
+        }
+    }
+}
+
+/// Content length class by runtime
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LengthBucket {
+    Short,
+    Medium,
+    Long,
+}
+
+impl LengthBucket {
+    pub const COUNT: usize = 3;
+
+    pub fn from_runtime(runtime_minutes: f32, config: &TemporalPatternConfig) -> Self {
+        if runtime_minutes <= config.short_max_minutes {
+            LengthBucket::Short
+        } else if runtime_minutes >= config.long_min_minutes {
+            LengthBucket::Long
+        } else {
+            LengthBucket::Medium
+        }
+    }
+
+    pub fn index(self) -> usize {
+        match self {
+            LengthBucket::Short => 0,
+            LengthBucket::Medium => 1,
+            LengthBucket::Long => 2,
+        }
+    }
+}
+
+/// Position of a month in seasonal patterns (winter first, northern hemisphere)
+pub fn season_index(month: u32) -> usize {
+    (month as usize % 12) / 3
+}
+
+fn utc_offset(minutes: i32) -> FixedOffset {
+    FixedOffset::east_opt(minutes * 60)
+        .unwrap_or_else(|| FixedOffset::east_opt(0).expect("zero offset is valid"))
+}
+
+impl TemporalContext {
+    /// `at` in the user's local time
+    pub fn local_time(&self, at: DateTime<Utc>) -> DateTime<FixedOffset> {
+        at.with_timezone(&utc_offset(self.utc_offset_minutes))
+    }
+
+    /// Slot the user is in at `at`
+    pub fn slot_at(&self, at: DateTime<Utc>) -> ViewingSlot {
+        ViewingSlot::at(self.local_time(at).naive_local())
+    }
+}
+
+/// Temporal pattern learning settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemporalPatternConfig {
+    /// Half-life of a session's weight (default: 90 days)
+    pub half_life_days: f32,
+    /// Pseudo-sessions pulling every share toward the user's overall mix
+    /// (default: 5.0)
+    pub prior_weight: f32,
+    /// Sessions needed before patterns are learned (default: 10)
+    pub min_sessions: usize,
+    /// Sessions with less viewing than this are ignored (default: 2 minutes)
+    pub min_watch_minutes: f32,
+    /// Runtime up to which content counts as short (default: 40 minutes)
+    pub short_max_minutes: f32,
+    /// Runtime from which content counts as long (default: 90 minutes)
+    pub long_min_minutes: f32,
+    /// Genres with slot patterns kept per user, by weight (default: 20)
+    pub max_genres: usize,
+    /// Playback history considered (default: 365 days)
+    pub history_days: i64,
+    /// Most recent sessions considered (default: 2000)
+    pub max_sessions: usize,
+}
+
+impl Default for TemporalPatternConfig {
+    fn default() -> Self {
+        Self {
+            half_life_days: 90.0,
+            prior_weight: 5.0,
+            min_sessions: 10,
+            min_watch_minutes: 2.0,
+            short_max_minutes: 40.0,
+            long_min_minutes: 90.0,
+            max_genres: 20,
+            history_days: 365,
+            max_sessions: 2000,
+        }
+    }
+}
+
+/// One playback session of a title
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlaybackSession {
+    pub content_id: Uuid,
+    pub started_at: DateTime<Utc>,
+    pub watched_minutes: f32,
+    pub runtime_minutes: Option<f32>,
+    pub genres: Vec<String>,
+}
+
+/// Preference for `weight` out of `total` against an expected share, in [0, 1]
+///
+/// The observed share is smoothed toward `expected` with `prior`
+/// pseudo-sessions, and its lift `l` over `expected` maps to `l / (1 + l)`,
+/// so viewing exactly as expected gives 0.5.
+fn preference(weight: f32, total: f32, expected: f32, prior: f32) -> f32 {
+    if expected <= 0.0 || total + prior <= 0.0 {
+        return 0.5;
+    }
+    let share = (weight + prior * expected) / (total + prior);
+    let lift = share / expected;
+    lift / (1.0 + lift)
+}
+
+/// Preferences for each bucket of a distribution against a uniform one
+fn distribution(weights: &[f32], total: f32, prior: f32) -> Vec<f32> {
+    let expected = 1.0 / weights.len() as f32;
+    weights
+        .iter()
+        .map(|&w| preference(w, total, expected, prior))
+        .collect()
+}
+
+/// Learn temporal patterns from playback history
+///
+/// Each session counts with weight `0.5^(age / half_life)`. Hourly, weekday
+/// and seasonal patterns compare the user's viewing to an even spread; genre
+/// and length patterns compare what the user picks in a slot to what they
+/// pick overall, so a user who mostly watches comedies only gets a comedy
+/// boost in the slots where comedies are even more common.
+pub struct LearnTemporalPatterns;
+
+impl LearnTemporalPatterns {
+    /// Patterns in the user's local time, or `None` with fewer than
+    /// `min_sessions` usable sessions
+    pub fn execute(
+        sessions: &[PlaybackSession],
+        utc_offset_minutes: i32,
+        config: &TemporalPatternConfig,
+        now: DateTime<Utc>,
+    ) -> Option<TemporalContext> {
+        let sessions: Vec<&PlaybackSession> = sessions
+            .iter()
+            .filter(|s| s.watched_minutes >= config.min_watch_minutes)
+            .collect();
+        if sessions.is_empty() || sessions.len() < config.min_sessions {
+            return None;
+        }
+
+        let offset = utc_offset(utc_offset_minutes);
+        let prior = config.prior_weight;
+
+        let mut total = 0.0;
+        let mut hourly = vec![0.0; 24];
+        let mut weekday = vec![0.0; 7];
+        let mut seasonal = vec![0.0; 4];
+        let mut slots = [0.0; ViewingSlot::COUNT];
+        let mut genre_totals: HashMap<String, f32> = HashMap::new();
+        let mut genre_slots: HashMap<String, Vec<f32>> = HashMap::new();
+        let mut sized_total = 0.0;
+        let mut sized_slots = [0.0; ViewingSlot::COUNT];
+        let mut length_totals = [0.0; LengthBucket::COUNT];
+        let mut length_slots = [[0.0; ViewingSlot::COUNT]; LengthBucket::COUNT];
+
+        for session in sessions {
+            let age_days = (now - session.started_at).num_seconds().max(0) as f32 / 86_400.0;
+            let weight = 0.5f32.powf(age_days / config.half_life_days);
+            let local = session.started_at.with_timezone(&offset).naive_local();
+            let slot = ViewingSlot::at(local).index();
+
+            total += weight;
+            hourly[local.hour() as usize] += weight;
+            weekday[local.weekday().num_days_from_monday() as usize] += weight;
+            seasonal[season_index(local.month())] += weight;
+            slots[slot] += weight;
+
+            let genres: HashSet<String> = session.genres.iter().map(|g| g.to_lowercase()).collect();
+            for genre in genres {
+                *genre_totals.entry(genre.clone()).or_default() += weight;
+                genre_slots
+                    .entry(genre)
+                    .or_insert_with(|| vec![0.0; ViewingSlot::COUNT])[slot] += weight;
+            }
+
+            if let Some(runtime) = session.runtime_minutes.filter(|&r| r > 0.0) {
+                let bucket = LengthBucket::from_runtime(runtime, config).index();
+                sized_total += weight;
+                sized_slots[slot] += weight;
+                length_totals[bucket] += weight;
+                length_slots[bucket][slot] += weight;
+            }
+        }
+
+        let mut genres: Vec<(String, f32)> = genre_totals.into_iter().collect();
+        genres.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        genres.truncate(config.max_genres);
+        let genre_slot_patterns = genres
+            .into_iter()
+            .map(|(genre, weight)| {
+                let expected = weight / total;
+                let counts = &genre_slots[&genre];
+                let pattern = (0..ViewingSlot::COUNT)
+                    .map(|s| preference(counts[s], slots[s], expected, prior))
+                    .collect();
+                (genre, pattern)
+            })
+            .collect();
+
+        let length_slot_patterns = if sized_total > 0.0 {
+            (0..LengthBucket::COUNT)
+                .map(|b| {
+                    let expected = length_totals[b] / sized_total;
+                    (0..ViewingSlot::COUNT)
+                        .map(|s| preference(length_slots[b][s], sized_slots[s], expected, prior))
+                        .collect()
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        Some(TemporalContext {
+            hourly_patterns: distribution(&hourly, total, prior),
+            weekday_patterns: distribution(&weekday, total, prior),
+            seasonal_patterns: distribution(&seasonal, total, prior),
+            recent_bias: TemporalContext::default().recent_bias,
+            genre_slot_patterns,
+            length_slot_patterns,
+            utc_offset_minutes,
+        })
+    }
+}
+
+/// Temporal pattern storage and the playback history they are learned from
+#[async_trait::async_trait]
+pub trait TemporalPatternRepository: Send + Sync {
+    /// A user's stored patterns
+    async fn load(&self, user_id: Uuid) -> Result<Option<TemporalContext>>;
+
+    /// Store patterns learned from `session_count` sessions
+    async fn save(
+        &self,
+        user_id: Uuid,
+        patterns: &TemporalContext,
+        session_count: usize,
+    ) -> Result<()>;
+
+    /// A user's sessions started since `since`, newest first
+    async fn playback_sessions(
+        &self,
+        user_id: Uuid,
+        since: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<PlaybackSession>>;
+
+    /// Users with playback activity since `since`
+    async fn active_users(&self, since: DateTime<Utc>) -> Result<Vec<Uuid>>;
+
+    /// Set the local time a user's patterns are applied and relearned in
+    async fn set_utc_offset(&self, user_id: Uuid, utc_offset_minutes: i32) -> Result<()>;
+}
+
+/// Largest offset of a local time from UTC, in minutes (UTC+14:00)
+pub const MAX_UTC_OFFSET_MINUTES: i32 = 14 * 60;
+
+/// Whether `utc_offset_minutes` is a real-world offset (UTC-12:00 to UTC+14:00)
+pub fn is_valid_utc_offset(utc_offset_minutes: i32) -> bool {
+    (-12 * 60..=MAX_UTC_OFFSET_MINUTES).contains(&utc_offset_minutes)
+}
+
+/// PostgreSQL implementation of TemporalPatternRepository
+pub struct PostgresTemporalPatternRepository {
+    pool: PgPool,
+}
+
+impl PostgresTemporalPatternRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl TemporalPatternRepository for PostgresTemporalPatternRepository {
+    async fn load(&self, user_id: Uuid) -> Result<Option<TemporalContext>> {
+        let row = sqlx::query(
+            r#"
+            SELECT patterns, utc_offset_minutes
+            FROM user_temporal_patterns
+            WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to load temporal patterns")?;
+
+        Ok(row.map(|row| {
+            let Json(mut patterns): Json<TemporalContext> = row.get("patterns");
+            patterns.utc_offset_minutes = row.get("utc_offset_minutes");
+            patterns
+        }))
+    }
+
+    #[instrument(skip(self, patterns))]
+    async fn save(
+        &self,
+        user_id: Uuid,
+        patterns: &TemporalContext,
+        session_count: usize,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO user_temporal_patterns
+                (user_id, patterns, utc_offset_minutes, session_count, learned_at)
+            VALUES ($1, $2, $3, $4, NOW())
+            ON CONFLICT (user_id) DO UPDATE
+            SET patterns = EXCLUDED.patterns,
+                session_count = EXCLUDED.session_count,
+                learned_at = EXCLUDED.learned_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(Json(patterns))
+        .bind(patterns.utc_offset_minutes)
+        .bind(session_count as i32)
+        .execute(&self.pool)
+        .await
+        .context("Failed to save temporal patterns")?;
+
+        Ok(())
+    }
+
+    async fn playback_sessions(
+        &self,
+        user_id: Uuid,
+        since: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<PlaybackSession>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT ps.content_id,
+                   ps.created_at,
+                   ps.position_seconds,
+                   COALESCE(c.runtime_minutes, ps.duration_seconds / 60.0)::REAL AS runtime_minutes,
+                   COALESCE(
+                       ARRAY_AGG(cg.genre) FILTER (WHERE cg.genre IS NOT NULL),
+                       '{}'
+                   ) AS genres
+            FROM playback_sessions ps
+            LEFT JOIN content c ON c.id = ps.content_id
+            LEFT JOIN content_genres cg ON cg.content_id = ps.content_id
+            WHERE ps.user_id = $1 AND ps.created_at >= $2
+            GROUP BY ps.id, ps.content_id, ps.created_at, ps.position_seconds,
+                     ps.duration_seconds, c.runtime_minutes
+            ORDER BY ps.created_at DESC
+            LIMIT $3
+            "#,
+        )
+        .bind(user_id)
+        .bind(since)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to load playback sessions")?;
+
+        Ok(rows
+            .iter()
+            .map(|row| PlaybackSession {
+                content_id: row.get("content_id"),
+                started_at: row.get("created_at"),
+                watched_minutes: row.get::<i32, _>("position_seconds") as f32 / 60.0,
+                // Unknown for titles missing from the catalog without a
+                // playback duration; such sessions only count toward dayparts
+                runtime_minutes: row.get::<Option<f32>, _>("runtime_minutes"),
+                genres: row.get("genres"),
+            })
+            .collect())
+    }
+
+    async fn active_users(&self, since: DateTime<Utc>) -> Result<Vec<Uuid>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT DISTINCT user_id
+            FROM playback_sessions
+            WHERE updated_at >= $1
+            "#,
+        )
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to list users with recent playback")?;
+
+        Ok(rows.iter().map(|row| row.get("user_id")).collect())
+    }
+
+    #[instrument(skip(self))]
+    async fn set_utc_offset(&self, user_id: Uuid, utc_offset_minutes: i32) -> Result<()> {
+        // Users without learned patterns start from neutral ones
+        let neutral = TemporalContext {
+            utc_offset_minutes,
+            ..Default::default()
+        };
+        sqlx::query(
+            r#"
+            INSERT INTO user_temporal_patterns (user_id, patterns, utc_offset_minutes, session_count)
+            VALUES ($1, $2, $3, 0)
+            ON CONFLICT (user_id) DO UPDATE
+            SET utc_offset_minutes = EXCLUDED.utc_offset_minutes
+            "#,
+        )
+        .bind(user_id)
+        .bind(Json(&neutral))
+        .bind(utc_offset_minutes)
+        .execute(&self.pool)
+        .await
+        .context("Failed to set UTC offset")?;
+
+        Ok(())
+    }
+}
+
+/// Outcome of one pattern learning pass
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TemporalJobReport {
+    pub users: usize,
+    pub updated: usize,
+    /// Users with too little history to learn from
+    pub skipped: usize,
+    pub failed: usize,
+}
+
+/// Relearns temporal patterns for users with new playback
+pub struct TemporalPatternJob {
+    repository: Arc<dyn TemporalPatternRepository>,
+    config: TemporalPatternConfig,
+}
+
+impl TemporalPatternJob {
+    pub fn new(
+        repository: Arc<dyn TemporalPatternRepository>,
+        config: TemporalPatternConfig,
+    ) -> Self {
+        Self { repository, config }
+    }
+
+    /// Relearn and store one user's patterns; `None` when their history is
+    /// too thin, in which case stored patterns are left as they are
+    ///
+    /// Patterns keep the UTC offset they were stored with.
+    pub async fn refresh_user(
+        &self,
+        user_id: Uuid,
+        now: DateTime<Utc>,
+    ) -> Result<Option<TemporalContext>> {
+        let utc_offset_minutes = self
+            .repository
+            .load(user_id)
+            .await?
+            .map(|p| p.utc_offset_minutes)
+            .unwrap_or(0);
+        let since = now - Duration::days(self.config.history_days);
+        let sessions = self
+            .repository
+            .playback_sessions(user_id, since, self.config.max_sessions)
+            .await?;
+
+        let Some(patterns) =
+            LearnTemporalPatterns::execute(&sessions, utc_offset_minutes, &self.config, now)
+        else {
+            debug!(
+                "Not enough playback to learn temporal patterns for user {} ({} sessions)",
+                user_id,
+                sessions.len()
+            );
+            return Ok(None);
+        };
+
+        self.repository
+            .save(user_id, &patterns, sessions.len())
+            .await?;
+        Ok(Some(patterns))
+    }
+
+    /// Relearn patterns for every user with playback since `since`
+    pub async fn run(&self, since: DateTime<Utc>, now: DateTime<Utc>) -> Result<TemporalJobReport> {
+        let users = self.repository.active_users(since).await?;
+        let mut report = TemporalJobReport {
+            users: users.len(),
+            ..Default::default()
+        };
+
+        for user_id in users {
+            match self.refresh_user(user_id, now).await {
+                Ok(Some(_)) => report.updated += 1,
+                Ok(None) => report.skipped += 1,
+                Err(e) => {
+                    warn!(
+                        "Temporal pattern learning failed for user {}: {}",
+                        user_id, e
+                    );
+                    report.failed += 1;
+                }
+            }
+        }
+
+        info!(
+            "Temporal patterns: {} users, {} updated, {} skipped, {} failed",
+            report.users, report.updated, report.skipped, report.failed
+        );
+        Ok(report)
+    }
+
+    /// Run every `interval`, each pass covering playback since the previous
+    /// one; the first pass covers the whole history window
+    pub fn spawn(self: Arc<Self>, interval: std::time::Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut since = Utc::now() - Duration::days(self.config.history_days);
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let now = Utc::now();
+                match self.run(since, now).await {
+                    Ok(_) => since = now,
+                    Err(e) => warn!("Temporal pattern pass failed: {}", e),
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn session(started_at: DateTime<Utc>, runtime: f32, genre: &str) -> PlaybackSession {
+        PlaybackSession {
+            content_id: Uuid::new_v4(),
+            started_at,
+            watched_minutes: runtime,
+            runtime_minutes: Some(runtime),
+            genres: vec![genre.to_string()],
+        }
+    }
+
+    /// Short comedies on weekday evenings, long dramas on weekend afternoons
+    fn history(now: DateTime<Utc>) -> Vec<PlaybackSession> {
+        let mut sessions = Vec::new();
+        for week in 0..6 {
+            // 2024-03-04 is a Monday
+            let monday = Utc.with_ymd_and_hms(2024, 3, 4, 0, 0, 0).unwrap() + Duration::weeks(week);
+            for day in 0..4 {
+                let evening = monday + Duration::days(day) + Duration::hours(20);
+                sessions.push(session(evening, 25.0, "Comedy"));
+            }
+            let saturday = monday + Duration::days(5) + Duration::hours(14);
+            sessions.push(session(saturday, 130.0, "Drama"));
+            sessions.push(session(saturday + Duration::days(1), 120.0, "Drama"));
+        }
+        sessions.retain(|s| s.started_at <= now);
+        sessions
+    }
+
+    #[test]
+    fn test_viewing_slots() {
+        let at = |d, h| {
+            ViewingSlot::at(
+                Utc.with_ymd_and_hms(2024, 3, d, h, 0, 0)
+                    .unwrap()
+                    .naive_utc(),
+            )
+        };
+        // Friday 20:00 is a weekday evening
+        assert_eq!(at(8, 20).index(), 2);
+        // Saturday 01:00 is still Friday night
+        assert_eq!(at(9, 1).index(), 3);
+        // Saturday 14:00 is a weekend afternoon
+        assert_eq!(at(9, 14).index(), 5);
+        // Monday 01:00 follows Sunday
+        assert_eq!(at(11, 1).index(), 7);
+        assert_eq!(season_index(12), 0);
+        assert_eq!(season_index(7), 2);
+    }
+
+    #[test]
+    fn test_learns_genre_and_length_by_slot() {
+        let now = Utc.with_ymd_and_hms(2024, 4, 15, 0, 0, 0).unwrap();
+        let config = TemporalPatternConfig::default();
+        let patterns = LearnTemporalPatterns::execute(&history(now), 0, &config, now).unwrap();
+
+        let weekday_evening = 2;
+        let weekend_afternoon = 5;
+        let comedy = &patterns.genre_slot_patterns["comedy"];
+        let drama = &patterns.genre_slot_patterns["drama"];
+        assert!(comedy[weekday_evening] > 0.5);
+        assert!(comedy[weekend_afternoon] < 0.5);
+        assert!(drama[weekend_afternoon] > 0.5);
+        assert!(drama[weekday_evening] < 0.5);
+
+        let short = &patterns.length_slot_patterns[LengthBucket::Short.index()];
+        let long = &patterns.length_slot_patterns[LengthBucket::Long.index()];
+        assert!(short[weekday_evening] > long[weekday_evening]);
+        assert!(long[weekend_afternoon] > short[weekend_afternoon]);
+
+        // Evenings beat the small hours; Saturday beats Friday
+        assert!(patterns.hourly_patterns[20] > 0.5);
+        assert!(patterns.hourly_patterns[4] < 0.5);
+        assert!(patterns.weekday_patterns[5] > patterns.weekday_patterns[4]);
+        // Unobserved slots stay neutral
+        assert!((comedy[0] - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_local_time_offset() {
+        // 20:00 in UTC-5 is 01:00 UTC the next day
+        let now = Utc.with_ymd_and_hms(2024, 4, 15, 0, 0, 0).unwrap();
+        let shifted: Vec<_> = history(now)
+            .into_iter()
+            .map(|mut s| {
+                s.started_at += Duration::hours(5);
+                s
+            })
+            .collect();
+        let config = TemporalPatternConfig::default();
+        let patterns = LearnTemporalPatterns::execute(&shifted, -300, &config, now).unwrap();
+
+        assert!(patterns.hourly_patterns[20] > 0.5);
+        assert!(patterns.genre_slot_patterns["comedy"][2] > 0.5);
+        let slot = patterns.slot_at(Utc.with_ymd_and_hms(2024, 4, 16, 1, 0, 0).unwrap());
+        assert_eq!(slot.index(), 2);
+
+        assert!(is_valid_utc_offset(-300));
+        assert!(is_valid_utc_offset(MAX_UTC_OFFSET_MINUTES));
+        assert!(!is_valid_utc_offset(15 * 60));
+        assert!(!is_valid_utc_offset(-13 * 60));
+    }
+
+    #[test]
+    fn test_requires_minimum_history() {
+        let now = Utc.with_ymd_and_hms(2024, 4, 15, 0, 0, 0).unwrap();
+        let config = TemporalPatternConfig::default();
+        let mut sessions: Vec<_> = history(now).into_iter().take(9).collect();
+        assert!(LearnTemporalPatterns::execute(&sessions, 0, &config, now).is_none());
+
+        // Sessions that barely started don't count
+        sessions.push(PlaybackSession {
+            watched_minutes: 0.5,
+            ..sessions[0].clone()
+        });
+        assert!(LearnTemporalPatterns::execute(&sessions, 0, &config, now).is_none());
+    }
+}
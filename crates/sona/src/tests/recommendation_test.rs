@@ -87,6 +87,8 @@ fn test_explanation_generation_content_based() {
         generated_at: Utc::now(),
         ttl_seconds: 3600,
         experiment_variant: None,
+        sources: vec![],
+        reasons: vec![],
     };
 
     assert_eq!(
@@ -108,6 +110,8 @@ fn test_explanation_generation_collaborative() {
         generated_at: Utc::now(),
         ttl_seconds: 3600,
         experiment_variant: None,
+        sources: vec![],
+        reasons: vec![],
     };
 
     assert_eq!(
@@ -128,6 +132,8 @@ fn test_recommendation_confidence_score_range() {
         generated_at: Utc::now(),
         ttl_seconds: 3600,
         experiment_variant: None,
+        sources: vec![],
+        reasons: vec![],
     };
 
     assert!(high_confidence.confidence_score >= 0.0);
@@ -146,6 +152,8 @@ fn test_recommendation_ttl() {
         generated_at: Utc::now(),
         ttl_seconds: 1800, // 30 minutes
         experiment_variant: None,
+        sources: vec![],
+        reasons: vec![],
     };
 
     assert_eq!(recommendation.ttl_seconds, 1800);
@@ -193,6 +201,8 @@ fn test_hybrid_recommendation_combines_sources() {
         generated_at: Utc::now(),
         ttl_seconds: 3600,
         experiment_variant: None,
+        sources: vec![],
+        reasons: vec![],
     };
 
     assert_eq!(hybrid.recommendation_type, RecommendationType::Hybrid);
@@ -213,6 +223,8 @@ fn test_context_aware_recommendation() {
         generated_at: Utc::now(),
         ttl_seconds: 600, // 10 minutes (context changes quickly)
         experiment_variant: None,
+        sources: vec![],
+        reasons: vec![],
     };
 
     assert_eq!(
@@ -234,6 +246,8 @@ fn test_recommendation_ranking_by_confidence() {
             generated_at: Utc::now(),
             ttl_seconds: 3600,
             experiment_variant: None,
+            sources: vec![],
+            reasons: vec![],
         },
         Recommendation {
             content_id: Uuid::new_v4(),
@@ -244,6 +258,8 @@ fn test_recommendation_ranking_by_confidence() {
             generated_at: Utc::now(),
             ttl_seconds: 3600,
             experiment_variant: None,
+            sources: vec![],
+            reasons: vec![],
         },
         Recommendation {
             content_id: Uuid::new_v4(),
@@ -254,6 +270,8 @@ fn test_recommendation_ranking_by_confidence() {
             generated_at: Utc::now(),
             ttl_seconds: 3600,
             experiment_variant: None,
+            sources: vec![],
+            reasons: vec![],
         },
     ];
 
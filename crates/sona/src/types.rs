@@ -1,7 +1,12 @@
 //! Core types for SONA personalization engine
 
+use crate::diversity::DiversityConfig;
+use crate::explanation::RankedReason;
+use crate::rerank::RerankConfig;
 use chrono::{DateTime, Utc};
+use media_gateway_core::types::MaturityRating;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use uuid::Uuid;
 
 /// User viewing event for building preference profiles
@@ -16,12 +21,45 @@ pub struct ViewingEvent {
 }
 
 /// Recommendation context (time, device, mood)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RecommendationContext {
     pub mood: Option<String>,
     pub time_of_day: Option<String>,
     pub device_type: Option<DeviceType>,
     pub viewing_with: Option<Vec<String>>,
+    /// Per-request diversification; the pipeline default applies when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diversity: Option<DiversityConfig>,
+    /// Final re-ranking objective, resolved from the tenant and experiment
+    /// variant; re-ranking is skipped when unset and no parental limit applies
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rerank: Option<RerankConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subscribed_platforms: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_maturity_rating: Option<MaturityRating>,
+    /// Locale explanations are rendered in (default: "en")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    /// ISO 3166-1 alpha-2 region, for "popular in your region" explanations
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    /// The client's offset from UTC; temporal patterns are learned and
+    /// applied in this local time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub utc_offset_minutes: Option<i32>,
+}
+
+impl RecommendationContext {
+    /// Co-viewers in `viewing_with` that identify users; free-form labels
+    /// such as "kids" are skipped
+    pub fn viewing_with_user_ids(&self) -> Vec<Uuid> {
+        self.viewing_with
+            .iter()
+            .flatten()
+            .filter_map(|v| Uuid::parse_str(v).ok())
+            .collect()
+    }
 }
 
 /// Device type enumeration
@@ -47,16 +85,34 @@ pub struct Recommendation {
     /// A/B test experiment variant (if user is in an active experiment)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub experiment_variant: Option<String>,
+    /// Candidate sources that proposed this content
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<SourceAttribution>,
+    /// Structured reasons, strongest first; `explanation` renders the first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reasons: Vec<RankedReason>,
 }
 
-/// Recommendation type
+/// A candidate source's contribution to a recommendation
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceAttribution {
+    pub source: RecommendationType,
+    /// Weighted score the source contributed
+    pub score: f32,
+    /// 1-based position in the source's own ranking
+    pub rank: usize,
+}
+
+/// Recommendation type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum RecommendationType {
     Collaborative,
     ContentBased,
     GraphBased,
     ContextAware,
+    SessionBased,
+    Trending,
     Hybrid,
 }
 
@@ -70,12 +126,25 @@ pub struct ScoredContent {
 }
 
 /// Temporal context patterns
+///
+/// Pattern values are preferences in [0, 1] where 0.5 is neutral; they are
+/// learned from playback history by `LearnTemporalPatterns`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TemporalContext {
-    pub hourly_patterns: Vec<f32>,   // 24 elements
-    pub weekday_patterns: Vec<f32>,  // 7 elements
-    pub seasonal_patterns: Vec<f32>, // 4 elements
+    pub hourly_patterns: Vec<f32>,   // 24 elements, local hour
+    pub weekday_patterns: Vec<f32>,  // 7 elements, Monday first
+    pub seasonal_patterns: Vec<f32>, // 4 elements, winter first
     pub recent_bias: f32,
+    /// Per-genre preference in each viewing slot, indexed by `ViewingSlot::index`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub genre_slot_patterns: HashMap<String, Vec<f32>>,
+    /// Per-length preference in each viewing slot, indexed by
+    /// `LengthBucket::index` then `ViewingSlot::index`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub length_slot_patterns: Vec<Vec<f32>>,
+    /// Offset of the user's local time from UTC the patterns are expressed in
+    #[serde(default)]
+    pub utc_offset_minutes: i32,
 }
 
 impl Default for TemporalContext {
@@ -85,8 +154,43 @@ impl Default for TemporalContext {
             weekday_patterns: vec![0.5; 7],
             seasonal_patterns: vec![0.5; 4],
             recent_bias: 0.8,
+            genre_slot_patterns: HashMap::new(),
+            length_slot_patterns: Vec::new(),
+            utc_offset_minutes: 0,
+        }
+    }
+}
+
+/// How a mood state was obtained
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MoodSource {
+    /// The user picked a mood
+    #[default]
+    Explicit,
+    /// Inferred from in-session behavior by `InferMood`
+    Inferred,
+}
+
+impl MoodSource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MoodSource::Explicit => "explicit",
+            MoodSource::Inferred => "inferred",
         }
     }
+
+    pub fn parse(source: &str) -> Option<Self> {
+        match source {
+            "explicit" => Some(MoodSource::Explicit),
+            "inferred" => Some(MoodSource::Inferred),
+            _ => None,
+        }
+    }
+}
+
+fn full_confidence() -> f32 {
+    1.0
 }
 
 /// Mood state vector
@@ -95,6 +199,11 @@ pub struct MoodState {
     pub timestamp: DateTime<Utc>,
     pub mood_vector: Vec<f32>, // [calm, energetic, happy, sad, focused, relaxed, social, introspective]
     pub context_tags: Vec<String>,
+    #[serde(default)]
+    pub source: MoodSource,
+    /// Certainty in [0, 1]; explicit picks are fully certain
+    #[serde(default = "full_confidence")]
+    pub confidence: f32,
 }
 
 impl Default for MoodState {
@@ -103,6 +212,8 @@ impl Default for MoodState {
             timestamp: Utc::now(),
             mood_vector: vec![0.5; 8],
             context_tags: vec![],
+            source: MoodSource::Explicit,
+            confidence: 1.0,
         }
     }
 }
@@ -263,6 +263,7 @@ fn test_matrix_factorization_basic() -> Result<()> {
         regularization: 0.1,
         iterations: 10,
         alpha: 40.0,
+        ..Default::default()
     });
 
     let user1 = Uuid::new_v4();
@@ -305,6 +306,7 @@ fn test_als_convergence() -> Result<()> {
         regularization: 0.05,
         iterations: 20,
         alpha: 40.0,
+        ..Default::default()
     });
 
     let user1 = Uuid::new_v4();
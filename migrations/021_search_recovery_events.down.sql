@@ -0,0 +1,3 @@
+-- Rollback search recovery events migration
+
+DROP TABLE IF EXISTS search_recovery_events;
@@ -0,0 +1,18 @@
+-- Zero-result recovery attempts
+-- One row per recovery strategy tried, so analytics can attribute which step recovered a query
+
+CREATE TABLE IF NOT EXISTS search_recovery_events (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    query_hash VARCHAR(64) NOT NULL,
+    query_text VARCHAR(500) NOT NULL,
+    attempted_query VARCHAR(500) NOT NULL,
+    user_id_hash VARCHAR(64),
+    strategy VARCHAR(32) NOT NULL,
+    result_count INTEGER NOT NULL,
+    succeeded BOOLEAN NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_search_recovery_time ON search_recovery_events(created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_search_recovery_strategy ON search_recovery_events(strategy, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_search_recovery_query_hash ON search_recovery_events(query_hash);
@@ -0,0 +1,4 @@
+-- Rollback quality remediation migration
+
+DROP TABLE IF EXISTS quality_score_snapshots;
+DROP TABLE IF EXISTS quality_remediation;
@@ -0,0 +1,35 @@
+-- Content quality remediation workflow
+-- Tracks per-content enrichment state and periodic quality score distribution snapshots
+
+CREATE TABLE IF NOT EXISTS quality_remediation (
+    content_id UUID PRIMARY KEY REFERENCES content(id) ON DELETE CASCADE,
+    title VARCHAR(500) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'queued',
+    missing_fields TEXT[] NOT NULL DEFAULT '{}',
+    quality_score_before REAL NOT NULL,
+    quality_score_after REAL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    sources TEXT[] NOT NULL DEFAULT '{}',
+    filled_fields TEXT[] NOT NULL DEFAULT '{}',
+    last_error TEXT,
+    snapshot JSONB NOT NULL,
+    queued_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT quality_remediation_status_check CHECK (
+        status IN ('queued', 'in_progress', 'enriched', 'unresolved', 'dismissed')
+    )
+);
+
+CREATE INDEX IF NOT EXISTS idx_quality_remediation_status ON quality_remediation(status, queued_at);
+
+CREATE TABLE IF NOT EXISTS quality_score_snapshots (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    captured_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    total_content BIGINT NOT NULL,
+    average_score REAL NOT NULL,
+    threshold REAL NOT NULL,
+    low_quality_count BIGINT NOT NULL,
+    distribution JSONB NOT NULL DEFAULT '[]'
+);
+
+CREATE INDEX IF NOT EXISTS idx_quality_score_snapshots_time ON quality_score_snapshots(captured_at DESC);
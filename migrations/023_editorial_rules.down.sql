@@ -0,0 +1,3 @@
+-- Rollback editorial rules migration
+
+DROP TABLE IF EXISTS editorial_rules;
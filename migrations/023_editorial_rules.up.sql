@@ -0,0 +1,28 @@
+-- Editorial search rules
+-- Pin, boost, bury or hide content for matching queries, optionally scoped by region and time window
+
+CREATE TABLE IF NOT EXISTS editorial_rules (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(200) NOT NULL,
+    action VARCHAR(10) NOT NULL,
+    boost_factor REAL,
+    content_ids UUID[] NOT NULL,
+    match_type VARCHAR(10) NOT NULL DEFAULT 'any',
+    queries TEXT[] NOT NULL DEFAULT '{}',
+    intent_terms TEXT[] NOT NULL DEFAULT '{}',
+    regions TEXT[] NOT NULL DEFAULT '{}',
+    starts_at TIMESTAMPTZ,
+    ends_at TIMESTAMPTZ,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    priority INTEGER NOT NULL DEFAULT 0,
+    reason TEXT,
+    created_by UUID,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT editorial_rules_action_check CHECK (action IN ('pin', 'boost', 'bury', 'hide')),
+    CONSTRAINT editorial_rules_match_type_check CHECK (match_type IN ('any', 'exact', 'phrase')),
+    CONSTRAINT editorial_rules_boost_check CHECK (action <> 'boost' OR boost_factor > 0),
+    CONSTRAINT editorial_rules_window_check CHECK (ends_at IS NULL OR starts_at IS NULL OR ends_at > starts_at)
+);
+
+CREATE INDEX IF NOT EXISTS idx_editorial_rules_live ON editorial_rules(enabled, ends_at);
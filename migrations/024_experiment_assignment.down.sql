@@ -0,0 +1,4 @@
+-- Rollback experiment assignment migration
+
+DROP INDEX IF EXISTS idx_experiments_assignment_layer;
+ALTER TABLE experiments DROP COLUMN IF EXISTS assignment;
@@ -0,0 +1,10 @@
+-- Experiment assignment settings
+-- Salt, layer bucket range, ramp-up, holdout and targeting used by stable hash assignment
+
+ALTER TABLE experiments
+    ADD COLUMN IF NOT EXISTS assignment JSONB NOT NULL DEFAULT '{}';
+
+-- Layer lookups when checking for overlapping bucket ranges
+CREATE INDEX IF NOT EXISTS idx_experiments_assignment_layer
+    ON experiments ((assignment->'layer'->>'name'))
+    WHERE assignment ? 'layer';
@@ -0,0 +1,4 @@
+-- Rollback experiment bandits migration
+
+DROP INDEX IF EXISTS idx_exposures_experiment_user_time;
+DROP TABLE IF EXISTS experiment_bandits;
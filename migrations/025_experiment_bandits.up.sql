@@ -0,0 +1,16 @@
+-- Multi-armed bandit experiments
+-- Bandit settings and the latest posterior and traffic allocation per arm
+
+CREATE TABLE IF NOT EXISTS experiment_bandits (
+    experiment_id UUID PRIMARY KEY REFERENCES experiments(id) ON DELETE CASCADE,
+    config JSONB NOT NULL,
+    arms JSONB NOT NULL DEFAULT '[]',
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE experiment_bandits IS 'Bandit policy and allocation state; replaces variant weights while present';
+COMMENT ON COLUMN experiment_bandits.arms IS 'Per-variant Beta posterior, observed counts and current allocation';
+
+-- First-exposure lookups for bandit replay
+CREATE INDEX IF NOT EXISTS idx_exposures_experiment_user_time
+    ON experiment_exposures(experiment_id, user_id, exposed_at);
@@ -0,0 +1,6 @@
+-- Rollback LoRA adapter rollout migration
+
+DROP TABLE IF EXISTS lora_adapter_rollouts;
+ALTER TABLE lora_adapters
+    DROP COLUMN IF EXISTS validation_metrics,
+    DROP COLUMN IF EXISTS status;
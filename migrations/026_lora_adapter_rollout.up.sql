@@ -0,0 +1,22 @@
+-- LoRA adapter rollout
+-- Candidate adapter versions are validated on held-out interactions before
+-- serving; the rollout pointer names the active version and the one it
+-- replaced so a regression can be rolled back
+
+ALTER TABLE lora_adapters
+    ADD COLUMN IF NOT EXISTS status VARCHAR(20) NOT NULL DEFAULT 'active',
+    ADD COLUMN IF NOT EXISTS validation_metrics JSONB;
+
+COMMENT ON COLUMN lora_adapters.status IS 'candidate, active, rejected or rolled_back';
+COMMENT ON COLUMN lora_adapters.validation_metrics IS 'Held-out loss/AUC of the version and of the adapter it was compared against';
+
+CREATE TABLE IF NOT EXISTS lora_adapter_rollouts (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    adapter_name VARCHAR(100) NOT NULL,
+    active_version INTEGER NOT NULL,
+    previous_version INTEGER,
+    activated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, adapter_name)
+);
+
+COMMENT ON TABLE lora_adapter_rollouts IS 'Serving pointer per adapter; absent rows fall back to the latest active version';
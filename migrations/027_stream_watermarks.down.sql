@@ -0,0 +1,3 @@
+-- Rollback streaming ingestion watermarks migration
+
+DROP TABLE IF EXISTS stream_watermarks;
@@ -0,0 +1,11 @@
+-- Streaming ingestion watermarks
+-- Each consumer of the user activity stream records the timestamp of the
+-- latest event it has applied so a restart resumes without refolding events
+
+CREATE TABLE IF NOT EXISTS stream_watermarks (
+    consumer VARCHAR(100) PRIMARY KEY,
+    watermark TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE stream_watermarks IS 'Latest applied activity event timestamp per stream consumer';
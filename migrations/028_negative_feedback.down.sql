@@ -0,0 +1,3 @@
+-- Rollback negative feedback migration
+
+DROP TABLE IF EXISTS negative_feedback;
@@ -0,0 +1,22 @@
+-- Negative feedback
+-- Explicit "not interested" signals on a title, genre or person, and
+-- implicit negatives derived from viewing (dismissals, early abandons,
+-- low ratings). Signals decay with age; undoing one marks it revoked so the
+-- history stays auditable
+
+CREATE TABLE IF NOT EXISTS negative_feedback (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    target_kind VARCHAR(20) NOT NULL,
+    target_value VARCHAR(255) NOT NULL,
+    source VARCHAR(20) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    revoked_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_negative_feedback_active
+    ON negative_feedback (user_id, created_at DESC)
+    WHERE revoked_at IS NULL;
+
+COMMENT ON COLUMN negative_feedback.target_kind IS 'content, genre or person';
+COMMENT ON COLUMN negative_feedback.source IS 'not_interested, dismissed, abandoned or disliked';
@@ -0,0 +1,3 @@
+-- Rollback recommendation explanations migration
+
+DROP TABLE IF EXISTS recommendation_explanations;
@@ -0,0 +1,17 @@
+-- Recommendation explanations
+-- Structured reasons of the latest recommendation of each item served to a
+-- user, kept so the user can ask why it was recommended
+
+CREATE TABLE IF NOT EXISTS recommendation_explanations (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    content_id UUID NOT NULL,
+    reasons JSONB NOT NULL DEFAULT '[]',
+    sources JSONB NOT NULL DEFAULT '[]',
+    generated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, content_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_recommendation_explanations_generated
+    ON recommendation_explanations (generated_at);
+
+COMMENT ON COLUMN recommendation_explanations.reasons IS 'Typed reasons with strengths, strongest first; rendered per locale on read';
@@ -0,0 +1,3 @@
+-- Rollback embedding migrations migration
+
+DROP TABLE IF EXISTS embedding_migrations;
@@ -0,0 +1,34 @@
+-- Embedding dimension migrations
+-- State of re-embedding jobs that move the content vectors behind a Qdrant
+-- alias to a new collection at another dimension
+
+CREATE TABLE IF NOT EXISTS embedding_migrations (
+    id UUID PRIMARY KEY,
+    alias VARCHAR(255) NOT NULL,
+    source_collection VARCHAR(255),
+    target_collection VARCHAR(255) NOT NULL UNIQUE,
+    target_dimension BIGINT NOT NULL CHECK (target_dimension > 0),
+    status VARCHAR(20) NOT NULL CHECK (status IN (
+        'backfilling', 'verifying', 'verified', 'switched',
+        'completed', 'failed', 'rolled_back'
+    )),
+    cursor UUID,
+    processed BIGINT NOT NULL DEFAULT 0,
+    failed BIGINT NOT NULL DEFAULT 0,
+    sample_ids UUID[] NOT NULL DEFAULT '{}',
+    coverage REAL,
+    recall REAL,
+    error TEXT,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    switched_at TIMESTAMPTZ,
+    completed_at TIMESTAMPTZ
+);
+
+-- At most one migration may own an alias at a time
+CREATE UNIQUE INDEX IF NOT EXISTS idx_embedding_migrations_active_alias
+    ON embedding_migrations (alias)
+    WHERE status NOT IN ('completed', 'failed', 'rolled_back');
+
+COMMENT ON COLUMN embedding_migrations.cursor IS 'Last content id backfilled; the backfill resumes after it';
+COMMENT ON COLUMN embedding_migrations.sample_ids IS 'Content ids whose nearest neighbours are compared between collections before switching';
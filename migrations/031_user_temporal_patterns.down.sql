@@ -0,0 +1,3 @@
+-- Rollback user temporal patterns migration
+
+DROP TABLE IF EXISTS user_temporal_patterns;
@@ -0,0 +1,16 @@
+-- User temporal patterns
+-- Hourly, weekday and seasonal viewing patterns plus per-genre and
+-- per-length preferences by viewing slot, learned from playback history and
+-- applied by context-aware scoring. Patterns are expressed in the user's
+-- local time at utc_offset_minutes, which relearning preserves
+
+CREATE TABLE IF NOT EXISTS user_temporal_patterns (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    patterns JSONB NOT NULL,
+    utc_offset_minutes INTEGER NOT NULL DEFAULT 0,
+    session_count INTEGER NOT NULL DEFAULT 0,
+    learned_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON COLUMN user_temporal_patterns.patterns IS 'Serialized TemporalContext';
+COMMENT ON COLUMN user_temporal_patterns.session_count IS 'Playback sessions the patterns were learned from';
@@ -0,0 +1,9 @@
+-- Rollback mood inference migration
+
+DROP TABLE IF EXISTS user_mood_observations;
+
+DELETE FROM content_moods WHERE source = 'inferred';
+
+ALTER TABLE content_moods
+    DROP COLUMN IF EXISTS score,
+    DROP COLUMN IF EXISTS source;
@@ -0,0 +1,28 @@
+-- Mood inference
+-- Explicit mood picks and inferred moods per user. Explicit picks keep the
+-- vector inference produced at the time, so later inferences can be
+-- calibrated toward the user's own labels. Content mood tags can now also be
+-- derived from embeddings; those rows are marked 'inferred' with a score
+
+ALTER TABLE content_moods
+    ADD COLUMN IF NOT EXISTS source VARCHAR(20) NOT NULL DEFAULT 'catalog',
+    ADD COLUMN IF NOT EXISTS score REAL;
+
+CREATE TABLE IF NOT EXISTS user_mood_observations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    source VARCHAR(20) NOT NULL,
+    mood_vector REAL[] NOT NULL,
+    inferred_vector REAL[],
+    confidence REAL NOT NULL DEFAULT 1.0,
+    context_tags TEXT[] NOT NULL DEFAULT '{}',
+    recorded_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_mood_observations_user
+    ON user_mood_observations (user_id, recorded_at DESC);
+
+COMMENT ON COLUMN content_moods.source IS 'catalog or inferred (from embeddings)';
+COMMENT ON COLUMN user_mood_observations.source IS 'explicit or inferred';
+COMMENT ON COLUMN user_mood_observations.mood_vector IS '[calm, energetic, happy, sad, focused, relaxed, social, introspective]';
+COMMENT ON COLUMN user_mood_observations.inferred_vector IS 'Inferred mood when the user picked explicitly';
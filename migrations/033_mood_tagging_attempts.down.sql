@@ -0,0 +1,5 @@
+-- Rollback mood tagging attempts migration
+
+DROP INDEX IF EXISTS idx_user_mood_observations_recorded;
+
+DROP TABLE IF EXISTS content_mood_tagging_attempts;
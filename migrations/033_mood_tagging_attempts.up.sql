@@ -0,0 +1,17 @@
+-- Mood tagging attempts
+-- Titles the embedding mood tagger has looked at, tagged or not, so each
+-- pass moves on to new titles instead of re-selecting the ones it could not
+-- tag. Attempts are retried once they are older than the tagging retry
+-- interval, when embeddings or catalog tags may have changed. Also indexes
+-- mood observations by age for retention pruning
+
+CREATE TABLE IF NOT EXISTS content_mood_tagging_attempts (
+    content_id UUID PRIMARY KEY REFERENCES content(id) ON DELETE CASCADE,
+    attempted_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_content_mood_tagging_attempts_at
+    ON content_mood_tagging_attempts (attempted_at);
+
+CREATE INDEX IF NOT EXISTS idx_user_mood_observations_recorded
+    ON user_mood_observations (recorded_at);
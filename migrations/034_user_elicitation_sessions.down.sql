@@ -0,0 +1,3 @@
+-- Rollback onboarding preference elicitation migration
+
+DROP TABLE IF EXISTS user_elicitation_sessions;
@@ -0,0 +1,12 @@
+-- Onboarding preference elicitation
+-- One in-progress or finished onboarding session per user: the latent-factor
+-- posterior, the titles presented and the answers given. Sessions are kept
+-- after completion so elicited preferences stand in for viewing history
+-- until the user has watched enough
+
+CREATE TABLE IF NOT EXISTS user_elicitation_sessions (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    session JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
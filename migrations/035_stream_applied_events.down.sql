@@ -0,0 +1,3 @@
+-- Rollback applied stream events migration
+
+DROP TABLE IF EXISTS stream_applied_events;
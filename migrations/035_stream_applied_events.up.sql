@@ -0,0 +1,16 @@
+-- Applied stream events
+-- Ids of activity events a stream consumer has applied within its lateness
+-- window, so late events behind the watermark are still applied once and
+-- replays after a restart are not refolded
+
+CREATE TABLE IF NOT EXISTS stream_applied_events (
+    consumer VARCHAR(100) NOT NULL,
+    event_id UUID NOT NULL,
+    event_time TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (consumer, event_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_stream_applied_events_time
+    ON stream_applied_events(consumer, event_time);
+
+COMMENT ON TABLE stream_applied_events IS 'Activity events applied by a stream consumer within its lateness window';
@@ -0,0 +1,4 @@
+-- Rollback content franchise migration
+
+DROP INDEX IF EXISTS idx_content_franchise;
+ALTER TABLE content DROP COLUMN IF EXISTS franchise;
@@ -0,0 +1,10 @@
+-- Content franchise
+-- Franchise or collection a title belongs to, so re-ranking can cap how many
+-- titles from one franchise a recommendation list shows
+
+ALTER TABLE content ADD COLUMN IF NOT EXISTS franchise VARCHAR(255);
+
+CREATE INDEX IF NOT EXISTS idx_content_franchise
+    ON content(franchise) WHERE franchise IS NOT NULL;
+
+COMMENT ON COLUMN content.franchise IS 'Franchise or collection the title belongs to, used for per-franchise re-ranking caps';
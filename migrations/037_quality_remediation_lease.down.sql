@@ -0,0 +1,4 @@
+-- Rollback quality remediation claim lease migration
+
+DROP INDEX IF EXISTS idx_quality_remediation_claimed;
+ALTER TABLE quality_remediation DROP COLUMN IF EXISTS claimed_at;
@@ -0,0 +1,10 @@
+-- Quality remediation claim lease
+-- Records when a worker claimed an item so claims abandoned by a crashed or
+-- timed-out run can be reclaimed once the lease expires
+
+ALTER TABLE quality_remediation ADD COLUMN IF NOT EXISTS claimed_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_quality_remediation_claimed
+    ON quality_remediation(claimed_at) WHERE status = 'in_progress';
+
+COMMENT ON COLUMN quality_remediation.claimed_at IS 'When the current worker claimed the item; in_progress items whose lease has expired are reclaimed';
@@ -0,0 +1,4 @@
+-- Rollback experiment holdouts migration
+
+DROP TABLE IF EXISTS experiment_holdout_exposures;
+DROP TABLE IF EXISTS experiment_holdouts;
@@ -0,0 +1,29 @@
+-- Experiment holdouts
+-- Holdout users are enrolled in an experiment but shown no variant, so they
+-- have no row in experiment_assignments or experiment_exposures
+
+CREATE TABLE IF NOT EXISTS experiment_holdouts (
+    id BIGSERIAL PRIMARY KEY,
+    experiment_id UUID NOT NULL REFERENCES experiments(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL,
+    assigned_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT unique_holdout_user_experiment UNIQUE(experiment_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_holdouts_user ON experiment_holdouts(user_id);
+
+CREATE TABLE IF NOT EXISTS experiment_holdout_exposures (
+    id BIGSERIAL PRIMARY KEY,
+    experiment_id UUID NOT NULL REFERENCES experiments(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL,
+    exposed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    context JSONB
+);
+
+CREATE INDEX IF NOT EXISTS idx_holdout_exposures_experiment ON experiment_holdout_exposures(experiment_id);
+CREATE INDEX IF NOT EXISTS idx_holdout_exposures_user ON experiment_holdout_exposures(user_id);
+CREATE INDEX IF NOT EXISTS idx_holdout_exposures_time ON experiment_holdout_exposures(exposed_at);
+
+COMMENT ON TABLE experiment_holdouts IS 'Users held out of an experiment as a long-term baseline';
+COMMENT ON TABLE experiment_holdout_exposures IS 'Tracks when held-out users are served the baseline experience';